@@ -0,0 +1,2137 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write;
+
+use crate::constant;
+use crate::constant::Constant;
+use crate::error;
+use crate::error::Error;
+use crate::nodes::address_of_node::AddressOfNode;
+use crate::nodes::array_node::ArrayNode;
+use crate::nodes::asm_node::AssemblyNode;
+use crate::nodes::assert_node::AssertNode;
+use crate::nodes::binop_node::BinOpNode;
+use crate::nodes::break_node::BreakNode;
+use crate::nodes::call_node::CallNode;
+use crate::nodes::cast_node::CastNode;
+use crate::nodes::char_node::CharNode;
+use crate::nodes::check_node::{Check, CheckNode, CHECK_FAILED_EXIT_CODE};
+use crate::nodes::const_def_node::ConstDefinitionNode;
+use crate::nodes::continue_node::ContinueNode;
+use crate::nodes::copy_node::CopyNode;
+use crate::nodes::extern_node::ExternNode;
+use crate::nodes::for_node::ForNode;
+use crate::nodes::functiondecl_node::FunctionDeclarationNode;
+use crate::nodes::functiondef_node::FunctionDefinitionNode;
+use crate::nodes::if_node::IfNode;
+use crate::nodes::import_node::ImportNode;
+use crate::nodes::memory_node::{MemoryNode, MemoryOperation};
+use crate::nodes::number_node::NumberNode;
+use crate::nodes::offset_node::OffsetNode;
+use crate::nodes::pointer_assign_node::PointerAssignNode;
+use crate::nodes::read_bytes_node::ReadBytesNode;
+use crate::nodes::return_node::ReturnNode;
+use crate::nodes::sizeof_node::SizeOfNode;
+use crate::nodes::slice_node::SliceNode;
+use crate::nodes::stack_allocation_node::StackAllocationNode;
+use crate::nodes::statements_node::StatementsNode;
+use crate::nodes::static_decl_node::StaticDeclarationNode;
+use crate::nodes::static_def_node::StaticDefinitionNode;
+use crate::nodes::string_node::StringNode;
+use crate::nodes::syscall_node::SyscallNode;
+use crate::nodes::unaryop_node::UnaryOpNode;
+use crate::nodes::var_node::declare::VarDeclarationNode;
+use crate::nodes::var_node::typed_access::VarTypedAccessNode;
+use crate::nodes::var_node::typed_assign::VarTypedAssignNode;
+use crate::nodes::while_node::WhileNode;
+use crate::nodes::{Node, NodeType};
+use crate::position::Position;
+use crate::syscall::{SyscallTable, TargetObjectType};
+use crate::token::TokenType;
+use crate::utils::unescape;
+use crate::values::value_size::ValueSize;
+use crate::values::value_type::slice_type::SLICE_LENGTH_OFFSET;
+use crate::values::value_type::ValueTypes;
+
+// the scratch registers are callee saved, so they survive calls
+const QW_SCRATCH_REGS: [&str; 7] = ["x19", "x20", "x21", "x22", "x23", "x24", "x25"];
+const DW_SCRATCH_REGS: [&str; 7] = ["w19", "w20", "w21", "w22", "w23", "w24", "w25"];
+
+const QW_NUMBER_ARG_REGS: [&str; 8] = ["x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7"];
+const DW_NUMBER_ARG_REGS: [&str; 8] = ["w0", "w1", "w2", "w3", "w4", "w5", "w6", "w7"];
+
+const SYSCALL_REGS: [&str; 4] = ["x8", "x0", "x1", "x2"];
+
+/// Zeroing at most this many bytes is unrolled into stores instead of a loop.
+const UNROLL_LIMIT: u64 = 128;
+
+/// Bytes reserved below the locals of a function to save the scratch registers
+const SAVED_REGS_SIZE: u64 = 64;
+
+const ENTRY_SYMBOL: &str = "_start";
+
+/// The `clone` flags that make it behave like `fork`: only send SIGCHLD to the parent on exit
+const CLONE_FORK_FLAGS: u64 = 17;
+
+/// A loop that `break` and `continue` can jump out of
+struct Loop {
+    label: Option<String>,
+    continue_label: u128,
+    break_label: u128,
+}
+
+/// Data with values known at compile time, like the values of statics and the arrays they point to.
+struct DataDefinition {
+    label: String,
+    read_only: bool,
+    /// Statics are exported, so they can be accessed from C
+    global: bool,
+    size: ValueSize,
+    /// Numbers or labels of `size` bytes each
+    values: Vec<String>,
+}
+
+/// Generates GNU assembler syntax for aarch64 Linux, following the AAPCS64 calling convention.
+/// Works like the x86-64 `Compiler`: every expression is computed in one of the scratch registers
+/// and locals live in the stack frame below `x29`.
+pub struct Aarch64Compiler {
+    scratch_regs: u8,
+    label_count: u128,
+    current_function_epilogue: Option<u128>,
+
+    /// The enclosing loops, the innermost one last
+    loops: Vec<Loop>,
+
+    strings: Vec<(Vec<u8>, String)>,
+    /// The values of constants, which are generated wherever a constant is read
+    constants: HashMap<String, Box<dyn Node>>,
+
+    base_offset: u64,
+    offset_table: HashMap<String, (u64, ValueSize)>,
+
+    externs: Vec<String>,
+
+    globals: Vec<String>,
+
+    statics: Vec<(String, ValueSize)>,
+    data: Vec<DataDefinition>,
+    data_label_count: usize,
+    /// Zero initialized data, that isn't a static itself
+    zeroed: Vec<(String, u64)>,
+
+    target_object_type: TargetObjectType,
+
+    /// Set when code generation fails for something this backend doesn't support
+    error: Option<Error>,
+}
+
+impl Aarch64Compiler {
+    pub fn new(target_object_type: TargetObjectType) -> Self {
+        Aarch64Compiler {
+            scratch_regs: 0,
+            label_count: 0,
+            loops: vec![],
+            current_function_epilogue: None,
+            strings: vec![],
+            constants: HashMap::new(),
+            base_offset: 0,
+            offset_table: HashMap::new(),
+            externs: vec![],
+            globals: vec![],
+            statics: vec![],
+            data: vec![],
+            data_label_count: 0,
+            zeroed: vec![],
+            target_object_type,
+            error: None,
+        }
+    }
+
+    // region Register distribution
+    fn res_scratch(&mut self) -> u8 {
+        if self.scratch_regs ^ 0b1111111 == 0 {
+            panic!("No free general purpose registers were found!");
+        }
+
+        let mut i = 0;
+        let mut current = self.scratch_regs;
+        while current & 1 == 1 {
+            i += 1;
+            current >>= 1;
+        }
+
+        self.scratch_regs |= 1 << i;
+
+        i
+    }
+
+    /// Returns the 32 bit view of the register for values smaller than a qword. Loads into it zero
+    /// the upper half, stores only write as many bytes as the instruction says.
+    fn scratch_name_lower_sized(&self, i: u8, size: &ValueSize) -> &str {
+        match size {
+            ValueSize::Qword => QW_SCRATCH_REGS[i as usize],
+            _ => DW_SCRATCH_REGS[i as usize],
+        }
+    }
+
+    fn scratch_name(&self, i: u8) -> &str {
+        self.scratch_name_lower_sized(i, &ValueSize::Qword)
+    }
+
+    fn number_arg_reg_name(&self, i: u8, size: &ValueSize) -> &str {
+        match size {
+            ValueSize::Qword => QW_NUMBER_ARG_REGS[i as usize],
+            _ => DW_NUMBER_ARG_REGS[i as usize],
+        }
+    }
+
+    fn free_scratch(&mut self, reg: u8) {
+        self.scratch_regs = !(!self.scratch_regs | (1 << reg));
+    }
+    // endregion
+
+    // region Label creation
+    fn label_create(&mut self) -> u128 {
+        self.label_count += 1;
+        self.label_count
+    }
+
+    /// Returns the loop `break` or `continue` with the label jumps out of. Without a label, it's
+    /// the innermost loop.
+    fn find_loop(&self, label: &Option<String>) -> &Loop {
+        match label {
+            Some(label) => self
+                .loops
+                .iter()
+                .rev()
+                .find(|l| l.label.as_ref() == Some(label))
+                .unwrap(),
+            None => self.loops.last().unwrap(),
+        }
+    }
+
+    fn label_name(&self, label: &u128) -> String {
+        format!(".L{}", label)
+    }
+
+    //endregion
+
+    // region Instructions
+
+    fn load_instruction(size: &ValueSize) -> &'static str {
+        match size {
+            ValueSize::Byte => "ldrb",
+            ValueSize::Word => "ldrh",
+            _ => "ldr",
+        }
+    }
+
+    fn store_instruction(size: &ValueSize) -> &'static str {
+        match size {
+            ValueSize::Byte => "strb",
+            ValueSize::Word => "strh",
+            _ => "str",
+        }
+    }
+
+    /// Moves the number into the register, 16 bits at a time.
+    fn load_immediate(&self, reg: &str, value: u64, w: &mut String) -> fmt::Result {
+        writeln!(w, "\tmovz    {}, #{}", reg, value & 0xffff)?;
+        for shift in [16, 32, 48] {
+            let part = (value >> shift) & 0xffff;
+            if part != 0 {
+                writeln!(w, "\tmovk    {}, #{}, lsl #{}", reg, part, shift)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the memory operand of the local at the given offset below the frame pointer. Offsets
+    /// out of the range of `ldur` and `stur` are computed in x16.
+    fn frame_slot(&self, offset: u64, w: &mut String) -> Result<String, fmt::Error> {
+        if offset <= 256 {
+            return Ok(format!("[x29, #-{}]", offset));
+        }
+
+        self.frame_address("x16", offset, w)?;
+        Ok(String::from("[x16]"))
+    }
+
+    fn frame_address(&self, reg: &str, offset: u64, w: &mut String) -> fmt::Result {
+        if offset < 4096 {
+            return writeln!(w, "\tsub     {}, x29, #{}", reg, offset);
+        }
+
+        self.load_immediate("x17", offset, w)?;
+        writeln!(w, "\tsub     {}, x29, x17", reg)
+    }
+
+    fn symbol_address(&self, reg: &str, symbol: &str, w: &mut String) -> fmt::Result {
+        writeln!(w, "\tadrp    {}, {}", reg, symbol)?;
+        writeln!(w, "\tadd     {}, {}, :lo12:{}", reg, reg, symbol)
+    }
+
+    fn push(&self, reg: &str, w: &mut String) -> fmt::Result {
+        // the stack pointer has to stay 16 byte aligned
+        writeln!(w, "\tstr     {}, [sp, #-16]!", reg)
+    }
+
+    fn pop(&self, reg: &str, w: &mut String) -> fmt::Result {
+        writeln!(w, "\tldr     {}, [sp], #16", reg)
+    }
+
+    /// Zeroes the given number of bytes below the frame pointer, starting at the offset.
+    fn zero_frame(&mut self, offset: u64, size: u64, w: &mut String) -> fmt::Result {
+        if size > UNROLL_LIMIT {
+            self.frame_address("x9", offset, w)?;
+            self.load_immediate("x10", size, w)?;
+            writeln!(w, "\tmov     x11, xzr")?;
+            return self.fill_memory(w);
+        }
+
+        self.frame_address("x16", offset, w)?;
+
+        let mut i = 0;
+        while i + 8 <= size {
+            writeln!(w, "\tstr     xzr, [x16, #{}]", i)?;
+            i += 8;
+        }
+        while i < size {
+            writeln!(w, "\tstrb    wzr, [x16, #{}]", i)?;
+            i += 1;
+        }
+        Ok(())
+    }
+
+    /// Stores the pattern in x11 to the x10 bytes at the address in x9, eight bytes at a time
+    /// while possible. All three registers are clobbered.
+    fn fill_memory(&mut self, w: &mut String) -> fmt::Result {
+        let label_qwords = self.label_create();
+        let label_bytes = self.label_create();
+        let label_end = self.label_create();
+
+        writeln!(w, "{}:", self.label_name(&label_qwords))?;
+        writeln!(w, "\tcmp     x10, #8")?;
+        writeln!(w, "\tb.lo    {}", self.label_name(&label_bytes))?;
+        writeln!(w, "\tstr     x11, [x9], #8")?;
+        writeln!(w, "\tsub     x10, x10, #8")?;
+        writeln!(w, "\tb       {}", self.label_name(&label_qwords))?;
+        writeln!(w, "{}:", self.label_name(&label_bytes))?;
+        writeln!(w, "\tcbz     x10, {}", self.label_name(&label_end))?;
+        writeln!(w, "\tstrb    w11, [x9], #1")?;
+        writeln!(w, "\tsub     x10, x10, #1")?;
+        writeln!(w, "\tb       {}", self.label_name(&label_bytes))?;
+        writeln!(w, "{}:", self.label_name(&label_end))
+    }
+
+    /// Copies x11 bytes from the address in x10 to the one in x9, eight bytes at a time while
+    /// possible. If the blocks can overlap, the copy runs backwards when the destination starts
+    /// within the source. x9 to x12 are clobbered.
+    fn copy_memory(&mut self, overlapping: bool, w: &mut String) -> fmt::Result {
+        let label_backwards = self.label_create();
+        let label_end = self.label_create();
+
+        if overlapping {
+            writeln!(w, "\tsub     x12, x9, x10")?;
+            writeln!(w, "\tcmp     x12, x11")?;
+            writeln!(w, "\tb.lo    {}", self.label_name(&label_backwards))?;
+        }
+
+        let label_qwords = self.label_create();
+        let label_bytes = self.label_create();
+        writeln!(w, "{}:", self.label_name(&label_qwords))?;
+        writeln!(w, "\tcmp     x11, #8")?;
+        writeln!(w, "\tb.lo    {}", self.label_name(&label_bytes))?;
+        writeln!(w, "\tldr     x12, [x10], #8")?;
+        writeln!(w, "\tstr     x12, [x9], #8")?;
+        writeln!(w, "\tsub     x11, x11, #8")?;
+        writeln!(w, "\tb       {}", self.label_name(&label_qwords))?;
+        writeln!(w, "{}:", self.label_name(&label_bytes))?;
+        writeln!(w, "\tcbz     x11, {}", self.label_name(&label_end))?;
+        writeln!(w, "\tldrb    w12, [x10], #1")?;
+        writeln!(w, "\tstrb    w12, [x9], #1")?;
+        writeln!(w, "\tsub     x11, x11, #1")?;
+        writeln!(w, "\tb       {}", self.label_name(&label_bytes))?;
+
+        if overlapping {
+            let label_qwords = self.label_create();
+            let label_bytes = self.label_create();
+            writeln!(w, "{}:", self.label_name(&label_backwards))?;
+            writeln!(w, "\tadd     x9, x9, x11")?;
+            writeln!(w, "\tadd     x10, x10, x11")?;
+            writeln!(w, "{}:", self.label_name(&label_qwords))?;
+            writeln!(w, "\tcmp     x11, #8")?;
+            writeln!(w, "\tb.lo    {}", self.label_name(&label_bytes))?;
+            writeln!(w, "\tldr     x12, [x10, #-8]!")?;
+            writeln!(w, "\tstr     x12, [x9, #-8]!")?;
+            writeln!(w, "\tsub     x11, x11, #8")?;
+            writeln!(w, "\tb       {}", self.label_name(&label_qwords))?;
+            writeln!(w, "{}:", self.label_name(&label_bytes))?;
+            writeln!(w, "\tcbz     x11, {}", self.label_name(&label_end))?;
+            writeln!(w, "\tldrb    w12, [x10, #-1]!")?;
+            writeln!(w, "\tstrb    w12, [x9, #-1]!")?;
+            writeln!(w, "\tsub     x11, x11, #1")?;
+            writeln!(w, "\tb       {}", self.label_name(&label_bytes))?;
+        }
+
+        writeln!(w, "{}:", self.label_name(&label_end))
+    }
+
+    //endregion
+
+    fn create_string_label(&mut self, bytes: Vec<u8>) -> String {
+        if let Some((_, label)) = self.strings.iter().find(|(s, _)| *s == bytes) {
+            return label.clone();
+        }
+
+        let uuid = format!("S{}", self.strings.len());
+
+        self.strings.push((bytes, uuid.clone()));
+        uuid
+    }
+
+    fn create_data_label(&mut self) -> String {
+        self.data_label_count += 1;
+        format!("D{}", self.data_label_count - 1)
+    }
+
+    /// Returns the number or label, that a constant of the given size is stored as. The data
+    /// addresses point to is created as well.
+    fn constant_value(&mut self, constant: &Constant, size: ValueSize) -> String {
+        match constant {
+            Constant::Integer(_) | Constant::Float(_) => {
+                constant::truncate(constant.bits().unwrap(), size).to_string()
+            }
+            Constant::String(bytes) => self.create_string_label(bytes.clone()),
+            Constant::Array {
+                element_size,
+                elements,
+            } => {
+                let mut values: Vec<String> = vec![];
+                for element in elements {
+                    values.push(self.constant_value(element, *element_size));
+                }
+
+                let label = self.create_data_label();
+                self.data.push(DataDefinition {
+                    label: label.clone(),
+                    read_only: false,
+                    global: false,
+                    size: *element_size,
+                    values,
+                });
+                label
+            }
+            Constant::Zeroed(bytes) => {
+                let label = self.create_data_label();
+                self.zeroed.push((label.clone(), *bytes));
+                label
+            }
+        }
+    }
+
+    /// Writes the given string to the given file descriptor using the `write` syscall.
+    /// Clobbers x0, x1, x2 and x8.
+    fn write_raw_string(&mut self, fd: u8, string: &str, w: &mut String) -> fmt::Result {
+        let str_label = self.create_string_label(string.as_bytes().to_vec());
+
+        writeln!(
+            w,
+            "\tmov     x8, #{}",
+            SyscallTable::Write.code(self.target_object_type)
+        )?;
+        writeln!(w, "\tmov     x0, #{}", fd)?;
+        self.symbol_address("x1", &str_label, w)?;
+        self.load_immediate("x2", string.len() as u64, w)?;
+        writeln!(w, "\tsvc     #0")
+    }
+
+    /// Writes the message to stderr and exits with the given code.
+    fn trap(&mut self, message: &str, exit_code: u64, w: &mut String) -> fmt::Result {
+        self.write_raw_string(2, message, w)?;
+
+        writeln!(w, "\tmov     x0, #{}", exit_code)?;
+        writeln!(
+            w,
+            "\tmov     x8, #{}",
+            SyscallTable::Exit.code(self.target_object_type)
+        )?;
+        writeln!(w, "\tsvc     #0")
+    }
+
+    /// Sign or zero extends the value of the given size in `src` to 64 bits in `dst`, both given as
+    /// the names of 64-bit registers.
+    fn extend(dst: &str, src: &str, size: ValueSize, signed: bool, w: &mut String) -> fmt::Result {
+        let (dst_w, src_w) = (dst.replacen('x', "w", 1), src.replacen('x', "w", 1));
+        match (size, signed) {
+            (ValueSize::Qword, _) => writeln!(w, "\tmov     {}, {}", dst, src),
+            (ValueSize::Byte, true) => writeln!(w, "\tsxtb    {}, {}", dst, src_w),
+            (ValueSize::Word, true) => writeln!(w, "\tsxth    {}, {}", dst, src_w),
+            (ValueSize::Dword, true) => writeln!(w, "\tsxtw    {}, {}", dst, src_w),
+            (ValueSize::Byte, false) => writeln!(w, "\tuxtb    {}, {}", dst_w, src_w),
+            (ValueSize::Word, false) => writeln!(w, "\tuxth    {}, {}", dst_w, src_w),
+            // writing the lower 32 bits clears the upper ones
+            (ValueSize::Dword, false) => writeln!(w, "\tmov     {}, {}", dst_w, src_w),
+        }
+    }
+
+    /// Returns the register holding the argument at the index. Arguments on the stack are loaded
+    /// into x9 first.
+    fn arg_operand(
+        &self,
+        index: usize,
+        size: ValueSize,
+        w: &mut String,
+    ) -> Result<String, fmt::Error> {
+        if index < QW_NUMBER_ARG_REGS.len() {
+            return Ok(self.number_arg_reg_name(index as u8, &size).to_string());
+        }
+
+        // arguments on the stack are above the saved frame pointer and link register
+        let stack_offset = (index - QW_NUMBER_ARG_REGS.len()) * 8 + 16;
+        writeln!(w, "\tldr     x9, [x29, #{}]", stack_offset)?;
+        Ok(String::from(match size {
+            ValueSize::Qword => "x9",
+            _ => "w9",
+        }))
+    }
+
+    fn register_var(&mut self, name: String, size: ValueSize) {
+        self.base_offset += size.get_size_in_bytes() as u64;
+        self.offset_table.insert(name, (self.base_offset, size));
+    }
+
+    fn get_var(&self, name: &str) -> (u64, ValueSize) {
+        self.offset_table[name]
+    }
+
+    fn reset_stack_offset(&mut self) {
+        self.base_offset = 0;
+        self.offset_table.clear();
+    }
+
+    fn add_extern(&mut self, s: String) {
+        self.externs.push(s);
+    }
+
+    fn add_static(&mut self, s: String, size: ValueSize) {
+        self.statics.push((s, size));
+    }
+
+    fn add_global(&mut self, g: String) {
+        self.globals.push(g);
+    }
+
+    fn get_static(&self, s: &str) -> Option<ValueSize> {
+        self.statics
+            .iter()
+            .find(|(name, _)| name == s)
+            .map(|(_, size)| *size)
+    }
+
+    fn get_static_name(&self, s: &str) -> String {
+        format!("ST_{}", s)
+    }
+
+    fn is_constant(&self, name: &str) -> bool {
+        self.constants.contains_key(name)
+    }
+
+    /// Remembers the error for `node` and returns a formatting error to stop the code generation.
+    fn unsupported(&mut self, node: &dyn Node, details: &str) -> fmt::Error {
+        self.error = Some(error::not_yet_implemented_error(
+            node.pos_start().clone(),
+            node.pos_end().clone(),
+            details,
+        ));
+        fmt::Error
+    }
+
+    fn codegen_error(&mut self, fmt_error: fmt::Error) -> Error {
+        self.error.take().unwrap_or_else(|| {
+            error::io_error(
+                Position::empty(),
+                Position::empty(),
+                format!("Could not format assembly: {}", fmt_error).as_str(),
+            )
+        })
+    }
+
+    pub fn scratch_regs(&self) -> &u8 {
+        &self.scratch_regs
+    }
+}
+
+impl Aarch64Compiler {
+    /// Generates a branch of an `if`, moving its value into `result_reg` if the `if` has one.
+    fn branch(
+        &mut self,
+        statements: &Box<dyn Node>,
+        result_reg: Option<u8>,
+        w: &mut String,
+    ) -> fmt::Result {
+        let reg = self.code_gen(statements, w)?;
+
+        if let (Some(result_reg), Some(reg)) = (result_reg, reg) {
+            writeln!(
+                w,
+                "\tmov     {}, {}",
+                self.scratch_name(result_reg),
+                self.scratch_name(reg)
+            )?;
+        }
+        if let Some(reg) = reg {
+            self.free_scratch(reg);
+        }
+        Ok(())
+    }
+
+    fn code_gen(&mut self, node: &Box<dyn Node>, w: &mut String) -> Result<Option<u8>, fmt::Error> {
+        if node.node_type() == NodeType::Statements {
+            let statements_node = node.as_any().downcast_ref::<StatementsNode>().unwrap();
+
+            let statement_nodes = statements_node.statement_nodes();
+            for (i, n) in statement_nodes.iter().enumerate() {
+                let reg = self.code_gen(n, w)?;
+
+                // the register of the last statement holds the value of the block
+                if statements_node.has_value() && i == statement_nodes.len() - 1 {
+                    return Ok(reg);
+                }
+                if let Some(reg) = reg {
+                    self.free_scratch(reg);
+                }
+            }
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Assembly {
+            let assembly_node = node.as_any().downcast_ref::<AssemblyNode>().unwrap();
+
+            let reg = self.res_scratch();
+            writeln!(w, "\t{}", assembly_node.content())?;
+            writeln!(w, "\tmov     {}, x0", self.scratch_name(reg))?;
+
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::Syscall {
+            let syscall_node = node.as_any().downcast_ref::<SyscallNode>().unwrap();
+
+            // all arguments are computed first, so later ones can't overwrite the registers
+            let mut arg_regs: Vec<u8> = vec![];
+            for arg in syscall_node.args() {
+                arg_regs.push(self.code_gen(arg, w)?.unwrap());
+            }
+
+            writeln!(w, "\n\t// Syscall injected")?;
+            for (i, reg) in arg_regs.into_iter().enumerate() {
+                writeln!(
+                    w,
+                    "\tmov     {}, {}",
+                    SYSCALL_REGS[i],
+                    self.scratch_name(reg)
+                )?;
+                self.free_scratch(reg);
+            }
+            writeln!(w, "\tsvc     #0\n\t// End injected syscall\n")?;
+
+            let result_reg = self.res_scratch();
+            writeln!(w, "\tmov     {}, x0", self.scratch_name(result_reg))?;
+
+            return Ok(Some(result_reg));
+        }
+
+        if node.node_type() == NodeType::Memory {
+            let memory_node = node.as_any().downcast_ref::<MemoryNode>().unwrap();
+            let destination_reg = self.code_gen(memory_node.destination(), w)?.unwrap();
+            let source_reg = self.code_gen(memory_node.source(), w)?.unwrap();
+            let count_reg = self.code_gen(memory_node.count(), w)?.unwrap();
+
+            writeln!(w, "\tmov     x9, {}", self.scratch_name(destination_reg))?;
+            match memory_node.operation() {
+                MemoryOperation::Set => {
+                    // repeats the byte in every byte of x11
+                    writeln!(w, "\tand     x11, {}, #0xff", self.scratch_name(source_reg))?;
+                    self.load_immediate("x12", 0x0101010101010101, w)?;
+                    writeln!(w, "\tmul     x11, x11, x12")?;
+                    writeln!(w, "\tmov     x10, {}", self.scratch_name(count_reg))?;
+                    self.fill_memory(w)?;
+                }
+                operation => {
+                    writeln!(w, "\tmov     x10, {}", self.scratch_name(source_reg))?;
+                    writeln!(w, "\tmov     x11, {}", self.scratch_name(count_reg))?;
+                    self.copy_memory(operation == MemoryOperation::Move, w)?;
+                }
+            }
+
+            self.free_scratch(count_reg);
+            self.free_scratch(source_reg);
+            self.free_scratch(destination_reg);
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Assert {
+            let assert_node = node.as_any().downcast_ref::<AssertNode>().unwrap();
+            let label_ok = self.label_create();
+
+            let condition_reg = self.code_gen(assert_node.condition_node(), w)?.unwrap();
+            writeln!(
+                w,
+                "\tcbnz    {}, {}",
+                self.scratch_name(condition_reg),
+                self.label_name(&label_ok)
+            )?;
+            self.free_scratch(condition_reg);
+
+            let pos = assert_node.pos_start();
+            self.trap(
+                &format!(
+                    "{}:{}:{}: Assertion failed!\n",
+                    pos.file_name().display(),
+                    pos.line() + 1,
+                    pos.col() + 1
+                ),
+                1,
+                w,
+            )?;
+
+            writeln!(w, "{}:", self.label_name(&label_ok))?;
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Check {
+            let check_node = node.as_any().downcast_ref::<CheckNode>().unwrap();
+            let label_ok = self.label_create();
+
+            let reg = match *check_node.check() {
+                Check::Bounds(length) => {
+                    let reg = self.code_gen(check_node.node(), w)?.unwrap();
+                    self.load_immediate("x9", length, w)?;
+                    // the comparison is unsigned, so negative indices are out of bounds as well
+                    writeln!(w, "\tcmp     {}, x9", self.scratch_name(reg))?;
+                    writeln!(w, "\tb.lo    {}", self.label_name(&label_ok))?;
+                    reg
+                }
+                Check::SliceBounds | Check::SliceRange => {
+                    let reg = self.code_gen(check_node.node(), w)?.unwrap();
+                    let length_reg = self.code_gen(check_node.length(), w)?.unwrap();
+                    writeln!(
+                        w,
+                        "\tcmp     {}, {}",
+                        self.scratch_name(reg),
+                        self.scratch_name(length_reg)
+                    )?;
+                    self.free_scratch(length_reg);
+                    let condition = match *check_node.check() {
+                        Check::SliceBounds => "b.lo",
+                        _ => "b.ls",
+                    };
+                    writeln!(w, "\t{:<8}{}", condition, self.label_name(&label_ok))?;
+                    reg
+                }
+                Check::Null => {
+                    let reg = self.code_gen(check_node.node(), w)?.unwrap();
+                    writeln!(
+                        w,
+                        "\tcbnz    {}, {}",
+                        self.scratch_name(reg),
+                        self.label_name(&label_ok)
+                    )?;
+                    reg
+                }
+                Check::Overflow { size, signed } => {
+                    let bin_op_node = check_node
+                        .node()
+                        .as_any()
+                        .downcast_ref::<BinOpNode>()
+                        .unwrap();
+                    let op = bin_op_node.op_token().token_type();
+                    let left_reg = self.code_gen(bin_op_node.left_node(), w)?.unwrap();
+                    let right_reg = self.code_gen(bin_op_node.right_node(), w)?.unwrap();
+                    let left = self.scratch_name(left_reg).to_string();
+                    let right = self.scratch_name(right_reg).to_string();
+
+                    if size == ValueSize::Qword {
+                        match (op, signed) {
+                            (TokenType::Mul, false) => {
+                                writeln!(w, "\tumulh   x9, {}, {}", left, right)?;
+                                writeln!(w, "\tmul     {}, {}, {}", left, left, right)?;
+                                writeln!(w, "\tcbz     x9, {}", self.label_name(&label_ok))?;
+                            }
+                            // the upper half of the product has to be the sign of the lower one
+                            (TokenType::Mul, true) => {
+                                writeln!(w, "\tsmulh   x9, {}, {}", left, right)?;
+                                writeln!(w, "\tmul     {}, {}, {}", left, left, right)?;
+                                writeln!(w, "\tcmp     x9, {}, asr #63", left)?;
+                                writeln!(w, "\tb.eq    {}", self.label_name(&label_ok))?;
+                            }
+                            _ => {
+                                let (instruction, condition) = match (op, signed) {
+                                    (TokenType::Plus, false) => ("adds", "b.cc"),
+                                    // the carry flag is set, if there was no borrow
+                                    (TokenType::Minus, false) => ("subs", "b.cs"),
+                                    (TokenType::Plus, true) => ("adds", "b.vc"),
+                                    _ => ("subs", "b.vc"),
+                                };
+                                writeln!(w, "\t{:<8}{}, {}, {}", instruction, left, left, right)?;
+                                writeln!(w, "\t{:<8}{}", condition, self.label_name(&label_ok))?;
+                            }
+                        }
+                    } else {
+                        // smaller integers are calculated with 64 bits, where they can't overflow,
+                        // and the result has to be representable in their size
+                        Self::extend(&left, &left, size, signed, w)?;
+                        Self::extend(&right, &right, size, signed, w)?;
+                        let instruction = match op {
+                            TokenType::Plus => "add",
+                            TokenType::Minus => "sub",
+                            _ => "mul",
+                        };
+                        writeln!(w, "\t{:<8}{}, {}, {}", instruction, left, left, right)?;
+
+                        Self::extend("x9", &left, size, signed, w)?;
+                        writeln!(w, "\tcmp     x9, {}", left)?;
+                        writeln!(w, "\tb.eq    {}", self.label_name(&label_ok))?;
+                    }
+                    self.free_scratch(right_reg);
+                    left_reg
+                }
+            };
+
+            self.trap(&check_node.failure_message(), CHECK_FAILED_EXIT_CODE, w)?;
+            writeln!(w, "{}:", self.label_name(&label_ok))?;
+
+            // negative results of smaller integers are zero extended, like all of their values
+            if let Check::Overflow { size, signed: true } = *check_node.check() {
+                if size != ValueSize::Qword {
+                    let name = self.scratch_name(reg).to_string();
+                    Self::extend(&name, &name, size, false, w)?;
+                }
+            }
+
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::Number {
+            let number_node = node.as_any().downcast_ref::<NumberNode>().unwrap();
+
+            let number = match number_node.size().get_size() {
+                ValueSize::Byte => number_node.get_number() & 0xff,
+                ValueSize::Word => number_node.get_number() & 0xffff,
+                ValueSize::Dword => number_node.get_number() & 0xffff_ffff,
+                ValueSize::Qword => number_node.get_number(),
+            };
+
+            let reg = self.res_scratch();
+            self.load_immediate(self.scratch_name(reg), number, w)?;
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::String {
+            let string = node
+                .as_any()
+                .downcast_ref::<StringNode>()
+                .unwrap()
+                .get_string();
+            let str_label = self.create_string_label(unescape(&string));
+
+            let reg = self.res_scratch();
+            self.symbol_address(self.scratch_name(reg), &str_label, w)?;
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::Char {
+            let reg = self.res_scratch();
+            writeln!(
+                w,
+                "\tmov     {}, #{}",
+                self.scratch_name(reg),
+                *node.as_any().downcast_ref::<CharNode>().unwrap().value() as u8
+            )?;
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::Array {
+            let array_node = node.as_any().downcast_ref::<ArrayNode>().unwrap();
+            let element_size = array_node.element_type().get_size();
+
+            self.base_offset += *array_node.size() as u64 * element_size.get_size_in_bytes() as u64;
+            let array_offset = self.base_offset;
+
+            if array_node.element_nodes().is_empty() {
+                self.zero_frame(
+                    array_offset,
+                    *array_node.size() as u64 * element_size.get_size_in_bytes() as u64,
+                    w,
+                )?;
+            }
+
+            for (i, elem) in array_node.element_nodes().iter().enumerate().rev() {
+                let reg = self.code_gen(elem, w)?.unwrap();
+                let slot = self.frame_slot(
+                    array_offset - i as u64 * element_size.get_size_in_bytes() as u64,
+                    w,
+                )?;
+                writeln!(
+                    w,
+                    "\t{:<8}{}, {}",
+                    Self::store_instruction(&element_size),
+                    self.scratch_name_lower_sized(reg, &element_size),
+                    slot
+                )?;
+                self.free_scratch(reg);
+            }
+
+            let reg = self.res_scratch();
+            self.frame_address(self.scratch_name(reg), array_offset, w)?;
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::BinOp {
+            let bin_op_node = node.as_any().downcast_ref::<BinOpNode>().unwrap();
+
+            // Special case for And and Or operators, which need to handle short-circuiting
+            if bin_op_node.op_token().token_type() == TokenType::And
+                || bin_op_node.op_token().token_type() == TokenType::Or
+            {
+                let label_end = self.label_create();
+
+                let left_reg = self.code_gen(bin_op_node.left_node(), w)?.unwrap();
+                writeln!(w, "\tcmp     {}, #0", self.scratch_name(left_reg))?;
+                writeln!(w, "\tcset    {}, ne", self.scratch_name(left_reg))?;
+                writeln!(
+                    w,
+                    "\t{:<8}{}, {}",
+                    if bin_op_node.op_token().token_type() == TokenType::And {
+                        "cbz"
+                    } else {
+                        "cbnz"
+                    },
+                    self.scratch_name(left_reg),
+                    self.label_name(&label_end)
+                )?;
+
+                let right_reg = self.code_gen(bin_op_node.right_node(), w)?.unwrap();
+                writeln!(w, "\tcmp     {}, #0", self.scratch_name(right_reg))?;
+                writeln!(w, "\tcset    {}, ne", self.scratch_name(left_reg))?;
+                self.free_scratch(right_reg);
+
+                writeln!(w, "{}:", self.label_name(&label_end))?;
+
+                return Ok(Some(left_reg));
+            }
+
+            let left_reg = self.code_gen(bin_op_node.left_node(), w)?.unwrap();
+            let right_reg = self.code_gen(bin_op_node.right_node(), w)?.unwrap();
+
+            let res_reg = left_reg;
+            let left = self.scratch_name(left_reg).to_string();
+            let right = self.scratch_name(right_reg).to_string();
+
+            let instruction = match bin_op_node.op_token().token_type() {
+                TokenType::Plus => Some("add"),
+                TokenType::Minus => Some("sub"),
+                TokenType::Mul => Some("mul"),
+                TokenType::Div => Some("sdiv"),
+                TokenType::BitAnd => Some("and"),
+                TokenType::BitOr => Some("orr"),
+                TokenType::BitXor => Some("eor"),
+                // like on x86-64, only the lower 6 bits of the shift count are used
+                TokenType::BitShl => Some("lsl"),
+                TokenType::BitShr => Some("asr"),
+                _ => None,
+            };
+
+            let condition = match bin_op_node.op_token().token_type() {
+                TokenType::Ee => Some("eq"),
+                TokenType::Ne => Some("ne"),
+                TokenType::Lt => Some("lt"),
+                TokenType::Gt => Some("gt"),
+                TokenType::Lte => Some("le"),
+                TokenType::Gte => Some("ge"),
+                _ => None,
+            };
+
+            if let Some(instruction) = instruction {
+                writeln!(w, "\t{:<8}{}, {}, {}", instruction, left, left, right)?;
+            } else if let Some(condition) = condition {
+                writeln!(w, "\tcmp     {}, {}", left, right)?;
+                writeln!(w, "\tcset    {}, {}", left, condition)?;
+            } else if bin_op_node.op_token().token_type() == TokenType::Modulo {
+                writeln!(w, "\tsdiv    x9, {}, {}", left, right)?;
+                writeln!(w, "\tmsub    {}, x9, {}, {}", left, right, left)?;
+            } else {
+                return Err(self.unsupported(
+                    node.as_ref(),
+                    &format!(
+                        "Token '{:?}' not supported as a binary operation yet!",
+                        bin_op_node.op_token().token_type()
+                    ),
+                ));
+            }
+
+            self.free_scratch(right_reg);
+
+            return Ok(Some(res_reg));
+        }
+
+        if node.node_type() == NodeType::UnaryOp {
+            let unary_op_node = node.as_any().downcast_ref::<UnaryOpNode>().unwrap();
+
+            let reg = self.code_gen(unary_op_node.node(), w)?.unwrap();
+            let name = self.scratch_name(reg).to_string();
+
+            if unary_op_node.op_token().token_type() == TokenType::Minus {
+                writeln!(w, "\tneg     {}, {}", name, name)?;
+            } else if unary_op_node.op_token().token_type() == TokenType::Not {
+                writeln!(w, "\tcmp     {}, #0", name)?;
+                writeln!(w, "\tcset    {}, eq", name)?;
+            } else if unary_op_node.op_token().token_type() == TokenType::BitNot {
+                writeln!(w, "\tmvn     {}, {}", name, name)?;
+            } else {
+                return Err(self.unsupported(
+                    node.as_ref(),
+                    &format!(
+                        "Token '{:?}' not supported as an unary operation yet!",
+                        unary_op_node.op_token().token_type()
+                    ),
+                ));
+            }
+
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::Call {
+            let call_node = node.as_any().downcast_ref::<CallNode>().unwrap();
+            let arg_count = call_node.passed_arg_types().len();
+
+            for (i, arg) in call_node.arg_nodes().iter().enumerate().rev() {
+                let reg = self.code_gen(arg, w)?.unwrap();
+
+                if call_node.is_slice_arg(i) {
+                    writeln!(
+                        w,
+                        "\tldr     x9, [{}, #{}]",
+                        self.scratch_name(reg),
+                        SLICE_LENGTH_OFFSET
+                    )?;
+                    self.push("x9", w)?;
+                    writeln!(
+                        w,
+                        "\tldr     {}, [{}]",
+                        self.scratch_name(reg),
+                        self.scratch_name(reg)
+                    )?;
+                }
+
+                // the caller promotes the arguments passed to the `...` of variadic functions to
+                // `int`, loads zero extend small signed integers though
+                let extension = match call_node.arg_types().get(i).map(|t| t.value_type()) {
+                    Some(ValueTypes::I8) => Some("sxtb"),
+                    Some(ValueTypes::I16) => Some("sxth"),
+                    _ => None,
+                };
+                if let Some(extension) = extension {
+                    writeln!(
+                        w,
+                        "\t{:<8}{}, {}",
+                        extension,
+                        self.scratch_name(reg),
+                        self.scratch_name_lower_sized(reg, &ValueSize::Dword)
+                    )?;
+                }
+
+                self.push(self.scratch_name(reg), w)?;
+                self.free_scratch(reg);
+            }
+
+            for reg in QW_NUMBER_ARG_REGS.iter().take(arg_count) {
+                self.pop(reg, w)?;
+            }
+
+            // the remaining arguments were pushed in 16 byte slots, but are passed in 8 byte ones
+            let stack_args = arg_count.saturating_sub(QW_NUMBER_ARG_REGS.len());
+            for i in 1..stack_args {
+                writeln!(w, "\tldr     x9, [sp, #{}]", i * 16)?;
+                writeln!(w, "\tstr     x9, [sp, #{}]", i * 8)?;
+            }
+
+            writeln!(w, "\tbl      {}", call_node.func_to_call())?;
+
+            if stack_args > 0 {
+                writeln!(w, "\tadd     sp, sp, #{}", stack_args * 16)?;
+            }
+
+            let reg = self.res_scratch();
+            writeln!(w, "\tmov     {}, x0", self.scratch_name(reg))?;
+
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::FunctionDef {
+            let func_def_node = node
+                .as_any()
+                .downcast_ref::<FunctionDefinitionNode>()
+                .unwrap();
+            let func_epilogue_label = self.label_create();
+
+            self.reset_stack_offset();
+
+            let func_label = func_def_node.var_name().to_string();
+
+            self.add_global(func_label.clone());
+            writeln!(w, "{}:", func_label)?;
+
+            writeln!(w, "\tstp     x29, x30, [sp, #-16]!")?;
+            writeln!(w, "\tmov     x29, sp")?;
+
+            let mut function_body = String::new();
+
+            // the index of the passed value, slices take up two of them
+            let mut arg_index = 0;
+            for (key, arg_type) in func_def_node.args() {
+                self.register_var(key.clone(), arg_type.get_size());
+
+                if arg_type.value_type() == ValueTypes::Slice {
+                    let variable_offset = self.base_offset;
+                    self.base_offset += arg_type.size_in_bytes();
+
+                    let pointer =
+                        self.arg_operand(arg_index, ValueSize::Qword, &mut function_body)?;
+                    let slot = self.frame_slot(self.base_offset, &mut function_body)?;
+                    writeln!(&mut function_body, "\tstr     {}, {}", pointer, slot)?;
+
+                    let length =
+                        self.arg_operand(arg_index + 1, ValueSize::Qword, &mut function_body)?;
+                    let slot = self
+                        .frame_slot(self.base_offset - SLICE_LENGTH_OFFSET, &mut function_body)?;
+                    writeln!(&mut function_body, "\tstr     {}, {}", length, slot)?;
+
+                    self.frame_address("x9", self.base_offset, &mut function_body)?;
+                    let slot = self.frame_slot(variable_offset, &mut function_body)?;
+                    writeln!(&mut function_body, "\tstr     x9, {}", slot)?;
+
+                    arg_index += 2;
+                    continue;
+                }
+
+                let arg_reg =
+                    self.arg_operand(arg_index, arg_type.get_size(), &mut function_body)?;
+                arg_index += 1;
+
+                let slot = self.frame_slot(self.base_offset, &mut function_body)?;
+                writeln!(
+                    &mut function_body,
+                    "\t{:<8}{}, {}",
+                    Self::store_instruction(&arg_type.get_size()),
+                    arg_reg,
+                    slot
+                )?;
+            }
+
+            self.current_function_epilogue = Some(func_epilogue_label);
+
+            self.code_gen(func_def_node.body_node(), &mut function_body)?;
+
+            // the locals are followed by the saved scratch registers, keeping sp 16 byte aligned
+            let frame_size = self.base_offset.div_ceil(16) * 16 + SAVED_REGS_SIZE;
+            if frame_size < 4096 {
+                writeln!(w, "\tsub     sp, sp, #{}", frame_size)?;
+            } else {
+                self.load_immediate("x16", frame_size, w)?;
+                writeln!(w, "\tsub     sp, sp, x16")?;
+            }
+
+            writeln!(w, "\tstp     x19, x20, [sp]")?;
+            writeln!(w, "\tstp     x21, x22, [sp, #16]")?;
+            writeln!(w, "\tstp     x23, x24, [sp, #32]")?;
+            writeln!(w, "\tstr     x25, [sp, #48]")?;
+
+            writeln!(w, "{}", function_body)?;
+
+            writeln!(w, "{}:", self.label_name(&func_epilogue_label))?;
+
+            writeln!(w, "\tldp     x19, x20, [sp]")?;
+            writeln!(w, "\tldp     x21, x22, [sp, #16]")?;
+            writeln!(w, "\tldp     x23, x24, [sp, #32]")?;
+            writeln!(w, "\tldr     x25, [sp, #48]")?;
+
+            writeln!(w, "\tmov     sp, x29")?;
+            writeln!(w, "\tldp     x29, x30, [sp], #16")?;
+            writeln!(w, "\tret\n")?;
+
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Return {
+            let return_node = node.as_any().downcast_ref::<ReturnNode>().unwrap();
+
+            if let Some(node_to_return) = return_node.node_to_return() {
+                let reg = self.code_gen(node_to_return, w)?.unwrap();
+                writeln!(w, "\tmov     x0, {}", self.scratch_name(reg))?;
+                self.free_scratch(reg);
+            } else {
+                writeln!(w, "\tmov     x0, xzr")?;
+            }
+
+            writeln!(
+                w,
+                "\tb       {}",
+                self.label_name(self.current_function_epilogue.as_ref().unwrap())
+            )?;
+
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Break {
+            let break_node = node.as_any().downcast_ref::<BreakNode>().unwrap();
+            let target = self.find_loop(break_node.label()).break_label;
+            writeln!(w, "\tb       {}", self.label_name(&target))?;
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Continue {
+            let continue_node = node.as_any().downcast_ref::<ContinueNode>().unwrap();
+            let target = self.find_loop(continue_node.label()).continue_label;
+            writeln!(w, "\tb       {}", self.label_name(&target))?;
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::VarDeclaration {
+            let var_declaration_node = node.as_any().downcast_ref::<VarDeclarationNode>().unwrap();
+
+            let result_reg = self
+                .code_gen(var_declaration_node.value_node(), w)?
+                .unwrap();
+
+            let size = var_declaration_node.var_type().get_size();
+            self.register_var(var_declaration_node.var_name().to_string(), size);
+
+            let slot = self.frame_slot(self.base_offset, w)?;
+            writeln!(
+                w,
+                "\t{:<8}{}, {}",
+                Self::store_instruction(&size),
+                self.scratch_name_lower_sized(result_reg, &size),
+                slot
+            )?;
+
+            return Ok(Some(result_reg));
+        }
+
+        if node.node_type() == NodeType::VarTypedAssign {
+            let var_assign_node = node.as_any().downcast_ref::<VarTypedAssignNode>().unwrap();
+
+            let reg = self.code_gen(var_assign_node.value_node(), w)?.unwrap();
+
+            if let Some(size) = self.get_static(var_assign_node.var_name()) {
+                let static_name = self.get_static_name(var_assign_node.var_name());
+                self.symbol_address("x16", &static_name, w)?;
+                writeln!(
+                    w,
+                    "\t{:<8}{}, [x16]",
+                    Self::store_instruction(&size),
+                    self.scratch_name_lower_sized(reg, &size)
+                )?;
+                return Ok(Some(reg));
+            }
+
+            let (var_offset, _) = self.get_var(var_assign_node.var_name());
+            let size = var_assign_node.value_type().get_size();
+
+            let slot = self.frame_slot(var_offset, w)?;
+            writeln!(
+                w,
+                "\t{:<8}{}, {}",
+                Self::store_instruction(&size),
+                self.scratch_name_lower_sized(reg, &size),
+                slot
+            )?;
+
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::VarTypedAccess {
+            let var_access_node = node.as_any().downcast_ref::<VarTypedAccessNode>().unwrap();
+
+            if self.is_constant(var_access_node.var_name()) {
+                let value = self.constants[var_access_node.var_name()].clone();
+                return self.code_gen(&value, w);
+            }
+
+            if let Some(size) = self.get_static(var_access_node.var_name()) {
+                let reg = self.res_scratch();
+                let static_name = self.get_static_name(var_access_node.var_name());
+                self.symbol_address("x16", &static_name, w)?;
+                writeln!(
+                    w,
+                    "\t{:<8}{}, [x16]",
+                    Self::load_instruction(&size),
+                    self.scratch_name_lower_sized(reg, &size)
+                )?;
+                return Ok(Some(reg));
+            }
+
+            let (var_offset, _) = self.get_var(var_access_node.var_name());
+            let reg = self.res_scratch();
+
+            let var_size = var_access_node.value_type().get_size();
+
+            let slot = self.frame_slot(var_offset, w)?;
+            writeln!(
+                w,
+                "\t{:<8}{}, {}",
+                Self::load_instruction(&var_size),
+                self.scratch_name_lower_sized(reg, &var_size),
+                slot
+            )?;
+
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::While {
+            let while_node = node.as_any().downcast_ref::<WhileNode>().unwrap();
+
+            let label_start = self.label_create();
+            let label_end = self.label_create();
+
+            self.loops.push(Loop {
+                label: while_node.label().clone(),
+                continue_label: label_start,
+                break_label: label_end,
+            });
+
+            writeln!(w, "{}:", self.label_name(&label_start))?;
+
+            let condition_reg = self.code_gen(while_node.condition_node(), w)?.unwrap();
+            writeln!(
+                w,
+                "\tcbz     {}, {}",
+                self.scratch_name(condition_reg),
+                self.label_name(&label_end)
+            )?;
+            self.free_scratch(condition_reg);
+
+            self.code_gen(while_node.body_node(), w)?;
+            writeln!(w, "\tb       {}", self.label_name(&label_start))?;
+
+            writeln!(w, "{}:", self.label_name(&label_end))?;
+
+            self.loops.pop();
+
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::For {
+            let for_node = node.as_any().downcast_ref::<ForNode>().unwrap();
+
+            let label_start = self.label_create();
+            let label_next = self.label_create();
+            let label_end = self.label_create();
+
+            self.loops.push(Loop {
+                label: for_node.label().clone(),
+                continue_label: label_next,
+                break_label: label_end,
+            });
+
+            let init_reg = self.code_gen(for_node.init_stmt(), w)?;
+            if let Some(init_reg) = init_reg {
+                self.free_scratch(init_reg);
+            }
+
+            writeln!(w, "{}:", self.label_name(&label_start))?;
+            let condition_reg = self.code_gen(for_node.condition(), w)?.unwrap();
+            writeln!(
+                w,
+                "\tcbz     {}, {}",
+                self.scratch_name(condition_reg),
+                self.label_name(&label_end)
+            )?;
+            self.free_scratch(condition_reg);
+
+            let body_reg = self.code_gen(for_node.body(), w)?;
+            if let Some(body_reg) = body_reg {
+                self.free_scratch(body_reg);
+            }
+
+            writeln!(w, "{}:", self.label_name(&label_next))?;
+            let next_reg = self.code_gen(for_node.next_expr(), w)?;
+            if let Some(next_reg) = next_reg {
+                self.free_scratch(next_reg);
+            }
+
+            writeln!(w, "\tb       {}", self.label_name(&label_start))?;
+            writeln!(w, "{}:", self.label_name(&label_end))?;
+
+            self.loops.pop();
+
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::If {
+            let if_node = node.as_any().downcast_ref::<IfNode>().unwrap();
+
+            let label_else = self.label_create();
+            let label_end = self.label_create();
+
+            // every branch moves its value into the same register
+            let result_reg = if if_node.has_value() {
+                Some(self.res_scratch())
+            } else {
+                None
+            };
+
+            let case_labels: Vec<u128> = if_node
+                .cases()
+                .iter()
+                .map(|_| self.label_create())
+                .collect();
+
+            for (i, case) in if_node.cases().iter().enumerate() {
+                writeln!(w, "{}:", self.label_name(&case_labels[i]))?;
+                let condition_reg = self.code_gen(case.condition(), w)?.unwrap();
+                writeln!(
+                    w,
+                    "\tcbz     {}, {}",
+                    self.scratch_name(condition_reg),
+                    if i == if_node.cases().len() - 1 {
+                        self.label_name(&label_else)
+                    } else {
+                        self.label_name(&case_labels[i + 1])
+                    }
+                )?;
+                self.free_scratch(condition_reg);
+
+                self.branch(case.statements(), result_reg, w)?;
+                writeln!(w, "\tb       {}", self.label_name(&label_end))?;
+            }
+
+            writeln!(w, "{}:", self.label_name(&label_else))?;
+            if let Some(else_case) = if_node.else_case() {
+                self.branch(else_case.statements(), result_reg, w)?;
+            }
+
+            writeln!(w, "{}:", self.label_name(&label_end))?;
+
+            return Ok(result_reg);
+        }
+
+        if node.node_type() == NodeType::Cast {
+            let cast_node = node.as_any().downcast_ref::<CastNode>().unwrap();
+            let reg = self.code_gen(cast_node.node(), w)?.unwrap();
+
+            let lower_name = self
+                .scratch_name_lower_sized(reg, &ValueSize::Dword)
+                .to_string();
+            match cast_node.cast_type().get_size() {
+                ValueSize::Byte => writeln!(w, "\tuxtb    {}, {}", lower_name, lower_name)?,
+                ValueSize::Word => writeln!(w, "\tuxth    {}, {}", lower_name, lower_name)?,
+                // writing the lower half zeroes the upper one
+                ValueSize::Dword => writeln!(w, "\tmov     {}, {}", lower_name, lower_name)?,
+                ValueSize::Qword => {}
+            }
+
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::ConstDef {
+            let const_def_node = node.as_any().downcast_ref::<ConstDefinitionNode>().unwrap();
+            self.constants.insert(
+                const_def_node.name().to_string(),
+                const_def_node.value().clone(),
+            );
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::SizeOf {
+            let size_of_node = node.as_any().downcast_ref::<SizeOfNode>().unwrap();
+
+            let res_reg = self.res_scratch();
+            writeln!(
+                w,
+                "\tmov     {}, #{}",
+                self.scratch_name(res_reg),
+                size_of_node.value_type().get_size().get_size_in_bytes()
+            )?;
+
+            return Ok(Some(res_reg));
+        }
+
+        if node.node_type() == NodeType::StaticDef {
+            let static_def_node = node
+                .as_any()
+                .downcast_ref::<StaticDefinitionNode>()
+                .unwrap();
+
+            let size = static_def_node.value_type().get_size();
+            self.add_static(static_def_node.name().to_string(), size);
+
+            // the validator makes sure, that the value is known at compile time
+            let value = constant::evaluate(static_def_node.value()).unwrap();
+            let definition = DataDefinition {
+                label: self.get_static_name(static_def_node.name()),
+                read_only: !*static_def_node.is_mutable(),
+                global: true,
+                size,
+                values: vec![self.constant_value(&value, size)],
+            };
+            self.data.push(definition);
+
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::ReadBytes {
+            let read_bytes_node = node.as_any().downcast_ref::<ReadBytesNode>().unwrap();
+
+            let reg = self.code_gen(read_bytes_node.node(), w)?.unwrap();
+
+            writeln!(
+                w,
+                "\t{:<8}{}, [{}]",
+                Self::load_instruction(read_bytes_node.bytes()),
+                self.scratch_name_lower_sized(reg, read_bytes_node.bytes()),
+                self.scratch_name(reg)
+            )?;
+
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::PointerAssign {
+            let pointer_assign_node = node.as_any().downcast_ref::<PointerAssignNode>().unwrap();
+            let left_reg = self.code_gen(pointer_assign_node.ptr(), w)?.unwrap();
+            let right_reg = self.code_gen(pointer_assign_node.value(), w)?.unwrap();
+
+            let size = pointer_assign_node.pointee_type().get_size();
+            writeln!(
+                w,
+                "\t{:<8}{}, [{}]",
+                Self::store_instruction(&size),
+                self.scratch_name_lower_sized(right_reg, &size),
+                self.scratch_name(left_reg)
+            )?;
+
+            self.free_scratch(left_reg);
+            return Ok(Some(right_reg));
+        }
+
+        if node.node_type() == NodeType::Offset {
+            let offset_node = node.as_any().downcast_ref::<OffsetNode>().unwrap();
+            let left_reg = self.code_gen(offset_node.node(), w)?.unwrap();
+            let right_reg = self.code_gen(offset_node.offset_node(), w)?.unwrap();
+
+            writeln!(
+                w,
+                "\tmov     x9, #{}",
+                offset_node.pointee_type().size_in_bytes()
+            )?;
+            writeln!(
+                w,
+                "\tmadd    {}, {}, x9, {}",
+                self.scratch_name(left_reg),
+                self.scratch_name(right_reg),
+                self.scratch_name(left_reg)
+            )?;
+
+            self.free_scratch(right_reg);
+
+            return Ok(Some(left_reg));
+        }
+
+        if node.node_type() == NodeType::Import {
+            let import_node = node.as_any().downcast_ref::<ImportNode>().unwrap();
+            self.code_gen(import_node.node(), w)?;
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Extern {
+            let extern_node = node.as_any().downcast_ref::<ExternNode>().unwrap();
+
+            match extern_node.top_level_statement().node_type() {
+                NodeType::FunctionDecl => {
+                    let func_decl_node = extern_node
+                        .top_level_statement()
+                        .as_any()
+                        .downcast_ref::<FunctionDeclarationNode>()
+                        .unwrap();
+                    self.add_extern(func_decl_node.var_name().to_string());
+                }
+                NodeType::StaticDecl => {
+                    let static_decl_node = extern_node
+                        .top_level_statement()
+                        .as_any()
+                        .downcast_ref::<StaticDeclarationNode>()
+                        .unwrap();
+                    self.add_extern(static_decl_node.name().to_string());
+                }
+                _ => unreachable!(),
+            }
+
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::AddressOf {
+            let address_of_node = node.as_any().downcast_ref::<AddressOfNode>().unwrap();
+
+            let res_reg = self.res_scratch();
+
+            if self.get_static(address_of_node.var_name()).is_some() {
+                let static_name = self.get_static_name(address_of_node.var_name());
+                self.symbol_address(self.scratch_name(res_reg), &static_name, w)?;
+                return Ok(Some(res_reg));
+            }
+
+            let (var_offset, _) = self.get_var(address_of_node.var_name());
+            self.frame_address(self.scratch_name(res_reg), var_offset, w)?;
+
+            return Ok(Some(res_reg));
+        }
+
+        if node.node_type() == NodeType::Copy {
+            let copy_node = node.as_any().downcast_ref::<CopyNode>().unwrap();
+            let destination_reg = self.code_gen(copy_node.destination(), w)?.unwrap();
+            let source_reg = self.code_gen(copy_node.source(), w)?.unwrap();
+
+            writeln!(w, "\tmov     x9, {}", self.scratch_name(destination_reg))?;
+            writeln!(w, "\tmov     x10, {}", self.scratch_name(source_reg))?;
+            self.load_immediate("x11", copy_node.size_in_bytes(), w)?;
+            self.copy_memory(false, w)?;
+
+            self.free_scratch(source_reg);
+            return Ok(Some(destination_reg));
+        }
+
+        if node.node_type() == NodeType::Slice {
+            let slice_node = node.as_any().downcast_ref::<SliceNode>().unwrap();
+
+            let slice_reg = self.code_gen(slice_node.node(), w)?.unwrap();
+            let start_reg = self.code_gen(slice_node.start(), w)?.unwrap();
+            let end_reg = self.code_gen(slice_node.end(), w)?.unwrap();
+
+            self.base_offset += 16;
+
+            writeln!(
+                w,
+                "\tsub     {}, {}, {}",
+                self.scratch_name(end_reg),
+                self.scratch_name(end_reg),
+                self.scratch_name(start_reg)
+            )?;
+            let slot = self.frame_slot(self.base_offset - SLICE_LENGTH_OFFSET, w)?;
+            writeln!(w, "\tstr     {}, {}", self.scratch_name(end_reg), slot)?;
+
+            writeln!(
+                w,
+                "\tldr     {}, [{}]",
+                self.scratch_name(slice_reg),
+                self.scratch_name(slice_reg)
+            )?;
+            self.load_immediate("x9", slice_node.element_size(), w)?;
+            writeln!(
+                w,
+                "\tmadd    {}, {}, x9, {}",
+                self.scratch_name(slice_reg),
+                self.scratch_name(start_reg),
+                self.scratch_name(slice_reg)
+            )?;
+            let slot = self.frame_slot(self.base_offset, w)?;
+            writeln!(w, "\tstr     {}, {}", self.scratch_name(slice_reg), slot)?;
+
+            self.frame_address(self.scratch_name(slice_reg), self.base_offset, w)?;
+
+            self.free_scratch(start_reg);
+            self.free_scratch(end_reg);
+            return Ok(Some(slice_reg));
+        }
+
+        if node.node_type() == NodeType::StackAllocationNode {
+            let stack_allocation_node =
+                node.as_any().downcast_ref::<StackAllocationNode>().unwrap();
+
+            self.base_offset += stack_allocation_node.size_in_bytes();
+            self.zero_frame(self.base_offset, *stack_allocation_node.size_in_bytes(), w)?;
+
+            let reg = self.res_scratch();
+            self.frame_address(self.scratch_name(reg), self.base_offset, w)?;
+            return Ok(Some(reg));
+        }
+
+        match node.node_type() {
+            // declarations, that the validator already resolved
+            NodeType::FunctionDecl
+            | NodeType::StaticDecl
+            | NodeType::StructDef
+            | NodeType::MacroDef
+            | NodeType::Ignored => Ok(None),
+            NodeType::FloatingPoint
+            | NodeType::FloatingBinOp
+            | NodeType::F64ToU64
+            | NodeType::U64ToF64 => Err(self.unsupported(
+                node.as_ref(),
+                "Floating point numbers aren't supported by the aarch64 backend yet!",
+            )),
+            node_type => Err(self.unsupported(
+                node.as_ref(),
+                &format!(
+                    "{:?} isn't supported by the aarch64 backend yet!",
+                    node_type
+                ),
+            )),
+        }
+    }
+
+    pub fn compile_to_str(
+        &mut self,
+        node: &Box<dyn Node>,
+        no_entry: bool,
+        arch: TargetObjectType,
+    ) -> Result<String, Error> {
+        self.compile_program(node, no_entry, arch)
+            .map_err(|fmt_error| self.codegen_error(fmt_error))
+    }
+
+    fn compile_program(
+        &mut self,
+        node: &Box<dyn Node>,
+        no_entry: bool,
+        arch: TargetObjectType,
+    ) -> Result<String, fmt::Error> {
+        let mut code = String::new();
+        self.code_gen(node, &mut code)?;
+
+        if !no_entry {
+            self.add_global(ENTRY_SYMBOL.to_string());
+
+            // the kernel puts argc on top of the stack, followed by the argument pointers
+            writeln!(code, "{}:", ENTRY_SYMBOL)?;
+            writeln!(code, "\tldr     x0, [sp]")?;
+            writeln!(code, "\tldr     x1, [sp, #8]")?;
+
+            writeln!(code, "\tbl      main")?;
+
+            writeln!(code, "\tmov     x8, #{}", SyscallTable::Exit.code(arch))?;
+            writeln!(code, "\tsvc     #0")?;
+        }
+
+        self.assemble_sections(code)
+    }
+
+    /// Compiles the given node with a test harness as entry point instead of `main`, which works
+    /// like the one of the x86-64 backend.
+    pub fn compile_tests_to_str(
+        &mut self,
+        node: &Box<dyn Node>,
+        tests: &[(String, Position)],
+        arch: TargetObjectType,
+    ) -> Result<String, Error> {
+        self.compile_test_program(node, tests, arch)
+            .map_err(|fmt_error| self.codegen_error(fmt_error))
+    }
+
+    fn compile_test_program(
+        &mut self,
+        node: &Box<dyn Node>,
+        tests: &[(String, Position)],
+        arch: TargetObjectType,
+    ) -> Result<String, fmt::Error> {
+        let mut code = String::new();
+        self.code_gen(node, &mut code)?;
+
+        self.add_global(ENTRY_SYMBOL.to_string());
+        writeln!(code, "{}:", ENTRY_SYMBOL)?;
+
+        // x19 counts the passed, x20 the failed tests
+        writeln!(code, "\tmov     x19, xzr")?;
+        writeln!(code, "\tmov     x20, xzr")?;
+
+        self.write_raw_string(
+            1,
+            &format!(
+                "\nrunning {} test{}\n",
+                tests.len(),
+                if tests.len() == 1 { "" } else { "s" }
+            ),
+            &mut code,
+        )?;
+
+        for (test, _) in tests {
+            let label_parent = self.label_create();
+            let label_failed = self.label_create();
+            let label_next = self.label_create();
+
+            writeln!(code, "\tmov     x0, #{}", CLONE_FORK_FLAGS)?;
+            writeln!(code, "\tmov     x1, xzr")?;
+            writeln!(code, "\tmov     x2, xzr")?;
+            writeln!(code, "\tmov     x3, xzr")?;
+            writeln!(code, "\tmov     x4, xzr")?;
+            writeln!(code, "\tmov     x8, #{}", SyscallTable::Fork.code(arch))?;
+            writeln!(code, "\tsvc     #0")?;
+            writeln!(code, "\tcbnz    x0, {}", self.label_name(&label_parent))?;
+
+            // child: run the test and exit successfully, a failed assertion exits with 1
+            writeln!(code, "\tbl      {}", test)?;
+            writeln!(code, "\tmov     x0, xzr")?;
+            writeln!(code, "\tmov     x8, #{}", SyscallTable::Exit.code(arch))?;
+            writeln!(code, "\tsvc     #0")?;
+
+            // parent: wait for the child and check its status
+            writeln!(code, "{}:", self.label_name(&label_parent))?;
+            writeln!(
+                code,
+                "\ttbnz    x0, #63, {}",
+                self.label_name(&label_failed)
+            )?;
+            writeln!(code, "\tsub     sp, sp, #16")?;
+            writeln!(code, "\tmov     x9, #-1")?;
+            writeln!(code, "\tstr     x9, [sp]")?;
+            writeln!(code, "\tmov     x1, sp")?;
+            writeln!(code, "\tmov     x2, xzr")?;
+            writeln!(code, "\tmov     x3, xzr")?;
+            writeln!(code, "\tmov     x8, #{}", SyscallTable::Wait4.code(arch))?;
+            writeln!(code, "\tsvc     #0")?;
+            writeln!(code, "\tldr     w9, [sp]")?;
+            writeln!(code, "\tadd     sp, sp, #16")?;
+            writeln!(code, "\tcbnz    w9, {}", self.label_name(&label_failed))?;
+
+            self.write_raw_string(1, &format!("test {} ... ok\n", test), &mut code)?;
+            writeln!(code, "\tadd     x19, x19, #1")?;
+            writeln!(code, "\tb       {}", self.label_name(&label_next))?;
+
+            writeln!(code, "{}:", self.label_name(&label_failed))?;
+            self.write_raw_string(1, &format!("test {} ... FAILED\n", test), &mut code)?;
+            writeln!(code, "\tadd     x20, x20, #1")?;
+
+            writeln!(code, "{}:", self.label_name(&label_next))?;
+        }
+
+        let label_print_number = self.label_create();
+        let label_failed = self.label_create();
+        let label_counts = self.label_create();
+
+        self.write_raw_string(1, "\ntest result: ", &mut code)?;
+        writeln!(code, "\tcbnz    x20, {}", self.label_name(&label_failed))?;
+        self.write_raw_string(1, "ok. ", &mut code)?;
+        writeln!(code, "\tb       {}", self.label_name(&label_counts))?;
+        writeln!(code, "{}:", self.label_name(&label_failed))?;
+        self.write_raw_string(1, "FAILED. ", &mut code)?;
+
+        writeln!(code, "{}:", self.label_name(&label_counts))?;
+        writeln!(code, "\tmov     x0, x19")?;
+        writeln!(code, "\tbl      {}", self.label_name(&label_print_number))?;
+        self.write_raw_string(1, " passed; ", &mut code)?;
+        writeln!(code, "\tmov     x0, x20")?;
+        writeln!(code, "\tbl      {}", self.label_name(&label_print_number))?;
+        self.write_raw_string(1, " failed\n\n", &mut code)?;
+
+        writeln!(code, "\tcmp     x20, #0")?;
+        writeln!(code, "\tcset    x0, ne")?;
+        writeln!(code, "\tmov     x8, #{}", SyscallTable::Exit.code(arch))?;
+        writeln!(code, "\tsvc     #0")?;
+
+        // prints the unsigned number in x0 to stdout
+        let label_next_digit = self.label_create();
+        writeln!(code, "{}:", self.label_name(&label_print_number))?;
+        writeln!(code, "\tsub     sp, sp, #32")?;
+        writeln!(code, "\tadd     x1, sp, #32")?;
+        writeln!(code, "\tmov     x10, #10")?;
+        writeln!(code, "{}:", self.label_name(&label_next_digit))?;
+        writeln!(code, "\tudiv    x9, x0, x10")?;
+        writeln!(code, "\tmsub    x11, x9, x10, x0")?;
+        writeln!(code, "\tadd     x11, x11, #'0'")?;
+        writeln!(code, "\tsub     x1, x1, #1")?;
+        writeln!(code, "\tstrb    w11, [x1]")?;
+        writeln!(code, "\tmov     x0, x9")?;
+        writeln!(code, "\tcbnz    x0, {}", self.label_name(&label_next_digit))?;
+        writeln!(code, "\tadd     x2, sp, #32")?;
+        writeln!(code, "\tsub     x2, x2, x1")?;
+        writeln!(code, "\tmov     x0, #1")?;
+        writeln!(code, "\tmov     x8, #{}", SyscallTable::Write.code(arch))?;
+        writeln!(code, "\tsvc     #0")?;
+        writeln!(code, "\tadd     sp, sp, #32")?;
+        writeln!(code, "\tret")?;
+
+        self.assemble_sections(code)
+    }
+
+    /// Puts the generated code together with the data and bss sections, as well as the global
+    /// and extern declarations.
+    fn assemble_sections(&self, code: String) -> Result<String, fmt::Error> {
+        let mut res = String::new();
+
+        // region .data
+        writeln!(res, "\t.data")?;
+
+        writeln!(res, "\t// Static strings")?;
+        for (bytes, uuid) in &self.strings {
+            let mut literal = String::new();
+            for byte in bytes {
+                match byte {
+                    b'"' | b'\\' => write!(literal, "\\{}", *byte as char)?,
+                    b' '..=b'~' => literal.push(*byte as char),
+                    _ => write!(literal, "\\{:03o}", byte)?,
+                }
+            }
+            writeln!(res, "{}:\t.asciz  \"{}\"", uuid, literal)?;
+        }
+
+        writeln!(
+            res,
+            "\t// Statics, exported for C headers, and the data they point to"
+        )?;
+        self.write_data_definitions(&mut res, false)?;
+
+        writeln!(res, "\t.section .rodata")?;
+        self.write_data_definitions(&mut res, true)?;
+
+        writeln!(res, "\t.bss")?;
+        for (label, bytes) in &self.zeroed {
+            writeln!(res, "\t.balign 8")?;
+            writeln!(res, "{}:\t.zero   {}", label, bytes)?;
+        }
+        // endregion
+
+        writeln!(res, "\n\t.text\n")?;
+
+        if !self.globals.is_empty() {
+            writeln!(res, "\t.global {}\n", self.globals.join(", "))?;
+        }
+
+        if !self.externs.is_empty() {
+            writeln!(res, "\t.extern {}", self.externs.join(", "))?;
+        }
+
+        writeln!(res, "{}", code)?;
+
+        Ok(res)
+    }
+
+    /// Writes the data definitions, that are either read-only or writable.
+    fn write_data_definitions(&self, res: &mut String, read_only: bool) -> fmt::Result {
+        for definition in self.data.iter().filter(|d| d.read_only == read_only) {
+            if definition.global {
+                writeln!(res, "\t.global {}", definition.label)?;
+            }
+
+            let directive = match definition.size {
+                ValueSize::Byte => ".byte",
+                ValueSize::Word => ".short",
+                ValueSize::Dword => ".long",
+                ValueSize::Qword => ".quad",
+            };
+            writeln!(res, "\t.balign 8")?;
+            writeln!(
+                res,
+                "{}:\t{:<7} {}",
+                definition.label,
+                directive,
+                definition.values.join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::semantics::{RuntimeCheck, Validator};
+    use std::path::PathBuf;
+
+    fn generate(source: &str) -> String {
+        generate_with_checks(source, &[])
+    }
+
+    fn generate_with_checks(source: &str, checks: &[RuntimeCheck]) -> String {
+        compile(source, checks).unwrap()
+    }
+
+    fn compile(source: &str, checks: &[RuntimeCheck]) -> Result<String, Error> {
+        let path = PathBuf::from("/tmp/aarch64_compiler_test.ub");
+        let tokens = Lexer::new(path, source.to_string()).make_tokens().unwrap();
+
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+
+        let validation_res = Validator::new_with_checks(checks).validate(&ast_root);
+        assert!(
+            validation_res.error().is_none(),
+            "{:?}",
+            validation_res.error()
+        );
+
+        Aarch64Compiler::new(TargetObjectType::Aarch64Linux).compile_to_str(
+            validation_res.node().as_ref().unwrap(),
+            false,
+            TargetObjectType::Aarch64Linux,
+        )
+    }
+
+    #[test]
+    pub fn compiler_register_distribution() {
+        let mut c = Aarch64Compiler::new(TargetObjectType::Aarch64Linux);
+
+        c.res_scratch();
+        c.res_scratch();
+        assert_eq!(*c.scratch_regs(), 0b0000011);
+        assert_eq!(c.scratch_name(1), "x20");
+        assert_eq!(c.scratch_name_lower_sized(1, &ValueSize::Byte), "w20");
+
+        c.free_scratch(0);
+        assert_eq!(*c.scratch_regs(), 0b0000010);
+    }
+
+    #[test]
+    fn test_generate_aarch64() {
+        let asm = generate(
+            "static mut counter: u16 = 3 as u16;
+
+fun many(a: u64, b: u64, c: u64, d: u64, e: u64, f: u64, g: u64, h: u64, i: u64): u64 {
+    return a % i;
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    counter = counter + 1 as u16;
+    syscall[64, 1, (\"hi\\n\" as char *) as u64, 3];
+    return many(1, 2, 3, 4, 5, 6, 7, 8, 70000);
+};",
+        );
+
+        assert!(asm.contains("\t.global many, main, _start\n"));
+        assert!(asm.contains("S0:\t.asciz  \"hi\\012\"\n"));
+        assert!(asm.contains("ST_counter:\t.short  3\n"));
+        assert!(asm.contains("\tstrh    w19, [x16]\n"));
+        assert!(asm.contains("\tldr     x9, [x29, #16]\n"));
+        assert!(asm.contains("\tmsub    x19, x9, x20, x19\n"));
+        assert!(asm.contains("\tmovz    x19, #4464\n\tmovk    x19, #1, lsl #16\n"));
+        assert!(asm.contains("\tmov     x8, x19\n\tmov     x0, x20\n"));
+        assert!(asm.contains("\tbl      main\n\tmov     x8, #93\n\tsvc     #0\n"));
+    }
+
+    #[test]
+    fn test_constants() {
+        let asm = generate(
+            "const LIMIT: u64 = 40 + 2;
+fun main(argc: u64, argv: char *): u64 {
+    return LIMIT + argc;
+};",
+        );
+
+        // the value of a constant is generated where it's read
+        assert!(asm.contains(
+            "\tmovz    x19, #40\n\tmovz    x20, #2\n\tadd     x19, x19, x20\n\tldr     x20, [x29, #-8]\n"
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_floats() {
+        let error = compile(
+            "extern fun printf(format: char *, ...): i32;
+fun main(argc: u64, argv: char *): u64 {
+    let x: f64 = 2.5;
+    printf(\"%f\" as char *, x);
+    return x as u64;
+};",
+            &[],
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.details(),
+            "Floating point numbers aren't supported by the aarch64 backend yet!"
+        );
+        assert_eq!(*error.pos_start().line(), 2);
+    }
+
+    #[test]
+    fn test_slices() {
+        let asm = generate(
+            "extern fun write_all(s: str): void;
+fun first(s: str): char { return *s[0]; };
+fun main(): u64 {
+    let text: str = \"hello\" as str;
+    write_all(text);
+    return first(text[1..3]) as u64;
+};",
+        );
+
+        // the callee stores the pointer and length of the slice on its stack
+        assert!(asm.contains(
+            "\tstr     x0, [x29, #-24]\n\tstr     x1, [x29, #-16]\n\tsub     x9, x29, #24\n"
+        ));
+        assert!(asm.contains("\tmadd    x19, x20, x9, x19\n\tstr     x19, [x29, #-40]\n"));
+        assert!(asm.contains(
+            "\tldr     x9, [x19, #8]\n\tstr     x9, [sp, #-16]!\n\tldr     x19, [x19]\n"
+        ));
+    }
+
+    #[test]
+    fn test_memory_intrinsics() {
+        let asm = generate(
+            "fun main(argc: u64, argv: char *): u64 {
+    let buf: [u8; 256] = [<u8>; 256];
+    let p: u8 *mut = buf as u8 *mut;
+    memset[p, 7 as u8, argc];
+    memcpy[p + 64, p, 16];
+    memmove[p + 1, p, argc];
+    return 0;
+};",
+        );
+
+        // large blocks are zeroed in a loop, eight bytes at a time
+        assert!(asm.contains("\tmovz    x10, #256\n\tmov     x11, xzr\n"));
+        assert!(asm.contains("\tstr     x11, [x9], #8\n"));
+        assert!(asm.contains("\tmul     x11, x11, x12\n"));
+        // overlapping blocks are copied backwards if the destination starts within the source
+        assert!(asm.contains("\tsub     x12, x9, x10\n\tcmp     x12, x11\n"));
+        assert!(asm.contains("\tldr     x12, [x10, #-8]!\n\tstr     x12, [x9, #-8]!\n"));
+    }
+
+    #[test]
+    fn test_runtime_checks() {
+        let asm = generate_with_checks(
+            "fun main(argc: u64, argv: char *): u64 {
+    let values: [u8; 3] = [<u8>; 3];
+    let small: i8 = (argc as i8) * (3 as i8);
+    return ((*values[argc]) as u64) + argc;
+};",
+            &[
+                RuntimeCheck::Bounds,
+                RuntimeCheck::Null,
+                RuntimeCheck::Overflow,
+            ],
+        );
+
+        // smaller integers are extended to 64 bits and the result has to fit into their size
+        assert!(asm.contains(
+            "\tsxtb    x19, w19\n\tsxtb    x20, w20\n\tmul     x19, x19, x20\n\tsxtb    x9, w19\n\tcmp     x9, x19\n\tb.eq    "
+        ));
+        assert!(asm.contains("\tmovz    x9, #3\n\tcmp     x20, x9\n\tb.lo    "));
+        assert!(asm.contains("\tcbnz    x19, "));
+        assert!(asm.contains("\tadds    x19, x19, x20\n\tb.cc    "));
+        assert!(asm.contains("\tmov     x0, #101\n\tmov     x8, #93\n\tsvc     #0\n"));
+    }
+
+    #[test]
+    fn test_slice_runtime_checks() {
+        let asm = generate_with_checks(
+            "fun main(argc: u64, argv: char *): u64 {
+    let values: [u64; 4] = [<u64>; 4];
+    let part: []u64 = values[1..argc];
+    return *part[argc];
+};",
+            &[RuntimeCheck::Bounds],
+        );
+
+        // the end of the sub-slice is compared to the length, then the start to the end
+        assert!(asm.contains("\tldr     x21, [x21]\n\tcmp     x20, x21\n\tb.ls    "));
+        assert!(asm.contains("\tcmp     x21, x22\n\tb.ls    "));
+        assert!(asm.contains("\tldr     x21, [x21]\n\tcmp     x20, x21\n\tb.lo    "));
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+
+pub const R_X86_64_64: u32 = 1;
+pub const R_X86_64_PC32: u32 = 2;
+pub const R_X86_64_PLT32: u32 = 4;
+pub const R_X86_64_32: u32 = 10;
+pub const R_X86_64_32S: u32 = 11;
+
+pub const HEADER_SIZE: usize = 64;
+pub const SECTION_HEADER_SIZE: usize = 64;
+pub const SYMBOL_SIZE: usize = 24;
+pub const RELOCATION_SIZE: usize = 24;
+
+const SHT_PROGBITS: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+pub const SHT_RELA: u32 = 4;
+pub const SHT_NOBITS: u32 = 8;
+
+pub const SHF_WRITE: u64 = 0x1;
+pub const SHF_ALLOC: u64 = 0x2;
+pub const SHF_EXECINSTR: u64 = 0x4;
+const SHF_INFO_LINK: u64 = 0x40;
+
+pub const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STT_NOTYPE: u8 = 0;
+const STT_SECTION: u8 = 3;
+
+const SECTION_NAMES: [&str; 12] = [
+    "",
+    ".data",
+    ".bss",
+    ".text",
+    ".rodata",
+    ".rela.data",
+    ".rela.text",
+    ".rela.rodata",
+    ".note.GNU-stack",
+    ".symtab",
+    ".strtab",
+    ".shstrtab",
+];
+const SYMTAB_INDEX: u32 = 9;
+const STRTAB_INDEX: u32 = 10;
+const SHSTRTAB_INDEX: u16 = 11;
+
+const SECTIONS: [Section; 4] = [Section::Data, Section::Bss, Section::Text, Section::Rodata];
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Section {
+    Data,
+    Bss,
+    Text,
+    Rodata,
+}
+
+impl Section {
+    /// The index of the section header, which is also the index of the section's symbol.
+    fn index(&self) -> u16 {
+        match self {
+            Section::Data => 1,
+            Section::Bss => 2,
+            Section::Text => 3,
+            Section::Rodata => 4,
+        }
+    }
+}
+
+/// A symbol of the object file. Symbols without a section are undefined and have to be resolved by
+/// the linker.
+pub struct Symbol {
+    pub name: String,
+    pub section: Option<Section>,
+    pub value: u64,
+    pub global: bool,
+}
+
+pub enum RelocationTarget {
+    Section(Section),
+    Symbol(String),
+}
+
+/// A relocation of a section with contents.
+pub struct Relocation {
+    pub section: Section,
+    pub offset: u64,
+    pub target: RelocationTarget,
+    pub kind: u32,
+    pub addend: i64,
+}
+
+/// An ELF64 relocatable object file for x86-64.
+pub struct ObjectFile {
+    pub data: Vec<u8>,
+    pub bss_size: u64,
+    pub text: Vec<u8>,
+    pub rodata: Vec<u8>,
+    pub symbols: Vec<Symbol>,
+    pub relocations: Vec<Relocation>,
+}
+
+struct SectionHeader {
+    name: u32,
+    kind: u32,
+    flags: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entry_size: u64,
+}
+
+fn write_symbol(w: &mut Vec<u8>, name: u32, bind: u8, kind: u8, section: u16, value: u64) {
+    w.extend_from_slice(&name.to_le_bytes());
+    w.push(bind << 4 | kind);
+    w.push(0);
+    w.extend_from_slice(&section.to_le_bytes());
+    w.extend_from_slice(&value.to_le_bytes());
+    w.extend_from_slice(&0_u64.to_le_bytes());
+}
+
+/// Appends the contents of a section at the given alignment and returns their offset in the file.
+fn append_aligned(file: &mut Vec<u8>, content: &[u8], align: usize) -> u64 {
+    file.resize(file.len().div_ceil(align) * align, 0);
+    let offset = file.len() as u64;
+    file.extend_from_slice(content);
+    offset
+}
+
+/// The header of the section with the relocations of the given section.
+fn rela_header(name: u32, offset: u64, rela: &[u8], section: Section) -> SectionHeader {
+    SectionHeader {
+        name,
+        kind: SHT_RELA,
+        flags: SHF_INFO_LINK,
+        offset,
+        size: rela.len() as u64,
+        link: SYMTAB_INDEX,
+        info: section.index() as u32,
+        align: 8,
+        entry_size: RELOCATION_SIZE as u64,
+    }
+}
+
+impl ObjectFile {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // region Symbols
+        let mut strtab: Vec<u8> = vec![0];
+        let mut symtab: Vec<u8> = vec![0; SYMBOL_SIZE];
+        let mut symbol_indices: HashMap<&str, u64> = HashMap::new();
+
+        for section in SECTIONS {
+            write_symbol(&mut symtab, 0, STB_LOCAL, STT_SECTION, section.index(), 0);
+        }
+
+        // local symbols have to come first
+        let locals = self.symbols.iter().filter(|symbol| !symbol.global);
+        let globals = self.symbols.iter().filter(|symbol| symbol.global);
+        let first_global = 1 + SECTIONS.len() + locals.clone().count();
+
+        for (i, symbol) in locals.chain(globals).enumerate() {
+            let name = strtab.len() as u32;
+            strtab.extend_from_slice(symbol.name.as_bytes());
+            strtab.push(0);
+
+            write_symbol(
+                &mut symtab,
+                name,
+                if symbol.global { STB_GLOBAL } else { STB_LOCAL },
+                STT_NOTYPE,
+                symbol.section.map_or(0, |section| section.index()),
+                symbol.value,
+            );
+            symbol_indices.insert(&symbol.name, (1 + SECTIONS.len() + i) as u64);
+        }
+        // endregion
+
+        let rela = |section: Section| {
+            let mut rela: Vec<u8> = vec![];
+            for relocation in self.relocations.iter().filter(|r| r.section == section) {
+                let symbol = match &relocation.target {
+                    RelocationTarget::Section(section) => section.index() as u64,
+                    RelocationTarget::Symbol(name) => symbol_indices[name.as_str()],
+                };
+
+                rela.extend_from_slice(&relocation.offset.to_le_bytes());
+                rela.extend_from_slice(&(symbol << 32 | relocation.kind as u64).to_le_bytes());
+                rela.extend_from_slice(&relocation.addend.to_le_bytes());
+            }
+            rela
+        };
+        let rela_data = rela(Section::Data);
+        let rela_text = rela(Section::Text);
+        let rela_rodata = rela(Section::Rodata);
+
+        let mut shstrtab: Vec<u8> = vec![];
+        let mut name_offsets: Vec<u32> = vec![];
+        for name in SECTION_NAMES {
+            name_offsets.push(shstrtab.len() as u32);
+            shstrtab.extend_from_slice(name.as_bytes());
+            shstrtab.push(0);
+        }
+
+        // region Layout
+        let mut file: Vec<u8> = vec![0; HEADER_SIZE];
+
+        let data_offset = append_aligned(&mut file, &self.data, 4);
+        let bss_offset = file.len() as u64;
+        let text_offset = append_aligned(&mut file, &self.text, 16);
+        let rodata_offset = append_aligned(&mut file, &self.rodata, 8);
+        let rela_data_offset = append_aligned(&mut file, &rela_data, 8);
+        let rela_text_offset = append_aligned(&mut file, &rela_text, 8);
+        let rela_rodata_offset = append_aligned(&mut file, &rela_rodata, 8);
+        let note_offset = file.len() as u64;
+        let symtab_offset = append_aligned(&mut file, &symtab, 8);
+        let strtab_offset = append_aligned(&mut file, &strtab, 1);
+        let shstrtab_offset = append_aligned(&mut file, &shstrtab, 1);
+
+        let section_headers = [
+            SectionHeader {
+                name: 0,
+                kind: 0,
+                flags: 0,
+                offset: 0,
+                size: 0,
+                link: 0,
+                info: 0,
+                align: 0,
+                entry_size: 0,
+            },
+            SectionHeader {
+                name: name_offsets[1],
+                kind: SHT_PROGBITS,
+                flags: SHF_WRITE | SHF_ALLOC,
+                offset: data_offset,
+                size: self.data.len() as u64,
+                link: 0,
+                info: 0,
+                align: 4,
+                entry_size: 0,
+            },
+            SectionHeader {
+                name: name_offsets[2],
+                kind: SHT_NOBITS,
+                flags: SHF_WRITE | SHF_ALLOC,
+                offset: bss_offset,
+                size: self.bss_size,
+                link: 0,
+                info: 0,
+                align: 4,
+                entry_size: 0,
+            },
+            SectionHeader {
+                name: name_offsets[3],
+                kind: SHT_PROGBITS,
+                flags: SHF_ALLOC | SHF_EXECINSTR,
+                offset: text_offset,
+                size: self.text.len() as u64,
+                link: 0,
+                info: 0,
+                align: 16,
+                entry_size: 0,
+            },
+            SectionHeader {
+                name: name_offsets[4],
+                kind: SHT_PROGBITS,
+                flags: SHF_ALLOC,
+                offset: rodata_offset,
+                size: self.rodata.len() as u64,
+                link: 0,
+                info: 0,
+                align: 8,
+                entry_size: 0,
+            },
+            rela_header(name_offsets[5], rela_data_offset, &rela_data, Section::Data),
+            rela_header(name_offsets[6], rela_text_offset, &rela_text, Section::Text),
+            rela_header(
+                name_offsets[7],
+                rela_rodata_offset,
+                &rela_rodata,
+                Section::Rodata,
+            ),
+            // marks the stack as not executable
+            SectionHeader {
+                name: name_offsets[8],
+                kind: SHT_PROGBITS,
+                flags: 0,
+                offset: note_offset,
+                size: 0,
+                link: 0,
+                info: 0,
+                align: 1,
+                entry_size: 0,
+            },
+            SectionHeader {
+                name: name_offsets[9],
+                kind: SHT_SYMTAB,
+                flags: 0,
+                offset: symtab_offset,
+                size: symtab.len() as u64,
+                link: STRTAB_INDEX,
+                info: first_global as u32,
+                align: 8,
+                entry_size: SYMBOL_SIZE as u64,
+            },
+            SectionHeader {
+                name: name_offsets[10],
+                kind: SHT_STRTAB,
+                flags: 0,
+                offset: strtab_offset,
+                size: strtab.len() as u64,
+                link: 0,
+                info: 0,
+                align: 1,
+                entry_size: 0,
+            },
+            SectionHeader {
+                name: name_offsets[11],
+                kind: SHT_STRTAB,
+                flags: 0,
+                offset: shstrtab_offset,
+                size: shstrtab.len() as u64,
+                link: 0,
+                info: 0,
+                align: 1,
+                entry_size: 0,
+            },
+        ];
+
+        file.resize(file.len().div_ceil(8) * 8, 0);
+        let section_headers_offset = file.len() as u64;
+
+        for header in &section_headers {
+            file.extend_from_slice(&header.name.to_le_bytes());
+            file.extend_from_slice(&header.kind.to_le_bytes());
+            file.extend_from_slice(&header.flags.to_le_bytes());
+            file.extend_from_slice(&0_u64.to_le_bytes());
+            file.extend_from_slice(&header.offset.to_le_bytes());
+            file.extend_from_slice(&header.size.to_le_bytes());
+            file.extend_from_slice(&header.link.to_le_bytes());
+            file.extend_from_slice(&header.info.to_le_bytes());
+            file.extend_from_slice(&header.align.to_le_bytes());
+            file.extend_from_slice(&header.entry_size.to_le_bytes());
+        }
+        // endregion
+
+        // region ELF header
+        let mut header: Vec<u8> = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0];
+        header.resize(16, 0);
+        header.extend_from_slice(&1_u16.to_le_bytes()); // relocatable
+        header.extend_from_slice(&62_u16.to_le_bytes()); // x86-64
+        header.extend_from_slice(&1_u32.to_le_bytes());
+        header.extend_from_slice(&0_u64.to_le_bytes()); // entry
+        header.extend_from_slice(&0_u64.to_le_bytes()); // program headers
+        header.extend_from_slice(&section_headers_offset.to_le_bytes());
+        header.extend_from_slice(&0_u32.to_le_bytes());
+        header.extend_from_slice(&(HEADER_SIZE as u16).to_le_bytes());
+        header.extend_from_slice(&0_u16.to_le_bytes());
+        header.extend_from_slice(&0_u16.to_le_bytes());
+        header.extend_from_slice(&(SECTION_HEADER_SIZE as u16).to_le_bytes());
+        header.extend_from_slice(&(section_headers.len() as u16).to_le_bytes());
+        header.extend_from_slice(&SHSTRTAB_INDEX.to_le_bytes());
+
+        file[..HEADER_SIZE].copy_from_slice(&header);
+        // endregion
+
+        file
+    }
+}
@@ -0,0 +1,1110 @@
+use crate::assembler::operand::{Immediate, Memory, Operand};
+use crate::values::value_size::ValueSize;
+
+const QW_REGISTERS: [&str; 16] = [
+    "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15",
+];
+const DW_REGISTERS: [&str; 16] = [
+    "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "r8d", "r9d", "r10d", "r11d", "r12d",
+    "r13d", "r14d", "r15d",
+];
+const W_REGISTERS: [&str; 16] = [
+    "ax", "cx", "dx", "bx", "sp", "bp", "si", "di", "r8w", "r9w", "r10w", "r11w", "r12w", "r13w",
+    "r14w", "r15w",
+];
+const B_REGISTERS: [&str; 16] = [
+    "al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil", "r8b", "r9b", "r10b", "r11b", "r12b",
+    "r13b", "r14b", "r15b",
+];
+/// Used to pass floating point arguments to C functions, by `movq` from and to 64 bit registers,
+/// and to set and copy memory in chunks of 16 bytes.
+const XMM_REGISTERS: [&str; 16] = [
+    "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7", "xmm8", "xmm9", "xmm10",
+    "xmm11", "xmm12", "xmm13", "xmm14", "xmm15",
+];
+
+/// Condition codes and their aliases, as used by `jcc`, `setcc` and `cmovcc`.
+const CONDITIONS: [(&str, u8); 30] = [
+    ("o", 0x0),
+    ("no", 0x1),
+    ("b", 0x2),
+    ("c", 0x2),
+    ("nae", 0x2),
+    ("ae", 0x3),
+    ("nb", 0x3),
+    ("nc", 0x3),
+    ("e", 0x4),
+    ("z", 0x4),
+    ("ne", 0x5),
+    ("nz", 0x5),
+    ("be", 0x6),
+    ("na", 0x6),
+    ("a", 0x7),
+    ("nbe", 0x7),
+    ("s", 0x8),
+    ("ns", 0x9),
+    ("p", 0xa),
+    ("pe", 0xa),
+    ("np", 0xb),
+    ("po", 0xb),
+    ("l", 0xc),
+    ("nge", 0xc),
+    ("ge", 0xd),
+    ("nl", 0xd),
+    ("le", 0xe),
+    ("ng", 0xe),
+    ("g", 0xf),
+    ("nle", 0xf),
+];
+
+/// Arithmetic instructions with the opcode extension of their immediate forms.
+const ARITHMETIC: [(&str, u8); 8] = [
+    ("add", 0),
+    ("or", 1),
+    ("adc", 2),
+    ("sbb", 3),
+    ("and", 4),
+    ("sub", 5),
+    ("xor", 6),
+    ("cmp", 7),
+];
+
+const SHIFTS: [(&str, u8); 8] = [
+    ("rol", 0),
+    ("ror", 1),
+    ("rcl", 2),
+    ("rcr", 3),
+    ("shl", 4),
+    ("sal", 4),
+    ("shr", 5),
+    ("sar", 7),
+];
+
+/// Instructions with a single r/m operand, encoded as `F6 /n` or `F7 /n`.
+const UNARY: [(&str, u8); 5] = [("not", 2), ("neg", 3), ("mul", 4), ("div", 6), ("idiv", 7)];
+
+const WITHOUT_OPERANDS: [(&str, &[u8]); 14] = [
+    ("ret", &[0xc3]),
+    ("syscall", &[0x0f, 0x05]),
+    ("nop", &[0x90]),
+    ("leave", &[0xc9]),
+    ("hlt", &[0xf4]),
+    ("int3", &[0xcc]),
+    ("ud2", &[0x0f, 0x0b]),
+    ("cdq", &[0x99]),
+    ("cqo", &[0x48, 0x99]),
+    ("cdqe", &[0x48, 0x98]),
+    ("stosb", &[0xaa]),
+    ("movsb", &[0xa4]),
+    ("cld", &[0xfc]),
+    ("std", &[0xfd]),
+];
+
+/// String instructions, that can be repeated `rcx` times with the `rep` prefix.
+const REPEATABLE: [&str; 2] = ["stosb", "movsb"];
+
+/// SSE instructions on two xmm registers, or an xmm register and memory, encoded as `66 0F xx /r`.
+const SSE: [(&str, u8); 2] = [("pxor", 0xef), ("punpcklqdq", 0x6c)];
+
+const INVALID_OPERANDS: &str = "Invalid combination of opcode and operands!";
+
+#[derive(Copy, Clone, PartialEq)]
+pub struct Register {
+    number: u8,
+    size: ValueSize,
+    is_xmm: bool,
+}
+
+impl Register {
+    pub fn from_name(name: &str) -> Option<Register> {
+        let name = name.to_ascii_lowercase();
+
+        if let Some(number) = XMM_REGISTERS.iter().position(|n| *n == name) {
+            return Some(Register {
+                number: number as u8,
+                size: ValueSize::Qword,
+                is_xmm: true,
+            });
+        }
+
+        [
+            (QW_REGISTERS, ValueSize::Qword),
+            (DW_REGISTERS, ValueSize::Dword),
+            (W_REGISTERS, ValueSize::Word),
+            (B_REGISTERS, ValueSize::Byte),
+        ]
+        .iter()
+        .find_map(|(names, size)| {
+            names
+                .iter()
+                .position(|n| *n == name)
+                .map(|number| Register {
+                    number: number as u8,
+                    size: *size,
+                    is_xmm: false,
+                })
+        })
+    }
+
+    pub fn size(&self) -> ValueSize {
+        self.size
+    }
+
+    pub fn is_stack_pointer(&self) -> bool {
+        self.number == 4
+    }
+
+    fn low_bits(&self) -> u8 {
+        self.number & 0b111
+    }
+
+    fn is_extended(&self) -> bool {
+        self.number >= 8
+    }
+
+    /// `spl`, `bpl`, `sil` and `dil` can only be encoded with a REX prefix, without it they would
+    /// be `ah`, `ch`, `dh` and `bh`.
+    fn needs_rex(&self) -> bool {
+        self.size == ValueSize::Byte && (4..8).contains(&self.number)
+    }
+}
+
+const CL: Register = Register {
+    number: 1,
+    size: ValueSize::Byte,
+    is_xmm: false,
+};
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum FixupKind {
+    /// Absolute 64 bit address, e.g. of `mov rax, label`.
+    Absolute64,
+    /// Absolute 32 bit address, zero extended.
+    Absolute32,
+    /// Absolute 32 bit address, sign extended. Used for addresses in memory operands.
+    Absolute32Signed,
+    /// 32 bit address relative to the end of the instruction, used by `[rel label]`.
+    Relative32,
+    /// 32 bit target of a jump or call, relative to the end of the instruction.
+    Branch32,
+}
+
+impl FixupKind {
+    pub fn is_relative(&self) -> bool {
+        matches!(self, FixupKind::Relative32 | FixupKind::Branch32)
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            FixupKind::Absolute64 => 8,
+            _ => 4,
+        }
+    }
+}
+
+/// A field of an encoded instruction, that has to be filled in with the address of a symbol.
+/// The value of the field is `symbol + addend`, minus the address of the field itself for
+/// relative fixups.
+pub struct Fixup {
+    pub offset: usize,
+    pub symbol: String,
+    pub addend: i64,
+    pub kind: FixupKind,
+}
+
+pub struct Encoded {
+    pub bytes: Vec<u8>,
+    pub fixups: Vec<Fixup>,
+}
+
+struct Field {
+    value: i64,
+    size: usize,
+    fixup: Option<(String, FixupKind)>,
+}
+
+#[derive(Default)]
+struct Instruction {
+    /// Mandatory prefix of SSE instructions, which has to precede the REX prefix
+    mandatory_prefix: Option<u8>,
+    operand_size_override: bool,
+    rex_w: bool,
+    rex_r: bool,
+    rex_x: bool,
+    rex_b: bool,
+    force_rex: bool,
+
+    opcode: Vec<u8>,
+    reg_field: u8,
+    /// mod and r/m field of the ModR/M byte, if the instruction has one
+    rm: Option<(u8, u8)>,
+    sib: Option<u8>,
+
+    displacement: Option<Field>,
+    immediate: Option<Field>,
+}
+
+impl Instruction {
+    fn new(opcode: &[u8]) -> Self {
+        Instruction {
+            opcode: opcode.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn operand_size(&mut self, size: ValueSize) -> &mut Self {
+        match size {
+            ValueSize::Word => self.operand_size_override = true,
+            ValueSize::Qword => self.rex_w = true,
+            _ => {}
+        }
+        self
+    }
+
+    /// Adds the register to the lower bits of the last opcode byte, like in `push r64`.
+    fn opcode_register(&mut self, register: Register) -> &mut Self {
+        *self.opcode.last_mut().unwrap() += register.low_bits();
+        self.rex_b = register.is_extended();
+        self.force_rex |= register.needs_rex();
+        self
+    }
+
+    fn reg(&mut self, register: Register) -> &mut Self {
+        self.reg_field = register.low_bits();
+        self.rex_r = register.is_extended();
+        self.force_rex |= register.needs_rex();
+        self
+    }
+
+    fn extension(&mut self, extension: u8) -> &mut Self {
+        self.reg_field = extension;
+        self
+    }
+
+    fn rm(&mut self, operand: &Operand) -> Result<&mut Self, String> {
+        match operand {
+            Operand::Register(register) => {
+                self.rm = Some((0b11, register.low_bits()));
+                self.rex_b = register.is_extended();
+                self.force_rex |= register.needs_rex();
+            }
+            Operand::Memory(memory, _) => self.memory(memory)?,
+            Operand::Immediate(..) => return Err(INVALID_OPERANDS.to_string()),
+        }
+        Ok(self)
+    }
+
+    fn memory(&mut self, memory: &Memory) -> Result<(), String> {
+        if memory.rip_relative {
+            if memory.base.is_some() || memory.index.is_some() {
+                return Err("RIP relative addresses can't contain registers!".to_string());
+            }
+
+            self.rm = Some((0b00, 0b101));
+            self.displacement = Some(Field {
+                value: memory.displacement,
+                size: 4,
+                fixup: memory
+                    .symbol
+                    .clone()
+                    .map(|symbol| (symbol, FixupKind::Relative32)),
+            });
+            return Ok(());
+        }
+
+        for register in memory
+            .base
+            .iter()
+            .chain(memory.index.iter().map(|(r, _)| r))
+        {
+            if register.size != ValueSize::Qword || register.is_xmm {
+                return Err("Only 64 bit registers can be used in addresses!".to_string());
+            }
+        }
+
+        if memory.symbol.is_none() && i32::try_from(memory.displacement).is_err() {
+            return Err("Displacement doesn't fit into 32 bits!".to_string());
+        }
+
+        let displacement_32 = Field {
+            value: memory.displacement,
+            size: 4,
+            fixup: memory
+                .symbol
+                .clone()
+                .map(|symbol| (symbol, FixupKind::Absolute32Signed)),
+        };
+
+        let index = match memory.index {
+            Some((index, _)) if index.is_stack_pointer() => {
+                return Err("The stack pointer can't be used as index!".to_string());
+            }
+            Some((index, scale)) => {
+                let scale_bits = match scale {
+                    1 => 0,
+                    2 => 1,
+                    4 => 2,
+                    8 => 3,
+                    _ => return Err(format!("Invalid scale '{}'!", scale)),
+                };
+                self.rex_x = index.is_extended();
+                Some((index.low_bits(), scale_bits))
+            }
+            None => None,
+        };
+
+        match memory.base {
+            None => {
+                // without a base, r/m 101 would be RIP relative, so the SIB byte is used
+                let (index_bits, scale_bits) = index.unwrap_or((0b100, 0));
+                self.rm = Some((0b00, 0b100));
+                self.sib = Some(scale_bits << 6 | index_bits << 3 | 0b101);
+                self.displacement = Some(displacement_32);
+            }
+            Some(base) => {
+                self.rex_b = base.is_extended();
+
+                // rbp and r13 as base always need a displacement
+                let mode = if memory.symbol.is_some() {
+                    self.displacement = Some(displacement_32);
+                    0b10
+                } else if memory.displacement == 0 && base.low_bits() != 0b101 {
+                    0b00
+                } else if i8::try_from(memory.displacement).is_ok() {
+                    self.displacement = Some(Field {
+                        value: memory.displacement,
+                        size: 1,
+                        fixup: None,
+                    });
+                    0b01
+                } else {
+                    self.displacement = Some(displacement_32);
+                    0b10
+                };
+
+                // rsp and r12 as base always need a SIB byte
+                if index.is_some() || base.low_bits() == 0b100 {
+                    let (index_bits, scale_bits) = index.unwrap_or((0b100, 0));
+                    self.rm = Some((mode, 0b100));
+                    self.sib = Some(scale_bits << 6 | index_bits << 3 | base.low_bits());
+                } else {
+                    self.rm = Some((mode, base.low_bits()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn immediate(&mut self, immediate: &Immediate, size: usize, kind: FixupKind) -> &mut Self {
+        self.immediate = Some(Field {
+            value: immediate.value,
+            size,
+            fixup: immediate.symbol.clone().map(|symbol| (symbol, kind)),
+        });
+        self
+    }
+
+    fn finish(&mut self) -> Result<Encoded, String> {
+        let mut bytes: Vec<u8> = vec![];
+
+        bytes.extend(self.mandatory_prefix);
+        if self.operand_size_override {
+            bytes.push(0x66);
+        }
+
+        let rex = 0x40
+            | (self.rex_w as u8) << 3
+            | (self.rex_r as u8) << 2
+            | (self.rex_x as u8) << 1
+            | self.rex_b as u8;
+        if rex != 0x40 || self.force_rex {
+            bytes.push(rex);
+        }
+
+        bytes.extend_from_slice(&self.opcode);
+
+        if let Some((mode, rm)) = self.rm {
+            bytes.push(mode << 6 | self.reg_field << 3 | rm);
+        }
+        if let Some(sib) = self.sib {
+            bytes.push(sib);
+        }
+
+        let mut fixups: Vec<Fixup> = vec![];
+
+        for field in [self.displacement.take(), self.immediate.take()]
+            .into_iter()
+            .flatten()
+        {
+            match field.fixup {
+                Some((symbol, kind)) => {
+                    if field.size != kind.size_in_bytes() {
+                        return Err("Symbol doesn't fit into the operand!".to_string());
+                    }
+                    fixups.push(Fixup {
+                        offset: bytes.len(),
+                        symbol,
+                        addend: field.value,
+                        kind,
+                    });
+                    bytes.resize(bytes.len() + field.size, 0);
+                }
+                None => bytes.extend_from_slice(&field.value.to_le_bytes()[..field.size]),
+            }
+        }
+
+        // the processor adds relative fields to the address of the next instruction, but
+        // relocations are relative to the field itself
+        let len = bytes.len() as i64;
+        for fixup in fixups.iter_mut().filter(|fixup| fixup.kind.is_relative()) {
+            fixup.addend -= len - fixup.offset as i64;
+        }
+
+        Ok(Encoded { bytes, fixups })
+    }
+}
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+}
+
+fn size_of(operand: &Operand) -> Option<ValueSize> {
+    match operand {
+        Operand::Register(register) => Some(register.size),
+        Operand::Immediate(_, size) | Operand::Memory(_, size) => *size,
+    }
+}
+
+/// The size of an operation, all operands with a known size have to agree on it.
+fn common_size(operands: &[Operand]) -> Result<ValueSize, String> {
+    let mut common: Option<ValueSize> = None;
+
+    for size in operands.iter().filter_map(size_of) {
+        match common {
+            Some(common) if common != size => {
+                return Err("Mismatch in operand sizes!".to_string());
+            }
+            _ => common = Some(size),
+        }
+    }
+
+    common.ok_or_else(|| "Operation size not specified!".to_string())
+}
+
+fn fits_i8(immediate: &Immediate) -> bool {
+    immediate.symbol.is_none() && i8::try_from(immediate.value).is_ok()
+}
+
+/// Checks, that a numeric immediate fits into a field of the given size. Fields of 64 bit
+/// operations are sign extended, the others can also hold unsigned values.
+fn check_immediate(
+    immediate: &Immediate,
+    field_size: usize,
+    size: ValueSize,
+) -> Result<(), String> {
+    if immediate.symbol.is_some() || field_size == 8 {
+        return Ok(());
+    }
+
+    let bits = field_size as u32 * 8;
+    let min = -(1_i64 << (bits - 1));
+    let max = if size == ValueSize::Qword {
+        (1_i64 << (bits - 1)) - 1
+    } else {
+        (1_i64 << bits) - 1
+    };
+
+    if immediate.value < min || immediate.value > max {
+        return Err(format!(
+            "Immediate value {} doesn't fit into {} bits!",
+            immediate.value, bits
+        ));
+    }
+
+    Ok(())
+}
+
+/// Size of the immediate field for an operation of the given size. 64 bit operations use sign
+/// extended 32 bit immediates.
+fn immediate_field_size(size: ValueSize) -> usize {
+    match size {
+        ValueSize::Qword => 4,
+        _ => size.get_size_in_bytes() as usize,
+    }
+}
+
+fn branch_target(operand: &Operand) -> Option<&Immediate> {
+    match operand {
+        Operand::Immediate(immediate, None) if immediate.symbol.is_some() => Some(immediate),
+        _ => None,
+    }
+}
+
+/// Encodes a single instruction, the symbols it references are returned as fixups.
+pub fn encode(mnemonic: &str, operands: &[Operand]) -> Result<Encoded, String> {
+    let mnemonic = mnemonic.to_ascii_lowercase();
+    let mnemonic = mnemonic.as_str();
+
+    if mnemonic == "movq" {
+        return encode_movq(operands);
+    }
+    if mnemonic == "movdqu" || lookup(&SSE, mnemonic).is_some() {
+        return encode_sse(mnemonic, operands);
+    }
+
+    // xmm registers are only valid for SSE instructions, all other instructions would silently
+    // encode them as general purpose registers
+    let encoded = encode_general_purpose(mnemonic, operands)?;
+    if operands
+        .iter()
+        .any(|operand| matches!(operand, Operand::Register(register) if register.is_xmm))
+    {
+        return Err(INVALID_OPERANDS.to_string());
+    }
+    Ok(encoded)
+}
+
+/// Encodes a string instruction with the `rep` prefix.
+pub fn encode_repeated(mnemonic: &str, operands: &[Operand]) -> Result<Encoded, String> {
+    let mnemonic = mnemonic.to_ascii_lowercase();
+    if !REPEATABLE.contains(&mnemonic.as_str()) {
+        return Err(format!("Instruction '{}' can't be repeated!", mnemonic));
+    }
+
+    let mut encoded = encode(&mnemonic, operands)?;
+    encoded.bytes.insert(0, 0xf3);
+    Ok(encoded)
+}
+
+fn encode_general_purpose(mnemonic: &str, operands: &[Operand]) -> Result<Encoded, String> {
+    if let Some(opcode) = lookup(&WITHOUT_OPERANDS, mnemonic) {
+        return match operands {
+            [] => Instruction::new(opcode).finish(),
+            [Operand::Immediate(immediate, _)] if mnemonic == "ret" => {
+                check_immediate(immediate, 2, ValueSize::Word)?;
+                Instruction::new(&[0xc2])
+                    .immediate(immediate, 2, FixupKind::Absolute32)
+                    .finish()
+            }
+            _ => Err(INVALID_OPERANDS.to_string()),
+        };
+    }
+
+    if let Some(extension) = lookup(&ARITHMETIC, mnemonic) {
+        return encode_arithmetic(extension, operands);
+    }
+    if let Some(extension) = lookup(&SHIFTS, mnemonic) {
+        return encode_shift(extension, operands);
+    }
+    if let Some(extension) = lookup(&UNARY, mnemonic) {
+        return encode_unary(extension, operands);
+    }
+
+    match mnemonic {
+        "mov" => return encode_mov(operands),
+        "lea" => return encode_lea(operands),
+        "test" => return encode_test(operands),
+        "imul" => return encode_imul(operands),
+        "inc" => return encode_inc_dec(0, operands),
+        "dec" => return encode_inc_dec(1, operands),
+        "push" => return encode_push(operands),
+        "pop" => return encode_pop(operands),
+        "jmp" => return encode_jmp_call(0xe9, 4, operands),
+        "call" => return encode_jmp_call(0xe8, 2, operands),
+        "movzx" => return encode_extend(0xb6, operands),
+        "movsx" => return encode_extend(0xbe, operands),
+        "movsxd" => return encode_movsxd(operands),
+        "int" => {
+            return match operands {
+                [Operand::Immediate(immediate, _)] if immediate.symbol.is_none() => {
+                    check_immediate(immediate, 1, ValueSize::Byte)?;
+                    Instruction::new(&[0xcd])
+                        .immediate(immediate, 1, FixupKind::Absolute32)
+                        .finish()
+                }
+                _ => Err(INVALID_OPERANDS.to_string()),
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(condition) = mnemonic
+        .strip_prefix("cmov")
+        .and_then(|c| lookup(&CONDITIONS, c))
+    {
+        return encode_cmov(condition, operands);
+    }
+    if let Some(condition) = mnemonic
+        .strip_prefix("set")
+        .and_then(|c| lookup(&CONDITIONS, c))
+    {
+        return match operands {
+            [operand] if size_of(operand).unwrap_or(ValueSize::Byte) == ValueSize::Byte => {
+                Instruction::new(&[0x0f, 0x90 + condition])
+                    .rm(operand)?
+                    .finish()
+            }
+            _ => Err(INVALID_OPERANDS.to_string()),
+        };
+    }
+    if let Some(condition) = mnemonic
+        .strip_prefix('j')
+        .and_then(|c| lookup(&CONDITIONS, c))
+    {
+        return match operands.first().and_then(branch_target) {
+            Some(target) if operands.len() == 1 => Instruction::new(&[0x0f, 0x80 + condition])
+                .immediate(target, 4, FixupKind::Branch32)
+                .finish(),
+            _ => Err(INVALID_OPERANDS.to_string()),
+        };
+    }
+
+    Err(format!("Unsupported instruction '{}'!", mnemonic))
+}
+
+/// Encodes `movq` between an xmm register and a 64 bit register.
+fn encode_movq(operands: &[Operand]) -> Result<Encoded, String> {
+    let (opcode, xmm, other) = match operands {
+        [Operand::Register(xmm), other @ Operand::Register(register)]
+            if xmm.is_xmm && !register.is_xmm && register.size == ValueSize::Qword =>
+        {
+            (0x6e, *xmm, other)
+        }
+        [other @ Operand::Register(register), Operand::Register(xmm)]
+            if xmm.is_xmm && !register.is_xmm && register.size == ValueSize::Qword =>
+        {
+            (0x7e, *xmm, other)
+        }
+        _ => return Err(INVALID_OPERANDS.to_string()),
+    };
+
+    let mut instruction = Instruction::new(&[0x0f, opcode]);
+    instruction.operand_size_override = true;
+    instruction.rex_w = true;
+    instruction.reg(xmm).rm(other)?.finish()
+}
+
+/// Encodes `movdqu` and the SSE instructions, whose destination is an xmm register.
+fn encode_sse(mnemonic: &str, operands: &[Operand]) -> Result<Encoded, String> {
+    let is_xmm_or_memory = |operand: &Operand| match operand {
+        Operand::Register(register) => register.is_xmm,
+        Operand::Memory(..) => true,
+        Operand::Immediate(..) => false,
+    };
+
+    let (prefix, opcode, xmm, other) = match (mnemonic, operands) {
+        ("movdqu", [Operand::Register(xmm), other]) if xmm.is_xmm && is_xmm_or_memory(other) => {
+            (0xf3, 0x6f, *xmm, other)
+        }
+        ("movdqu", [other @ Operand::Memory(..), Operand::Register(xmm)]) if xmm.is_xmm => {
+            (0xf3, 0x7f, *xmm, other)
+        }
+        (_, [Operand::Register(xmm), other]) if xmm.is_xmm && is_xmm_or_memory(other) => {
+            match lookup(&SSE, mnemonic) {
+                Some(opcode) => (0x66, opcode, *xmm, other),
+                None => return Err(INVALID_OPERANDS.to_string()),
+            }
+        }
+        _ => return Err(INVALID_OPERANDS.to_string()),
+    };
+
+    let mut instruction = Instruction::new(&[0x0f, opcode]);
+    instruction.mandatory_prefix = Some(prefix);
+    instruction.reg(xmm).rm(other)?.finish()
+}
+
+fn encode_mov(operands: &[Operand]) -> Result<Encoded, String> {
+    let [destination, source] = operands else {
+        return Err(INVALID_OPERANDS.to_string());
+    };
+    let size = common_size(operands)?;
+    let is_byte = size == ValueSize::Byte;
+
+    match (destination, source) {
+        (Operand::Register(_) | Operand::Memory(..), Operand::Register(source)) => {
+            Instruction::new(&[if is_byte { 0x88 } else { 0x89 }])
+                .operand_size(size)
+                .reg(*source)
+                .rm(destination)?
+                .finish()
+        }
+        (Operand::Register(destination), Operand::Memory(..)) => {
+            Instruction::new(&[if is_byte { 0x8a } else { 0x8b }])
+                .operand_size(size)
+                .reg(*destination)
+                .rm(source)?
+                .finish()
+        }
+        (Operand::Register(destination), Operand::Immediate(immediate, _)) => match size {
+            ValueSize::Qword if immediate.symbol.is_some() => Instruction::new(&[0xb8])
+                .operand_size(size)
+                .opcode_register(*destination)
+                .immediate(immediate, 8, FixupKind::Absolute64)
+                .finish(),
+            // writing the lower 32 bits clears the upper ones, which saves the REX prefix
+            ValueSize::Qword if u32::try_from(immediate.value).is_ok() => Instruction::new(&[0xb8])
+                .opcode_register(*destination)
+                .immediate(immediate, 4, FixupKind::Absolute32)
+                .finish(),
+            ValueSize::Qword if i32::try_from(immediate.value).is_ok() => Instruction::new(&[0xc7])
+                .operand_size(size)
+                .extension(0)
+                .rm(&Operand::Register(*destination))?
+                .immediate(immediate, 4, FixupKind::Absolute32Signed)
+                .finish(),
+            ValueSize::Qword => Instruction::new(&[0xb8])
+                .operand_size(size)
+                .opcode_register(*destination)
+                .immediate(immediate, 8, FixupKind::Absolute64)
+                .finish(),
+            _ => {
+                let field_size = size.get_size_in_bytes() as usize;
+                check_immediate(immediate, field_size, size)?;
+
+                Instruction::new(&[if is_byte { 0xb0 } else { 0xb8 }])
+                    .operand_size(size)
+                    .opcode_register(*destination)
+                    .immediate(immediate, field_size, FixupKind::Absolute32)
+                    .finish()
+            }
+        },
+        (Operand::Memory(..), Operand::Immediate(immediate, _)) => {
+            let field_size = immediate_field_size(size);
+            check_immediate(immediate, field_size, size)?;
+
+            Instruction::new(&[if is_byte { 0xc6 } else { 0xc7 }])
+                .operand_size(size)
+                .extension(0)
+                .rm(destination)?
+                .immediate(immediate, field_size, FixupKind::Absolute32Signed)
+                .finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_lea(operands: &[Operand]) -> Result<Encoded, String> {
+    match operands {
+        [Operand::Register(destination), source @ Operand::Memory(..)]
+            if destination.size != ValueSize::Byte =>
+        {
+            Instruction::new(&[0x8d])
+                .operand_size(destination.size)
+                .reg(*destination)
+                .rm(source)?
+                .finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_arithmetic(extension: u8, operands: &[Operand]) -> Result<Encoded, String> {
+    let [destination, source] = operands else {
+        return Err(INVALID_OPERANDS.to_string());
+    };
+    let size = common_size(operands)?;
+    let is_byte = size == ValueSize::Byte;
+    let base = extension << 3;
+
+    match (destination, source) {
+        (Operand::Register(_) | Operand::Memory(..), Operand::Register(source)) => {
+            Instruction::new(&[base + if is_byte { 0x00 } else { 0x01 }])
+                .operand_size(size)
+                .reg(*source)
+                .rm(destination)?
+                .finish()
+        }
+        (Operand::Register(destination), Operand::Memory(..)) => {
+            Instruction::new(&[base + if is_byte { 0x02 } else { 0x03 }])
+                .operand_size(size)
+                .reg(*destination)
+                .rm(source)?
+                .finish()
+        }
+        (Operand::Register(_) | Operand::Memory(..), Operand::Immediate(immediate, _)) => {
+            let (opcode, field_size) = if is_byte {
+                (0x80, 1)
+            } else if fits_i8(immediate) {
+                (0x83, 1)
+            } else {
+                (0x81, immediate_field_size(size))
+            };
+            check_immediate(immediate, field_size, size)?;
+
+            Instruction::new(&[opcode])
+                .operand_size(size)
+                .extension(extension)
+                .rm(destination)?
+                .immediate(immediate, field_size, FixupKind::Absolute32Signed)
+                .finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_test(operands: &[Operand]) -> Result<Encoded, String> {
+    let [left, right] = operands else {
+        return Err(INVALID_OPERANDS.to_string());
+    };
+    let size = common_size(operands)?;
+    let is_byte = size == ValueSize::Byte;
+
+    match (left, right) {
+        (rm @ (Operand::Register(_) | Operand::Memory(..)), Operand::Register(register)) => {
+            Instruction::new(&[if is_byte { 0x84 } else { 0x85 }])
+                .operand_size(size)
+                .reg(*register)
+                .rm(rm)?
+                .finish()
+        }
+        (Operand::Register(register), rm @ Operand::Memory(..)) => {
+            Instruction::new(&[if is_byte { 0x84 } else { 0x85 }])
+                .operand_size(size)
+                .reg(*register)
+                .rm(rm)?
+                .finish()
+        }
+        (Operand::Register(_) | Operand::Memory(..), Operand::Immediate(immediate, _)) => {
+            let field_size = immediate_field_size(size);
+            check_immediate(immediate, field_size, size)?;
+
+            Instruction::new(&[if is_byte { 0xf6 } else { 0xf7 }])
+                .operand_size(size)
+                .extension(0)
+                .rm(left)?
+                .immediate(immediate, field_size, FixupKind::Absolute32Signed)
+                .finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_shift(extension: u8, operands: &[Operand]) -> Result<Encoded, String> {
+    let [destination, count] = operands else {
+        return Err(INVALID_OPERANDS.to_string());
+    };
+    let size = size_of(destination).ok_or_else(|| "Operation size not specified!".to_string())?;
+    let is_byte = size == ValueSize::Byte;
+
+    match count {
+        Operand::Register(register) if *register == CL => {
+            Instruction::new(&[if is_byte { 0xd2 } else { 0xd3 }])
+                .operand_size(size)
+                .extension(extension)
+                .rm(destination)?
+                .finish()
+        }
+        Operand::Immediate(immediate, _) if immediate.symbol.is_none() => {
+            check_immediate(immediate, 1, ValueSize::Byte)?;
+
+            if immediate.value == 1 {
+                Instruction::new(&[if is_byte { 0xd0 } else { 0xd1 }])
+                    .operand_size(size)
+                    .extension(extension)
+                    .rm(destination)?
+                    .finish()
+            } else {
+                Instruction::new(&[if is_byte { 0xc0 } else { 0xc1 }])
+                    .operand_size(size)
+                    .extension(extension)
+                    .rm(destination)?
+                    .immediate(immediate, 1, FixupKind::Absolute32)
+                    .finish()
+            }
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_unary(extension: u8, operands: &[Operand]) -> Result<Encoded, String> {
+    let [operand @ (Operand::Register(_) | Operand::Memory(..))] = operands else {
+        return Err(INVALID_OPERANDS.to_string());
+    };
+    let size = common_size(operands)?;
+
+    Instruction::new(&[if size == ValueSize::Byte { 0xf6 } else { 0xf7 }])
+        .operand_size(size)
+        .extension(extension)
+        .rm(operand)?
+        .finish()
+}
+
+fn encode_inc_dec(extension: u8, operands: &[Operand]) -> Result<Encoded, String> {
+    let [operand @ (Operand::Register(_) | Operand::Memory(..))] = operands else {
+        return Err(INVALID_OPERANDS.to_string());
+    };
+    let size = common_size(operands)?;
+
+    Instruction::new(&[if size == ValueSize::Byte { 0xfe } else { 0xff }])
+        .operand_size(size)
+        .extension(extension)
+        .rm(operand)?
+        .finish()
+}
+
+fn encode_imul(operands: &[Operand]) -> Result<Encoded, String> {
+    match operands {
+        [_] => encode_unary(5, operands),
+        [Operand::Register(destination), source @ (Operand::Register(_) | Operand::Memory(..))] => {
+            let size = common_size(operands)?;
+            if size == ValueSize::Byte {
+                return Err(INVALID_OPERANDS.to_string());
+            }
+
+            Instruction::new(&[0x0f, 0xaf])
+                .operand_size(size)
+                .reg(*destination)
+                .rm(source)?
+                .finish()
+        }
+        [destination @ Operand::Register(_), immediate @ Operand::Immediate(..)] => {
+            encode_imul(&[destination.clone(), destination.clone(), immediate.clone()])
+        }
+        [Operand::Register(destination), source @ (Operand::Register(_) | Operand::Memory(..)), Operand::Immediate(immediate, _)] =>
+        {
+            let size = common_size(&operands[..2])?;
+            if size == ValueSize::Byte {
+                return Err(INVALID_OPERANDS.to_string());
+            }
+
+            let (opcode, field_size) = if fits_i8(immediate) {
+                (0x6b, 1)
+            } else {
+                (0x69, immediate_field_size(size))
+            };
+            check_immediate(immediate, field_size, size)?;
+
+            Instruction::new(&[opcode])
+                .operand_size(size)
+                .reg(*destination)
+                .rm(source)?
+                .immediate(immediate, field_size, FixupKind::Absolute32Signed)
+                .finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_push(operands: &[Operand]) -> Result<Encoded, String> {
+    match operands {
+        [Operand::Register(register)] if register.size == ValueSize::Qword => {
+            Instruction::new(&[0x50])
+                .opcode_register(*register)
+                .finish()
+        }
+        [operand @ Operand::Memory(_, None | Some(ValueSize::Qword))] => {
+            Instruction::new(&[0xff]).extension(6).rm(operand)?.finish()
+        }
+        [Operand::Immediate(immediate, _)] => {
+            if fits_i8(immediate) {
+                Instruction::new(&[0x6a])
+                    .immediate(immediate, 1, FixupKind::Absolute32Signed)
+                    .finish()
+            } else {
+                check_immediate(immediate, 4, ValueSize::Qword)?;
+                Instruction::new(&[0x68])
+                    .immediate(immediate, 4, FixupKind::Absolute32Signed)
+                    .finish()
+            }
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_pop(operands: &[Operand]) -> Result<Encoded, String> {
+    match operands {
+        [Operand::Register(register)] if register.size == ValueSize::Qword => {
+            Instruction::new(&[0x58])
+                .opcode_register(*register)
+                .finish()
+        }
+        [operand @ Operand::Memory(_, None | Some(ValueSize::Qword))] => {
+            Instruction::new(&[0x8f]).extension(0).rm(operand)?.finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_jmp_call(opcode: u8, extension: u8, operands: &[Operand]) -> Result<Encoded, String> {
+    if let Some(target) = operands.first().and_then(branch_target) {
+        if operands.len() == 1 {
+            return Instruction::new(&[opcode])
+                .immediate(target, 4, FixupKind::Branch32)
+                .finish();
+        }
+    }
+
+    match operands {
+        [operand @ Operand::Register(register)] if register.size == ValueSize::Qword => {
+            Instruction::new(&[0xff])
+                .extension(extension)
+                .rm(operand)?
+                .finish()
+        }
+        [operand @ Operand::Memory(_, None | Some(ValueSize::Qword))] => Instruction::new(&[0xff])
+            .extension(extension)
+            .rm(operand)?
+            .finish(),
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_cmov(condition: u8, operands: &[Operand]) -> Result<Encoded, String> {
+    match operands {
+        [Operand::Register(destination), source @ (Operand::Register(_) | Operand::Memory(..))] => {
+            let size = common_size(operands)?;
+            if size == ValueSize::Byte {
+                return Err(INVALID_OPERANDS.to_string());
+            }
+
+            Instruction::new(&[0x0f, 0x40 + condition])
+                .operand_size(size)
+                .reg(*destination)
+                .rm(source)?
+                .finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+/// Encodes `movzx` and `movsx`, which take the opcode of their byte sized variant.
+fn encode_extend(opcode: u8, operands: &[Operand]) -> Result<Encoded, String> {
+    match operands {
+        [Operand::Register(destination), source @ (Operand::Register(_) | Operand::Memory(..))] => {
+            let opcode = match size_of(source) {
+                Some(ValueSize::Byte) => opcode,
+                Some(ValueSize::Word) if destination.size != ValueSize::Word => opcode + 1,
+                Some(_) => return Err(INVALID_OPERANDS.to_string()),
+                None => return Err("Operation size not specified!".to_string()),
+            };
+            if destination.size == ValueSize::Byte {
+                return Err(INVALID_OPERANDS.to_string());
+            }
+
+            Instruction::new(&[0x0f, opcode])
+                .operand_size(destination.size)
+                .reg(*destination)
+                .rm(source)?
+                .finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
+
+fn encode_movsxd(operands: &[Operand]) -> Result<Encoded, String> {
+    match operands {
+        [Operand::Register(destination), source @ (Operand::Register(_) | Operand::Memory(..))]
+            if destination.size == ValueSize::Qword
+                && size_of(source).unwrap_or(ValueSize::Dword) == ValueSize::Dword =>
+        {
+            Instruction::new(&[0x63])
+                .operand_size(ValueSize::Qword)
+                .reg(*destination)
+                .rm(source)?
+                .finish()
+        }
+        _ => Err(INVALID_OPERANDS.to_string()),
+    }
+}
@@ -0,0 +1,611 @@
+use std::collections::HashMap;
+use std::path::Path;
+#[cfg(test)]
+use std::path::PathBuf;
+
+use crate::assembler::elf::{ObjectFile, Relocation, RelocationTarget, Section, Symbol};
+use crate::assembler::encoder::{Encoded, Fixup, FixupKind};
+use crate::assembler::operand::{
+    parse_count, parse_data, parse_operand, split_list, strip_comment,
+};
+use crate::error;
+use crate::error::Error;
+use crate::position::Position;
+
+pub(crate) mod elf;
+mod encoder;
+mod operand;
+
+const DATA_DIRECTIVES: [(&str, usize); 4] = [("db", 1), ("dw", 2), ("dd", 4), ("dq", 8)];
+const RESERVE_DIRECTIVES: [(&str, usize); 4] = [("resb", 1), ("resw", 2), ("resd", 4), ("resq", 8)];
+
+fn directive_size(directives: &[(&str, usize)], word: &str) -> Option<usize> {
+    directives
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(word))
+        .map(|(_, size)| *size)
+}
+
+fn is_data_directive(word: &str) -> bool {
+    directive_size(&DATA_DIRECTIVES, word).is_some()
+        || directive_size(&RESERVE_DIRECTIVES, word).is_some()
+}
+
+fn split_first_word(text: &str) -> (&str, &str) {
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (text, ""),
+    }
+}
+
+/// Splits off the label at the start of a line, like `main:`.
+fn split_label(line: &str) -> Option<(&str, &str)> {
+    let (label, rest) = line.split_once(':')?;
+
+    let is_label = label.starts_with(|c: char| c.is_ascii_alphabetic() || "._?".contains(c))
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.$?@#~".contains(c));
+
+    is_label.then_some((label, rest))
+}
+
+/// Assembles the NASM source the compiler generates into an ELF64 object file for x86-64.
+struct Assembler {
+    section: Section,
+    data: Vec<u8>,
+    bss_size: u64,
+    text: Vec<u8>,
+    rodata: Vec<u8>,
+
+    labels: HashMap<String, (Section, u64)>,
+    label_order: Vec<String>,
+    /// The last label not starting with a dot, local labels like `.L1` belong to it
+    scope: String,
+
+    globals: Vec<(String, Position)>,
+    externs: Vec<String>,
+
+    /// The fixups and the sections they are applied to
+    fixups: Vec<(Section, Fixup, Position)>,
+    default_rel: bool,
+}
+
+impl Assembler {
+    fn new() -> Self {
+        Assembler {
+            section: Section::Text,
+            data: vec![],
+            bss_size: 0,
+            text: vec![],
+            rodata: vec![],
+            labels: HashMap::new(),
+            label_order: vec![],
+            scope: String::new(),
+            globals: vec![],
+            externs: vec![],
+            fixups: vec![],
+            default_rel: false,
+        }
+    }
+
+    fn offset(&self) -> u64 {
+        match self.section {
+            Section::Bss => self.bss_size,
+            section => self.contents(section).len() as u64,
+        }
+    }
+
+    fn contents(&self, section: Section) -> &Vec<u8> {
+        match section {
+            Section::Data => &self.data,
+            Section::Text => &self.text,
+            Section::Rodata => &self.rodata,
+            Section::Bss => unreachable!("the .bss section has no contents"),
+        }
+    }
+
+    fn contents_mut(&mut self, section: Section) -> &mut Vec<u8> {
+        match section {
+            Section::Data => &mut self.data,
+            Section::Text => &mut self.text,
+            Section::Rodata => &mut self.rodata,
+            Section::Bss => unreachable!("the .bss section has no contents"),
+        }
+    }
+
+    fn full_label_name(&self, name: &str) -> String {
+        if name.starts_with('.') && !name.starts_with("..") {
+            format!("{}{}", self.scope, name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn define_label(&mut self, name: &str) -> Result<(), String> {
+        if !name.starts_with('.') {
+            self.scope = name.to_string();
+        }
+        let name = self.full_label_name(name);
+
+        if self.labels.contains_key(&name) {
+            return Err(format!("Label '{}' is already defined!", name));
+        }
+
+        self.labels
+            .insert(name.clone(), (self.section, self.offset()));
+        self.label_order.push(name);
+        Ok(())
+    }
+
+    fn line(&mut self, line: &str, pos: &Position) -> Result<(), String> {
+        let mut line = strip_comment(line).trim();
+
+        if let Some((label, rest)) = split_label(line) {
+            self.define_label(label)?;
+            line = rest.trim();
+        }
+
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        let (first, rest) = split_first_word(line);
+
+        match first.to_ascii_lowercase().as_str() {
+            "section" | "segment" => {
+                let (name, _) = split_first_word(rest);
+                self.section = match name {
+                    ".data" => Section::Data,
+                    ".bss" => Section::Bss,
+                    ".text" => Section::Text,
+                    ".rodata" => Section::Rodata,
+                    _ => return Err(format!("Unsupported section '{}'!", name)),
+                };
+            }
+            "global" | "extern" => {
+                for name in split_list(rest) {
+                    // NASM allows a type after the name, like `main:function`
+                    let name = name.split(':').next().unwrap().trim().to_string();
+
+                    if first.eq_ignore_ascii_case("global") {
+                        self.globals.push((name, pos.clone()));
+                    } else if !self.externs.contains(&name) {
+                        self.externs.push(name);
+                    }
+                }
+            }
+            "bits" => {
+                if rest.trim() != "64" {
+                    return Err("Only 64 bit code is supported!".to_string());
+                }
+            }
+            "default" => match rest.trim().to_ascii_lowercase().as_str() {
+                "rel" => self.default_rel = true,
+                "abs" => self.default_rel = false,
+                _ => return Err(format!("Invalid default '{}'!", rest.trim())),
+            },
+            _ if is_data_directive(first) => self.data_directive(first, rest, pos)?,
+            _ => {
+                // NASM also allows labels without a colon in front of data
+                let (second, data) = split_first_word(rest);
+                if is_data_directive(second) {
+                    self.define_label(first)?;
+                    self.data_directive(second, data, pos)?;
+                } else {
+                    self.instruction(first, rest, pos)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn data_directive(
+        &mut self,
+        directive: &str,
+        rest: &str,
+        pos: &Position,
+    ) -> Result<(), String> {
+        if let Some(size) = directive_size(&RESERVE_DIRECTIVES, directive) {
+            let len = parse_count(rest)? * size as u64;
+            match self.section {
+                Section::Bss => self.bss_size += len,
+                section => {
+                    let contents = self.contents_mut(section);
+                    contents.resize(contents.len() + len as usize, 0);
+                }
+            }
+            return Ok(());
+        }
+
+        if self.section == Section::Bss {
+            return Err("Initialized data can't be placed in the '.bss' section!".to_string());
+        }
+
+        let encoded = parse_data(rest, directive_size(&DATA_DIRECTIVES, directive).unwrap())?;
+        self.append(encoded, pos);
+
+        Ok(())
+    }
+
+    /// Appends encoded bytes to the current section and records their fixups.
+    fn append(&mut self, encoded: Encoded, pos: &Position) {
+        let section = self.section;
+        let start = self.contents(section).len();
+
+        for mut fixup in encoded.fixups {
+            fixup.offset += start;
+            fixup.symbol = self.full_label_name(&fixup.symbol);
+            self.fixups.push((section, fixup, pos.clone()));
+        }
+        self.contents_mut(section).extend_from_slice(&encoded.bytes);
+    }
+
+    fn instruction(&mut self, mnemonic: &str, rest: &str, pos: &Position) -> Result<(), String> {
+        if self.section != Section::Text {
+            return Err("Instructions can only be placed in the '.text' section!".to_string());
+        }
+
+        // `rep` prefixes the string instruction following it
+        let repeated = mnemonic.eq_ignore_ascii_case("rep");
+        let (mnemonic, rest) = if repeated {
+            split_first_word(rest)
+        } else {
+            (mnemonic, rest)
+        };
+
+        let mut operands = split_list(rest)
+            .iter()
+            .map(|operand| parse_operand(operand, self.default_rel))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        for operand in operands.iter_mut() {
+            if let Some(symbol) = operand.symbol_mut() {
+                *symbol = self.full_label_name(symbol);
+            }
+        }
+
+        let encoded = if repeated {
+            encoder::encode_repeated(mnemonic, &operands)?
+        } else {
+            encoder::encode(mnemonic, &operands)?
+        };
+
+        self.append(encoded, pos);
+
+        Ok(())
+    }
+
+    /// Resolves the relative references from the `.text` section to labels in it and creates
+    /// relocations for the other ones.
+    fn finish(mut self) -> Result<ObjectFile, Error> {
+        for (name, pos) in &self.globals {
+            if !self.labels.contains_key(name) {
+                return Err(error::assembler_error(
+                    pos.clone(),
+                    pos.clone(),
+                    format!("Symbol '{}' is declared global, but not defined!", name).as_str(),
+                ));
+            }
+        }
+
+        let mut relocations: Vec<Relocation> = vec![];
+        let mut used_externs: Vec<String> = vec![];
+
+        for (fixup_section, fixup, pos) in &self.fixups {
+            let offset = fixup.offset as u64;
+
+            match self.labels.get(&fixup.symbol) {
+                Some((Section::Text, target))
+                    if *fixup_section == Section::Text && fixup.kind.is_relative() =>
+                {
+                    let value = *target as i64 + fixup.addend - offset as i64;
+                    let value = i32::try_from(value).map_err(|_| {
+                        error::assembler_error(
+                            pos.clone(),
+                            pos.clone(),
+                            format!("Label '{}' is out of range!", fixup.symbol).as_str(),
+                        )
+                    })?;
+                    self.text[fixup.offset..fixup.offset + 4].copy_from_slice(&value.to_le_bytes());
+                }
+                Some((section, target)) => relocations.push(Relocation {
+                    section: *fixup_section,
+                    offset,
+                    target: RelocationTarget::Section(*section),
+                    kind: match fixup.kind {
+                        FixupKind::Branch32 => elf::R_X86_64_PC32,
+                        kind => relocation_kind(kind),
+                    },
+                    addend: fixup.addend + *target as i64,
+                }),
+                None if self.externs.contains(&fixup.symbol) => {
+                    if !used_externs.contains(&fixup.symbol) {
+                        used_externs.push(fixup.symbol.clone());
+                    }
+
+                    relocations.push(Relocation {
+                        section: *fixup_section,
+                        offset,
+                        target: RelocationTarget::Symbol(fixup.symbol.clone()),
+                        kind: relocation_kind(fixup.kind),
+                        addend: fixup.addend,
+                    });
+                }
+                None => {
+                    return Err(error::assembler_error(
+                        pos.clone(),
+                        pos.clone(),
+                        format!("Symbol '{}' is not defined!", fixup.symbol).as_str(),
+                    ));
+                }
+            }
+        }
+
+        let mut symbols: Vec<Symbol> = vec![];
+        for name in &self.label_order {
+            let (section, value) = self.labels[name];
+            symbols.push(Symbol {
+                name: name.clone(),
+                section: Some(section),
+                value,
+                global: self.globals.iter().any(|(global, _)| global == name),
+            });
+        }
+        for name in used_externs {
+            symbols.push(Symbol {
+                name,
+                section: None,
+                value: 0,
+                global: true,
+            });
+        }
+
+        Ok(ObjectFile {
+            data: self.data,
+            bss_size: self.bss_size,
+            text: self.text,
+            rodata: self.rodata,
+            symbols,
+            relocations,
+        })
+    }
+}
+
+fn relocation_kind(kind: FixupKind) -> u32 {
+    match kind {
+        FixupKind::Absolute64 => elf::R_X86_64_64,
+        FixupKind::Absolute32 => elf::R_X86_64_32,
+        FixupKind::Absolute32Signed => elf::R_X86_64_32S,
+        FixupKind::Relative32 => elf::R_X86_64_PC32,
+        FixupKind::Branch32 => elf::R_X86_64_PLT32,
+    }
+}
+
+/// Assembles the NASM source generated by the compiler into an ELF64 relocatable object file, so no
+/// external assembler is needed. Besides the instructions the compiler emits, common instructions
+/// used in inline assembly are supported.
+/// The given file is only used for the positions of errors.
+pub fn assemble(source: &str, file: &Path) -> Result<Vec<u8>, Error> {
+    let mut assembler = Assembler::new();
+    let mut pos = Position::new(file.to_path_buf());
+
+    for line in source.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        let pos_start = pos.clone();
+        for c in content.chars() {
+            pos.advance(&c);
+        }
+
+        if let Err(details) = assembler.line(content, &pos_start) {
+            return Err(error::assembler_error(
+                pos_start,
+                pos.clone(),
+                details.as_str(),
+            ));
+        }
+
+        for c in line[content.len()..].chars() {
+            pos.advance(&c);
+        }
+    }
+
+    Ok(assembler.finish()?.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assemble_lines(source: &str) -> ObjectFile {
+        let mut assembler = Assembler::new();
+        let pos = Position::new(PathBuf::from("test.asm"));
+
+        for line in source.lines() {
+            assembler.line(line, &pos).unwrap();
+        }
+
+        match assembler.finish() {
+            Ok(object) => object,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn test_encode_instructions() {
+        let instructions: [(&str, &[u8]); 33] = [
+            ("mov     QWORD [rbp - (16)], rbx", &[0x48, 0x89, 0x5d, 0xf0]),
+            ("mov     r12b, BYTE [rbp-1]", &[0x44, 0x8a, 0x65, 0xff]),
+            (
+                "mov     DWORD [rsp], -1",
+                &[0xc7, 0x04, 0x24, 0xff, 0xff, 0xff, 0xff],
+            ),
+            ("mov     r13w, WORD 513", &[0x66, 0x41, 0xbd, 0x01, 0x02]),
+            (
+                "mov     rbx, QWORD 4886718345",
+                &[0x48, 0xbb, 0x89, 0x67, 0x45, 0x23, 0x01, 0x00, 0x00, 0x00],
+            ),
+            (
+                "mov     rbx, -2",
+                &[0x48, 0xc7, 0xc3, 0xfe, 0xff, 0xff, 0xff],
+            ),
+            ("lea     r10, [rsp+32]", &[0x4c, 0x8d, 0x54, 0x24, 0x20]),
+            (
+                "lea     rax, [rax + r12*8 + 300]",
+                &[0x4a, 0x8d, 0x84, 0xe0, 0x2c, 0x01, 0x00, 0x00],
+            ),
+            ("add     rbx, r10", &[0x4c, 0x01, 0xd3]),
+            ("sub     rsp, 16", &[0x48, 0x83, 0xec, 0x10]),
+            ("cmp     eax, 0", &[0x83, 0xf8, 0x00]),
+            (
+                "cmp     r15, 1000",
+                &[0x49, 0x81, 0xff, 0xe8, 0x03, 0x00, 0x00],
+            ),
+            ("imul    r11", &[0x49, 0xf7, 0xeb]),
+            ("idiv    rbx", &[0x48, 0xf7, 0xfb]),
+            ("sar     r14, cl", &[0x49, 0xd3, 0xfe]),
+            ("setne   dil", &[0x40, 0x0f, 0x95, 0xc7]),
+            ("inc     r12", &[0x49, 0xff, 0xc4]),
+            ("push    r15", &[0x41, 0x57]),
+            ("pop     rbp", &[0x5d]),
+            ("add     dl, '0'", &[0x80, 0xc2, 0x30]),
+            ("syscall", &[0x0f, 0x05]),
+            ("movq    xmm1, rax", &[0x66, 0x48, 0x0f, 0x6e, 0xc8]),
+            ("movq    rax, xmm0", &[0x66, 0x48, 0x0f, 0x7e, 0xc0]),
+            ("movq    xmm9, r11", &[0x66, 0x4d, 0x0f, 0x6e, 0xcb]),
+            ("rep stosb", &[0xf3, 0xaa]),
+            ("rep movsb", &[0xf3, 0xa4]),
+            ("std", &[0xfd]),
+            ("cld", &[0xfc]),
+            (
+                "movdqu  xmm3, [r12 + 16]",
+                &[0xf3, 0x41, 0x0f, 0x6f, 0x5c, 0x24, 0x10],
+            ),
+            ("movdqu  [rbp-48], xmm7", &[0xf3, 0x0f, 0x7f, 0x7d, 0xd0]),
+            ("movdqu  xmm9, [rbx]", &[0xf3, 0x44, 0x0f, 0x6f, 0x0b]),
+            ("pxor    xmm0, xmm0", &[0x66, 0x0f, 0xef, 0xc0]),
+            ("punpcklqdq xmm0, xmm0", &[0x66, 0x0f, 0x6c, 0xc0]),
+        ];
+
+        for (instruction, bytes) in instructions {
+            assert_eq!(assemble_lines(instruction).text, bytes, "{}", instruction);
+        }
+    }
+
+    #[test]
+    fn test_labels_and_relocations() {
+        let object = assemble_lines(
+            "section .data
+\tS0: db  `hi\\n`, 0
+section .bss
+\tST_x  RESB 8
+section .text
+global main
+\textern puts
+main:
+\tjmp     .L1
+.L1:
+\tmov     rbx, [ST_x]
+\tmov     rsi, QWORD S0
+\tcall    puts
+\tret",
+        );
+
+        assert_eq!(object.data, b"hi\n\0");
+        assert_eq!(object.bss_size, 8);
+
+        // the jump to the local label is resolved directly
+        assert_eq!(object.text[..5], [0xe9, 0, 0, 0, 0]);
+
+        let relocations: Vec<(u64, u32, i64)> = object
+            .relocations
+            .iter()
+            .map(|r| (r.offset, r.kind, r.addend))
+            .collect();
+        assert_eq!(
+            relocations,
+            vec![
+                (9, elf::R_X86_64_32S, 0),
+                (15, elf::R_X86_64_64, 0),
+                (24, elf::R_X86_64_PLT32, -4)
+            ]
+        );
+
+        let symbols: Vec<(&str, bool)> = object
+            .symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.global))
+            .collect();
+        assert_eq!(
+            symbols,
+            vec![
+                ("S0", false),
+                ("ST_x", false),
+                ("main", true),
+                ("main.L1", false),
+                ("puts", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_data_relocations() {
+        let object = assemble_lines(
+            "section .data
+\tS0: db  `hi`, 0
+\tST_x: dq  S0 + 1
+section .rodata
+\tST_y: dw  1, 2
+\tdq  ST_x
+section .text
+\tmov     rax, [ST_y]",
+        );
+
+        assert_eq!(object.data[..11], *b"hi\0\0\0\0\0\0\0\0\0");
+        assert_eq!(object.rodata[..4], [1, 0, 2, 0]);
+
+        let relocations: Vec<(bool, u64, u32, i64)> = object
+            .relocations
+            .iter()
+            .map(|r| (r.section == Section::Data, r.offset, r.kind, r.addend))
+            .collect();
+        assert_eq!(
+            relocations,
+            vec![
+                (true, 3, elf::R_X86_64_64, 1),
+                (false, 4, elf::R_X86_64_64, 3),
+                (false, 4, elf::R_X86_64_32S, 0)
+            ]
+        );
+
+        let err = assemble("section .bss\n\tdq  1\n", Path::new("test.asm")).unwrap_err();
+        assert_eq!(
+            err.details(),
+            "Initialized data can't be placed in the '.bss' section!"
+        );
+        let err = assemble("section .data\n\tdd  S0\n", Path::new("test.asm")).unwrap_err();
+        assert_eq!(err.details(), "Addresses can only be stored in 'dq'!");
+    }
+
+    #[test]
+    fn test_errors() {
+        let err =
+            assemble("section .text\n\tjmp     nowhere\n", Path::new("test.asm")).unwrap_err();
+        assert_eq!(err.error_name(), "AssemblerError");
+        assert_eq!(*err.pos_start().line(), 1);
+
+        let err = assemble("\tcvtsi2sd xmm0, rax\n", Path::new("test.asm")).unwrap_err();
+        assert_eq!(err.details(), "Unsupported instruction 'cvtsi2sd'!");
+
+        let err = assemble("\tmov     xmm0, rax\n", Path::new("test.asm")).unwrap_err();
+        assert_eq!(err.details(), "Invalid combination of opcode and operands!");
+
+        let err = assemble("\tmov     [rbx], 1\n", Path::new("test.asm")).unwrap_err();
+        assert_eq!(err.details(), "Operation size not specified!");
+
+        let err = assemble("\trep ret\n", Path::new("test.asm")).unwrap_err();
+        assert_eq!(err.details(), "Instruction 'ret' can't be repeated!");
+    }
+}
@@ -0,0 +1,517 @@
+use crate::assembler::encoder::{Encoded, Fixup, FixupKind, Register};
+use crate::utils::unescape;
+use crate::values::value_size::ValueSize;
+
+#[derive(Clone)]
+pub struct Immediate {
+    pub value: i64,
+    pub symbol: Option<String>,
+}
+
+/// A memory operand of the form `[base + index * scale + symbol + displacement]`.
+#[derive(Clone)]
+pub struct Memory {
+    pub base: Option<Register>,
+    pub index: Option<(Register, u8)>,
+    pub displacement: i64,
+    pub symbol: Option<String>,
+    pub rip_relative: bool,
+}
+
+/// An operand of an instruction. Immediates and memory operands can be given an explicit size,
+/// like in `mov QWORD [rsp], -1`.
+#[derive(Clone)]
+pub enum Operand {
+    Register(Register),
+    Immediate(Immediate, Option<ValueSize>),
+    Memory(Memory, Option<ValueSize>),
+}
+
+impl Operand {
+    pub fn symbol_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Operand::Register(_) => None,
+            Operand::Immediate(immediate, _) => immediate.symbol.as_mut(),
+            Operand::Memory(memory, _) => memory.symbol.as_mut(),
+        }
+    }
+}
+
+enum ExpressionToken {
+    Number(i64),
+    Name(String),
+    Plus,
+    Minus,
+    Mul,
+    Lparen,
+    Rparen,
+}
+
+/// A sum of registers multiplied by constants, at most one symbol and a constant.
+struct Expression {
+    constant: i64,
+    symbol: Option<String>,
+    registers: Vec<(Register, i64)>,
+}
+
+impl Expression {
+    fn constant(constant: i64) -> Self {
+        Expression {
+            constant,
+            symbol: None,
+            registers: vec![],
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        self.symbol.is_none() && self.registers.is_empty()
+    }
+
+    fn add(mut self, other: Expression) -> Result<Expression, String> {
+        if self.symbol.is_some() && other.symbol.is_some() {
+            return Err("Expressions can only contain a single symbol!".to_string());
+        }
+
+        self.constant = self.constant.wrapping_add(other.constant);
+        self.symbol = self.symbol.or(other.symbol);
+        self.registers.extend(other.registers);
+        Ok(self)
+    }
+
+    fn scale(mut self, factor: i64) -> Result<Expression, String> {
+        if self.symbol.is_some() {
+            return Err("Symbols can only be added to expressions!".to_string());
+        }
+
+        self.constant = self.constant.wrapping_mul(factor);
+        for (_, register_factor) in self.registers.iter_mut() {
+            *register_factor = register_factor.wrapping_mul(factor);
+        }
+        Ok(self)
+    }
+
+    fn mul(self, other: Expression) -> Result<Expression, String> {
+        if other.is_constant() {
+            self.scale(other.constant)
+        } else if self.is_constant() {
+            other.scale(self.constant)
+        } else {
+            Err("Only constants can be multiplied!".to_string())
+        }
+    }
+}
+
+const SIZES: [(&str, ValueSize); 4] = [
+    ("BYTE", ValueSize::Byte),
+    ("WORD", ValueSize::Word),
+    ("DWORD", ValueSize::Dword),
+    ("QWORD", ValueSize::Qword),
+];
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$' | '?' | '@')
+}
+
+fn is_quote(c: char) -> bool {
+    matches!(c, '\'' | '"' | '`')
+}
+
+/// Returns the length of the quoted string at the start of the given text, including the quotes.
+/// Backquoted strings can contain escaped quotes.
+fn quoted_len(text: &str) -> Option<usize> {
+    let quote = text.chars().next().filter(|c| is_quote(*c))?;
+
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if quote == '`' && c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Some(i + 1);
+        }
+    }
+
+    None
+}
+
+/// The bytes of a quoted string, only backquoted strings have escape sequences.
+fn string_bytes(quoted: &str) -> Vec<u8> {
+    let content = &quoted[1..quoted.len() - 1];
+
+    if quoted.starts_with('`') {
+        unescape(content)
+    } else {
+        content.as_bytes().to_vec()
+    }
+}
+
+/// Finds the byte positions of the characters matching the predicate, that are not inside of
+/// quotes or brackets.
+fn top_level_positions(text: &str, predicate: impl Fn(char) -> bool) -> Vec<usize> {
+    let mut positions: Vec<usize> = vec![];
+    let mut depth = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+
+        if is_quote(c) {
+            match quoted_len(rest) {
+                Some(len) => i += len,
+                None => break,
+            }
+            continue;
+        }
+
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            _ if depth == 0 && predicate(c) => positions.push(i),
+            _ => {}
+        }
+        i += c.len_utf8();
+    }
+
+    positions
+}
+
+/// Removes the comment of a line, if it has one.
+pub fn strip_comment(line: &str) -> &str {
+    match top_level_positions(line, |c| c == ';').first() {
+        Some(position) => &line[..*position],
+        None => line,
+    }
+}
+
+/// Splits a list of operands or data items at its commas.
+pub fn split_list(text: &str) -> Vec<&str> {
+    if text.trim().is_empty() {
+        return vec![];
+    }
+
+    let mut items: Vec<&str> = vec![];
+    let mut start = 0;
+    for position in top_level_positions(text, |c| c == ',') {
+        items.push(text[start..position].trim());
+        start = position + 1;
+    }
+    items.push(text[start..].trim());
+
+    items
+}
+
+fn parse_number(text: &str) -> Result<i64, String> {
+    let cleaned = text.replace('_', "").to_ascii_lowercase();
+
+    let parsed = if let Some(hex) = cleaned.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16)
+    } else if let Some(binary) = cleaned.strip_prefix("0b") {
+        u64::from_str_radix(binary, 2)
+    } else if let Some(octal) = cleaned
+        .strip_prefix("0o")
+        .or_else(|| cleaned.strip_prefix("0q"))
+    {
+        u64::from_str_radix(octal, 8)
+    } else if let Some(hex) = cleaned.strip_suffix('h') {
+        u64::from_str_radix(hex, 16)
+    } else {
+        cleaned.parse::<u64>()
+    };
+
+    // negative numbers are written with a unary minus, so values above i64::MAX wrap around
+    parsed
+        .map(|value| value as i64)
+        .map_err(|_| format!("Invalid number '{}'!", text))
+}
+
+fn tokenize(text: &str) -> Result<Vec<ExpressionToken>, String> {
+    let mut tokens: Vec<ExpressionToken> = vec![];
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+
+        if c.is_whitespace() {
+            i += c.len_utf8();
+            continue;
+        }
+
+        if is_quote(c) {
+            let len = quoted_len(rest).ok_or_else(|| "Unterminated string!".to_string())?;
+            let bytes = string_bytes(&rest[..len]);
+            if bytes.len() > 8 {
+                return Err("Character constant is too long!".to_string());
+            }
+
+            let mut value = [0_u8; 8];
+            value[..bytes.len()].copy_from_slice(&bytes);
+            tokens.push(ExpressionToken::Number(i64::from_le_bytes(value)));
+            i += len;
+            continue;
+        }
+
+        if is_name_char(c) {
+            let len = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+            let word = &rest[..len];
+
+            tokens.push(if c.is_ascii_digit() {
+                ExpressionToken::Number(parse_number(word)?)
+            } else {
+                ExpressionToken::Name(word.to_string())
+            });
+            i += len;
+            continue;
+        }
+
+        tokens.push(match c {
+            '+' => ExpressionToken::Plus,
+            '-' => ExpressionToken::Minus,
+            '*' => ExpressionToken::Mul,
+            '(' => ExpressionToken::Lparen,
+            ')' => ExpressionToken::Rparen,
+            _ => return Err(format!("Unexpected character '{}' in expression!", c)),
+        });
+        i += c.len_utf8();
+    }
+
+    Ok(tokens)
+}
+
+struct ExpressionParser {
+    tokens: Vec<ExpressionToken>,
+    index: usize,
+}
+
+impl ExpressionParser {
+    fn peek(&self) -> Option<&ExpressionToken> {
+        self.tokens.get(self.index)
+    }
+
+    fn expression(&mut self) -> Result<Expression, String> {
+        let mut left = self.term()?;
+
+        loop {
+            match self.peek() {
+                Some(ExpressionToken::Plus) => {
+                    self.index += 1;
+                    left = left.add(self.term()?)?;
+                }
+                Some(ExpressionToken::Minus) => {
+                    self.index += 1;
+                    left = left.add(self.term()?.scale(-1)?)?;
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Expression, String> {
+        let mut left = self.factor()?;
+
+        while let Some(ExpressionToken::Mul) = self.peek() {
+            self.index += 1;
+            left = left.mul(self.factor()?)?;
+        }
+
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expression, String> {
+        let token = self
+            .tokens
+            .get(self.index)
+            .ok_or_else(|| "Expected an expression!".to_string())?;
+        self.index += 1;
+
+        match token {
+            ExpressionToken::Number(value) => Ok(Expression::constant(*value)),
+            ExpressionToken::Name(name) => Ok(match Register::from_name(name) {
+                Some(register) => Expression {
+                    constant: 0,
+                    symbol: None,
+                    registers: vec![(register, 1)],
+                },
+                None => Expression {
+                    constant: 0,
+                    symbol: Some(name.clone()),
+                    registers: vec![],
+                },
+            }),
+            ExpressionToken::Plus => self.factor(),
+            ExpressionToken::Minus => self.factor()?.scale(-1),
+            ExpressionToken::Lparen => {
+                let expression = self.expression()?;
+                match self.peek() {
+                    Some(ExpressionToken::Rparen) => {
+                        self.index += 1;
+                        Ok(expression)
+                    }
+                    _ => Err("Expected ')'!".to_string()),
+                }
+            }
+            _ => Err("Expected an expression!".to_string()),
+        }
+    }
+}
+
+fn parse_expression(text: &str) -> Result<Expression, String> {
+    let mut parser = ExpressionParser {
+        tokens: tokenize(text)?,
+        index: 0,
+    };
+
+    let expression = parser.expression()?;
+    if parser.index != parser.tokens.len() {
+        return Err(format!("Invalid expression '{}'!", text.trim()));
+    }
+
+    Ok(expression)
+}
+
+/// Splits off a leading size keyword like `QWORD`.
+fn split_size(text: &str) -> (Option<ValueSize>, &str) {
+    let len = text
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(text.len());
+    let (word, rest) = text.split_at(len);
+
+    match SIZES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(word))
+    {
+        Some((_, size)) if rest.starts_with(|c: char| c.is_whitespace() || c == '[') => {
+            (Some(*size), rest.trim_start())
+        }
+        _ => (None, text),
+    }
+}
+
+fn parse_memory(text: &str, default_rel: bool) -> Result<Memory, String> {
+    let text = text.trim();
+
+    let (rip_relative, text) = match text.split_once(char::is_whitespace) {
+        Some((keyword, rest)) if keyword.eq_ignore_ascii_case("rel") => (Some(true), rest),
+        Some((keyword, rest)) if keyword.eq_ignore_ascii_case("abs") => (Some(false), rest),
+        _ => (None, text),
+    };
+
+    let expression = parse_expression(text)?;
+
+    let mut base: Option<Register> = None;
+    let mut index: Option<(Register, u8)> = None;
+
+    for (register, factor) in &expression.registers {
+        match factor {
+            1 if base.is_none() => base = Some(*register),
+            1 | 2 | 4 | 8 if index.is_none() => index = Some((*register, *factor as u8)),
+            _ => return Err("Invalid effective address!".to_string()),
+        }
+    }
+
+    // the stack pointer can't be an index, but the operands of an addition can be swapped
+    if let (Some(b), Some((i, 1))) = (base, index) {
+        if i.is_stack_pointer() {
+            base = Some(i);
+            index = Some((b, 1));
+        }
+    }
+
+    Ok(Memory {
+        rip_relative: rip_relative.unwrap_or(
+            default_rel && expression.registers.is_empty() && expression.symbol.is_some(),
+        ),
+        base,
+        index,
+        displacement: expression.constant,
+        symbol: expression.symbol,
+    })
+}
+
+/// Parses an operand of an instruction. With `default_rel` memory operands, that only consist of a
+/// symbol, are RIP relative.
+pub fn parse_operand(text: &str, default_rel: bool) -> Result<Operand, String> {
+    let (size, text) = split_size(text.trim());
+
+    if let Some(inner) = text.strip_prefix('[') {
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or_else(|| "Expected ']'!".to_string())?;
+        return Ok(Operand::Memory(parse_memory(inner, default_rel)?, size));
+    }
+
+    let expression = parse_expression(text)?;
+
+    match expression.registers.as_slice() {
+        [] => Ok(Operand::Immediate(
+            Immediate {
+                value: expression.constant,
+                symbol: expression.symbol,
+            },
+            size,
+        )),
+        [(register, 1)]
+            if expression.constant == 0
+                && expression.symbol.is_none()
+                && size.is_none_or(|size| size == register.size()) =>
+        {
+            Ok(Operand::Register(*register))
+        }
+        _ => Err(format!("Invalid operand '{}'!", text)),
+    }
+}
+
+/// Parses the items of a data directive like `db`, each one taking `size` bytes. Strings take as
+/// many elements as needed to hold them, addresses of labels are returned as fixups.
+pub fn parse_data(text: &str, size: usize) -> Result<Encoded, String> {
+    let mut bytes: Vec<u8> = vec![];
+    let mut fixups: Vec<Fixup> = vec![];
+
+    for item in split_list(text) {
+        if quoted_len(item) == Some(item.len()) {
+            let string = string_bytes(item);
+            let padded_len = string.len().div_ceil(size) * size;
+
+            bytes.extend_from_slice(&string);
+            bytes.resize(bytes.len() + padded_len - string.len(), 0);
+            continue;
+        }
+
+        let expression = parse_expression(item)?;
+        if !expression.registers.is_empty() {
+            return Err("Only constants and addresses are supported as data!".to_string());
+        }
+
+        if let Some(symbol) = expression.symbol {
+            if size != 8 {
+                return Err("Addresses can only be stored in 'dq'!".to_string());
+            }
+
+            fixups.push(Fixup {
+                offset: bytes.len(),
+                symbol,
+                addend: expression.constant,
+                kind: FixupKind::Absolute64,
+            });
+            bytes.extend_from_slice(&[0; 8]);
+            continue;
+        }
+
+        bytes.extend_from_slice(&expression.constant.to_le_bytes()[..size]);
+    }
+
+    Ok(Encoded { bytes, fixups })
+}
+
+/// Parses the count of a reserve directive like `resb`.
+pub fn parse_count(text: &str) -> Result<u64, String> {
+    let expression = parse_expression(text)?;
+
+    if !expression.is_constant() || expression.constant < 0 {
+        return Err(format!("Invalid count '{}'!", text.trim()));
+    }
+
+    Ok(expression.constant as u64)
+}
@@ -0,0 +1,22 @@
+use clap::Parser;
+use std::process::exit;
+
+#[derive(Parser)]
+#[clap(author, version, about = "Language server for umber", long_about = None)]
+struct LspArgs {
+    /// Include paths used to resolve imports
+    #[clap(short, long, value_parser)]
+    include: Option<Vec<String>>,
+}
+
+fn main() {
+    let args = LspArgs::parse();
+
+    match umber_lang::lsp::run(args.include.unwrap_or_default()) {
+        Ok(exit_code) => exit(exit_code),
+        Err(err) => {
+            eprintln!("umber-lsp: {}", err);
+            exit(1);
+        }
+    }
+}
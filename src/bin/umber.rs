@@ -1,18 +1,35 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
-use std::process::{exit, Command};
+use std::process::{exit, Command, ExitStatus};
 use std::time::Instant;
 use std::{env, fs};
+use umber_lang::compiler::AssemblySyntax;
 use umber_lang::error;
 use umber_lang::error::Error;
+use umber_lang::nodes::Node;
 use umber_lang::position::Position;
+use umber_lang::semantics::RuntimeCheck;
 use umber_lang::syscall::TargetObjectType;
 
 #[derive(Subcommand)]
 enum Subcommands {
     /// Compile a file to an executable binary.
     Com(SubCompile),
+    /// Compile a file and run the resulting binary.
+    Run(SubRun),
+    /// Compile the tests of a file and run them.
+    Test(SubTest),
+    /// Format files in the canonical style.
+    Fmt(SubFormat),
+    /// Start an interactive interpreter.
+    Repl(SubRepl),
+    /// Interpret a file without compiling it.
+    Eval(SubEval),
+    /// Generate Umber declarations from a C header.
+    Bindgen(SubBindgen),
 }
 
 #[derive(Args)]
@@ -21,13 +38,94 @@ struct SubCompile {
     #[clap(value_parser)]
     name: Vec<String>,
 
-    /// Options for the assembler.
+    #[clap(flatten)]
+    build: BuildArgs,
+
+    /// Dont add entry point '_start'
+    #[clap(short, long, action)]
+    no_entry: bool,
+
+    /// Only compile and assemble, dont link
+    #[clap(short, long, action)]
+    compile_only: bool,
+
+    /// Only write the given output to the build directory, instead of building a binary
+    #[clap(long, value_enum)]
+    emit: Option<Emit>,
+
+    /// The kind of output to build. Libraries don't have an entry point.
+    #[clap(long, value_enum, default_value_t = CrateType::Bin)]
+    crate_type: CrateType,
+}
+
+#[derive(Args)]
+struct SubRun {
+    /// The file to compile and run.
+    #[clap(value_parser)]
+    name: String,
+
+    #[clap(flatten)]
+    build: BuildArgs,
+
+    /// Report how long compiling and running took
+    #[clap(long, action)]
+    time: bool,
+
+    /// Arguments for the program, given after '--'
+    #[clap(last = true, value_parser)]
+    args: Vec<String>,
+}
+
+#[derive(Args)]
+struct SubBindgen {
+    /// The C header to generate the declarations from.
+    #[clap(value_parser)]
+    name: String,
+
+    /// The file to write the declarations to, they are printed if it isn't given
     #[clap(short, long, value_parser)]
-    asm: Option<String>,
+    output: Option<String>,
+}
+
+#[derive(Args)]
+struct SubTest {
+    /// The file containing the tests.
+    #[clap(value_parser)]
+    name: String,
+
+    #[clap(flatten)]
+    build: BuildArgs,
 
-    /// Options for the linker.
+    /// Report how long compiling and running the tests took
+    #[clap(long, action)]
+    time: bool,
+}
+
+#[derive(Args)]
+struct BuildArgs {
+    /// The code generator to use
+    #[clap(long, value_enum, default_value_t = Backend::Asm)]
+    backend: Backend,
+
+    /// Options for the assembler. Only used with '--assembler=nasm'.
     #[clap(short, long, value_parser)]
-    linker: Option<String>,
+    asm: Option<String>,
+
+    /// The assembler to use
+    #[clap(long, value_enum, default_value_t = Assembler::Nasm)]
+    assembler: Assembler,
+
+    /// The syntax of the generated x86-64 assembly
+    #[clap(long, value_enum, default_value_t = AssemblySyntax::Nasm)]
+    asm_syntax: AssemblySyntax,
+
+    /// Options for the linker. Only used with '--linker=system'.
+    #[clap(short = 'l', long, value_parser)]
+    linker_args: Option<String>,
+
+    /// The linker to use
+    #[clap(long, value_enum, default_value_t = Linker::System)]
+    linker: Linker,
 
     /// Include paths seperated by ';'
     #[clap(short, long, value_parser)]
@@ -37,17 +135,103 @@ struct SubCompile {
     #[clap(short, long, action)]
     verbose: bool,
 
-    /// Dont add entry point '_start'
-    #[clap(short, long, action)]
-    no_entry: bool,
-
-    /// Only compile and assemble, dont link
-    #[clap(short, long, action)]
-    compile_only: bool,
-
     /// Object format to compile to
     #[clap(short, long, value_enum, default_value_t=TargetObjectType::X86_64)]
     target: TargetObjectType,
+
+    /// Checks to add to the program, seperated by ','. Failed checks exit with code 101.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    checks: Vec<RuntimeCheck>,
+}
+
+#[derive(Args)]
+struct SubFormat {
+    /// The files to format.
+    #[clap(value_parser)]
+    name: Vec<String>,
+
+    /// Dont write the files, only list the ones that are not formatted and fail if there are any
+    #[clap(long, action)]
+    check: bool,
+}
+
+#[derive(Args)]
+struct SubRepl {
+    /// Include paths seperated by ';'
+    #[clap(short, long, value_parser)]
+    include: Option<Vec<String>>,
+}
+
+#[derive(Args)]
+struct SubEval {
+    /// The file to interpret.
+    #[clap(value_parser)]
+    name: String,
+
+    /// Include paths seperated by ';'
+    #[clap(short, long, value_parser)]
+    include: Option<Vec<String>>,
+
+    /// Checks to add to the program, seperated by ','. Failed checks exit with code 101.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    checks: Vec<RuntimeCheck>,
+
+    /// Arguments for the program, given after '--'
+    #[clap(last = true, value_parser)]
+    args: Vec<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum Assembler {
+    /// Run 'nasm', or GNU 'as' for '--asm-syntax=gas' and the 'aarch64-linux' target
+    Nasm,
+    /// Use the assembler built into the compiler, only supports the 'x86-64' target
+    Builtin,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum Linker {
+    /// Run 'ld', needed to link against libraries like libc
+    System,
+    /// Use the linker built into the compiler, only supports freestanding 'x86-64' programs
+    Builtin,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum Backend {
+    /// Generate NASM assembly
+    Asm,
+    /// Generate C and compile it with the C compiler in '$CC' (default 'cc'), the assembler and
+    /// linker options are not used
+    C,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum Emit {
+    /// Textual LLVM IR ('.ll'), for 'llc' or 'clang'
+    Llvm,
+    /// A C header ('.h') with the prototypes, structures and statics of the file
+    CHeader,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum CrateType {
+    /// An executable
+    Bin,
+    /// A static library ('lib<name>.a'), created with 'ar'
+    Staticlib,
+    /// A shared library ('lib<name>.so') exporting the 'pub' functions, only for 'x86-64'
+    Cdylib,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum EntryPoint {
+    /// `_start` calls `main`
+    Main,
+    /// `_start` runs all `test` functions
+    Tests,
+    /// no `_start` is added
+    None,
 }
 
 #[derive(Parser)]
@@ -58,19 +242,23 @@ struct BinaryArgs {
     command: Subcommands,
 }
 
+/// Compiles the given files into `build_output` and returns the paths of the linked binaries.
 fn compile(
     files: Vec<String>,
-    include: Option<Vec<String>>,
-    assembler_options: Option<String>,
-    linker_options: Option<String>,
-    verbose: bool,
-    no_entry: bool,
+    build_output: &Path,
+    options: &BuildArgs,
+    entry: EntryPoint,
     compile_only: bool,
-    arch: TargetObjectType,
-) -> Result<(), Error> {
-    let now = Instant::now();
+    emit: Option<Emit>,
+    crate_type: CrateType,
+) -> Result<Vec<PathBuf>, Error> {
+    let include = &options.include;
+    let assembler_options = &options.asm;
+    let linker_options = &options.linker_args;
+    let verbose = options.verbose;
+    let arch = options.target;
 
-    let build_output = env::current_dir().unwrap().join("build");
+    let mut binaries: Vec<PathBuf> = vec![];
 
     for file in files {
         if verbose {
@@ -82,9 +270,21 @@ fn compile(
 
         // let build_output = file.parent().unwrap().join("build");
 
-        let asm_path = build_output.join(format!("{}.asm", file_stem));
+        // GNU as is used for aarch64 and GAS syntax
+        let gnu_as = matches!(arch, TargetObjectType::Aarch64Linux)
+            || options.asm_syntax == AssemblySyntax::Gas;
+        let asm_extension = if gnu_as { "s" } else { "asm" };
+        let asm_path = build_output.join(format!("{}.{}", file_stem, asm_extension));
         let obj_path = build_output.join(format!("{}.{}", file_stem, arch.object_file_extension()));
-        let bin_path = build_output.join(file_stem);
+        if crate_type != CrateType::Bin && emit.is_none() {
+            check_library_options(file, options, crate_type)?;
+        }
+
+        let bin_path = match crate_type {
+            CrateType::Bin => build_output.join(file_stem),
+            CrateType::Staticlib => build_output.join(format!("lib{}.a", file_stem)),
+            CrateType::Cdylib => build_output.join(format!("lib{}.so", file_stem)),
+        };
 
         let file_read_res = fs::read_to_string(&file);
         if let Err(file_err) = file_read_res {
@@ -134,7 +334,7 @@ fn compile(
         if verbose {
             print!("Validating...")
         }
-        let mut validator = umber_lang::semantics::Validator::new();
+        let mut validator = umber_lang::semantics::Validator::new_with_checks(&options.checks);
         let validation_res = validator.validate(ast_root);
 
         if let Some(error) = validation_res.error() {
@@ -147,26 +347,78 @@ fn compile(
             println!("Done")
         }
 
+        if emit == Some(Emit::Llvm) {
+            emit_llvm(file, ast_root, build_output, entry, options)?;
+            continue;
+        }
+
+        if emit == Some(Emit::CHeader) {
+            emit_c_header(file, ast_root, build_output, options)?;
+            continue;
+        }
+
+        if options.backend == Backend::C {
+            let tests = validator.tests();
+            build_c(
+                file,
+                ast_root,
+                tests,
+                build_output,
+                entry,
+                compile_only,
+                options,
+            )?;
+
+            if !compile_only {
+                binaries.push(bin_path);
+            }
+            continue;
+        }
+
         if verbose {
             print!("Generating assembly...")
         }
-        let mut compiler = umber_lang::compiler::Compiler::new(arch);
-        let asm = compiler.compile_to_str(ast_root, no_entry, arch);
+        let asm = if matches!(arch, TargetObjectType::Aarch64Linux) {
+            let mut compiler = umber_lang::aarch64_compiler::Aarch64Compiler::new(arch);
+            match entry {
+                EntryPoint::Tests => {
+                    compiler.compile_tests_to_str(ast_root, validator.tests(), arch)?
+                }
+                _ => compiler.compile_to_str(ast_root, entry == EntryPoint::None, arch)?,
+            }
+        } else {
+            let mut compiler = umber_lang::compiler::Compiler::with_options(
+                arch,
+                options.asm_syntax,
+                // C compilers create position independent executables by default, so static
+                // libraries need position independent code as well
+                crate_type != CrateType::Bin,
+            );
+            let asm = match entry {
+                EntryPoint::Tests => {
+                    compiler.compile_tests_to_str(ast_root, validator.tests(), arch)
+                }
+                _ => compiler.compile_to_str(ast_root, entry == EntryPoint::None, arch),
+            };
 
-        if let Err(fmt_error) = asm {
-            return Err(error::io_error(
-                Position::empty(),
-                Position::empty(),
-                format!("Could not format assembly: {}", fmt_error).as_str(),
-            ));
-        }
+            match asm {
+                Ok(asm) => asm,
+                Err(fmt_error) => {
+                    return Err(error::io_error(
+                        Position::empty(),
+                        Position::empty(),
+                        format!("Could not format assembly: {}", fmt_error).as_str(),
+                    ));
+                }
+            }
+        };
 
         if verbose {
             println!("Done")
         }
 
         if !build_output.exists() || !build_output.is_dir() {
-            if let Err(fs_error) = fs::create_dir(&build_output) {
+            if let Err(fs_error) = fs::create_dir_all(build_output) {
                 return Err(error::io_error(
                     Position::new(file.to_path_buf()),
                     Position::new(file.to_path_buf()),
@@ -175,7 +427,7 @@ fn compile(
             }
         }
 
-        if let Err(fs_error) = fs::write(&asm_path, asm.unwrap()) {
+        if let Err(fs_error) = fs::write(&asm_path, &asm) {
             return Err(error::io_error(
                 Position::new(file.to_path_buf()),
                 Position::new(file.to_path_buf()),
@@ -187,83 +439,69 @@ fn compile(
             print!("Compiling assembly...")
         }
 
-        let mut assembler_cmd = Command::new("nasm");
-        assembler_cmd.args([
-            "-f",
-            arch.object_format(),
-            "-o",
-            obj_path.to_str().unwrap(),
-            asm_path.to_str().unwrap(),
-        ]);
-
-        if let Some(ao) = assembler_options.as_ref() {
-            assembler_cmd.args(ao.split(' '));
-        }
-
-        let assembler_cmd_output = assembler_cmd.output();
-
-        if let Err(nasm_err) = assembler_cmd_output {
-            return Err(error::io_error(
-                Position::new(file.to_path_buf()),
-                Position::new(file.to_path_buf()),
-                format!("Failed to execute 'nasm'-command: {}", nasm_err).as_str(),
-            ));
-        }
-
-        if !assembler_cmd_output.as_ref().unwrap().status.success() {
-            return Err(error::io_error_with_parent(
-                Position::new(file.to_path_buf()),
-                Position::new(file.to_path_buf()),
-                "Assembling (NASM) failed with non-zero exit code",
-                error::io_error(
+        if options.assembler == Assembler::Builtin {
+            if options.asm_syntax != AssemblySyntax::Nasm {
+                return Err(error::io_error(
                     Position::new(file.to_path_buf()),
                     Position::new(file.to_path_buf()),
-                    format!(
-                        "\n\"{}\"",
-                        String::from_utf8_lossy(&*assembler_cmd_output.unwrap().stderr)
-                    )
-                    .as_str(),
-                ),
-            ));
-        }
+                    "The builtin assembler only supports '--asm-syntax=nasm'",
+                ));
+            }
 
-        if verbose {
-            println!("Done")
-        }
+            assemble_builtin(file, &asm, &asm_path, &obj_path, arch)?;
+        } else {
+            let assembler_name = match arch {
+                TargetObjectType::Aarch64Linux => gnu_tool("as"),
+                _ if gnu_as => String::from("as"),
+                _ => String::from("nasm"),
+            };
 
-        if !compile_only {
-            if verbose {
-                print!("Linking...")
+            let mut assembler_cmd = Command::new(&assembler_name);
+            if gnu_as {
+                assembler_cmd.args(["-o", obj_path.to_str().unwrap(), asm_path.to_str().unwrap()]);
+            } else {
+                assembler_cmd.args([
+                    "-f",
+                    arch.object_format(),
+                    "-o",
+                    obj_path.to_str().unwrap(),
+                    asm_path.to_str().unwrap(),
+                ]);
             }
 
-            let mut linker_cmd = Command::new("ld");
-            linker_cmd.args(["-o", bin_path.to_str().unwrap(), obj_path.to_str().unwrap()]);
-
-            if let Some(lo) = linker_options.as_ref() {
-                linker_cmd.args(lo.split(' '));
+            if let Some(ao) = assembler_options.as_ref() {
+                assembler_cmd.args(ao.split(' '));
             }
 
-            let linker_cmd_output = linker_cmd.output();
+            let assembler_cmd_output = assembler_cmd.output();
 
-            if let Err(linker_err) = linker_cmd_output {
+            if let Err(assembler_err) = assembler_cmd_output {
                 return Err(error::io_error(
                     Position::new(file.to_path_buf()),
                     Position::new(file.to_path_buf()),
-                    format!("Failed to run 'ld'-command: {}", linker_err).as_str(),
+                    format!(
+                        "Failed to execute '{}'-command: {}",
+                        assembler_name, assembler_err
+                    )
+                    .as_str(),
                 ));
             }
 
-            if !linker_cmd_output.as_ref().unwrap().status.success() {
+            if !assembler_cmd_output.as_ref().unwrap().status.success() {
                 return Err(error::io_error_with_parent(
                     Position::new(file.to_path_buf()),
                     Position::new(file.to_path_buf()),
-                    "Linking (ld) failed with non-zero exit code",
+                    format!(
+                        "Assembling ({}) failed with non-zero exit code",
+                        assembler_name
+                    )
+                    .as_str(),
                     error::io_error(
                         Position::new(file.to_path_buf()),
                         Position::new(file.to_path_buf()),
                         format!(
                             "\n\"{}\"",
-                            String::from_utf8_lossy(&*linker_cmd_output.unwrap().stderr)
+                            String::from_utf8_lossy(&*assembler_cmd_output.unwrap().stderr)
                         )
                         .as_str(),
                     ),
@@ -274,9 +512,627 @@ fn compile(
         if verbose {
             println!("Done")
         }
+
+        if !compile_only {
+            if verbose {
+                print!("Linking...")
+            }
+
+            if crate_type == CrateType::Staticlib {
+                archive(file, &obj_path, &bin_path)?;
+            } else if options.linker == Linker::Builtin {
+                link_builtin(file, &obj_path, &bin_path, arch)?;
+            } else {
+                let linker_name = match arch {
+                    TargetObjectType::Aarch64Linux => gnu_tool("ld"),
+                    _ => String::from("ld"),
+                };
+
+                let mut linker_cmd = Command::new(&linker_name);
+                if crate_type == CrateType::Cdylib {
+                    linker_cmd.arg("-shared");
+                }
+                linker_cmd.args(["-o", bin_path.to_str().unwrap(), obj_path.to_str().unwrap()]);
+
+                if let Some(lo) = linker_options.as_ref() {
+                    linker_cmd.args(lo.split(' '));
+                }
+
+                let linker_cmd_output = linker_cmd.output();
+
+                if let Err(linker_err) = linker_cmd_output {
+                    return Err(error::io_error(
+                        Position::new(file.to_path_buf()),
+                        Position::new(file.to_path_buf()),
+                        format!("Failed to run '{}'-command: {}", linker_name, linker_err).as_str(),
+                    ));
+                }
+
+                if !linker_cmd_output.as_ref().unwrap().status.success() {
+                    return Err(error::io_error_with_parent(
+                        Position::new(file.to_path_buf()),
+                        Position::new(file.to_path_buf()),
+                        format!("Linking ({}) failed with non-zero exit code", linker_name)
+                            .as_str(),
+                        error::io_error(
+                            Position::new(file.to_path_buf()),
+                            Position::new(file.to_path_buf()),
+                            format!(
+                                "\n\"{}\"",
+                                String::from_utf8_lossy(&*linker_cmd_output.unwrap().stderr)
+                            )
+                            .as_str(),
+                        ),
+                    ));
+                }
+            }
+
+            binaries.push(bin_path);
+        }
+
+        if verbose {
+            println!("Done")
+        }
+    }
+
+    Ok(binaries)
+}
+
+/// Checks that the backend, target, assembler and linker can build the given kind of library.
+fn check_library_options(
+    file: &Path,
+    options: &BuildArgs,
+    crate_type: CrateType,
+) -> Result<(), Error> {
+    let unsupported = if options.backend != Backend::Asm {
+        Some("Libraries can only be built with '--backend=asm'")
+    } else if options.assembler == Assembler::Builtin {
+        Some("The builtin assembler can't build libraries")
+    } else if crate_type != CrateType::Cdylib {
+        None
+    } else if !matches!(options.target, TargetObjectType::X86_64) {
+        Some("Shared libraries are only supported for the 'x86-64' target")
+    } else if options.linker == Linker::Builtin {
+        Some("The builtin linker can't build shared libraries")
+    } else {
+        None
+    };
+
+    match unsupported {
+        Some(message) => Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            message,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Puts the object file into a static library with `ar`.
+fn archive(file: &Path, obj_path: &Path, lib_path: &Path) -> Result<(), Error> {
+    // `ar` only adds to existing archives, so members of removed files would stay otherwise
+    if lib_path.exists() {
+        if let Err(fs_error) = fs::remove_file(lib_path) {
+            return Err(error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!("Could not remove old library: {}", fs_error).as_str(),
+            ));
+        }
     }
 
-    println!("All done! Took: {}ms", now.elapsed().as_millis());
+    let ar_cmd_output = Command::new("ar")
+        .args([
+            "rcs",
+            lib_path.to_str().unwrap(),
+            obj_path.to_str().unwrap(),
+        ])
+        .output();
+
+    if let Err(ar_err) = ar_cmd_output {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Failed to run 'ar'-command: {}", ar_err).as_str(),
+        ));
+    }
+
+    if !ar_cmd_output.as_ref().unwrap().status.success() {
+        return Err(error::io_error_with_parent(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            "Archiving (ar) failed with non-zero exit code",
+            error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!(
+                    "\n\"{}\"",
+                    String::from_utf8_lossy(&ar_cmd_output.unwrap().stderr)
+                )
+                .as_str(),
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the name of the GNU binutils tool for aarch64. On other hosts the cross compiling
+/// version with the `aarch64-linux-gnu-` prefix is used.
+fn gnu_tool(name: &str) -> String {
+    if env::consts::ARCH == "aarch64" {
+        name.to_string()
+    } else {
+        format!("aarch64-linux-gnu-{}", name)
+    }
+}
+
+/// Generates LLVM IR for the validated AST and writes it to the build directory.
+fn emit_llvm(
+    file: &Path,
+    ast_root: &Box<dyn Node>,
+    build_output: &Path,
+    entry: EntryPoint,
+    options: &BuildArgs,
+) -> Result<(), Error> {
+    let verbose = options.verbose;
+    let file_stem = file.file_stem().unwrap().to_str().unwrap();
+    let ll_path = build_output.join(format!("{}.ll", file_stem));
+
+    if verbose {
+        print!("Generating LLVM IR...")
+    }
+    let mut compiler = umber_lang::llvm_compiler::LlvmCompiler::new(options.target);
+    let ir = compiler.compile_to_str(ast_root, entry == EntryPoint::None)?;
+    if verbose {
+        println!("Done")
+    }
+
+    if !build_output.exists() || !build_output.is_dir() {
+        if let Err(fs_error) = fs::create_dir_all(build_output) {
+            return Err(error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!("Could not create build directory: {}", fs_error).as_str(),
+            ));
+        }
+    }
+
+    if let Err(fs_error) = fs::write(&ll_path, &ir) {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Could not write LLVM IR: {}", fs_error).as_str(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Generates a C header for the validated AST and writes it to the build directory.
+fn emit_c_header(
+    file: &Path,
+    ast_root: &Box<dyn Node>,
+    build_output: &Path,
+    options: &BuildArgs,
+) -> Result<(), Error> {
+    let verbose = options.verbose;
+    let file_stem = file.file_stem().unwrap().to_str().unwrap();
+    let h_path = build_output.join(format!("{}.h", file_stem));
+
+    let guard = format!(
+        "{}_H",
+        file_stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            })
+            .collect::<String>()
+    );
+
+    if verbose {
+        print!("Generating C header...")
+    }
+    let mut generator = umber_lang::c_header::CHeaderGenerator::new();
+    let header = generator.generate_to_str(ast_root, guard.as_str())?;
+    if verbose {
+        println!("Done")
+    }
+
+    if !build_output.exists() || !build_output.is_dir() {
+        if let Err(fs_error) = fs::create_dir_all(build_output) {
+            return Err(error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!("Could not create build directory: {}", fs_error).as_str(),
+            ));
+        }
+    }
+
+    if let Err(fs_error) = fs::write(&h_path, &header) {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Could not write C header: {}", fs_error).as_str(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Generates C for the validated AST and compiles it with the system's C compiler.
+fn build_c(
+    file: &Path,
+    ast_root: &Box<dyn Node>,
+    tests: &[(String, Position)],
+    build_output: &Path,
+    entry: EntryPoint,
+    compile_only: bool,
+    options: &BuildArgs,
+) -> Result<(), Error> {
+    let verbose = options.verbose;
+    let file_stem = file.file_stem().unwrap().to_str().unwrap();
+    let c_path = build_output.join(format!("{}.c", file_stem));
+
+    if verbose {
+        print!("Generating C...")
+    }
+    let mut compiler = umber_lang::c_compiler::CCompiler::new(options.target);
+    let source = match entry {
+        EntryPoint::Tests => compiler.compile_tests_to_str(ast_root, tests)?,
+        _ => compiler.compile_to_str(ast_root, entry == EntryPoint::None)?,
+    };
+    if verbose {
+        println!("Done")
+    }
+
+    if !build_output.exists() || !build_output.is_dir() {
+        if let Err(fs_error) = fs::create_dir_all(build_output) {
+            return Err(error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!("Could not create build directory: {}", fs_error).as_str(),
+            ));
+        }
+    }
+
+    if let Err(fs_error) = fs::write(&c_path, &source) {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Could not write C file: {}", fs_error).as_str(),
+        ));
+    }
+
+    if verbose {
+        print!("Compiling C...")
+    }
+
+    let cc = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let mut cc_cmd = Command::new(&cc);
+    // pointers passed to functions like 'memcpy' mustn't be assumed to be non-null afterwards
+    cc_cmd.args(["-O2", "-fno-delete-null-pointer-checks"]);
+    if compile_only {
+        let obj_path = build_output.join(format!(
+            "{}.{}",
+            file_stem,
+            options.target.object_file_extension()
+        ));
+        cc_cmd.args([
+            "-c",
+            "-o",
+            obj_path.to_str().unwrap(),
+            c_path.to_str().unwrap(),
+        ]);
+    } else {
+        let bin_path = build_output.join(file_stem);
+        // 'fmod' for the floating point modulo is in libm
+        cc_cmd.args([
+            "-o",
+            bin_path.to_str().unwrap(),
+            c_path.to_str().unwrap(),
+            "-lm",
+        ]);
+    }
+
+    let cc_cmd_output = cc_cmd.output();
+
+    if let Err(cc_err) = cc_cmd_output {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Failed to run '{}'-command: {}", cc, cc_err).as_str(),
+        ));
+    }
+
+    if !cc_cmd_output.as_ref().unwrap().status.success() {
+        return Err(error::io_error_with_parent(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Compiling C ({}) failed with non-zero exit code", cc).as_str(),
+            error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!(
+                    "\n\"{}\"",
+                    String::from_utf8_lossy(&cc_cmd_output.unwrap().stderr)
+                )
+                .as_str(),
+            ),
+        ));
+    }
+
+    if verbose {
+        println!("Done")
+    }
+
+    Ok(())
+}
+
+/// Assembles the generated assembly with the builtin assembler and writes the object file.
+fn assemble_builtin(
+    file: &Path,
+    asm: &str,
+    asm_path: &Path,
+    obj_path: &Path,
+    arch: TargetObjectType,
+) -> Result<(), Error> {
+    if !matches!(arch, TargetObjectType::X86_64) {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!(
+                "The builtin assembler only supports the 'x86-64' target, not '{:?}'",
+                arch
+            )
+            .as_str(),
+        ));
+    }
+
+    let object = umber_lang::assembler::assemble(asm, asm_path)?;
+
+    if let Err(fs_error) = fs::write(obj_path, object) {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Could not write object file: {}", fs_error).as_str(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Links the object file into a freestanding executable with the builtin linker.
+fn link_builtin(
+    file: &Path,
+    obj_path: &Path,
+    bin_path: &Path,
+    arch: TargetObjectType,
+) -> Result<(), Error> {
+    if !matches!(arch, TargetObjectType::X86_64) {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!(
+                "The builtin linker only supports the 'x86-64' target, not '{:?}'",
+                arch
+            )
+            .as_str(),
+        ));
+    }
+
+    let object = match fs::read(obj_path) {
+        Ok(object) => object,
+        Err(fs_error) => {
+            return Err(error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!("Could not read object file: {}", fs_error).as_str(),
+            ));
+        }
+    };
+
+    let executable = umber_lang::linker::link(&[(obj_path.to_path_buf(), object)])?;
+
+    if let Err(fs_error) = write_executable(bin_path, &executable) {
+        return Err(error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Could not write executable: {}", fs_error).as_str(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_executable(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::write(path, contents)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn write_executable(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    fs::write(path, contents)
+}
+
+/// Returns the directory `umber run` builds the given file in. Every source file gets its own
+/// directory in the temporary directory, so it is reused between runs.
+fn run_build_dir(file: &Path) -> PathBuf {
+    let path = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+
+    env::temp_dir().join("umber").join(format!(
+        "{}-{:016x}",
+        file.file_stem().unwrap_or_default().to_string_lossy(),
+        hasher.finish()
+    ))
+}
+
+#[cfg(unix)]
+fn exit_code(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+
+    // like shells do, report a program killed by a signal with 128 + the signal number
+    status
+        .code()
+        .unwrap_or_else(|| 128 + status.signal().unwrap_or_default())
+}
+
+#[cfg(not(unix))]
+fn exit_code(status: ExitStatus) -> i32 {
+    status.code().unwrap_or(-1)
+}
+
+fn run(
+    file: String,
+    options: &BuildArgs,
+    entry: EntryPoint,
+    args: Vec<String>,
+    time: bool,
+) -> Result<i32, Error> {
+    let now = Instant::now();
+
+    if entry == EntryPoint::Tests
+        && !matches!(
+            options.target,
+            TargetObjectType::X86_64 | TargetObjectType::Aarch64Linux
+        )
+    {
+        return Err(error::io_error(
+            Position::new(PathBuf::from(&file)),
+            Position::new(PathBuf::from(&file)),
+            format!("Tests are not supported for target {:?}!", options.target).as_str(),
+        ));
+    }
+
+    let build_output = run_build_dir(Path::new(file.as_str()));
+    let binaries = compile(
+        vec![file.clone()],
+        &build_output,
+        options,
+        entry,
+        false,
+        None,
+        CrateType::Bin,
+    )?;
+    let compile_time = now.elapsed();
+
+    let now = Instant::now();
+    // aarch64 binaries are run with qemu's user mode emulation on other hosts
+    let status = if matches!(options.target, TargetObjectType::Aarch64Linux)
+        && env::consts::ARCH != "aarch64"
+    {
+        Command::new("qemu-aarch64")
+            .arg(&binaries[0])
+            .args(args)
+            .status()
+    } else {
+        Command::new(&binaries[0]).args(args).status()
+    };
+    let run_time = now.elapsed();
+
+    let status = match status {
+        Ok(status) => status,
+        Err(run_err) => {
+            return Err(error::io_error(
+                Position::new(PathBuf::from(&file)),
+                Position::new(PathBuf::from(&file)),
+                format!(
+                    "Failed to run '{}': {}",
+                    binaries[0].to_str().unwrap(),
+                    run_err
+                )
+                .as_str(),
+            ))
+        }
+    };
+
+    if time {
+        eprintln!(
+            "Compiling took: {}ms, running took: {}ms",
+            compile_time.as_millis(),
+            run_time.as_millis()
+        );
+    }
+
+    Ok(exit_code(status))
+}
+
+fn format(files: Vec<String>, check: bool) -> Result<bool, Error> {
+    let mut all_formatted = true;
+
+    for file in files {
+        let file = Path::new(file.as_str());
+
+        let file_contents = match fs::read_to_string(&file) {
+            Ok(file_contents) => file_contents,
+            Err(file_err) => {
+                return Err(error::io_error(
+                    Position::new(file.to_path_buf()),
+                    Position::new(file.to_path_buf()),
+                    format!("Could not read file: {}", file_err).as_str(),
+                ))
+            }
+        };
+
+        let formatted = umber_lang::formatter::format_source(file.to_path_buf(), &file_contents)?;
+        if formatted == file_contents {
+            continue;
+        }
+
+        if check {
+            println!("{}", file.display());
+            all_formatted = false;
+        } else if let Err(fs_error) = fs::write(&file, formatted) {
+            return Err(error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!("Could not write file: {}", fs_error).as_str(),
+            ));
+        }
+    }
+
+    Ok(all_formatted)
+}
+
+fn bindgen(file: String, output: Option<String>) -> Result<(), Error> {
+    let file = Path::new(file.as_str());
+
+    let file_contents = match fs::read_to_string(file) {
+        Ok(file_contents) => file_contents,
+        Err(file_err) => {
+            return Err(error::io_error(
+                Position::new(file.to_path_buf()),
+                Position::new(file.to_path_buf()),
+                format!("Could not read file: {}", file_err).as_str(),
+            ))
+        }
+    };
+
+    let bindings = umber_lang::bindgen::generate_bindings(file.to_path_buf(), &file_contents);
+    for warning in &bindings.warnings {
+        eprint!("{}", warning);
+    }
+
+    match output {
+        Some(output) => {
+            if let Err(fs_error) = fs::write(&output, bindings.source) {
+                return Err(error::io_error(
+                    Position::new(PathBuf::from(&output)),
+                    Position::new(PathBuf::from(&output)),
+                    format!("Could not write file: {}", fs_error).as_str(),
+                ));
+            }
+        }
+        None => print!("{}", bindings.source),
+    }
 
     Ok(())
 }
@@ -285,16 +1141,74 @@ fn main() {
     let args = BinaryArgs::parse();
 
     if let Err(err) = match args.command {
-        Subcommands::Com(subcommand) => compile(
+        Subcommands::Com(subcommand) => {
+            let now = Instant::now();
+
+            compile(
+                subcommand.name,
+                &env::current_dir().unwrap().join("build"),
+                &subcommand.build,
+                if subcommand.no_entry || subcommand.crate_type != CrateType::Bin {
+                    EntryPoint::None
+                } else {
+                    EntryPoint::Main
+                },
+                subcommand.compile_only,
+                subcommand.emit,
+                subcommand.crate_type,
+            )
+            .map(|_| println!("All done! Took: {}ms", now.elapsed().as_millis()))
+        }
+        Subcommands::Run(subcommand) => match run(
+            subcommand.name,
+            &subcommand.build,
+            EntryPoint::Main,
+            subcommand.args,
+            subcommand.time,
+        ) {
+            Ok(code) => exit(code),
+            Err(err) => Err(err),
+        },
+        Subcommands::Test(subcommand) => match run(
             subcommand.name,
-            subcommand.include,
-            subcommand.asm,
-            subcommand.linker,
-            subcommand.verbose,
-            subcommand.no_entry,
-            subcommand.compile_only,
-            subcommand.target,
-        ),
+            &subcommand.build,
+            EntryPoint::Tests,
+            vec![],
+            subcommand.time,
+        ) {
+            Ok(code) => exit(code),
+            Err(err) => Err(err),
+        },
+        Subcommands::Fmt(subcommand) => match format(subcommand.name, subcommand.check) {
+            Ok(true) => Ok(()),
+            Ok(false) => exit(1),
+            Err(err) => Err(err),
+        },
+        Subcommands::Repl(subcommand) => {
+            match umber_lang::interpreter::repl::run(subcommand.include.unwrap_or_default()) {
+                Ok(code) => exit(code),
+                Err(err) => {
+                    eprintln!("umber repl: {}", err);
+                    exit(1);
+                }
+            }
+        }
+        Subcommands::Eval(subcommand) => {
+            // the program name comes first, like for a compiled program
+            let mut args = vec![subcommand.name.clone()];
+            args.extend(subcommand.args);
+
+            match umber_lang::interpreter::run_file(
+                Path::new(&subcommand.name),
+                &subcommand.include.unwrap_or_default(),
+                &subcommand.checks,
+                &args,
+            ) {
+                Ok(code) => exit(code),
+                Err(err) => Err(err),
+            }
+        }
+        Subcommands::Bindgen(subcommand) => bindgen(subcommand.name, subcommand.output),
     } {
         println!("\n{}", err);
         exit(-1);
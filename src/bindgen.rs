@@ -0,0 +1,1752 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::error;
+use crate::error::Error;
+use crate::position::Position;
+use crate::token::KEYWORDS;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CTokenType {
+    Identifier,
+    Number,
+    String,
+    Char,
+    Punctuator,
+    /// A whole preprocessor line, without the `#`
+    Directive,
+}
+
+#[derive(Clone, Debug)]
+struct CToken {
+    token_type: CTokenType,
+    value: String,
+    pos_start: Position,
+    pos_end: Position,
+}
+
+impl CToken {
+    fn is(&self, value: &str) -> bool {
+        matches!(
+            self.token_type,
+            CTokenType::Identifier | CTokenType::Punctuator
+        ) && self.value == value
+    }
+}
+
+const PUNCTUATORS: [&str; 15] = [
+    "...", "<<", ">>", "->", "==", "!=", "<=", ">=", "&&", "||", "++", "--", "##", "+=", "-=",
+];
+
+/// Splits C source into tokens. Comments are dropped and preprocessor lines are kept as a single
+/// `Directive` token, so the parser can handle them between declarations.
+fn tokenize(file_path: PathBuf, text: &str) -> Vec<CToken> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = vec![];
+    let mut pos = Position::new(file_path);
+    let mut i = 0;
+    let mut line_start = true;
+
+    macro_rules! advance {
+        () => {{
+            pos.advance(&chars[i]);
+            i += 1;
+        }};
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c == '\n' {
+            line_start = true;
+            advance!();
+            continue;
+        }
+
+        if c.is_whitespace() {
+            advance!();
+            continue;
+        }
+
+        if c == '\\' && next == Some('\n') {
+            advance!();
+            advance!();
+            continue;
+        }
+
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                advance!();
+            }
+            continue;
+        }
+
+        if c == '/' && next == Some('*') {
+            advance!();
+            advance!();
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                advance!();
+            }
+            if i < chars.len() {
+                advance!();
+                advance!();
+            }
+            continue;
+        }
+
+        let pos_start = pos.clone();
+        let mut value = String::new();
+
+        let token_type = if c == '#' && line_start {
+            advance!();
+            while i < chars.len() && chars[i] != '\n' {
+                if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') {
+                    advance!();
+                    advance!();
+                    value.push(' ');
+                    continue;
+                }
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'/') {
+                    while i < chars.len() && chars[i] != '\n' {
+                        advance!();
+                    }
+                    break;
+                }
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                        advance!();
+                    }
+                    if i < chars.len() {
+                        advance!();
+                        advance!();
+                    }
+                    value.push(' ');
+                    continue;
+                }
+                value.push(chars[i]);
+                advance!();
+            }
+            value = value.trim().to_string();
+            CTokenType::Directive
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                value.push(chars[i]);
+                advance!();
+            }
+            CTokenType::Identifier
+        } else if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) {
+            while i < chars.len() {
+                let d = chars[i];
+                let exponent = (d == '+' || d == '-')
+                    && matches!(value.chars().last(), Some('e' | 'E' | 'p' | 'P'))
+                    && !value.starts_with("0x")
+                    && !value.starts_with("0X");
+                if !(d.is_ascii_alphanumeric() || d == '.' || d == '_' || exponent) {
+                    break;
+                }
+                value.push(d);
+                advance!();
+            }
+            CTokenType::Number
+        } else if c == '"' || c == '\'' {
+            advance!();
+            while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    value.push(chars[i]);
+                    advance!();
+                }
+                value.push(chars[i]);
+                advance!();
+            }
+            if i < chars.len() && chars[i] == c {
+                advance!();
+            }
+            if c == '"' {
+                CTokenType::String
+            } else {
+                CTokenType::Char
+            }
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 3)].iter().collect();
+            let punctuator = PUNCTUATORS
+                .iter()
+                .find(|p| rest.starts_with(*p))
+                .map_or(c.to_string(), |p| p.to_string());
+            for _ in 0..punctuator.chars().count() {
+                advance!();
+            }
+            value = punctuator;
+            CTokenType::Punctuator
+        };
+
+        line_start = token_type == CTokenType::Directive;
+        tokens.push(CToken {
+            token_type,
+            value,
+            pos_start,
+            pos_end: pos.clone(),
+        });
+    }
+
+    tokens
+}
+
+#[derive(Clone, Debug)]
+enum CType {
+    Void,
+    Bool,
+    Char,
+    /// An integer with its size in bytes and whether it is signed
+    Integer(u64, bool),
+    Float,
+    Double,
+    LongDouble,
+    /// A pointer and whether the pointee can be changed through it
+    Pointer(Box<CType>, bool),
+    Array(Box<CType>, Option<u64>),
+    Function(Box<CType>, Vec<Parameter>, bool),
+    /// A structure by its key in `Bindgen::structs`
+    Struct(String),
+    Union,
+    /// A typedef which is not declared in the header
+    Named(String),
+}
+
+/// The types of the standard headers, which are often used without including them.
+fn builtin_typedef(name: &str) -> Option<CType> {
+    Some(match name {
+        "int8_t" => CType::Integer(1, true),
+        "int16_t" => CType::Integer(2, true),
+        "int32_t" | "pid_t" | "wchar_t" => CType::Integer(4, true),
+        "int64_t" | "ssize_t" | "ptrdiff_t" | "intptr_t" | "intmax_t" | "off_t" | "time_t" => {
+            CType::Integer(8, true)
+        }
+        "uint8_t" => CType::Integer(1, false),
+        "uint16_t" => CType::Integer(2, false),
+        "uint32_t" | "uid_t" | "gid_t" | "mode_t" => CType::Integer(4, false),
+        "uint64_t" | "size_t" | "uintptr_t" | "uintmax_t" => CType::Integer(8, false),
+        _ => return None,
+    })
+}
+
+/// A parameter of a function type, the name is optional in prototypes
+type Parameter = (Option<String>, CType);
+
+enum Derivation {
+    /// A pointer and whether the pointer itself is `const`
+    Pointer(bool),
+    Array(Option<u64>),
+    Function(Vec<Parameter>, bool),
+}
+
+struct Specifiers {
+    base: CType,
+    is_const: bool,
+    is_typedef: bool,
+    is_static: bool,
+}
+
+struct Structure {
+    /// The name of the Umber structure, the tag or the name of the first typedef
+    name: Option<String>,
+    fields: Vec<(String, CType)>,
+    /// Why the structure can't be expressed in Umber
+    unsupported: Option<String>,
+}
+
+enum Item {
+    Macro(String, i64),
+    Struct(String),
+    Function(String, CType),
+    Static(String, CType, bool),
+}
+
+struct Bindgen {
+    tokens: Vec<CToken>,
+    index: usize,
+    end_pos: Position,
+
+    typedefs: HashMap<String, CType>,
+    structs: HashMap<String, Structure>,
+    constants: HashMap<String, i64>,
+    anonymous_structs: usize,
+    /// The depth of `extern "C" {` blocks
+    linkage_depth: usize,
+
+    items: Vec<(Item, Position, Position)>,
+    warnings: Vec<Error>,
+}
+
+/// The Umber declarations generated from a C header, with warnings for everything that was
+/// skipped.
+pub struct Bindings {
+    pub source: String,
+    pub warnings: Vec<Error>,
+}
+
+/// Generates Umber declarations for the function prototypes, structures, enums, integer `#define`s
+/// and typedefs of a simple C header. The header isn't preprocessed: `#include`s aren't followed
+/// and both branches of conditionals are used, where only the first declaration of a name counts.
+/// Functions become `extern fun`s, variables `extern static`s and constants `macro`s. Structures
+/// get explicit padding fields, because Umber structures are packed.
+pub fn generate_bindings(file_path: PathBuf, text: &str) -> Bindings {
+    let mut end_pos = Position::new(file_path.clone());
+    for c in text.chars() {
+        end_pos.advance(&c);
+    }
+
+    let mut bindgen = Bindgen {
+        tokens: tokenize(file_path.clone(), text),
+        index: 0,
+        end_pos,
+        typedefs: HashMap::new(),
+        structs: HashMap::new(),
+        constants: HashMap::new(),
+        anonymous_structs: 0,
+        linkage_depth: 0,
+        items: vec![],
+        warnings: vec![],
+    };
+    bindgen.parse();
+
+    let header_name = file_path
+        .file_name()
+        .map_or(String::new(), |name| name.to_string_lossy().to_string());
+    let source = bindgen.render(&header_name);
+    bindgen
+        .warnings
+        .sort_by_key(|warning| *warning.pos_start().index());
+
+    Bindings {
+        source,
+        warnings: bindgen.warnings,
+    }
+}
+
+impl Bindgen {
+    // region Helper functions
+
+    fn current(&self) -> Option<&CToken> {
+        self.tokens.get(self.index)
+    }
+
+    fn current_is(&self, value: &str) -> bool {
+        self.current().is_some_and(|token| token.is(value))
+    }
+
+    fn peek_is(&self, offset: usize, value: &str) -> bool {
+        self.tokens
+            .get(self.index + offset)
+            .is_some_and(|token| token.is(value))
+    }
+
+    fn pos(&self) -> Position {
+        self.current()
+            .map_or(self.end_pos.clone(), |token| token.pos_start.clone())
+    }
+
+    fn warning(&self, details: &str) -> Error {
+        let (pos_start, pos_end) = match self.current() {
+            Some(token) => (token.pos_start.clone(), token.pos_end.clone()),
+            None => (self.end_pos.clone(), self.end_pos.clone()),
+        };
+        error::warning(pos_start, pos_end, details)
+    }
+
+    fn expect(&mut self, value: &str) -> Result<(), Error> {
+        if !self.current_is(value) {
+            return Err(self.warning(
+                format!(
+                    "Expected '{}', found '{}', the declaration was skipped",
+                    value,
+                    self.current()
+                        .map_or("end of file", |token| token.value.as_str())
+                )
+                .as_str(),
+            ));
+        }
+        self.index += 1;
+        Ok(())
+    }
+
+    /// Skips balanced brackets, starting at the opening one.
+    fn skip_balanced(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.current() {
+            if token.token_type == CTokenType::Punctuator {
+                match token.value.as_str() {
+                    "(" | "[" | "{" => depth += 1,
+                    ")" | "]" | "}" => depth -= 1,
+                    _ => {}
+                }
+            }
+            self.index += 1;
+            if depth <= 0 {
+                break;
+            }
+        }
+    }
+
+    /// Skips GCC attributes and symbol renames, like `__attribute__((nonnull))`.
+    fn skip_attributes(&mut self) {
+        while let Some(token) = self.current() {
+            if !matches!(
+                token.value.as_str(),
+                "__attribute__" | "__attribute" | "__declspec" | "__asm__" | "__asm" | "asm"
+            ) || token.token_type != CTokenType::Identifier
+            {
+                break;
+            }
+            self.index += 1;
+            if self.current_is("(") {
+                self.skip_balanced();
+            }
+        }
+    }
+
+    /// Skips attributes after a declarator, including macros from the reserved namespace like
+    /// glibc's `__THROW` or `__nonnull ((1))`.
+    fn skip_trailing_attributes(&mut self) {
+        while let Some(token) = self.current() {
+            if token.token_type != CTokenType::Identifier || !token.value.starts_with("__") {
+                break;
+            }
+            self.index += 1;
+            if self.current_is("(") {
+                self.skip_balanced();
+            }
+        }
+    }
+
+    /// Continues after the declaration starting at `start`, which couldn't be parsed.
+    fn recover(&mut self, start: usize) {
+        self.index = start;
+        let mut depth = 0;
+        while let Some(token) = self.current() {
+            let after_parenthesis = self.index > 0 && self.tokens[self.index - 1].is(")");
+            match token.value.as_str() {
+                "(" | "[" | "{" if token.token_type == CTokenType::Punctuator => {
+                    // function bodies aren't followed by a ';'
+                    if token.value == "{" && depth == 0 && after_parenthesis {
+                        self.skip_balanced();
+                        return;
+                    }
+                    depth += 1;
+                }
+                ")" | "]" | "}" if token.token_type == CTokenType::Punctuator => {
+                    if depth == 0 {
+                        // the end of an `extern "C"` block
+                        return;
+                    }
+                    depth -= 1;
+                }
+                ";" if depth == 0 => {
+                    self.index += 1;
+                    return;
+                }
+                _ => {}
+            }
+            self.index += 1;
+        }
+    }
+
+    fn declare_item(&mut self, item: Item, pos_start: Position) {
+        let pos_end = self
+            .tokens
+            .get(self.index.saturating_sub(1))
+            .map_or(self.end_pos.clone(), |token| token.pos_end.clone());
+        self.items.push((item, pos_start, pos_end));
+    }
+
+    // endregion
+
+    // region Constant expressions
+
+    fn number(&self, literal: &str) -> Option<i64> {
+        let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+        let (digits, radix) = if let Some(hex) = digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+        {
+            (hex, 16)
+        } else if let Some(binary) = digits
+            .strip_prefix("0b")
+            .or_else(|| digits.strip_prefix("0B"))
+        {
+            (binary, 2)
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            (&digits[1..], 8)
+        } else {
+            (digits, 10)
+        };
+
+        u64::from_str_radix(digits, radix).ok().map(|n| n as i64)
+    }
+
+    fn character(literal: &str) -> Option<i64> {
+        let mut chars = literal.chars();
+        let value = match (chars.next()?, chars.next()) {
+            ('\\', Some('n')) => '\n' as i64,
+            ('\\', Some('t')) => '\t' as i64,
+            ('\\', Some('r')) => '\r' as i64,
+            ('\\', Some('0')) => 0,
+            ('\\', Some(c)) => c as i64,
+            (c, None) => c as i64,
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Evaluates an integer constant expression, like the value of an enum constant.
+    fn evaluate(&self, tokens: &[CToken]) -> Option<i64> {
+        let mut index = 0;
+        let value = self.binary(tokens, &mut index, 0)?;
+        if index != tokens.len() {
+            return None;
+        }
+        Some(value)
+    }
+
+    fn binary(&self, tokens: &[CToken], index: &mut usize, min_precedence: u8) -> Option<i64> {
+        let mut left = self.unary(tokens, index)?;
+
+        while let Some(token) = tokens.get(*index) {
+            if token.token_type != CTokenType::Punctuator {
+                return None;
+            }
+            let precedence = match token.value.as_str() {
+                "|" => 1,
+                "^" => 2,
+                "&" => 3,
+                "<<" | ">>" => 4,
+                "+" | "-" => 5,
+                "*" | "/" | "%" => 6,
+                _ => break,
+            };
+            if precedence < min_precedence {
+                break;
+            }
+            *index += 1;
+
+            let right = self.binary(tokens, index, precedence + 1)?;
+            left = match token.value.as_str() {
+                "|" => left | right,
+                "^" => left ^ right,
+                "&" => left & right,
+                "<<" => left.checked_shl(right as u32)?,
+                ">>" => left.checked_shr(right as u32)?,
+                "+" => left.wrapping_add(right),
+                "-" => left.wrapping_sub(right),
+                "*" => left.wrapping_mul(right),
+                "/" => left.checked_div(right)?,
+                _ => left.checked_rem(right)?,
+            };
+        }
+
+        Some(left)
+    }
+
+    fn unary(&self, tokens: &[CToken], index: &mut usize) -> Option<i64> {
+        let token = tokens.get(*index)?;
+        *index += 1;
+
+        match token.token_type {
+            CTokenType::Number => self.number(&token.value),
+            CTokenType::Char => Self::character(&token.value),
+            CTokenType::Identifier => self.constants.get(&token.value).copied(),
+            CTokenType::Punctuator => match token.value.as_str() {
+                "-" => Some(self.unary(tokens, index)?.wrapping_neg()),
+                "+" => self.unary(tokens, index),
+                "~" => Some(!self.unary(tokens, index)?),
+                "(" => {
+                    // casts like `(int)1` are ignored
+                    let next = tokens.get(*index)?;
+                    if next.token_type == CTokenType::Identifier
+                        && !self.constants.contains_key(&next.value)
+                    {
+                        while !tokens.get(*index)?.is(")") {
+                            *index += 1;
+                        }
+                        *index += 1;
+                        return self.unary(tokens, index);
+                    }
+
+                    let value = self.binary(tokens, index, 0)?;
+                    if !tokens.get(*index)?.is(")") {
+                        return None;
+                    }
+                    *index += 1;
+                    Some(value)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // endregion
+
+    // region Preprocessor
+
+    fn directive(&mut self, token: &CToken) {
+        let (name, rest) = token
+            .value
+            .split_once(|c: char| c.is_whitespace())
+            .unwrap_or((token.value.as_str(), ""));
+
+        match name {
+            "define" => {
+                let macro_name: String = rest
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                    .collect();
+                let body = &rest[macro_name.len()..];
+
+                if body.starts_with('(') {
+                    self.warnings.push(error::warning(
+                        token.pos_start.clone(),
+                        token.pos_end.clone(),
+                        format!("Function-like macro '{}' was skipped", macro_name).as_str(),
+                    ));
+                    return;
+                }
+
+                // include guards, flags without a value and reserved macros of the implementation
+                if body.trim().is_empty() || macro_name.starts_with('_') {
+                    return;
+                }
+
+                let body_tokens = tokenize(token.pos_start.file_name().clone(), body);
+                match self.evaluate(&body_tokens) {
+                    Some(value) => {
+                        self.constants.insert(macro_name.clone(), value);
+                        self.items.push((
+                            Item::Macro(macro_name, value),
+                            token.pos_start.clone(),
+                            token.pos_end.clone(),
+                        ));
+                    }
+                    None => self.warnings.push(error::warning(
+                        token.pos_start.clone(),
+                        token.pos_end.clone(),
+                        format!(
+                            "Macro '{}' is not an integer constant and was skipped",
+                            macro_name
+                        )
+                        .as_str(),
+                    )),
+                }
+            }
+            "include" => self.warnings.push(error::warning(
+                token.pos_start.clone(),
+                token.pos_end.clone(),
+                format!(
+                    "'#include {}' isn't followed, generate the bindings of that header separately",
+                    rest.trim()
+                )
+                .as_str(),
+            )),
+            // conditionals aren't evaluated, both branches are used
+            _ => {}
+        }
+    }
+
+    // endregion
+
+    // region Declarations
+
+    fn parse(&mut self) {
+        while let Some(token) = self.current().cloned() {
+            if token.token_type == CTokenType::Directive {
+                self.index += 1;
+                self.directive(&token);
+                continue;
+            }
+
+            // like `__BEGIN_DECLS`, which expand to nothing or `extern "C" {`
+            if token.is(";")
+                || token.is("__extension__")
+                || (token.token_type == CTokenType::Identifier
+                    && token.value.starts_with("__")
+                    && !token.value.chars().any(|c| c.is_ascii_lowercase()))
+            {
+                self.index += 1;
+                continue;
+            }
+
+            if token.is("extern")
+                && self
+                    .tokens
+                    .get(self.index + 1)
+                    .is_some_and(|next| next.token_type == CTokenType::String)
+            {
+                self.index += 2;
+                if self.current_is("{") {
+                    self.index += 1;
+                    self.linkage_depth += 1;
+                }
+                continue;
+            }
+
+            if token.is("}") && self.linkage_depth > 0 {
+                self.index += 1;
+                self.linkage_depth -= 1;
+                continue;
+            }
+
+            if token.is("_Static_assert") || token.is("static_assert") {
+                self.recover(self.index);
+                continue;
+            }
+
+            let start = self.index;
+            if let Err(warning) = self.declaration() {
+                self.warnings.push(warning);
+                self.recover(start);
+                if self.index == start {
+                    self.index += 1;
+                }
+            }
+        }
+    }
+
+    fn declaration(&mut self) -> Result<(), Error> {
+        let pos_start = self.pos();
+        let specifiers = self.specifiers()?;
+
+        if self.current_is(";") {
+            self.index += 1;
+            return Ok(());
+        }
+
+        loop {
+            let (name, c_type, is_const) =
+                self.declarator(specifiers.base.clone(), specifiers.is_const)?;
+            self.skip_attributes();
+
+            let name = match name {
+                Some(name) => name,
+                None => return Err(self.warning("Expected a name, the declaration was skipped")),
+            };
+
+            if specifiers.is_typedef {
+                self.typedef(name, c_type);
+            } else if let CType::Function(..) = c_type {
+                if self.current_is("{") {
+                    let warning = self.warning(
+                        format!(
+                            "Function '{}' is defined in the header and was skipped",
+                            name
+                        )
+                        .as_str(),
+                    );
+                    self.warnings.push(warning);
+                    self.skip_balanced();
+                    return Ok(());
+                }
+
+                // static functions are only visible in the file including the header
+                if !specifiers.is_static {
+                    self.declare_item(Item::Function(name, c_type), pos_start.clone());
+                }
+            } else {
+                if self.current_is("=") {
+                    while !self.current_is(",") && !self.current_is(";") {
+                        if self.current().is_none() {
+                            break;
+                        }
+                        if self.current_is("{") || self.current_is("(") {
+                            self.skip_balanced();
+                        } else {
+                            self.index += 1;
+                        }
+                    }
+                }
+
+                if !specifiers.is_static {
+                    self.declare_item(Item::Static(name, c_type, !is_const), pos_start.clone());
+                }
+            }
+
+            if self.current_is(",") {
+                self.index += 1;
+                continue;
+            }
+            return self.expect(";");
+        }
+    }
+
+    fn typedef(&mut self, name: String, c_type: CType) {
+        // anonymous structures are named after their first typedef, and so are reserved tags
+        // like `struct _point`
+        if let CType::Struct(key) = &c_type {
+            if let Some(structure) = self.structs.get_mut(key) {
+                if structure
+                    .name
+                    .as_ref()
+                    .is_none_or(|tag| tag.starts_with('_'))
+                {
+                    structure.name = Some(name.clone());
+                }
+            }
+        }
+
+        self.typedefs.entry(name).or_insert(c_type);
+    }
+
+    fn specifiers(&mut self) -> Result<Specifiers, Error> {
+        let mut specifiers = Specifiers {
+            base: CType::Void,
+            is_const: false,
+            is_typedef: false,
+            is_static: false,
+        };
+
+        let mut base: Option<CType> = None;
+        let (mut signed, mut unsigned) = (false, false);
+        let (mut char, mut short, mut int, mut long) = (false, false, false, 0);
+        let (mut float, mut double) = (false, false);
+
+        while let Some(token) = self.current().cloned() {
+            if token.token_type != CTokenType::Identifier {
+                break;
+            }
+
+            let has_type = base.is_some()
+                || signed
+                || unsigned
+                || char
+                || short
+                || int
+                || long > 0
+                || float
+                || double;
+
+            match token.value.as_str() {
+                "typedef" => specifiers.is_typedef = true,
+                "static" => specifiers.is_static = true,
+                "const" | "__const" => specifiers.is_const = true,
+                "extern" | "inline" | "__inline" | "__inline__" | "volatile" | "__volatile__"
+                | "register" | "auto" | "restrict" | "__restrict" | "__restrict__"
+                | "__extension__" | "_Noreturn" | "_Thread_local" | "__thread" => {}
+                "__attribute__" | "__attribute" | "__declspec" | "_Alignas" => {
+                    self.index += 1;
+                    if self.current_is("(") {
+                        self.skip_balanced();
+                    }
+                    continue;
+                }
+                "void" => base = Some(CType::Void),
+                "_Bool" | "bool" => base = Some(CType::Bool),
+                "char" => char = true,
+                "short" => short = true,
+                "int" => int = true,
+                "long" => long += 1,
+                "signed" | "__signed__" => signed = true,
+                "unsigned" => unsigned = true,
+                "float" => float = true,
+                "double" => double = true,
+                "struct" | "union" => {
+                    base = Some(self.struct_specifier()?);
+                    continue;
+                }
+                "enum" => {
+                    self.enum_specifier()?;
+                    base = Some(CType::Integer(4, true));
+                    continue;
+                }
+                name if !has_type => {
+                    base = Some(
+                        self.typedefs
+                            .get(name)
+                            .cloned()
+                            .or_else(|| builtin_typedef(name))
+                            .unwrap_or_else(|| CType::Named(name.to_string())),
+                    );
+                }
+                // the name of the declaration
+                _ => break,
+            }
+
+            self.index += 1;
+        }
+
+        specifiers.base = if float {
+            CType::Float
+        } else if double {
+            if long > 0 {
+                CType::LongDouble
+            } else {
+                CType::Double
+            }
+        } else if char {
+            match (signed, unsigned) {
+                (true, _) => CType::Integer(1, true),
+                (_, true) => CType::Integer(1, false),
+                _ => CType::Char,
+            }
+        } else if short {
+            CType::Integer(2, !unsigned)
+        } else if long > 0 {
+            CType::Integer(8, !unsigned)
+        } else if int || signed || unsigned {
+            CType::Integer(4, !unsigned)
+        } else {
+            match base {
+                Some(base) => base,
+                None => return Err(self.warning("Expected a type, the declaration was skipped")),
+            }
+        };
+
+        Ok(specifiers)
+    }
+
+    fn struct_specifier(&mut self) -> Result<CType, Error> {
+        let pos_start = self.pos();
+        let is_union = self.current_is("union");
+        self.index += 1;
+        self.skip_attributes();
+
+        let tag = match self.current() {
+            Some(token) if token.token_type == CTokenType::Identifier => {
+                let tag = token.value.clone();
+                self.index += 1;
+                Some(tag)
+            }
+            _ => None,
+        };
+
+        if is_union {
+            if self.current_is("{") {
+                self.skip_balanced();
+
+                // declarations using the union report it, a definition on its own has to as well
+                if self.current_is(";") {
+                    let name = match &tag {
+                        Some(tag) => format!("Union '{}'", tag),
+                        None => String::from("Union"),
+                    };
+                    self.warnings.push(error::warning(
+                        pos_start,
+                        self.pos(),
+                        format!("{} was skipped, unions aren't supported", name).as_str(),
+                    ));
+                }
+            }
+            return Ok(CType::Union);
+        }
+
+        let key = match &tag {
+            Some(tag) => tag.clone(),
+            None => {
+                self.anonymous_structs += 1;
+                format!("#{}", self.anonymous_structs)
+            }
+        };
+
+        if !self.current_is("{") {
+            return Ok(CType::Struct(key));
+        }
+        self.index += 1;
+
+        let mut fields = vec![];
+        let mut unsupported = None;
+
+        while !self.current_is("}") {
+            if self.current().is_none() {
+                return Err(self.warning("Expected '}', the structure was skipped"));
+            }
+            if self.current().unwrap().token_type == CTokenType::Directive {
+                self.index += 1;
+                continue;
+            }
+
+            let specifiers = self.specifiers()?;
+            if self.current_is(";") {
+                unsupported.get_or_insert(String::from("it has anonymous members"));
+                self.index += 1;
+                continue;
+            }
+
+            loop {
+                let (name, c_type, _) =
+                    self.declarator(specifiers.base.clone(), specifiers.is_const)?;
+                if self.current_is(":") {
+                    unsupported.get_or_insert(String::from("it has bit fields"));
+                    while !self.current_is(",") && !self.current_is(";") {
+                        if self.current().is_none() {
+                            break;
+                        }
+                        self.index += 1;
+                    }
+                }
+                self.skip_attributes();
+
+                if let CType::Union = c_type {
+                    unsupported.get_or_insert(String::from("it contains a union"));
+                }
+                fields.push((name.unwrap_or_default(), c_type));
+
+                if self.current_is(",") {
+                    self.index += 1;
+                    continue;
+                }
+                self.expect(";")?;
+                break;
+            }
+        }
+        self.index += 1;
+        self.skip_attributes();
+
+        if self.structs.contains_key(&key) {
+            // both branches of a conditional
+            return Ok(CType::Struct(key));
+        }
+
+        self.structs.insert(
+            key.clone(),
+            Structure {
+                name: tag,
+                fields,
+                unsupported,
+            },
+        );
+        self.declare_item(Item::Struct(key.clone()), pos_start);
+
+        Ok(CType::Struct(key))
+    }
+
+    fn enum_specifier(&mut self) -> Result<(), Error> {
+        self.index += 1;
+        self.skip_attributes();
+
+        if self
+            .current()
+            .is_some_and(|token| token.token_type == CTokenType::Identifier)
+        {
+            self.index += 1;
+        }
+
+        if !self.current_is("{") {
+            return Ok(());
+        }
+        self.index += 1;
+
+        let mut value: i64 = -1;
+        while !self.current_is("}") {
+            let token = match self.current() {
+                Some(token) if token.token_type == CTokenType::Identifier => token.clone(),
+                _ => return Err(self.warning("Expected the name of an enum constant")),
+            };
+            self.index += 1;
+            self.skip_attributes();
+
+            value = if self.current_is("=") {
+                self.index += 1;
+
+                let start = self.index;
+                let mut depth = 0;
+                while let Some(token) = self.current() {
+                    if depth == 0 && (token.is(",") || token.is("}")) {
+                        break;
+                    }
+                    if token.is("(") {
+                        depth += 1;
+                    } else if token.is(")") {
+                        depth -= 1;
+                    }
+                    self.index += 1;
+                }
+
+                match self.evaluate(&self.tokens[start..self.index]) {
+                    Some(value) => value,
+                    None => {
+                        return Err(error::warning(
+                            token.pos_start.clone(),
+                            token.pos_end.clone(),
+                            format!(
+                                "The value of enum constant '{}' couldn't be evaluated, the enum was skipped",
+                                token.value
+                            )
+                            .as_str(),
+                        ))
+                    }
+                }
+            } else {
+                value.wrapping_add(1)
+            };
+
+            self.constants.insert(token.value.clone(), value);
+            self.items.push((
+                Item::Macro(token.value.clone(), value),
+                token.pos_start.clone(),
+                token.pos_end.clone(),
+            ));
+
+            if self.current_is(",") {
+                self.index += 1;
+            }
+        }
+        self.index += 1;
+
+        Ok(())
+    }
+
+    /// Parses a declarator like `*const names[]` or `(*callback)(int)` and returns the name, the
+    /// type and whether the declared value itself is `const`.
+    fn declarator(
+        &mut self,
+        base: CType,
+        base_const: bool,
+    ) -> Result<(Option<String>, CType, bool), Error> {
+        let (name, derivations) = self.derivations()?;
+
+        let mut c_type = base;
+        let mut is_const = base_const;
+        for derivation in derivations {
+            c_type = match derivation {
+                Derivation::Pointer(pointer_const) => {
+                    let pointer = CType::Pointer(Box::new(c_type), !is_const);
+                    is_const = pointer_const;
+                    pointer
+                }
+                Derivation::Array(size) => CType::Array(Box::new(c_type), size),
+                Derivation::Function(params, variadic) => {
+                    is_const = false;
+                    CType::Function(Box::new(c_type), params, variadic)
+                }
+            };
+        }
+
+        Ok((name, c_type, is_const))
+    }
+
+    /// Returns the name and how the declarator derives the type from the base type, from the
+    /// innermost derivation to the outermost.
+    fn derivations(&mut self) -> Result<(Option<String>, Vec<Derivation>), Error> {
+        let mut pointers = vec![];
+        while self.current_is("*") {
+            self.index += 1;
+            let mut pointer_const = false;
+            while let Some(token) = self.current() {
+                match token.value.as_str() {
+                    "const" | "__const" => pointer_const = true,
+                    "volatile" | "restrict" | "__restrict" | "__restrict__" => {}
+                    _ => break,
+                }
+                self.index += 1;
+            }
+            self.skip_attributes();
+            pointers.push(Derivation::Pointer(pointer_const));
+        }
+
+        let (name, inner) =
+            if self.current_is("(") && (self.peek_is(1, "*") || self.peek_is(1, "(")) {
+                self.index += 1;
+                let inner = self.derivations()?;
+                self.expect(")")?;
+                inner
+            } else {
+                match self.current() {
+                    Some(token) if token.token_type == CTokenType::Identifier => {
+                        let name = token.value.clone();
+                        self.index += 1;
+                        (Some(name), vec![])
+                    }
+                    _ => (None, vec![]),
+                }
+            };
+        self.skip_attributes();
+
+        let mut suffixes = vec![];
+        loop {
+            if self.current_is("[") {
+                self.index += 1;
+                let start = self.index;
+                while !self.current_is("]") {
+                    if self.current().is_none() {
+                        return Err(self.warning("Expected ']'"));
+                    }
+                    self.index += 1;
+                }
+                let size = self.evaluate(&self.tokens[start..self.index]);
+                self.index += 1;
+                suffixes.push(Derivation::Array(size.map(|size| size as u64)));
+            } else if self.current_is("(") {
+                self.index += 1;
+                let (params, variadic) = self.parameters()?;
+                suffixes.push(Derivation::Function(params, variadic));
+            } else {
+                break;
+            }
+        }
+        self.skip_trailing_attributes();
+
+        let mut derivations = pointers;
+        derivations.extend(suffixes.into_iter().rev());
+        derivations.extend(inner);
+
+        Ok((name, derivations))
+    }
+
+    fn parameters(&mut self) -> Result<(Vec<Parameter>, bool), Error> {
+        let mut params = vec![];
+        let mut variadic = false;
+
+        // `(void)` has no parameters
+        if self.current_is("void") && self.peek_is(1, ")") {
+            self.index += 1;
+        }
+
+        while !self.current_is(")") {
+            if self.current_is("...") {
+                self.index += 1;
+                variadic = true;
+                continue;
+            }
+
+            let specifiers = self.specifiers()?;
+            let (name, c_type, _) = self.declarator(specifiers.base, specifiers.is_const)?;
+            params.push((name, c_type));
+
+            if self.current_is(",") {
+                self.index += 1;
+            } else if !self.current_is(")") {
+                return Err(self.warning("Expected ',' or ')' in the parameter list"));
+            }
+        }
+        self.index += 1;
+
+        Ok((params, variadic))
+    }
+
+    // endregion
+
+    // region Umber output
+
+    /// Returns the size and alignment of a type on x86-64 and aarch64 Linux.
+    fn layout(&self, c_type: &CType) -> Result<(u64, u64), String> {
+        Ok(match c_type {
+            CType::Bool | CType::Char => (1, 1),
+            CType::Integer(size, _) => (*size, *size),
+            CType::Float => (4, 4),
+            CType::Double | CType::Pointer(..) => (8, 8),
+            CType::LongDouble => (16, 16),
+            CType::Array(element, size) => {
+                let (element_size, align) = self.layout(element)?;
+                (element_size * size.unwrap_or(0), align)
+            }
+            CType::Struct(key) => {
+                let structure = self.structure(key)?;
+                let mut size: u64 = 0;
+                let mut align: u64 = 1;
+                for (_, field_type) in &structure.fields {
+                    let (field_size, field_align) = self.layout(field_type)?;
+                    size = size.next_multiple_of(field_align) + field_size;
+                    align = align.max(field_align);
+                }
+                (size.next_multiple_of(align), align)
+            }
+            CType::Void | CType::Function(..) | CType::Union | CType::Named(_) => {
+                return Err(format!("the size of {} isn't known", self.describe(c_type)))
+            }
+        })
+    }
+
+    fn structure(&self, key: &str) -> Result<&Structure, String> {
+        let structure = match self.structs.get(key) {
+            Some(structure) => structure,
+            None => return Err(format!("'struct {}' isn't defined", key)),
+        };
+        if let Some(reason) = &structure.unsupported {
+            return Err(format!(
+                "'struct {}' can't be expressed, {}",
+                structure.name.as_deref().unwrap_or(key),
+                reason
+            ));
+        }
+        Ok(structure)
+    }
+
+    fn describe(&self, c_type: &CType) -> String {
+        match c_type {
+            CType::Named(name) => format!("'{}'", name),
+            CType::Union => String::from("unions"),
+            CType::Function(..) => String::from("functions"),
+            _ => String::from("'void'"),
+        }
+    }
+
+    /// Renames parameters, fields and structures which aren't valid identifiers in Umber. Keywords
+    /// get a `_` suffix, and leading underscores are moved to the end.
+    fn umber_name(name: &str) -> String {
+        let trimmed = name.trim_start_matches('_');
+        if trimmed.len() != name.len() && !trimmed.is_empty() {
+            format!("{}{}", trimmed, &name[trimmed.len()..])
+        } else if KEYWORDS.contains(&name) {
+            format!("{}_", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn umber_type(&self, c_type: &CType) -> Result<String, String> {
+        Ok(match c_type {
+            CType::Void => String::from("void"),
+            CType::Bool => String::from("bool"),
+            CType::Char => String::from("char"),
+            CType::Integer(size, signed) => {
+                format!("{}{}", if *signed { "i" } else { "u" }, size * 8)
+            }
+            CType::Double => String::from("f64"),
+            CType::Float => return Err(String::from("'float' isn't supported, only 'double'")),
+            CType::LongDouble => return Err(String::from("'long double' isn't supported")),
+            CType::Pointer(pointee, mutable) => {
+                // pointers to types Umber can't express become void pointers
+                let pointee = match pointee.as_ref() {
+                    CType::Struct(key) => match self.structure(key) {
+                        Ok(structure) if structure.name.is_some() => {
+                            format!(
+                                "struct {}",
+                                Self::umber_name(structure.name.as_ref().unwrap())
+                            )
+                        }
+                        _ => String::from("void"),
+                    },
+                    pointee => self
+                        .umber_type(pointee)
+                        .unwrap_or_else(|_| String::from("void")),
+                };
+                format!("{} *{}", pointee, if *mutable { "mut" } else { "" })
+                    .trim_end()
+                    .to_string()
+            }
+            CType::Array(..) => return Err(String::from("arrays can't be declared in Umber")),
+            CType::Function(..) => return Err(String::from("function types aren't supported")),
+            CType::Struct(_) => {
+                return Err(String::from(
+                    "structures can only be used through pointers in Umber",
+                ))
+            }
+            CType::Union => return Err(String::from("unions aren't supported")),
+            CType::Named(name) => return Err(format!("the type '{}' isn't declared", name)),
+        })
+    }
+
+    /// Converts a parameter type like C does, arrays and functions are passed as pointers.
+    fn parameter_type(&self, c_type: &CType) -> Result<String, String> {
+        match c_type {
+            CType::Array(element, _) => self.umber_type(&CType::Pointer(element.clone(), true)),
+            CType::Function(..) => Ok(String::from("void *")),
+            c_type => self.umber_type(c_type),
+        }
+    }
+
+    /// Lays out the fields of a structure in Umber. Nested structures are flattened and arrays
+    /// are represented by their first element, so every C field is at the same offset. The gaps
+    /// are filled with padding fields, which don't have a name yet.
+    fn struct_fields(
+        &self,
+        key: &str,
+        prefix: &str,
+        fields: &mut Vec<(Option<String>, String)>,
+        offset: &mut u64,
+    ) -> Result<(), String> {
+        let structure = self.structure(key)?;
+
+        for (name, field_type) in &structure.fields {
+            let (size, align) = self.layout(field_type)?;
+            self.padding(fields, offset, offset.next_multiple_of(align));
+            let name = format!("{}{}", prefix, name);
+            let end = *offset + size;
+
+            let mut element_type = field_type;
+            while let CType::Array(element, _) = element_type {
+                element_type = element.as_ref();
+            }
+
+            if size > 0 {
+                if let CType::Struct(element_key) = element_type {
+                    self.struct_fields(element_key, format!("{}_", name).as_str(), fields, offset)?;
+                } else {
+                    fields.push((
+                        Some(Self::umber_name(&name)),
+                        self.umber_type(element_type)?,
+                    ));
+                    *offset += self.layout(element_type)?.0;
+                }
+            }
+
+            self.padding(fields, offset, end);
+        }
+
+        let (size, _) = self.layout(&CType::Struct(key.to_string()))?;
+        if prefix.is_empty() {
+            self.padding(fields, offset, size);
+        }
+
+        Ok(())
+    }
+
+    fn padding(&self, fields: &mut Vec<(Option<String>, String)>, offset: &mut u64, end: u64) {
+        while *offset < end {
+            let size = [8, 4, 2, 1]
+                .into_iter()
+                .find(|size| *offset + size <= end)
+                .unwrap();
+            fields.push((None, format!("u{}", size * 8)));
+            *offset += size;
+        }
+    }
+
+    fn render_item(&self, item: &Item, declared: &mut HashSet<String>) -> Result<String, String> {
+        let name = match item {
+            Item::Macro(name, _) | Item::Function(name, _) | Item::Static(name, _, _) => name,
+            Item::Struct(key) => match &self.structs[key].name {
+                Some(name) => name,
+                // only used by value in other structures, where it is flattened
+                None => return Ok(String::new()),
+            },
+        };
+
+        // redeclarations and both branches of conditionals
+        let namespace = if let Item::Struct(_) = item {
+            "struct "
+        } else {
+            ""
+        };
+        if !declared.insert(format!("{}{}", namespace, name)) {
+            return Ok(String::new());
+        }
+
+        // the names of functions, statics and constants have to stay the same
+        if !matches!(item, Item::Struct(_)) {
+            if KEYWORDS.contains(&name.as_str()) {
+                return Err(format!("'{}' is an Umber keyword", name));
+            }
+            if name.starts_with('_') {
+                return Err(format!("'{}' isn't a valid Umber identifier", name));
+            }
+        }
+
+        Ok(match item {
+            Item::Macro(name, value) => format!("macro {} {};", name, value),
+            Item::Struct(key) => {
+                let mut fields = vec![];
+                self.struct_fields(key, "", &mut fields, &mut 0)?;
+                if fields.is_empty() {
+                    return Err(format!("'struct {}' doesn't have any fields", name));
+                }
+
+                // padding fields are named 'pad<n>', skipping the names of the C fields
+                let mut padding_count = 0;
+                let mut lines = vec![];
+                for (field_name, field_type) in &fields {
+                    let field_name = match field_name {
+                        Some(field_name) => field_name.clone(),
+                        None => loop {
+                            let padding_name = format!("pad{}", padding_count);
+                            padding_count += 1;
+                            if !fields
+                                .iter()
+                                .any(|(name, _)| name.as_ref() == Some(&padding_name))
+                            {
+                                break padding_name;
+                            }
+                        },
+                    };
+                    lines.push(format!("    {}: {}", field_name, field_type));
+                }
+
+                format!(
+                    "struct {} {{\n{}\n}};",
+                    Self::umber_name(name),
+                    lines.join(",\n")
+                )
+            }
+            Item::Function(name, CType::Function(return_type, params, variadic)) => {
+                if *variadic && params.is_empty() {
+                    return Err(String::from(
+                        "variadic functions need an argument before '...'",
+                    ));
+                }
+
+                let mut args = vec![];
+                for (i, (param_name, param_type)) in params.iter().enumerate() {
+                    let param_name = match param_name {
+                        Some(param_name) => Self::umber_name(param_name),
+                        None => format!("arg{}", i),
+                    };
+                    args.push(format!(
+                        "{}: {}",
+                        param_name,
+                        self.parameter_type(param_type)?
+                    ));
+                }
+                if *variadic {
+                    args.push(String::from("..."));
+                }
+
+                format!(
+                    "extern fun {}({}): {};",
+                    name,
+                    args.join(", "),
+                    self.umber_type(return_type)?
+                )
+            }
+            Item::Function(..) => unreachable!(),
+            Item::Static(name, c_type, is_mutable) => format!(
+                "extern static {}{}: {};",
+                if *is_mutable { "mut " } else { "" },
+                name,
+                self.umber_type(c_type)?
+            ),
+        })
+    }
+
+    fn render(&mut self, header_name: &str) -> String {
+        let mut res = format!("// Generated by 'umber bindgen' from '{}'\n", header_name);
+        let mut declared = HashSet::new();
+        let mut last_was_macro = false;
+
+        for (item, pos_start, pos_end) in &self.items {
+            match self.render_item(item, &mut declared) {
+                Ok(rendered) if rendered.is_empty() => {}
+                Ok(rendered) => {
+                    let is_macro = matches!(item, Item::Macro(..));
+                    if !(is_macro && last_was_macro) {
+                        res.push('\n');
+                    }
+                    writeln!(res, "{}", rendered).unwrap();
+                    last_was_macro = is_macro;
+                }
+                Err(reason) => {
+                    let name = match item {
+                        Item::Macro(name, _) => format!("Constant '{}'", name),
+                        Item::Struct(key) => format!(
+                            "Structure '{}'",
+                            self.structs[key].name.as_deref().unwrap_or(key)
+                        ),
+                        Item::Function(name, _) => format!("Function '{}'", name),
+                        Item::Static(name, _, _) => format!("Variable '{}'", name),
+                    };
+                    self.warnings.push(error::warning(
+                        pos_start.clone(),
+                        pos_end.clone(),
+                        format!("{} was skipped, {}", name, reason).as_str(),
+                    ));
+                }
+            }
+        }
+
+        res
+    }
+
+    // endregion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::semantics::Validator;
+
+    fn generate(header: &str) -> Bindings {
+        let bindings = generate_bindings(PathBuf::from("/tmp/bindgen_test.h"), header);
+
+        // the declarations have to be valid Umber
+        let tokens = Lexer::new(
+            PathBuf::from("/tmp/bindgen_test.ub"),
+            bindings.source.clone(),
+        )
+        .make_tokens()
+        .unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+        let validation_res = Validator::new().validate(&ast_root);
+        assert!(
+            validation_res.error().is_none(),
+            "{:?}",
+            validation_res.error()
+        );
+
+        bindings
+    }
+
+    fn warnings(bindings: &Bindings) -> Vec<&str> {
+        bindings
+            .warnings
+            .iter()
+            .map(|warning| warning.details())
+            .collect()
+    }
+
+    #[test]
+    fn test_functions_and_statics() {
+        let bindings = generate(
+            "#include <stddef.h>
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+typedef struct _file FILE;
+extern int errno_value;
+extern const char *const program_name;
+int puts(const char *s);
+FILE *fopen(const char *__restrict path, const char *mode) __attribute__((nonnull(1)));
+size_t fread(void *ptr, size_t size, size_t n, FILE *stream);
+void *memcpy(void *__restrict dst, const void *__restrict src, size_t n);
+void qsort(void *base, size_t n, size_t size, int (*compare)(const void *, const void *));
+char **split(char *text, int counts[]);
+int fun(int match);
+int puts(const char *s);
+int printf(const char *__restrict format, ...);
+int callback(...);
+#ifdef __cplusplus
+}
+#endif",
+        );
+
+        assert_eq!(
+            bindings.source,
+            "// Generated by 'umber bindgen' from 'bindgen_test.h'
+
+extern static mut errno_value: i32;
+
+extern static program_name: char *;
+
+extern fun puts(s: char *): i32;
+
+extern fun fopen(path: char *, mode: char *): void *mut;
+
+extern fun fread(ptr: void *mut, size: u64, n: u64, stream: void *mut): u64;
+
+extern fun memcpy(dst: void *mut, src: void *, n: u64): void *mut;
+
+extern fun qsort(base: void *mut, n: u64, size: u64, compare: void *mut): void;
+
+extern fun split(text: char *mut, counts: i32 *mut): char *mut *mut;
+
+extern fun printf(format: char *, ...): i32;
+"
+        );
+        assert_eq!(
+            warnings(&bindings),
+            vec![
+                "'#include <stddef.h>' isn't followed, generate the bindings of that header separately",
+                "Function 'fun' was skipped, 'fun' is an Umber keyword",
+                "Function 'callback' was skipped, variadic functions need an argument before '...'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_constants() {
+        let bindings = generate(
+            "#ifndef TEST_H
+#define TEST_H
+#define BUFFER_SIZE 0x100
+#define FLAGS (1 << 3) | 1 /* read and write */
+#define NAME \"test\"
+#define MAX(a, b) ((a) > (b) ? (a) : (b))
+enum color { RED, GREEN = BUFFER_SIZE / 2, BLUE };
+typedef enum { OFF = 'a' } state_t;
+state_t get_state(enum color color);
+#endif",
+        );
+
+        assert_eq!(
+            bindings.source,
+            "// Generated by 'umber bindgen' from 'bindgen_test.h'
+
+macro BUFFER_SIZE 256;
+macro FLAGS 9;
+macro RED 0;
+macro GREEN 128;
+macro BLUE 129;
+macro OFF 97;
+
+extern fun get_state(color: i32): i32;
+"
+        );
+        assert_eq!(
+            warnings(&bindings),
+            vec![
+                "Macro 'NAME' is not an integer constant and was skipped",
+                "Function-like macro 'MAX' was skipped",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_structures() {
+        let bindings = generate(
+            "typedef struct {
+    char tag;
+    int values[2];
+    double weight;
+} item_t;
+struct list {
+    item_t first;
+    short pad0;
+    struct list *next;
+    const item_t *items;
+};
+struct flags { unsigned int a : 1; };
+union value { int a; float b; };
+void push(struct list *list, const item_t *item);
+void set(struct flags *flags);
+float average(struct list *list);
+item_t pop(struct list *list);",
+        );
+
+        assert_eq!(
+            bindings.source,
+            "// Generated by 'umber bindgen' from 'bindgen_test.h'
+
+struct item_t {
+    tag: char,
+    pad0: u16,
+    pad1: u8,
+    values: i32,
+    pad2: u32,
+    pad3: u32,
+    weight: f64
+};
+
+struct list {
+    first_tag: char,
+    pad1: u16,
+    pad2: u8,
+    first_values: i32,
+    pad3: u32,
+    pad4: u32,
+    first_weight: f64,
+    pad0: i16,
+    pad5: u32,
+    pad6: u16,
+    next: struct list *mut,
+    items: struct item_t *
+};
+
+extern fun push(list: struct list *mut, item: struct item_t *): void;
+
+extern fun set(flags: void *mut): void;
+"
+        );
+        assert_eq!(
+            warnings(&bindings),
+            vec![
+                "Structure 'flags' was skipped, 'struct flags' can't be expressed, it has bit fields",
+                "Union 'value' was skipped, unions aren't supported",
+                "Function 'average' was skipped, 'float' isn't supported, only 'double'",
+                "Function 'pop' was skipped, structures can only be used through pointers in Umber",
+            ]
+        );
+    }
+}
@@ -0,0 +1,2509 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::constant;
+use crate::constant::Constant;
+use crate::error;
+use crate::error::Error;
+use crate::nodes::address_of_node::AddressOfNode;
+use crate::nodes::array_node::ArrayNode;
+use crate::nodes::assert_node::AssertNode;
+use crate::nodes::binop_node::BinOpNode;
+use crate::nodes::break_node::BreakNode;
+use crate::nodes::call_node::CallNode;
+use crate::nodes::cast_node::CastNode;
+use crate::nodes::char_node::CharNode;
+use crate::nodes::check_node::{Check, CheckNode, CHECK_FAILED_EXIT_CODE};
+use crate::nodes::const_def_node::ConstDefinitionNode;
+use crate::nodes::continue_node::ContinueNode;
+use crate::nodes::copy_node::CopyNode;
+use crate::nodes::extern_node::ExternNode;
+use crate::nodes::f64_to_u64_node::F64ToU64Node;
+use crate::nodes::floating_binop_node::FloatingBinOpNode;
+use crate::nodes::floating_point_node::FloatingPointNode;
+use crate::nodes::for_node::ForNode;
+use crate::nodes::functiondecl_node::FunctionDeclarationNode;
+use crate::nodes::functiondef_node::FunctionDefinitionNode;
+use crate::nodes::if_node::IfNode;
+use crate::nodes::import_node::ImportNode;
+use crate::nodes::memory_node::{MemoryNode, MemoryOperation};
+use crate::nodes::number_node::NumberNode;
+use crate::nodes::offset_node::OffsetNode;
+use crate::nodes::pointer_assign_node::PointerAssignNode;
+use crate::nodes::read_bytes_node::ReadBytesNode;
+use crate::nodes::return_node::ReturnNode;
+use crate::nodes::sizeof_node::SizeOfNode;
+use crate::nodes::slice_node::SliceNode;
+use crate::nodes::stack_allocation_node::StackAllocationNode;
+use crate::nodes::statements_node::StatementsNode;
+use crate::nodes::static_decl_node::StaticDeclarationNode;
+use crate::nodes::static_def_node::StaticDefinitionNode;
+use crate::nodes::string_node::StringNode;
+use crate::nodes::syscall_node::SyscallNode;
+use crate::nodes::u64_to_f64_node::U64ToF64Node;
+use crate::nodes::unaryop_node::UnaryOpNode;
+use crate::nodes::var_node::declare::VarDeclarationNode;
+use crate::nodes::var_node::typed_access::VarTypedAccessNode;
+use crate::nodes::var_node::typed_assign::VarTypedAssignNode;
+use crate::nodes::while_node::WhileNode;
+use crate::nodes::{Node, NodeType};
+use crate::position::Position;
+use crate::syscall::{SyscallTable, TargetObjectType};
+use crate::token::TokenType;
+use crate::utils::unescape;
+use crate::values::value_size::ValueSize;
+use crate::values::value_type::slice_type::{passed_types, SLICE_LENGTH_OFFSET};
+use crate::values::value_type::{ValueType, ValueTypes};
+
+const C_KEYWORDS: [&str; 37] = [
+    "auto",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+    "_Bool",
+    "_Complex",
+    "_Imaginary",
+];
+
+/// The helpers every generated file starts with. Umber values are kept in `uint64_t`, like in the
+/// registers of the assembly backend, and memory is accessed byte wise in little endian order, so
+/// unaligned pointers into structures are fine. Dereferencing null is undefined behavior in C,
+/// which optimizing compilers may remove, so null pointers crash the program explicitly, like
+/// they do with the assembly backend.
+const PRELUDE: &str = "#include <signal.h>
+#include <stdint.h>
+#include <string.h>
+
+typedef union {
+    uint64_t bits;
+    double value;
+} umber_f64_bits;
+
+static inline double umber_f64(uint64_t bits) {
+    umber_f64_bits f;
+    f.bits = bits;
+    return f.value;
+}
+
+static inline uint64_t umber_bits(double value) {
+    umber_f64_bits f;
+    f.value = value;
+    return f.bits;
+}
+
+static void umber_null_dereference(void) {
+    raise(SIGSEGV);
+}
+
+static inline uint64_t umber_load(uint64_t address, int size) {
+    const uint8_t *bytes = (const uint8_t *)(uintptr_t)address;
+    uint64_t value = 0;
+    int i;
+    if (address == 0) {
+        umber_null_dereference();
+    }
+    for (i = size - 1; i >= 0; i--) {
+        value = value << 8 | bytes[i];
+    }
+    return value;
+}
+
+static inline void umber_store(uint64_t address, int size, uint64_t value) {
+    uint8_t *bytes = (uint8_t *)(uintptr_t)address;
+    int i;
+    if (address == 0) {
+        umber_null_dereference();
+    }
+    for (i = 0; i < size; i++) {
+        bytes[i] = (uint8_t)(value >> (8 * i));
+    }
+}
+
+static inline void umber_fill(uint64_t address, uint64_t value, uint64_t size) {
+    memset((void *)(uintptr_t)address, (uint8_t)value, size);
+}
+
+static inline void umber_zero(uint64_t address, uint64_t size) {
+    umber_fill(address, 0, size);
+}
+
+static inline void umber_copy(uint64_t destination, uint64_t source, uint64_t size) {
+    memcpy((void *)(uintptr_t)destination, (const void *)(uintptr_t)source, size);
+}
+
+static inline void umber_move(uint64_t destination, uint64_t source, uint64_t size) {
+    memmove((void *)(uintptr_t)destination, (const void *)(uintptr_t)source, size);
+}
+
+static inline uint64_t umber_syscall(uint64_t number, uint64_t arg1, uint64_t arg2, uint64_t arg3) {
+#if defined(__x86_64__)
+    uint64_t res;
+    register uint64_t r10 __asm__(\"r10\") = 0;
+    __asm__ volatile(\"syscall\"
+                     : \"=a\"(res)
+                     : \"a\"(number), \"D\"(arg1), \"S\"(arg2), \"d\"(arg3), \"r\"(r10)
+                     : \"rcx\", \"r11\", \"memory\");
+    return res;
+#elif defined(__aarch64__)
+    register uint64_t x8 __asm__(\"x8\") = number;
+    register uint64_t x0 __asm__(\"x0\") = arg1;
+    register uint64_t x1 __asm__(\"x1\") = arg2;
+    register uint64_t x2 __asm__(\"x2\") = arg3;
+    register uint64_t x3 __asm__(\"x3\") = 0;
+    __asm__ volatile(\"svc 0\" : \"+r\"(x0) : \"r\"(x8), \"r\"(x1), \"r\"(x2), \"r\"(x3) : \"memory\");
+    return x0;
+#else
+#error \"syscall is only supported on x86-64 and aarch64\"
+#endif
+}
+";
+
+/// The result of generating an expression: C code evaluating to a `uint64_t`.
+struct Expr {
+    code: String,
+    /// Whether the value can't change anymore, like constants and temporaries. Other expressions
+    /// read variables or memory and have to be evaluated before any following side effect.
+    stable: bool,
+}
+
+impl Expr {
+    fn stable(code: String) -> Self {
+        Expr { code, stable: true }
+    }
+
+    fn pure(code: String) -> Self {
+        Expr {
+            code,
+            stable: false,
+        }
+    }
+
+    fn zero() -> Self {
+        Expr::stable(String::from("0"))
+    }
+}
+
+#[derive(Clone)]
+struct Variable {
+    c_name: String,
+    is_f64: bool,
+}
+
+struct Signature {
+    /// The types of the values passed to the function, with slices split into pointer and length
+    arg_types: Vec<Box<dyn ValueType>>,
+    return_type: Box<dyn ValueType>,
+}
+
+struct Loop {
+    label: Option<String>,
+    /// The label `continue` jumps to, for loops that have to run their `next` expression first
+    /// and labeled loops that can be continued from a nested loop
+    continue_label: Option<String>,
+    continue_used: bool,
+    /// The label after the loop, which `break` jumps to when it leaves nested loops too
+    break_label: String,
+    break_used: bool,
+}
+
+fn c_type(value_type: &dyn ValueType) -> &'static str {
+    match value_type.value_type() {
+        ValueTypes::F64 => "double",
+        ValueTypes::Void => "void",
+        _ => c_integer_type(value_type.get_size()),
+    }
+}
+
+fn c_integer_type(size: ValueSize) -> &'static str {
+    match size {
+        ValueSize::Byte => "uint8_t",
+        ValueSize::Word => "uint16_t",
+        ValueSize::Dword => "uint32_t",
+        ValueSize::Qword => "uint64_t",
+    }
+}
+
+fn c_number(value: u64) -> String {
+    if value <= i32::MAX as u64 {
+        value.to_string()
+    } else {
+        format!("UINT64_C({})", value)
+    }
+}
+
+/// Writes a double as a constant expression, which can be used to initialize statics.
+fn c_double(value: f64) -> String {
+    if value.is_nan() {
+        String::from("(0.0 / 0.0)")
+    } else if value.is_infinite() {
+        format!("({}1.0 / 0.0)", if value < 0.0 { "-" } else { "" })
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+fn truncate(value: u64, size: ValueSize) -> u64 {
+    match size {
+        ValueSize::Byte => value & 0xff,
+        ValueSize::Word => value & 0xffff,
+        ValueSize::Dword => value & 0xffff_ffff,
+        ValueSize::Qword => value,
+    }
+}
+
+/// Converts a C expression of the given type to the `uint64_t` Umber works with.
+fn from_c_value(code: String, value_type: &dyn ValueType) -> String {
+    match value_type.value_type() {
+        ValueTypes::F64 => format!("umber_bits({})", code),
+        _ if value_type.get_size() == ValueSize::Qword => code,
+        _ => format!("(uint64_t){}", code),
+    }
+}
+
+/// Converts a `uint64_t` to a C value of the given type.
+fn to_c_value(code: String, value_type: &dyn ValueType) -> String {
+    match value_type.value_type() {
+        ValueTypes::F64 => format!("umber_f64({})", code),
+        _ => code,
+    }
+}
+
+/// Converts an argument passed to the `...` of a variadic function to the C type of its Umber
+/// type, so the C compiler applies the default argument promotions (e.g. sign extending `i8`s).
+fn to_c_variadic_value(code: String, value_type: &dyn ValueType) -> String {
+    match value_type.value_type() {
+        ValueTypes::F64 => format!("umber_f64({})", code),
+        ValueTypes::I8 => format!("(int8_t)({})", code),
+        ValueTypes::I16 => format!("(int16_t)({})", code),
+        ValueTypes::I32 => format!("(int32_t)({})", code),
+        _ => match value_type.get_size() {
+            ValueSize::Qword => code,
+            size => format!("({})({})", c_integer_type(size), code),
+        },
+    }
+}
+
+/// Writes the bytes as a C string literal, escaping everything that is not printable ASCII.
+fn c_string_literal(bytes: &[u8]) -> String {
+    let mut literal = String::from("\"");
+    for byte in bytes {
+        match byte {
+            b'"' | b'\\' => {
+                literal.push('\\');
+                literal.push(*byte as char);
+            }
+            // '?' is escaped to prevent trigraphs
+            b' '..=b'~' if *byte != b'?' => literal.push(*byte as char),
+            _ => literal.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Generates portable C99 from validated ASTs, as an alternative to the NASM backend. The result is
+/// compiled with the system's C compiler, only `syscall` needs a small inline assembly shim.
+/// Like in the assembly backend, values are handled as 64 bit registers: loads zero extend, and
+/// division, right shifts and comparisons are signed.
+pub struct CCompiler {
+    target_object_type: TargetObjectType,
+
+    functions: HashMap<String, Signature>,
+    externs: Vec<String>,
+    globals: HashMap<String, Variable>,
+    strings: HashMap<Vec<u8>, String>,
+    /// The number of arrays and structs, that the values of statics point to
+    data_count: usize,
+
+    /// The declarations of strings, statics and externs
+    declarations: Vec<String>,
+    prototypes: Vec<String>,
+    definitions: Vec<String>,
+
+    /// Whether the Umber `main` is renamed, because the generated C `main` calls it
+    rename_main: bool,
+
+    // region State of the current function
+    lines: Vec<(usize, String)>,
+    locals: Vec<String>,
+    indent: usize,
+    scopes: Vec<HashMap<String, Variable>>,
+    used_names: HashSet<String>,
+    loops: Vec<Loop>,
+    return_type: Option<Box<dyn ValueType>>,
+    // endregion
+}
+
+impl CCompiler {
+    pub fn new(target_object_type: TargetObjectType) -> Self {
+        CCompiler {
+            target_object_type,
+            functions: HashMap::new(),
+            externs: vec![],
+            globals: HashMap::new(),
+            strings: HashMap::new(),
+            data_count: 0,
+            declarations: vec![],
+            prototypes: vec![],
+            definitions: vec![],
+            rename_main: false,
+            lines: vec![],
+            locals: vec![],
+            indent: 0,
+            scopes: vec![],
+            used_names: HashSet::new(),
+            loops: vec![],
+            return_type: None,
+        }
+    }
+
+    // region Helper functions
+
+    fn unsupported(node: &dyn Node, details: &str) -> Error {
+        error::not_yet_implemented_error(node.pos_start().clone(), node.pos_end().clone(), details)
+    }
+
+    fn line(&mut self, line: String) {
+        self.lines.push((self.indent, line));
+    }
+
+    fn function_name(&self, name: &str) -> String {
+        if self.rename_main && name == "main" {
+            String::from("umber_main")
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Returns an unused name in the current function, based on the given one.
+    fn fresh_name(&mut self, name: &str) -> String {
+        // the hidden variables of `for` loops contain a '.', e.g. `i.end`
+        let name = name.replace('.', "_");
+        let name = name.as_str();
+        let mut candidate = if C_KEYWORDS.contains(&name) {
+            format!("{}_", name)
+        } else {
+            name.to_string()
+        };
+
+        let mut i = 1;
+        while self.used_names.contains(&candidate) {
+            candidate = format!("{}_{}", name, i);
+            i += 1;
+        }
+
+        self.used_names.insert(candidate.clone());
+        candidate
+    }
+
+    /// Returns the index of the loop `break` or `continue` with the label jumps out of. Without a
+    /// label, it's the innermost loop.
+    fn find_loop(&self, label: &Option<String>) -> usize {
+        match label {
+            Some(label) => self
+                .loops
+                .iter()
+                .rposition(|l| l.label.as_ref() == Some(label))
+                .unwrap(),
+            None => self.loops.len() - 1,
+        }
+    }
+
+    fn temporary(&mut self, code: String) -> Expr {
+        let name = self.fresh_name("_t");
+        self.line(format!("uint64_t {} = {};", name, code));
+        Expr::stable(name)
+    }
+
+    fn declare_local(&mut self, name: &str, value_type: &dyn ValueType) -> Variable {
+        let variable = Variable {
+            c_name: self.fresh_name(name),
+            is_f64: value_type.value_type() == ValueTypes::F64,
+        };
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), variable.clone());
+        variable
+    }
+
+    fn get_var(&self, name: &str) -> Variable {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .or_else(|| self.globals.get(name))
+            .cloned()
+            .unwrap_or_else(|| Variable {
+                c_name: name.to_string(),
+                is_f64: false,
+            })
+    }
+
+    fn read_var(variable: &Variable, value_type: &dyn ValueType) -> String {
+        if variable.is_f64 {
+            format!("umber_bits({})", variable.c_name)
+        } else {
+            from_c_value(variable.c_name.clone(), value_type)
+        }
+    }
+
+    fn assign_var(variable: &Variable, value: String) -> String {
+        if variable.is_f64 {
+            format!("{} = umber_f64({});", variable.c_name, value)
+        } else {
+            format!("{} = {};", variable.c_name, value)
+        }
+    }
+
+    /// Declares a buffer on the stack of the current function and returns its address. Like in the
+    /// assembly backend, the buffer is reused every time the node is evaluated.
+    fn stack_buffer(&mut self, size: u64) -> Expr {
+        let name = self.fresh_name("_a");
+        self.locals
+            .push(format!("uint8_t {}[{}];", name, size.max(1)));
+        Expr::stable(format!("(uint64_t)(uintptr_t){}", name))
+    }
+
+    fn string_address(&mut self, bytes: Vec<u8>) -> Expr {
+        let label = match self.strings.get(&bytes) {
+            Some(label) => label.clone(),
+            None => {
+                let label = format!("S{}", self.strings.len());
+                // strings are writable, like in the data section of the assembly backend
+                self.declarations.push(format!(
+                    "static char {}[] = {};",
+                    label,
+                    c_string_literal(&bytes)
+                ));
+                self.strings.insert(bytes, label.clone());
+                label
+            }
+        };
+
+        Expr::stable(format!("(uint64_t)(uintptr_t){}", label))
+    }
+
+    /// Checks, whether the given node evaluates to a floating point number, as unary operations
+    /// on them are not lowered by the validator.
+    fn is_floating_point(&self, node: &Box<dyn Node>) -> bool {
+        match node.node_type() {
+            NodeType::FloatingPoint | NodeType::U64ToF64 => true,
+            NodeType::FloatingBinOp => !matches!(
+                node.as_any()
+                    .downcast_ref::<FloatingBinOpNode>()
+                    .unwrap()
+                    .op_token()
+                    .token_type(),
+                TokenType::Ee
+                    | TokenType::Ne
+                    | TokenType::Gt
+                    | TokenType::Lt
+                    | TokenType::Gte
+                    | TokenType::Lte
+            ),
+            NodeType::UnaryOp => {
+                self.is_floating_point(node.as_any().downcast_ref::<UnaryOpNode>().unwrap().node())
+            }
+            NodeType::VarTypedAccess => {
+                node.as_any()
+                    .downcast_ref::<VarTypedAccessNode>()
+                    .unwrap()
+                    .value_type()
+                    .value_type()
+                    == ValueTypes::F64
+            }
+            NodeType::Call => self
+                .functions
+                .get(
+                    node.as_any()
+                        .downcast_ref::<CallNode>()
+                        .unwrap()
+                        .func_to_call(),
+                )
+                .is_some_and(|f| f.return_type.value_type() == ValueTypes::F64),
+            _ => false,
+        }
+    }
+
+    // endregion
+}
+
+impl CCompiler {
+    // region Declarations
+
+    /// Collects the signatures of all functions and defines the statics and externs, so they can be
+    /// used before their definition in the C file.
+    fn declare(&mut self, node: &Box<dyn Node>) -> Result<(), Error> {
+        match node.node_type() {
+            NodeType::Statements => {
+                for statement in node
+                    .as_any()
+                    .downcast_ref::<StatementsNode>()
+                    .unwrap()
+                    .statement_nodes()
+                {
+                    self.declare(statement)?;
+                }
+            }
+            NodeType::Import => {
+                self.declare(node.as_any().downcast_ref::<ImportNode>().unwrap().node())?
+            }
+            NodeType::FunctionDef => {
+                let func_def_node = node
+                    .as_any()
+                    .downcast_ref::<FunctionDefinitionNode>()
+                    .unwrap();
+                self.functions.insert(
+                    func_def_node.var_name().to_string(),
+                    Signature {
+                        arg_types: passed_types(
+                            &func_def_node
+                                .args()
+                                .iter()
+                                .map(|(_, t)| t.clone())
+                                .collect::<Vec<_>>(),
+                        ),
+                        return_type: func_def_node.return_type().clone(),
+                    },
+                );
+            }
+            NodeType::Extern => {
+                self.declare_extern(node.as_any().downcast_ref::<ExternNode>().unwrap())
+            }
+            NodeType::StaticDef => {
+                let static_def_node = node
+                    .as_any()
+                    .downcast_ref::<StaticDefinitionNode>()
+                    .unwrap();
+                self.define_global(
+                    format!("ST_{}", static_def_node.name()),
+                    static_def_node.name(),
+                    static_def_node.value_type().as_ref(),
+                    static_def_node.value(),
+                    !*static_def_node.is_mutable(),
+                )?;
+            }
+            NodeType::ConstDef => {
+                let const_def_node = node.as_any().downcast_ref::<ConstDefinitionNode>().unwrap();
+                self.define_global(
+                    format!("C_{}", const_def_node.name()),
+                    const_def_node.name(),
+                    const_def_node.value_type().as_ref(),
+                    const_def_node.value(),
+                    true,
+                )?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn declare_extern(&mut self, extern_node: &ExternNode) {
+        let statement = extern_node.top_level_statement();
+
+        match statement.node_type() {
+            NodeType::FunctionDecl => {
+                let func_decl_node = statement
+                    .as_any()
+                    .downcast_ref::<FunctionDeclarationNode>()
+                    .unwrap();
+                let name = func_decl_node.var_name().to_string();
+
+                let arg_types: Vec<Box<dyn ValueType>> = func_decl_node
+                    .args()
+                    .iter()
+                    .map(|(_, t)| t.clone())
+                    .collect();
+                let args: Vec<String> = passed_types(&arg_types)
+                    .iter()
+                    .map(|arg_type| c_type(arg_type.as_ref()).to_string())
+                    .collect();
+                self.declarations.push(format!(
+                    "extern {} {}({}{});",
+                    c_type(func_decl_node.return_type().as_ref()),
+                    name,
+                    if args.is_empty() {
+                        String::from("void")
+                    } else {
+                        args.join(", ")
+                    },
+                    if func_decl_node.is_variadic() {
+                        ", ..."
+                    } else {
+                        ""
+                    }
+                ));
+
+                self.functions.insert(
+                    name.clone(),
+                    Signature {
+                        arg_types: passed_types(
+                            &func_decl_node
+                                .args()
+                                .iter()
+                                .map(|(_, t)| t.clone())
+                                .collect::<Vec<_>>(),
+                        ),
+                        return_type: func_decl_node.return_type().clone(),
+                    },
+                );
+                self.externs.push(name);
+            }
+            NodeType::StaticDecl => {
+                let static_decl_node = statement
+                    .as_any()
+                    .downcast_ref::<StaticDeclarationNode>()
+                    .unwrap();
+
+                self.declarations.push(format!(
+                    "extern {} {};",
+                    c_type(static_decl_node.value_type().as_ref()),
+                    static_decl_node.name()
+                ));
+                self.globals.insert(
+                    static_decl_node.name().to_string(),
+                    Variable {
+                        c_name: static_decl_node.name().to_string(),
+                        is_f64: static_decl_node.value_type().value_type() == ValueTypes::F64,
+                    },
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Defines a static or constant, whose value has to be known at compile time.
+    fn define_global(
+        &mut self,
+        c_name: String,
+        name: &str,
+        value_type: &dyn ValueType,
+        value: &Box<dyn Node>,
+        is_const: bool,
+    ) -> Result<(), Error> {
+        let initializer = match constant::evaluate(value) {
+            // unlike other numbers, doubles aren't stored as their bits
+            Some(Constant::Float(f)) => c_double(f),
+            Some(value) => self.constant_initializer(&value, value_type.get_size()),
+            None => {
+                return Err(Self::unsupported(
+                    value.as_ref(),
+                    format!(
+                        "The value of '{}' has to be a constant for the C backend!",
+                        name
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+
+        let variable = Variable {
+            c_name,
+            is_f64: value_type.value_type() == ValueTypes::F64,
+        };
+
+        self.declarations.push(format!(
+            "static {}{} {} = {};",
+            if is_const { "const " } else { "" },
+            c_type(value_type),
+            variable.c_name,
+            initializer
+        ));
+        self.globals.insert(name.to_string(), variable);
+
+        Ok(())
+    }
+
+    /// Returns the C constant expression for a value known at compile time. The arrays and structs
+    /// addresses point to are declared as well.
+    fn constant_initializer(&mut self, value: &Constant, size: ValueSize) -> String {
+        match value {
+            Constant::Integer(_) | Constant::Float(_) => {
+                c_number(truncate(value.bits().unwrap(), size))
+            }
+            Constant::String(bytes) => self.string_address(bytes.clone()).code,
+            Constant::Array {
+                element_size,
+                elements,
+            } => {
+                let elements: Vec<String> = elements
+                    .iter()
+                    .map(|element| self.constant_initializer(element, *element_size))
+                    .collect();
+
+                let label = format!("D{}", self.data_count);
+                self.data_count += 1;
+                self.declarations.push(format!(
+                    "static {} {}[] = {{{}}};",
+                    c_integer_type(*element_size),
+                    label,
+                    elements.join(", ")
+                ));
+                format!("(uint64_t)(uintptr_t){}", label)
+            }
+            Constant::Zeroed(bytes) => {
+                let label = format!("D{}", self.data_count);
+                self.data_count += 1;
+                self.declarations
+                    .push(format!("static uint8_t {}[{}];", label, (*bytes).max(1)));
+                format!("(uint64_t)(uintptr_t){}", label)
+            }
+        }
+    }
+
+    // endregion
+
+    // region Functions
+
+    fn function(&mut self, node: &FunctionDefinitionNode) -> Result<(), Error> {
+        let name = self.function_name(node.var_name());
+
+        self.lines.clear();
+        self.locals.clear();
+        self.indent = 1;
+        self.scopes = vec![HashMap::new()];
+        self.loops.clear();
+        self.return_type = Some(node.return_type().clone());
+
+        // locals must not shadow the functions and statics they might use
+        self.used_names = self
+            .functions
+            .keys()
+            .map(|function| self.function_name(function))
+            .chain(self.globals.values().map(|global| global.c_name.clone()))
+            .collect();
+
+        let mut args: Vec<String> = vec![];
+        for (arg_name, arg_type) in node.args() {
+            let variable = self.declare_local(arg_name, arg_type.as_ref());
+
+            // the pointer and length of slices are stored in a buffer, which is the slice
+            if arg_type.value_type() == ValueTypes::Slice {
+                let pointer = self.fresh_name(&format!("{}_ptr", arg_name));
+                let length = self.fresh_name(&format!("{}_len", arg_name));
+                args.push(format!("uint64_t {}", pointer));
+                args.push(format!("uint64_t {}", length));
+
+                let slice = self.stack_buffer(arg_type.size_in_bytes());
+                self.line(format!("umber_store({}, 8, {});", slice.code, pointer));
+                self.line(format!(
+                    "umber_store({} + {}, 8, {});",
+                    slice.code, SLICE_LENGTH_OFFSET, length
+                ));
+                self.line(format!("uint64_t {} = {};", variable.c_name, slice.code));
+                continue;
+            }
+
+            args.push(format!("{} {}", c_type(arg_type.as_ref()), variable.c_name));
+        }
+
+        let signature = format!(
+            "{} {}({})",
+            c_type(node.return_type().as_ref()),
+            name,
+            if args.is_empty() {
+                String::from("void")
+            } else {
+                args.join(", ")
+            }
+        );
+
+        let body = node.body_node();
+        if body.node_type() == NodeType::Statements {
+            for statement in body
+                .as_any()
+                .downcast_ref::<StatementsNode>()
+                .unwrap()
+                .statement_nodes()
+            {
+                self.statement(statement)?;
+            }
+        } else {
+            self.statement(body)?;
+        }
+
+        // like falling off the end of a function in the assembly backend, the result is undefined
+        let ends_with_return = self
+            .lines
+            .last()
+            .is_some_and(|(indent, line)| *indent == 1 && line.starts_with("return"));
+        if node.return_type().value_type() != ValueTypes::Void && !ends_with_return {
+            self.line(String::from("return 0;"));
+        }
+
+        let mut definition = String::new();
+        writeln!(definition, "{} {{", signature).unwrap();
+        for local in &self.locals {
+            writeln!(definition, "    {}", local).unwrap();
+        }
+        for (indent, line) in &self.lines {
+            if line.ends_with(':') || line.ends_with(":;") {
+                // labels are written one level to the left
+                writeln!(definition, "{}{}", "    ".repeat(indent - 1), line).unwrap();
+            } else {
+                writeln!(definition, "{}{}", "    ".repeat(*indent), line).unwrap();
+            }
+        }
+        writeln!(definition, "}}").unwrap();
+
+        self.prototypes.push(format!("{};", signature));
+        self.definitions.push(definition);
+
+        Ok(())
+    }
+
+    fn top_level(&mut self, node: &Box<dyn Node>) -> Result<(), Error> {
+        match node.node_type() {
+            NodeType::Statements => {
+                for statement in node
+                    .as_any()
+                    .downcast_ref::<StatementsNode>()
+                    .unwrap()
+                    .statement_nodes()
+                {
+                    self.top_level(statement)?;
+                }
+                Ok(())
+            }
+            NodeType::Import => {
+                self.top_level(node.as_any().downcast_ref::<ImportNode>().unwrap().node())
+            }
+            NodeType::FunctionDef => self.function(
+                node.as_any()
+                    .downcast_ref::<FunctionDefinitionNode>()
+                    .unwrap(),
+            ),
+            NodeType::Assembly => Err(Self::unsupported(
+                node.as_ref(),
+                "Inline assembly is not supported by the C backend!",
+            )),
+            // everything else was declared before
+            _ => Ok(()),
+        }
+    }
+
+    // endregion
+
+    // region Statements
+
+    /// Generates the statements of a block in a new scope, without the braces.
+    /// Generates the statements of a block. If `result` is given, the value of the block is
+    /// assigned to that variable.
+    fn block(&mut self, node: &Box<dyn Node>, result: Option<&str>) -> Result<(), Error> {
+        self.indent += 1;
+        self.scopes.push(HashMap::new());
+
+        let res = if node.node_type() == NodeType::Statements {
+            let statement_nodes = node
+                .as_any()
+                .downcast_ref::<StatementsNode>()
+                .unwrap()
+                .statement_nodes();
+            statement_nodes
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, statement)| match result {
+                    Some(result) if i == statement_nodes.len() - 1 => {
+                        let value = self.expression(statement)?;
+                        self.line(format!("{} = {};", result, value.code));
+                        Ok(())
+                    }
+                    _ => self.statement(statement),
+                })
+        } else {
+            self.statement(node)
+        };
+
+        self.scopes.pop();
+        self.indent -= 1;
+        res
+    }
+
+    fn statement(&mut self, node: &Box<dyn Node>) -> Result<(), Error> {
+        match node.node_type() {
+            NodeType::Statements => {
+                self.line(String::from("{"));
+                self.block(node, None)?;
+                self.line(String::from("}"));
+            }
+            NodeType::VarDeclaration => {
+                let var_declaration_node =
+                    node.as_any().downcast_ref::<VarDeclarationNode>().unwrap();
+                let value = self.expression(var_declaration_node.value_node())?;
+
+                let var_type = var_declaration_node.var_type();
+                let variable =
+                    self.declare_local(var_declaration_node.var_name(), var_type.as_ref());
+                self.line(format!(
+                    "{} {} = {};",
+                    c_type(var_type.as_ref()),
+                    variable.c_name,
+                    to_c_value(value.code, var_type.as_ref())
+                ));
+            }
+            NodeType::VarTypedAssign => {
+                let var_assign_node = node.as_any().downcast_ref::<VarTypedAssignNode>().unwrap();
+                let value = self.expression(var_assign_node.value_node())?;
+
+                let variable = self.get_var(var_assign_node.var_name());
+                self.line(Self::assign_var(&variable, value.code));
+            }
+            NodeType::PointerAssign => {
+                let pointer_assign_node =
+                    node.as_any().downcast_ref::<PointerAssignNode>().unwrap();
+                let [ptr, value] =
+                    self.sequence([pointer_assign_node.ptr(), pointer_assign_node.value()])?;
+
+                self.line(format!(
+                    "umber_store({}, {}, {});",
+                    ptr.code,
+                    pointer_assign_node
+                        .pointee_type()
+                        .get_size()
+                        .get_size_in_bytes(),
+                    value.code
+                ));
+            }
+            NodeType::Call => {
+                let call = self.call(node.as_any().downcast_ref::<CallNode>().unwrap())?;
+                self.line(format!("{};", call));
+            }
+            NodeType::Syscall => {
+                let syscall = self.syscall(node.as_any().downcast_ref::<SyscallNode>().unwrap())?;
+                self.line(format!("{};", syscall));
+            }
+            NodeType::Memory => {
+                let memory_node = node.as_any().downcast_ref::<MemoryNode>().unwrap();
+                let [destination, source, count] = self.sequence([
+                    memory_node.destination(),
+                    memory_node.source(),
+                    memory_node.count(),
+                ])?;
+
+                let function = match memory_node.operation() {
+                    MemoryOperation::Set => "umber_fill",
+                    MemoryOperation::Copy => "umber_copy",
+                    MemoryOperation::Move => "umber_move",
+                };
+                self.line(format!(
+                    "{}({}, {}, {});",
+                    function, destination.code, source.code, count.code
+                ));
+            }
+            NodeType::Return => {
+                let return_node = node.as_any().downcast_ref::<ReturnNode>().unwrap();
+                let return_type = self.return_type.clone().unwrap();
+
+                match return_node.node_to_return() {
+                    Some(node_to_return) if return_type.value_type() != ValueTypes::Void => {
+                        let value = self.expression(node_to_return)?;
+                        self.line(format!(
+                            "return {};",
+                            to_c_value(value.code, return_type.as_ref())
+                        ));
+                    }
+                    Some(node_to_return) => {
+                        self.statement(node_to_return)?;
+                        self.line(String::from("return;"));
+                    }
+                    None if return_type.value_type() != ValueTypes::Void => {
+                        self.line(String::from("return 0;"))
+                    }
+                    None => self.line(String::from("return;")),
+                }
+            }
+            NodeType::Break => {
+                let break_node = node.as_any().downcast_ref::<BreakNode>().unwrap();
+                let index = self.find_loop(break_node.label());
+
+                let line = if index == self.loops.len() - 1 {
+                    String::from("break;")
+                } else {
+                    let target = &mut self.loops[index];
+                    target.break_used = true;
+                    format!("goto {};", target.break_label)
+                };
+                self.line(line);
+            }
+            NodeType::Continue => {
+                let continue_node = node.as_any().downcast_ref::<ContinueNode>().unwrap();
+                let index = self.find_loop(continue_node.label());
+                let is_innermost = index == self.loops.len() - 1;
+
+                let target = &mut self.loops[index];
+                let line = match &target.continue_label {
+                    Some(label) => {
+                        target.continue_used = true;
+                        format!("goto {};", label)
+                    }
+                    None if is_innermost => String::from("continue;"),
+                    None => unreachable!("Only labeled loops can be continued from a nested loop"),
+                };
+                self.line(line);
+            }
+            NodeType::Assert => {
+                let assert_node = node.as_any().downcast_ref::<AssertNode>().unwrap();
+                let condition = self.expression(assert_node.condition_node())?;
+
+                let pos = assert_node.pos_start();
+                let message = format!(
+                    "{}:{}:{}: Assertion failed!\n",
+                    pos.file_name().display(),
+                    pos.line() + 1,
+                    pos.col() + 1
+                );
+
+                self.line(format!("if (!({})) {{", condition.code));
+                self.indent += 1;
+                self.trap(message, 1);
+                self.indent -= 1;
+                self.line(String::from("}"));
+            }
+            NodeType::While => {
+                self.while_node(node.as_any().downcast_ref::<WhileNode>().unwrap())?
+            }
+            NodeType::For => self.for_node(node.as_any().downcast_ref::<ForNode>().unwrap())?,
+            NodeType::If => self.if_node(node.as_any().downcast_ref::<IfNode>().unwrap(), None)?,
+            NodeType::Import => {
+                self.statement(node.as_any().downcast_ref::<ImportNode>().unwrap().node())?
+            }
+            NodeType::FunctionDef
+            | NodeType::FunctionDecl
+            | NodeType::StaticDecl
+            | NodeType::StaticDef
+            | NodeType::ConstDef
+            | NodeType::StructDef
+            | NodeType::Extern
+            | NodeType::MacroDef
+            | NodeType::Ignored => {}
+            _ => {
+                // the side effects of expressions are emitted as statements, so the value itself
+                // can be dropped
+                self.expression(node)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generates the condition of a loop, breaking out of it, if it doesn't hold.
+    fn loop_condition(&mut self, condition: &Box<dyn Node>) -> Result<(), Error> {
+        let condition = self.expression(condition)?;
+        self.line(format!("if (!({})) {{", condition.code));
+        self.indent += 1;
+        self.line(String::from("break;"));
+        self.indent -= 1;
+        self.line(String::from("}"));
+        Ok(())
+    }
+
+    /// Generates the condition into separate lines, to find out whether it needs any statements.
+    /// Writes the message to stderr and exits with the given code.
+    fn trap(&mut self, message: String, exit_code: u64) {
+        // there are no stable syscall numbers on Windows, so we can only exit there
+        if !matches!(self.target_object_type, TargetObjectType::Win64) {
+            let message_address = self.string_address(message.clone().into_bytes());
+            self.line(format!(
+                "umber_syscall({}, 2, {}, {});",
+                SyscallTable::Write.code(self.target_object_type),
+                message_address.code,
+                message.len()
+            ));
+        }
+        self.line(format!(
+            "umber_syscall({}, {}, 0, 0);",
+            SyscallTable::Exit.code(self.target_object_type),
+            exit_code
+        ));
+    }
+
+    fn check(&mut self, node: &CheckNode) -> Result<Expr, Error> {
+        let (value, failed) = match *node.check() {
+            Check::Overflow { size, signed } => {
+                let bin_op_node = node.node().as_any().downcast_ref::<BinOpNode>().unwrap();
+                let [left, right] =
+                    self.sequence([bin_op_node.left_node(), bin_op_node.right_node()])?;
+
+                // the builtins check, whether the exact result fits into the type of the result
+                let bits = size.get_size_in_bytes() as u32 * 8;
+                let c_type = format!("{}int{}_t", if signed { "" } else { "u" }, bits);
+                let result = self.fresh_name("_t");
+                self.line(format!("{} {};", c_type, result));
+
+                let builtin = match bin_op_node.op_token().token_type() {
+                    TokenType::Plus => "add",
+                    TokenType::Minus => "sub",
+                    _ => "mul",
+                };
+                let value = if size == ValueSize::Qword {
+                    result.clone()
+                } else {
+                    format!("(uint64_t)(uint{}_t){}", bits, result)
+                };
+                (
+                    Expr::stable(value),
+                    format!(
+                        "__builtin_{}_overflow(({}){}, ({}){}, &{})",
+                        builtin, c_type, left.code, c_type, right.code, result
+                    ),
+                )
+            }
+            Check::SliceBounds | Check::SliceRange => {
+                let [value, length] = self.sequence([node.node(), node.length()])?;
+                let comparison = match *node.check() {
+                    Check::SliceBounds => ">=",
+                    _ => ">",
+                };
+                let failed = format!("{} {} {}", value.code, comparison, length.code);
+                (value, failed)
+            }
+            check => {
+                let value = self.expression(node.node())?;
+                let value = if value.stable {
+                    value
+                } else {
+                    self.temporary(value.code)
+                };
+
+                let failed = match check {
+                    Check::Bounds(length) => format!("{} >= {}", value.code, c_number(length)),
+                    _ => format!("{} == 0", value.code),
+                };
+                (value, failed)
+            }
+        };
+
+        self.line(format!("if ({}) {{", failed));
+        self.indent += 1;
+        self.trap(node.failure_message(), CHECK_FAILED_EXIT_CODE);
+        self.indent -= 1;
+        self.line(String::from("}"));
+
+        Ok(value)
+    }
+
+    fn separate_condition(
+        &mut self,
+        condition: &Box<dyn Node>,
+    ) -> Result<(Expr, Vec<(usize, String)>), Error> {
+        let lines = std::mem::take(&mut self.lines);
+        let res = self.expression(condition);
+        let condition_lines = std::mem::replace(&mut self.lines, lines);
+
+        Ok((res?, condition_lines))
+    }
+
+    fn while_node(&mut self, node: &WhileNode) -> Result<(), Error> {
+        let (condition, condition_lines) = self.separate_condition(node.condition_node())?;
+
+        if condition_lines.is_empty() {
+            self.line(format!("while ({}) {{", condition.code));
+        } else {
+            self.line(String::from("for (;;) {"));
+            self.indent += 1;
+            self.loop_condition(node.condition_node())?;
+            self.indent -= 1;
+        }
+
+        // a nested loop can only continue this one with a `goto` to the end of the body
+        let continue_label = node.label().as_ref().map(|_| self.fresh_name("_continue"));
+        let break_label = self.fresh_name("_break");
+        self.loops.push(Loop {
+            label: node.label().clone(),
+            continue_label: continue_label.clone(),
+            continue_used: false,
+            break_label: break_label.clone(),
+            break_used: false,
+        });
+        self.block(node.body_node(), None)?;
+        let current_loop = self.loops.pop().unwrap();
+
+        if current_loop.continue_used {
+            self.indent += 1;
+            self.line(format!("{}:;", continue_label.unwrap()));
+            self.indent -= 1;
+        }
+        self.line(String::from("}"));
+        if current_loop.break_used {
+            self.line(format!("{}:;", break_label));
+        }
+        Ok(())
+    }
+
+    fn for_node(&mut self, node: &ForNode) -> Result<(), Error> {
+        // the variable declared in the init statement is only visible in the loop
+        self.line(String::from("{"));
+        self.indent += 1;
+        self.scopes.push(HashMap::new());
+
+        self.statement(node.init_stmt())?;
+
+        let (condition, condition_lines) = self.separate_condition(node.condition())?;
+        if condition_lines.is_empty() {
+            self.line(format!("while ({}) {{", condition.code));
+            self.indent += 1;
+        } else {
+            self.line(String::from("for (;;) {"));
+            self.indent += 1;
+            self.loop_condition(node.condition())?;
+        }
+
+        // `continue` has to run the next expression, so it jumps to it
+        let continue_label = self.fresh_name("_continue");
+        let break_label = self.fresh_name("_break");
+        self.loops.push(Loop {
+            label: node.label().clone(),
+            continue_label: Some(continue_label.clone()),
+            continue_used: false,
+            break_label: break_label.clone(),
+            break_used: false,
+        });
+        self.line(String::from("{"));
+        self.block(node.body(), None)?;
+        self.line(String::from("}"));
+
+        let current_loop = self.loops.pop().unwrap();
+        if current_loop.continue_used {
+            self.line(format!("{}:;", continue_label));
+        }
+        self.statement(node.next_expr())?;
+
+        self.indent -= 1;
+        self.line(String::from("}"));
+
+        self.scopes.pop();
+        self.indent -= 1;
+        self.line(String::from("}"));
+        if current_loop.break_used {
+            self.line(format!("{}:;", break_label));
+        }
+
+        Ok(())
+    }
+
+    /// Generates an `if`. If `result` is given, the value of the taken branch is assigned to that
+    /// variable.
+    fn if_node(&mut self, node: &IfNode, result: Option<&str>) -> Result<(), Error> {
+        // conditions needing statements can't be chained with `else if`, so they are nested
+        let mut nested_blocks = 0;
+
+        for (i, case) in node.cases().iter().enumerate() {
+            let (condition, condition_lines) = self.separate_condition(case.condition())?;
+
+            if i == 0 {
+                self.lines.extend(condition_lines);
+                self.line(format!("if ({}) {{", condition.code));
+            } else if condition_lines.is_empty() {
+                self.line(format!("}} else if ({}) {{", condition.code));
+            } else {
+                self.line(String::from("} else {"));
+                self.indent += 1;
+                nested_blocks += 1;
+
+                self.lines.extend(
+                    condition_lines
+                        .into_iter()
+                        .map(|(indent, line)| (indent + 1, line)),
+                );
+                self.line(format!("if ({}) {{", condition.code));
+            }
+
+            self.block(case.statements(), result)?;
+        }
+
+        if let Some(else_case) = node.else_case() {
+            self.line(String::from("} else {"));
+            self.block(else_case.statements(), result)?;
+        }
+        self.line(String::from("}"));
+
+        for _ in 0..nested_blocks {
+            self.indent -= 1;
+            self.line(String::from("}"));
+        }
+
+        Ok(())
+    }
+
+    // endregion
+
+    // region Expressions
+
+    /// Generates the given nodes in order. If a node needs statements, the values of the nodes
+    /// before it are stored in temporaries first, so side effects happen in the same order as in
+    /// the assembly backend.
+    fn sequence<const N: usize>(&mut self, nodes: [&Box<dyn Node>; N]) -> Result<[Expr; N], Error> {
+        let mut exprs: Vec<Expr> = vec![];
+        let mut ends: Vec<usize> = vec![];
+
+        for node in nodes {
+            exprs.push(self.expression(node)?);
+            ends.push(self.lines.len());
+        }
+
+        self.materialize(&mut exprs, &ends);
+        Ok(exprs.try_into().ok().unwrap())
+    }
+
+    fn materialize(&mut self, exprs: &mut [Expr], ends: &[usize]) {
+        for i in (0..exprs.len()).rev() {
+            if exprs[i].stable || ends[i] == self.lines.len() {
+                continue;
+            }
+
+            let name = self.fresh_name("_t");
+            self.lines.insert(
+                ends[i],
+                (
+                    self.indent,
+                    format!("uint64_t {} = {};", name, exprs[i].code),
+                ),
+            );
+            exprs[i] = Expr::stable(name);
+        }
+    }
+
+    fn cast(code: String, size: ValueSize) -> String {
+        if size == ValueSize::Qword {
+            code
+        } else {
+            format!("(uint64_t)({})({})", c_integer_type(size), code)
+        }
+    }
+
+    fn expression(&mut self, node: &Box<dyn Node>) -> Result<Expr, Error> {
+        match node.node_type() {
+            NodeType::Number => {
+                let number_node = node.as_any().downcast_ref::<NumberNode>().unwrap();
+                Ok(Expr::stable(c_number(truncate(
+                    number_node.get_number(),
+                    number_node.size().get_size(),
+                ))))
+            }
+            NodeType::FloatingPoint => Ok(Expr::stable(format!(
+                "UINT64_C({:#x})",
+                node.as_any()
+                    .downcast_ref::<FloatingPointNode>()
+                    .unwrap()
+                    .ieee_754()
+            ))),
+            NodeType::String => {
+                let string_node = node.as_any().downcast_ref::<StringNode>().unwrap();
+                Ok(self.string_address(unescape(&string_node.get_string())))
+            }
+            NodeType::Char => Ok(Expr::stable(c_number(
+                *node.as_any().downcast_ref::<CharNode>().unwrap().value() as u8 as u64,
+            ))),
+            NodeType::SizeOf => Ok(Expr::stable(c_number(
+                node.as_any()
+                    .downcast_ref::<SizeOfNode>()
+                    .unwrap()
+                    .value_type()
+                    .get_size()
+                    .get_size_in_bytes() as u64,
+            ))),
+            NodeType::Array => self.array(node.as_any().downcast_ref::<ArrayNode>().unwrap()),
+            NodeType::StackAllocationNode => {
+                let size = *node
+                    .as_any()
+                    .downcast_ref::<StackAllocationNode>()
+                    .unwrap()
+                    .size_in_bytes();
+
+                let address = self.stack_buffer(size);
+                self.line(format!("umber_zero({}, {});", address.code, size));
+                Ok(address)
+            }
+            NodeType::Copy => {
+                let copy_node = node.as_any().downcast_ref::<CopyNode>().unwrap();
+                let [destination, source] =
+                    self.sequence([copy_node.destination(), copy_node.source()])?;
+
+                // the destination is the value of the copy
+                let destination = if destination.stable {
+                    destination
+                } else {
+                    self.temporary(destination.code)
+                };
+                self.line(format!(
+                    "umber_copy({}, {}, {});",
+                    destination.code,
+                    source.code,
+                    copy_node.size_in_bytes()
+                ));
+                Ok(destination)
+            }
+            NodeType::BinOp => self.bin_op(node.as_any().downcast_ref::<BinOpNode>().unwrap()),
+            NodeType::Check => self.check(node.as_any().downcast_ref::<CheckNode>().unwrap()),
+            NodeType::Slice => self.slice(node.as_any().downcast_ref::<SliceNode>().unwrap()),
+            NodeType::FloatingBinOp => {
+                self.floating_bin_op(node.as_any().downcast_ref::<FloatingBinOpNode>().unwrap())
+            }
+            NodeType::UnaryOp => {
+                let unary_op_node = node.as_any().downcast_ref::<UnaryOpNode>().unwrap();
+                let value = self.expression(unary_op_node.node())?;
+
+                let code = match unary_op_node.op_token().token_type() {
+                    TokenType::Minus if self.is_floating_point(unary_op_node.node()) => {
+                        format!("umber_bits(-umber_f64({}))", value.code)
+                    }
+                    TokenType::Minus => format!("(0 - {})", value.code),
+                    TokenType::Not => format!("(uint64_t)({} == 0)", value.code),
+                    TokenType::BitNot => format!("~{}", value.code),
+                    _ => value.code,
+                };
+
+                Ok(Expr {
+                    code,
+                    stable: value.stable,
+                })
+            }
+            NodeType::VarTypedAccess => {
+                let var_access_node = node.as_any().downcast_ref::<VarTypedAccessNode>().unwrap();
+                let variable = self.get_var(var_access_node.var_name());
+                Ok(Expr::pure(Self::read_var(
+                    &variable,
+                    var_access_node.value_type().as_ref(),
+                )))
+            }
+            NodeType::AddressOf => {
+                let address_of_node = node.as_any().downcast_ref::<AddressOfNode>().unwrap();
+                let variable = self.get_var(address_of_node.var_name());
+                Ok(Expr::stable(format!(
+                    "(uint64_t)(uintptr_t)&{}",
+                    variable.c_name
+                )))
+            }
+            NodeType::Call => {
+                let call_node = node.as_any().downcast_ref::<CallNode>().unwrap();
+                let call = self.call(call_node)?;
+
+                match self.functions.get(call_node.func_to_call()) {
+                    Some(signature) if signature.return_type.value_type() == ValueTypes::Void => {
+                        self.line(format!("{};", call));
+                        Ok(Expr::zero())
+                    }
+                    Some(signature) => {
+                        let value = from_c_value(call, signature.return_type.as_ref());
+                        Ok(self.temporary(value))
+                    }
+                    None => Ok(self.temporary(call)),
+                }
+            }
+            NodeType::Syscall => {
+                let syscall = self.syscall(node.as_any().downcast_ref::<SyscallNode>().unwrap())?;
+                Ok(self.temporary(syscall))
+            }
+            NodeType::Cast => {
+                let cast_node = node.as_any().downcast_ref::<CastNode>().unwrap();
+                let value = self.expression(cast_node.node())?;
+                Ok(Expr {
+                    code: Self::cast(value.code, cast_node.cast_type().get_size()),
+                    stable: value.stable,
+                })
+            }
+            NodeType::F64ToU64 => {
+                let value =
+                    self.expression(node.as_any().downcast_ref::<F64ToU64Node>().unwrap().node())?;
+                Ok(Expr {
+                    code: format!("(uint64_t)(int64_t)umber_f64({})", value.code),
+                    stable: value.stable,
+                })
+            }
+            NodeType::U64ToF64 => {
+                let value =
+                    self.expression(node.as_any().downcast_ref::<U64ToF64Node>().unwrap().node())?;
+                Ok(Expr {
+                    code: format!("umber_bits((double)(int64_t){})", value.code),
+                    stable: value.stable,
+                })
+            }
+            NodeType::ReadBytes => {
+                let read_bytes_node = node.as_any().downcast_ref::<ReadBytesNode>().unwrap();
+                let address = self.expression(read_bytes_node.node())?;
+                Ok(Expr::pure(format!(
+                    "umber_load({}, {})",
+                    address.code,
+                    read_bytes_node.bytes().get_size_in_bytes()
+                )))
+            }
+            NodeType::Offset => {
+                let offset_node = node.as_any().downcast_ref::<OffsetNode>().unwrap();
+                let [address, offset] =
+                    self.sequence([offset_node.node(), offset_node.offset_node()])?;
+
+                Ok(Expr {
+                    code: format!(
+                        "({} + {} * {})",
+                        address.code,
+                        offset.code,
+                        offset_node.pointee_type().size_in_bytes()
+                    ),
+                    stable: address.stable && offset.stable,
+                })
+            }
+            NodeType::VarDeclaration | NodeType::VarTypedAssign | NodeType::PointerAssign => {
+                // the value of an assignment is the assigned value
+                let value_node = match node.node_type() {
+                    NodeType::VarDeclaration => node
+                        .as_any()
+                        .downcast_ref::<VarDeclarationNode>()
+                        .unwrap()
+                        .value_node(),
+                    NodeType::VarTypedAssign => node
+                        .as_any()
+                        .downcast_ref::<VarTypedAssignNode>()
+                        .unwrap()
+                        .value_node(),
+                    _ => node
+                        .as_any()
+                        .downcast_ref::<PointerAssignNode>()
+                        .unwrap()
+                        .value(),
+                };
+
+                let is_simple = value_node.node_type() == NodeType::Number;
+                self.statement(node)?;
+
+                if is_simple {
+                    self.expression(value_node)
+                } else {
+                    Ok(Expr::zero())
+                }
+            }
+            NodeType::Assembly => Err(Self::unsupported(
+                node.as_ref(),
+                "Inline assembly is not supported by the C backend!",
+            )),
+            // blocks and `if`s with a value assign it to a temporary in their last statement
+            NodeType::Statements
+                if node
+                    .as_any()
+                    .downcast_ref::<StatementsNode>()
+                    .unwrap()
+                    .has_value() =>
+            {
+                let result = self.fresh_name("_t");
+                self.line(format!("uint64_t {};", result));
+                self.line(String::from("{"));
+                self.block(node, Some(&result))?;
+                self.line(String::from("}"));
+                Ok(Expr::stable(result))
+            }
+            NodeType::If if node.as_any().downcast_ref::<IfNode>().unwrap().has_value() => {
+                let result = self.fresh_name("_t");
+                self.line(format!("uint64_t {};", result));
+                self.if_node(
+                    node.as_any().downcast_ref::<IfNode>().unwrap(),
+                    Some(&result),
+                )?;
+                Ok(Expr::stable(result))
+            }
+            _ => {
+                self.statement(node)?;
+                Ok(Expr::zero())
+            }
+        }
+    }
+
+    fn array(&mut self, node: &ArrayNode) -> Result<Expr, Error> {
+        let element_size = node.element_type().get_size().get_size_in_bytes() as u64;
+        let address = self.stack_buffer(*node.size() as u64 * element_size);
+
+        if node.element_nodes().is_empty() {
+            self.line(format!(
+                "umber_zero({}, {});",
+                address.code,
+                *node.size() as u64 * element_size
+            ));
+        }
+
+        // like the assembly backend, evaluate the elements from last to first
+        for (i, element) in node.element_nodes().iter().enumerate().rev() {
+            let value = self.expression(element)?;
+            self.line(format!(
+                "umber_store({} + {}, {}, {});",
+                address.code,
+                i as u64 * element_size,
+                element_size,
+                value.code
+            ));
+        }
+
+        Ok(address)
+    }
+
+    fn slice(&mut self, node: &SliceNode) -> Result<Expr, Error> {
+        let [slice, start, end] = self.sequence([node.node(), node.start(), node.end()])?;
+        let address = self.stack_buffer(16);
+
+        self.line(format!(
+            "umber_store({} + {}, 8, {} - {});",
+            address.code, SLICE_LENGTH_OFFSET, end.code, start.code
+        ));
+        self.line(format!(
+            "umber_store({}, 8, umber_load({}, 8) + {} * {});",
+            address.code,
+            slice.code,
+            start.code,
+            node.element_size()
+        ));
+
+        Ok(address)
+    }
+
+    fn bin_op(&mut self, node: &BinOpNode) -> Result<Expr, Error> {
+        let op = node.op_token().token_type();
+
+        if op == TokenType::And || op == TokenType::Or {
+            let left = self.expression(node.left_node())?;
+
+            let (right, right_lines) = self.separate_condition(node.right_node())?;
+            if right_lines.is_empty() {
+                return Ok(Expr {
+                    code: format!(
+                        "(uint64_t)({} {} {})",
+                        left.code,
+                        if op == TokenType::And { "&&" } else { "||" },
+                        right.code
+                    ),
+                    stable: left.stable && right.stable,
+                });
+            }
+
+            // the right side has side effects, which may only happen if it is evaluated
+            let res = self.temporary(format!("{} != 0", left.code));
+            self.line(format!(
+                "if ({}{}) {{",
+                if op == TokenType::And { "" } else { "!" },
+                res.code
+            ));
+            self.indent += 1;
+            self.lines.extend(
+                right_lines
+                    .into_iter()
+                    .map(|(indent, line)| (indent + 1, line)),
+            );
+            self.line(format!("{} = {} != 0;", res.code, right.code));
+            self.indent -= 1;
+            self.line(String::from("}"));
+
+            return Ok(res);
+        }
+
+        let [left, right] = self.sequence([node.left_node(), node.right_node()])?;
+        let (l, r) = (&left.code, &right.code);
+
+        let code = match op {
+            TokenType::Plus => format!("({} + {})", l, r),
+            TokenType::Minus => format!("({} - {})", l, r),
+            TokenType::Mul => format!("({} * {})", l, r),
+            TokenType::Div => format!("(uint64_t)((int64_t){} / (int64_t){})", l, r),
+            TokenType::Modulo => format!("(uint64_t)((int64_t){} % (int64_t){})", l, r),
+            TokenType::BitAnd => format!("({} & {})", l, r),
+            TokenType::BitOr => format!("({} | {})", l, r),
+            TokenType::BitXor => format!("({} ^ {})", l, r),
+            TokenType::BitShl => format!("({} << ({} & 63))", l, r),
+            TokenType::BitShr => format!("(uint64_t)((int64_t){} >> ({} & 63))", l, r),
+            TokenType::Ee => format!("(uint64_t)({} == {})", l, r),
+            TokenType::Ne => format!("(uint64_t)({} != {})", l, r),
+            TokenType::Lt => format!("(uint64_t)((int64_t){} < (int64_t){})", l, r),
+            TokenType::Gt => format!("(uint64_t)((int64_t){} > (int64_t){})", l, r),
+            TokenType::Lte => format!("(uint64_t)((int64_t){} <= (int64_t){})", l, r),
+            TokenType::Gte => format!("(uint64_t)((int64_t){} >= (int64_t){})", l, r),
+            _ => {
+                return Err(Self::unsupported(
+                    node,
+                    format!(
+                        "Binary operation '{:?}' is not supported by the C backend!",
+                        op
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+
+        Ok(Expr {
+            code,
+            stable: left.stable && right.stable,
+        })
+    }
+
+    fn floating_bin_op(&mut self, node: &FloatingBinOpNode) -> Result<Expr, Error> {
+        let [left, right] = self.sequence([node.left_node(), node.right_node()])?;
+        let (bits_l, bits_r) = (&left.code, &right.code);
+        let l = format!("umber_f64({})", bits_l);
+        let r = format!("umber_f64({})", bits_r);
+
+        let code = match node.op_token().token_type() {
+            TokenType::Plus => format!("umber_bits({} + {})", l, r),
+            TokenType::Minus => format!("umber_bits({} - {})", l, r),
+            TokenType::Mul => format!("umber_bits({} * {})", l, r),
+            TokenType::Div => format!("umber_bits({} / {})", l, r),
+            TokenType::Modulo => {
+                if !self.declarations.iter().any(|d| d.contains(" fmod(")) {
+                    self.declarations
+                        .push(String::from("double fmod(double x, double y);"));
+                }
+                format!("umber_bits(fmod({}, {}))", l, r)
+            }
+            TokenType::BitAnd => format!("({} & {})", bits_l, bits_r),
+            TokenType::BitOr => format!("({} | {})", bits_l, bits_r),
+            TokenType::BitXor => format!("({} ^ {})", bits_l, bits_r),
+            TokenType::BitShl => format!("({} << ((uint32_t){} & 63))", bits_l, r),
+            TokenType::BitShr => format!("({} >> ((uint32_t){} & 63))", bits_l, r),
+            TokenType::Ee => format!("(uint64_t)({} == {})", l, r),
+            TokenType::Ne => format!("(uint64_t)({} != {})", l, r),
+            TokenType::Lt => format!("(uint64_t)({} < {})", l, r),
+            TokenType::Gt => format!("(uint64_t)({} > {})", l, r),
+            TokenType::Lte => format!("(uint64_t)({} <= {})", l, r),
+            TokenType::Gte => format!("(uint64_t)({} >= {})", l, r),
+            op => {
+                return Err(Self::unsupported(
+                    node,
+                    format!(
+                        "Binary operation '{:?}' is not supported by the C backend!",
+                        op
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+
+        Ok(Expr {
+            code,
+            stable: left.stable && right.stable,
+        })
+    }
+
+    /// Returns the C expression calling the function. The arguments are evaluated from last to
+    /// first, like in the assembly backend.
+    fn call(&mut self, node: &CallNode) -> Result<String, Error> {
+        let mut args: Vec<Expr> = vec![];
+        let mut ends: Vec<usize> = vec![];
+        for (i, arg) in node.arg_nodes().iter().enumerate().rev() {
+            let value = self.expression(arg)?;
+
+            if node.is_slice_arg(i) {
+                args.push(Expr::pure(format!(
+                    "umber_load({} + {}, 8)",
+                    value.code, SLICE_LENGTH_OFFSET
+                )));
+                ends.push(self.lines.len());
+                args.push(Expr::pure(format!("umber_load({}, 8)", value.code)));
+            } else {
+                args.push(value);
+            }
+            ends.push(self.lines.len());
+        }
+        self.materialize(&mut args, &ends);
+        args.reverse();
+        let passed_arg_types = node.passed_arg_types();
+
+        let arg_types = self
+            .functions
+            .get(node.func_to_call())
+            .map(|signature| signature.arg_types.clone())
+            .unwrap_or_default();
+
+        let args: Vec<String> = args
+            .into_iter()
+            .enumerate()
+            .map(
+                |(i, arg)| match (arg_types.get(i), passed_arg_types.get(i)) {
+                    (Some(arg_type), _) => to_c_value(arg.code, arg_type.as_ref()),
+                    (None, Some(arg_type)) => to_c_variadic_value(arg.code, arg_type.as_ref()),
+                    (None, None) => arg.code,
+                },
+            )
+            .collect();
+
+        Ok(format!(
+            "{}({})",
+            self.function_name(node.func_to_call()),
+            args.join(", ")
+        ))
+    }
+
+    fn syscall(&mut self, node: &SyscallNode) -> Result<String, Error> {
+        let args = node.args();
+        let [code, arg1, arg2, arg3] = self.sequence([&args[0], &args[1], &args[2], &args[3]])?;
+
+        Ok(format!(
+            "umber_syscall({}, {}, {}, {})",
+            code.code, arg1.code, arg2.code, arg3.code
+        ))
+    }
+
+    // endregion
+
+    // region Entry points
+
+    fn compile(&mut self, node: &Box<dyn Node>) -> Result<(), Error> {
+        self.declare(node)?;
+        self.top_level(node)
+    }
+
+    /// Puts the prelude, declarations and functions together.
+    fn assemble_file(&self, entry: &str) -> String {
+        let mut res = String::new();
+
+        writeln!(res, "/* Generated by the Umber compiler */").unwrap();
+        writeln!(res, "{}", PRELUDE).unwrap();
+
+        for declaration in &self.declarations {
+            writeln!(res, "{}", declaration).unwrap();
+        }
+        if !self.declarations.is_empty() {
+            writeln!(res).unwrap();
+        }
+
+        for prototype in &self.prototypes {
+            writeln!(res, "{}", prototype).unwrap();
+        }
+
+        for definition in &self.definitions {
+            writeln!(res, "\n{}", definition.trim_end()).unwrap();
+        }
+
+        write!(res, "{}", entry).unwrap();
+        res
+    }
+
+    /// Compiles the given node to a C file. Unless `no_entry` is set, a C `main` function calls the
+    /// Umber `main` function with the argument count and a pointer to the first argument, like
+    /// `_start` does in the assembly backend.
+    pub fn compile_to_str(
+        &mut self,
+        node: &Box<dyn Node>,
+        no_entry: bool,
+    ) -> Result<String, Error> {
+        self.rename_main = !no_entry;
+        self.compile(node)?;
+
+        let mut entry = String::new();
+        if !no_entry {
+            let main_args = self
+                .functions
+                .get("main")
+                .map_or(0, |main| main.arg_types.len());
+            let args = ["(uint64_t)argc", "(uint64_t)(uintptr_t)argv[0]"];
+
+            writeln!(entry, "\nint main(int argc, char **argv) {{").unwrap();
+            if main_args < 2 {
+                writeln!(entry, "    (void)argv;").unwrap();
+            }
+            if main_args < 1 {
+                writeln!(entry, "    (void)argc;").unwrap();
+            }
+            writeln!(
+                entry,
+                "    return (int)((uint64_t)umber_main({}) & 0xff);",
+                args[..main_args.min(2)].join(", ")
+            )
+            .unwrap();
+            writeln!(entry, "}}").unwrap();
+        }
+
+        Ok(self.assemble_file(&entry))
+    }
+
+    /// Compiles the given node to a C file with a test harness as `main`, which runs every test in
+    /// a forked process like the one of the assembly backend.
+    /// Only supported on targets with `fork` and `wait4` syscalls.
+    pub fn compile_tests_to_str(
+        &mut self,
+        node: &Box<dyn Node>,
+        tests: &[(String, Position)],
+    ) -> Result<String, Error> {
+        self.rename_main = true;
+        self.compile(node)?;
+
+        let arch = self.target_object_type;
+        let mut entry = String::new();
+
+        writeln!(entry, "\nstatic void umber_print(const char *string) {{").unwrap();
+        writeln!(entry, "    uint64_t len = 0;").unwrap();
+        writeln!(entry, "    while (string[len] != 0) {{").unwrap();
+        writeln!(entry, "        len++;").unwrap();
+        writeln!(entry, "    }}").unwrap();
+        writeln!(
+            entry,
+            "    umber_syscall({}, 1, (uint64_t)(uintptr_t)string, len);",
+            SyscallTable::Write.code(arch)
+        )
+        .unwrap();
+        writeln!(entry, "}}\n").unwrap();
+
+        writeln!(entry, "static void umber_print_number(uint64_t number) {{").unwrap();
+        writeln!(entry, "    char digits[21];").unwrap();
+        writeln!(entry, "    int i = 20;").unwrap();
+        writeln!(entry, "    digits[20] = 0;").unwrap();
+        writeln!(entry, "    do {{").unwrap();
+        writeln!(entry, "        digits[--i] = (char)('0' + number % 10);").unwrap();
+        writeln!(entry, "        number /= 10;").unwrap();
+        writeln!(entry, "    }} while (number != 0);").unwrap();
+        writeln!(entry, "    umber_print(digits + i);").unwrap();
+        writeln!(entry, "}}\n").unwrap();
+
+        writeln!(
+            entry,
+            "/* Waits for the forked test and returns whether it passed */"
+        )
+        .unwrap();
+        writeln!(entry, "static int umber_test_passed(uint64_t pid) {{").unwrap();
+        writeln!(entry, "    uint32_t status = 0xffffffff;").unwrap();
+        writeln!(entry, "    if ((int64_t)pid < 0) {{").unwrap();
+        writeln!(entry, "        return 0;").unwrap();
+        writeln!(entry, "    }}").unwrap();
+        writeln!(
+            entry,
+            "    umber_syscall({}, pid, (uint64_t)(uintptr_t)&status, 0);",
+            SyscallTable::Wait4.code(arch)
+        )
+        .unwrap();
+        writeln!(entry, "    return status == 0;").unwrap();
+        writeln!(entry, "}}\n").unwrap();
+
+        writeln!(entry, "int main(void) {{").unwrap();
+        writeln!(entry, "    uint64_t passed = 0;").unwrap();
+        writeln!(entry, "    uint64_t failed = 0;").unwrap();
+        writeln!(
+            entry,
+            "    umber_print(\"\\nrunning {} test{}\\n\");",
+            tests.len(),
+            if tests.len() == 1 { "" } else { "s" }
+        )
+        .unwrap();
+
+        for (test, _) in tests {
+            writeln!(entry, "    {{").unwrap();
+            writeln!(
+                entry,
+                "        uint64_t pid = umber_syscall({}, 0, 0, 0);",
+                SyscallTable::Fork.code(arch)
+            )
+            .unwrap();
+            writeln!(entry, "        if (pid == 0) {{").unwrap();
+            writeln!(entry, "            {}();", self.function_name(test)).unwrap();
+            writeln!(
+                entry,
+                "            umber_syscall({}, 0, 0, 0);",
+                SyscallTable::Exit.code(arch)
+            )
+            .unwrap();
+            writeln!(entry, "        }}").unwrap();
+            writeln!(entry, "        if (umber_test_passed(pid)) {{").unwrap();
+            writeln!(
+                entry,
+                "            umber_print(\"test {} ... ok\\n\");",
+                test
+            )
+            .unwrap();
+            writeln!(entry, "            passed++;").unwrap();
+            writeln!(entry, "        }} else {{").unwrap();
+            writeln!(
+                entry,
+                "            umber_print(\"test {} ... FAILED\\n\");",
+                test
+            )
+            .unwrap();
+            writeln!(entry, "            failed++;").unwrap();
+            writeln!(entry, "        }}").unwrap();
+            writeln!(entry, "    }}").unwrap();
+        }
+
+        writeln!(entry, "    umber_print(\"\\ntest result: \");").unwrap();
+        writeln!(
+            entry,
+            "    umber_print(failed == 0 ? \"ok. \" : \"FAILED. \");"
+        )
+        .unwrap();
+        writeln!(entry, "    umber_print_number(passed);").unwrap();
+        writeln!(entry, "    umber_print(\" passed; \");").unwrap();
+        writeln!(entry, "    umber_print_number(failed);").unwrap();
+        writeln!(entry, "    umber_print(\" failed\\n\\n\");").unwrap();
+        writeln!(entry, "    return failed != 0;").unwrap();
+        writeln!(entry, "}}").unwrap();
+
+        Ok(self.assemble_file(&entry))
+    }
+
+    // endregion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::semantics::{RuntimeCheck, Validator};
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    fn generate(source: &str, no_entry: bool) -> Result<String, Error> {
+        generate_with_checks(source, no_entry, &[])
+    }
+
+    fn generate_with_checks(
+        source: &str,
+        no_entry: bool,
+        checks: &[RuntimeCheck],
+    ) -> Result<String, Error> {
+        let path = PathBuf::from("/tmp/c_compiler_test.ub");
+        let tokens = Lexer::new(path, source.to_string()).make_tokens().unwrap();
+
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+
+        let validation_res = Validator::new_with_checks(checks).validate(&ast_root);
+        assert!(
+            validation_res.error().is_none(),
+            "{:?}",
+            validation_res.error()
+        );
+
+        CCompiler::new(TargetObjectType::X86_64)
+            .compile_to_str(validation_res.node().as_ref().unwrap(), no_entry)
+    }
+
+    #[test]
+    fn test_generate_c() {
+        let c = generate(
+            "static mut counter: u16 = 3 as u16;
+extern fun puts(s: char *): u32;
+
+fun bump(by: u64): u64 {
+    counter = counter + by as u16;
+    return counter as u64;
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let int: u64 = bump(2) / 2;
+    puts(\"hi\" as char *);
+    return int >> 1;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(c.contains("static uint16_t ST_counter = 3;"));
+        assert!(c.contains("extern uint32_t puts(uint64_t);"));
+        assert!(c.contains("static char S0[] = \"hi\";"));
+        assert!(c.contains("uint64_t bump(uint64_t by);"));
+        // C keywords are renamed and division and shifts are signed
+        assert!(c.contains("uint64_t int_ = (uint64_t)((int64_t)_t / (int64_t)2);"));
+        assert!(c.contains("return (uint64_t)((int64_t)int_ >> (1 & 63));"));
+        assert!(c.contains("uint64_t umber_main(uint64_t argc, uint64_t argv) {"));
+        assert!(c.contains("umber_main((uint64_t)argc, (uint64_t)(uintptr_t)argv[0])"));
+
+        let library = generate("fun main(): u64 { return 0; };", true).unwrap();
+        assert!(library.contains("uint64_t main(void) {"));
+    }
+
+    #[test]
+    fn test_variadic_call() {
+        let c = generate(
+            "extern fun printf(fmt: char *, ...): i32;
+fun main(): u64 {
+    printf(\"%d %u %f\" as char *, 255 as i8, 7, 2.5);
+    return 0;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(c.contains("extern uint32_t printf(uint64_t, ...);"));
+        assert!(c.contains(", (int8_t)("));
+        assert!(c.contains(", umber_f64("));
+    }
+
+    #[test]
+    fn test_slices() {
+        let c = generate(
+            "extern fun write_all(s: str): void;
+fun first(s: str): char { return *s[0]; };
+fun main(): u64 {
+    let text: str = \"hello\" as str;
+    write_all(text);
+    return first(text[1..3]) as u64;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(c.contains("extern void write_all(uint64_t, uint64_t);"));
+        assert!(c.contains("uint8_t first(uint64_t s_ptr, uint64_t s_len) {"));
+        assert!(c.contains("write_all(umber_load(text, 8), umber_load(text + 8, 8));"));
+        assert!(c.contains(", 8, 3 - 1);"));
+    }
+
+    #[test]
+    fn test_value_expressions() {
+        let c = generate(
+            "fun main(argc: u64, argv: char *): u64 {
+    let x: u64 = if argc > 1 { argc } else { 2 };
+    return { let y: u64 = x * 2; y + 1 };
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(c.contains("uint64_t _t;\n    if ("));
+        assert!(c.contains("        _t = argc;\n    } else {\n        _t = 2;\n    }"));
+        assert!(c.contains("uint64_t x = _t;"));
+        assert!(c.contains("        _t_1 = (y + 1);\n    }\n    return _t_1;"));
+    }
+
+    #[test]
+    fn test_for_in() {
+        let c = generate(
+            "fun main(argc: u64, argv: char *): u64 {
+    let mut n: u64 = 0;
+    for i in 0..argc {
+        n += i;
+    };
+    return n;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(c.contains("uint64_t i_end = argc;"));
+        assert!(c.contains("i_rest = (i_rest - 1);"));
+        assert!(c.contains("uint64_t i = (i_end - i_rest);"));
+    }
+
+    #[test]
+    fn test_labeled_loops() {
+        let c = generate(
+            "fun main(argc: u64, argv: char *): u64 {
+    let mut n: u64 = 0;
+    'outer: while n < argc {
+        defer n += 1;
+        while 1 == 1 {
+            if n == 3 {
+                break 'outer;
+            };
+            continue 'outer;
+        };
+    };
+    return n;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(c.contains("goto _break;"));
+        assert!(c.contains("goto _continue;"));
+        assert!(c.contains("_continue:;"));
+        assert!(c.contains("_break:;"));
+        assert!(!c.contains("_break_1:;"));
+    }
+
+    #[test]
+    fn test_memory_intrinsics() {
+        let c = generate(
+            "fun main(argc: u64, argv: char *): u64 {
+    let buf: [u8; 256] = [<u8>; 256];
+    let p: u8 *mut = buf as u8 *mut;
+    memset[p, 7 as u8, argc];
+    memcpy[p + 64, p, 16];
+    memmove[p + 1, p, argc];
+    return 0;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(c.contains("umber_zero((uint64_t)(uintptr_t)_a, 256);"));
+        assert!(c.contains("umber_fill(p, (uint64_t)(uint8_t)(7), argc);"));
+        assert!(c.contains("umber_copy((p + 64), p, 16);"));
+        assert!(c.contains("umber_move((p + 1), p, argc);"));
+    }
+
+    #[test]
+    fn test_static_initializers() {
+        let c = generate(
+            "static half: f64 = 1.0 / 2.0;
+static mut values: [u16; 2] = [<u16> 1 as u16, 2 as u16];
+static p: [u64; 2] = [<u64>; 2];
+fun main(): u64 { return 0; };",
+            false,
+        )
+        .unwrap();
+
+        assert!(c.contains("static const double ST_half = 0.5;"));
+        assert!(c.contains("static uint16_t D0[] = {1, 2};"));
+        assert!(c.contains("static uint64_t ST_values = (uint64_t)(uintptr_t)D0;"));
+        assert!(c.contains("static uint8_t D1[16];"));
+        assert!(c.contains("static const uint64_t ST_p = (uint64_t)(uintptr_t)D1;"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = generate(
+            "fun one(): u64 { return 1; };
+const x: u64 = one();
+fun main(): u64 { return x; };",
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.details(),
+            "The value of 'x' has to be a constant for the C backend!"
+        );
+
+        let err = generate("fun f(): void { asm[\"nop\"]; return; };", true).unwrap_err();
+        assert_eq!(
+            err.details(),
+            "Inline assembly is not supported by the C backend!"
+        );
+    }
+
+    #[test]
+    fn test_runtime_checks() {
+        let c = generate_with_checks(
+            "fun main(argc: u64, argv: char *): u64 {
+    let values: [u8; 3] = [<u8>; 3];
+    let small: i8 = (argc as i8) * (3 as i8);
+    return ((*values[argc]) as u64) + argc;
+};",
+            false,
+            &[
+                RuntimeCheck::Bounds,
+                RuntimeCheck::Null,
+                RuntimeCheck::Overflow,
+            ],
+        )
+        .unwrap();
+
+        assert!(c.contains(
+            "    int8_t _t;
+    if (__builtin_mul_overflow((int8_t)(uint64_t)(uint8_t)(argc), (int8_t)(uint64_t)(uint8_t)(3), &_t)) {
+        umber_syscall(1, 2, (uint64_t)(uintptr_t)S0, 51);
+        umber_syscall(60, 101, 0, 0);
+    }
+    uint8_t small = (uint64_t)(uint8_t)_t;"
+        ));
+        assert!(c.contains("    if (_t_1 >= 3) {\n"));
+        assert!(c.contains("    if ((_t_2 + _t_1 * 1) == 0) {\n"));
+        assert!(c.contains("    return _t_3;\n"));
+        assert!(c.contains(
+            "static char S1[] = \"/tmp/c_compiler_test.ub:4:15: Index out of bounds!\\012\";"
+        ));
+    }
+
+    #[test]
+    fn test_compile_with_cc() {
+        // differential testing needs a C compiler, skip it otherwise
+        if Command::new("cc").arg("--version").output().is_err() {
+            return;
+        }
+
+        let c = generate(
+            "fun side(p: u64 *mut): u64 {
+    p @= *p + 1;
+    return *p;
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let mut n: u64 = 0;
+    let arr: [u64; 3] = [<u64> side(&n), side(&n), 7];
+    let f: f64 = 2.5 * 4.0;
+    let mut res: u64 = *arr[0] * 100 + *arr[1] * 10 + f as u64;
+    for let mut i: u64 = 0; i < 5; i += 1 {
+        if i == 2 { continue; };
+        res += i;
+    };
+    return res - 200;
+};",
+            false,
+        )
+        .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("umber_c_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("test.c"), c).unwrap();
+
+        let cc = Command::new("cc")
+            .args(["-std=c99", "-o", "test", "test.c", "-lm"])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(
+            cc.status.success(),
+            "{}",
+            String::from_utf8_lossy(&cc.stderr)
+        );
+
+        let status = Command::new(dir.join("test")).status().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // the elements are evaluated from last to first: 2 * 100 + 1 * 10 + 10 + 8 - 200
+        assert_eq!(status.code(), Some(28));
+    }
+
+    #[test]
+    fn test_null_dereference_with_cc() {
+        if Command::new("cc").arg("--version").output().is_err() {
+            return;
+        }
+
+        // the unused load can be removed, but not the crash
+        let c = generate(
+            "fun main(argc: u64, argv: char *): u64 {
+    let p: u64 * = (argc - 1) as u64 *;
+    let v: u64 = *p;
+    if p == 0 as u64 * {
+        return 3;
+    };
+    return 4;
+};",
+            false,
+        )
+        .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("umber_c_null_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("test.c"), c).unwrap();
+
+        let cc = Command::new("cc")
+            .args([
+                "-std=c99",
+                "-O2",
+                "-fno-delete-null-pointer-checks",
+                "-o",
+                "test",
+                "test.c",
+                "-lm",
+            ])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(
+            cc.status.success(),
+            "{}",
+            String::from_utf8_lossy(&cc.stderr)
+        );
+
+        let status = Command::new(dir.join("test")).status().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // killed by SIGSEGV, without an exit code
+        assert_eq!(status.code(), None);
+    }
+
+    #[test]
+    fn test_slice_checks_with_cc() {
+        if Command::new("cc").arg("--version").output().is_err() {
+            return;
+        }
+
+        let c = generate_with_checks(
+            "fun main(argc: u64, argv: char *): u64 {
+    let values: [u64; 4] = [<u64> 1, 2, 3, 4];
+    let mut start: u64 = 1;
+    let mut end: u64 = 3;
+    let mut index: u64 = 1;
+    if argc == 2 {
+        end = 5;
+    };
+    if argc == 3 {
+        start = 3;
+        end = 2;
+    };
+    if argc == 4 {
+        index = 2;
+    };
+    let part: []u64 = values[start..end];
+    let element: u64 = *part[index];
+    return element + part[0..argc / 5 + 2].len;
+};",
+            false,
+            &[RuntimeCheck::Bounds],
+        )
+        .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("umber_c_slice_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("test.c"), c).unwrap();
+
+        let cc = Command::new("cc")
+            .args(["-std=c99", "-o", "test", "test.c", "-lm"])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(
+            cc.status.success(),
+            "{}",
+            String::from_utf8_lossy(&cc.stderr)
+        );
+
+        let results: Vec<(Option<i32>, String)> = (0..5)
+            .map(|args| {
+                let output = Command::new(dir.join("test"))
+                    .args(vec!["a"; args])
+                    .output()
+                    .unwrap();
+                let error_output = String::from_utf8_lossy(&output.stderr).to_string();
+                (output.status.code(), error_output)
+            })
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // the sub-slice of the array ends past it, then starts after its end, then the index and
+        // the end of the sub-slice of the slice are past its length
+        let failed = |position: &str, message: &str| {
+            (
+                Some(101),
+                format!("/tmp/c_compiler_test.ub:{}: {}\n", position, message),
+            )
+        };
+        assert_eq!(
+            results,
+            vec![
+                (Some(5), String::new()),
+                failed("16:29", "Slice range out of bounds!"),
+                failed("16:29", "Slice range out of bounds!"),
+                failed("17:25", "Index out of bounds!"),
+                failed("18:26", "Slice range out of bounds!"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,465 @@
+use std::fmt::Write;
+
+use crate::error;
+use crate::error::Error;
+use crate::nodes::functiondef_node::FunctionDefinitionNode;
+use crate::nodes::import_node::ImportNode;
+use crate::nodes::statements_node::StatementsNode;
+use crate::nodes::static_def_node::StaticDefinitionNode;
+use crate::nodes::struct_def_node::StructDefinitionNode;
+use crate::nodes::{Node, NodeType};
+use crate::values::value_type::array_type::ArrayType;
+use crate::values::value_type::char_type::CharType;
+use crate::values::value_type::function_type::FunctionType;
+use crate::values::value_type::pointer_type::PointerType;
+use crate::values::value_type::slice_type::SliceType;
+use crate::values::value_type::struct_type::StructType;
+use crate::values::value_type::{ValueType, ValueTypes};
+
+/// Generates a C header for the functions, structures and statics of a validated AST, so C code
+/// can be linked against the objects and libraries of the assembly backend.
+/// Structures are packed, like the Umber layout, and statics are declared with their symbol name
+/// `ST_<name>`.
+pub struct CHeaderGenerator {
+    structs: Vec<String>,
+    statics: Vec<String>,
+    prototypes: Vec<String>,
+}
+
+impl Default for CHeaderGenerator {
+    fn default() -> Self {
+        CHeaderGenerator::new()
+    }
+}
+
+impl CHeaderGenerator {
+    pub fn new() -> Self {
+        CHeaderGenerator {
+            structs: vec![],
+            statics: vec![],
+            prototypes: vec![],
+        }
+    }
+
+    /// Returns the C declaration of `name` with the given type. An empty name gives the type
+    /// itself, like in parameter lists of prototypes. With `is_const` the declared value itself
+    /// is read only, which is used for the pointees of immutable pointers.
+    fn declaration(
+        node: &dyn Node,
+        value_type: &dyn ValueType,
+        name: &str,
+        is_const: bool,
+    ) -> Result<String, Error> {
+        let base = match value_type.value_type() {
+            ValueTypes::U64 => "uint64_t",
+            ValueTypes::U32 => "uint32_t",
+            ValueTypes::U16 => "uint16_t",
+            ValueTypes::U8 => "uint8_t",
+            ValueTypes::I64 => "int64_t",
+            ValueTypes::I32 => "int32_t",
+            ValueTypes::I16 => "int16_t",
+            ValueTypes::I8 => "int8_t",
+            ValueTypes::Bool => "bool",
+            ValueTypes::Char => "char",
+            ValueTypes::F64 => "double",
+            ValueTypes::Void => "void",
+            ValueTypes::String => {
+                let pointer = format!("*{}{}", if is_const { "const " } else { "" }, name);
+                return Self::declaration(node, &CharType::new(), pointer.trim_end(), false);
+            }
+            ValueTypes::Pointer => {
+                let pointer_type = value_type.as_any().downcast_ref::<PointerType>().unwrap();
+                let pointer = format!("*{}{}", if is_const { "const " } else { "" }, name);
+                return Self::declaration(
+                    node,
+                    pointer_type.pointee_type().as_ref(),
+                    pointer.trim_end(),
+                    !pointer_type.is_mutable(),
+                );
+            }
+            ValueTypes::Array => {
+                // outside of structures arrays are passed around as the address of their elements
+                let array_type = value_type.as_any().downcast_ref::<ArrayType>().unwrap();
+                let pointer = format!("*{}{}", if is_const { "const " } else { "" }, name);
+                return Self::declaration(
+                    node,
+                    array_type.element_type().as_ref(),
+                    pointer.trim_end(),
+                    false,
+                );
+            }
+            ValueTypes::Slice => {
+                // outside of structures slices are the address of their pointer and length
+                let pointer = format!("*{}{}", if is_const { "const " } else { "" }, name);
+                return Ok(Self::join(
+                    Self::slice_struct(node, value_type)?.as_str(),
+                    pointer.trim_end(),
+                ));
+            }
+            ValueTypes::Function => {
+                // function values are addresses, like function pointers in C
+                let function_type = value_type.as_any().downcast_ref::<FunctionType>().unwrap();
+                let mut args = Self::parameters(
+                    node,
+                    function_type
+                        .arg_types()
+                        .iter()
+                        .map(|arg_type| ("", arg_type)),
+                )?;
+                if function_type.is_variadic() {
+                    args.push_str(", ...");
+                }
+                return Self::declaration(
+                    node,
+                    function_type.return_type().as_ref(),
+                    format!(
+                        "(*{}{})({})",
+                        if is_const { "const " } else { "" },
+                        name,
+                        args
+                    )
+                    .as_str(),
+                    false,
+                );
+            }
+            ValueTypes::Struct if !name.starts_with('*') => {
+                return Err(error::not_yet_implemented_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    format!(
+                        "Structures can only be used through pointers in C headers, '{}' isn't!",
+                        value_type
+                    )
+                    .as_str(),
+                ));
+            }
+            ValueTypes::Struct => value_type
+                .as_any()
+                .downcast_ref::<StructType>()
+                .unwrap()
+                .name(),
+            ValueTypes::Generic | ValueTypes::Ignored => unreachable!(),
+        };
+
+        Ok(Self::join(
+            format!("{}{}", if is_const { "const " } else { "" }, base).as_str(),
+            name,
+        ))
+    }
+
+    /// Returns the structure slices are stored as, which has the same layout as in Umber.
+    fn slice_struct(node: &dyn Node, value_type: &dyn ValueType) -> Result<String, Error> {
+        let slice_type = value_type.as_any().downcast_ref::<SliceType>().unwrap();
+        Ok(format!(
+            "struct {{ {}; uint64_t len; }}",
+            Self::declaration(node, slice_type.element_type().as_ref(), "*ptr", true)?
+        ))
+    }
+
+    fn join(base: &str, declarator: &str) -> String {
+        if declarator.is_empty() {
+            base.to_string()
+        } else {
+            format!("{} {}", base, declarator)
+        }
+    }
+
+    /// Returns the parameter list of a prototype, `void` if there are no parameters. Slices are
+    /// passed as the pointer to their first element and their length.
+    fn parameters<'a>(
+        node: &dyn Node,
+        args: impl Iterator<Item = (&'a str, &'a Box<dyn ValueType>)>,
+    ) -> Result<String, Error> {
+        let mut parameters: Vec<String> = vec![];
+        for (name, arg_type) in args {
+            match arg_type.as_any().downcast_ref::<SliceType>() {
+                Some(slice_type) => {
+                    let suffixed = |suffix: &str| {
+                        if name.is_empty() {
+                            String::new()
+                        } else {
+                            format!("{}_{}", name, suffix)
+                        }
+                    };
+                    parameters.push(Self::declaration(
+                        node,
+                        slice_type.element_type().as_ref(),
+                        format!("*{}", suffixed("ptr")).as_str(),
+                        true,
+                    )?);
+                    parameters.push(Self::join("uint64_t", suffixed("len").as_str()));
+                }
+                None => parameters.push(Self::declaration(node, arg_type.as_ref(), name, false)?),
+            }
+        }
+        let args = parameters;
+
+        Ok(if args.is_empty() {
+            String::from("void")
+        } else {
+            args.join(", ")
+        })
+    }
+
+    fn function(&mut self, node: &FunctionDefinitionNode) -> Result<(), Error> {
+        if !node.generics().is_empty() {
+            self.prototypes.push(format!(
+                "/* '{}' is generic and has no C prototype */",
+                node.var_name()
+            ));
+            return Ok(());
+        }
+
+        let args = Self::parameters(
+            node,
+            node.args()
+                .iter()
+                .map(|(name, arg_type)| (name.as_str(), arg_type)),
+        )?;
+
+        let prototype = Self::declaration(
+            node,
+            node.return_type().as_ref(),
+            format!("{}({})", node.var_name(), args).as_str(),
+            false,
+        )?;
+        self.prototypes.push(format!("{};", prototype));
+
+        Ok(())
+    }
+
+    fn structure(&mut self, node: &StructDefinitionNode) -> Result<(), Error> {
+        let mut definition = format!("struct {} {{\n", node.name());
+        for (name, field_type) in node.fields() {
+            // arrays and slices are stored inline
+            let mut declarator = name.clone();
+            let mut field_type = field_type.as_ref();
+            while let Some(array_type) = field_type.as_any().downcast_ref::<ArrayType>() {
+                write!(declarator, "[{}]", array_type.length()).unwrap();
+                field_type = array_type.element_type().as_ref();
+            }
+            let declaration = if field_type.value_type() == ValueTypes::Slice {
+                Self::join(
+                    Self::slice_struct(node, field_type)?.as_str(),
+                    declarator.as_str(),
+                )
+            } else {
+                Self::declaration(node, field_type, declarator.as_str(), false)?
+            };
+            writeln!(definition, "    {};", declaration).unwrap();
+        }
+        definition.push_str("};");
+
+        self.structs.push(definition);
+        Ok(())
+    }
+
+    fn collect(&mut self, node: &Box<dyn Node>) -> Result<(), Error> {
+        match node.node_type() {
+            NodeType::Statements => {
+                for statement in node
+                    .as_any()
+                    .downcast_ref::<StatementsNode>()
+                    .unwrap()
+                    .statement_nodes()
+                {
+                    self.collect(statement)?;
+                }
+            }
+            NodeType::Import => {
+                self.collect(node.as_any().downcast_ref::<ImportNode>().unwrap().node())?
+            }
+            NodeType::FunctionDef => self.function(
+                node.as_any()
+                    .downcast_ref::<FunctionDefinitionNode>()
+                    .unwrap(),
+            )?,
+            NodeType::StructDef => self.structure(
+                node.as_any()
+                    .downcast_ref::<StructDefinitionNode>()
+                    .unwrap(),
+            )?,
+            NodeType::StaticDef => {
+                let static_def_node = node
+                    .as_any()
+                    .downcast_ref::<StaticDefinitionNode>()
+                    .unwrap();
+                let declaration = Self::declaration(
+                    node.as_ref(),
+                    static_def_node.value_type().as_ref(),
+                    format!("ST_{}", static_def_node.name()).as_str(),
+                    !static_def_node.is_mutable(),
+                )?;
+                self.statics.push(format!("extern {};", declaration));
+            }
+            // externs are defined somewhere else and tests aren't callable
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Generates the header for the given node. `guard` is the name of the include guard macro.
+    pub fn generate_to_str(&mut self, node: &Box<dyn Node>, guard: &str) -> Result<String, Error> {
+        self.collect(node)?;
+
+        let mut res = String::new();
+
+        writeln!(res, "/* Generated by the Umber compiler */").unwrap();
+        writeln!(res, "#ifndef {}", guard).unwrap();
+        writeln!(res, "#define {}\n", guard).unwrap();
+        writeln!(res, "#include <stdbool.h>").unwrap();
+        writeln!(res, "#include <stdint.h>\n").unwrap();
+        writeln!(res, "#ifdef __cplusplus\nextern \"C\" {{\n#endif\n").unwrap();
+
+        if !self.structs.is_empty() {
+            // every structure is declared first, so they can point to each other
+            for definition in &self.structs {
+                let name = definition["struct ".len()..].split(' ').next().unwrap();
+                writeln!(res, "typedef struct {} {};", name, name).unwrap();
+            }
+
+            writeln!(res, "\n#pragma pack(push, 1)").unwrap();
+            for definition in &self.structs {
+                writeln!(res, "{}", definition).unwrap();
+            }
+            writeln!(res, "#pragma pack(pop)\n").unwrap();
+        }
+
+        for declaration in &self.statics {
+            writeln!(res, "{}", declaration).unwrap();
+        }
+        if !self.statics.is_empty() {
+            writeln!(res).unwrap();
+        }
+
+        for prototype in &self.prototypes {
+            writeln!(res, "{}", prototype).unwrap();
+        }
+        if !self.prototypes.is_empty() {
+            writeln!(res).unwrap();
+        }
+
+        writeln!(res, "#ifdef __cplusplus\n}}\n#endif\n").unwrap();
+        writeln!(res, "#endif /* {} */", guard).unwrap();
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::semantics::Validator;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn generate(source: &str) -> Result<String, Error> {
+        let path = PathBuf::from("/tmp/c_header_test.ub");
+        let tokens = Lexer::new(path, source.to_string()).make_tokens().unwrap();
+
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+
+        let validation_res = Validator::new().validate(&ast_root);
+        assert!(
+            validation_res.error().is_none(),
+            "{:?}",
+            validation_res.error()
+        );
+
+        CHeaderGenerator::new().generate_to_str(validation_res.node().as_ref().unwrap(), "TEST_H")
+    }
+
+    #[test]
+    fn test_generate_header() {
+        let header = generate(
+            "struct point_t {
+    x: i32,
+    flag: bool,
+    next: struct point_t *mut
+};
+static mut counter: u16 = 3 as u16;
+static greeting: char * = \"hi\" as char *;
+extern fun puts(s: char *): u32;
+
+pub fun length(p: struct point_t *, names: char * * mut): f64 {
+    return 1.0;
+};
+fun reset(): void {
+    return;
+};",
+        )
+        .unwrap();
+
+        assert!(header.contains("#ifndef TEST_H\n#define TEST_H\n"));
+        assert!(header.contains("typedef struct point_t point_t;\n"));
+        assert!(header.contains(
+            "#pragma pack(push, 1)\nstruct point_t {\n    int32_t x;\n    bool flag;\n    point_t *next;\n};\n#pragma pack(pop)\n"
+        ));
+        assert!(header.contains("extern uint16_t ST_counter;\n"));
+        assert!(header.contains("extern const char *const ST_greeting;\n"));
+        assert!(header.contains("double length(const point_t *p, const char **names);\n"));
+        assert!(header.contains("void reset(void);\n"));
+        // externs are declared by the library defining them
+        assert!(!header.contains("puts"));
+    }
+
+    #[test]
+    fn test_arrays() {
+        let header = generate(
+            "struct packet_t {
+    len: u16,
+    grid: [[u8; 2]; 3]
+};
+static table: [u16; 3] = [<u16> 1 as u16, 2 as u16, 3 as u16];
+pub fun sum(values: [u32; 4]): u64 {
+    return 0;
+};",
+        )
+        .unwrap();
+
+        // arrays are stored inline in structures and passed around as the address of their elements
+        assert!(
+            header.contains("struct packet_t {\n    uint16_t len;\n    uint8_t grid[3][2];\n};")
+        );
+        assert!(header.contains("extern uint16_t *const ST_table;\n"));
+        assert!(header.contains("uint64_t sum(uint32_t *values);\n"));
+    }
+
+    #[test]
+    fn test_slices() {
+        let header = generate(
+            "struct name_t {
+    len: u16,
+    text: str
+};
+pub fun count(s: str, values: []u16): u64 {
+    return 0;
+};",
+        )
+        .unwrap();
+
+        // slices are stored inline in structures and split into two parameters
+        assert!(header.contains(
+            "struct name_t {\n    uint16_t len;\n    struct { const char *ptr; uint64_t len; } text;\n};"
+        ));
+        assert!(header.contains(
+            "uint64_t count(const char *s_ptr, uint64_t s_len, const uint16_t *values_ptr, uint64_t values_len);\n"
+        ));
+    }
+
+    #[test]
+    fn test_struct_by_value() {
+        let err = generate("struct a_t { x: u64 };\nstruct b_t { a: struct a_t };").unwrap_err();
+        assert_eq!(
+            err.details(),
+            "Structures can only be used through pointers in C headers, 'struct a_t' isn't!"
+        );
+    }
+}
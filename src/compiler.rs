@@ -2,26 +2,38 @@ use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Write;
 
+use clap::ValueEnum;
+
+use crate::constant;
+use crate::constant::Constant;
 use crate::nodes::address_of_node::AddressOfNode;
 use crate::nodes::array_node::ArrayNode;
 use crate::nodes::asm_node::AssemblyNode;
+use crate::nodes::assert_node::AssertNode;
 use crate::nodes::binop_node::BinOpNode;
+use crate::nodes::break_node::BreakNode;
 use crate::nodes::call_node::CallNode;
 use crate::nodes::cast_node::CastNode;
 use crate::nodes::char_node::CharNode;
+use crate::nodes::check_node::{Check, CheckNode, CHECK_FAILED_EXIT_CODE};
+use crate::nodes::floating_point_node::FloatingPointNode;
 use crate::nodes::const_def_node::ConstDefinitionNode;
+use crate::nodes::continue_node::ContinueNode;
+use crate::nodes::copy_node::CopyNode;
 use crate::nodes::extern_node::ExternNode;
 use crate::nodes::for_node::ForNode;
 use crate::nodes::functiondecl_node::FunctionDeclarationNode;
 use crate::nodes::functiondef_node::FunctionDefinitionNode;
 use crate::nodes::if_node::IfNode;
 use crate::nodes::import_node::ImportNode;
+use crate::nodes::memory_node::{MemoryNode, MemoryOperation};
 use crate::nodes::number_node::NumberNode;
 use crate::nodes::offset_node::OffsetNode;
 use crate::nodes::pointer_assign_node::PointerAssignNode;
 use crate::nodes::read_bytes_node::ReadBytesNode;
 use crate::nodes::return_node::ReturnNode;
 use crate::nodes::sizeof_node::SizeOfNode;
+use crate::nodes::slice_node::SliceNode;
 use crate::nodes::stack_allocation_node::StackAllocationNode;
 use crate::nodes::statements_node::StatementsNode;
 use crate::nodes::static_decl_node::StaticDeclarationNode;
@@ -34,9 +46,13 @@ use crate::nodes::var_node::typed_access::VarTypedAccessNode;
 use crate::nodes::var_node::typed_assign::VarTypedAssignNode;
 use crate::nodes::while_node::WhileNode;
 use crate::nodes::{Node, NodeType};
+use crate::position::Position;
 use crate::syscall::{CallingConvention, SyscallTable, TargetObjectType};
 use crate::token::TokenType;
+use crate::utils::unescape;
 use crate::values::value_size::ValueSize;
+use crate::values::value_type::slice_type::SLICE_LENGTH_OFFSET;
+use crate::values::value_type::ValueTypes;
 
 const QW_SCRATCH_REGS: [&str; 7] = ["rbx", "r10", "r11", "r12", "r13", "r14", "r15"];
 const DW_SCRATCH_REGS: [&str; 7] = ["ebx", "r10d", "r11d", "r12d", "r13d", "r14d", "r15d"];
@@ -57,13 +73,60 @@ const SYSCALL_REGS: [&str; 4] = ["rax", "rdi", "rsi", "rdx"];
 
 const ENTRY_SYMBOL: &str = "_start";
 
+/// Memory operations on at most this many bytes are unrolled into moves instead of using
+/// `rep stosb` or `rep movsb`.
+const UNROLL_LIMIT: u64 = 128;
+
+/// The assembler dialect the x86-64 code is generated in.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq)]
+pub enum AssemblySyntax {
+    /// NASM syntax, assembled with `nasm`
+    Nasm,
+    /// Intel syntax for the GNU assembler (`.intel_syntax noprefix`), assembled with `as`
+    Gas,
+}
+
+/// The address of a block of memory, which is either held by a scratch register or at the given
+/// offset below `rbp`.
+#[derive(Copy, Clone)]
+enum MemoryAddress {
+    Register(u8),
+    Frame(u64),
+}
+
+/// The number of bytes of a memory operation, either known at compile time or in a scratch
+/// register.
+#[derive(Copy, Clone)]
+enum MemoryCount {
+    Constant(u64),
+    Register(u8),
+}
+
+/// A loop that `break` and `continue` can jump out of
+struct Loop {
+    label: Option<String>,
+    continue_label: u128,
+    break_label: u128,
+}
+
+/// Data with values known at compile time, like the values of statics and the arrays they point to.
+struct DataDefinition {
+    label: String,
+    read_only: bool,
+    /// Statics are exported, so they can be accessed from C
+    global: bool,
+    size: ValueSize,
+    /// Numbers or labels of `size` bytes each
+    values: Vec<String>,
+}
+
 pub struct Compiler {
     scratch_regs: u8,
     label_count: u128,
     current_function_epilogue: Option<u128>,
 
-    current_loop_start: Option<u128>,
-    current_loop_break: Option<u128>,
+    /// The enclosing loops, the innermost one last
+    loops: Vec<Loop>,
 
     strings: HashMap<String, String>,
     constants: HashMap<String, String>,
@@ -72,30 +135,60 @@ pub struct Compiler {
     offset_table: HashMap<String, (u64, ValueSize)>,
 
     externs: Vec<String>,
+    /// Extern functions returning a floating point number, which is returned in xmm0
+    externs_returning_f64: Vec<String>,
 
     globals: Vec<String>,
 
     statics: HashMap<String, ValueSize>,
+    data: Vec<DataDefinition>,
+    data_label_count: usize,
+    /// Zero initialized data, that isn't a static itself
+    zeroed: Vec<(String, u64)>,
 
     target_object_type: TargetObjectType,
+
+    syntax: AssemblySyntax,
+
+    /// Whether the code is going to be linked into a shared library
+    position_independent: bool,
+    /// Functions that are not `pub` and therefore not exported from shared libraries
+    hidden: Vec<String>,
 }
 
 impl Compiler {
     pub fn new(target_object_type: TargetObjectType) -> Self {
+        Compiler::with_options(target_object_type, AssemblySyntax::Nasm, false)
+    }
+
+    /// Creates a compiler generating the given assembly syntax. Position independent code
+    /// addresses statics and strings relative to rip, calls functions through the PLT and only
+    /// exports `pub` functions, so it can be linked into a shared library.
+    pub fn with_options(
+        target_object_type: TargetObjectType,
+        syntax: AssemblySyntax,
+        position_independent: bool,
+    ) -> Self {
         Compiler {
             scratch_regs: 0,
             label_count: 0,
-            current_loop_start: None,
-            current_loop_break: None,
+            loops: vec![],
             current_function_epilogue: None,
             strings: HashMap::new(),
             constants: HashMap::new(),
             base_offset: 0,
             offset_table: HashMap::new(),
             externs: vec![],
+            externs_returning_f64: vec![],
             globals: vec![],
             statics: HashMap::new(),
+            data: vec![],
+            data_label_count: 0,
+            zeroed: vec![],
             target_object_type,
+            syntax,
+            position_independent,
+            hidden: vec![],
         }
     }
 
@@ -134,6 +227,7 @@ impl Compiler {
         match calling_convention {
             CallingConvention::Unix => 6,
             CallingConvention::Win => 4,
+            CallingConvention::Aapcs64 => unreachable!("AAPCS64 is only used by the aarch64 backend"),
         }
     }
 
@@ -147,18 +241,30 @@ impl Compiler {
             ValueSize::Byte => match calling_convention {
                 CallingConvention::Unix => B_NUMBER_ARG_REGS_UNIX[i as usize],
                 CallingConvention::Win => B_NUMBER_ARG_REGS_WIN[i as usize],
+                CallingConvention::Aapcs64 => {
+                    unreachable!("AAPCS64 is only used by the aarch64 backend")
+                }
             },
             ValueSize::Word => match calling_convention {
                 CallingConvention::Unix => W_NUMBER_ARG_REGS_UNIX[i as usize],
                 CallingConvention::Win => W_NUMBER_ARG_REGS_WIN[i as usize],
+                CallingConvention::Aapcs64 => {
+                    unreachable!("AAPCS64 is only used by the aarch64 backend")
+                }
             },
             ValueSize::Dword => match calling_convention {
                 CallingConvention::Unix => DW_NUMBER_ARG_REGS_UNIX[i as usize],
                 CallingConvention::Win => DW_NUMBER_ARG_REGS_WIN[i as usize],
+                CallingConvention::Aapcs64 => {
+                    unreachable!("AAPCS64 is only used by the aarch64 backend")
+                }
             },
             ValueSize::Qword => match calling_convention {
                 CallingConvention::Unix => QW_NUMBER_ARG_REGS_UNIX[i as usize],
                 CallingConvention::Win => QW_NUMBER_ARG_REGS_WIN[i as usize],
+                CallingConvention::Aapcs64 => {
+                    unreachable!("AAPCS64 is only used by the aarch64 backend")
+                }
             },
         }
     }
@@ -174,6 +280,20 @@ impl Compiler {
         self.label_count
     }
 
+    /// Returns the loop `break` or `continue` with the label jumps out of. Without a label, it's
+    /// the innermost loop.
+    fn find_loop(&self, label: &Option<String>) -> &Loop {
+        match label {
+            Some(label) => self
+                .loops
+                .iter()
+                .rev()
+                .find(|l| l.label.as_ref() == Some(label))
+                .unwrap(),
+            None => self.loops.last().unwrap(),
+        }
+    }
+
     fn label_name(&self, label: &u128) -> String {
         format!(".L{}", label)
     }
@@ -184,6 +304,75 @@ impl Compiler {
 
     //endregion
 
+    // region Syntax
+
+    /// Returns the size of a memory operand. GAS needs a `PTR`, otherwise it treats the size as a
+    /// number and adds it to the address.
+    fn size_spec(&self, size: &ValueSize) -> String {
+        match self.syntax {
+            AssemblySyntax::Nasm => format!("{}", size),
+            AssemblySyntax::Gas => format!("{} PTR", size),
+        }
+    }
+
+    /// Returns an immediate of the given size. GAS infers the size from the other operand.
+    fn sized_immediate(&self, size: &ValueSize, value: u64) -> String {
+        match self.syntax {
+            AssemblySyntax::Nasm => format!("{} {}", size, value),
+            AssemblySyntax::Gas => format!("{}", value),
+        }
+    }
+
+    fn should_use_rel(&self) -> bool {
+        self.target_object_type.should_use_rel() || self.position_independent
+    }
+
+    /// Returns the memory operand at the given symbol, relative to rip if the target needs it.
+    fn symbol_memory(&self, symbol: &str) -> String {
+        match (self.syntax, self.should_use_rel()) {
+            (AssemblySyntax::Nasm, true) => format!("[rel {}]", symbol),
+            (AssemblySyntax::Gas, true) => format!("[rip + {}]", symbol),
+            (_, false) => format!("[{}]", symbol),
+        }
+    }
+
+    /// Returns the address of the given symbol as an immediate.
+    fn symbol_offset(&self, symbol: &str) -> String {
+        match self.syntax {
+            AssemblySyntax::Nasm => format!("QWORD {}", symbol),
+            AssemblySyntax::Gas => format!("OFFSET {}", symbol),
+        }
+    }
+
+    /// Returns the operand to call the given function with, which goes through the PLT for
+    /// position independent code.
+    fn call_target(&self, function: &str) -> String {
+        match (self.syntax, self.position_independent) {
+            (AssemblySyntax::Nasm, true) => format!("{} wrt ..plt", function),
+            (AssemblySyntax::Gas, true) => format!("{}@PLT", function),
+            (_, false) => function.to_string(),
+        }
+    }
+
+    /// Returns the memory operand of the GOT entry of a static. Statics are exported, so in
+    /// position independent code they have to be accessed through the GOT, because an executable
+    /// linking the shared library can have its own copy of them.
+    fn static_got_entry(&self, static_name: &str) -> String {
+        match self.syntax {
+            AssemblySyntax::Nasm => format!("[rel {} wrt ..gotpcrel]", static_name),
+            AssemblySyntax::Gas => format!("[rip + {}@GOTPCREL]", static_name),
+        }
+    }
+
+    fn comment(&self) -> &str {
+        match self.syntax {
+            AssemblySyntax::Nasm => ";;",
+            AssemblySyntax::Gas => "#",
+        }
+    }
+
+    //endregion
+
     fn create_string_label(&mut self, string: String) -> String {
         if self.strings.contains_key(&string) {
             return self.strings[&string].clone();
@@ -195,6 +384,389 @@ impl Compiler {
         self.strings[&string].clone()
     }
 
+    /// Creates a label for a string, that is not taken from the source and therefore still has to be
+    /// escaped for NASM.
+    fn create_raw_string_label(&mut self, string: &str) -> String {
+        let escaped = string
+            .replace('\\', "\\\\")
+            .replace('`', "\\`")
+            .replace('\n', "\\n");
+
+        self.create_string_label(escaped)
+    }
+
+    fn create_data_label(&mut self) -> String {
+        self.data_label_count += 1;
+        format!("D{}", self.data_label_count - 1)
+    }
+
+    /// Returns the number or label, that a constant of the given size is stored as. The data
+    /// addresses point to is created as well.
+    fn constant_value(&mut self, constant: &Constant, size: ValueSize) -> String {
+        match constant {
+            Constant::Integer(_) | Constant::Float(_) => {
+                constant::truncate(constant.bits().unwrap(), size).to_string()
+            }
+            Constant::String(bytes) => {
+                let mut escaped = String::new();
+                for byte in bytes {
+                    match byte {
+                        b'`' | b'\\' => {
+                            escaped.push('\\');
+                            escaped.push(*byte as char);
+                        }
+                        b' '..=b'~' => escaped.push(*byte as char),
+                        _ => escaped.push_str(&format!("\\x{:02x}", byte)),
+                    }
+                }
+
+                self.create_string_label(escaped)
+            }
+            Constant::Array {
+                element_size,
+                elements,
+            } => {
+                let mut values: Vec<String> = vec![];
+                for element in elements {
+                    values.push(self.constant_value(element, *element_size));
+                }
+
+                // the elements are created first, so the label is unique
+                let label = self.create_data_label();
+                self.data.push(DataDefinition {
+                    label: label.clone(),
+                    read_only: false,
+                    global: false,
+                    size: *element_size,
+                    values,
+                });
+                label
+            }
+            Constant::Zeroed(bytes) => {
+                let label = self.create_data_label();
+                self.zeroed.push((label.clone(), *bytes));
+                label
+            }
+        }
+    }
+
+    /// Writes the given string to the given file descriptor using the `write` syscall.
+    /// Clobbers rax, rdi, rsi, rdx, rcx and r11.
+    fn write_raw_string(&mut self, fd: u8, string: &str, w: &mut String) -> fmt::Result {
+        let str_label = self.create_raw_string_label(string);
+
+        writeln!(
+            w,
+            "\tmov     rax, {}",
+            SyscallTable::Write.code(self.target_object_type)
+        )?;
+        writeln!(w, "\tmov     rdi, {}", fd)?;
+        if self.should_use_rel() {
+            writeln!(w, "\tlea     rsi, {}", self.symbol_memory(&str_label))?;
+        } else {
+            writeln!(w, "\tmov     rsi, {}", self.symbol_offset(&str_label))?;
+        }
+        writeln!(w, "\tmov     rdx, {}", string.len())?;
+        writeln!(w, "\tsyscall")
+    }
+
+    /// Writes the message to stderr and exits with the given code.
+    fn trap(&mut self, message: &str, exit_code: u64, w: &mut String) -> fmt::Result {
+        // there are no stable syscall numbers on Windows, so we can only exit there
+        if !matches!(self.target_object_type, TargetObjectType::Win64) {
+            self.write_raw_string(2, message, w)?;
+        }
+
+        writeln!(w, "\tmov     rdi, {}", exit_code)?;
+        writeln!(
+            w,
+            "\tmov     rax, {}",
+            SyscallTable::Exit.code(self.target_object_type)
+        )?;
+        writeln!(w, "\tsyscall")
+    }
+
+    /// Sign or zero extends the value of the given size in `src` to 64 bits in `dst`.
+    fn extend(
+        &self,
+        dst: u8,
+        src: u8,
+        size: ValueSize,
+        signed: bool,
+        w: &mut String,
+    ) -> fmt::Result {
+        match (size, signed) {
+            (ValueSize::Qword, _) => {
+                writeln!(w, "\tmov     {}, {}", self.scratch_name(dst), self.scratch_name(src))
+            }
+            // writing the lower 32 bits clears the upper ones
+            (ValueSize::Dword, false) => writeln!(
+                w,
+                "\tmov     {}, {}",
+                self.scratch_name_lower_sized(dst, &size),
+                self.scratch_name_lower_sized(src, &size)
+            ),
+            (ValueSize::Dword, true) => writeln!(
+                w,
+                "\tmovsxd  {}, {}",
+                self.scratch_name(dst),
+                self.scratch_name_lower_sized(src, &size)
+            ),
+            _ => writeln!(
+                w,
+                "\t{:<8}{}, {}",
+                if signed { "movsx" } else { "movzx" },
+                self.scratch_name(dst),
+                self.scratch_name_lower_sized(src, &size)
+            ),
+        }
+    }
+
+    // region Memory operations
+
+    /// Returns the memory operand `displacement` bytes after the address.
+    fn memory_operand(&self, address: MemoryAddress, displacement: u64) -> String {
+        match address {
+            MemoryAddress::Register(reg) if displacement == 0 => {
+                format!("[{}]", self.scratch_name(reg))
+            }
+            MemoryAddress::Register(reg) => {
+                format!("[{} + {}]", self.scratch_name(reg), displacement)
+            }
+            MemoryAddress::Frame(offset) => format!("[rbp-{}]", offset - displacement),
+        }
+    }
+
+    /// Loads the address into the given register.
+    fn load_address(&self, dst: &str, address: MemoryAddress, w: &mut String) -> fmt::Result {
+        match address {
+            MemoryAddress::Register(reg) => {
+                writeln!(w, "\tmov     {}, {}", dst, self.scratch_name(reg))
+            }
+            MemoryAddress::Frame(_) => {
+                writeln!(w, "\tlea     {}, {}", dst, self.memory_operand(address, 0))
+            }
+        }
+    }
+
+    /// Loads the number of bytes into `rcx`.
+    fn load_count(&self, count: MemoryCount, w: &mut String) -> fmt::Result {
+        match count {
+            MemoryCount::Constant(count) => writeln!(w, "\tmov     rcx, {}", count),
+            MemoryCount::Register(reg) => writeln!(w, "\tmov     rcx, {}", self.scratch_name(reg)),
+        }
+    }
+
+    /// Returns the width and the offsets of the moves covering `count` bytes. The last move
+    /// overlaps the previous one, if the count isn't a multiple of the width.
+    fn unrolled_moves(count: u64) -> (u64, Vec<u64>) {
+        let width = match count {
+            16.. => 16,
+            8.. => 8,
+            4.. => 4,
+            2.. => 2,
+            _ => 1,
+        };
+
+        let mut offsets: Vec<u64> = (0..count / width).map(|i| i * width).collect();
+        if !count.is_multiple_of(width) {
+            offsets.push(count - width);
+        }
+        (width, offsets)
+    }
+
+    /// Returns the register moving the `index`th chunk of `width` bytes and the size of its memory
+    /// operand. Chunks of 16 bytes are moved through `xmm0` to `xmm7`, smaller ones through `rax`
+    /// and `rcx`.
+    fn unrolled_register(&self, width: u64, index: usize) -> (String, String) {
+        let size = match width {
+            16 => return (format!("xmm{}", index), String::new()),
+            8 => ValueSize::Qword,
+            4 => ValueSize::Dword,
+            2 => ValueSize::Word,
+            _ => ValueSize::Byte,
+        };
+
+        let register = match (size, index) {
+            (ValueSize::Qword, 0) => "rax",
+            (ValueSize::Qword, _) => "rcx",
+            (ValueSize::Dword, 0) => "eax",
+            (ValueSize::Dword, _) => "ecx",
+            (ValueSize::Word, 0) => "ax",
+            (ValueSize::Word, _) => "cx",
+            (ValueSize::Byte, 0) => "al",
+            (ValueSize::Byte, _) => "cl",
+        };
+        (register.to_string(), format!("{} ", self.size_spec(&size)))
+    }
+
+    /// Sets `count` bytes at the address to the lowest byte of the value register, or to zero.
+    fn fill_memory(
+        &mut self,
+        destination: MemoryAddress,
+        value: Option<u8>,
+        count: MemoryCount,
+        w: &mut String,
+    ) -> fmt::Result {
+        let count = match count {
+            MemoryCount::Constant(count) if count <= UNROLL_LIMIT => count,
+            _ => {
+                writeln!(w, "\tpush    rdi")?;
+                writeln!(w, "\tpush    rcx")?;
+                writeln!(w, "\tpush    rax")?;
+                self.load_address("rdi", destination, w)?;
+                self.load_count(count, w)?;
+                match value {
+                    Some(value) => writeln!(
+                        w,
+                        "\tmovzx   eax, {}",
+                        self.scratch_name_lower_sized(value, &ValueSize::Byte)
+                    )?,
+                    None => writeln!(w, "\txor     eax, eax")?,
+                }
+                writeln!(w, "\trep stosb")?;
+                writeln!(w, "\tpop     rax")?;
+                writeln!(w, "\tpop     rcx")?;
+                return writeln!(w, "\tpop     rdi");
+            }
+        };
+
+        let (width, offsets) = Self::unrolled_moves(count);
+        if offsets.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(w, "\tpush    rax")?;
+        writeln!(w, "\tpush    rcx")?;
+        match value {
+            // repeat the byte in every byte of rax
+            Some(value) => {
+                writeln!(
+                    w,
+                    "\tmovzx   eax, {}",
+                    self.scratch_name_lower_sized(value, &ValueSize::Byte)
+                )?;
+                writeln!(w, "\tmov     rcx, 0x0101010101010101")?;
+                writeln!(w, "\timul    rax, rcx")?;
+                if width == 16 {
+                    writeln!(w, "\tmovq    xmm0, rax")?;
+                    writeln!(w, "\tpunpcklqdq xmm0, xmm0")?;
+                }
+            }
+            None if width == 16 => writeln!(w, "\tpxor    xmm0, xmm0")?,
+            None => writeln!(w, "\txor     eax, eax")?,
+        }
+
+        let (register, size) = self.unrolled_register(width, 0);
+        let mnemonic = if width == 16 { "movdqu" } else { "mov" };
+        for offset in offsets {
+            writeln!(
+                w,
+                "\t{:<8}{}{}, {}",
+                mnemonic,
+                size,
+                self.memory_operand(destination, offset),
+                register
+            )?;
+        }
+        writeln!(w, "\tpop     rcx")?;
+        writeln!(w, "\tpop     rax")
+    }
+
+    /// Copies `count` bytes from the source to the destination address. If the blocks can
+    /// overlap, the copy runs backwards when the destination starts within the source.
+    fn copy_memory(
+        &mut self,
+        destination: MemoryAddress,
+        source: MemoryAddress,
+        count: MemoryCount,
+        overlapping: bool,
+        w: &mut String,
+    ) -> fmt::Result {
+        let count = match count {
+            MemoryCount::Constant(count) if count <= UNROLL_LIMIT => count,
+            _ => {
+                writeln!(w, "\tpush    rdi")?;
+                writeln!(w, "\tpush    rsi")?;
+                writeln!(w, "\tpush    rcx")?;
+                self.load_address("rdi", destination, w)?;
+                self.load_address("rsi", source, w)?;
+                self.load_count(count, w)?;
+
+                if !overlapping {
+                    writeln!(w, "\trep movsb")?;
+                } else {
+                    let label_forward = self.label_create();
+                    let label_end = self.label_create();
+
+                    // copying forwards is only wrong if the destination starts within the source
+                    writeln!(w, "\tpush    rax")?;
+                    writeln!(w, "\tmov     rax, rdi")?;
+                    writeln!(w, "\tsub     rax, rsi")?;
+                    writeln!(w, "\tcmp     rax, rcx")?;
+                    writeln!(w, "\tpop     rax")?;
+                    writeln!(w, "\tjae     {}", self.label_name(&label_forward))?;
+                    writeln!(w, "\tadd     rdi, rcx")?;
+                    writeln!(w, "\tdec     rdi")?;
+                    writeln!(w, "\tadd     rsi, rcx")?;
+                    writeln!(w, "\tdec     rsi")?;
+                    writeln!(w, "\tstd")?;
+                    writeln!(w, "\trep movsb")?;
+                    writeln!(w, "\tcld")?;
+                    writeln!(w, "\tjmp     {}", self.label_name(&label_end))?;
+                    writeln!(w, "{}:", self.label_name(&label_forward))?;
+                    writeln!(w, "\trep movsb")?;
+                    writeln!(w, "{}:", self.label_name(&label_end))?;
+                }
+
+                writeln!(w, "\tpop     rcx")?;
+                writeln!(w, "\tpop     rsi")?;
+                return writeln!(w, "\tpop     rdi");
+            }
+        };
+
+        let (width, offsets) = Self::unrolled_moves(count);
+        if offsets.is_empty() {
+            return Ok(());
+        }
+
+        // all chunks are loaded before the first store, so overlapping blocks are copied correctly
+        let mnemonic = if width == 16 { "movdqu" } else { "mov" };
+        if width != 16 {
+            writeln!(w, "\tpush    rax")?;
+            writeln!(w, "\tpush    rcx")?;
+        }
+        for (i, offset) in offsets.iter().enumerate() {
+            let (register, size) = self.unrolled_register(width, i);
+            writeln!(
+                w,
+                "\t{:<8}{}, {}{}",
+                mnemonic,
+                register,
+                size,
+                self.memory_operand(source, *offset)
+            )?;
+        }
+        for (i, offset) in offsets.iter().enumerate() {
+            let (register, size) = self.unrolled_register(width, i);
+            writeln!(
+                w,
+                "\t{:<8}{}{}, {}",
+                mnemonic,
+                size,
+                self.memory_operand(destination, *offset),
+                register
+            )?;
+        }
+        if width != 16 {
+            writeln!(w, "\tpop     rcx")?;
+            writeln!(w, "\tpop     rax")?;
+        }
+        Ok(())
+    }
+
+    //endregion
+
     fn register_var(&mut self, name: String, size: ValueSize) {
         self.base_offset += size.get_size_in_bytes() as u64;
         self.offset_table.insert(name, (self.base_offset, size));
@@ -262,15 +834,226 @@ impl Compiler {
 }
 
 impl Compiler {
+    /// Calls a C function following the System V ABI. Floating point arguments are passed in the
+    /// xmm registers and `al` holds the number of them, which variadic functions rely on. Integers
+    /// smaller than 32 bits are extended to `int`, like C's default argument promotions, and the
+    /// stack is aligned to 16 bytes for the call.
+    /// Pushes the arguments of the call in reverse order, so the first one is on the top of the
+    /// stack. Slices are pushed as their pointer and length.
+    fn push_args(&mut self, call_node: &CallNode, w: &mut String) -> fmt::Result {
+        for (i, arg) in call_node.arg_nodes().iter().enumerate().rev() {
+            let reg = self.code_gen(arg, w)?.unwrap();
+            if call_node.is_slice_arg(i) {
+                writeln!(
+                    w,
+                    "\tpush    {} [{} + {}]",
+                    self.size_spec(&ValueSize::Qword),
+                    self.scratch_name(reg),
+                    SLICE_LENGTH_OFFSET
+                )?;
+                writeln!(
+                    w,
+                    "\tpush    {} [{}]",
+                    self.size_spec(&ValueSize::Qword),
+                    self.scratch_name(reg)
+                )?;
+            } else {
+                writeln!(w, "\tpush    {}", self.scratch_name(reg))?;
+            }
+            self.free_scratch(reg);
+        }
+        Ok(())
+    }
+
+    /// Stores the argument with the index, counting slices as two arguments, at `[rbp - offset]`.
+    fn store_arg(
+        &mut self,
+        index: usize,
+        offset: u64,
+        size: &ValueSize,
+        w: &mut String,
+    ) -> fmt::Result {
+        let num_arg_regs = self.number_arg_reg_size(&self.target_object_type.calling_convention());
+        if index >= num_arg_regs {
+            let reg = self.res_scratch();
+
+            let non_reg_index = index - num_arg_regs + 1;
+
+            writeln!(
+                w,
+                "\tmov     {}, {} [rbp + {}]",
+                self.scratch_name(reg),
+                self.size_spec(&ValueSize::Qword),
+                (non_reg_index * 8) + 8
+            )?;
+            writeln!(
+                w,
+                "\tmov     {} [rbp - ({})], {}",
+                self.size_spec(size),
+                offset,
+                self.scratch_name_lower_sized(reg, size)
+            )?;
+            self.free_scratch(reg);
+        } else {
+            writeln!(
+                w,
+                "\tmov     {} [rbp - ({})], {}",
+                self.size_spec(size),
+                offset,
+                self.number_arg_reg_name(
+                    index as u8,
+                    size,
+                    &self.target_object_type.calling_convention()
+                )
+            )?;
+        }
+        Ok(())
+    }
+
+    fn extern_call_sysv(&mut self, call_node: &CallNode, w: &mut String) -> Result<u8, fmt::Error> {
+        writeln!(w, "\tpush    r10")?;
+        writeln!(w, "\tpush    r11")?;
+
+        // the argument i is at [r11 + i * 8] after this
+        self.push_args(call_node, w)?;
+        writeln!(w, "\tmov     r11, rsp")?;
+        writeln!(w, "\tand     rsp, -16")?;
+        writeln!(w, "\tpush    r11")?;
+
+        let arg_types = call_node.passed_arg_types();
+        let mut int_args: Vec<usize> = vec![];
+        let mut float_args: Vec<usize> = vec![];
+        let mut stack_args: Vec<usize> = vec![];
+        for (i, arg_type) in arg_types.iter().enumerate() {
+            if arg_type.value_type() == ValueTypes::F64 && float_args.len() < 8 {
+                float_args.push(i);
+            } else if arg_type.value_type() != ValueTypes::F64
+                && int_args.len() < QW_NUMBER_ARG_REGS_UNIX.len()
+            {
+                int_args.push(i);
+            } else {
+                stack_args.push(i);
+            }
+        }
+
+        // the saved stack pointer and the stack arguments have to keep the alignment
+        let padding = if stack_args.len().is_multiple_of(2) { 8 } else { 0 };
+        if padding > 0 {
+            writeln!(w, "\tsub     rsp, {}", padding)?;
+        }
+        for i in stack_args.iter().rev() {
+            writeln!(
+                w,
+                "\tpush    {} [r11 + {}]",
+                self.size_spec(&ValueSize::Qword),
+                i * 8
+            )?;
+        }
+
+        for (reg_index, i) in int_args.iter().enumerate() {
+            let size = arg_types[*i].get_size();
+            let extension = match arg_types[*i].value_type() {
+                ValueTypes::I8 | ValueTypes::I16 => "movsx",
+                _ => "movzx",
+            };
+
+            if size == ValueSize::Byte || size == ValueSize::Word {
+                writeln!(
+                    w,
+                    "\t{:<8}{}, {} [r11 + {}]",
+                    extension,
+                    DW_NUMBER_ARG_REGS_UNIX[reg_index],
+                    self.size_spec(&size),
+                    i * 8
+                )?;
+            } else {
+                writeln!(
+                    w,
+                    "\tmov     {}, {} [r11 + {}]",
+                    QW_NUMBER_ARG_REGS_UNIX[reg_index],
+                    self.size_spec(&ValueSize::Qword),
+                    i * 8
+                )?;
+            }
+        }
+
+        for (reg_index, i) in float_args.iter().enumerate() {
+            writeln!(
+                w,
+                "\tmov     rax, {} [r11 + {}]",
+                self.size_spec(&ValueSize::Qword),
+                i * 8
+            )?;
+            writeln!(w, "\tmovq    xmm{}, rax", reg_index)?;
+        }
+
+        writeln!(w, "\tmov     eax, {}", float_args.len())?;
+        writeln!(
+            w,
+            "\tcall    {}",
+            self.call_target(call_node.func_to_call())
+        )?;
+
+        if self
+            .externs_returning_f64
+            .contains(&call_node.func_to_call().to_string())
+        {
+            writeln!(w, "\tmovq    rax, xmm0")?;
+        }
+
+        writeln!(
+            w,
+            "\tmov     rsp, {} [rsp + {}]",
+            self.size_spec(&ValueSize::Qword),
+            stack_args.len() * 8 + padding
+        )?;
+        if !arg_types.is_empty() {
+            writeln!(w, "\tadd     rsp, {}", arg_types.len() * 8)?;
+        }
+        writeln!(w, "\tpop     r11")?;
+        writeln!(w, "\tpop     r10")?;
+
+        let reg = self.res_scratch();
+        writeln!(w, "\tmov     {}, rax", self.scratch_name(reg))?;
+
+        Ok(reg)
+    }
+
+    /// Generates a branch of an `if`, moving its value into `result_reg` if the `if` has one.
+    fn branch(
+        &mut self,
+        statements: &Box<dyn Node>,
+        result_reg: Option<u8>,
+        w: &mut String,
+    ) -> fmt::Result {
+        let reg = self.code_gen(statements, w)?;
+
+        if let (Some(result_reg), Some(reg)) = (result_reg, reg) {
+            writeln!(
+                w,
+                "\tmov     {}, {}",
+                self.scratch_name(result_reg),
+                self.scratch_name(reg)
+            )?;
+        }
+        if let Some(reg) = reg {
+            self.free_scratch(reg);
+        }
+        Ok(())
+    }
+
     fn code_gen(&mut self, node: &Box<dyn Node>, w: &mut String) -> Result<Option<u8>, fmt::Error> {
         if node.node_type() == NodeType::Statements {
-            for n in node
-                .as_any()
-                .downcast_ref::<StatementsNode>()
-                .unwrap()
-                .statement_nodes()
-            {
+            let statements_node = node.as_any().downcast_ref::<StatementsNode>().unwrap();
+
+            let statement_nodes = statements_node.statement_nodes();
+            for (i, n) in statement_nodes.iter().enumerate() {
                 let reg = self.code_gen(n, w)?;
+
+                // the register of the last statement holds the value of the block
+                if statements_node.has_value() && i == statement_nodes.len() - 1 {
+                    return Ok(reg);
+                }
                 if reg.is_some() {
                     self.free_scratch(reg.unwrap());
                 }
@@ -294,7 +1077,7 @@ impl Compiler {
             // TODO: push syscall regs
 
             let syscall_node = node.as_any().downcast_ref::<SyscallNode>().unwrap();
-            writeln!(w, "\n;; Syscall injected")?;
+            writeln!(w, "\n{} Syscall injected", self.comment())?;
             for i in 0..4 {
                 let reg = self.code_gen(&syscall_node.args()[i], w)?.unwrap();
                 writeln!(
@@ -305,7 +1088,7 @@ impl Compiler {
                 )?;
                 self.free_scratch(reg);
             }
-            writeln!(w, "\tsyscall\n;; End injected syscall\n")?;
+            writeln!(w, "\tsyscall\n{} End injected syscall\n", self.comment())?;
 
             let result_reg = self.res_scratch();
             writeln!(w, "\tmov     {}, rax", self.scratch_name(result_reg))?;
@@ -313,6 +1096,194 @@ impl Compiler {
             return Ok(Some(result_reg));
         }
 
+        if node.node_type() == NodeType::Memory {
+            let memory_node = node.as_any().downcast_ref::<MemoryNode>().unwrap();
+            let destination_reg = self.code_gen(memory_node.destination(), w)?.unwrap();
+            let source_reg = self.code_gen(memory_node.source(), w)?.unwrap();
+
+            let count = match memory_node.count().node_type() {
+                NodeType::Number => MemoryCount::Constant(
+                    memory_node
+                        .count()
+                        .as_any()
+                        .downcast_ref::<NumberNode>()
+                        .unwrap()
+                        .get_number(),
+                ),
+                _ => MemoryCount::Register(self.code_gen(memory_node.count(), w)?.unwrap()),
+            };
+
+            let destination = MemoryAddress::Register(destination_reg);
+            match memory_node.operation() {
+                MemoryOperation::Set => {
+                    self.fill_memory(destination, Some(source_reg), count, w)?
+                }
+                operation => self.copy_memory(
+                    destination,
+                    MemoryAddress::Register(source_reg),
+                    count,
+                    operation == MemoryOperation::Move,
+                    w,
+                )?,
+            }
+
+            if let MemoryCount::Register(count_reg) = count {
+                self.free_scratch(count_reg);
+            }
+            self.free_scratch(source_reg);
+            self.free_scratch(destination_reg);
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Assert {
+            let assert_node = node.as_any().downcast_ref::<AssertNode>().unwrap();
+            let label_ok = self.label_create();
+
+            let condition_reg = self.code_gen(assert_node.condition_node(), w)?.unwrap();
+            writeln!(w, "\tcmp     {}, 0", self.scratch_name(condition_reg))?;
+            self.free_scratch(condition_reg);
+            writeln!(w, "\tjne     {}", self.label_name(&label_ok))?;
+
+            let pos = assert_node.pos_start();
+            self.trap(
+                &format!(
+                    "{}:{}:{}: Assertion failed!\n",
+                    pos.file_name().display(),
+                    pos.line() + 1,
+                    pos.col() + 1
+                ),
+                1,
+                w,
+            )?;
+
+            writeln!(w, "{}:", self.label_name(&label_ok))?;
+            return Ok(None);
+        }
+
+        if node.node_type() == NodeType::Check {
+            let check_node = node.as_any().downcast_ref::<CheckNode>().unwrap();
+            let label_ok = self.label_create();
+
+            let reg = match *check_node.check() {
+                Check::Bounds(length) => {
+                    let reg = self.code_gen(check_node.node(), w)?.unwrap();
+                    let length_reg = self.res_scratch();
+                    writeln!(w, "\tmov     {}, {}", self.scratch_name(length_reg), length)?;
+                    // the comparison is unsigned, so negative indices are out of bounds as well
+                    writeln!(
+                        w,
+                        "\tcmp     {}, {}",
+                        self.scratch_name(reg),
+                        self.scratch_name(length_reg)
+                    )?;
+                    self.free_scratch(length_reg);
+                    writeln!(w, "\tjb      {}", self.label_name(&label_ok))?;
+                    reg
+                }
+                Check::SliceBounds | Check::SliceRange => {
+                    let reg = self.code_gen(check_node.node(), w)?.unwrap();
+                    let length_reg = self.code_gen(check_node.length(), w)?.unwrap();
+                    writeln!(
+                        w,
+                        "\tcmp     {}, {}",
+                        self.scratch_name(reg),
+                        self.scratch_name(length_reg)
+                    )?;
+                    self.free_scratch(length_reg);
+                    let condition = match *check_node.check() {
+                        Check::SliceBounds => "jb",
+                        _ => "jbe",
+                    };
+                    writeln!(w, "\t{:<8}{}", condition, self.label_name(&label_ok))?;
+                    reg
+                }
+                Check::Null => {
+                    let reg = self.code_gen(check_node.node(), w)?.unwrap();
+                    writeln!(w, "\tcmp     {}, 0", self.scratch_name(reg))?;
+                    writeln!(w, "\tjne     {}", self.label_name(&label_ok))?;
+                    reg
+                }
+                Check::Overflow { size, signed } => {
+                    let bin_op_node = check_node
+                        .node()
+                        .as_any()
+                        .downcast_ref::<BinOpNode>()
+                        .unwrap();
+                    let op = bin_op_node.op_token().token_type();
+                    let left_reg = self.code_gen(bin_op_node.left_node(), w)?.unwrap();
+                    let right_reg = self.code_gen(bin_op_node.right_node(), w)?.unwrap();
+
+                    // smaller integers are calculated with 64 bits, where they can't overflow, and
+                    // the result has to be representable in their size
+                    if size != ValueSize::Qword {
+                        self.extend(left_reg, left_reg, size, signed, w)?;
+                        self.extend(right_reg, right_reg, size, signed, w)?;
+                    }
+
+                    let (left, right) = (
+                        self.scratch_name(left_reg).to_string(),
+                        self.scratch_name(right_reg).to_string(),
+                    );
+                    match op {
+                        TokenType::Plus => writeln!(w, "\tadd     {}, {}", left, right)?,
+                        TokenType::Minus => writeln!(w, "\tsub     {}, {}", left, right)?,
+                        // the unsigned multiplication sets the overflow flag like 'imul'
+                        TokenType::Mul if size == ValueSize::Qword && !signed => {
+                            writeln!(w, "\tpush    rax")?;
+                            writeln!(w, "\tpush    rdx")?;
+                            writeln!(w, "\tmov     rax, {}", left)?;
+                            writeln!(w, "\tmul     {}", right)?;
+                            writeln!(w, "\tmov     {}, rax", left)?;
+                            writeln!(w, "\tpop     rdx")?;
+                            writeln!(w, "\tpop     rax")?;
+                        }
+                        _ => writeln!(w, "\timul    {}, {}", left, right)?,
+                    }
+                    self.free_scratch(right_reg);
+
+                    if size == ValueSize::Qword {
+                        let condition = match (op, signed) {
+                            (TokenType::Plus | TokenType::Minus, false) => "jnc",
+                            _ => "jno",
+                        };
+                        writeln!(w, "\t{:<8}{}", condition, self.label_name(&label_ok))?;
+                    } else {
+                        let truncated_reg = self.res_scratch();
+                        self.extend(truncated_reg, left_reg, size, signed, w)?;
+                        writeln!(
+                            w,
+                            "\tcmp     {}, {}",
+                            self.scratch_name(truncated_reg),
+                            left
+                        )?;
+                        self.free_scratch(truncated_reg);
+                        writeln!(w, "\tje      {}", self.label_name(&label_ok))?;
+                    }
+                    left_reg
+                }
+            };
+
+            self.trap(
+                &check_node.failure_message(),
+                CHECK_FAILED_EXIT_CODE,
+                w,
+            )?;
+            writeln!(w, "{}:", self.label_name(&label_ok))?;
+
+            // negative results of smaller integers are zero extended, like all of their values
+            if let Check::Overflow {
+                size,
+                signed: true,
+            } = *check_node.check()
+            {
+                if size != ValueSize::Qword {
+                    self.extend(reg, reg, size, false, w)?;
+                }
+            }
+
+            return Ok(Some(reg));
+        }
+
         if node.node_type() == NodeType::Number {
             let number_node = node.as_any().downcast_ref::<NumberNode>().unwrap();
 
@@ -327,10 +1298,9 @@ impl Compiler {
             }
             writeln!(
                 w,
-                "\tmov     {}, {} {}",
+                "\tmov     {}, {}",
                 self.scratch_name_lower_sized(reg, &number_node.size().get_size()),
-                number_node.size().get_size(),
-                number_node.get_number()
+                self.sized_immediate(&number_node.size().get_size(), number_node.get_number())
             )?;
             return Ok(Some(reg));
         }
@@ -344,19 +1314,19 @@ impl Compiler {
             );
             let reg = self.res_scratch();
 
-            if self.target_object_type.should_use_rel() {
+            if self.should_use_rel() {
                 writeln!(
                     w,
-                    "\tlea     {}, [rel {}]",
+                    "\tlea     {}, {}",
                     self.scratch_name(reg),
-                    str_label
+                    self.symbol_memory(&str_label)
                 )?;
             } else {
                 writeln!(
                     w,
-                    "\tmov     {}, QWORD {}",
+                    "\tmov     {}, {}",
                     self.scratch_name(reg),
-                    str_label
+                    self.symbol_offset(&str_label)
                 )?;
             }
             return Ok(Some(reg));
@@ -372,9 +1342,12 @@ impl Compiler {
             )?;
             writeln!(
                 w,
-                "\tmov     {}, BYTE {}",
+                "\tmov     {}, {}",
                 self.scratch_name_lower_sized(reg, &ValueSize::Byte),
-                *node.as_any().downcast_ref::<CharNode>().unwrap().value() as u8
+                self.sized_immediate(
+                    &ValueSize::Byte,
+                    *node.as_any().downcast_ref::<CharNode>().unwrap().value() as u64
+                )
             )?;
             return Ok(Some(reg));
         }
@@ -387,29 +1360,24 @@ impl Compiler {
                 * array_node.element_type().get_size().get_size_in_bytes() as u64;
 
             if array_node.element_nodes().is_empty() {
-                println!("it is empty");
-                for i in 0..*array_node.size() {
-                    writeln!(
-                        w,
-                        "\tmov     {} [rbp-{}], {}",
-                        array_node.element_type().get_size(),
-                        beginning_offset
-                            + ((i + 1) as u64
-                                * array_node.element_type().get_size().get_size_in_bytes() as u64),
-                        0
-                    )?;
-                }
+                self.fill_memory(
+                    MemoryAddress::Frame(self.base_offset),
+                    None,
+                    MemoryCount::Constant(self.base_offset - beginning_offset),
+                    w,
+                )?;
             }
 
             for (i, elem) in array_node.element_nodes().iter().rev().enumerate() {
                 let reg = self.code_gen(elem, w)?.unwrap();
                 writeln!(
                     w,
-                    "\tmov     [rbp-{}], {}",
+                    "\tmov     {} [rbp-{}], {}",
+                    self.size_spec(&array_node.element_type().get_size()),
                     beginning_offset
                         + ((i + 1) as u64
                             * array_node.element_type().get_size().get_size_in_bytes() as u64),
-                    self.scratch_name(reg)
+                    self.scratch_name_lower_sized(reg, &array_node.element_type().get_size())
                 )?;
                 self.free_scratch(reg);
             }
@@ -593,11 +1561,21 @@ impl Compiler {
                     writeln!(w, "\tjne     {}", self.label_name(&label_true))?;
                 }
 
-                writeln!(w, "\tmov     {}, QWORD 0", self.scratch_name(res_reg))?;
+                writeln!(
+                    w,
+                    "\tmov     {}, {}",
+                    self.scratch_name(res_reg),
+                    self.sized_immediate(&ValueSize::Qword, 0)
+                )?;
                 writeln!(w, "\tjmp     {}", self.label_name(&label_after))?;
 
                 writeln!(w, "{}:", self.label_name(&label_true))?;
-                writeln!(w, "\tmov     {}, QWORD 1", self.scratch_name(res_reg))?;
+                writeln!(
+                    w,
+                    "\tmov     {}, {}",
+                    self.scratch_name(res_reg),
+                    self.sized_immediate(&ValueSize::Qword, 1)
+                )?;
 
                 writeln!(w, "{}:", self.label_name(&label_after))?;
             } else {
@@ -625,13 +1603,28 @@ impl Compiler {
                 let label_after = self.label_create();
 
                 res_reg = self.res_scratch();
-                writeln!(w, "\tcmp     {}, QWORD 0", self.scratch_name(left))?;
+                writeln!(
+                    w,
+                    "\tcmp     {}, {}",
+                    self.scratch_name(left),
+                    self.sized_immediate(&ValueSize::Qword, 0)
+                )?;
                 self.free_scratch(left);
                 writeln!(w, "\tje      {}", self.label_name(&label_true))?;
-                writeln!(w, "\tmov     {}, QWORD 0", self.scratch_name(res_reg))?;
+                writeln!(
+                    w,
+                    "\tmov     {}, {}",
+                    self.scratch_name(res_reg),
+                    self.sized_immediate(&ValueSize::Qword, 0)
+                )?;
                 writeln!(w, "\tjmp     {}", self.label_name(&label_after))?;
                 writeln!(w, "{}:", self.label_name(&label_true))?;
-                writeln!(w, "\tmov     {}, QWORD 1", self.scratch_name(res_reg))?;
+                writeln!(
+                    w,
+                    "\tmov     {}, {}",
+                    self.scratch_name(res_reg),
+                    self.sized_immediate(&ValueSize::Qword, 1)
+                )?;
                 writeln!(w, "{}:", self.label_name(&label_after))?;
             } else if unary_op_node.op_token().token_type() == TokenType::BitNot {
                 writeln!(w, "\tnot     {}", self.scratch_name(res_reg))?;
@@ -645,9 +1638,32 @@ impl Compiler {
             return Ok(Some(res_reg));
         }
 
+        if node.node_type() == NodeType::FloatingPoint {
+            let floating_point_node = node.as_any().downcast_ref::<FloatingPointNode>().unwrap();
+
+            let reg = self.res_scratch();
+            writeln!(
+                w,
+                "\tmov     {}, {}",
+                self.scratch_name(reg),
+                floating_point_node.ieee_754()
+            )?;
+            return Ok(Some(reg));
+        }
+
         if node.node_type() == NodeType::Call {
             let call_node = node.as_any().downcast_ref::<CallNode>().unwrap();
-            let func_label = if self.externs.contains(&call_node.func_to_call().to_string()) {
+            let is_extern = self.externs.contains(&call_node.func_to_call().to_string());
+            if is_extern
+                && matches!(
+                    self.target_object_type.calling_convention(),
+                    CallingConvention::Unix
+                )
+            {
+                return self.extern_call_sysv(call_node, w).map(Some);
+            }
+
+            let func_label = if is_extern {
                 call_node.func_to_call().to_string()
             } else {
                 self.function_label_name(call_node.func_to_call())
@@ -656,13 +1672,10 @@ impl Compiler {
             writeln!(w, "\tpush    r10")?;
             writeln!(w, "\tpush    r11")?;
 
-            for arg in call_node.arg_nodes().iter().rev() {
-                let reg = self.code_gen(arg, w)?.unwrap();
-                writeln!(w, "\tpush    {}", self.scratch_name(reg))?;
-                self.free_scratch(reg);
-            }
+            self.push_args(call_node, w)?;
 
-            for (i, _arg) in call_node.arg_nodes().iter().enumerate() {
+            let arg_count = call_node.passed_arg_types().len();
+            for i in 0..arg_count {
                 if i >= self.number_arg_reg_size(&self.target_object_type.calling_convention()) {
                     break;
                 };
@@ -677,13 +1690,13 @@ impl Compiler {
                 )?;
             }
 
-            writeln!(w, "\tcall    {}", func_label)?;
+            writeln!(w, "\tcall    {}", self.call_target(&func_label))?;
 
-            if call_node.arg_nodes().len() > QW_NUMBER_ARG_REGS_UNIX.len() {
+            if arg_count > QW_NUMBER_ARG_REGS_UNIX.len() {
                 writeln!(
                     w,
                     "\tadd     rsp, {}",
-                    (call_node.arg_nodes().len() - QW_NUMBER_ARG_REGS_UNIX.len()) * 8
+                    (arg_count - QW_NUMBER_ARG_REGS_UNIX.len()) * 8
                 )?;
             }
 
@@ -708,6 +1721,9 @@ impl Compiler {
             let func_label = self.function_label_name(func_def_node.var_name());
 
             self.add_global(func_label.clone());
+            if self.position_independent && !func_def_node.is_public() {
+                self.hidden.push(func_label.clone());
+            }
             writeln!(w, "{}:", func_label)?;
 
             writeln!(w, "\tpush    rbp")?;
@@ -715,43 +1731,56 @@ impl Compiler {
 
             let mut function_body = String::new();
 
-            for (i, (key, arg_type)) in func_def_node.args().iter().enumerate() {
+            let mut arg_index = 0;
+            for (key, arg_type) in func_def_node.args() {
                 self.register_var(key.clone(), arg_type.get_size());
 
-                let num_arg_regs =
-                    self.number_arg_reg_size(&self.target_object_type.calling_convention());
-                if i >= num_arg_regs {
+                // the pointer and length of slices are stored on the stack, the variable points to
+                // them
+                if arg_type.value_type() == ValueTypes::Slice {
+                    let variable_offset = self.base_offset;
+                    self.base_offset += arg_type.size_in_bytes();
+
+                    self.store_arg(
+                        arg_index,
+                        self.base_offset,
+                        &ValueSize::Qword,
+                        &mut function_body,
+                    )?;
+                    self.store_arg(
+                        arg_index + 1,
+                        self.base_offset - SLICE_LENGTH_OFFSET,
+                        &ValueSize::Qword,
+                        &mut function_body,
+                    )?;
+
                     let reg = self.res_scratch();
-
-                    let non_reg_index = i - num_arg_regs + 1;
-
                     writeln!(
                         &mut function_body,
-                        "\tmov     {}, QWORD [rbp + {}]",
+                        "\tlea     {}, [rbp-{}]",
                         self.scratch_name(reg),
-                        (non_reg_index * 8) + 8
+                        self.base_offset
                     )?;
                     writeln!(
                         &mut function_body,
                         "\tmov     {} [rbp - ({})], {}",
-                        arg_type.get_size(),
-                        self.base_offset,
-                        self.scratch_name_lower_sized(reg, &arg_type.get_size())
+                        self.size_spec(&ValueSize::Qword),
+                        variable_offset,
+                        self.scratch_name(reg)
                     )?;
                     self.free_scratch(reg);
-                } else {
-                    writeln!(
-                        &mut function_body,
-                        "\tmov     {} [rbp - ({})], {}",
-                        arg_type.get_size(),
-                        self.base_offset,
-                        self.number_arg_reg_name(
-                            i as u8,
-                            &arg_type.get_size(),
-                            &self.target_object_type.calling_convention()
-                        )
-                    )?;
+
+                    arg_index += 2;
+                    continue;
                 }
+
+                self.store_arg(
+                    arg_index,
+                    self.base_offset,
+                    &arg_type.get_size(),
+                    &mut function_body,
+                )?;
+                arg_index += 1;
             }
 
             self.current_function_epilogue = Some(func_epilogue_label);
@@ -811,20 +1840,16 @@ impl Compiler {
         }
 
         if node.node_type() == NodeType::Break {
-            writeln!(
-                w,
-                "\tjmp     {}",
-                self.label_name(self.current_loop_break.as_ref().unwrap())
-            )?;
+            let break_node = node.as_any().downcast_ref::<BreakNode>().unwrap();
+            let target = self.find_loop(break_node.label()).break_label;
+            writeln!(w, "\tjmp     {}", self.label_name(&target))?;
             return Ok(None);
         }
 
         if node.node_type() == NodeType::Continue {
-            writeln!(
-                w,
-                "\tjmp     {}",
-                self.label_name(self.current_loop_start.as_ref().unwrap())
-            )?;
+            let continue_node = node.as_any().downcast_ref::<ContinueNode>().unwrap();
+            let target = self.find_loop(continue_node.label()).continue_label;
+            writeln!(w, "\tjmp     {}", self.label_name(&target))?;
             return Ok(None);
         }
 
@@ -843,7 +1868,7 @@ impl Compiler {
             writeln!(
                 w,
                 "\tmov     {} [rbp - ({})], {}",
-                var_declaration_node.var_type().get_size(),
+                self.size_spec(&var_declaration_node.var_type().get_size()),
                 self.base_offset,
                 self.scratch_name_lower_sized(
                     result_reg,
@@ -860,15 +1885,29 @@ impl Compiler {
             let reg = self.code_gen(var_assign_node.value_node(), w)?.unwrap();
 
             if self.is_static(var_assign_node.var_name()) {
+                let static_name = self.get_static_name(var_assign_node.var_name());
+                if self.position_independent {
+                    let address_reg = self.res_scratch();
+                    writeln!(
+                        w,
+                        "\tmov     {}, {}",
+                        self.scratch_name(address_reg),
+                        self.static_got_entry(&static_name)
+                    )?;
+                    writeln!(
+                        w,
+                        "\tmov     [{}], {}",
+                        self.scratch_name(address_reg),
+                        self.scratch_name(reg)
+                    )?;
+                    self.free_scratch(address_reg);
+                    return Ok(Some(reg));
+                }
+
                 writeln!(
                     w,
-                    "\tmov     [{}{}], {}",
-                    if self.target_object_type.should_use_rel() {
-                        "rel "
-                    } else {
-                        ""
-                    },
-                    self.get_static_name(var_assign_node.var_name()),
+                    "\tmov     {}, {}",
+                    self.symbol_memory(&static_name),
                     self.scratch_name(reg)
                 )?;
                 return Ok(Some(reg));
@@ -879,7 +1918,7 @@ impl Compiler {
             writeln!(
                 w,
                 "\tmov     {} [rbp - ({})], {}",
-                var_assign_node.value_type().get_size(),
+                self.size_spec(&var_assign_node.value_type().get_size()),
                 var_offset,
                 self.scratch_name_lower_sized(reg, &var_assign_node.value_type().get_size())
             )?;
@@ -906,17 +1945,29 @@ impl Compiler {
             }
 
             if self.is_static(var_access_node.var_name()) {
+                let static_name = self.get_static_name(var_access_node.var_name());
                 let reg = self.res_scratch();
+                if self.position_independent {
+                    writeln!(
+                        w,
+                        "\tmov     {}, {}",
+                        self.scratch_name(reg),
+                        self.static_got_entry(&static_name)
+                    )?;
+                    writeln!(
+                        w,
+                        "\tmov     {}, [{}]",
+                        self.scratch_name(reg),
+                        self.scratch_name(reg)
+                    )?;
+                    return Ok(Some(reg));
+                }
+
                 writeln!(
                     w,
-                    "\tmov     {}, [{}{}]",
+                    "\tmov     {}, {}",
                     self.scratch_name(reg),
-                    if self.target_object_type.should_use_rel() {
-                        "rel "
-                    } else {
-                        ""
-                    },
-                    self.get_static_name(var_access_node.var_name())
+                    self.symbol_memory(&static_name)
                 )?;
                 return Ok(Some(reg));
             }
@@ -939,7 +1990,7 @@ impl Compiler {
                 w,
                 "\tmov     {}, {} [rbp - ({})]",
                 self.scratch_name_lower_sized(reg, &var_size),
-                var_size,
+                self.size_spec(&var_size),
                 var_offset
             )?;
 
@@ -952,11 +2003,11 @@ impl Compiler {
             let label_start = self.label_create();
             let label_end = self.label_create();
 
-            let prev_loop_start = self.current_loop_start;
-            let prev_loop_break = self.current_loop_break;
-
-            self.current_loop_start = Some(label_start);
-            self.current_loop_break = Some(label_end);
+            self.loops.push(Loop {
+                label: while_node.label().clone(),
+                continue_label: label_start,
+                break_label: label_end,
+            });
 
             writeln!(w, "{}:", self.label_name(&label_start))?;
 
@@ -970,8 +2021,7 @@ impl Compiler {
 
             writeln!(w, "{}:", self.label_name(&label_end))?;
 
-            self.current_loop_start = prev_loop_start;
-            self.current_loop_break = prev_loop_break;
+            self.loops.pop();
 
             return Ok(None);
         }
@@ -979,15 +2029,15 @@ impl Compiler {
         if node.node_type() == NodeType::For {
             let for_node = node.as_any().downcast_ref::<ForNode>().unwrap();
 
-            let prev_loop_start = self.current_loop_start;
-            let prev_loop_break = self.current_loop_break;
-
             let label_start = self.label_create();
             let label_next = self.label_create();
             let label_end = self.label_create();
 
-            self.current_loop_start = Some(label_next);
-            self.current_loop_break = Some(label_end);
+            self.loops.push(Loop {
+                label: for_node.label().clone(),
+                continue_label: label_next,
+                break_label: label_end,
+            });
 
             let init_reg = self.code_gen(for_node.init_stmt(), w)?;
             if let Some(init_reg) = init_reg {
@@ -1014,8 +2064,7 @@ impl Compiler {
             writeln!(w, "\tjmp     {}", self.label_name(&label_start))?;
             writeln!(w, "{}:", self.label_name(&label_end))?;
 
-            self.current_loop_start = prev_loop_start;
-            self.current_loop_break = prev_loop_break;
+            self.loops.pop();
 
             return Ok(None);
         }
@@ -1026,6 +2075,13 @@ impl Compiler {
             let label_else = self.label_create();
             let label_end = self.label_create();
 
+            // every branch moves its value into the same register
+            let result_reg = if if_node.has_value() {
+                Some(self.res_scratch())
+            } else {
+                None
+            };
+
             let mut case_labels: Vec<u128> = vec![];
             case_labels.reserve(if_node.cases().len());
 
@@ -1050,18 +2106,22 @@ impl Compiler {
                         self.label_name(&case_labels[i + 1])
                     }
                 )?;
-                self.code_gen(case.statements(), w)?;
+                self.branch(case.statements(), result_reg, w)?;
                 writeln!(w, "\tjmp     {}", self.label_name(&label_end))?;
             }
 
             writeln!(w, "{}:", self.label_name(&label_else))?;
             if if_node.else_case().is_some() {
-                self.code_gen(if_node.else_case().as_ref().unwrap().statements(), w)?;
+                self.branch(
+                    if_node.else_case().as_ref().unwrap().statements(),
+                    result_reg,
+                    w,
+                )?;
             }
 
             writeln!(w, "{}:", self.label_name(&label_end))?;
 
-            return Ok(None);
+            return Ok(result_reg);
         }
 
         if node.node_type() == NodeType::Cast {
@@ -1115,10 +2175,21 @@ impl Compiler {
                 .downcast_ref::<StaticDefinitionNode>()
                 .unwrap();
 
-            self.add_static(
-                static_def_node.name().to_string(),
-                static_def_node.value_type().get_size(),
-            );
+            let size = static_def_node.value_type().get_size();
+            self.add_static(static_def_node.name().to_string(), size);
+
+            // the validator makes sure, that the value is known at compile time
+            let value = constant::evaluate(static_def_node.value()).unwrap();
+            let definition = DataDefinition {
+                label: self.get_static_name(static_def_node.name()),
+                // the dynamic loader has to be able to relocate addresses in shared libraries
+                read_only: !(*static_def_node.is_mutable()
+                    || (self.position_independent && value.bits().is_none())),
+                global: true,
+                size,
+                values: vec![self.constant_value(&value, size)],
+            };
+            self.data.push(definition);
 
             return Ok(None);
         }
@@ -1142,7 +2213,7 @@ impl Compiler {
                 w,
                 "\tmov     {}, {} [{}]",
                 self.scratch_name_lower_sized(res_reg, read_bytes_node.bytes()),
-                read_bytes_node.bytes(),
+                self.size_spec(read_bytes_node.bytes()),
                 self.scratch_name(from_reg)
             )?;
 
@@ -1167,7 +2238,7 @@ impl Compiler {
                 writeln!(
                     w,
                     "\tmov     {} [{}], {}",
-                    pointer_assign_node.pointee_type().get_size(),
+                    self.size_spec(&pointer_assign_node.pointee_type().get_size()),
                     self.scratch_name(left_reg),
                     self.scratch_name_lower_sized(
                         right_reg,
@@ -1194,7 +2265,7 @@ impl Compiler {
                 w,
                 "\tmov     {}, {}",
                 self.scratch_name(right_reg),
-                offset_node.pointee_type().get_size().get_size_in_bytes()
+                offset_node.pointee_type().size_in_bytes()
             )?;
             writeln!(w, "\timul    {}", self.scratch_name(right_reg))?;
 
@@ -1239,6 +2310,10 @@ impl Compiler {
                         .downcast_ref::<FunctionDeclarationNode>()
                         .unwrap();
                     self.add_extern(func_decl_node.var_name().to_string());
+                    if func_decl_node.return_type().value_type() == ValueTypes::F64 {
+                        self.externs_returning_f64
+                            .push(func_decl_node.var_name().to_string());
+                    }
                 }
                 NodeType::StaticDecl => {
                     let static_decl_node = extern_node
@@ -1260,12 +2335,29 @@ impl Compiler {
             let res_reg = self.res_scratch();
 
             if self.is_static(address_of_node.var_name()) {
-                writeln!(
-                    w,
-                    "\tmov     {}, {}",
-                    self.scratch_name(res_reg),
-                    self.get_static_name(address_of_node.var_name())
-                )?;
+                let static_name = self.get_static_name(address_of_node.var_name());
+                if self.position_independent {
+                    writeln!(
+                        w,
+                        "\tmov     {}, {}",
+                        self.scratch_name(res_reg),
+                        self.static_got_entry(&static_name)
+                    )?;
+                } else if self.should_use_rel() {
+                    writeln!(
+                        w,
+                        "\tlea     {}, {}",
+                        self.scratch_name(res_reg),
+                        self.symbol_memory(&static_name)
+                    )?;
+                } else {
+                    writeln!(
+                        w,
+                        "\tmov     {}, {}",
+                        self.scratch_name(res_reg),
+                        self.symbol_offset(&static_name)
+                    )?;
+                }
                 return Ok(Some(res_reg));
             }
 
@@ -1281,6 +2373,23 @@ impl Compiler {
             return Ok(Some(res_reg));
         }
 
+        if node.node_type() == NodeType::Copy {
+            let copy_node = node.as_any().downcast_ref::<CopyNode>().unwrap();
+            let destination_reg = self.code_gen(copy_node.destination(), w)?.unwrap();
+            let source_reg = self.code_gen(copy_node.source(), w)?.unwrap();
+
+            self.copy_memory(
+                MemoryAddress::Register(destination_reg),
+                MemoryAddress::Register(source_reg),
+                MemoryCount::Constant(copy_node.size_in_bytes()),
+                false,
+                w,
+            )?;
+
+            self.free_scratch(source_reg);
+            return Ok(Some(destination_reg));
+        }
+
         if node.node_type() == NodeType::StackAllocationNode {
             let stack_allocation_node =
                 node.as_any().downcast_ref::<StackAllocationNode>().unwrap();
@@ -1288,23 +2397,78 @@ impl Compiler {
             let beginning_offset = self.base_offset;
             self.base_offset += stack_allocation_node.size_in_bytes();
 
-            for i in 0..*stack_allocation_node.size_in_bytes() {
+            self.fill_memory(
+                MemoryAddress::Frame(self.base_offset),
+                None,
+                MemoryCount::Constant(self.base_offset - beginning_offset),
+                w,
+            )?;
+
+            let reg = self.res_scratch();
+            writeln!(
+                w,
+                "\tlea     {}, [rbp-{}]",
+                self.scratch_name(reg),
+                self.base_offset
+            )?;
+            return Ok(Some(reg));
+        }
+
+        if node.node_type() == NodeType::Slice {
+            let slice_node = node.as_any().downcast_ref::<SliceNode>().unwrap();
+            let slice_reg = self.code_gen(slice_node.node(), w)?.unwrap();
+            let start_reg = self.code_gen(slice_node.start(), w)?.unwrap();
+            let end_reg = self.code_gen(slice_node.end(), w)?.unwrap();
+
+            self.base_offset += 16;
+
+            writeln!(
+                w,
+                "\tsub     {}, {}",
+                self.scratch_name(end_reg),
+                self.scratch_name(start_reg)
+            )?;
+            writeln!(
+                w,
+                "\tmov     {} [rbp-{}], {}",
+                self.size_spec(&ValueSize::Qword),
+                self.base_offset - SLICE_LENGTH_OFFSET,
+                self.scratch_name(end_reg)
+            )?;
+
+            if slice_node.element_size() != 1 {
                 writeln!(
                     w,
-                    "\tmov     BYTE [rbp-{}], {}",
-                    beginning_offset + (i + 1),
-                    0
+                    "\timul    {}, {}, {}",
+                    self.scratch_name(start_reg),
+                    self.scratch_name(start_reg),
+                    slice_node.element_size()
                 )?;
             }
+            writeln!(
+                w,
+                "\tadd     {}, {} [{}]",
+                self.scratch_name(start_reg),
+                self.size_spec(&ValueSize::Qword),
+                self.scratch_name(slice_reg)
+            )?;
+            writeln!(
+                w,
+                "\tmov     {} [rbp-{}], {}",
+                self.size_spec(&ValueSize::Qword),
+                self.base_offset,
+                self.scratch_name(start_reg)
+            )?;
 
-            let reg = self.res_scratch();
             writeln!(
                 w,
                 "\tlea     {}, [rbp-{}]",
-                self.scratch_name(reg),
+                self.scratch_name(slice_reg),
                 self.base_offset
             )?;
-            return Ok(Some(reg));
+            self.free_scratch(start_reg);
+            self.free_scratch(end_reg);
+            return Ok(Some(slice_reg));
         }
 
         Ok(None)
@@ -1316,11 +2480,173 @@ impl Compiler {
         no_entry: bool,
         arch: TargetObjectType,
     ) -> Result<String, fmt::Error> {
-        let mut res = String::new();
+        let mut code = String::new();
+        self.code_gen(node, &mut code)?;
+
+        if !no_entry {
+            self.add_global(ENTRY_SYMBOL.to_string());
+
+            writeln!(code, "{}:", ENTRY_SYMBOL)?;
+            writeln!(code, "\tpop     rdi")?;
+            writeln!(code, "\tpop     rsi")?;
+
+            writeln!(code, "\tcall    {}", self.function_label_name("main"))?;
 
+            writeln!(code, "\tmov     rdi, rax")?;
+            writeln!(code, "\tmov     rax, {}", SyscallTable::Exit.code(arch))?;
+            writeln!(code, "\tsyscall")?;
+            writeln!(code, "\tret")?;
+        }
+
+        self.assemble_sections(code)
+    }
+
+    /// Compiles the given node with a test harness as entry point instead of `main`. The harness
+    /// runs every given test in a forked process and reports, whether it passed. The exit code is
+    /// 1, if any test failed.
+    /// Only supported on targets with `fork` and `wait4` syscalls.
+    pub fn compile_tests_to_str(
+        &mut self,
+        node: &Box<dyn Node>,
+        tests: &[(String, Position)],
+        arch: TargetObjectType,
+    ) -> Result<String, fmt::Error> {
         let mut code = String::new();
         self.code_gen(node, &mut code)?;
 
+        self.add_global(ENTRY_SYMBOL.to_string());
+        writeln!(code, "{}:", ENTRY_SYMBOL)?;
+
+        // r12 counts the passed, r13 the failed tests
+        writeln!(code, "\txor     r12, r12")?;
+        writeln!(code, "\txor     r13, r13")?;
+
+        self.write_raw_string(
+            1,
+            &format!(
+                "\nrunning {} test{}\n",
+                tests.len(),
+                if tests.len() == 1 { "" } else { "s" }
+            ),
+            &mut code,
+        )?;
+
+        for (test, _) in tests {
+            let label_parent = self.label_create();
+            let label_failed = self.label_create();
+            let label_next = self.label_create();
+
+            writeln!(code, "\tmov     rax, {}", SyscallTable::Fork.code(arch))?;
+            writeln!(code, "\tsyscall")?;
+            writeln!(code, "\tcmp     rax, 0")?;
+            writeln!(code, "\tjne     {}", self.label_name(&label_parent))?;
+
+            // child: run the test and exit successfully, a failed assertion exits with 1
+            writeln!(code, "\tcall    {}", self.function_label_name(test))?;
+            writeln!(code, "\txor     rdi, rdi")?;
+            writeln!(code, "\tmov     rax, {}", SyscallTable::Exit.code(arch))?;
+            writeln!(code, "\tsyscall")?;
+
+            // parent: wait for the child and check its status
+            writeln!(code, "{}:", self.label_name(&label_parent))?;
+            writeln!(code, "\tjl      {}", self.label_name(&label_failed))?;
+            writeln!(code, "\tsub     rsp, 16")?;
+            writeln!(
+                code,
+                "\tmov     {} [rsp], -1",
+                self.size_spec(&ValueSize::Qword)
+            )?;
+            writeln!(code, "\tmov     rdi, rax")?;
+            writeln!(code, "\tmov     rsi, rsp")?;
+            writeln!(code, "\txor     rdx, rdx")?;
+            writeln!(code, "\txor     r10, r10")?;
+            writeln!(code, "\tmov     rax, {}", SyscallTable::Wait4.code(arch))?;
+            writeln!(code, "\tsyscall")?;
+            writeln!(
+                code,
+                "\tmov     eax, {} [rsp]",
+                self.size_spec(&ValueSize::Dword)
+            )?;
+            writeln!(code, "\tadd     rsp, 16")?;
+            writeln!(code, "\tcmp     eax, 0")?;
+            writeln!(code, "\tjne     {}", self.label_name(&label_failed))?;
+
+            self.write_raw_string(1, &format!("test {} ... ok\n", test), &mut code)?;
+            writeln!(code, "\tinc     r12")?;
+            writeln!(code, "\tjmp     {}", self.label_name(&label_next))?;
+
+            writeln!(code, "{}:", self.label_name(&label_failed))?;
+            self.write_raw_string(1, &format!("test {} ... FAILED\n", test), &mut code)?;
+            writeln!(code, "\tinc     r13")?;
+
+            writeln!(code, "{}:", self.label_name(&label_next))?;
+        }
+
+        let label_print_number = self.label_create();
+        let label_failed = self.label_create();
+        let label_counts = self.label_create();
+
+        self.write_raw_string(1, "\ntest result: ", &mut code)?;
+        writeln!(code, "\tcmp     r13, 0")?;
+        writeln!(code, "\tjne     {}", self.label_name(&label_failed))?;
+        self.write_raw_string(1, "ok. ", &mut code)?;
+        writeln!(code, "\tjmp     {}", self.label_name(&label_counts))?;
+        writeln!(code, "{}:", self.label_name(&label_failed))?;
+        self.write_raw_string(1, "FAILED. ", &mut code)?;
+
+        writeln!(code, "{}:", self.label_name(&label_counts))?;
+        writeln!(code, "\tmov     rdi, r12")?;
+        writeln!(code, "\tcall    {}", self.label_name(&label_print_number))?;
+        self.write_raw_string(1, " passed; ", &mut code)?;
+        writeln!(code, "\tmov     rdi, r13")?;
+        writeln!(code, "\tcall    {}", self.label_name(&label_print_number))?;
+        self.write_raw_string(1, " failed\n\n", &mut code)?;
+
+        writeln!(code, "\txor     rdi, rdi")?;
+        writeln!(code, "\tcmp     r13, 0")?;
+        writeln!(code, "\tsetne   dil")?;
+        writeln!(code, "\tmov     rax, {}", SyscallTable::Exit.code(arch))?;
+        writeln!(code, "\tsyscall")?;
+
+        // prints the unsigned number in rdi to stdout
+        let label_next_digit = self.label_create();
+        writeln!(code, "{}:", self.label_name(&label_print_number))?;
+        writeln!(code, "\tsub     rsp, 32")?;
+        writeln!(code, "\tlea     rsi, [rsp+32]")?;
+        writeln!(code, "\tmov     rax, rdi")?;
+        writeln!(code, "\tmov     rcx, 10")?;
+        writeln!(code, "{}:", self.label_name(&label_next_digit))?;
+        writeln!(code, "\txor     rdx, rdx")?;
+        writeln!(code, "\tdiv     rcx")?;
+        writeln!(code, "\tadd     dl, '0'")?;
+        writeln!(code, "\tdec     rsi")?;
+        writeln!(
+            code,
+            "\tmov     {} [rsi], dl",
+            self.size_spec(&ValueSize::Byte)
+        )?;
+        writeln!(code, "\tcmp     rax, 0")?;
+        writeln!(code, "\tjne     {}", self.label_name(&label_next_digit))?;
+        writeln!(code, "\tlea     rdx, [rsp+32]")?;
+        writeln!(code, "\tsub     rdx, rsi")?;
+        writeln!(code, "\tmov     rdi, 1")?;
+        writeln!(code, "\tmov     rax, {}", SyscallTable::Write.code(arch))?;
+        writeln!(code, "\tsyscall")?;
+        writeln!(code, "\tadd     rsp, 32")?;
+        writeln!(code, "\tret")?;
+
+        self.assemble_sections(code)
+    }
+
+    /// Puts the generated code together with the data and bss sections, as well as the global
+    /// and extern declarations.
+    fn assemble_sections(&self, code: String) -> Result<String, fmt::Error> {
+        if self.syntax == AssemblySyntax::Gas {
+            return self.assemble_gas_sections(code);
+        }
+
+        let mut res = String::new();
+
         // region .data
         writeln!(res, "section .data")?;
 
@@ -1329,44 +2655,155 @@ impl Compiler {
             writeln!(res, "\t{}: db  `{}`, 0", uuid, str)?;
         }
 
+        writeln!(res, "\t;; Statics, exported for C headers, and the data they point to")?;
+        self.write_data_definitions(&mut res, false)?;
+
+        writeln!(res, "section .rodata")?;
+        self.write_data_definitions(&mut res, true)?;
+
         writeln!(res, "section .bss")?;
-        writeln!(res, "\t;; Other statics")?;
-        for (name, size) in &self.statics {
-            writeln!(
-                res,
-                "\t{}  RESB {}",
-                self.get_static_name(name),
-                size.get_size_in_bytes()
-            )?;
+        for (label, bytes) in &self.zeroed {
+            writeln!(res, "\t{}  RESB {}", label, bytes)?;
         }
         // endregion
 
         writeln!(res, "\nsection .text\n")?;
 
-        if !no_entry {
-            self.add_global(ENTRY_SYMBOL.to_string());
+        if self.position_independent {
+            // the symbol type and visibility are needed for the dynamic symbol table
+            for global in &self.globals {
+                if self.hidden.contains(global) {
+                    writeln!(res, "global {}:function hidden", global)?;
+                } else {
+                    writeln!(res, "global {}:function", global)?;
+                }
+            }
+            writeln!(res)?;
+        } else if !self.globals.is_empty() {
+            writeln!(res, "global {}\n", self.globals.join(","))?;
+        }
 
-            writeln!(code, "{}:", ENTRY_SYMBOL)?;
-            writeln!(code, "\tpop     rdi")?;
-            writeln!(code, "\tpop     rsi")?;
+        if !self.externs.is_empty() {
+            writeln!(res, "\textern {}", self.externs.join(","))?;
+        }
 
-            writeln!(code, "\tcall    {}", self.function_label_name("main"))?;
+        if self.position_independent {
+            // otherwise linkers assume, that the stack has to be executable
+            writeln!(res, "section .note.GNU-stack noalloc noexec nowrite progbits")?;
+            writeln!(res, "section .text")?;
+        }
 
-            writeln!(code, "\tmov     rdi, rax")?;
-            writeln!(code, "\tmov     rax, {}", SyscallTable::Exit.code(arch))?;
-            writeln!(code, "\tsyscall")?;
-            writeln!(code, "\tret")?;
+        write!(res, "{}\n", code)?;
+
+        Ok(res)
+    }
+
+    /// Writes the data definitions, that are either read-only or writable.
+    fn write_data_definitions(&self, res: &mut String, read_only: bool) -> fmt::Result {
+        for definition in self.data.iter().filter(|d| d.read_only == read_only) {
+            let bytes = definition.values.len() * definition.size.get_size_in_bytes() as usize;
+            let values = definition.values.join(", ");
+
+            match self.syntax {
+                AssemblySyntax::Nasm => {
+                    if definition.global && self.position_independent {
+                        writeln!(res, "\tglobal {}:data {}", definition.label, bytes)?;
+                    } else if definition.global {
+                        writeln!(res, "\tglobal {}", definition.label)?;
+                    }
+
+                    let directive = match definition.size {
+                        ValueSize::Byte => "db",
+                        ValueSize::Word => "dw",
+                        ValueSize::Dword => "dd",
+                        ValueSize::Qword => "dq",
+                    };
+                    writeln!(res, "\t{}: {}  {}", definition.label, directive, values)?;
+                }
+                AssemblySyntax::Gas => {
+                    if definition.global {
+                        writeln!(res, "\t.globl  {}", definition.label)?;
+                        if self.position_independent {
+                            writeln!(res, "\t.type   {}, @object", definition.label)?;
+                            writeln!(res, "\t.size   {}, {}", definition.label, bytes)?;
+                        }
+                    }
+
+                    let directive = match definition.size {
+                        ValueSize::Byte => ".byte",
+                        ValueSize::Word => ".short",
+                        ValueSize::Dword => ".long",
+                        ValueSize::Qword => ".quad",
+                    };
+                    writeln!(res, "{}:\t{:<7} {}", definition.label, directive, values)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Like `assemble_sections`, but with the directives of the GNU assembler.
+    fn assemble_gas_sections(&self, code: String) -> Result<String, fmt::Error> {
+        let mut res = String::new();
+
+        writeln!(res, "\t.intel_syntax noprefix")?;
+
+        // region .data
+        writeln!(res, "\t.data")?;
+
+        writeln!(res, "\t# Static strings")?;
+        for (str, uuid) in &self.strings {
+            // the strings are escaped for NASM's backtick strings
+            let mut literal = String::new();
+            for byte in unescape(str) {
+                match byte {
+                    b'"' | b'\\' => write!(literal, "\\{}", byte as char)?,
+                    b' '..=b'~' => literal.push(byte as char),
+                    _ => write!(literal, "\\{:03o}", byte)?,
+                }
+            }
+            writeln!(res, "{}:\t.asciz  \"{}\"", uuid, literal)?;
+        }
+
+        writeln!(res, "\t# Statics, exported for C headers, and the data they point to")?;
+        self.write_data_definitions(&mut res, false)?;
+
+        writeln!(res, "\t.section .rodata")?;
+        self.write_data_definitions(&mut res, true)?;
+
+        writeln!(res, "\t.bss")?;
+        for (label, bytes) in &self.zeroed {
+            writeln!(res, "{}:\t.zero   {}", label, bytes)?;
         }
+        // endregion
+
+        writeln!(res, "\n\t.text\n")?;
 
         if !self.globals.is_empty() {
-            writeln!(res, "global {}\n", self.globals.join(","))?;
+            writeln!(res, "\t.globl  {}", self.globals.join(", "))?;
+            if self.position_independent {
+                for global in &self.globals {
+                    writeln!(res, "\t.type   {}, @function", global)?;
+                }
+            }
+            if !self.hidden.is_empty() {
+                writeln!(res, "\t.hidden {}", self.hidden.join(", "))?;
+            }
+            writeln!(res)?;
         }
 
         if !self.externs.is_empty() {
-            writeln!(res, "\textern {}", self.externs.join(","))?;
+            writeln!(res, "\t.extern {}", self.externs.join(", "))?;
         }
 
-        write!(res, "{}\n", code)?;
+        if self.position_independent {
+            // otherwise linkers assume, that the stack has to be executable
+            writeln!(res, "\t.section .note.GNU-stack, \"\", @progbits")?;
+            writeln!(res, "\t.text")?;
+        }
+
+        writeln!(res, "{}", code)?;
 
         Ok(res)
     }
@@ -1375,6 +2812,10 @@ impl Compiler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::semantics::{RuntimeCheck, Validator};
+    use std::path::PathBuf;
 
     #[test]
     pub fn compiler_register_distribution() {
@@ -1401,4 +2842,295 @@ mod tests {
         c.free_scratch(5);
         assert_eq!(*c.scratch_regs(), 0b0000000);
     }
+
+    #[test]
+    pub fn compiler_gas_syntax() {
+        let nasm = Compiler::new(TargetObjectType::Macho64);
+        let gas = Compiler::with_options(TargetObjectType::Macho64, AssemblySyntax::Gas, false);
+
+        assert_eq!(nasm.size_spec(&ValueSize::Dword), "DWORD");
+        assert_eq!(gas.size_spec(&ValueSize::Dword), "DWORD PTR");
+
+        assert_eq!(nasm.sized_immediate(&ValueSize::Byte, 7), "BYTE 7");
+        assert_eq!(gas.sized_immediate(&ValueSize::Byte, 7), "7");
+
+        assert_eq!(nasm.symbol_memory("S0"), "[rel S0]");
+        assert_eq!(gas.symbol_memory("S0"), "[rip + S0]");
+
+        let mut gas = Compiler::with_options(TargetObjectType::X86_64, AssemblySyntax::Gas, false);
+        assert_eq!(gas.symbol_offset("S0"), "OFFSET S0");
+
+        gas.create_raw_string_label("a \"b\"\n");
+        let asm = gas.assemble_sections(String::new()).unwrap();
+        assert!(asm.starts_with("\t.intel_syntax noprefix\n"));
+        assert!(asm.contains("S0:\t.asciz  \"a \\\"b\\\"\\012\"\n"));
+    }
+
+    #[test]
+    pub fn compiler_position_independent() {
+        let mut nasm = Compiler::with_options(TargetObjectType::X86_64, AssemblySyntax::Nasm, true);
+        let gas = Compiler::with_options(TargetObjectType::X86_64, AssemblySyntax::Gas, true);
+
+        assert_eq!(nasm.symbol_memory("ST_x"), "[rel ST_x]");
+        assert_eq!(gas.symbol_memory("ST_x"), "[rip + ST_x]");
+
+        assert_eq!(nasm.call_target("puts"), "puts wrt ..plt");
+        assert_eq!(gas.call_target("puts"), "puts@PLT");
+
+        assert_eq!(
+            nasm.static_got_entry("ST_x"),
+            "[rel ST_x wrt ..gotpcrel]"
+        );
+        assert_eq!(gas.static_got_entry("ST_x"), "[rip + ST_x@GOTPCREL]");
+
+        nasm.add_global("exported".to_string());
+        nasm.add_global("helper".to_string());
+        nasm.hidden.push("helper".to_string());
+
+        let asm = nasm.assemble_sections(String::new()).unwrap();
+        assert!(asm.contains("global exported:function\n"));
+        assert!(asm.contains("global helper:function hidden\n"));
+        assert!(asm.contains("section .note.GNU-stack"));
+    }
+
+    #[test]
+    pub fn compiler_variadic_extern_call() {
+        let source = "extern fun printf(fmt: char *, ...): i32;
+fun main(): u64 {
+    printf(\"%d %f\" as char *, 255 as i8, 2.5);
+    return 0;
+};";
+        let tokens = Lexer::new(PathBuf::from("test.ub"), source.to_string())
+            .make_tokens()
+            .unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+        let validation_res = Validator::new().validate(&ast_root);
+        assert!(validation_res.error().is_none());
+
+        let asm = Compiler::new(TargetObjectType::X86_64)
+            .compile_to_str(
+                validation_res.node().as_ref().unwrap(),
+                false,
+                TargetObjectType::X86_64,
+            )
+            .unwrap();
+
+        // the stack is aligned to 16 bytes, promotions are applied and 'al' holds the number of
+        // floating point arguments
+        assert!(asm.contains("\tand     rsp, -16\n"));
+        assert!(asm.contains("\tmovsx   esi, BYTE [r11 + 8]\n"));
+        assert!(asm.contains("\tmovq    xmm0, rax\n"));
+        assert!(asm.contains("\tmov     eax, 1\n\tcall    printf\n"));
+    }
+
+    #[test]
+    pub fn compiler_static_initializers() {
+        let source = "struct pair { a: u64, b: u8 };
+static mut counter: u64 = 40 + 2;
+static small: u16 = 70000 as u16;
+static name: char * = \"hi\" as char *;
+static values: [u16; 2] = [<u16> 1 as u16, 2 as u16];
+static p: struct pair *mut = struct pair {};";
+        let tokens = Lexer::new(PathBuf::from("test.ub"), source.to_string())
+            .make_tokens()
+            .unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+        let validation_res = Validator::new().validate(&ast_root);
+        assert!(validation_res.error().is_none());
+
+        let compile = |syntax: AssemblySyntax| {
+            Compiler::with_options(TargetObjectType::X86_64, syntax, false)
+                .compile_to_str(
+                    validation_res.node().as_ref().unwrap(),
+                    true,
+                    TargetObjectType::X86_64,
+                )
+                .unwrap()
+        };
+
+        // mutable statics are placed in .data and the other ones in .rodata, the arrays and
+        // structs they point to are writable
+        let asm = compile(AssemblySyntax::Nasm);
+        let (data, rest) = asm.split_once("section .rodata\n").unwrap();
+        let (rodata, bss) = rest.split_once("section .bss\n").unwrap();
+        assert!(data.contains("\tS0: db  `hi`, 0\n"));
+        assert!(data.contains("\tglobal ST_counter\n\tST_counter: dq  42\n"));
+        assert!(data.contains("\tD0: dw  1, 2\n"));
+        assert!(rodata.contains("\tST_small: dw  4464\n"));
+        assert!(rodata.contains("\tST_name: dq  S0\n"));
+        assert!(rodata.contains("\tST_values: dq  D0\n"));
+        assert!(rodata.contains("\tST_p: dq  D1\n"));
+        assert!(bss.starts_with("\tD1  RESB 9\n"));
+
+        let asm = compile(AssemblySyntax::Gas);
+        assert!(asm.contains("\t.globl  ST_counter\nST_counter:\t.quad   42\n"));
+        assert!(asm.contains("\t.section .rodata\n\t.globl  ST_small\nST_small:\t.short  4464\n"));
+        assert!(asm.contains("D1:\t.zero   9\n"));
+    }
+
+    #[test]
+    pub fn compiler_slice_arguments() {
+        let source = "fun first(s: str): char { return *s[0]; };
+fun main(argc: u64, argv: char *): u64 {
+    let text: str = \"hello\" as str;
+    return first(text[1..3]) as u64;
+};";
+        let tokens = Lexer::new(PathBuf::from("test.ub"), source.to_string())
+            .make_tokens()
+            .unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+        let validation_res = Validator::new().validate(&ast_root);
+        assert!(validation_res.error().is_none());
+
+        let asm = Compiler::new(TargetObjectType::X86_64)
+            .compile_to_str(
+                validation_res.node().as_ref().unwrap(),
+                false,
+                TargetObjectType::X86_64,
+            )
+            .unwrap();
+
+        // the callee stores the pointer and length of the slice on its stack
+        assert!(asm.contains(
+            "\tmov     QWORD [rbp - (24)], rdi\n\tmov     QWORD [rbp - (16)], rsi\n\tlea     rbx, [rbp-24]\n"
+        ));
+        assert!(asm.contains(
+            "\tsub     r11, r10\n\tmov     QWORD [rbp-48], r11\n\tadd     r10, QWORD [rbx]\n"
+        ));
+        assert!(asm.contains(
+            "\tpush    QWORD [rbx + 8]\n\tpush    QWORD [rbx]\n\tpop     rdi\n\tpop     rsi\n\tcall    first\n"
+        ));
+    }
+
+    #[test]
+    pub fn compiler_memory_intrinsics() {
+        let source = "struct pair { a: u64, b: u64 };
+fun main(argc: u64, argv: char *): u64 {
+    let buf: [u8; 4096] = [<u8>; 4096];
+    let p: u8 *mut = buf as u8 *mut;
+    let s: struct pair *mut = struct pair {};
+    memset[p, 7 as u8, 10];
+    memmove[p + 1, p, argc];
+    return 0;
+};";
+        let tokens = Lexer::new(PathBuf::from("test.ub"), source.to_string())
+            .make_tokens()
+            .unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+        let validation_res = Validator::new().validate(&ast_root);
+        assert!(validation_res.error().is_none());
+
+        let asm = Compiler::new(TargetObjectType::X86_64)
+            .compile_to_str(
+                validation_res.node().as_ref().unwrap(),
+                false,
+                TargetObjectType::X86_64,
+            )
+            .unwrap();
+
+        // large blocks are zeroed with a string instruction, small ones with unrolled moves
+        assert!(asm.contains(
+            "\tlea     rdi, [rbp-4112]\n\tmov     rcx, 4096\n\txor     eax, eax\n\trep stosb\n"
+        ));
+        assert!(asm.contains("\tpxor    xmm0, xmm0\n\tmovdqu  [rbp-4144], xmm0\n"));
+        assert!(asm.contains(
+            "\timul    rax, rcx\n\tmov     QWORD [rbx], rax\n\tmov     QWORD [rbx + 2], rax\n"
+        ));
+        // overlapping blocks are copied backwards if the destination starts within the source
+        assert!(asm.contains("\tstd\n\trep movsb\n\tcld\n"));
+    }
+
+    #[test]
+    pub fn compiler_runtime_checks() {
+        let source = "fun main(argc: u64, argv: char *): u64 {
+    let values: [u8; 3] = [<u8>; 3];
+    let small: i8 = (argc as i8) * (3 as i8);
+    return ((*values[argc]) as u64) + argc;
+};";
+        let tokens = Lexer::new(PathBuf::from("test.ub"), source.to_string())
+            .make_tokens()
+            .unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+        let validation_res = Validator::new_with_checks(&[
+            RuntimeCheck::Bounds,
+            RuntimeCheck::Null,
+            RuntimeCheck::Overflow,
+        ])
+        .validate(&ast_root);
+        assert!(validation_res.error().is_none());
+
+        let asm = Compiler::new(TargetObjectType::X86_64)
+            .compile_to_str(
+                validation_res.node().as_ref().unwrap(),
+                false,
+                TargetObjectType::X86_64,
+            )
+            .unwrap();
+
+        // smaller integers are extended to 64 bits and the result has to fit into their size
+        assert!(asm.contains(
+            "\tmovsx   r10, r10b\n\tmovsx   r11, r11b\n\timul    r10, r11\n\tmovsx   rbx, r10b\n\tcmp     rbx, r10\n"
+        ));
+        assert!(asm.contains("\tmov     r11, 3\n\tcmp     r10, r11\n\tjb      "));
+        assert!(asm.contains("\tcmp     rbx, 0\n\tjne     "));
+        assert!(asm.contains("\tadd     rbx, r10\n\tjnc     "));
+        assert!(asm.contains("\tmov     rdi, 101\n"));
+        assert!(asm.contains("`test.ub:4:15: Index out of bounds!\\n`"));
+        assert!(asm.contains("`test.ub:3:22: Arithmetic overflow!\\n`"));
+    }
+
+    #[test]
+    pub fn compiler_slice_runtime_checks() {
+        let source = "fun main(argc: u64, argv: char *): u64 {
+    let values: [u64; 4] = [<u64>; 4];
+    let part: []u64 = values[1..argc];
+    return *part[argc];
+};";
+        let tokens = Lexer::new(PathBuf::from("test.ub"), source.to_string())
+            .make_tokens()
+            .unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+        let validation_res =
+            Validator::new_with_checks(&[RuntimeCheck::Bounds]).validate(&ast_root);
+        assert!(validation_res.error().is_none());
+
+        let asm = Compiler::new(TargetObjectType::X86_64)
+            .compile_to_str(
+                validation_res.node().as_ref().unwrap(),
+                false,
+                TargetObjectType::X86_64,
+            )
+            .unwrap();
+
+        // the end of the sub-slice is compared to the length, then the start to the end
+        assert!(asm.contains("\tmov     r12, QWORD [r11]\n\tcmp     r10, r12\n\tjbe     "));
+        assert!(asm.contains("\tcmp     r11, r12\n\tjbe     "));
+        assert!(asm.contains("\tmov     r12, QWORD [r11]\n\tcmp     rbx, r12\n\tjb      "));
+        assert!(asm.contains("`test.ub:3:29: Slice range out of bounds!\\n`"));
+        assert!(asm.contains("`test.ub:4:13: Index out of bounds!\\n`"));
+    }
 }
@@ -0,0 +1,418 @@
+use crate::nodes::array_node::ArrayNode;
+use crate::nodes::binop_node::BinOpNode;
+use crate::nodes::cast_node::CastNode;
+use crate::nodes::char_node::CharNode;
+use crate::nodes::check_node::{Check, CheckNode};
+use crate::nodes::f64_to_u64_node::F64ToU64Node;
+use crate::nodes::floating_binop_node::FloatingBinOpNode;
+use crate::nodes::floating_point_node::FloatingPointNode;
+use crate::nodes::number_node::NumberNode;
+use crate::nodes::stack_allocation_node::StackAllocationNode;
+use crate::nodes::string_node::StringNode;
+use crate::nodes::u64_to_f64_node::U64ToF64Node;
+use crate::nodes::unaryop_node::UnaryOpNode;
+use crate::nodes::{Node, NodeType};
+use crate::token::TokenType;
+use crate::utils::unescape;
+use crate::values::value_size::ValueSize;
+
+/// A value known at compile time, used to initialize statics.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constant {
+    /// Integers, chars and bools
+    Integer(u64),
+    Float(f64),
+    /// The address of a null terminated string
+    String(Vec<u8>),
+    /// The address of an array with the given elements
+    Array {
+        element_size: ValueSize,
+        elements: Vec<Constant>,
+    },
+    /// The address of the given number of zero initialized bytes, e.g. of a struct
+    Zeroed(u64),
+}
+
+impl Constant {
+    /// Returns the bits of numbers, `None` for addresses.
+    pub fn bits(&self) -> Option<u64> {
+        match self {
+            Constant::Integer(n) => Some(*n),
+            Constant::Float(f) => Some(f.to_bits()),
+            _ => None,
+        }
+    }
+}
+
+pub fn truncate(value: u64, size: ValueSize) -> u64 {
+    match size {
+        ValueSize::Byte => value & 0xff,
+        ValueSize::Word => value & 0xffff,
+        ValueSize::Dword => value & 0xffff_ffff,
+        ValueSize::Qword => value,
+    }
+}
+
+/// Applies `+`, `-` or `*` to integers of the given size and signedness, which are stored in the low
+/// bits of the operands. Returns the result zero extended from the size, or `None` if it doesn't fit
+/// into the type.
+pub fn checked_integer_bin_op(
+    op: TokenType,
+    size: ValueSize,
+    signed: bool,
+    left: u64,
+    right: u64,
+) -> Option<u64> {
+    let bits = size.get_size_in_bytes() as u32 * 8;
+    let extend = |value: u64| -> i128 {
+        let shift = 128 - bits;
+        if signed {
+            ((value as i128) << shift) >> shift
+        } else {
+            truncate(value, size) as i128
+        }
+    };
+    let (left, right) = (extend(left), extend(right));
+
+    let result = match op {
+        TokenType::Plus => left + right,
+        TokenType::Minus => left - right,
+        TokenType::Mul => left.checked_mul(right)?,
+        _ => return None,
+    };
+
+    let (min, max) = if signed {
+        (-(1_i128 << (bits - 1)), (1_i128 << (bits - 1)) - 1)
+    } else {
+        (0, (1_i128 << bits) - 1)
+    };
+    if result < min || result > max {
+        return None;
+    }
+
+    Some(truncate(result as u64, size))
+}
+
+/// Evaluates a validated expression at compile time. Like at runtime, division, right shifts and
+/// comparisons are signed. Returns `None`, if the value isn't known before the program runs.
+pub fn evaluate(node: &Box<dyn Node>) -> Option<Constant> {
+    match node.node_type() {
+        NodeType::Number => {
+            let number_node = node.as_any().downcast_ref::<NumberNode>().unwrap();
+            Some(Constant::Integer(truncate(
+                number_node.get_number(),
+                number_node.size().get_size(),
+            )))
+        }
+        NodeType::Char => Some(Constant::Integer(
+            *node.as_any().downcast_ref::<CharNode>().unwrap().value() as u8 as u64,
+        )),
+        NodeType::FloatingPoint => Some(Constant::Float(
+            node.as_any()
+                .downcast_ref::<FloatingPointNode>()
+                .unwrap()
+                .get_float(),
+        )),
+        NodeType::String => Some(Constant::String(unescape(
+            &node
+                .as_any()
+                .downcast_ref::<StringNode>()
+                .unwrap()
+                .get_string(),
+        ))),
+        NodeType::Cast => {
+            let cast_node = node.as_any().downcast_ref::<CastNode>().unwrap();
+            match evaluate(cast_node.node())? {
+                Constant::Integer(n) => Some(Constant::Integer(truncate(
+                    n,
+                    cast_node.cast_type().get_size(),
+                ))),
+                Constant::Float(_) => None,
+                address => Some(address),
+            }
+        }
+        NodeType::U64ToF64 => {
+            let node = node.as_any().downcast_ref::<U64ToF64Node>().unwrap().node();
+            match evaluate(node)? {
+                Constant::Integer(n) => Some(Constant::Float(n as i64 as f64)),
+                _ => None,
+            }
+        }
+        NodeType::F64ToU64 => {
+            let node = node.as_any().downcast_ref::<F64ToU64Node>().unwrap().node();
+            match evaluate(node)? {
+                Constant::Float(f) => Some(Constant::Integer(f as i64 as u64)),
+                _ => None,
+            }
+        }
+        NodeType::BinOp => {
+            let bin_op_node = node.as_any().downcast_ref::<BinOpNode>().unwrap();
+            let left = evaluate(bin_op_node.left_node())?;
+            let right = evaluate(bin_op_node.right_node())?;
+            match (left, right) {
+                (Constant::Integer(left), Constant::Integer(right)) => {
+                    integer_bin_op(bin_op_node.op_token().token_type(), left, right)
+                        .map(Constant::Integer)
+                }
+                _ => None,
+            }
+        }
+        // checks that fail at compile time would fail at runtime, so the value isn't constant
+        NodeType::Check => {
+            let check_node = node.as_any().downcast_ref::<CheckNode>().unwrap();
+            if let Check::Overflow { size, signed } = check_node.check() {
+                let bin_op_node = check_node
+                    .node()
+                    .as_any()
+                    .downcast_ref::<BinOpNode>()
+                    .unwrap();
+                let left = evaluate(bin_op_node.left_node())?.bits()?;
+                let right = evaluate(bin_op_node.right_node())?.bits()?;
+                return checked_integer_bin_op(
+                    bin_op_node.op_token().token_type(),
+                    *size,
+                    *signed,
+                    left,
+                    right,
+                )
+                .map(Constant::Integer);
+            }
+
+            match (check_node.check(), evaluate(check_node.node())?) {
+                (Check::Bounds(length), Constant::Integer(index)) if index < *length => {
+                    Some(Constant::Integer(index))
+                }
+                (Check::Null, Constant::Integer(0)) | (Check::Bounds(_), _) => None,
+                (_, value) => Some(value),
+            }
+        }
+        NodeType::FloatingBinOp => {
+            let bin_op_node = node.as_any().downcast_ref::<FloatingBinOpNode>().unwrap();
+            let left = evaluate(bin_op_node.left_node())?;
+            let right = evaluate(bin_op_node.right_node())?;
+            match (left, right) {
+                (Constant::Float(left), Constant::Float(right)) => {
+                    float_bin_op(bin_op_node.op_token().token_type(), left, right)
+                }
+                _ => None,
+            }
+        }
+        NodeType::UnaryOp => {
+            let unary_op_node = node.as_any().downcast_ref::<UnaryOpNode>().unwrap();
+            match (
+                unary_op_node.op_token().token_type(),
+                evaluate(unary_op_node.node())?,
+            ) {
+                (TokenType::Plus, value @ (Constant::Integer(_) | Constant::Float(_))) => {
+                    Some(value)
+                }
+                (TokenType::Minus, Constant::Float(f)) => Some(Constant::Float(-f)),
+                (TokenType::Minus, Constant::Integer(n)) => {
+                    Some(Constant::Integer(n.wrapping_neg()))
+                }
+                (TokenType::Not, Constant::Integer(n)) => Some(Constant::Integer((n == 0) as u64)),
+                (TokenType::BitNot, Constant::Integer(n)) => Some(Constant::Integer(!n)),
+                _ => None,
+            }
+        }
+        NodeType::Array => {
+            let array_node = node.as_any().downcast_ref::<ArrayNode>().unwrap();
+            let element_size = array_node.element_type().get_size();
+            // arrays with only a length are zero initialized
+            if array_node.element_nodes().is_empty() {
+                return Some(Constant::Zeroed(
+                    (*array_node.size() * element_size.get_size_in_bytes() as usize) as u64,
+                ));
+            }
+
+            let elements = array_node
+                .element_nodes()
+                .iter()
+                .map(|element| match evaluate(element)? {
+                    Constant::Integer(n) => Some(Constant::Integer(truncate(n, element_size))),
+                    element => Some(element),
+                })
+                .collect::<Option<Vec<Constant>>>()?;
+
+            Some(Constant::Array {
+                element_size,
+                elements,
+            })
+        }
+        // struct initializations are lowered to stack allocations by the validator
+        NodeType::StackAllocationNode => Some(Constant::Zeroed(
+            *node
+                .as_any()
+                .downcast_ref::<StackAllocationNode>()
+                .unwrap()
+                .size_in_bytes(),
+        )),
+        _ => None,
+    }
+}
+
+fn integer_bin_op(op: TokenType, left: u64, right: u64) -> Option<u64> {
+    Some(match op {
+        TokenType::Plus => left.wrapping_add(right),
+        TokenType::Minus => left.wrapping_sub(right),
+        TokenType::Mul => left.wrapping_mul(right),
+        TokenType::Div => (left as i64).checked_div(right as i64)? as u64,
+        TokenType::Modulo => (left as i64).checked_rem(right as i64)? as u64,
+        TokenType::BitAnd => left & right,
+        TokenType::BitOr => left | right,
+        TokenType::BitXor => left ^ right,
+        TokenType::BitShl => left.wrapping_shl(right as u32),
+        TokenType::BitShr => (left as i64).wrapping_shr(right as u32) as u64,
+        TokenType::Ee => (left == right) as u64,
+        TokenType::Ne => (left != right) as u64,
+        TokenType::Lt => ((left as i64) < (right as i64)) as u64,
+        TokenType::Gt => ((left as i64) > (right as i64)) as u64,
+        TokenType::Lte => ((left as i64) <= (right as i64)) as u64,
+        TokenType::Gte => ((left as i64) >= (right as i64)) as u64,
+        TokenType::And => (left != 0 && right != 0) as u64,
+        TokenType::Or => (left != 0 || right != 0) as u64,
+        _ => return None,
+    })
+}
+
+fn float_bin_op(op: TokenType, left: f64, right: f64) -> Option<Constant> {
+    Some(match op {
+        TokenType::Plus => Constant::Float(left + right),
+        TokenType::Minus => Constant::Float(left - right),
+        TokenType::Mul => Constant::Float(left * right),
+        TokenType::Div => Constant::Float(left / right),
+        TokenType::Modulo => Constant::Float(left % right),
+        TokenType::Ee => Constant::Integer((left == right) as u64),
+        TokenType::Ne => Constant::Integer((left != right) as u64),
+        TokenType::Lt => Constant::Integer((left < right) as u64),
+        TokenType::Gt => Constant::Integer((left > right) as u64),
+        TokenType::Lte => Constant::Integer((left <= right) as u64),
+        TokenType::Gte => Constant::Integer((left >= right) as u64),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::nodes::statements_node::StatementsNode;
+    use crate::nodes::static_def_node::StaticDefinitionNode;
+    use crate::parser::Parser;
+    use crate::semantics::Validator;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    /// Validates a static with the given type and value and evaluates its value, returns `None`
+    /// if the validation failed.
+    fn evaluate_static(value_type: &str, value: &str) -> Option<Constant> {
+        let source = format!(
+            "struct pair {{ a: u64, b: u8 }};\nstatic x: {} = {};",
+            value_type, value
+        );
+        let tokens = Lexer::new(PathBuf::new(), source).make_tokens().unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+
+        let validation_res = Validator::new().validate(&ast_root);
+        let root = validation_res.node().as_ref()?;
+        let statements = root.as_any().downcast_ref::<StatementsNode>().unwrap();
+        let static_def_node = statements
+            .statement_nodes()
+            .iter()
+            .find_map(|node| node.as_any().downcast_ref::<StaticDefinitionNode>())?;
+        evaluate(static_def_node.value())
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(evaluate_static("u64", "42"), Some(Constant::Integer(42)));
+        assert_eq!(
+            evaluate_static("u8", "300 as u8"),
+            Some(Constant::Integer(44))
+        );
+        assert_eq!(
+            evaluate_static("u64", "(1 << 4) + 10 / 3 - 1"),
+            Some(Constant::Integer(18))
+        );
+        assert_eq!(evaluate_static("char", "'a'"), Some(Constant::Integer(97)));
+        assert_eq!(
+            evaluate_static("bool", "1 == 1 && !(2 < 1)"),
+            Some(Constant::Integer(1))
+        );
+        assert_eq!(
+            evaluate_static("f64", "2.5 * 2.0"),
+            Some(Constant::Float(5.0))
+        );
+        assert_eq!(
+            evaluate_static("f64", "3 as f64"),
+            Some(Constant::Float(3.0))
+        );
+        assert_eq!(
+            evaluate_static("char *", "\"hi\\n\" as char *"),
+            Some(Constant::String(b"hi\n".to_vec()))
+        );
+        assert_eq!(
+            evaluate_static("[u16; 2]", "[<u16> 1 as u16, 70000 as u16]"),
+            Some(Constant::Array {
+                element_size: ValueSize::Word,
+                elements: vec![Constant::Integer(1), Constant::Integer(4464)],
+            })
+        );
+        assert_eq!(
+            evaluate_static("[u16; 3]", "[<u16>; 3]"),
+            Some(Constant::Zeroed(6))
+        );
+        assert_eq!(
+            evaluate_static("struct pair *mut", "struct pair {}"),
+            Some(Constant::Zeroed(9))
+        );
+        assert_eq!(evaluate_static("u64", "1 / 0"), None);
+    }
+
+    #[test]
+    fn test_checked_integer_bin_op() {
+        let check =
+            |op, size, signed, left, right| checked_integer_bin_op(op, size, signed, left, right);
+
+        assert_eq!(
+            check(TokenType::Plus, ValueSize::Byte, false, 200, 55),
+            Some(255)
+        );
+        assert_eq!(
+            check(TokenType::Plus, ValueSize::Byte, false, 200, 56),
+            None
+        );
+        assert_eq!(check(TokenType::Minus, ValueSize::Qword, false, 3, 4), None);
+        assert_eq!(
+            check(TokenType::Mul, ValueSize::Qword, false, 1 << 32, 1 << 32),
+            None
+        );
+        // signed values are read from the low bits and negative results are zero extended
+        assert_eq!(
+            check(TokenType::Plus, ValueSize::Byte, true, 0xff, 0xff),
+            Some(0xfe)
+        );
+        assert_eq!(
+            check(TokenType::Mul, ValueSize::Byte, true, 0x80, 0xff),
+            None
+        );
+        assert_eq!(
+            check(TokenType::Minus, ValueSize::Qword, true, i64::MIN as u64, 1),
+            None
+        );
+        assert_eq!(
+            check(
+                TokenType::Mul,
+                ValueSize::Dword,
+                true,
+                (-65536_i64) as u64,
+                32768
+            ),
+            Some(0x8000_0000)
+        );
+    }
+}
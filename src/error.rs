@@ -56,6 +56,9 @@ impl Error {
     pub fn details(&self) -> &str {
         &self.details
     }
+    pub fn parent(&self) -> &Option<Box<Error>> {
+        &self.parent
+    }
 }
 
 impl Display for Error {
@@ -244,3 +247,92 @@ pub fn not_yet_implemented_parent(
     )
 }
 // endregion
+
+// region RuntimeError
+pub fn runtime_error(pos_start: Position, pos_end: Position, details: &str) -> Error {
+    Error::new(
+        pos_start,
+        pos_end,
+        String::from("RuntimeError"),
+        String::from(details),
+    )
+}
+
+pub fn runtime_error_with_parent(
+    pos_start: Position,
+    pos_end: Position,
+    details: &str,
+    parent: Error,
+) -> Error {
+    Error::from_parent(
+        pos_start,
+        pos_end,
+        String::from("RuntimeError"),
+        String::from(details),
+        parent,
+    )
+}
+// endregion
+
+// region AssemblerError
+pub fn assembler_error(pos_start: Position, pos_end: Position, details: &str) -> Error {
+    Error::new(
+        pos_start,
+        pos_end,
+        String::from("AssemblerError"),
+        String::from(details),
+    )
+}
+
+pub fn assembler_error_with_parent(
+    pos_start: Position,
+    pos_end: Position,
+    details: &str,
+    parent: Error,
+) -> Error {
+    Error::from_parent(
+        pos_start,
+        pos_end,
+        String::from("AssemblerError"),
+        String::from(details),
+        parent,
+    )
+}
+// endregion
+
+// region LinkerError
+pub fn linker_error(pos_start: Position, pos_end: Position, details: &str) -> Error {
+    Error::new(
+        pos_start,
+        pos_end,
+        String::from("LinkerError"),
+        String::from(details),
+    )
+}
+
+pub fn linker_error_with_parent(
+    pos_start: Position,
+    pos_end: Position,
+    details: &str,
+    parent: Error,
+) -> Error {
+    Error::from_parent(
+        pos_start,
+        pos_end,
+        String::from("LinkerError"),
+        String::from(details),
+        parent,
+    )
+}
+// endregion
+
+// region Warning
+pub fn warning(pos_start: Position, pos_end: Position, details: &str) -> Error {
+    Error::new(
+        pos_start,
+        pos_end,
+        String::from("Warning"),
+        String::from(details),
+    )
+}
+// endregion
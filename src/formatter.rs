@@ -0,0 +1,663 @@
+use std::path::PathBuf;
+
+use crate::error;
+use crate::error::Error;
+use crate::lexer::Lexer;
+use crate::position::Position;
+use crate::token::{Token, TokenType};
+
+const INDENT: &str = "    ";
+
+const TYPE_KEYWORDS: [&str; 14] = [
+    "u64", "u32", "u16", "u8", "i64", "i32", "i16", "i8", "f64", "string", "bool", "char", "void",
+    "generic",
+];
+
+// operators, that would lex differently, if they were written without a space in between
+const AMBIGUOUS_PAIRS: [&str; 19] = [
+    "++", "--", "&&", "||", "==", "!=", "<=", ">=", "<<", ">>", "//", "/*", "*/", "->", "@=", "+=",
+    "-=", "*=", "/=",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Brace {
+    Block,
+    Struct,
+    Empty,
+}
+
+struct Item {
+    token: Token,
+    text: String,
+    newlines_before: usize,
+}
+
+struct Formatter<'a> {
+    items: &'a [Item],
+    out: String,
+
+    indent: usize,
+    line_empty: bool,
+    pending_break: bool,
+    blank_line_allowed: bool,
+    line_indent: usize,
+
+    braces: Vec<(Brace, usize)>,
+    // the continuation indent of the lines, on which the open parentheses were opened
+    open_parens: Vec<usize>,
+    in_for_header: bool,
+    in_type: bool,
+    in_array_type: bool,
+    // the number of open brackets of array types like `[u8; 4]`
+    array_type_depth: usize,
+    struct_def_pending: bool,
+    comment_break_pending: bool,
+
+    prev: Option<&'a Item>,
+    prev_is_unary: bool,
+    prev_is_pointer: bool,
+    // whether the previous token closed the `[]` of a slice type
+    prev_is_slice: bool,
+    // whether the previous identifier is the contextual keyword `in` or `defer`
+    prev_is_keyword: bool,
+}
+
+fn is_type_keyword(item: &Item) -> bool {
+    item.token.token_type() == TokenType::Keyword && TYPE_KEYWORDS.contains(&item.text.as_str())
+}
+
+/// Checks, whether the item can only be the start of a new statement after a block.
+fn starts_statement(item: Option<&Item>) -> bool {
+    item.is_some_and(|item| match item.token.token_type() {
+        TokenType::Identifier | TokenType::Label => true,
+        TokenType::Keyword => !matches!(item.text.as_str(), "else" | "as"),
+        _ => false,
+    })
+}
+
+fn is_operator(token_type: TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::Plus
+            | TokenType::Minus
+            | TokenType::Mul
+            | TokenType::Div
+            | TokenType::Modulo
+            | TokenType::Eq
+            | TokenType::Ee
+            | TokenType::Ne
+            | TokenType::Lt
+            | TokenType::Gt
+            | TokenType::Lte
+            | TokenType::Gte
+            | TokenType::And
+            | TokenType::Or
+            | TokenType::BitAnd
+            | TokenType::BitOr
+            | TokenType::BitXor
+            | TokenType::BitShl
+            | TokenType::BitShr
+            | TokenType::BitNot
+            | TokenType::Not
+            | TokenType::Arrow
+            | TokenType::PointerAssign
+    )
+}
+
+impl<'a> Formatter<'a> {
+    fn new(items: &'a [Item]) -> Self {
+        Self {
+            items,
+            out: String::new(),
+            indent: 0,
+            line_empty: true,
+            pending_break: false,
+            blank_line_allowed: false,
+            line_indent: 0,
+            braces: vec![],
+            open_parens: vec![],
+            in_for_header: false,
+            in_type: false,
+            in_array_type: false,
+            array_type_depth: 0,
+            struct_def_pending: false,
+            comment_break_pending: false,
+            prev: None,
+            prev_is_unary: false,
+            prev_is_pointer: false,
+            prev_is_slice: false,
+            prev_is_keyword: false,
+        }
+    }
+
+    fn next_significant(&self, index: usize) -> Option<&'a Item> {
+        self.items[index + 1..]
+            .iter()
+            .find(|item| item.token.token_type() != TokenType::Comment)
+    }
+
+    /// Checks, whether a `-`, `*`, `&`, `!` or `~` after the previous token is an unary operator.
+    fn is_unary_position(&self) -> bool {
+        match self.prev {
+            None => true,
+            Some(prev) => match prev.token.token_type() {
+                TokenType::Keyword => !is_type_keyword(prev),
+                TokenType::Identifier => self.prev_is_keyword,
+                TokenType::Colon
+                | TokenType::Comma
+                | TokenType::Lparen
+                | TokenType::Lsquare
+                | TokenType::Lcurly
+                | TokenType::Newline => true,
+                t => is_operator(t),
+            },
+        }
+    }
+
+    fn is_type_token(&self, item: &Item) -> bool {
+        match item.token.token_type() {
+            TokenType::Keyword => {
+                is_type_keyword(item) || item.text == "struct" || item.text == "mut"
+            }
+            TokenType::Identifier => {
+                item.text == "str" || self.prev.is_some_and(|p| p.text == "struct")
+            }
+            TokenType::Mul | TokenType::Lsquare => true,
+            TokenType::Newline | TokenType::U64 | TokenType::Rsquare => self.array_type_depth > 0,
+            _ => false,
+        }
+    }
+
+    fn newline(&mut self) {
+        while self.out.ends_with(' ') {
+            self.out.pop();
+        }
+        self.out.push('\n');
+        self.line_empty = true;
+    }
+
+    fn start_line(&mut self, extra_indent: usize) {
+        for _ in 0..self.indent + extra_indent {
+            self.out.push_str(INDENT);
+        }
+    }
+
+    fn line_break(&mut self, newlines_before: usize) {
+        if !self.out.is_empty() {
+            self.newline();
+            if self.blank_line_allowed && newlines_before >= 2 {
+                self.newline();
+            }
+        }
+
+        self.start_line(0);
+        self.pending_break = false;
+        self.line_indent = 0;
+    }
+
+    fn space_between(&self, item: &Item) -> bool {
+        let prev = match self.prev {
+            Some(prev) => prev,
+            None => return false,
+        };
+        let (p, t) = (prev.token.token_type(), item.token.token_type());
+
+        if let (Some(a), Some(b)) = (prev.text.chars().last(), item.text.chars().next()) {
+            if AMBIGUOUS_PAIRS.contains(&format!("{}{}", a, b).as_str()) {
+                return true;
+            }
+        }
+
+        if matches!(
+            t,
+            TokenType::Rparen
+                | TokenType::Rsquare
+                | TokenType::Comma
+                | TokenType::Newline
+                | TokenType::Dot
+                | TokenType::DotDot
+                | TokenType::Colon
+                | TokenType::PlusPlus
+                | TokenType::MinusMinus
+        ) {
+            return false;
+        }
+
+        if matches!(
+            p,
+            TokenType::Lparen | TokenType::Lsquare | TokenType::Dot | TokenType::DotDot
+        ) || self.prev_is_unary
+            || self.prev_is_slice
+        {
+            return false;
+        }
+
+        if self.in_array_type && (p == TokenType::Lt || t == TokenType::Gt) {
+            return false;
+        }
+
+        if self.in_type && t == TokenType::Mul {
+            return !self.prev_is_pointer;
+        }
+
+        if self.prev_is_pointer && item.text == "mut" {
+            return false;
+        }
+
+        match t {
+            TokenType::Lparen => p != TokenType::Identifier,
+            TokenType::Lsquare => {
+                !(matches!(
+                    p,
+                    TokenType::Identifier | TokenType::Rparen | TokenType::Rsquare
+                ) || ["sizeof", "syscall", "asm"].contains(&prev.text.as_str()))
+            }
+            TokenType::Rcurly => p != TokenType::Lcurly,
+            _ => true,
+        }
+    }
+
+    fn write_comment(&mut self, item: &'a Item) {
+        let is_line_comment = item.text.starts_with("//");
+
+        if item.newlines_before == 0 && !self.line_empty {
+            // trailing comment
+            self.out.push(' ');
+            self.out.push_str(&item.text);
+
+            if is_line_comment {
+                self.pending_break = true;
+            } else {
+                self.comment_break_pending = false;
+            }
+            return;
+        }
+
+        if self.out.is_empty() {
+            self.start_line(0);
+        } else {
+            let blank_line = self.blank_line_allowed || self.pending_break;
+            self.newline();
+            if blank_line && item.newlines_before >= 2 {
+                self.newline();
+            }
+            self.start_line(self.line_indent);
+        }
+
+        self.out.push_str(&item.text);
+        self.line_empty = false;
+
+        // a comment on its own line is always followed by a line break
+        self.pending_break = is_line_comment || self.pending_break || self.prev.is_none();
+        self.comment_break_pending = !self.pending_break;
+        self.blank_line_allowed = true;
+    }
+
+    fn write_token(&mut self, index: usize, item: &'a Item) {
+        let token_type = item.token.token_type();
+
+        if self.in_type && !self.is_type_token(item) {
+            self.in_type = false;
+        }
+
+        let mut closes_block = false;
+        if token_type == TokenType::Rcurly {
+            if let Some((brace, _)) = self.braces.pop() {
+                if brace != Brace::Empty {
+                    self.indent = self.indent.saturating_sub(1);
+                    closes_block = true;
+                }
+            }
+        }
+
+        if self.pending_break || closes_block {
+            if closes_block {
+                self.blank_line_allowed = false;
+            }
+            self.line_break(item.newlines_before);
+        } else if self.comment_break_pending && item.newlines_before > 0 {
+            self.line_break(item.newlines_before);
+        } else if self.out.is_empty() {
+            self.start_line(0);
+        } else if item.newlines_before > 0
+            && !matches!(
+                token_type,
+                TokenType::Newline | TokenType::Comma | TokenType::Lcurly | TokenType::Dot
+            )
+            && !item.token.matches_keyword("else")
+        {
+            // keep line breaks inside of statements, but indent the continued lines one level
+            // deeper than the line of the innermost open parenthesis (closing parentheses are
+            // aligned with that line)
+            self.line_indent = match self.open_parens.last() {
+                Some(indent) if matches!(token_type, TokenType::Rparen | TokenType::Rsquare) => {
+                    *indent
+                }
+                Some(indent) => indent + 1,
+                None => 1,
+            };
+            self.newline();
+            self.start_line(self.line_indent);
+        } else if self.comment_break_pending || self.space_between(item) {
+            self.out.push(' ');
+        }
+
+        self.comment_break_pending = false;
+        self.blank_line_allowed = false;
+        self.out.push_str(&item.text);
+        self.line_empty = false;
+
+        let is_unary = matches!(
+            token_type,
+            TokenType::Minus
+                | TokenType::Mul
+                | TokenType::BitAnd
+                | TokenType::Not
+                | TokenType::BitNot
+        ) && !self.in_type
+            && !item.token.has_flag(crate::token::TOKEN_FLAGS_IS_ASSIGN)
+            && self.is_unary_position();
+        let is_pointer = self.in_type && token_type == TokenType::Mul;
+        let is_slice = self.in_type
+            && token_type == TokenType::Rsquare
+            && self
+                .prev
+                .is_some_and(|p| p.token.token_type() == TokenType::Lsquare);
+        let is_keyword = token_type == TokenType::Identifier
+            && match item.text.as_str() {
+                "in" => {
+                    self.in_for_header
+                        && self
+                            .prev
+                            .is_some_and(|p| p.token.token_type() == TokenType::Identifier)
+                }
+                "defer" => self.at_statement_start(),
+                _ => false,
+            };
+
+        match token_type {
+            TokenType::Lcurly => {
+                self.in_for_header = false;
+                self.in_type = false;
+
+                let is_empty = self
+                    .next_significant(index)
+                    .is_some_and(|next| next.token.token_type() == TokenType::Rcurly);
+
+                if is_empty {
+                    self.braces.push((Brace::Empty, self.open_parens.len()));
+                } else {
+                    let brace = if self.struct_def_pending {
+                        Brace::Struct
+                    } else {
+                        Brace::Block
+                    };
+                    self.braces.push((brace, self.open_parens.len()));
+                    self.indent += 1;
+                    self.pending_break = true;
+                }
+                self.struct_def_pending = false;
+            }
+            // a block statement, that's directly followed by the next statement, is missing its `;`
+            TokenType::Rcurly if starts_statement(self.next_significant(index)) => {
+                self.out.push(';');
+                self.pending_break = true;
+                self.blank_line_allowed = true;
+            }
+            TokenType::Newline if !self.in_for_header && self.open_parens.is_empty() => {
+                self.pending_break = true;
+                self.blank_line_allowed = true;
+            }
+            TokenType::Comma => {
+                if let Some((Brace::Struct, depth)) = self.braces.last() {
+                    self.pending_break = *depth == self.open_parens.len();
+                }
+            }
+            TokenType::Colon => self.in_type = true,
+            TokenType::Lparen | TokenType::Lsquare => {
+                self.open_parens.push(self.line_indent);
+
+                if token_type == TokenType::Lsquare && self.in_type {
+                    self.array_type_depth += 1;
+                }
+                if self.prev.is_some_and(|p| p.token.matches_keyword("sizeof")) {
+                    self.in_type = true;
+                }
+            }
+            TokenType::Rsquare if self.array_type_depth > 0 => {
+                self.open_parens.pop();
+                self.array_type_depth -= 1;
+            }
+            TokenType::Rparen | TokenType::Rsquare => {
+                self.open_parens.pop();
+                self.in_array_type = false;
+            }
+            TokenType::Lt
+                if self.prev.map(|p| p.token.token_type()) == Some(TokenType::Lsquare) =>
+            {
+                self.in_array_type = true;
+                self.in_type = true;
+            }
+            TokenType::Gt if self.in_array_type => {
+                self.in_array_type = false;
+                self.in_type = false;
+            }
+            TokenType::Keyword => match item.text.as_str() {
+                "for" => self.in_for_header = true,
+                "as" => self.in_type = true,
+                "struct" if self.at_statement_start() => self.struct_def_pending = true,
+                _ => {}
+            },
+            _ => {}
+        }
+
+        self.prev = Some(item);
+        self.prev_is_unary = is_unary;
+        self.prev_is_pointer = is_pointer;
+        self.prev_is_slice = is_slice;
+        self.prev_is_keyword = is_keyword;
+    }
+
+    fn at_statement_start(&self) -> bool {
+        match self.prev {
+            None => true,
+            Some(prev) => matches!(
+                prev.token.token_type(),
+                TokenType::Newline | TokenType::Lcurly | TokenType::Rcurly
+            ),
+        }
+    }
+
+    fn format(mut self) -> String {
+        for (index, item) in self.items.iter().enumerate() {
+            if item.token.token_type() == TokenType::Comment {
+                self.write_comment(item);
+            } else {
+                self.write_token(index, item);
+            }
+        }
+
+        let mut out = self.out.trim_end().to_string();
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn same_token(a: &Token, b: &Token) -> bool {
+    a.token_type() == b.token_type() && a.token_value() == b.token_value() && a.flags() == b.flags()
+}
+
+/// Compares the tokens of the original and the formatted file, ignoring the `;` the formatter
+/// inserted after blocks.
+fn same_tokens(original: &[Token], formatted: &[Token]) -> bool {
+    let mut formatted = formatted.iter();
+    let mut prev: Option<&Token> = None;
+
+    for token in original {
+        let mut next = formatted.next();
+        if let (Some(prev), Some(inserted)) = (prev, next) {
+            if prev.token_type() == TokenType::Rcurly
+                && inserted.token_type() == TokenType::Newline
+                && token.token_type() != TokenType::Newline
+            {
+                next = formatted.next();
+            }
+        }
+
+        if !next.is_some_and(|next| same_token(token, next)) {
+            return false;
+        }
+        prev = Some(token);
+    }
+
+    formatted.next().is_none()
+}
+
+/// Formats the given source in the canonical style: four spaces of indentation per block, one
+/// statement per line, a single space around binary operators and no blank lines at the start or
+/// end of blocks. Comments, as well as single blank lines between statements, are kept. Blocks
+/// directly followed by the next statement get their missing `;`.
+pub fn format_source(file_path: PathBuf, text: &str) -> Result<String, Error> {
+    let lossless_tokens = Lexer::new(file_path.clone(), text.to_string()).make_lossless_tokens()?;
+
+    let mut items: Vec<Item> = vec![];
+    let mut newlines_before = 0;
+    for (token, token_text) in lossless_tokens {
+        if token.token_type() == TokenType::Whitespace {
+            newlines_before += token_text.matches('\n').count();
+            continue;
+        }
+
+        items.push(Item {
+            token,
+            text: token_text,
+            newlines_before,
+        });
+        newlines_before = 0;
+    }
+
+    let formatted = Formatter::new(&items).format();
+
+    // make sure, that formatting didn't change the meaning of the file
+    let original_tokens = Lexer::new(file_path.clone(), text.to_string()).make_tokens()?;
+    let formatted_tokens = Lexer::new(file_path.clone(), formatted.clone()).make_tokens();
+
+    match formatted_tokens {
+        Ok(formatted_tokens) if same_tokens(&original_tokens, &formatted_tokens) => Ok(formatted),
+        _ => Err(error::io_error(
+            Position::new(file_path.clone()),
+            Position::new(file_path),
+            "Formatting would change the tokens of this file. This is a bug in the formatter!",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(text: &str) -> String {
+        format_source(PathBuf::from("test.ub"), text).unwrap()
+    }
+
+    #[test]
+    fn test_lossless_tokens() {
+        let text = "// comment\nfun  main(): u64 {\n\treturn 0 ; /* done */\n};\n";
+        let tokens = Lexer::new(PathBuf::new(), text.to_string())
+            .make_lossless_tokens()
+            .unwrap();
+
+        let joined: String = tokens.iter().map(|(_, t)| t.as_str()).collect();
+        assert_eq!(joined, text);
+    }
+
+    #[test]
+    fn test_format_function() {
+        assert_eq!(
+            format("fun add(a:u64,b : u64):u64{let c:u64=a+b;return c;};"),
+            "fun add(a: u64, b: u64): u64 {\n    let c: u64 = a + b;\n    return c;\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_comments() {
+        assert_eq!(
+            format("// main\nfun main(): u64 { // entry\n\n\n  return 0; /* ok */\n};"),
+            "// main\nfun main(): u64 { // entry\n    return 0; /* ok */\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_format_types_and_operators() {
+        assert_eq!(
+            format("let p:char*mut=x as u8*;let y:u64=-a*(b @8)+*q;let a:u8 *mut=[<u8>;10];let s:u64=sizeof[void*mut];"),
+            "let p: char *mut = x as u8 *;\nlet y: u64 = -a * (b @8) + *q;\nlet a: u8 *mut = [<u8>; 10];\nlet s: u64 = sizeof[void *mut];\n"
+        );
+    }
+
+    #[test]
+    fn test_format_array_types() {
+        assert_eq!(
+            format("let a:[u8;4]=[<u8>;4];let m:[[u8;2];3]*mut=&n;let b:[<[u8;2]>;3];let l:u64=lenof[ a ]+sizeof[[u8;4]];"),
+            "let a: [u8; 4] = [<u8>; 4];\nlet m: [[u8; 2]; 3] *mut = &n;\nlet b: [<[u8; 2]>; 3];\nlet l: u64 = lenof[a] + sizeof[[u8; 4]];\n"
+        );
+    }
+
+    #[test]
+    fn test_format_slices() {
+        assert_eq!(
+            format("let s:str=\"hi\" as str;let v:[] [ ]u8* =a as [][]u8*;let t:str=s[1 .. lenof[ s ]];"),
+            "let s: str = \"hi\" as str;\nlet v: [][]u8 * = a as [][]u8 *;\nlet t: str = s[1..lenof[s]];\n"
+        );
+        assert_eq!(
+            format("let str:str=s;let n:u64=str.len*2;"),
+            "let str: str = s;\nlet n: u64 = str.len * 2;\n"
+        );
+    }
+
+    #[test]
+    fn test_format_memory_intrinsics() {
+        assert_eq!(
+            format("memset [p,0 as u8,16];memcpy [ q,p,n ];memmove[q+1,q,n];"),
+            "memset[p, 0 as u8, 16];\nmemcpy[q, p, n];\nmemmove[q + 1, q, n];\n"
+        );
+    }
+
+    #[test]
+    fn test_format_contextual_keywords() {
+        assert_eq!(
+            format("for x in -2..n {defer *p = x;};let in:u64=defer-1;"),
+            "for x in -2..n {\n    defer *p = x;\n};\nlet in: u64 = defer - 1;\n"
+        );
+    }
+
+    #[test]
+    fn test_format_structs_and_control_flow() {
+        let text = "struct point {x: u64, y: u64};\nfun f(): void {\nif a {b();} else {c();};\nfor let mut i: u64 = 0; i < 10; i += 1 { let p: struct point *mut = struct point {}; };\nreturn;\n};\n";
+
+        assert_eq!(
+            format(text),
+            "struct point {\n    x: u64,\n    y: u64\n};\nfun f(): void {\n    if a {\n        b();\n    } else {\n        c();\n    };\n    for let mut i: u64 = 0; i < 10; i += 1 {\n        let p: struct point *mut = struct point {};\n    };\n    return;\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_format_inserts_missing_semicolons() {
+        let text = "fun f(): void {\nwhile c { c -= 1; } let y: u64 = 1;\nif y == 1 { g(); } // done\nreturn;\n}";
+
+        assert_eq!(
+            format(text),
+            "fun f(): void {\n    while c {\n        c -= 1;\n    };\n    let y: u64 = 1;\n    if y == 1 {\n        g();\n    }; // done\n    return;\n}\n"
+        );
+        assert_eq!(format(&format(text)), format(text));
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let text = "fun main(): u64 {\n    return\n        (a == b)\n        && c;\n};\n";
+        assert_eq!(format(text), text);
+        assert_eq!(format(&format(text)), text);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::values::value_size::ValueSize;
+
+/// Address of the segment holding string literals, statics and the program arguments.
+const DATA_START: u64 = 0x0040_0000;
+/// Address of the stack. Unlike the stack of a real process, it grows towards higher addresses.
+const STACK_START: u64 = 0x7ff0_0000_0000;
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// The simulated memory of an interpreted program. Addresses outside of the allocated parts of the
+/// segments (including null) can't be accessed.
+pub struct Memory {
+    data: Vec<u8>,
+    stack: Vec<u8>,
+}
+
+fn align(size: usize) -> usize {
+    (size + 7) & !7
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Memory {
+            data: vec![],
+            stack: vec![],
+        }
+    }
+
+    /// Copies the given bytes into the data segment and returns their address.
+    pub fn allocate_data(&mut self, bytes: &[u8]) -> u64 {
+        let address = DATA_START + self.data.len() as u64;
+
+        self.data.extend_from_slice(bytes);
+        self.data.resize(align(self.data.len()), 0);
+
+        address
+    }
+
+    /// Allocates zeroed memory on the stack and returns its address, if the stack has enough space
+    /// left.
+    pub fn allocate_stack(&mut self, size: u64) -> Option<u64> {
+        let address = STACK_START + self.stack.len() as u64;
+
+        let new_len = self.stack.len().saturating_add(size as usize);
+        if new_len > STACK_SIZE {
+            return None;
+        }
+        self.stack.resize(align(new_len), 0);
+
+        Some(address)
+    }
+
+    /// The current top of the stack, to free everything allocated afterwards with `free_stack`.
+    pub fn stack_top(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn free_stack(&mut self, top: usize) {
+        self.stack.truncate(top);
+    }
+
+    fn segment(&self, address: u64) -> Option<(&Vec<u8>, u64)> {
+        if address >= STACK_START {
+            Some((&self.stack, address - STACK_START))
+        } else if address >= DATA_START {
+            Some((&self.data, address - DATA_START))
+        } else {
+            None
+        }
+    }
+
+    fn segment_mut(&mut self, address: u64) -> Option<(&mut Vec<u8>, u64)> {
+        if address >= STACK_START {
+            Some((&mut self.stack, address - STACK_START))
+        } else if address >= DATA_START {
+            Some((&mut self.data, address - DATA_START))
+        } else {
+            None
+        }
+    }
+
+    pub fn bytes(&self, address: u64, len: u64) -> Option<&[u8]> {
+        let (segment, start) = self.segment(address)?;
+
+        let end = start.checked_add(len)?;
+        segment.get(start as usize..usize::try_from(end).ok()?)
+    }
+
+    pub fn bytes_mut(&mut self, address: u64, len: u64) -> Option<&mut [u8]> {
+        let (segment, start) = self.segment_mut(address)?;
+
+        let end = start.checked_add(len)?;
+        segment.get_mut(start as usize..usize::try_from(end).ok()?)
+    }
+
+    /// Reads a little endian value of the given size, zero extended to 64 bits.
+    pub fn read(&self, address: u64, size: ValueSize) -> Option<u64> {
+        let bytes = self.bytes(address, size.get_size_in_bytes() as u64)?;
+
+        let mut value = [0_u8; 8];
+        value[..bytes.len()].copy_from_slice(bytes);
+        Some(u64::from_le_bytes(value))
+    }
+
+    /// Writes the lower bytes of the given value, returns false if the memory can't be accessed.
+    pub fn write(&mut self, address: u64, size: ValueSize, value: u64) -> bool {
+        let len = size.get_size_in_bytes() as usize;
+
+        match self.bytes_mut(address, len as u64) {
+            Some(bytes) => {
+                bytes.copy_from_slice(&value.to_le_bytes()[..len]);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reads the zero terminated string at the given address, without the terminating zero.
+    pub fn c_string(&self, address: u64) -> Option<&[u8]> {
+        let (segment, start) = self.segment(address)?;
+
+        let rest = segment.get(start as usize..)?;
+        let len = rest.iter().position(|b| *b == 0)?;
+        Some(&rest[..len])
+    }
+}
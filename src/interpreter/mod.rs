@@ -0,0 +1,1882 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::thread;
+
+use crate::constant;
+use crate::error;
+use crate::error::Error;
+use crate::interpreter::memory::Memory;
+use crate::lexer::Lexer;
+use crate::nodes::address_of_node::AddressOfNode;
+use crate::nodes::array_node::ArrayNode;
+use crate::nodes::assert_node::AssertNode;
+use crate::nodes::binop_node::BinOpNode;
+use crate::nodes::break_node::BreakNode;
+use crate::nodes::call_node::CallNode;
+use crate::nodes::cast_node::CastNode;
+use crate::nodes::char_node::CharNode;
+use crate::nodes::check_node::{Check, CheckNode, CHECK_FAILED_EXIT_CODE};
+use crate::nodes::const_def_node::ConstDefinitionNode;
+use crate::nodes::continue_node::ContinueNode;
+use crate::nodes::copy_node::CopyNode;
+use crate::nodes::f64_to_u64_node::F64ToU64Node;
+use crate::nodes::floating_binop_node::FloatingBinOpNode;
+use crate::nodes::floating_point_node::FloatingPointNode;
+use crate::nodes::for_node::ForNode;
+use crate::nodes::functiondef_node::FunctionDefinitionNode;
+use crate::nodes::if_node::IfNode;
+use crate::nodes::import_node::ImportNode;
+use crate::nodes::memory_node::{MemoryNode, MemoryOperation};
+use crate::nodes::number_node::NumberNode;
+use crate::nodes::offset_node::OffsetNode;
+use crate::nodes::pointer_assign_node::PointerAssignNode;
+use crate::nodes::read_bytes_node::ReadBytesNode;
+use crate::nodes::return_node::ReturnNode;
+use crate::nodes::slice_node::SliceNode;
+use crate::nodes::stack_allocation_node::StackAllocationNode;
+use crate::nodes::statements_node::StatementsNode;
+use crate::nodes::static_def_node::StaticDefinitionNode;
+use crate::nodes::string_node::StringNode;
+use crate::nodes::syscall_node::SyscallNode;
+use crate::nodes::u64_to_f64_node::U64ToF64Node;
+use crate::nodes::unaryop_node::UnaryOpNode;
+use crate::nodes::var_node::declare::VarDeclarationNode;
+use crate::nodes::var_node::typed_access::VarTypedAccessNode;
+use crate::nodes::var_node::typed_assign::VarTypedAssignNode;
+use crate::nodes::while_node::WhileNode;
+use crate::nodes::{Node, NodeType};
+use crate::parser::Parser;
+use crate::position::Position;
+use crate::semantics::{RuntimeCheck, Validator};
+use crate::syscall::{SyscallTable, TargetObjectType};
+use crate::token::TokenType;
+use crate::utils::unescape;
+use crate::values::value_size::ValueSize;
+use crate::values::value_type::slice_type::SLICE_LENGTH_OFFSET;
+use crate::values::value_type::{ValueType, ValueTypes};
+
+mod memory;
+pub mod repl;
+
+/// Calls can't be nested deeper than this, so deeply recursive programs report a stack overflow
+/// instead of overflowing the stack of the interpreter itself.
+const MAX_CALL_DEPTH: usize = 4096;
+/// The size of the stack of the thread running the interpreter, which has to hold
+/// `MAX_CALL_DEPTH` nested evaluations.
+const INTERPRETER_STACK_SIZE: usize = 1 << 30;
+
+const EBADF: u64 = 9;
+const EFAULT: u64 = 14;
+
+/// The result of interpreting a top level statement.
+pub enum Completion {
+    /// The statement was evaluated to the given value.
+    Value(u64),
+    /// The program exited with the given exit code.
+    Exit(i32),
+}
+
+/// Stops the evaluation of the current node and unwinds to the node handling it.
+enum Unwind {
+    Return(u64),
+    /// Carries the label of the loop to leave, if one was given
+    Break(Option<String>),
+    Continue(Option<String>),
+    Exit(i32),
+    Error(Error),
+}
+
+impl From<Error> for Unwind {
+    fn from(err: Error) -> Self {
+        Unwind::Error(err)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Variable {
+    address: u64,
+    size: ValueSize,
+}
+
+struct Frame {
+    scopes: Vec<HashMap<String, Variable>>,
+    /// Memory allocated for variables, structures and arrays, by the node allocating it. Like in
+    /// compiled code, a node reuses its memory every time it is evaluated within the same call.
+    allocations: HashMap<usize, u64>,
+    stack_top: usize,
+}
+
+impl Frame {
+    fn new(stack_top: usize) -> Self {
+        Frame {
+            scopes: vec![HashMap::new()],
+            allocations: HashMap::new(),
+            stack_top,
+        }
+    }
+}
+
+/// Interprets validated ASTs directly, without compiling them. Memory is simulated, only the
+/// `read`, `write` and `exit` syscalls (with their Linux x86-64 numbers) are passed to the host.
+pub struct Interpreter {
+    memory: Memory,
+    frames: Vec<Frame>,
+    functions: HashMap<String, Rc<FunctionDefinitionNode>>,
+    strings: HashMap<String, u64>,
+
+    input: Box<dyn Read>,
+    output: Box<dyn Write>,
+    error_output: Box<dyn Write>,
+}
+
+fn truncate(value: u64, size: ValueSize) -> u64 {
+    match size {
+        ValueSize::Byte => value & 0xff,
+        ValueSize::Word => value & 0xffff,
+        ValueSize::Dword => value & 0xffff_ffff,
+        ValueSize::Qword => value,
+    }
+}
+
+fn sign_extend(value: u64, size: ValueSize) -> i64 {
+    match size {
+        ValueSize::Byte => value as i8 as i64,
+        ValueSize::Word => value as i16 as i64,
+        ValueSize::Dword => value as i32 as i64,
+        ValueSize::Qword => value as i64,
+    }
+}
+
+/// Whether a `break` or `continue` with the label leaves the loop with the other one. Without a
+/// label, they leave the innermost loop.
+fn targets(label: &Option<String>, loop_label: &Option<String>) -> bool {
+    label.is_none() || label == loop_label
+}
+
+fn allocation_key(node: &dyn Node) -> usize {
+    node as *const dyn Node as *const () as usize
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
+
+impl Interpreter {
+    /// Creates an interpreter, that reads from and writes to the standard streams of the host.
+    pub fn new() -> Self {
+        Interpreter::with_io(
+            Box::new(io::stdin()),
+            Box::new(io::stdout()),
+            Box::new(io::stderr()),
+        )
+    }
+
+    pub fn with_io(
+        input: Box<dyn Read>,
+        output: Box<dyn Write>,
+        error_output: Box<dyn Write>,
+    ) -> Self {
+        Interpreter {
+            memory: Memory::new(),
+            frames: vec![Frame::new(0)],
+            functions: HashMap::new(),
+            strings: HashMap::new(),
+            input,
+            output,
+            error_output,
+        }
+    }
+
+    /// Interprets a validated program: evaluates its top level statements and calls `main` with
+    /// the given arguments. Returns the exit code of the program.
+    pub fn run_program(&mut self, node: &Box<dyn Node>, args: &[String]) -> Result<i32, Error> {
+        if let Completion::Exit(code) = self.run_top_level(node)? {
+            return Ok(code);
+        }
+
+        let main = match self.functions.get("main") {
+            Some(main) => main.clone(),
+            None => {
+                return Err(error::runtime_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    "No function 'main' was defined!",
+                ))
+            }
+        };
+
+        // like on Linux, the arguments are stored after each other and `main` gets a pointer to the
+        // first one
+        let mut arg_bytes: Vec<u8> = vec![];
+        for arg in args {
+            arg_bytes.extend_from_slice(arg.as_bytes());
+            arg_bytes.push(0);
+        }
+        let argv = self.memory.allocate_data(&arg_bytes);
+
+        let mut main_args = vec![args.len() as u64, argv];
+        main_args.truncate(main.args().len());
+
+        match self.call_function(&main, main_args, node.as_ref()) {
+            Ok(code) => Ok((code & 0xff) as i32),
+            Err(Unwind::Exit(code)) => Ok(code),
+            Err(Unwind::Error(err)) => Err(err),
+            Err(_) => unreachable!("'return', 'break' and 'continue' don't leave a function"),
+        }
+    }
+
+    /// Interprets a validated top level statement (or all statements of a file) in the global
+    /// scope.
+    pub fn run_top_level(&mut self, node: &Box<dyn Node>) -> Result<Completion, Error> {
+        let res = self.eval_top_level(node);
+
+        // the nodes of the statement might not outlive it, so their memory is not reused
+        self.frames[0].allocations.clear();
+
+        match res {
+            Ok(value) => Ok(Completion::Value(value)),
+            Err(Unwind::Exit(code)) => Ok(Completion::Exit(code)),
+            Err(Unwind::Error(err)) => Err(err),
+            Err(_) => unreachable!("'return', 'break' and 'continue' are only valid in functions"),
+        }
+    }
+
+    /// Formats a value of the given type for displaying it to the user. Returns None for types
+    /// without a meaningful value, like `void`.
+    pub fn display_value(&self, value: u64, value_type: &dyn ValueType) -> Option<String> {
+        let size = value_type.get_size();
+
+        match value_type.value_type() {
+            ValueTypes::U64 | ValueTypes::U32 | ValueTypes::U16 | ValueTypes::U8 => {
+                Some(truncate(value, size).to_string())
+            }
+            ValueTypes::I64 | ValueTypes::I32 | ValueTypes::I16 | ValueTypes::I8 => {
+                Some(sign_extend(value, size).to_string())
+            }
+            ValueTypes::Bool => Some((truncate(value, size) != 0).to_string()),
+            ValueTypes::Char => Some(format!("{:?}", truncate(value, size) as u8 as char)),
+            ValueTypes::F64 => Some(format!("{:?}", f64::from_bits(value))),
+            ValueTypes::String => self
+                .memory
+                .c_string(value)
+                .map(|s| format!("{:?}", String::from_utf8_lossy(s))),
+            ValueTypes::Pointer => Some(format!("{:#x}", value)),
+            _ => None,
+        }
+    }
+
+    // region Helper functions
+
+    fn runtime_error(node: &dyn Node, details: &str) -> Unwind {
+        Unwind::Error(error::runtime_error(
+            node.pos_start().clone(),
+            node.pos_end().clone(),
+            details,
+        ))
+    }
+
+    fn current_frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().unwrap()
+    }
+
+    fn push_scope(&mut self) {
+        self.current_frame().scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.current_frame().scopes.pop();
+    }
+
+    fn declare_var(&mut self, name: &str, variable: Variable) {
+        self.current_frame()
+            .scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), variable);
+    }
+
+    fn declare_global(&mut self, name: &str, variable: Variable) {
+        self.frames[0].scopes[0].insert(name.to_string(), variable);
+    }
+
+    /// Looks up a variable in the scopes of the current function, then in the global scope.
+    fn get_var(&self, name: &str, node: &dyn Node) -> Result<Variable, Unwind> {
+        let frame = self.frames.last().unwrap();
+
+        frame
+            .scopes
+            .iter()
+            .rev()
+            .chain(self.frames[0].scopes.first())
+            .find_map(|scope| scope.get(name))
+            .copied()
+            .ok_or_else(|| {
+                Self::runtime_error(
+                    node,
+                    format!(
+                        "'{}' has no definition! External statics can't be accessed by the interpreter.",
+                        name
+                    )
+                    .as_str(),
+                )
+            })
+    }
+
+    /// Allocates zeroed stack memory for the given node, which is reused when the node is evaluated
+    /// again in the same call.
+    fn allocate(&mut self, node: &dyn Node, size: u64) -> Result<u64, Unwind> {
+        let key = allocation_key(node);
+
+        if let Some(address) = self.current_frame().allocations.get(&key).copied() {
+            self.memory.bytes_mut(address, size).unwrap().fill(0);
+            return Ok(address);
+        }
+
+        let address = self
+            .memory
+            .allocate_stack(size)
+            .ok_or_else(|| Self::runtime_error(node, "Stack overflow!"))?;
+        self.current_frame().allocations.insert(key, address);
+
+        Ok(address)
+    }
+
+    fn read(&self, address: u64, size: ValueSize, node: &dyn Node) -> Result<u64, Unwind> {
+        self.memory.read(address, size).ok_or_else(|| {
+            Self::runtime_error(
+                node,
+                format!(
+                    "Segmentation fault! Invalid read of {} bytes at {:#x}.",
+                    size.get_size_in_bytes(),
+                    address
+                )
+                .as_str(),
+            )
+        })
+    }
+
+    fn write(
+        &mut self,
+        address: u64,
+        size: ValueSize,
+        value: u64,
+        node: &dyn Node,
+    ) -> Result<(), Unwind> {
+        if !self.memory.write(address, size, value) {
+            return Err(Self::runtime_error(
+                node,
+                format!(
+                    "Segmentation fault! Invalid write of {} bytes at {:#x}.",
+                    size.get_size_in_bytes(),
+                    address
+                )
+                .as_str(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Copies `size` bytes, failing like the reads and writes, if either range is invalid.
+    fn copy(
+        &mut self,
+        destination: u64,
+        source: u64,
+        size: u64,
+        node: &dyn Node,
+    ) -> Result<(), Unwind> {
+        let bytes = match self.memory.bytes(source, size) {
+            Some(bytes) => bytes.to_vec(),
+            None => {
+                return Err(Self::runtime_error(
+                    node,
+                    format!(
+                        "Segmentation fault! Invalid read of {} bytes at {:#x}.",
+                        size, source
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+
+        match self.memory.bytes_mut(destination, size) {
+            Some(to) => {
+                to.copy_from_slice(&bytes);
+                Ok(())
+            }
+            None => Err(Self::runtime_error(
+                node,
+                format!(
+                    "Segmentation fault! Invalid write of {} bytes at {:#x}.",
+                    size, destination
+                )
+                .as_str(),
+            )),
+        }
+    }
+
+    fn call_function(
+        &mut self,
+        function: &FunctionDefinitionNode,
+        args: Vec<u64>,
+        node: &dyn Node,
+    ) -> Result<u64, Unwind> {
+        if self.frames.len() > MAX_CALL_DEPTH {
+            return Err(Self::runtime_error(node, "Stack overflow!"));
+        }
+
+        self.frames.push(Frame::new(self.memory.stack_top()));
+
+        let res = self.eval_function_body(function, args);
+
+        let frame = self.frames.pop().unwrap();
+        self.memory.free_stack(frame.stack_top);
+
+        match res {
+            Ok(_) => Ok(0),
+            Err(Unwind::Return(value)) => Ok(value),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn eval_function_body(
+        &mut self,
+        function: &FunctionDefinitionNode,
+        args: Vec<u64>,
+    ) -> Result<u64, Unwind> {
+        let mut args = args.into_iter();
+        for (name, arg_type) in function.args() {
+            // the pointer and length of slices are stored on the stack, which is the slice
+            if arg_type.value_type() == ValueTypes::Slice {
+                let slice = self
+                    .memory
+                    .allocate_stack(arg_type.size_in_bytes())
+                    .ok_or_else(|| Self::runtime_error(function, "Stack overflow!"))?;
+                self.write(slice, ValueSize::Qword, args.next().unwrap(), function)?;
+                self.write(
+                    slice + SLICE_LENGTH_OFFSET,
+                    ValueSize::Qword,
+                    args.next().unwrap(),
+                    function,
+                )?;
+
+                let address = self
+                    .memory
+                    .allocate_stack(ValueSize::Qword.get_size_in_bytes() as u64)
+                    .ok_or_else(|| Self::runtime_error(function, "Stack overflow!"))?;
+                self.write(address, ValueSize::Qword, slice, function)?;
+                self.declare_var(
+                    name,
+                    Variable {
+                        address,
+                        size: ValueSize::Qword,
+                    },
+                );
+                continue;
+            }
+
+            let value = args.next().unwrap();
+            let size = arg_type.get_size();
+            let address = self
+                .memory
+                .allocate_stack(size.get_size_in_bytes() as u64)
+                .ok_or_else(|| Self::runtime_error(function, "Stack overflow!"))?;
+
+            self.write(address, size, value, function)?;
+            self.declare_var(name, Variable { address, size });
+        }
+
+        self.eval(function.body_node())
+    }
+
+    /// Checks, whether the given node evaluates to a floating point number, as unary operations
+    /// on them are not lowered by the validator.
+    fn is_floating_point(&self, node: &Box<dyn Node>) -> bool {
+        match node.node_type() {
+            NodeType::FloatingPoint | NodeType::U64ToF64 => true,
+            NodeType::FloatingBinOp => !matches!(
+                node.as_any()
+                    .downcast_ref::<FloatingBinOpNode>()
+                    .unwrap()
+                    .op_token()
+                    .token_type(),
+                TokenType::Ee
+                    | TokenType::Ne
+                    | TokenType::Gt
+                    | TokenType::Lt
+                    | TokenType::Gte
+                    | TokenType::Lte
+            ),
+            NodeType::UnaryOp => {
+                self.is_floating_point(node.as_any().downcast_ref::<UnaryOpNode>().unwrap().node())
+            }
+            NodeType::VarTypedAccess => {
+                node.as_any()
+                    .downcast_ref::<VarTypedAccessNode>()
+                    .unwrap()
+                    .value_type()
+                    .value_type()
+                    == ValueTypes::F64
+            }
+            NodeType::Call => self
+                .functions
+                .get(
+                    node.as_any()
+                        .downcast_ref::<CallNode>()
+                        .unwrap()
+                        .func_to_call(),
+                )
+                .is_some_and(|f| f.return_type().value_type() == ValueTypes::F64),
+            _ => false,
+        }
+    }
+
+    // endregion
+}
+
+impl Interpreter {
+    /// Evaluates top level statements. Unlike blocks, the statements of a file (or an imported
+    /// file) don't open a new scope.
+    fn eval_top_level(&mut self, node: &Box<dyn Node>) -> Result<u64, Unwind> {
+        match node.node_type() {
+            NodeType::Statements => {
+                for statement in node
+                    .as_any()
+                    .downcast_ref::<StatementsNode>()
+                    .unwrap()
+                    .statement_nodes()
+                {
+                    self.eval_top_level(statement)?;
+                }
+                Ok(0)
+            }
+            NodeType::Import => {
+                self.eval_top_level(node.as_any().downcast_ref::<ImportNode>().unwrap().node())
+            }
+            _ => self.eval(node),
+        }
+    }
+
+    fn eval(&mut self, node: &Box<dyn Node>) -> Result<u64, Unwind> {
+        match node.node_type() {
+            NodeType::Statements => {
+                self.eval_statements_node(node.as_any().downcast_ref::<StatementsNode>().unwrap())
+            }
+            NodeType::Number => {
+                self.eval_number_node(node.as_any().downcast_ref::<NumberNode>().unwrap())
+            }
+            NodeType::FloatingPoint => Ok(node
+                .as_any()
+                .downcast_ref::<FloatingPointNode>()
+                .unwrap()
+                .ieee_754()),
+            NodeType::String => {
+                self.eval_string_node(node.as_any().downcast_ref::<StringNode>().unwrap())
+            }
+            NodeType::Char => {
+                Ok(*node.as_any().downcast_ref::<CharNode>().unwrap().value() as u8 as u64)
+            }
+            NodeType::Array => {
+                self.eval_array_node(node.as_any().downcast_ref::<ArrayNode>().unwrap())
+            }
+            NodeType::BinOp => {
+                self.eval_bin_op_node(node.as_any().downcast_ref::<BinOpNode>().unwrap())
+            }
+            NodeType::FloatingBinOp => self.eval_floating_bin_op_node(
+                node.as_any().downcast_ref::<FloatingBinOpNode>().unwrap(),
+            ),
+            NodeType::UnaryOp => {
+                self.eval_unary_op_node(node.as_any().downcast_ref::<UnaryOpNode>().unwrap())
+            }
+            NodeType::VarDeclaration => self.eval_var_declaration_node(
+                node.as_any().downcast_ref::<VarDeclarationNode>().unwrap(),
+            ),
+            NodeType::VarTypedAssign => self.eval_var_typed_assign_node(
+                node.as_any().downcast_ref::<VarTypedAssignNode>().unwrap(),
+            ),
+            NodeType::VarTypedAccess => {
+                let var_access_node = node.as_any().downcast_ref::<VarTypedAccessNode>().unwrap();
+                let variable = self.get_var(var_access_node.var_name(), var_access_node)?;
+                self.read(variable.address, variable.size, var_access_node)
+            }
+            NodeType::AddressOf => {
+                let address_of_node = node.as_any().downcast_ref::<AddressOfNode>().unwrap();
+                Ok(self
+                    .get_var(address_of_node.var_name(), address_of_node)?
+                    .address)
+            }
+            NodeType::FunctionDef => {
+                let func_def_node = node
+                    .as_any()
+                    .downcast_ref::<FunctionDefinitionNode>()
+                    .unwrap();
+                self.functions.insert(
+                    func_def_node.var_name().to_string(),
+                    Rc::new(func_def_node.clone()),
+                );
+                Ok(0)
+            }
+            NodeType::Call => {
+                self.eval_call_node(node.as_any().downcast_ref::<CallNode>().unwrap())
+            }
+            NodeType::Return => {
+                let return_node = node.as_any().downcast_ref::<ReturnNode>().unwrap();
+                let value = match return_node.node_to_return() {
+                    Some(node_to_return) => self.eval(node_to_return)?,
+                    None => 0,
+                };
+                Err(Unwind::Return(value))
+            }
+            NodeType::Break => {
+                let break_node = node.as_any().downcast_ref::<BreakNode>().unwrap();
+                Err(Unwind::Break(break_node.label().clone()))
+            }
+            NodeType::Continue => {
+                let continue_node = node.as_any().downcast_ref::<ContinueNode>().unwrap();
+                Err(Unwind::Continue(continue_node.label().clone()))
+            }
+            NodeType::Syscall => {
+                self.eval_syscall_node(node.as_any().downcast_ref::<SyscallNode>().unwrap())
+            }
+            NodeType::Assert => {
+                self.eval_assert_node(node.as_any().downcast_ref::<AssertNode>().unwrap())
+            }
+            NodeType::Check => {
+                self.eval_check_node(node.as_any().downcast_ref::<CheckNode>().unwrap())
+            }
+            NodeType::While => {
+                self.eval_while_node(node.as_any().downcast_ref::<WhileNode>().unwrap())
+            }
+            NodeType::For => {
+                let for_node = node.as_any().downcast_ref::<ForNode>().unwrap();
+
+                self.push_scope();
+                let res = self.eval_for_node(for_node);
+                self.pop_scope();
+
+                res
+            }
+            NodeType::If => self.eval_if_node(node.as_any().downcast_ref::<IfNode>().unwrap()),
+            NodeType::Cast => {
+                let cast_node = node.as_any().downcast_ref::<CastNode>().unwrap();
+                let value = self.eval(cast_node.node())?;
+                Ok(truncate(value, cast_node.cast_type().get_size()))
+            }
+            NodeType::F64ToU64 => {
+                let value =
+                    self.eval(node.as_any().downcast_ref::<F64ToU64Node>().unwrap().node())?;
+                Ok(f64::from_bits(value) as i64 as u64)
+            }
+            NodeType::U64ToF64 => {
+                let value =
+                    self.eval(node.as_any().downcast_ref::<U64ToF64Node>().unwrap().node())?;
+                Ok((value as i64 as f64).to_bits())
+            }
+            NodeType::ConstDef => {
+                let const_def_node = node.as_any().downcast_ref::<ConstDefinitionNode>().unwrap();
+                self.eval_global(
+                    const_def_node.name(),
+                    const_def_node.value(),
+                    const_def_node.value_type().get_size(),
+                )
+            }
+            NodeType::StaticDef => {
+                let static_def_node = node
+                    .as_any()
+                    .downcast_ref::<StaticDefinitionNode>()
+                    .unwrap();
+                self.eval_global(
+                    static_def_node.name(),
+                    static_def_node.value(),
+                    static_def_node.value_type().get_size(),
+                )
+            }
+            NodeType::ReadBytes => {
+                let read_bytes_node = node.as_any().downcast_ref::<ReadBytesNode>().unwrap();
+                let address = self.eval(read_bytes_node.node())?;
+                self.read(address, *read_bytes_node.bytes(), read_bytes_node)
+            }
+            NodeType::PointerAssign => self.eval_pointer_assign_node(
+                node.as_any().downcast_ref::<PointerAssignNode>().unwrap(),
+            ),
+            NodeType::Offset => {
+                let offset_node = node.as_any().downcast_ref::<OffsetNode>().unwrap();
+                let address = self.eval(offset_node.node())?;
+                let offset = self.eval(offset_node.offset_node())?;
+                let pointee_size = offset_node.pointee_type().size_in_bytes();
+
+                Ok(address.wrapping_add(offset.wrapping_mul(pointee_size)))
+            }
+            NodeType::Import => self.eval_top_level(node),
+            NodeType::StackAllocationNode => {
+                let stack_allocation_node =
+                    node.as_any().downcast_ref::<StackAllocationNode>().unwrap();
+                self.allocate(
+                    stack_allocation_node,
+                    *stack_allocation_node.size_in_bytes(),
+                )
+            }
+            NodeType::Copy => {
+                let copy_node = node.as_any().downcast_ref::<CopyNode>().unwrap();
+                let destination = self.eval(copy_node.destination())?;
+                let source = self.eval(copy_node.source())?;
+
+                self.copy(destination, source, copy_node.size_in_bytes(), copy_node)?;
+                Ok(destination)
+            }
+            NodeType::Slice => {
+                self.eval_slice_node(node.as_any().downcast_ref::<SliceNode>().unwrap())
+            }
+            NodeType::Memory => {
+                self.eval_memory_node(node.as_any().downcast_ref::<MemoryNode>().unwrap())
+            }
+            NodeType::FunctionDecl
+            | NodeType::StaticDecl
+            | NodeType::StructDef
+            | NodeType::Extern
+            | NodeType::MacroDef
+            | NodeType::Ignored => Ok(0),
+            NodeType::Assembly => Err(Self::runtime_error(
+                node.as_ref(),
+                "Inline assembly can't be interpreted!",
+            )),
+            _ => Err(Self::runtime_error(
+                node.as_ref(),
+                format!("Node type {:?} can't be interpreted!", node.node_type()).as_str(),
+            )),
+        }
+    }
+
+    fn eval_statements_node(&mut self, node: &StatementsNode) -> Result<u64, Unwind> {
+        self.push_scope();
+
+        let res = node
+            .statement_nodes()
+            .iter()
+            .try_fold(0, |_, statement| self.eval(statement));
+
+        self.pop_scope();
+
+        // the value of the last statement is only used if it's the value of the block
+        res.map(|value| if node.has_value() { value } else { 0 })
+    }
+
+    fn eval_number_node(&mut self, node: &NumberNode) -> Result<u64, Unwind> {
+        Ok(truncate(node.get_number(), node.size().get_size()))
+    }
+
+    fn eval_string_node(&mut self, node: &StringNode) -> Result<u64, Unwind> {
+        let string = node.get_string();
+
+        if let Some(address) = self.strings.get(&string) {
+            return Ok(*address);
+        }
+
+        let mut bytes = unescape(&string);
+        bytes.push(0);
+
+        let address = self.memory.allocate_data(&bytes);
+        self.strings.insert(string, address);
+
+        Ok(address)
+    }
+
+    fn eval_array_node(&mut self, node: &ArrayNode) -> Result<u64, Unwind> {
+        let element_size = node.element_type().get_size();
+        let address = self.allocate(
+            node,
+            *node.size() as u64 * element_size.get_size_in_bytes() as u64,
+        )?;
+
+        // like the compiler, evaluate the elements from last to first
+        for (i, element) in node.element_nodes().iter().enumerate().rev() {
+            let value = self.eval(element)?;
+            self.write(
+                address + (i as u64 * element_size.get_size_in_bytes() as u64),
+                element_size,
+                value,
+                node,
+            )?;
+        }
+
+        Ok(address)
+    }
+
+    fn eval_slice_node(&mut self, node: &SliceNode) -> Result<u64, Unwind> {
+        let slice = self.eval(node.node())?;
+        let start = self.eval(node.start())?;
+        let end = self.eval(node.end())?;
+
+        let pointer = self.read(slice, ValueSize::Qword, node)?;
+        let address = self.allocate(node, 16)?;
+        self.write(
+            address,
+            ValueSize::Qword,
+            pointer.wrapping_add(start.wrapping_mul(node.element_size())),
+            node,
+        )?;
+        self.write(
+            address + SLICE_LENGTH_OFFSET,
+            ValueSize::Qword,
+            end.wrapping_sub(start),
+            node,
+        )?;
+
+        Ok(address)
+    }
+
+    fn eval_memory_node(&mut self, node: &MemoryNode) -> Result<u64, Unwind> {
+        let destination = self.eval(node.destination())?;
+        let source = self.eval(node.source())?;
+        let count = self.eval(node.count())?;
+
+        match node.operation() {
+            MemoryOperation::Set => match self.memory.bytes_mut(destination, count) {
+                Some(bytes) => bytes.fill(source as u8),
+                None => {
+                    return Err(Self::runtime_error(
+                        node,
+                        format!(
+                            "Segmentation fault! Invalid write of {} bytes at {:#x}.",
+                            count, destination
+                        )
+                        .as_str(),
+                    ))
+                }
+            },
+            // the source is read completely before the destination is written, so they may overlap
+            MemoryOperation::Copy | MemoryOperation::Move => {
+                self.copy(destination, source, count, node)?
+            }
+        }
+
+        Ok(0)
+    }
+
+    fn eval_bin_op_node(&mut self, node: &BinOpNode) -> Result<u64, Unwind> {
+        let op = node.op_token().token_type();
+
+        if op == TokenType::And || op == TokenType::Or {
+            let left = self.eval(node.left_node())? != 0;
+            if left == (op == TokenType::Or) {
+                return Ok(left as u64);
+            }
+
+            return Ok((self.eval(node.right_node())? != 0) as u64);
+        }
+
+        let left = self.eval(node.left_node())?;
+        let right = self.eval(node.right_node())?;
+
+        let value = match op {
+            TokenType::Plus => left.wrapping_add(right),
+            TokenType::Minus => left.wrapping_sub(right),
+            TokenType::Mul => left.wrapping_mul(right),
+            TokenType::Div | TokenType::Modulo => {
+                let res = if op == TokenType::Div {
+                    (left as i64).checked_div(right as i64)
+                } else {
+                    (left as i64).checked_rem(right as i64)
+                };
+
+                match res {
+                    Some(value) => value as u64,
+                    None => {
+                        return Err(Self::runtime_error(
+                            node,
+                            "Floating point exception! Division by zero or overflow.",
+                        ))
+                    }
+                }
+            }
+            TokenType::BitAnd => left & right,
+            TokenType::BitOr => left | right,
+            TokenType::BitXor => left ^ right,
+            TokenType::BitShl => left.wrapping_shl(right as u32),
+            TokenType::BitShr => (left as i64).wrapping_shr(right as u32) as u64,
+            TokenType::Ee => (left == right) as u64,
+            TokenType::Ne => (left != right) as u64,
+            TokenType::Lt => ((left as i64) < (right as i64)) as u64,
+            TokenType::Gt => ((left as i64) > (right as i64)) as u64,
+            TokenType::Lte => ((left as i64) <= (right as i64)) as u64,
+            TokenType::Gte => ((left as i64) >= (right as i64)) as u64,
+            _ => {
+                return Err(Self::runtime_error(
+                    node,
+                    format!("Binary operation '{:?}' can't be interpreted!", op).as_str(),
+                ))
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn eval_floating_bin_op_node(&mut self, node: &FloatingBinOpNode) -> Result<u64, Unwind> {
+        let left_bits = self.eval(node.left_node())?;
+        let right_bits = self.eval(node.right_node())?;
+        let left = f64::from_bits(left_bits);
+        let right = f64::from_bits(right_bits);
+
+        let value = match node.op_token().token_type() {
+            TokenType::Plus => (left + right).to_bits(),
+            TokenType::Minus => (left - right).to_bits(),
+            TokenType::Mul => (left * right).to_bits(),
+            TokenType::Div => (left / right).to_bits(),
+            TokenType::Modulo => (left % right).to_bits(),
+            TokenType::BitAnd => left_bits & right_bits,
+            TokenType::BitOr => left_bits | right_bits,
+            TokenType::BitXor => left_bits ^ right_bits,
+            TokenType::BitShl => left_bits.wrapping_shl(right as u32),
+            TokenType::BitShr => left_bits.wrapping_shr(right as u32),
+            TokenType::Ee => (left == right) as u64,
+            TokenType::Ne => (left != right) as u64,
+            TokenType::Lt => (left < right) as u64,
+            TokenType::Gt => (left > right) as u64,
+            TokenType::Lte => (left <= right) as u64,
+            TokenType::Gte => (left >= right) as u64,
+            op => {
+                return Err(Self::runtime_error(
+                    node,
+                    format!("Binary operation '{:?}' can't be interpreted!", op).as_str(),
+                ))
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn eval_unary_op_node(&mut self, node: &UnaryOpNode) -> Result<u64, Unwind> {
+        let value = self.eval(node.node())?;
+
+        match node.op_token().token_type() {
+            TokenType::Plus => Ok(value),
+            TokenType::Minus if self.is_floating_point(node.node()) => {
+                Ok((-f64::from_bits(value)).to_bits())
+            }
+            TokenType::Minus => Ok(value.wrapping_neg()),
+            TokenType::Not => Ok((value == 0) as u64),
+            TokenType::BitNot => Ok(!value),
+            op => Err(Self::runtime_error(
+                node,
+                format!("Unary operation '{:?}' can't be interpreted!", op).as_str(),
+            )),
+        }
+    }
+
+    fn eval_var_declaration_node(&mut self, node: &VarDeclarationNode) -> Result<u64, Unwind> {
+        let value = self.eval(node.value_node())?;
+
+        let size = node.var_type().get_size();
+        let address = self.allocate(node, size.get_size_in_bytes() as u64)?;
+        self.write(address, size, value, node)?;
+
+        self.declare_var(node.var_name(), Variable { address, size });
+
+        Ok(value)
+    }
+
+    fn eval_var_typed_assign_node(&mut self, node: &VarTypedAssignNode) -> Result<u64, Unwind> {
+        let value = self.eval(node.value_node())?;
+
+        let variable = self.get_var(node.var_name(), node)?;
+        self.write(variable.address, variable.size, value, node)?;
+
+        Ok(value)
+    }
+
+    /// Defines a static or constant, which are stored in the data segment.
+    fn eval_global(
+        &mut self,
+        name: &str,
+        value_node: &Box<dyn Node>,
+        size: ValueSize,
+    ) -> Result<u64, Unwind> {
+        let value = self.eval(value_node)?;
+
+        let address = self
+            .memory
+            .allocate_data(&value.to_le_bytes()[..size.get_size_in_bytes() as usize]);
+        self.declare_global(name, Variable { address, size });
+
+        Ok(value)
+    }
+
+    fn eval_call_node(&mut self, node: &CallNode) -> Result<u64, Unwind> {
+        let function = match self.functions.get(node.func_to_call()) {
+            Some(function) => function.clone(),
+            None => {
+                return Err(Self::runtime_error(
+                    node,
+                    format!(
+                        "Function '{}' has no definition! External functions can't be called by the interpreter.",
+                        node.func_to_call()
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+
+        // like the compiler, evaluate the arguments from last to first and pass slices as their
+        // pointer and length
+        let mut args: Vec<u64> = vec![];
+        for (i, arg) in node.arg_nodes().iter().enumerate().rev() {
+            let value = self.eval(arg)?;
+            if node.is_slice_arg(i) {
+                args.push(self.read(value + SLICE_LENGTH_OFFSET, ValueSize::Qword, node)?);
+                args.push(self.read(value, ValueSize::Qword, node)?);
+            } else {
+                args.push(value);
+            }
+        }
+        args.reverse();
+
+        self.call_function(&function, args, node)
+    }
+
+    fn eval_syscall_node(&mut self, node: &SyscallNode) -> Result<u64, Unwind> {
+        let mut args = [0_u64; 4];
+        for (i, arg) in node.args().iter().enumerate() {
+            args[i] = self.eval(arg)?;
+        }
+        let [code, arg1, arg2, arg3] = args;
+
+        let code_of = |syscall: SyscallTable| syscall.code(TargetObjectType::X86_64) as u64;
+
+        if code == code_of(SyscallTable::Exit) {
+            return Err(Unwind::Exit((arg1 & 0xff) as i32));
+        }
+
+        if code == code_of(SyscallTable::Write) {
+            let bytes = match self.memory.bytes(arg2, arg3) {
+                Some(bytes) => bytes,
+                None => return Ok(EFAULT.wrapping_neg()),
+            };
+
+            let res = match arg1 {
+                1 => self
+                    .output
+                    .write_all(bytes)
+                    .and_then(|_| self.output.flush()),
+                2 => self
+                    .error_output
+                    .write_all(bytes)
+                    .and_then(|_| self.error_output.flush()),
+                _ => return Ok(EBADF.wrapping_neg()),
+            };
+
+            return match res {
+                Ok(_) => Ok(arg3),
+                Err(err) => Ok((err.raw_os_error().unwrap_or(5) as u64).wrapping_neg()),
+            };
+        }
+
+        if code == code_of(SyscallTable::Read) {
+            if arg1 != 0 {
+                return Ok(EBADF.wrapping_neg());
+            }
+
+            let buffer = match self.memory.bytes_mut(arg2, arg3) {
+                Some(buffer) => buffer,
+                None => return Ok(EFAULT.wrapping_neg()),
+            };
+
+            return match self.input.read(buffer) {
+                Ok(read) => Ok(read as u64),
+                Err(err) => Ok((err.raw_os_error().unwrap_or(5) as u64).wrapping_neg()),
+            };
+        }
+
+        Err(Self::runtime_error(
+            node,
+            format!("Syscall {} is not supported by the interpreter!", code).as_str(),
+        ))
+    }
+
+    fn eval_assert_node(&mut self, node: &AssertNode) -> Result<u64, Unwind> {
+        if self.eval(node.condition_node())? != 0 {
+            return Ok(0);
+        }
+
+        let pos = node.pos_start();
+        // there is nothing sensible left to do, if the error can't be reported
+        let _ = writeln!(
+            self.error_output,
+            "{}:{}:{}: Assertion failed!",
+            pos.file_name().display(),
+            pos.line() + 1,
+            pos.col() + 1
+        );
+
+        Err(Unwind::Exit(1))
+    }
+
+    fn eval_check_node(&mut self, node: &CheckNode) -> Result<u64, Unwind> {
+        let value = match node.check() {
+            Check::Overflow { size, signed } => {
+                let bin_op_node = node.node().as_any().downcast_ref::<BinOpNode>().unwrap();
+                let left = self.eval(bin_op_node.left_node())?;
+                let right = self.eval(bin_op_node.right_node())?;
+                constant::checked_integer_bin_op(
+                    bin_op_node.op_token().token_type(),
+                    *size,
+                    *signed,
+                    left,
+                    right,
+                )
+            }
+            Check::Bounds(length) => Some(self.eval(node.node())?).filter(|index| index < length),
+            Check::SliceBounds => {
+                let index = self.eval(node.node())?;
+                let length = self.eval(node.length())?;
+                Some(index).filter(|index| *index < length)
+            }
+            Check::SliceRange => {
+                let bound = self.eval(node.node())?;
+                let length = self.eval(node.length())?;
+                Some(bound).filter(|bound| *bound <= length)
+            }
+            Check::Null => Some(self.eval(node.node())?).filter(|address| *address != 0),
+        };
+
+        match value {
+            Some(value) => Ok(value),
+            None => {
+                // there is nothing sensible left to do, if the error can't be reported
+                let _ = write!(self.error_output, "{}", node.failure_message());
+                Err(Unwind::Exit(CHECK_FAILED_EXIT_CODE as i32))
+            }
+        }
+    }
+
+    fn eval_while_node(&mut self, node: &WhileNode) -> Result<u64, Unwind> {
+        while self.eval(node.condition_node())? != 0 {
+            match self.eval(node.body_node()) {
+                Ok(_) => {}
+                Err(Unwind::Continue(label)) if targets(&label, node.label()) => {}
+                Err(Unwind::Break(label)) if targets(&label, node.label()) => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(0)
+    }
+
+    fn eval_for_node(&mut self, node: &ForNode) -> Result<u64, Unwind> {
+        self.eval(node.init_stmt())?;
+
+        while self.eval(node.condition())? != 0 {
+            match self.eval(node.body()) {
+                Ok(_) => {}
+                Err(Unwind::Continue(label)) if targets(&label, node.label()) => {}
+                Err(Unwind::Break(label)) if targets(&label, node.label()) => break,
+                Err(err) => return Err(err),
+            }
+
+            self.eval(node.next_expr())?;
+        }
+
+        Ok(0)
+    }
+
+    fn eval_if_node(&mut self, node: &IfNode) -> Result<u64, Unwind> {
+        for case in node.cases() {
+            if self.eval(case.condition())? != 0 {
+                return self.eval(case.statements());
+            }
+        }
+
+        if let Some(else_case) = node.else_case() {
+            return self.eval(else_case.statements());
+        }
+
+        Ok(0)
+    }
+
+    fn eval_pointer_assign_node(&mut self, node: &PointerAssignNode) -> Result<u64, Unwind> {
+        let address = self.eval(node.ptr())?;
+        let value = self.eval(node.value())?;
+
+        self.write(address, node.pointee_type().get_size(), value, node)?;
+
+        Ok(value)
+    }
+}
+
+/// Runs the given function on a thread with a stack large enough for the interpreter.
+fn with_interpreter_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .stack_size(INTERPRETER_STACK_SIZE)
+            .spawn_scoped(scope, f)
+            .expect("Failed to spawn the interpreter thread!")
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// Lexes, parses, validates and interprets the given file. Returns the exit code of the program.
+pub fn run_file(
+    file: &Path,
+    include_paths: &Vec<String>,
+    checks: &[RuntimeCheck],
+    args: &[String],
+) -> Result<i32, Error> {
+    with_interpreter_stack(|| interpret_file(file, include_paths, checks, args))
+}
+
+fn interpret_file(
+    file: &Path,
+    include_paths: &Vec<String>,
+    checks: &[RuntimeCheck],
+    args: &[String],
+) -> Result<i32, Error> {
+    let text = std::fs::read_to_string(file).map_err(|file_err| {
+        error::io_error(
+            Position::new(file.to_path_buf()),
+            Position::new(file.to_path_buf()),
+            format!("Could not read file: {}", file_err).as_str(),
+        )
+    })?;
+
+    let mut lexer = Lexer::new(file.to_path_buf(), text);
+    let tokens = lexer.make_tokens()?;
+
+    let mut macros = HashMap::new();
+    let mut already_included: Vec<PathBuf> = vec![];
+
+    let mut parser = Parser::new(tokens, include_paths, &mut macros, &mut already_included);
+    let ast_root = parser.parse()?;
+
+    let mut validator = Validator::new_with_checks(checks);
+    let validation_res = validator.validate(&ast_root);
+    if let Some(err) = validation_res.error() {
+        return Err(err.clone());
+    }
+
+    Interpreter::new().run_program(validation_res.node().as_ref().unwrap(), args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// A writer, whose output can still be read after handing it to the interpreter.
+    #[derive(Clone, Default)]
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Runs the given program and returns its exit code, standard output and standard error.
+    fn run(source: &str, input: &str, args: &[&str]) -> (i32, String, String) {
+        run_with_checks(source, input, args, &[])
+    }
+
+    fn run_with_checks(
+        source: &str,
+        input: &str,
+        args: &[&str],
+        checks: &[RuntimeCheck],
+    ) -> (i32, String, String) {
+        let path = PathBuf::from("/tmp/interpreter_test.ub");
+        let tokens = Lexer::new(path, source.to_string()).make_tokens().unwrap();
+
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+
+        let validation_res = Validator::new_with_checks(checks).validate(&ast_root);
+        assert!(
+            validation_res.error().is_none(),
+            "{:?}",
+            validation_res.error()
+        );
+
+        let output = SharedOutput::default();
+        let error_output = SharedOutput::default();
+        let mut interpreter = Interpreter::with_io(
+            Box::new(io::Cursor::new(input.as_bytes().to_vec())),
+            Box::new(output.clone()),
+            Box::new(error_output.clone()),
+        );
+
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let code = interpreter
+            .run_program(validation_res.node().as_ref().unwrap(), &args)
+            .unwrap();
+
+        let output = String::from_utf8(output.0.borrow().clone()).unwrap();
+        let error_output = String::from_utf8(error_output.0.borrow().clone()).unwrap();
+        (code, output, error_output)
+    }
+
+    #[test]
+    fn test_exit_code_and_recursion() {
+        let (code, _, _) = run(
+            "fun fib(n: u64): u64 {
+    if n < 2 {
+        return n;
+    };
+    return fib(n - 1) + fib(n - 2);
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    return fib(10);
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 55);
+    }
+
+    #[test]
+    fn test_structs_arrays_and_pointers() {
+        let (code, _, _) = run(
+            "struct point { x: u64, y: u64 };
+
+fun swap(p: struct point *mut): void {
+    let tmp: u64 = *p.x;
+    p.x @= *p.y;
+    p.y @= tmp;
+    return;
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let mut p: struct point *mut = struct point {};
+    p.x @= 3;
+    p.y @= 4;
+    swap(p);
+
+    let arr: [u64; 3] = [<u64> 10, 20, 30];
+    let mut sum: u64 = 0;
+    for let mut i: u64 = 0; i < 3; i += 1 {
+        sum += *arr[i];
+    };
+
+    return *p.x * 100 + sum;
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 460 % 256);
+    }
+
+    #[test]
+    fn test_array_values() {
+        let (code, _, _) = run(
+            "struct packet { len: u64, data: [u8; 3] };
+
+fun clear(values: [u8; 3]): u64 {
+    (values as u8 *mut) @= 0 as u8;
+    return lenof[values];
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let a: [u8; 3] = [<u8> 1 as u8, 2 as u8, 3 as u8];
+    let mut b: [u8; 3] = a;
+    (a as u8 *mut) @= 7 as u8;
+    let copied: u64 = (b[0] @1) as u64;
+    b = a;
+
+    let p: struct packet *mut = struct packet {};
+    p.data @= b;
+    let n: u64 = clear(*p.data);
+
+    return copied * 100 + (((*p.data)[0] @1) as u64) * 10 + n + sizeof[struct packet];
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 184);
+    }
+
+    #[test]
+    fn test_slice_values() {
+        let (code, _, _) = run(
+            "fun count(s: str, c: char): u64 {
+    let mut n: u64 = 0;
+    for let mut i: u64 = 0; i < s.len; i += 1 {
+        if *s[i] == c { n += 1; };
+    };
+    return n;
+};
+
+fun sum(values: []u16): u64 {
+    let mut total: u64 = 0;
+    for let mut i: u64 = 0; i < lenof[values]; i += 1 {
+        total += (*values[i]) as u64;
+    };
+    return total;
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let text: str = \"hello world\" as str;
+    let values: [u16; 4] = [<u16> 1 as u16, 20 as u16, 30 as u16, 40 as u16];
+    let all: []u16 = values as []u16;
+    let mut part: []u16 = all[1..3];
+    let copy: []u16 = part;
+    part = all[3..4];
+    let p: char * = text.ptr;
+    let str: str = text[0..5];
+    return count(text[6..11], 'o') * 100 + sum(copy) + sum(part) + count(p[0..5], 'l')
+        - count(str, 'l') * 10;
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 172);
+    }
+
+    #[test]
+    fn test_memory_intrinsics() {
+        let (code, _, _) = run(
+            "struct big { a: [u8; 40], b: u64 };
+
+fun main(argc: u64, argv: char *): u64 {
+    let buf: [u8; 4096] = [<u8>; 4096];
+    let p: u8 *mut = buf as u8 *mut;
+    memset[p, 7 as u8, 100];
+    memset[p + 1000, 3 as u8, argc + 2999];
+    let q: u8 *mut = p + 200;
+    memcpy[q, p + 98, 5];
+    memmove[p + 2, p, 10];
+    memmove[p, p + 1, argc + 20];
+    let s: struct big *mut = struct big {};
+    memset[s as u8 *mut, 1 as u8, 48];
+    memcpy[(s as u8 *mut) + 8, p + 990, 24];
+    let mut total: u64 = 0;
+    for let mut i: u64 = 0; i < 4096; i += 1 {
+        total += (*p[i]) as u64;
+    };
+    for let mut i: u64 = 0; i < 48; i += 1 {
+        total += (*((s as u8 *mut) + i)) as u64;
+    };
+    return total % 251;
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 239);
+    }
+
+    #[test]
+    fn test_value_expressions() {
+        let (code, _, _) = run(
+            "fun max(a: u64, b: u64): u64 {
+    return if a > b { a } else { b };
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let x: u64 = if argc > 5 { 1 } else if argc == 0 { 20 } else { 300 };
+    let y: u64 = {
+        let t: u64 = max(3, 9);
+        t * 2
+    };
+    let mut n: u64 = 0;
+    while n < 3 { n += 1 };
+    let z: u64 = 1 + if x == 20 { { 4 } } else { 5 } * 2;
+    return x + y + n + z;
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 50);
+    }
+
+    #[test]
+    fn test_for_in() {
+        let (code, _, _) = run(
+            "fun sum(values: []u64): u64 {
+    let mut total: u64 = 0;
+    for value in values {
+        total += value;
+    };
+    return total;
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let arr: [u64; 4] = [<u64> 1, 2, 3, 4];
+    let mut n: u64 = 0;
+    for i in 0..5 {
+        n += i;
+    };
+    for i in (0..10).step(3) {
+        n = n * 2 + i;
+    };
+    for i in (2..6).rev() {
+        if i == 3 {
+            continue;
+        };
+        n = n * 3 + i;
+    };
+    for i in (argc..10).rev().step(4) {
+        n += i;
+    };
+    for i in 5..2 {
+        n += 1000;
+    };
+    for x in arr {
+        for y in arr {
+            if y > x {
+                break;
+            };
+            n += y;
+        };
+    };
+    let p: u64 * = &arr[0];
+    return (n + sum(p[1..3])) % 251;
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 39);
+    }
+
+    #[test]
+    fn test_labels_and_defer() {
+        let (code, _, _) = run(
+            "fun record(t: u64 *mut, k: u64): u64 {
+    *t = *t * 10 + k;
+    return 0;
+};
+
+fun early(t: u64 *mut, n: u64): u64 {
+    defer record(t, 1);
+    defer record(t, 2);
+    if n > 0 {
+        return n;
+    };
+    record(t, 3);
+    return 0;
+};
+
+fun main(argc: u64): u64 {
+    let mut hits: u64 = 0;
+    'outer: for i in 0..5 {
+        'inner: while 1 == 1 {
+            let mut j: u64 = 0;
+            for k in 0..10 {
+                if k == 3 {
+                    continue 'outer;
+                };
+                if i == 4 {
+                    break 'outer;
+                };
+                if k == i {
+                    break 'inner;
+                };
+                hits = hits + 1;
+            };
+            j = j + 1;
+        };
+        hits = hits + 100;
+    };
+    let mut t: u64 = 0;
+    let r: u64 = early(&t, argc);
+    let mut u: u64 = 0;
+    for i in 0..3 {
+        defer record(&u, i);
+        if i == 1 {
+            continue;
+        };
+        record(&u, 7);
+    };
+    {
+        defer record(&u, 5);
+        record(&u, 6);
+    };
+    let v: u64 = {
+        defer record(&u, 8);
+        u % 7
+    };
+    return hits * 1000 % 97 + t % 13 + u % 11 + r + v;
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 80);
+    }
+
+    #[test]
+    fn test_declarations_without_value() {
+        let (code, _, _) = run(
+            "fun pick(c: u64): u64 {
+    let x: u64;
+    if c > 2 {
+        x = 10;
+    } else if c == 1 {
+        x = 20;
+    } else {
+        return 7;
+    };
+    return x;
+};
+
+fun forever(n: u64): u64 {
+    let mut i: u64 = 0;
+    while 1 == 1 {
+        i = i + 1;
+        if i == n {
+            return i;
+        };
+    };
+};
+
+fun nothing(): void {
+    let f: f64;
+    f = 1.5;
+    let arr: [u64; 4];
+    arr = [<u64> 1, 2, 3, 4];
+    let mut s: []u64;
+    s = (arr as []u64)[1..3];
+};
+
+fun main(argc: u64): u64 {
+    let y: u64;
+    let mut z: u64;
+    z = argc;
+    y = z + 1;
+    nothing();
+    let w: u64 = if argc == 1 { pick(3) } else { pick(0) };
+    let a: [u64; 3];
+    a = [<u64> 5, 6, 7];
+    return pick(1) + pick(0) + y + w + forever(4) + *a[2];
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 46);
+    }
+
+    #[test]
+    fn test_lvalues() {
+        let (code, _, _) = run(
+            "struct point { x: u64, y: u64 };
+
+fun bump(p: u64 *mut): void {
+    *p += 3;
+    return;
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let mut arr: [u64; 4] = [<u64> 1, 2, 3, 4];
+    arr[1] = 10;
+    arr[2] += 5;
+    *arr[3] *= *arr[1];
+    bump(&arr[0]);
+    let p: struct point *mut = struct point {};
+    p.x = 7;
+    *p.y = 2;
+    p.y <<= 2;
+    let q: u64 *mut = &p.y;
+    *q = *q + 1;
+    return *arr[0] + *arr[1] + *arr[2] + *arr[3] + *p.x + *p.y;
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 78);
+    }
+
+    #[test]
+    fn test_syscalls() {
+        let (code, output, _) = run(
+            "fun main(argc: u64, argv: char *): u64 {
+    let buf: [char; 4] = [<char>; 4];
+    let read: u64 = syscall[0, 0, buf as u64, 4];
+    syscall[1, 1, argv as u64, 4];
+    syscall[1, 1, buf as u64, read];
+    let bang: char * = \"\\041\\n\" as char *;
+    syscall[1, 1, bang as u64, 2];
+    syscall[60, argc + 40, 0, 0];
+    return 0;
+};",
+            "in",
+            &["prog", "arg"],
+        );
+
+        assert_eq!(code, 42);
+        assert_eq!(output, "progin!\n");
+    }
+
+    #[test]
+    fn test_assert_failure() {
+        let (code, _, error_output) = run(
+            "fun main(argc: u64, argv: char *): u64 {
+    assert(argc == 2);
+    return 0;
+};",
+            "",
+            &["prog"],
+        );
+
+        assert_eq!(code, 1);
+        assert_eq!(
+            error_output,
+            "/tmp/interpreter_test.ub:2:5: Assertion failed!\n"
+        );
+    }
+
+    #[test]
+    fn test_contextual_keywords() {
+        let (code, _, _) = run(
+            "pub fun test(assert: u64): u64 {
+    let pub: u64 = 2;
+    return assert * pub;
+};
+
+test fun doubles(): void {
+    assert(test(2) == 4);
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    let mut assert: u64 = test(3);
+    assert = assert + 1;
+    assert(assert == 7);
+    let memset: u64 = assert + 2;
+    let mut values: [u8; 2] = [<u8>; 2];
+    memset[values as u8 *mut, memset as u8, 2];
+    let mut defer: u64 = 0;
+    for in in 0..3 {
+        defer += in;
+    };
+    {
+        defer defer += 1;
+    };
+    let lenof: u64 = lenof[values] + defer;
+    return (*values[1]) as u64 + lenof;
+};",
+            "",
+            &[],
+        );
+
+        assert_eq!(code, 15);
+    }
+
+    #[test]
+    fn test_runtime_checks() {
+        let source = "fun main(argc: u64, argv: char *): u64 {
+    let values: [u8; 3] = [<u8> 1 as u8, 2 as u8, 250 as u8];
+    let mut p: u8 * = values as u8 *;
+    if argc == 2 {
+        p = 0 as u8 *;
+    };
+    let small: u8 = *values[argc - 1] + *p;
+    return (small + (5 as u8)) as u64;
+};";
+        let checks = [
+            RuntimeCheck::Bounds,
+            RuntimeCheck::Null,
+            RuntimeCheck::Overflow,
+        ];
+        let run_checked = |args: &[&str], checks: &[RuntimeCheck]| {
+            let (code, _, error_output) = run_with_checks(source, "", args, checks);
+            (code, error_output)
+        };
+
+        assert_eq!(run_checked(&["prog"], &checks), (7, String::new()));
+        assert_eq!(
+            run_checked(&["prog", "a"], &checks),
+            (
+                101,
+                String::from("/tmp/interpreter_test.ub:7:42: Null pointer dereference!\n")
+            )
+        );
+        assert_eq!(
+            run_checked(&["prog", "a", "b", "c"], &checks),
+            (
+                101,
+                String::from("/tmp/interpreter_test.ub:7:22: Index out of bounds!\n")
+            )
+        );
+        assert_eq!(
+            run_checked(&["prog", "a", "b"], &checks),
+            (
+                101,
+                String::from("/tmp/interpreter_test.ub:8:13: Arithmetic overflow!\n")
+            )
+        );
+        // without the check, 250 + 1 + 5 silently wraps around
+        assert_eq!(
+            run_checked(&["prog", "a", "b"], &[RuntimeCheck::Bounds]),
+            (0, String::new())
+        );
+    }
+
+    #[test]
+    fn test_slice_runtime_checks() {
+        let source = "fun main(argc: u64, argv: char *): u64 {
+    let values: [u64; 4] = [<u64> 1, 2, 3, 4];
+    let mut start: u64 = 1;
+    let mut end: u64 = 3;
+    let mut index: u64 = 1;
+    if argc == 2 {
+        end = 5;
+    };
+    if argc == 3 {
+        start = 3;
+        end = 2;
+    };
+    if argc == 4 {
+        index = 2;
+    };
+    let part: []u64 = values[start..end];
+    let element: u64 = *part[index];
+    return element + part[0..argc / 5 + 2].len;
+};";
+        let run_checked = |args: &[&str]| {
+            let (code, _, error_output) =
+                run_with_checks(source, "", args, &[RuntimeCheck::Bounds]);
+            (code, error_output)
+        };
+        let range_error = |line: u64, col: u64| {
+            format!(
+                "/tmp/interpreter_test.ub:{}:{}: Slice range out of bounds!\n",
+                line, col
+            )
+        };
+
+        assert_eq!(run_checked(&["prog"]), (5, String::new()));
+        // the end of a sub-slice of an array is past its length
+        assert_eq!(run_checked(&["prog", "a"]), (101, range_error(16, 29)));
+        // the start of a sub-slice is after its end
+        assert_eq!(run_checked(&["prog", "a", "b"]), (101, range_error(16, 29)));
+        assert_eq!(
+            run_checked(&["prog", "a", "b", "c"]),
+            (
+                101,
+                String::from("/tmp/interpreter_test.ub:17:25: Index out of bounds!\n")
+            )
+        );
+        // the end of a sub-slice of a slice is past its length
+        assert_eq!(
+            run_checked(&["prog", "a", "b", "c", "d"]),
+            (101, range_error(18, 26))
+        );
+    }
+}
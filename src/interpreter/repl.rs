@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::interpreter::{with_interpreter_stack, Completion, Interpreter};
+use crate::lexer::Lexer;
+use crate::nodes::{Node, NodeType};
+use crate::parser::Parser;
+use crate::semantics::Validator;
+use crate::token::TokenType;
+
+/// The file name used in positions of the REPL input.
+const REPL_FILE_NAME: &str = "<repl>";
+
+/// The result of evaluating an input of the REPL.
+pub enum Outcome {
+    /// The values of the evaluated expressions, formatted for displaying them.
+    Values(Vec<String>),
+    /// The program exited with the given exit code.
+    Exit(i32),
+}
+
+/// An interactive session, in which every input is validated and interpreted in the context of all
+/// previous inputs.
+pub struct Repl {
+    validator: Validator,
+    interpreter: Interpreter,
+
+    include_paths: Vec<String>,
+    macros: HashMap<String, Box<dyn Node>>,
+    already_included: Vec<PathBuf>,
+}
+
+impl Repl {
+    pub fn new(interpreter: Interpreter, include_paths: Vec<String>) -> Self {
+        Repl {
+            validator: Validator::new(),
+            interpreter,
+            include_paths,
+            macros: HashMap::new(),
+            already_included: vec![],
+        }
+    }
+
+    /// Evaluates the statements of the given input one after another. Evaluation stops at the first
+    /// statement, that fails to validate or interpret.
+    pub fn eval(&mut self, input: &str) -> Result<Outcome, Error> {
+        let mut lexer = Lexer::new(PathBuf::from(REPL_FILE_NAME), input.to_string());
+        let tokens = lexer.make_tokens()?;
+
+        let mut parser = Parser::new(
+            tokens,
+            &self.include_paths,
+            &mut self.macros,
+            &mut self.already_included,
+        );
+        let statements = parser.parse_statements()?;
+
+        let mut values: Vec<String> = vec![];
+
+        for statement in statements {
+            let validation_res = self.validator.validate_global_statement(&statement);
+            if let Some(err) = validation_res.error() {
+                return Err(err.clone());
+            }
+
+            let node = validation_res.node().as_ref().unwrap();
+            let value = match self.interpreter.run_top_level(node)? {
+                Completion::Value(value) => value,
+                Completion::Exit(code) => return Ok(Outcome::Exit(code)),
+            };
+
+            // declarations and assignments are not echoed
+            if matches!(
+                node.node_type(),
+                NodeType::VarDeclaration
+                    | NodeType::VarTypedAssign
+                    | NodeType::PointerAssign
+                    | NodeType::StaticDef
+            ) {
+                continue;
+            }
+
+            let value_type = validation_res.value_type().as_ref().unwrap();
+            if let Some(displayed) = self.interpreter.display_value(value, value_type.as_ref()) {
+                values.push(format!("{}: {}", displayed, value_type));
+            }
+        }
+
+        Ok(Outcome::Values(values))
+    }
+}
+
+/// Checks, whether the given input ends within an unclosed block, so the REPL should read more
+/// lines before evaluating it.
+pub fn is_incomplete(input: &str) -> bool {
+    let mut lexer = Lexer::new(PathBuf::from(REPL_FILE_NAME), input.to_string());
+
+    match lexer.make_tokens() {
+        Ok(tokens) => {
+            let depth = tokens
+                .iter()
+                .fold(0_i64, |depth, token| match token.token_type() {
+                    TokenType::Lcurly | TokenType::Lparen | TokenType::Lsquare => depth + 1,
+                    TokenType::Rcurly | TokenType::Rparen | TokenType::Rsquare => depth - 1,
+                    _ => depth,
+                });
+            depth > 0
+        }
+        // let the evaluation report the error
+        Err(_) => false,
+    }
+}
+
+/// Runs the REPL on stdin/stdout until the input ends or the program exits.
+/// Returns the exit code of the REPL.
+pub fn run(include_paths: Vec<String>) -> io::Result<i32> {
+    with_interpreter_stack(|| run_repl(include_paths))
+}
+
+fn run_repl(include_paths: Vec<String>) -> io::Result<i32> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    let mut repl = Repl::new(Interpreter::new(), include_paths);
+    let mut input = String::new();
+
+    loop {
+        write!(
+            stdout,
+            "{}",
+            if input.is_empty() {
+                "umber> "
+            } else {
+                "...... "
+            }
+        )?;
+        stdout.flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            writeln!(stdout)?;
+            return Ok(0);
+        }
+
+        input.push_str(&line);
+        if input.trim().is_empty() {
+            input.clear();
+            continue;
+        }
+        if is_incomplete(&input) {
+            continue;
+        }
+
+        match repl.eval(&input) {
+            Ok(Outcome::Values(values)) => {
+                for value in values {
+                    writeln!(stdout, "{}", value)?;
+                }
+            }
+            Ok(Outcome::Exit(code)) => return Ok(code),
+            Err(err) => eprintln!("{}", err),
+        }
+
+        input.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(repl: &mut Repl, input: &str) -> Vec<String> {
+        match repl.eval(input) {
+            Ok(Outcome::Values(values)) => values,
+            Ok(Outcome::Exit(code)) => panic!("exited with {}", code),
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_repl_keeps_declarations() {
+        let mut repl = Repl::new(Interpreter::new(), vec![]);
+
+        assert!(eval(&mut repl, "let mut x: u64 = 40;").is_empty());
+        assert!(eval(&mut repl, "fun add(a: u64, b: u64): u64 { return a + b; };").is_empty());
+        assert!(eval(&mut repl, "x += 1").is_empty());
+        assert_eq!(eval(&mut repl, "add(x, 1)"), vec!["42: u64"]);
+        assert_eq!(
+            eval(&mut repl, "x == 41; 'a'; 1.5"),
+            vec!["true: bool", "'a': char", "1.5: f64"]
+        );
+    }
+
+    #[test]
+    fn test_repl_recovers_from_errors() {
+        let mut repl = Repl::new(Interpreter::new(), vec![]);
+
+        assert!(repl.eval("fun f(): u64 { return undefined; };").is_err());
+        assert!(eval(&mut repl, "fun f(): u64 { return 1; };").is_empty());
+        assert_eq!(eval(&mut repl, "f()"), vec!["1: u64"]);
+    }
+
+    #[test]
+    fn test_is_incomplete() {
+        assert!(is_incomplete("fun f(): u64 {\n"));
+        assert!(is_incomplete("f(1,\n"));
+        assert!(!is_incomplete("fun f(): u64 { return 1; };\n"));
+    }
+}
@@ -28,11 +28,7 @@ impl Lexer {
     fn advance(&mut self) {
         self.pos.advance(self.current_char.as_ref().unwrap());
 
-        if *self.pos.index() < self.file_text.len() {
-            self.current_char = Some(self.file_text.chars().nth(*self.pos.index()).unwrap());
-        } else {
-            self.current_char = None;
-        }
+        self.current_char = self.file_text.chars().nth(*self.pos.index());
     }
 
     pub fn make_tokens(&mut self) -> Result<Vec<Token>, Error> {
@@ -43,184 +39,229 @@ impl Lexer {
         )];
 
         while self.current_char.is_some() {
-            let current = self.current_char.unwrap();
+            if let Some(token) = self.make_token()? {
+                tokens.push(token);
+            }
+        }
 
-            if current == ' ' || current == '\t' || current == '\n' || current == '\r' {
-                self.advance();
-            } else if current == ';' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Newline,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if utils::is_digit(&current) {
-                tokens.push(self.make_number()?);
-            } else if utils::is_alpha(&current) {
-                tokens.push(self.make_identifier());
-            } else if current == '"' {
-                tokens.push(self.make_string());
-            } else if current == '\'' {
-                tokens.push(self.make_char()?);
-            } else if current == '+' {
-                tokens.push(self.make_plus());
-            } else if current == '-' {
-                tokens.push(self.make_minus_or_arrow());
-            } else if current == '*' {
-                tokens.push(self.make_mul());
-            } else if current == '/' {
-                if let Some(token) = self.make_div_or_comment()? {
-                    tokens.push(token);
-                }
-            } else if current == '%' {
-                tokens.push(self.make_modulo());
-            } else if current == '^' {
-                tokens.push(self.make_bit_xor());
-            } else if current == ':' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Colon,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if current == '(' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Lparen,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if current == ')' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Rparen,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if current == '[' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Lsquare,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if current == ']' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Rsquare,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if current == '{' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Lcurly,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if current == '}' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Rcurly,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if current == '!' {
-                tokens.push(self.make_not_equals());
-            } else if current == '=' {
-                tokens.push(self.make_equals());
-            } else if current == '<' {
-                tokens.push(self.make_less_than());
-            } else if current == '>' {
-                tokens.push(self.make_greater_than());
-            } else if current == '&' {
-                tokens.push(self.make_and());
-            } else if current == '|' {
-                tokens.push(self.make_or());
-            } else if current == '~' {
-                tokens.push(Token::new_without_value(
-                    TokenType::BitNot,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
-                self.advance();
-            } else if current == '@' {
-                let pos_start = self.pos.clone();
+        tokens.push(Token::new_without_value(
+            TokenType::Rcurly,
+            self.pos.clone(),
+            self.pos.clone(),
+        ));
+        tokens.push(Token::new_without_value(
+            TokenType::Eof,
+            self.pos.clone(),
+            self.pos.clone(),
+        ));
+        Ok(tokens)
+    }
 
-                self.advance();
+    /// Makes a lossless token stream of the file, which also contains whitespace and comments
+    /// (as `TokenType::Whitespace` and `TokenType::Comment` tokens). Every token is returned
+    /// together with its source text, so concatenating them yields the original file.
+    pub fn make_lossless_tokens(&mut self) -> Result<Vec<(Token, String)>, Error> {
+        let chars: Vec<char> = self.file_text.chars().collect();
+        let mut tokens: Vec<(Token, String)> = vec![];
 
-                if self.current_char.is_some() && self.current_char.unwrap() == '=' {
-                    tokens.push(Token::new_without_value(
-                        TokenType::PointerAssign,
-                        pos_start,
-                        self.pos.clone(),
-                    ));
+        while let Some(current) = self.current_char {
+            let pos_start = self.pos.clone();
+            let start = *self.pos.index();
+
+            let token = if current == ' ' || current == '\t' || current == '\n' || current == '\r' {
+                while matches!(self.current_char, Some(' ' | '\t' | '\n' | '\r')) {
                     self.advance();
-                    continue;
                 }
 
-                if self.current_char.is_none()
-                    || !utils::is_digit(self.current_char.as_ref().unwrap())
-                {
-                    return Err(error::illegal_character_error(
-                        pos_start,
-                        self.pos.clone(),
-                        "Expected number after '@'!",
-                    ));
+                Token::new_without_value(TokenType::Whitespace, pos_start, self.pos.clone())
+            } else if current == '/' && matches!(chars.get(start + 1), Some('/' | '*')) {
+                self.advance();
+
+                if self.current_char.unwrap() == '*' {
+                    self.skip_multiline_comment()?;
+                } else {
+                    // unlike skip_comment, the line break isn't part of the comment
+                    while self.current_char.is_some() && self.current_char.unwrap() != '\n' {
+                        self.advance();
+                    }
                 }
 
-                let number = self.make_number()?;
-                if number.token_type() != TokenType::U64 {
-                    return Err(error::illegal_character_error(
-                        pos_start,
-                        self.pos.clone(),
-                        "Expected integer number after '@'!",
-                    ));
+                Token::new_without_value(TokenType::Comment, pos_start, self.pos.clone())
+            } else {
+                match self.make_token()? {
+                    Some(token) => token,
+                    None => continue,
                 }
+            };
 
-                tokens.push(Token::new_with_value(
-                    TokenType::ReadBytes,
-                    number.token_value().as_ref().unwrap().clone(),
-                    pos_start,
-                    self.pos.clone(),
-                ));
-            } else if current == ',' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Comma,
-                    self.pos.clone(),
-                    self.pos.clone(),
-                ));
+            tokens.push((token, chars[start..*self.pos.index()].iter().collect()));
+        }
+
+        Ok(tokens)
+    }
+
+    /// Makes the token starting at the current character. Returns `None`, if there is no token
+    /// there (e.g. for whitespace or comments).
+    fn make_token(&mut self) -> Result<Option<Token>, Error> {
+        let mut token: Option<Token> = None;
+        let current = self.current_char.unwrap();
+
+        if current == ' ' || current == '\t' || current == '\n' || current == '\r' {
+            self.advance();
+        } else if current == ';' {
+            token = Some(Token::new_without_value(
+                TokenType::Newline,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if utils::is_digit(&current) {
+            token = Some(self.make_number()?);
+        } else if utils::is_alpha(&current) {
+            token = Some(self.make_identifier());
+        } else if current == '"' {
+            token = Some(self.make_string());
+        } else if current == '\'' && self.is_label() {
+            token = Some(self.make_label());
+        } else if current == '\'' {
+            token = Some(self.make_char()?);
+        } else if current == '+' {
+            token = Some(self.make_plus());
+        } else if current == '-' {
+            token = Some(self.make_minus_or_arrow());
+        } else if current == '*' {
+            token = Some(self.make_mul());
+        } else if current == '/' {
+            token = self.make_div_or_comment()?;
+        } else if current == '%' {
+            token = Some(self.make_modulo());
+        } else if current == '^' {
+            token = Some(self.make_bit_xor());
+        } else if current == ':' {
+            token = Some(Token::new_without_value(
+                TokenType::Colon,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == '(' {
+            token = Some(Token::new_without_value(
+                TokenType::Lparen,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == ')' {
+            token = Some(Token::new_without_value(
+                TokenType::Rparen,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == '[' {
+            token = Some(Token::new_without_value(
+                TokenType::Lsquare,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == ']' {
+            token = Some(Token::new_without_value(
+                TokenType::Rsquare,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == '{' {
+            token = Some(Token::new_without_value(
+                TokenType::Lcurly,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == '}' {
+            token = Some(Token::new_without_value(
+                TokenType::Rcurly,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == '!' {
+            token = Some(self.make_not_equals());
+        } else if current == '=' {
+            token = Some(self.make_equals());
+        } else if current == '<' {
+            token = Some(self.make_less_than());
+        } else if current == '>' {
+            token = Some(self.make_greater_than());
+        } else if current == '&' {
+            token = Some(self.make_and());
+        } else if current == '|' {
+            token = Some(self.make_or());
+        } else if current == '~' {
+            token = Some(Token::new_without_value(
+                TokenType::BitNot,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == '@' {
+            let pos_start = self.pos.clone();
+
+            self.advance();
+
+            if self.current_char.is_some() && self.current_char.unwrap() == '=' {
+                let token =
+                    Token::new_without_value(TokenType::PointerAssign, pos_start, self.pos.clone());
                 self.advance();
-            } else if current == '.' {
-                tokens.push(Token::new_without_value(
-                    TokenType::Dot,
-                    self.pos.clone(),
+                return Ok(Some(token));
+            }
+
+            if self.current_char.is_none() || !utils::is_digit(self.current_char.as_ref().unwrap())
+            {
+                return Err(error::illegal_character_error(
+                    pos_start,
                     self.pos.clone(),
+                    "Expected number after '@'!",
                 ));
-                self.advance();
-            } else {
-                let pos_start = self.pos.clone();
-                self.advance();
+            }
 
+            let number = self.make_number()?;
+            if number.token_type() != TokenType::U64 {
                 return Err(error::illegal_character_error(
                     pos_start,
                     self.pos.clone(),
-                    format!("'{}'", current).as_str(),
+                    "Expected integer number after '@'!",
                 ));
             }
+
+            token = Some(Token::new_with_value(
+                TokenType::ReadBytes,
+                number.token_value().as_ref().unwrap().clone(),
+                pos_start,
+                self.pos.clone(),
+            ));
+        } else if current == ',' {
+            token = Some(Token::new_without_value(
+                TokenType::Comma,
+                self.pos.clone(),
+                self.pos.clone(),
+            ));
+            self.advance();
+        } else if current == '.' {
+            token = Some(self.make_dots());
+        } else {
+            let pos_start = self.pos.clone();
+            self.advance();
+
+            return Err(error::illegal_character_error(
+                pos_start,
+                self.pos.clone(),
+                format!("'{}'", current).as_str(),
+            ));
         }
 
-        tokens.push(Token::new_without_value(
-            TokenType::Rcurly,
-            self.pos.clone(),
-            self.pos.clone(),
-        ));
-        tokens.push(Token::new_without_value(
-            TokenType::Eof,
-            self.pos.clone(),
-            self.pos.clone(),
-        ));
-        Ok(tokens)
+        Ok(token)
     }
 
     fn skip_comment(&mut self) -> () {
@@ -273,7 +314,8 @@ impl Lexer {
             let current = self.current_char.unwrap();
 
             if current == '.' {
-                if dot_count == 1 {
+                // the number is the start of a range like `1..3`
+                if dot_count == 1 || self.next_char_is('.') {
                     break;
                 }
                 dot_count += 1;
@@ -337,6 +379,32 @@ impl Lexer {
         Token::new_with_value(token_type, id_str, pos_start, self.pos.clone())
     }
 
+    /// Checks, whether the `'` at the current character starts a label like `'outer` instead of a
+    /// character like `'a'`.
+    fn is_label(&self) -> bool {
+        let mut chars = self.file_text.chars().skip(*self.pos.index() + 1);
+
+        chars.next().is_some_and(|c| utils::is_alpha(&c)) && chars.next() != Some('\'')
+    }
+
+    fn make_label(&mut self) -> Token {
+        let pos_start = self.pos.clone();
+
+        self.advance();
+
+        let mut label = String::new();
+        while self.current_char.is_some()
+            && (utils::is_digit(self.current_char.as_ref().unwrap())
+                || utils::is_alpha(self.current_char.as_ref().unwrap())
+                || self.current_char.unwrap() == '_')
+        {
+            label.push(self.current_char.unwrap());
+            self.advance();
+        }
+
+        Token::new_with_value(TokenType::Label, label, pos_start, self.pos.clone())
+    }
+
     fn make_string(&mut self) -> Token {
         let mut new_string = String::new();
         let pos_start = self.pos.clone();
@@ -573,6 +641,32 @@ impl Lexer {
         Token::new_with_flags_no_value(TokenType::Mul, pos_start, self.pos.clone(), flags)
     }
 
+    fn next_char_is(&self, c: char) -> bool {
+        self.file_text.chars().nth(*self.pos.index() + 1) == Some(c)
+    }
+
+    fn make_dots(&mut self) -> Token {
+        let pos_start = self.pos.clone();
+        let index = *self.pos.index();
+
+        if self.file_text.chars().skip(index + 1).take(2).eq("..".chars()) {
+            self.advance();
+            self.advance();
+            self.advance();
+            return Token::new_without_value(TokenType::Ellipsis, pos_start, self.pos.clone());
+        }
+
+        if self.next_char_is('.') {
+            self.advance();
+            self.advance();
+            return Token::new_without_value(TokenType::DotDot, pos_start, self.pos.clone());
+        }
+
+        let token = Token::new_without_value(TokenType::Dot, pos_start.clone(), pos_start);
+        self.advance();
+        token
+    }
+
     fn make_div_or_comment(&mut self) -> Result<Option<Token>, Error> {
         let mut flags = TOKEN_FLAGS_NULL;
         let pos_start = self.pos.clone();
@@ -662,4 +756,67 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    pub fn test_make_token_ellipsis() -> Result<(), Error> {
+        let mut lexer = Lexer::new(PathBuf::new(), "(a, ...) p.x .. 1..3".to_string());
+        let tokens = lexer.make_tokens()?;
+
+        let token_types: Vec<TokenType> = tokens.iter().map(|t| t.token_type()).collect();
+        assert_eq!(
+            token_types,
+            vec![
+                TokenType::Lcurly,
+                TokenType::Lparen,
+                TokenType::Identifier,
+                TokenType::Comma,
+                TokenType::Ellipsis,
+                TokenType::Rparen,
+                TokenType::Identifier,
+                TokenType::Dot,
+                TokenType::Identifier,
+                TokenType::DotDot,
+                TokenType::U64,
+                TokenType::DotDot,
+                TokenType::U64,
+                TokenType::Rcurly,
+                TokenType::Eof,
+            ]
+        );
+        assert_eq!(*tokens[4].pos_end().index() - *tokens[4].pos_start().index(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_make_token_label() -> Result<(), Error> {
+        let mut lexer = Lexer::new(
+            PathBuf::new(),
+            "'outer: while 'a' { break 'outer; }".to_string(),
+        );
+        let tokens = lexer.make_tokens()?;
+
+        let token_types: Vec<TokenType> = tokens.iter().map(|t| t.token_type()).collect();
+        assert_eq!(
+            token_types,
+            vec![
+                TokenType::Lcurly,
+                TokenType::Label,
+                TokenType::Colon,
+                TokenType::Keyword,
+                TokenType::Char,
+                TokenType::Lcurly,
+                TokenType::Keyword,
+                TokenType::Label,
+                TokenType::Newline,
+                TokenType::Rcurly,
+                TokenType::Rcurly,
+                TokenType::Eof,
+            ]
+        );
+        assert_eq!(tokens[1].token_value().as_ref().unwrap(), "outer");
+        assert_eq!(tokens[7].token_value().as_ref().unwrap(), "outer");
+
+        Ok(())
+    }
 }
@@ -1,10 +1,22 @@
+pub mod aarch64_compiler;
+pub mod assembler;
+pub mod bindgen;
+pub mod c_compiler;
+pub mod c_header;
 pub mod compiler;
+pub mod constant;
 pub mod error;
+pub mod formatter;
+pub mod interpreter;
 pub mod lexer;
+pub mod linker;
+pub mod llvm_compiler;
+pub mod lsp;
 pub mod nodes;
 pub mod parser;
 pub mod position;
 pub mod results;
+pub mod semantic_info;
 pub mod semantics;
 pub mod symbol_table;
 pub mod syscall;
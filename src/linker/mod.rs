@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::assembler::elf::{
+    HEADER_SIZE, R_X86_64_32, R_X86_64_32S, R_X86_64_64, R_X86_64_PC32, R_X86_64_PLT32, SHF_WRITE,
+};
+use crate::error;
+use crate::error::Error;
+use crate::linker::object::{InputObject, SHN_ABS, SHN_COMMON, SHN_UNDEF};
+use crate::position::Position;
+
+mod object;
+
+const BASE_ADDRESS: u64 = 0x400000;
+const PAGE_SIZE: u64 = 0x1000;
+const PROGRAM_HEADER_SIZE: u64 = 56;
+const ENTRY_POINT: &str = "_start";
+
+const PT_LOAD: u32 = 1;
+const PT_GNU_STACK: u32 = 0x6474e551;
+
+const PF_X: u32 = 0x1;
+const PF_W: u32 = 0x2;
+const PF_R: u32 = 0x4;
+
+/// The segments the sections of the input objects are merged into.
+#[derive(Copy, Clone, PartialEq)]
+enum Segment {
+    /// Code and read only data
+    Text,
+    Data,
+    Bss,
+}
+
+fn align(value: u64, align: u64) -> u64 {
+    value.div_ceil(align) * align
+}
+
+struct ProgramHeader {
+    kind: u32,
+    flags: u32,
+    offset: u64,
+    address: u64,
+    file_size: u64,
+    memory_size: u64,
+    align: u64,
+}
+
+struct Linker {
+    files: Vec<PathBuf>,
+    objects: Vec<InputObject>,
+    /// The segment and the offset in it of every allocated section, by object and section index
+    placements: HashMap<(usize, usize), (Segment, u64)>,
+    text_address: u64,
+    data_address: u64,
+    bss_address: u64,
+    globals: HashMap<String, u64>,
+}
+
+impl Linker {
+    fn error(&self, object: usize, details: &str) -> Error {
+        let pos = Position::new(self.files[object].clone());
+        error::linker_error(pos.clone(), pos, details)
+    }
+
+    fn segment_address(&self, segment: Segment) -> u64 {
+        match segment {
+            Segment::Text => self.text_address,
+            Segment::Data => self.data_address,
+            Segment::Bss => self.bss_address,
+        }
+    }
+
+    fn section_address(&self, object: usize, section: usize) -> Option<u64> {
+        let (segment, offset) = self.placements.get(&(object, section))?;
+        Some(self.segment_address(*segment) + offset)
+    }
+
+    /// Places the allocated sections of all objects one after another in their segment and returns
+    /// the sizes of the segments.
+    fn place_sections(&mut self) -> [u64; 3] {
+        let mut sizes = [0_u64; 3];
+
+        for segment in [Segment::Text, Segment::Data, Segment::Bss] {
+            let size = &mut sizes[segment as usize];
+
+            for (i, object) in self.objects.iter().enumerate() {
+                for (j, section) in object.sections.iter().enumerate() {
+                    let Some(section) = section else {
+                        continue;
+                    };
+
+                    let section_segment = if section.flags & SHF_WRITE == 0 {
+                        Segment::Text
+                    } else if section.data.len() as u64 == section.size {
+                        Segment::Data
+                    } else {
+                        Segment::Bss
+                    };
+
+                    if section_segment == segment {
+                        *size = align(*size, section.align);
+                        self.placements.insert((i, j), (segment, *size));
+                        *size += section.size;
+                    }
+                }
+            }
+        }
+
+        sizes
+    }
+
+    fn resolve_globals(&mut self) -> Result<(), Error> {
+        let mut weak: Vec<String> = vec![];
+
+        for (i, object) in self.objects.iter().enumerate() {
+            for symbol in object.symbols.iter().filter(|symbol| symbol.global) {
+                let address = match symbol.section {
+                    SHN_UNDEF => continue,
+                    SHN_ABS => symbol.value,
+                    SHN_COMMON => {
+                        return Err(self.error(
+                            i,
+                            format!("Common symbol '{}' is not supported!", symbol.name).as_str(),
+                        ));
+                    }
+                    section => match self.section_address(i, section as usize) {
+                        Some(address) => address + symbol.value,
+                        None => continue,
+                    },
+                };
+
+                if self.globals.contains_key(&symbol.name) {
+                    if symbol.weak {
+                        continue;
+                    }
+                    if !weak.contains(&symbol.name) {
+                        return Err(self.error(
+                            i,
+                            format!("Symbol '{}' is defined more than once!", symbol.name).as_str(),
+                        ));
+                    }
+                }
+
+                if symbol.weak {
+                    weak.push(symbol.name.clone());
+                } else {
+                    weak.retain(|name| *name != symbol.name);
+                }
+                self.globals.insert(symbol.name.clone(), address);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn symbol_address(&self, object: usize, index: usize) -> Result<u64, Error> {
+        let symbol = self.objects[object]
+            .symbols
+            .get(index)
+            .ok_or_else(|| self.error(object, "Invalid symbol index in relocation!"))?;
+
+        match symbol.section {
+            SHN_UNDEF if symbol.global => match self.globals.get(&symbol.name) {
+                Some(address) => Ok(*address),
+                None if symbol.weak => Ok(0),
+                None => Err(self.error(
+                    object,
+                    format!("Undefined reference to '{}'!", symbol.name).as_str(),
+                )),
+            },
+            SHN_ABS => Ok(symbol.value),
+            _ if symbol.global && self.globals.contains_key(&symbol.name) => {
+                Ok(self.globals[&symbol.name])
+            }
+            section => match self.section_address(object, section as usize) {
+                Some(address) => Ok(address + symbol.value),
+                None => Err(self.error(
+                    object,
+                    format!("Symbol '{}' is not in a loaded section!", symbol.name).as_str(),
+                )),
+            },
+        }
+    }
+
+    /// Applies all relocations to the contents of the sections.
+    fn relocate(&mut self) -> Result<(), Error> {
+        for i in 0..self.objects.len() {
+            for j in 0..self.objects[i].relocations.len() {
+                let relocation = &self.objects[i].relocations[j];
+                let (section, offset, kind) =
+                    (relocation.section, relocation.offset, relocation.kind);
+
+                let target = self.symbol_address(i, relocation.symbol)? as i64 + relocation.addend;
+                let place = (self.section_address(i, section).unwrap() + offset) as i64;
+
+                let bytes = match kind {
+                    R_X86_64_64 => target.to_le_bytes().to_vec(),
+                    R_X86_64_PC32 | R_X86_64_PLT32 => i32::try_from(target - place)
+                        .map(|value| value.to_le_bytes().to_vec())
+                        .map_err(|_| self.error(i, "Relative relocation is out of range!"))?,
+                    R_X86_64_32 => u32::try_from(target)
+                        .map(|value| value.to_le_bytes().to_vec())
+                        .map_err(|_| self.error(i, "Relocation is out of range!"))?,
+                    R_X86_64_32S => i32::try_from(target)
+                        .map(|value| value.to_le_bytes().to_vec())
+                        .map_err(|_| self.error(i, "Relocation is out of range!"))?,
+                    _ => {
+                        return Err(self
+                            .error(i, format!("Unsupported relocation type {}!", kind).as_str()));
+                    }
+                };
+
+                let data = &mut self.objects[i].sections[section].as_mut().unwrap().data;
+                let start = offset as usize;
+                match data.get_mut(start..start + bytes.len()) {
+                    Some(place) => place.copy_from_slice(&bytes),
+                    None => return Err(self.error(i, "Relocation is out of bounds!")),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copies the contents of all sections of a segment into the file at the given offset.
+    fn write_segment(&self, file: &mut Vec<u8>, segment: Segment, offset: u64) {
+        for ((i, j), (section_segment, section_offset)) in &self.placements {
+            if *section_segment != segment {
+                continue;
+            }
+
+            let data = &self.objects[*i].sections[*j].as_ref().unwrap().data;
+            let start = (offset + section_offset) as usize;
+            if file.len() < start + data.len() {
+                file.resize(start + data.len(), 0);
+            }
+            file[start..start + data.len()].copy_from_slice(data);
+        }
+    }
+}
+
+/// Statically links ELF64 relocatable object files for x86-64 into an executable, without running
+/// 'ld'. Only freestanding programs are supported, every symbol has to be defined in one of the
+/// objects and the program starts at `_start`.
+/// The paths of the objects are only used for the positions of errors.
+pub fn link(objects: &[(PathBuf, Vec<u8>)]) -> Result<Vec<u8>, Error> {
+    let mut linker = Linker {
+        files: objects.iter().map(|(path, _)| path.clone()).collect(),
+        objects: vec![],
+        placements: HashMap::new(),
+        text_address: 0,
+        data_address: 0,
+        bss_address: 0,
+        globals: HashMap::new(),
+    };
+
+    for (i, (_, bytes)) in objects.iter().enumerate() {
+        match object::read(bytes) {
+            Ok(object) => linker.objects.push(object),
+            Err(details) => {
+                return Err(error::linker_error_with_parent(
+                    Position::new(linker.files[i].clone()),
+                    Position::new(linker.files[i].clone()),
+                    "Could not read object file",
+                    linker.error(i, details.as_str()),
+                ));
+            }
+        }
+    }
+
+    // region Layout
+    let [text_size, data_size, bss_size] = linker.place_sections();
+    let has_data = data_size + bss_size > 0;
+    let program_headers = if has_data { 3 } else { 2 };
+
+    let text_offset = align(
+        HEADER_SIZE as u64 + program_headers * PROGRAM_HEADER_SIZE,
+        16,
+    );
+    let data_offset = align(text_offset + text_size, PAGE_SIZE);
+
+    linker.text_address = BASE_ADDRESS + text_offset;
+    linker.data_address = BASE_ADDRESS + data_offset;
+    linker.bss_address = align(linker.data_address + data_size, 16);
+    // endregion
+
+    linker.resolve_globals()?;
+    linker.relocate()?;
+
+    let entry = match linker.globals.get(ENTRY_POINT) {
+        Some(entry) => *entry,
+        None => {
+            return Err(linker.error(
+                0,
+                format!("Entry point '{}' is not defined!", ENTRY_POINT).as_str(),
+            ));
+        }
+    };
+
+    let mut headers = vec![ProgramHeader {
+        kind: PT_LOAD,
+        flags: PF_R | PF_X,
+        offset: 0,
+        address: BASE_ADDRESS,
+        file_size: text_offset + text_size,
+        memory_size: text_offset + text_size,
+        align: PAGE_SIZE,
+    }];
+    if has_data {
+        headers.push(ProgramHeader {
+            kind: PT_LOAD,
+            flags: PF_R | PF_W,
+            offset: data_offset,
+            address: linker.data_address,
+            file_size: data_size,
+            memory_size: linker.bss_address + bss_size - linker.data_address,
+            align: PAGE_SIZE,
+        });
+    }
+    // marks the stack as not executable
+    headers.push(ProgramHeader {
+        kind: PT_GNU_STACK,
+        flags: PF_R | PF_W,
+        offset: 0,
+        address: 0,
+        file_size: 0,
+        memory_size: 0,
+        align: 16,
+    });
+
+    // region ELF header
+    let mut file: Vec<u8> = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0];
+    file.resize(16, 0);
+    file.extend_from_slice(&2_u16.to_le_bytes()); // executable
+    file.extend_from_slice(&62_u16.to_le_bytes()); // x86-64
+    file.extend_from_slice(&1_u32.to_le_bytes());
+    file.extend_from_slice(&entry.to_le_bytes());
+    file.extend_from_slice(&(HEADER_SIZE as u64).to_le_bytes()); // program headers
+    file.extend_from_slice(&0_u64.to_le_bytes()); // section headers
+    file.extend_from_slice(&0_u32.to_le_bytes());
+    file.extend_from_slice(&(HEADER_SIZE as u16).to_le_bytes());
+    file.extend_from_slice(&(PROGRAM_HEADER_SIZE as u16).to_le_bytes());
+    file.extend_from_slice(&(headers.len() as u16).to_le_bytes());
+    file.extend_from_slice(&0_u16.to_le_bytes());
+    file.extend_from_slice(&0_u16.to_le_bytes());
+    file.extend_from_slice(&0_u16.to_le_bytes());
+    // endregion
+
+    for header in &headers {
+        file.extend_from_slice(&header.kind.to_le_bytes());
+        file.extend_from_slice(&header.flags.to_le_bytes());
+        file.extend_from_slice(&header.offset.to_le_bytes());
+        file.extend_from_slice(&header.address.to_le_bytes());
+        file.extend_from_slice(&header.address.to_le_bytes());
+        file.extend_from_slice(&header.file_size.to_le_bytes());
+        file.extend_from_slice(&header.memory_size.to_le_bytes());
+        file.extend_from_slice(&header.align.to_le_bytes());
+    }
+
+    file.resize(text_offset as usize, 0);
+    linker.write_segment(&mut file, Segment::Text, text_offset);
+    file.resize((text_offset + text_size) as usize, 0);
+    if data_size > 0 {
+        linker.write_segment(&mut file, Segment::Data, data_offset);
+    }
+
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::assembler::assemble;
+
+    fn link_source(source: &str) -> Result<Vec<u8>, Error> {
+        let object = assemble(source, Path::new("test.asm")).unwrap();
+        link(&[(PathBuf::from("test.o"), object)])
+    }
+
+    fn read_u64(file: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(file[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_link() {
+        let file = match link_source(
+            "section .data
+\tS0: db  `hi`, 0
+section .bss
+\tST_x  RESB 8
+section .text
+global _start
+_start:
+\tmov     rsi, QWORD S0
+\tmov     rbx, [ST_x]
+\tcall    _start",
+        ) {
+            Ok(file) => file,
+            Err(err) => panic!("{}", err),
+        };
+
+        assert_eq!(file[..4], *b"\x7fELF");
+        assert_eq!(file[16], 2);
+
+        let text_address = BASE_ADDRESS + 0xf0;
+        let data_address = BASE_ADDRESS + PAGE_SIZE;
+        assert_eq!(read_u64(&file, 24), text_address);
+
+        // the contents of the sections are placed at their addresses relative to the base
+        assert_eq!(file[0xf0..0xf2], [0x48, 0xbe]);
+        assert_eq!(read_u64(&file, 0xf2), data_address);
+        assert_eq!(file[0xfa..0xfe], [0x48, 0x8b, 0x1c, 0x25]);
+        assert_eq!(file[0xfe..0x102], (data_address as u32 + 16).to_le_bytes());
+        assert_eq!(file[0x102..0x107], [0xe8, 0xe9, 0xff, 0xff, 0xff]);
+        assert_eq!(file[PAGE_SIZE as usize..], *b"hi\0");
+
+        // the size in memory of the data segment includes the .bss section
+        let data_header = HEADER_SIZE + PROGRAM_HEADER_SIZE as usize;
+        assert_eq!(read_u64(&file, data_header + 32), 3);
+        assert_eq!(read_u64(&file, data_header + 40), 24);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = link_source("section .text\nextern puts\n_start:\n\tcall    puts").unwrap_err();
+        assert_eq!(err.error_name(), "LinkerError");
+        assert_eq!(err.details(), "Undefined reference to 'puts'!");
+
+        let err = link_source("section .text\nglobal main\nmain:\n\tret").unwrap_err();
+        assert_eq!(err.details(), "Entry point '_start' is not defined!");
+
+        let err = link(&[(PathBuf::from("test.o"), b"not an object".to_vec())]).unwrap_err();
+        assert_eq!(err.details(), "Could not read object file");
+    }
+}
@@ -0,0 +1,207 @@
+use crate::assembler::elf::{
+    HEADER_SIZE, RELOCATION_SIZE, SECTION_HEADER_SIZE, SHF_ALLOC, SHT_NOBITS, SHT_RELA, SHT_SYMTAB,
+    STB_LOCAL, SYMBOL_SIZE,
+};
+
+pub const SHN_UNDEF: u16 = 0;
+pub const SHN_ABS: u16 = 0xfff1;
+pub const SHN_COMMON: u16 = 0xfff2;
+
+const STB_WEAK: u8 = 2;
+
+/// A section of an input object that ends up in the executable.
+pub struct InputSection {
+    pub flags: u64,
+    /// Empty for `SHT_NOBITS` sections like `.bss`
+    pub data: Vec<u8>,
+    pub size: u64,
+    pub align: u64,
+}
+
+pub struct InputSymbol {
+    pub name: String,
+    pub section: u16,
+    pub value: u64,
+    pub global: bool,
+    pub weak: bool,
+}
+
+pub struct InputRelocation {
+    /// The index of the section the relocation is applied to
+    pub section: usize,
+    pub offset: u64,
+    pub symbol: usize,
+    pub kind: u32,
+    pub addend: i64,
+}
+
+/// An ELF64 relocatable object file for x86-64, as written by NASM or the builtin assembler.
+pub struct InputObject {
+    /// The allocated sections by their index, all other sections are `None`
+    pub sections: Vec<Option<InputSection>>,
+    pub symbols: Vec<InputSymbol>,
+    pub relocations: Vec<InputRelocation>,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn slice(&self, offset: u64, len: u64) -> Result<&'a [u8], String> {
+        let start = usize::try_from(offset).map_err(|_| "Invalid offset!".to_string())?;
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| start.checked_add(len))
+            .filter(|end| *end <= self.bytes.len());
+
+        match end {
+            Some(end) => Ok(&self.bytes[start..end]),
+            None => Err("Unexpected end of file!".to_string()),
+        }
+    }
+
+    fn u16(&self, offset: u64) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(
+            self.slice(offset, 2)?.try_into().unwrap(),
+        ))
+    }
+
+    fn u32(&self, offset: u64) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(
+            self.slice(offset, 4)?.try_into().unwrap(),
+        ))
+    }
+
+    fn u64(&self, offset: u64) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(
+            self.slice(offset, 8)?.try_into().unwrap(),
+        ))
+    }
+
+    fn string(&self, table: u64, offset: u32) -> Result<String, String> {
+        let rest = usize::try_from(table + offset as u64)
+            .ok()
+            .and_then(|start| self.bytes.get(start..))
+            .ok_or_else(|| "Unexpected end of file!".to_string())?;
+        let len = rest
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| "Unterminated string!".to_string())?;
+
+        Ok(String::from_utf8_lossy(&rest[..len]).to_string())
+    }
+}
+
+struct SectionHeader {
+    kind: u32,
+    flags: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+}
+
+/// Reads the allocated sections, the symbols and the relocations of an object file.
+pub fn read(bytes: &[u8]) -> Result<InputObject, String> {
+    let reader = Reader { bytes };
+
+    if reader.slice(0, 4).ok() != Some(b"\x7fELF".as_slice()) {
+        return Err("Not an ELF file!".to_string());
+    }
+    if reader.slice(4, 2)? != [2, 1] {
+        return Err("Only 64 bit little endian object files are supported!".to_string());
+    }
+    if reader.u16(16)? != 1 {
+        return Err("Not a relocatable object file!".to_string());
+    }
+    if reader.u16(18)? != 62 {
+        return Err("Only x86-64 object files are supported!".to_string());
+    }
+
+    let section_headers_offset = reader.u64(40)?;
+    if reader.u16(52)? as usize != HEADER_SIZE || reader.u16(58)? as usize != SECTION_HEADER_SIZE {
+        return Err("Invalid ELF header!".to_string());
+    }
+
+    let mut headers: Vec<SectionHeader> = vec![];
+    for i in 0..reader.u16(60)? as u64 {
+        let offset = section_headers_offset + i * SECTION_HEADER_SIZE as u64;
+        headers.push(SectionHeader {
+            kind: reader.u32(offset + 4)?,
+            flags: reader.u64(offset + 8)?,
+            offset: reader.u64(offset + 24)?,
+            size: reader.u64(offset + 32)?,
+            link: reader.u32(offset + 40)?,
+            info: reader.u32(offset + 44)?,
+            align: reader.u64(offset + 48)?,
+        });
+    }
+
+    let mut sections: Vec<Option<InputSection>> = vec![];
+    for header in &headers {
+        if header.flags & SHF_ALLOC == 0 {
+            sections.push(None);
+            continue;
+        }
+
+        sections.push(Some(InputSection {
+            flags: header.flags,
+            data: if header.kind == SHT_NOBITS {
+                vec![]
+            } else {
+                reader.slice(header.offset, header.size)?.to_vec()
+            },
+            size: header.size,
+            align: header.align.max(1),
+        }));
+    }
+
+    let mut symbols: Vec<InputSymbol> = vec![];
+    if let Some(symtab) = headers.iter().find(|header| header.kind == SHT_SYMTAB) {
+        let strtab = headers
+            .get(symtab.link as usize)
+            .ok_or_else(|| "Invalid string table!".to_string())?;
+
+        for i in 0..symtab.size / SYMBOL_SIZE as u64 {
+            let offset = symtab.offset + i * SYMBOL_SIZE as u64;
+            let bind = reader.slice(offset + 4, 1)?[0] >> 4;
+
+            symbols.push(InputSymbol {
+                name: reader.string(strtab.offset, reader.u32(offset)?)?,
+                section: reader.u16(offset + 6)?,
+                value: reader.u64(offset + 8)?,
+                global: bind != STB_LOCAL,
+                weak: bind == STB_WEAK,
+            });
+        }
+    }
+
+    let mut relocations: Vec<InputRelocation> = vec![];
+    for header in headers.iter().filter(|header| header.kind == SHT_RELA) {
+        // relocations of sections that are not loaded, like debug information, are not needed
+        if !matches!(sections.get(header.info as usize), Some(Some(_))) {
+            continue;
+        }
+
+        for i in 0..header.size / RELOCATION_SIZE as u64 {
+            let offset = header.offset + i * RELOCATION_SIZE as u64;
+            let info = reader.u64(offset + 8)?;
+
+            relocations.push(InputRelocation {
+                section: header.info as usize,
+                offset: reader.u64(offset)?,
+                symbol: (info >> 32) as usize,
+                kind: info as u32,
+                addend: reader.u64(offset + 16)? as i64,
+            });
+        }
+    }
+
+    Ok(InputObject {
+        sections,
+        symbols,
+        relocations,
+    })
+}
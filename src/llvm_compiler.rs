@@ -0,0 +1,1994 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::constant;
+use crate::constant::Constant;
+use crate::error;
+use crate::error::Error;
+use crate::nodes::address_of_node::AddressOfNode;
+use crate::nodes::array_node::ArrayNode;
+use crate::nodes::assert_node::AssertNode;
+use crate::nodes::binop_node::BinOpNode;
+use crate::nodes::break_node::BreakNode;
+use crate::nodes::call_node::CallNode;
+use crate::nodes::cast_node::CastNode;
+use crate::nodes::check_node::{Check, CheckNode, CHECK_FAILED_EXIT_CODE};
+use crate::nodes::const_def_node::ConstDefinitionNode;
+use crate::nodes::continue_node::ContinueNode;
+use crate::nodes::copy_node::CopyNode;
+use crate::nodes::extern_node::ExternNode;
+use crate::nodes::f64_to_u64_node::F64ToU64Node;
+use crate::nodes::floating_binop_node::FloatingBinOpNode;
+use crate::nodes::for_node::ForNode;
+use crate::nodes::functiondecl_node::FunctionDeclarationNode;
+use crate::nodes::functiondef_node::FunctionDefinitionNode;
+use crate::nodes::if_node::IfNode;
+use crate::nodes::import_node::ImportNode;
+use crate::nodes::memory_node::{MemoryNode, MemoryOperation};
+use crate::nodes::offset_node::OffsetNode;
+use crate::nodes::pointer_assign_node::PointerAssignNode;
+use crate::nodes::read_bytes_node::ReadBytesNode;
+use crate::nodes::return_node::ReturnNode;
+use crate::nodes::sizeof_node::SizeOfNode;
+use crate::nodes::slice_node::SliceNode;
+use crate::nodes::stack_allocation_node::StackAllocationNode;
+use crate::nodes::statements_node::StatementsNode;
+use crate::nodes::static_decl_node::StaticDeclarationNode;
+use crate::nodes::static_def_node::StaticDefinitionNode;
+use crate::nodes::string_node::StringNode;
+use crate::nodes::struct_def_node::StructDefinitionNode;
+use crate::nodes::syscall_node::SyscallNode;
+use crate::nodes::u64_to_f64_node::U64ToF64Node;
+use crate::nodes::unaryop_node::UnaryOpNode;
+use crate::nodes::var_node::declare::VarDeclarationNode;
+use crate::nodes::var_node::typed_access::VarTypedAccessNode;
+use crate::nodes::var_node::typed_assign::VarTypedAssignNode;
+use crate::nodes::while_node::WhileNode;
+use crate::nodes::{Node, NodeType};
+use crate::syscall::{SyscallTable, TargetObjectType};
+use crate::token::TokenType;
+use crate::utils::unescape;
+use crate::values::value_size::ValueSize;
+use crate::values::value_type::slice_type::{passed_types, SLICE_LENGTH_OFFSET};
+use crate::values::value_type::{ValueType, ValueTypes};
+
+/// The LLVM type Umber values of the given type are passed and stored as.
+fn llvm_type(value_type: &dyn ValueType) -> &'static str {
+    match value_type.value_type() {
+        ValueTypes::F64 => "double",
+        ValueTypes::Void => "void",
+        ValueTypes::Pointer
+        | ValueTypes::Array
+        | ValueTypes::Slice
+        | ValueTypes::String
+        | ValueTypes::Function => "ptr",
+        _ => integer_type(value_type.get_size()),
+    }
+}
+
+fn integer_type(size: ValueSize) -> &'static str {
+    match size {
+        ValueSize::Byte => "i8",
+        ValueSize::Word => "i16",
+        ValueSize::Dword => "i32",
+        ValueSize::Qword => "i64",
+    }
+}
+
+fn zero_value(llvm_type: &str) -> &'static str {
+    match llvm_type {
+        "double" => "0.0",
+        "ptr" => "null",
+        _ => "0",
+    }
+}
+
+fn truncate(value: u64, size: ValueSize) -> u64 {
+    match size {
+        ValueSize::Byte => value & 0xff,
+        ValueSize::Word => value & 0xffff,
+        ValueSize::Dword => value & 0xffff_ffff,
+        ValueSize::Qword => value,
+    }
+}
+
+/// Writes the bytes as an LLVM string constant, escaping everything that is not printable ASCII.
+fn llvm_string_literal(bytes: &[u8]) -> String {
+    let mut literal = String::from("c\"");
+    for byte in bytes {
+        match byte {
+            b' '..=b'~' if *byte != b'"' && *byte != b'\\' => literal.push(*byte as char),
+            _ => literal.push_str(&format!("\\{:02X}", byte)),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+#[derive(Clone)]
+struct Variable {
+    /// The global (`@`) or the stack slot (`%`) of the variable
+    address: String,
+    llvm_type: &'static str,
+}
+
+struct Signature {
+    /// The types of the values passed to the function, with slices split into pointer and length
+    arg_types: Vec<&'static str>,
+    return_type: &'static str,
+    is_variadic: bool,
+}
+
+impl Signature {
+    fn parameter_list(&self) -> String {
+        let mut parameters = self.arg_types.join(", ");
+        if self.is_variadic {
+            parameters.push_str(", ...");
+        }
+        parameters
+    }
+}
+
+struct Loop {
+    label: Option<String>,
+    continue_label: String,
+    break_label: String,
+}
+
+/// Generates textual LLVM IR from validated ASTs, which can be compiled by `llc` or `clang`.
+/// Functions, statics and externs get typed signatures, while expressions are computed in `i64`
+/// like in the registers of the assembly backend: loads zero extend, and division, right shifts and
+/// comparisons are signed. Locals live in `alloca`s, which LLVM promotes to registers.
+pub struct LlvmCompiler {
+    target_object_type: TargetObjectType,
+
+    functions: HashMap<String, Signature>,
+    globals: HashMap<String, Variable>,
+    strings: HashMap<Vec<u8>, String>,
+    /// The number of arrays and structs, that the values of statics point to
+    data_count: usize,
+
+    /// The type definitions, strings, statics and external declarations
+    declarations: Vec<String>,
+    definitions: Vec<String>,
+    uses_memset: bool,
+    uses_memcpy: bool,
+    uses_memmove: bool,
+    /// The declarations of the `llvm.*.with.overflow.*` intrinsics used by overflow checks
+    overflow_intrinsics: BTreeSet<String>,
+
+    /// Whether the Umber `main` is renamed, because the generated `main` calls it
+    rename_main: bool,
+
+    // region State of the current function
+    lines: Vec<String>,
+    allocas: Vec<String>,
+    next_id: usize,
+    local_names: HashMap<String, usize>,
+    scopes: Vec<HashMap<String, Variable>>,
+    loops: Vec<Loop>,
+    return_type: &'static str,
+    current_block: String,
+    /// Whether the current block already ended with a branch or return
+    terminated: bool,
+    // endregion
+}
+
+impl LlvmCompiler {
+    pub fn new(target_object_type: TargetObjectType) -> Self {
+        LlvmCompiler {
+            target_object_type,
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            strings: HashMap::new(),
+            data_count: 0,
+            declarations: vec![],
+            definitions: vec![],
+            uses_memset: false,
+            uses_memcpy: false,
+            uses_memmove: false,
+            overflow_intrinsics: BTreeSet::new(),
+            rename_main: false,
+            lines: vec![],
+            allocas: vec![],
+            next_id: 0,
+            local_names: HashMap::new(),
+            scopes: vec![],
+            loops: vec![],
+            return_type: "void",
+            current_block: String::new(),
+            terminated: false,
+        }
+    }
+
+    // region Helper functions
+
+    fn unsupported(node: &dyn Node, details: &str) -> Error {
+        error::not_yet_implemented_error(node.pos_start().clone(), node.pos_end().clone(), details)
+    }
+
+    fn function_name(&self, name: &str) -> String {
+        if self.rename_main && name == "main" {
+            String::from("umber_main")
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn target_triple(&self) -> &'static str {
+        match self.target_object_type {
+            TargetObjectType::X86_64 => "x86_64-unknown-linux-gnu",
+            TargetObjectType::Win64 => "x86_64-pc-windows-msvc",
+            TargetObjectType::Macho64 => "x86_64-apple-macosx10.15.0",
+            TargetObjectType::Aarch64Linux => "aarch64-unknown-linux-gnu",
+        }
+    }
+
+    /// Adds an instruction to the current block. Code after a `br` or `ret` is unreachable, but
+    /// still has to be in a block, so a new one is started.
+    fn inst(&mut self, inst: String) {
+        if self.terminated {
+            let label = self.new_label("dead");
+            self.start_block(label);
+        }
+        self.lines.push(format!("  {}", inst));
+    }
+
+    /// Adds an instruction producing a value and returns the register it is stored in.
+    fn value(&mut self, inst: String) -> String {
+        let register = format!("%.t{}", self.next_id);
+        self.next_id += 1;
+        self.inst(format!("{} = {}", register, inst));
+        register
+    }
+
+    fn terminate(&mut self, inst: String) {
+        self.inst(inst);
+        self.terminated = true;
+    }
+
+    fn new_label(&mut self, name: &str) -> String {
+        let label = format!("{}.{}", name, self.next_id);
+        self.next_id += 1;
+        label
+    }
+
+    /// Starts a new block, falling through from the current one if it isn't terminated yet.
+    fn start_block(&mut self, label: String) {
+        if !self.terminated {
+            self.lines.push(format!("  br label %{}", label));
+        }
+        self.lines.push(format!("{}:", label));
+        self.current_block = label;
+        self.terminated = false;
+    }
+
+    /// Branches to one of the labels, depending on whether the value isn't zero.
+    fn branch(&mut self, value: &str, if_true: &str, if_false: &str) {
+        let condition = self.value(format!("icmp ne i64 {}, 0", value));
+        self.terminate(format!(
+            "br i1 {}, label %{}, label %{}",
+            condition, if_true, if_false
+        ));
+    }
+
+    fn bool_to_i64(&mut self, condition: String) -> String {
+        self.value(format!("zext i1 {} to i64", condition))
+    }
+
+    /// Converts an `i64` to the given type.
+    fn cast_from_i64(&mut self, value: String, llvm_type: &str) -> String {
+        match llvm_type {
+            "i64" => value,
+            "double" => self.value(format!("bitcast i64 {} to double", value)),
+            "ptr" => self.value(format!("inttoptr i64 {} to ptr", value)),
+            _ => self.value(format!("trunc i64 {} to {}", value, llvm_type)),
+        }
+    }
+
+    /// Converts a value of the given type to an `i64`.
+    fn cast_to_i64(&mut self, value: String, llvm_type: &str) -> String {
+        match llvm_type {
+            "i64" => value,
+            "double" => self.value(format!("bitcast double {} to i64", value)),
+            "ptr" => self.value(format!("ptrtoint ptr {} to i64", value)),
+            _ => self.value(format!("zext {} {} to i64", llvm_type, value)),
+        }
+    }
+
+    fn as_double(&mut self, value: String) -> String {
+        self.cast_from_i64(value, "double")
+    }
+
+    fn declare_local(&mut self, name: &str, llvm_type: &'static str) -> Variable {
+        let count = self.local_names.entry(name.to_string()).or_insert(0);
+        // labels share the namespace of locals, so the stack slots get a suffix
+        let address = if *count == 0 {
+            format!("%{}.addr", name)
+        } else {
+            format!("%{}.{}.addr", name, count)
+        };
+        *count += 1;
+
+        self.allocas
+            .push(format!("  {} = alloca {}", address, llvm_type));
+
+        let variable = Variable { address, llvm_type };
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), variable.clone());
+        variable
+    }
+
+    fn get_var(&self, name: &str) -> Variable {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .or_else(|| self.globals.get(name))
+            .cloned()
+            .unwrap()
+    }
+
+    fn load_var(&mut self, variable: &Variable) -> String {
+        let value = self.value(format!(
+            "load {}, ptr {}",
+            variable.llvm_type, variable.address
+        ));
+        self.cast_to_i64(value, variable.llvm_type)
+    }
+
+    fn store_var(&mut self, variable: &Variable, value: String) {
+        let value = self.cast_from_i64(value, variable.llvm_type);
+        self.inst(format!(
+            "store {} {}, ptr {}",
+            variable.llvm_type, value, variable.address
+        ));
+    }
+
+    /// Reserves a buffer on the stack of the current function and returns its address. Like in the
+    /// assembly backend, the buffer is reused every time the node is evaluated.
+    fn stack_buffer(&mut self, size: u64) -> String {
+        let name = format!("%.a{}", self.next_id);
+        self.next_id += 1;
+        self.allocas.push(format!(
+            "  {} = alloca [{} x i8], align 8",
+            name,
+            size.max(1)
+        ));
+        self.cast_to_i64(name, "ptr")
+    }
+
+    fn load_qword(&mut self, address: &str) -> String {
+        let ptr = self.cast_from_i64(address.to_string(), "ptr");
+        self.value(format!("load i64, ptr {}, align 1", ptr))
+    }
+
+    fn store_qword(&mut self, address: &str, value: String) {
+        let ptr = self.cast_from_i64(address.to_string(), "ptr");
+        self.inst(format!("store i64 {}, ptr {}, align 1", value, ptr));
+    }
+
+    /// Sets `size` bytes at the address to the `i8` value.
+    fn fill_memory(&mut self, address: &str, value: &str, size: &str) {
+        self.uses_memset = true;
+        let ptr = self.cast_from_i64(address.to_string(), "ptr");
+        self.inst(format!(
+            "call void @llvm.memset.p0.i64(ptr align 1 {}, i8 {}, i64 {}, i1 false)",
+            ptr, value, size
+        ));
+    }
+
+    fn zero_memory(&mut self, address: &str, size: u64) {
+        self.fill_memory(address, "0", &size.to_string());
+    }
+
+    /// Copies `size` bytes with `llvm.memmove` if the blocks can overlap, `llvm.memcpy` otherwise.
+    fn copy_memory(&mut self, destination: &str, source: &str, size: &str, overlapping: bool) {
+        let intrinsic = if overlapping {
+            self.uses_memmove = true;
+            "memmove"
+        } else {
+            self.uses_memcpy = true;
+            "memcpy"
+        };
+
+        let destination = self.cast_from_i64(destination.to_string(), "ptr");
+        let source = self.cast_from_i64(source.to_string(), "ptr");
+        self.inst(format!(
+            "call void @llvm.{}.p0.p0.i64(ptr align 1 {}, ptr align 1 {}, i64 {}, i1 false)",
+            intrinsic, destination, source, size
+        ));
+    }
+
+    fn string_label(&mut self, bytes: Vec<u8>) -> String {
+        match self.strings.get(&bytes) {
+            Some(label) => label.clone(),
+            None => {
+                let label = format!("@.str.{}", self.strings.len());
+                // strings are writable and null terminated, like in the data section of the
+                // assembly backend
+                let mut terminated = bytes.clone();
+                terminated.push(0);
+                self.declarations.push(format!(
+                    "{} = private global [{} x i8] {}",
+                    label,
+                    terminated.len(),
+                    llvm_string_literal(&terminated)
+                ));
+                self.strings.insert(bytes, label.clone());
+                label
+            }
+        }
+    }
+
+    /// Checks, whether the given node evaluates to a floating point number, as unary operations
+    /// on them are not lowered by the validator.
+    fn is_floating_point(&self, node: &Box<dyn Node>) -> bool {
+        match node.node_type() {
+            NodeType::FloatingPoint | NodeType::U64ToF64 => true,
+            NodeType::FloatingBinOp => !matches!(
+                node.as_any()
+                    .downcast_ref::<FloatingBinOpNode>()
+                    .unwrap()
+                    .op_token()
+                    .token_type(),
+                TokenType::Ee
+                    | TokenType::Ne
+                    | TokenType::Gt
+                    | TokenType::Lt
+                    | TokenType::Gte
+                    | TokenType::Lte
+            ),
+            NodeType::UnaryOp => {
+                self.is_floating_point(node.as_any().downcast_ref::<UnaryOpNode>().unwrap().node())
+            }
+            NodeType::VarTypedAccess => {
+                node.as_any()
+                    .downcast_ref::<VarTypedAccessNode>()
+                    .unwrap()
+                    .value_type()
+                    .value_type()
+                    == ValueTypes::F64
+            }
+            NodeType::Call => self
+                .functions
+                .get(
+                    node.as_any()
+                        .downcast_ref::<CallNode>()
+                        .unwrap()
+                        .func_to_call(),
+                )
+                .is_some_and(|f| f.return_type == "double"),
+            _ => false,
+        }
+    }
+
+    // endregion
+}
+
+impl LlvmCompiler {
+    // region Declarations
+
+    /// Collects the signatures of all functions and defines the structs, statics and externs, so
+    /// they can be used before their definition.
+    fn declare(&mut self, node: &Box<dyn Node>) -> Result<(), Error> {
+        match node.node_type() {
+            NodeType::Statements => {
+                for statement in node
+                    .as_any()
+                    .downcast_ref::<StatementsNode>()
+                    .unwrap()
+                    .statement_nodes()
+                {
+                    self.declare(statement)?;
+                }
+            }
+            NodeType::Import => {
+                self.declare(node.as_any().downcast_ref::<ImportNode>().unwrap().node())?
+            }
+            NodeType::FunctionDef => {
+                let func_def_node = node
+                    .as_any()
+                    .downcast_ref::<FunctionDefinitionNode>()
+                    .unwrap();
+                self.functions.insert(
+                    func_def_node.var_name().to_string(),
+                    Signature {
+                        arg_types: passed_types(
+                            &func_def_node
+                                .args()
+                                .iter()
+                                .map(|(_, arg_type)| arg_type.clone())
+                                .collect::<Vec<_>>(),
+                        )
+                        .iter()
+                        .map(|arg_type| llvm_type(arg_type.as_ref()))
+                        .collect(),
+                        return_type: llvm_type(func_def_node.return_type().as_ref()),
+                        is_variadic: false,
+                    },
+                );
+            }
+            NodeType::StructDef => {
+                let struct_def_node = node
+                    .as_any()
+                    .downcast_ref::<StructDefinitionNode>()
+                    .unwrap();
+                // structs are packed, their fields are only accessed through byte offsets
+                let fields: Vec<&str> = struct_def_node
+                    .fields()
+                    .iter()
+                    .map(|(_, field_type)| llvm_type(field_type.as_ref()))
+                    .collect();
+                self.declarations.push(format!(
+                    "%struct.{} = type <{{ {} }}>",
+                    struct_def_node.name(),
+                    fields.join(", ")
+                ));
+            }
+            NodeType::Extern => {
+                self.declare_extern(node.as_any().downcast_ref::<ExternNode>().unwrap())
+            }
+            NodeType::StaticDef => {
+                let static_def_node = node
+                    .as_any()
+                    .downcast_ref::<StaticDefinitionNode>()
+                    .unwrap();
+                self.define_global(
+                    format!("@ST_{}", static_def_node.name()),
+                    static_def_node.name(),
+                    static_def_node.value_type().as_ref(),
+                    static_def_node.value(),
+                    !*static_def_node.is_mutable(),
+                )?;
+            }
+            NodeType::ConstDef => {
+                let const_def_node = node.as_any().downcast_ref::<ConstDefinitionNode>().unwrap();
+                self.define_global(
+                    format!("@C_{}", const_def_node.name()),
+                    const_def_node.name(),
+                    const_def_node.value_type().as_ref(),
+                    const_def_node.value(),
+                    true,
+                )?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn declare_extern(&mut self, extern_node: &ExternNode) {
+        let statement = extern_node.top_level_statement();
+
+        match statement.node_type() {
+            NodeType::FunctionDecl => {
+                let func_decl_node = statement
+                    .as_any()
+                    .downcast_ref::<FunctionDeclarationNode>()
+                    .unwrap();
+
+                let signature = Signature {
+                    arg_types: passed_types(
+                        &func_decl_node
+                            .args()
+                            .iter()
+                            .map(|(_, arg_type)| arg_type.clone())
+                            .collect::<Vec<_>>(),
+                    )
+                    .iter()
+                    .map(|arg_type| llvm_type(arg_type.as_ref()))
+                    .collect(),
+                    return_type: llvm_type(func_decl_node.return_type().as_ref()),
+                    is_variadic: func_decl_node.is_variadic(),
+                };
+                self.declarations.push(format!(
+                    "declare {} @{}({})",
+                    signature.return_type,
+                    func_decl_node.var_name(),
+                    signature.parameter_list()
+                ));
+                self.functions
+                    .insert(func_decl_node.var_name().to_string(), signature);
+            }
+            NodeType::StaticDecl => {
+                let static_decl_node = statement
+                    .as_any()
+                    .downcast_ref::<StaticDeclarationNode>()
+                    .unwrap();
+                let variable = Variable {
+                    address: format!("@{}", static_decl_node.name()),
+                    llvm_type: llvm_type(static_decl_node.value_type().as_ref()),
+                };
+
+                self.declarations.push(format!(
+                    "{} = external global {}",
+                    variable.address, variable.llvm_type
+                ));
+                self.globals
+                    .insert(static_decl_node.name().to_string(), variable);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Defines a static or constant, whose value has to be known at compile time.
+    fn define_global(
+        &mut self,
+        address: String,
+        name: &str,
+        value_type: &dyn ValueType,
+        value: &Box<dyn Node>,
+        is_const: bool,
+    ) -> Result<(), Error> {
+        let variable = Variable {
+            address,
+            llvm_type: llvm_type(value_type),
+        };
+
+        let initializer = match constant::evaluate(value) {
+            Some(value) => {
+                self.constant_initializer(&value, variable.llvm_type, value_type.get_size())
+            }
+            None => {
+                return Err(Self::unsupported(
+                    value.as_ref(),
+                    format!(
+                        "The value of '{}' has to be a constant for the LLVM backend!",
+                        name
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+
+        self.declarations.push(format!(
+            "{} = internal {} {} {}",
+            variable.address,
+            if is_const { "constant" } else { "global" },
+            variable.llvm_type,
+            initializer
+        ));
+        self.globals.insert(name.to_string(), variable);
+
+        Ok(())
+    }
+
+    /// Returns the LLVM constant for a value known at compile time. The arrays and structs
+    /// addresses point to are defined as well.
+    fn constant_initializer(
+        &mut self,
+        value: &Constant,
+        llvm_type: &str,
+        size: ValueSize,
+    ) -> String {
+        let label = match value {
+            Constant::Integer(_) | Constant::Float(_) => {
+                let bits = value.bits().unwrap();
+                return match llvm_type {
+                    "ptr" if bits == 0 => String::from("null"),
+                    "ptr" => format!("inttoptr (i64 {} to ptr)", bits as i64),
+                    "double" => format!("0x{:016X}", bits),
+                    _ => (truncate(bits, size) as i64).to_string(),
+                };
+            }
+            Constant::String(bytes) => self.string_label(bytes.clone()),
+            Constant::Array {
+                element_size,
+                elements,
+            } => {
+                let element_type = integer_type(*element_size);
+                let elements: Vec<String> = elements
+                    .iter()
+                    .map(|element| {
+                        let initializer =
+                            self.constant_initializer(element, element_type, *element_size);
+                        format!("{} {}", element_type, initializer)
+                    })
+                    .collect();
+
+                let label = format!("@.data.{}", self.data_count);
+                self.data_count += 1;
+                self.declarations.push(format!(
+                    "{} = private global [{} x {}] [{}]",
+                    label,
+                    elements.len(),
+                    element_type,
+                    elements.join(", ")
+                ));
+                label
+            }
+            Constant::Zeroed(bytes) => {
+                let label = format!("@.data.{}", self.data_count);
+                self.data_count += 1;
+                self.declarations.push(format!(
+                    "{} = private global [{} x i8] zeroinitializer",
+                    label, bytes
+                ));
+                label
+            }
+        };
+
+        match llvm_type {
+            "ptr" => label,
+            llvm_type => format!("ptrtoint (ptr {} to {})", label, llvm_type),
+        }
+    }
+
+    // endregion
+
+    // region Functions
+
+    fn function(&mut self, node: &FunctionDefinitionNode) -> Result<(), Error> {
+        let name = self.function_name(node.var_name());
+
+        self.lines.clear();
+        self.allocas.clear();
+        self.next_id = 0;
+        self.local_names.clear();
+        self.scopes = vec![HashMap::new()];
+        self.loops.clear();
+        self.return_type = llvm_type(node.return_type().as_ref());
+        self.current_block = String::from("entry");
+        self.terminated = false;
+
+        let mut args: Vec<String> = vec![];
+        for (arg_name, arg_type) in node.args() {
+            let variable = self.declare_local(arg_name, llvm_type(arg_type.as_ref()));
+
+            // the pointer and length of slices are stored in a buffer, which is the slice
+            if arg_type.value_type() == ValueTypes::Slice {
+                let name = variable.address.trim_end_matches(".addr").to_string();
+                let pointer = format!("{}.ptr", name);
+                let length = format!("{}.len", name);
+                args.push(format!("ptr {}", pointer));
+                args.push(format!("i64 {}", length));
+
+                let slice = self.stack_buffer(arg_type.size_in_bytes());
+                let pointer = self.cast_to_i64(pointer, "ptr");
+                self.store_qword(&slice, pointer);
+                let length_address =
+                    self.value(format!("add i64 {}, {}", slice, SLICE_LENGTH_OFFSET));
+                self.store_qword(&length_address, length);
+                self.store_var(&variable, slice);
+                continue;
+            }
+
+            let arg = format!("{}.arg", variable.address.trim_end_matches(".addr"));
+
+            args.push(format!("{} {}", variable.llvm_type, arg));
+            self.inst(format!(
+                "store {} {}, ptr {}",
+                variable.llvm_type, arg, variable.address
+            ));
+        }
+
+        let body = node.body_node();
+        if body.node_type() == NodeType::Statements {
+            for statement in body
+                .as_any()
+                .downcast_ref::<StatementsNode>()
+                .unwrap()
+                .statement_nodes()
+            {
+                self.statement(statement)?;
+            }
+        } else {
+            self.statement(body)?;
+        }
+
+        // like falling off the end of a function in the assembly backend, the result is undefined
+        if !self.terminated {
+            match self.return_type {
+                "void" => self.terminate(String::from("ret void")),
+                return_type => {
+                    self.terminate(format!("ret {} {}", return_type, zero_value(return_type)))
+                }
+            }
+        }
+
+        let mut definition = String::new();
+        writeln!(
+            definition,
+            "define {} @{}({}) {{",
+            self.return_type,
+            name,
+            args.join(", ")
+        )
+        .unwrap();
+        writeln!(definition, "entry:").unwrap();
+        for line in self.allocas.iter().chain(self.lines.iter()) {
+            writeln!(definition, "{}", line).unwrap();
+        }
+        writeln!(definition, "}}").unwrap();
+
+        self.definitions.push(definition);
+
+        Ok(())
+    }
+
+    fn top_level(&mut self, node: &Box<dyn Node>) -> Result<(), Error> {
+        match node.node_type() {
+            NodeType::Statements => {
+                for statement in node
+                    .as_any()
+                    .downcast_ref::<StatementsNode>()
+                    .unwrap()
+                    .statement_nodes()
+                {
+                    self.top_level(statement)?;
+                }
+                Ok(())
+            }
+            NodeType::Import => {
+                self.top_level(node.as_any().downcast_ref::<ImportNode>().unwrap().node())
+            }
+            NodeType::FunctionDef => self.function(
+                node.as_any()
+                    .downcast_ref::<FunctionDefinitionNode>()
+                    .unwrap(),
+            ),
+            NodeType::Assembly => Err(Self::unsupported(
+                node.as_ref(),
+                "Inline assembly is not supported by the LLVM backend!",
+            )),
+            // everything else was declared before
+            _ => Ok(()),
+        }
+    }
+
+    // endregion
+
+    // region Statements
+
+    /// Generates the statements of a block in a new scope. If `result` is given, the value of the
+    /// block is stored at that address.
+    fn block(&mut self, node: &Box<dyn Node>, result: Option<&str>) -> Result<(), Error> {
+        self.scopes.push(HashMap::new());
+
+        let res = if node.node_type() == NodeType::Statements {
+            let statement_nodes = node
+                .as_any()
+                .downcast_ref::<StatementsNode>()
+                .unwrap()
+                .statement_nodes();
+            statement_nodes
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, statement)| match result {
+                    Some(result) if i == statement_nodes.len() - 1 => {
+                        let value = self.expression(statement)?;
+                        self.store_qword(result, value);
+                        Ok(())
+                    }
+                    _ => self.statement(statement),
+                })
+        } else {
+            self.statement(node)
+        };
+
+        self.scopes.pop();
+        res
+    }
+
+    fn statement(&mut self, node: &Box<dyn Node>) -> Result<(), Error> {
+        match node.node_type() {
+            NodeType::Statements => self.block(node, None)?,
+            NodeType::VarDeclaration => {
+                let var_declaration_node =
+                    node.as_any().downcast_ref::<VarDeclarationNode>().unwrap();
+                let value = self.expression(var_declaration_node.value_node())?;
+
+                let variable = self.declare_local(
+                    var_declaration_node.var_name(),
+                    llvm_type(var_declaration_node.var_type().as_ref()),
+                );
+                self.store_var(&variable, value);
+            }
+            NodeType::VarTypedAssign => {
+                let var_assign_node = node.as_any().downcast_ref::<VarTypedAssignNode>().unwrap();
+                let value = self.expression(var_assign_node.value_node())?;
+
+                let variable = self.get_var(var_assign_node.var_name());
+                self.store_var(&variable, value);
+            }
+            NodeType::PointerAssign => {
+                let pointer_assign_node =
+                    node.as_any().downcast_ref::<PointerAssignNode>().unwrap();
+                let address = self.expression(pointer_assign_node.ptr())?;
+                let value = self.expression(pointer_assign_node.value())?;
+
+                let size = pointer_assign_node.pointee_type().get_size();
+                let ptr = self.cast_from_i64(address, "ptr");
+                let value = self.cast_from_i64(value, integer_type(size));
+                self.inst(format!(
+                    "store {} {}, ptr {}, align 1",
+                    integer_type(size),
+                    value,
+                    ptr
+                ));
+            }
+            NodeType::Return => {
+                let return_node = node.as_any().downcast_ref::<ReturnNode>().unwrap();
+
+                match return_node.node_to_return() {
+                    Some(node_to_return) if self.return_type != "void" => {
+                        let value = self.expression(node_to_return)?;
+                        let return_type = self.return_type;
+                        let value = self.cast_from_i64(value, return_type);
+                        self.terminate(format!("ret {} {}", return_type, value));
+                    }
+                    Some(node_to_return) => {
+                        self.statement(node_to_return)?;
+                        self.terminate(String::from("ret void"));
+                    }
+                    None if self.return_type != "void" => {
+                        let return_type = self.return_type;
+                        self.terminate(format!("ret {} {}", return_type, zero_value(return_type)))
+                    }
+                    None => self.terminate(String::from("ret void")),
+                }
+            }
+            NodeType::Break => {
+                let break_node = node.as_any().downcast_ref::<BreakNode>().unwrap();
+                let label = self.find_loop(break_node.label()).break_label.clone();
+                self.terminate(format!("br label %{}", label));
+            }
+            NodeType::Continue => {
+                let continue_node = node.as_any().downcast_ref::<ContinueNode>().unwrap();
+                let label = self.find_loop(continue_node.label()).continue_label.clone();
+                self.terminate(format!("br label %{}", label));
+            }
+            NodeType::Assert => self.assert(node.as_any().downcast_ref::<AssertNode>().unwrap())?,
+            NodeType::Memory => self.memory(node.as_any().downcast_ref::<MemoryNode>().unwrap())?,
+            NodeType::While => {
+                self.while_node(node.as_any().downcast_ref::<WhileNode>().unwrap())?
+            }
+            NodeType::For => self.for_node(node.as_any().downcast_ref::<ForNode>().unwrap())?,
+            NodeType::If => self.if_node(node.as_any().downcast_ref::<IfNode>().unwrap(), None)?,
+            NodeType::Import => {
+                self.statement(node.as_any().downcast_ref::<ImportNode>().unwrap().node())?
+            }
+            NodeType::FunctionDef
+            | NodeType::FunctionDecl
+            | NodeType::StaticDecl
+            | NodeType::StaticDef
+            | NodeType::ConstDef
+            | NodeType::StructDef
+            | NodeType::Extern
+            | NodeType::MacroDef
+            | NodeType::Ignored => {}
+            _ => {
+                self.expression(node)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn memory(&mut self, node: &MemoryNode) -> Result<(), Error> {
+        let destination = self.expression(node.destination())?;
+        let source = self.expression(node.source())?;
+        let count = self.expression(node.count())?;
+
+        match node.operation() {
+            MemoryOperation::Set => {
+                let value = self.cast_from_i64(source, "i8");
+                self.fill_memory(&destination, &value, &count);
+            }
+            operation => self.copy_memory(
+                &destination,
+                &source,
+                &count,
+                operation == MemoryOperation::Move,
+            ),
+        }
+        Ok(())
+    }
+
+    fn assert(&mut self, node: &AssertNode) -> Result<(), Error> {
+        let condition = self.expression(node.condition_node())?;
+
+        let label_failed = self.new_label("assert.failed");
+        let label_end = self.new_label("assert.end");
+        self.branch(&condition, &label_end, &label_failed);
+
+        self.start_block(label_failed);
+        let pos = node.pos_start();
+        self.trap(
+            format!(
+                "{}:{}:{}: Assertion failed!\n",
+                pos.file_name().display(),
+                pos.line() + 1,
+                pos.col() + 1
+            ),
+            1,
+        );
+
+        self.start_block(label_end);
+        Ok(())
+    }
+
+    /// Writes the message to stderr and exits with the given code, which ends the current block.
+    fn trap(&mut self, message: String, exit_code: u64) {
+        // there are no stable syscall numbers on Windows, so we can only exit there
+        if !matches!(self.target_object_type, TargetObjectType::Win64) {
+            let label = self.string_label(message.clone().into_bytes());
+            let address = self.cast_to_i64(label, "ptr");
+            let write = SyscallTable::Write
+                .code(self.target_object_type)
+                .to_string();
+            self.syscall_inst([write, String::from("2"), address, message.len().to_string()]);
+        }
+        let exit = SyscallTable::Exit.code(self.target_object_type).to_string();
+        self.syscall_inst([
+            exit,
+            exit_code.to_string(),
+            String::from("0"),
+            String::from("0"),
+        ]);
+        self.terminate(String::from("unreachable"));
+    }
+
+    fn check(&mut self, node: &CheckNode) -> Result<String, Error> {
+        let (value, failed) = match *node.check() {
+            Check::Overflow { size, signed } => {
+                let bin_op_node = node.node().as_any().downcast_ref::<BinOpNode>().unwrap();
+                let left = self.expression(bin_op_node.left_node())?;
+                let right = self.expression(bin_op_node.right_node())?;
+
+                let llvm_type = format!("i{}", size.get_size_in_bytes() as u32 * 8);
+                let left = self.cast_from_i64(left, &llvm_type);
+                let right = self.cast_from_i64(right, &llvm_type);
+
+                let intrinsic = format!(
+                    "@llvm.{}{}.with.overflow.{}",
+                    if signed { "s" } else { "u" },
+                    match bin_op_node.op_token().token_type() {
+                        TokenType::Plus => "add",
+                        TokenType::Minus => "sub",
+                        _ => "mul",
+                    },
+                    llvm_type
+                );
+                let result_type = format!("{{ {}, i1 }}", llvm_type);
+                self.overflow_intrinsics.insert(format!(
+                    "declare {} {}({}, {})",
+                    result_type, intrinsic, llvm_type, llvm_type
+                ));
+
+                let res = self.value(format!(
+                    "call {} {}({} {}, {} {})",
+                    result_type, intrinsic, llvm_type, left, llvm_type, right
+                ));
+                let result = self.value(format!("extractvalue {} {}, 0", result_type, res));
+                let overflow = self.value(format!("extractvalue {} {}, 1", result_type, res));
+                (self.cast_to_i64(result, &llvm_type), overflow)
+            }
+            Check::Bounds(length) => {
+                let index = self.expression(node.node())?;
+                let failed = self.value(format!("icmp uge i64 {}, {}", index, length));
+                (index, failed)
+            }
+            Check::SliceBounds | Check::SliceRange => {
+                let value = self.expression(node.node())?;
+                let length = self.expression(node.length())?;
+                let comparison = match *node.check() {
+                    Check::SliceBounds => "uge",
+                    _ => "ugt",
+                };
+                let failed = self.value(format!("icmp {} i64 {}, {}", comparison, value, length));
+                (value, failed)
+            }
+            Check::Null => {
+                let address = self.expression(node.node())?;
+                let failed = self.value(format!("icmp eq i64 {}, 0", address));
+                (address, failed)
+            }
+        };
+
+        let label_failed = self.new_label("check.failed");
+        let label_ok = self.new_label("check.ok");
+        self.terminate(format!(
+            "br i1 {}, label %{}, label %{}",
+            failed, label_failed, label_ok
+        ));
+
+        self.start_block(label_failed);
+        self.trap(node.failure_message(), CHECK_FAILED_EXIT_CODE);
+
+        self.start_block(label_ok);
+        Ok(value)
+    }
+
+    /// Returns the loop `break` or `continue` with the label jumps out of. Without a label, it's
+    /// the innermost loop.
+    fn find_loop(&self, label: &Option<String>) -> &Loop {
+        match label {
+            Some(label) => self
+                .loops
+                .iter()
+                .rev()
+                .find(|l| l.label.as_ref() == Some(label))
+                .unwrap(),
+            None => self.loops.last().unwrap(),
+        }
+    }
+
+    fn while_node(&mut self, node: &WhileNode) -> Result<(), Error> {
+        let label_condition = self.new_label("while.condition");
+        let label_body = self.new_label("while.body");
+        let label_end = self.new_label("while.end");
+
+        self.start_block(label_condition.clone());
+        let condition = self.expression(node.condition_node())?;
+        self.branch(&condition, &label_body, &label_end);
+
+        self.start_block(label_body);
+        self.loops.push(Loop {
+            label: node.label().clone(),
+            continue_label: label_condition.clone(),
+            break_label: label_end.clone(),
+        });
+        self.block(node.body_node(), None)?;
+        self.loops.pop();
+        if !self.terminated {
+            self.terminate(format!("br label %{}", label_condition));
+        }
+
+        self.start_block(label_end);
+        Ok(())
+    }
+
+    fn for_node(&mut self, node: &ForNode) -> Result<(), Error> {
+        // the variable declared in the init statement is only visible in the loop
+        self.scopes.push(HashMap::new());
+        self.statement(node.init_stmt())?;
+
+        let label_condition = self.new_label("for.condition");
+        let label_body = self.new_label("for.body");
+        let label_next = self.new_label("for.next");
+        let label_end = self.new_label("for.end");
+
+        self.start_block(label_condition.clone());
+        let condition = self.expression(node.condition())?;
+        self.branch(&condition, &label_body, &label_end);
+
+        self.start_block(label_body);
+        self.loops.push(Loop {
+            label: node.label().clone(),
+            continue_label: label_next.clone(),
+            break_label: label_end.clone(),
+        });
+        self.block(node.body(), None)?;
+        self.loops.pop();
+
+        self.start_block(label_next);
+        self.statement(node.next_expr())?;
+        if !self.terminated {
+            self.terminate(format!("br label %{}", label_condition));
+        }
+
+        self.start_block(label_end);
+        self.scopes.pop();
+        Ok(())
+    }
+
+    /// Generates an `if`. If `result` is given, the value of the taken branch is stored at that
+    /// address.
+    fn if_node(&mut self, node: &IfNode, result: Option<&str>) -> Result<(), Error> {
+        let label_end = self.new_label("if.end");
+
+        for case in node.cases() {
+            let label_then = self.new_label("if.then");
+            let label_else = self.new_label("if.else");
+
+            let condition = self.expression(case.condition())?;
+            self.branch(&condition, &label_then, &label_else);
+
+            self.start_block(label_then);
+            self.block(case.statements(), result)?;
+            if !self.terminated {
+                self.terminate(format!("br label %{}", label_end));
+            }
+
+            self.start_block(label_else);
+        }
+
+        if let Some(else_case) = node.else_case() {
+            self.block(else_case.statements(), result)?;
+        }
+
+        self.start_block(label_end);
+        Ok(())
+    }
+
+    // endregion
+
+    // region Expressions
+
+    fn expression(&mut self, node: &Box<dyn Node>) -> Result<String, Error> {
+        match node.node_type() {
+            NodeType::Number | NodeType::Char | NodeType::FloatingPoint => {
+                let bits = constant::evaluate(node).and_then(|value| value.bits());
+                Ok((bits.unwrap() as i64).to_string())
+            }
+            NodeType::String => {
+                let string_node = node.as_any().downcast_ref::<StringNode>().unwrap();
+                let label = self.string_label(unescape(&string_node.get_string()));
+                Ok(self.cast_to_i64(label, "ptr"))
+            }
+            NodeType::SizeOf => Ok(node
+                .as_any()
+                .downcast_ref::<SizeOfNode>()
+                .unwrap()
+                .value_type()
+                .get_size()
+                .get_size_in_bytes()
+                .to_string()),
+            NodeType::Array => self.array(node.as_any().downcast_ref::<ArrayNode>().unwrap()),
+            NodeType::StackAllocationNode => {
+                let size = *node
+                    .as_any()
+                    .downcast_ref::<StackAllocationNode>()
+                    .unwrap()
+                    .size_in_bytes();
+
+                let address = self.stack_buffer(size);
+                self.zero_memory(&address, size);
+                Ok(address)
+            }
+            NodeType::Copy => {
+                let copy_node = node.as_any().downcast_ref::<CopyNode>().unwrap();
+                let destination = self.expression(copy_node.destination())?;
+                let source = self.expression(copy_node.source())?;
+
+                let size = copy_node.size_in_bytes().to_string();
+                self.copy_memory(&destination, &source, &size, false);
+                Ok(destination)
+            }
+            NodeType::BinOp => self.bin_op(node.as_any().downcast_ref::<BinOpNode>().unwrap()),
+            NodeType::Check => self.check(node.as_any().downcast_ref::<CheckNode>().unwrap()),
+            NodeType::Slice => self.slice(node.as_any().downcast_ref::<SliceNode>().unwrap()),
+            NodeType::FloatingBinOp => {
+                self.floating_bin_op(node.as_any().downcast_ref::<FloatingBinOpNode>().unwrap())
+            }
+            NodeType::UnaryOp => {
+                let unary_op_node = node.as_any().downcast_ref::<UnaryOpNode>().unwrap();
+                let value = self.expression(unary_op_node.node())?;
+
+                Ok(match unary_op_node.op_token().token_type() {
+                    TokenType::Minus if self.is_floating_point(unary_op_node.node()) => {
+                        let value = self.as_double(value);
+                        let negated = self.value(format!("fneg double {}", value));
+                        self.cast_to_i64(negated, "double")
+                    }
+                    TokenType::Minus => self.value(format!("sub i64 0, {}", value)),
+                    TokenType::Not => {
+                        let condition = self.value(format!("icmp eq i64 {}, 0", value));
+                        self.bool_to_i64(condition)
+                    }
+                    TokenType::BitNot => self.value(format!("xor i64 {}, -1", value)),
+                    _ => value,
+                })
+            }
+            NodeType::VarTypedAccess => {
+                let var_access_node = node.as_any().downcast_ref::<VarTypedAccessNode>().unwrap();
+                let variable = self.get_var(var_access_node.var_name());
+                Ok(self.load_var(&variable))
+            }
+            NodeType::AddressOf => {
+                let address_of_node = node.as_any().downcast_ref::<AddressOfNode>().unwrap();
+                let variable = self.get_var(address_of_node.var_name());
+                Ok(self.cast_to_i64(variable.address, "ptr"))
+            }
+            NodeType::Call => self.call(node.as_any().downcast_ref::<CallNode>().unwrap()),
+            NodeType::Syscall => {
+                let syscall_node = node.as_any().downcast_ref::<SyscallNode>().unwrap();
+                let args = syscall_node.args();
+                let code = self.expression(&args[0])?;
+                let arg1 = self.expression(&args[1])?;
+                let arg2 = self.expression(&args[2])?;
+                let arg3 = self.expression(&args[3])?;
+                Ok(self.syscall_inst([code, arg1, arg2, arg3]))
+            }
+            NodeType::Cast => {
+                let cast_node = node.as_any().downcast_ref::<CastNode>().unwrap();
+                let value = self.expression(cast_node.node())?;
+
+                let size = cast_node.cast_type().get_size();
+                if size == ValueSize::Qword {
+                    return Ok(value);
+                }
+                let truncated = self.cast_from_i64(value, integer_type(size));
+                Ok(self.cast_to_i64(truncated, integer_type(size)))
+            }
+            NodeType::F64ToU64 => {
+                let value =
+                    self.expression(node.as_any().downcast_ref::<F64ToU64Node>().unwrap().node())?;
+                let value = self.as_double(value);
+                Ok(self.value(format!("fptosi double {} to i64", value)))
+            }
+            NodeType::U64ToF64 => {
+                let value =
+                    self.expression(node.as_any().downcast_ref::<U64ToF64Node>().unwrap().node())?;
+                let converted = self.value(format!("sitofp i64 {} to double", value));
+                Ok(self.cast_to_i64(converted, "double"))
+            }
+            NodeType::ReadBytes => {
+                let read_bytes_node = node.as_any().downcast_ref::<ReadBytesNode>().unwrap();
+                let address = self.expression(read_bytes_node.node())?;
+
+                let llvm_type = integer_type(*read_bytes_node.bytes());
+                let ptr = self.cast_from_i64(address, "ptr");
+                let value = self.value(format!("load {}, ptr {}, align 1", llvm_type, ptr));
+                Ok(self.cast_to_i64(value, llvm_type))
+            }
+            NodeType::Offset => {
+                let offset_node = node.as_any().downcast_ref::<OffsetNode>().unwrap();
+                let address = self.expression(offset_node.node())?;
+                let offset = self.expression(offset_node.offset_node())?;
+
+                let offset = self.value(format!(
+                    "mul i64 {}, {}",
+                    offset,
+                    offset_node.pointee_type().size_in_bytes()
+                ));
+                Ok(self.value(format!("add i64 {}, {}", address, offset)))
+            }
+            NodeType::Assembly => Err(Self::unsupported(
+                node.as_ref(),
+                "Inline assembly is not supported by the LLVM backend!",
+            )),
+            // blocks and `if`s with a value store it in a stack slot in their last statement
+            NodeType::Statements
+                if node
+                    .as_any()
+                    .downcast_ref::<StatementsNode>()
+                    .unwrap()
+                    .has_value() =>
+            {
+                let result = self.stack_buffer(8);
+                self.block(node, Some(&result))?;
+                Ok(self.load_qword(&result))
+            }
+            NodeType::If if node.as_any().downcast_ref::<IfNode>().unwrap().has_value() => {
+                let result = self.stack_buffer(8);
+                self.if_node(
+                    node.as_any().downcast_ref::<IfNode>().unwrap(),
+                    Some(&result),
+                )?;
+                Ok(self.load_qword(&result))
+            }
+            _ => {
+                self.statement(node)?;
+                Ok(String::from("0"))
+            }
+        }
+    }
+
+    fn array(&mut self, node: &ArrayNode) -> Result<String, Error> {
+        let element_size = node.element_type().get_size();
+        let size = *node.size() as u64 * element_size.get_size_in_bytes() as u64;
+        let address = self.stack_buffer(size);
+
+        if node.element_nodes().is_empty() {
+            self.zero_memory(&address, size);
+        }
+
+        // like the assembly backend, evaluate the elements from last to first
+        for (i, element) in node.element_nodes().iter().enumerate().rev() {
+            let value = self.expression(element)?;
+
+            let element_address = self.value(format!(
+                "add i64 {}, {}",
+                address,
+                i * element_size.get_size_in_bytes() as usize
+            ));
+            let ptr = self.cast_from_i64(element_address, "ptr");
+            let value = self.cast_from_i64(value, integer_type(element_size));
+            self.inst(format!(
+                "store {} {}, ptr {}, align 1",
+                integer_type(element_size),
+                value,
+                ptr
+            ));
+        }
+
+        Ok(address)
+    }
+
+    fn slice(&mut self, node: &SliceNode) -> Result<String, Error> {
+        let slice = self.expression(node.node())?;
+        let start = self.expression(node.start())?;
+        let end = self.expression(node.end())?;
+
+        let pointer = self.load_qword(&slice);
+        let offset = self.value(format!("mul i64 {}, {}", start, node.element_size()));
+        let pointer = self.value(format!("add i64 {}, {}", pointer, offset));
+        let length = self.value(format!("sub i64 {}, {}", end, start));
+
+        let address = self.stack_buffer(16);
+        self.store_qword(&address, pointer);
+        let length_address = self.value(format!("add i64 {}, {}", address, SLICE_LENGTH_OFFSET));
+        self.store_qword(&length_address, length);
+
+        Ok(address)
+    }
+
+    fn bin_op(&mut self, node: &BinOpNode) -> Result<String, Error> {
+        let op = node.op_token().token_type();
+
+        if op == TokenType::And || op == TokenType::Or {
+            let label_right = self.new_label("logic.right");
+            let label_end = self.new_label("logic.end");
+
+            let left = self.expression(node.left_node())?;
+            let left_block = self.current_block.clone();
+            if op == TokenType::And {
+                self.branch(&left, &label_right, &label_end);
+            } else {
+                self.branch(&left, &label_end, &label_right);
+            }
+
+            self.start_block(label_right);
+            let right = self.expression(node.right_node())?;
+            let right = self.value(format!("icmp ne i64 {}, 0", right));
+            let right_block = self.current_block.clone();
+            self.terminate(format!("br label %{}", label_end));
+
+            self.start_block(label_end);
+            let res = self.value(format!(
+                "phi i1 [ {}, %{} ], [ {}, %{} ]",
+                op == TokenType::Or,
+                left_block,
+                right,
+                right_block
+            ));
+            return Ok(self.bool_to_i64(res));
+        }
+
+        let left = self.expression(node.left_node())?;
+        let right = self.expression(node.right_node())?;
+
+        let comparison = match op {
+            TokenType::Ee => Some("eq"),
+            TokenType::Ne => Some("ne"),
+            TokenType::Lt => Some("slt"),
+            TokenType::Gt => Some("sgt"),
+            TokenType::Lte => Some("sle"),
+            TokenType::Gte => Some("sge"),
+            _ => None,
+        };
+        if let Some(comparison) = comparison {
+            let res = self.value(format!("icmp {} i64 {}, {}", comparison, left, right));
+            return Ok(self.bool_to_i64(res));
+        }
+
+        let instruction = match op {
+            TokenType::Plus => "add",
+            TokenType::Minus => "sub",
+            TokenType::Mul => "mul",
+            TokenType::Div => "sdiv",
+            TokenType::Modulo => "srem",
+            TokenType::BitAnd => "and",
+            TokenType::BitOr => "or",
+            TokenType::BitXor => "xor",
+            TokenType::BitShl | TokenType::BitShr => {
+                // like the shift instructions, only the lower 6 bits of the count are used
+                let count = self.value(format!("and i64 {}, 63", right));
+                let instruction = if op == TokenType::BitShl {
+                    "shl"
+                } else {
+                    "ashr"
+                };
+                return Ok(self.value(format!("{} i64 {}, {}", instruction, left, count)));
+            }
+            _ => {
+                return Err(Self::unsupported(
+                    node,
+                    format!(
+                        "Binary operation '{:?}' is not supported by the LLVM backend!",
+                        op
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+
+        Ok(self.value(format!("{} i64 {}, {}", instruction, left, right)))
+    }
+
+    fn floating_bin_op(&mut self, node: &FloatingBinOpNode) -> Result<String, Error> {
+        let left = self.expression(node.left_node())?;
+        let right = self.expression(node.right_node())?;
+        let op = node.op_token().token_type();
+
+        let bit_operation = match op {
+            TokenType::BitAnd => Some("and"),
+            TokenType::BitOr => Some("or"),
+            TokenType::BitXor => Some("xor"),
+            _ => None,
+        };
+        if let Some(instruction) = bit_operation {
+            return Ok(self.value(format!("{} i64 {}, {}", instruction, left, right)));
+        }
+
+        let l = self.as_double(left.clone());
+        let r = self.as_double(right);
+
+        if op == TokenType::BitShl || op == TokenType::BitShr {
+            // the bits are shifted by the count converted to an integer
+            let count = self.value(format!("fptosi double {} to i64", r));
+            let count = self.value(format!("and i64 {}, 63", count));
+            let instruction = if op == TokenType::BitShl {
+                "shl"
+            } else {
+                "lshr"
+            };
+            return Ok(self.value(format!("{} i64 {}, {}", instruction, left, count)));
+        }
+
+        let comparison = match op {
+            TokenType::Ee => Some("oeq"),
+            TokenType::Ne => Some("une"),
+            TokenType::Lt => Some("olt"),
+            TokenType::Gt => Some("ogt"),
+            TokenType::Lte => Some("ole"),
+            TokenType::Gte => Some("oge"),
+            _ => None,
+        };
+        if let Some(comparison) = comparison {
+            let res = self.value(format!("fcmp {} double {}, {}", comparison, l, r));
+            return Ok(self.bool_to_i64(res));
+        }
+
+        let instruction = match op {
+            TokenType::Plus => "fadd",
+            TokenType::Minus => "fsub",
+            TokenType::Mul => "fmul",
+            TokenType::Div => "fdiv",
+            TokenType::Modulo => "frem",
+            _ => {
+                return Err(Self::unsupported(
+                    node,
+                    format!(
+                        "Binary operation '{:?}' is not supported by the LLVM backend!",
+                        op
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+
+        let res = self.value(format!("{} double {}, {}", instruction, l, r));
+        Ok(self.cast_to_i64(res, "double"))
+    }
+
+    /// Calls the function with the arguments converted to its signature. The arguments are
+    /// evaluated from last to first, like in the assembly backend.
+    fn call(&mut self, node: &CallNode) -> Result<String, Error> {
+        let mut args: Vec<String> = vec![];
+        for (i, arg) in node.arg_nodes().iter().enumerate().rev() {
+            let value = self.expression(arg)?;
+
+            if node.is_slice_arg(i) {
+                let length_address =
+                    self.value(format!("add i64 {}, {}", value, SLICE_LENGTH_OFFSET));
+                let length = self.load_qword(&length_address);
+                args.push(length);
+                let pointer = self.load_qword(&value);
+                args.push(pointer);
+            } else {
+                args.push(value);
+            }
+        }
+        args.reverse();
+        let passed_arg_types = node.passed_arg_types();
+
+        let signature = &self.functions[node.func_to_call()];
+        let arg_types = signature.arg_types.clone();
+        let return_type = signature.return_type;
+        // calls to variadic functions need the function type instead of only the return type
+        let function_type = if signature.is_variadic {
+            format!("{} ({})", return_type, signature.parameter_list())
+        } else {
+            return_type.to_string()
+        };
+
+        let mut typed_args: Vec<String> = vec![];
+        for (i, arg) in args.into_iter().enumerate() {
+            let typed_arg = match arg_types.get(i) {
+                Some(arg_type) => {
+                    let arg = self.cast_from_i64(arg, arg_type);
+                    format!("{} {}", arg_type, arg)
+                }
+                None => self.variadic_arg(arg, passed_arg_types[i].as_ref()),
+            };
+            typed_args.push(typed_arg);
+        }
+
+        let call = format!(
+            "call {} @{}({})",
+            function_type,
+            self.function_name(node.func_to_call()),
+            typed_args.join(", ")
+        );
+
+        if return_type == "void" {
+            self.inst(call);
+            return Ok(String::from("0"));
+        }
+
+        let res = self.value(call);
+        Ok(self.cast_to_i64(res, return_type))
+    }
+
+    /// Converts an argument passed to the `...` of a variadic function, applying C's default
+    /// argument promotions, and returns it together with its type.
+    fn variadic_arg(&mut self, value: String, value_type: &dyn ValueType) -> String {
+        let llvm_type = llvm_type(value_type);
+        let value = self.cast_from_i64(value, llvm_type);
+        match llvm_type {
+            "i8" | "i16" => {
+                let extension = match value_type.value_type() {
+                    ValueTypes::I8 | ValueTypes::I16 => "sext",
+                    _ => "zext",
+                };
+                let value = self.value(format!("{} {} {} to i32", extension, llvm_type, value));
+                format!("i32 {}", value)
+            }
+            _ => format!("{} {}", llvm_type, value),
+        }
+    }
+
+    /// Does a syscall with inline assembly, passing the arguments in the registers of the target.
+    fn syscall_inst(&mut self, args: [String; 4]) -> String {
+        let [code, arg1, arg2, arg3] = args;
+        let (instruction, registers) = match self.target_object_type {
+            TargetObjectType::Aarch64Linux => {
+                ("svc #0", "={x0},{x8},{x0},{x1},{x2},{x3},~{memory}")
+            }
+            _ => (
+                "syscall",
+                "={rax},{rax},{rdi},{rsi},{rdx},{r10},~{rcx},~{r11},~{memory}",
+            ),
+        };
+
+        self.value(format!(
+            "call i64 asm sideeffect \"{}\", \"{}\"(i64 {}, i64 {}, i64 {}, i64 {}, i64 0)",
+            instruction, registers, code, arg1, arg2, arg3
+        ))
+    }
+
+    // endregion
+
+    // region Entry points
+
+    /// Generates the `main` function called by the C runtime.
+    fn entry_point(&mut self) -> String {
+        self.lines.clear();
+        self.allocas.clear();
+        self.next_id = 0;
+        self.terminated = false;
+
+        let argc = self.value(String::from("zext i32 %argc to i64"));
+        let arg0 = self.value(String::from("load ptr, ptr %argv"));
+        let arg0 = self.cast_to_i64(arg0, "ptr");
+
+        let (arg_types, return_type) = match self.functions.get("main") {
+            Some(main) => (main.arg_types.clone(), main.return_type),
+            None => (vec![], "void"),
+        };
+
+        let mut args: Vec<String> = vec![];
+        for (arg, arg_type) in [argc, arg0].into_iter().zip(arg_types) {
+            let arg = self.cast_from_i64(arg, arg_type);
+            args.push(format!("{} {}", arg_type, arg));
+        }
+
+        let call = format!("call {} @umber_main({})", return_type, args.join(", "));
+        if return_type == "void" {
+            self.inst(call);
+            self.terminate(String::from("ret i32 0"));
+        } else {
+            let res = self.value(call);
+            let res = self.cast_to_i64(res, return_type);
+            let code = self.value(format!("and i64 {}, 255", res));
+            let code = self.cast_from_i64(code, "i32");
+            self.terminate(format!("ret i32 {}", code));
+        }
+
+        let mut definition = String::from("define i32 @main(i32 %argc, ptr %argv) {\nentry:\n");
+        for line in &self.lines {
+            writeln!(definition, "{}", line).unwrap();
+        }
+        writeln!(definition, "}}").unwrap();
+        definition
+    }
+
+    /// Compiles the given node to an LLVM module. Unless `no_entry` is set, a `main` function for
+    /// the C runtime calls the Umber `main` function with the argument count and a pointer to the
+    /// first argument, like `_start` does in the assembly backend.
+    pub fn compile_to_str(
+        &mut self,
+        node: &Box<dyn Node>,
+        no_entry: bool,
+    ) -> Result<String, Error> {
+        self.rename_main = !no_entry;
+        self.declare(node)?;
+        self.top_level(node)?;
+
+        let mut res = String::new();
+        writeln!(
+            res,
+            "; Generated by the Umber compiler\ntarget triple = \"{}\"\n",
+            self.target_triple()
+        )
+        .unwrap();
+
+        for declaration in &self.declarations {
+            writeln!(res, "{}", declaration).unwrap();
+        }
+        if self.uses_memset {
+            writeln!(
+                res,
+                "declare void @llvm.memset.p0.i64(ptr, i8, i64, i1 immarg)"
+            )
+            .unwrap();
+        }
+        if self.uses_memcpy {
+            writeln!(
+                res,
+                "declare void @llvm.memcpy.p0.p0.i64(ptr, ptr, i64, i1 immarg)"
+            )
+            .unwrap();
+        }
+        if self.uses_memmove {
+            writeln!(
+                res,
+                "declare void @llvm.memmove.p0.p0.i64(ptr, ptr, i64, i1 immarg)"
+            )
+            .unwrap();
+        }
+        for declaration in &self.overflow_intrinsics {
+            writeln!(res, "{}", declaration).unwrap();
+        }
+
+        for definition in &self.definitions {
+            write!(res, "\n{}", definition).unwrap();
+        }
+
+        if !no_entry {
+            write!(res, "\n{}", self.entry_point()).unwrap();
+        }
+
+        Ok(res)
+    }
+
+    // endregion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::semantics::{RuntimeCheck, Validator};
+    use std::path::PathBuf;
+
+    fn generate(source: &str, no_entry: bool) -> Result<String, Error> {
+        generate_with_checks(source, no_entry, &[])
+    }
+
+    fn generate_with_checks(
+        source: &str,
+        no_entry: bool,
+        checks: &[RuntimeCheck],
+    ) -> Result<String, Error> {
+        let path = PathBuf::from("/tmp/llvm_compiler_test.ub");
+        let tokens = Lexer::new(path, source.to_string()).make_tokens().unwrap();
+
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+        let ast_root = Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+            .parse()
+            .unwrap();
+
+        let validation_res = Validator::new_with_checks(checks).validate(&ast_root);
+        assert!(
+            validation_res.error().is_none(),
+            "{:?}",
+            validation_res.error()
+        );
+
+        LlvmCompiler::new(TargetObjectType::X86_64)
+            .compile_to_str(validation_res.node().as_ref().unwrap(), no_entry)
+    }
+
+    #[test]
+    fn test_variadic_call() {
+        let ir = generate(
+            "extern fun printf(fmt: char *, ...): i32;
+fun main(): u64 {
+    printf(\"%d %u %f\" as char *, 255 as i8, 7, 2.5);
+    return 0;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(ir.contains("declare i32 @printf(ptr, ...)"));
+        assert!(ir.contains("sext i8 "));
+        assert!(ir.contains("call i32 (ptr, ...) @printf(ptr "));
+        assert!(ir.contains(", i64 7, double "));
+    }
+
+    #[test]
+    fn test_generate_llvm() {
+        let ir = generate(
+            "struct pair { a: u8, b: u64 *, c: f64 };
+static mut counter: u16 = 3 as u16;
+extern fun puts(s: char *): u32;
+
+fun bump(by: u64): u64 {
+    counter = counter + by as u16;
+    return counter as u64;
+};
+
+fun main(argc: u64, argv: char *): u64 {
+    puts(\"hi\" as char *);
+    if argc > 1 && bump(2) / 2 == 2 {
+        return 1;
+    };
+    return 0;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(ir.contains("target triple = \"x86_64-unknown-linux-gnu\""));
+        assert!(ir.contains("%struct.pair = type <{ i8, ptr, double }>"));
+        assert!(ir.contains("@ST_counter = internal global i16 3"));
+        assert!(ir.contains("declare i32 @puts(ptr)"));
+        assert!(ir.contains("@.str.0 = private global [3 x i8] c\"hi\\00\""));
+        assert!(ir.contains("define i64 @bump(i64 %by.arg) {"));
+        assert!(ir.contains("define i64 @umber_main(i64 %argc.arg, ptr %argv.arg) {"));
+        assert!(ir.contains("define i32 @main(i32 %argc, ptr %argv) {"));
+        // division and comparisons are signed, '&&' short circuits
+        assert!(ir.contains("sdiv i64"));
+        assert!(ir.contains("icmp sgt i64"));
+        assert!(ir.contains("phi i1 [ false, %entry ]"));
+
+        let library = generate("fun main(): u64 { return 0; };", true).unwrap();
+        assert!(library.contains("define i64 @main() {"));
+    }
+
+    #[test]
+    fn test_slices() {
+        let ir = generate(
+            "extern fun write_all(s: str): void;
+fun first(s: str): char { return *s[0]; };
+fun main(): u64 {
+    let text: str = \"hello\" as str;
+    write_all(text);
+    return first(text[1..3]) as u64;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(ir.contains("declare void @write_all(ptr, i64)"));
+        assert!(ir.contains("define i8 @first(ptr %s.ptr, i64 %s.len) {"));
+        assert!(ir.contains("store i64 %s.len, ptr "));
+        assert!(ir.contains(" = sub i64 3, 1\n"));
+    }
+
+    #[test]
+    fn test_memory_intrinsics() {
+        let ir = generate(
+            "fun main(argc: u64, argv: char *): u64 {
+    let buf: [u8; 256] = [<u8>; 256];
+    let p: u8 *mut = buf as u8 *mut;
+    memset[p, 7 as u8, argc];
+    memcpy[p + 64, p, 16];
+    memmove[p + 1, p, argc];
+    return 0;
+};",
+            false,
+        )
+        .unwrap();
+
+        assert!(ir.contains("declare void @llvm.memmove.p0.p0.i64(ptr, ptr, i64, i1 immarg)"));
+        assert!(ir.contains("i8 0, i64 256, i1 false)"));
+        assert!(ir.contains(", i64 16, i1 false)"));
+        assert!(ir.contains("call void @llvm.memmove.p0.p0.i64(ptr align 1 "));
+    }
+
+    #[test]
+    fn test_static_initializers() {
+        let ir = generate(
+            "static half: f64 = 1.0 / 2.0;
+static mut values: [u16; 2] = [<u16> 1 as u16, 2 as u16];
+static p: [u64; 2] = [<u64>; 2];
+fun main(): u64 { return 0; };",
+            false,
+        )
+        .unwrap();
+
+        assert!(ir.contains("@ST_half = internal constant double 0x3FE0000000000000"));
+        assert!(ir.contains("@.data.0 = private global [2 x i16] [i16 1, i16 2]"));
+        assert!(ir.contains("@ST_values = internal global ptr @.data.0"));
+        assert!(ir.contains("@.data.1 = private global [16 x i8] zeroinitializer"));
+        assert!(ir.contains("@ST_p = internal constant ptr @.data.1"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = generate(
+            "fun one(): u64 { return 1; };
+const x: u64 = one();
+fun main(): u64 { return x; };",
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.details(),
+            "The value of 'x' has to be a constant for the LLVM backend!"
+        );
+
+        let err = generate("fun f(): void { asm[\"nop\"]; return; };", true).unwrap_err();
+        assert_eq!(
+            err.details(),
+            "Inline assembly is not supported by the LLVM backend!"
+        );
+    }
+
+    #[test]
+    fn test_runtime_checks() {
+        let ir = generate_with_checks(
+            "fun main(argc: u64, argv: char *): u64 {
+    let values: [u8; 3] = [<u8>; 3];
+    let small: i8 = (argc as i8) * (3 as i8);
+    return ((*values[argc]) as u64) + argc;
+};",
+            false,
+            &[
+                RuntimeCheck::Bounds,
+                RuntimeCheck::Null,
+                RuntimeCheck::Overflow,
+            ],
+        )
+        .unwrap();
+
+        assert!(ir.contains("declare { i8, i1 } @llvm.smul.with.overflow.i8(i8, i8)\n"));
+        assert!(ir.contains("declare { i64, i1 } @llvm.uadd.with.overflow.i64(i64, i64)\n"));
+        assert!(ir.contains(" = icmp uge i64 %.t23, 3\n  br i1 %.t24, label %check.failed."));
+        assert!(ir.contains(" = icmp eq i64 %.t31, 0\n  br i1 %.t32, label %check.failed."));
+        assert!(ir.contains("c\"/tmp/llvm_compiler_test.ub:3:22: Arithmetic overflow!\\0A\\00\""));
+    }
+
+    #[test]
+    fn test_slice_runtime_checks() {
+        let ir = generate_with_checks(
+            "fun main(argc: u64, argv: char *): u64 {
+    let values: [u64; 4] = [<u64>; 4];
+    let part: []u64 = values[1..argc];
+    return *part[argc];
+};",
+            false,
+            &[RuntimeCheck::Bounds],
+        )
+        .unwrap();
+
+        // the end of the sub-slice is compared to the length, then the start to the end
+        assert!(ir.contains(" = icmp ugt i64 %.t18, %.t23\n  br i1 %.t24, label %check.failed."));
+        assert!(ir.contains(" = icmp ugt i64 %.t32, %.t33\n  br i1 %.t34, label %check.failed."));
+        assert!(ir.contains(" = icmp uge i64 %.t66, %.t71\n  br i1 %.t72, label %check.failed."));
+        assert!(
+            ir.contains("c\"/tmp/llvm_compiler_test.ub:3:29: Slice range out of bounds!\\0A\\00\"")
+        );
+    }
+}
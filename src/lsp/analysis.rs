@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::panic;
+use std::path::{Path, PathBuf};
+
+use crate::error;
+use crate::error::Error;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::position::Position;
+use crate::semantic_info::{Declaration, DeclarationKind, SemanticInfo};
+use crate::semantics::Validator;
+use crate::values::value_type::pointer_type::PointerType;
+use crate::values::value_type::struct_type::StructType;
+use crate::values::value_type::{ValueType, ValueTypes};
+
+/// The result of running the lexer, parser and validator over a single document.
+pub struct Analysis {
+    error: Option<Error>,
+    info: Option<SemanticInfo>,
+}
+
+impl Analysis {
+    pub fn error(&self) -> &Option<Error> {
+        &self.error
+    }
+    pub fn info(&self) -> &Option<SemanticInfo> {
+        &self.info
+    }
+    pub fn take_info(&mut self) -> Option<SemanticInfo> {
+        self.info.take()
+    }
+}
+
+pub fn analyze(path: &Path, text: &str, include_paths: &Vec<String>) -> Analysis {
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let mut lexer = Lexer::new(path.to_path_buf(), text.to_string());
+        let tokens = match lexer.make_tokens() {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                return Analysis {
+                    error: Some(err),
+                    info: None,
+                }
+            }
+        };
+
+        let mut macros = HashMap::new();
+        let mut already_included: Vec<PathBuf> = vec![];
+
+        let mut parser = Parser::new(tokens, include_paths, &mut macros, &mut already_included);
+        let ast_root = match parser.parse() {
+            Ok(ast_root) => ast_root,
+            Err(err) => {
+                return Analysis {
+                    error: Some(err),
+                    info: None,
+                }
+            }
+        };
+
+        let mut validator = Validator::new_with_info();
+        let validation_res = validator.validate(&ast_root);
+
+        Analysis {
+            error: validation_res.error().clone(),
+            info: validator.info().clone(),
+        }
+    }));
+
+    result.unwrap_or_else(|_| Analysis {
+        error: Some(error::semantic_error(
+            Position::new(path.to_path_buf()),
+            Position::new(path.to_path_buf()),
+            "Internal compiler error while analyzing this file!",
+        )),
+        info: None,
+    })
+}
+
+/// Returns the position that should be reported for the given error in the given file, as well as
+/// the full message (including the messages of all parent errors).
+/// The innermost error located in the file is preferred, as it is usually the most precise one.
+pub fn error_location(err: &Error, path: &Path) -> (Position, Position, String) {
+    let mut location = (err.pos_start().clone(), err.pos_end().clone());
+    let mut messages: Vec<String> = vec![];
+
+    let mut current = Some(err);
+    while let Some(e) = current {
+        messages.push(format!("{}: {}", e.error_name(), e.details()));
+
+        if e.pos_start().file_name() == path {
+            location = (e.pos_start().clone(), e.pos_end().clone());
+        }
+
+        current = e.parent().as_deref();
+    }
+
+    (location.0, location.1, messages.join("\n"))
+}
+
+fn format_declaration(declaration: &Declaration) -> String {
+    match (declaration.kind(), declaration.value_type()) {
+        (DeclarationKind::Struct, _) | (_, None) => format!("struct {}", declaration.name()),
+        (DeclarationKind::Static, Some(t)) => format!("static {}: {}", declaration.name(), t),
+        (_, Some(t)) => format!("{}: {}", declaration.name(), t),
+    }
+}
+
+/// Converts a column in characters, as used by the lexer, to UTF-16 code units, the default
+/// position encoding of the protocol. Columns past the end of the line are kept as they are.
+pub fn utf16_col(line_text: &str, col: usize) -> usize {
+    let chars = line_text.chars().count();
+    let units: usize = line_text.chars().take(col).map(char::len_utf16).sum();
+    units + col.saturating_sub(chars)
+}
+
+/// Converts a column in UTF-16 code units back to characters. A column inside of a surrogate pair
+/// is rounded up to the character after it.
+pub fn char_col(line_text: &str, utf16_col: usize) -> usize {
+    let mut units = 0;
+    for (col, c) in line_text.chars().enumerate() {
+        if units >= utf16_col {
+            return col;
+        }
+        units += c.len_utf16();
+    }
+    line_text.chars().count() + utf16_col.saturating_sub(units)
+}
+
+/// Returns a short description of the symbol or expression at the given (zero based) line and
+/// column.
+pub fn hover(info: &SemanticInfo, path: &Path, line: usize, col: usize) -> Option<String> {
+    if let Some(reference) = info.reference_at(path, line, col) {
+        return Some(format_declaration(reference.definition()));
+    }
+
+    info.expression_types()
+        .iter()
+        .filter(|(pos, _)| pos.file_name() == path && *pos.line() == line && *pos.col() <= col)
+        .max_by_key(|(pos, _)| *pos.col())
+        .map(|(_, value_type)| value_type.to_string())
+}
+
+pub fn definition(info: &SemanticInfo, path: &Path, line: usize, col: usize) -> Option<Position> {
+    info.reference_at(path, line, col)
+        .map(|reference| reference.definition().pos().clone())
+}
+
+fn struct_name_of(value_type: &dyn ValueType) -> Option<String> {
+    match value_type.value_type() {
+        ValueTypes::Struct => Some(
+            value_type
+                .as_any()
+                .downcast_ref::<StructType>()
+                .unwrap()
+                .name()
+                .to_string(),
+        ),
+        ValueTypes::Pointer => struct_name_of(
+            value_type
+                .as_any()
+                .downcast_ref::<PointerType>()
+                .unwrap()
+                .pointee_type()
+                .as_ref(),
+        ),
+        _ => None,
+    }
+}
+
+/// Completes the fields of a structure after a `.`, e.g. `a.b.` where `a` is a variable or static
+/// of (pointer to) structure type.
+pub fn complete_fields<'a>(
+    info: &'a SemanticInfo,
+    path: &Path,
+    line_text: &str,
+    line: usize,
+) -> Vec<&'a Declaration> {
+    let before_cursor = line_text.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+    if !before_cursor.ends_with('.') {
+        return vec![];
+    }
+
+    let chain_start = before_cursor
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let chain = &before_cursor[chain_start..before_cursor.len() - 1];
+
+    let mut parts = chain.split('.');
+    let root = match parts.next() {
+        Some(root) if !root.is_empty() => root,
+        _ => return vec![],
+    };
+
+    // prefer the last declaration of this name before the cursor in the same file
+    let root_declaration = info
+        .declarations()
+        .iter()
+        .filter(|d| {
+            d.name() == root
+                && matches!(
+                    d.kind(),
+                    DeclarationKind::Variable | DeclarationKind::Static
+                )
+        })
+        .max_by_key(|d| {
+            (
+                d.pos().file_name() == path && *d.pos().line() <= line,
+                *d.pos().line(),
+            )
+        });
+
+    let mut struct_name = match root_declaration.and_then(|d| d.value_type().as_ref()) {
+        Some(t) => struct_name_of(t.as_ref()),
+        None => None,
+    };
+
+    for field in parts {
+        let field_type = struct_name
+            .as_ref()
+            .and_then(|name| info.struct_fields(name))
+            .and_then(|fields| fields.iter().find(|f| f.name() == field))
+            .and_then(|f| f.value_type().as_ref());
+
+        struct_name = field_type.and_then(|t| struct_name_of(t.as_ref()));
+    }
+
+    struct_name
+        .and_then(|name| info.struct_fields(&name))
+        .map(|fields| fields.iter().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "struct point { x: u64, y: u64 };
+static origin: u64 = 0;
+
+fun length(p: struct point *mut): u64 {
+    return *p.x + *p.y;
+};
+
+fun main(argc: u64, argv: char*): u64 {
+    let mut p: struct point *mut = struct point {};
+    p.x @= 3;
+    return length(p);
+};
+";
+
+    fn analyze_source() -> (PathBuf, SemanticInfo) {
+        let path = PathBuf::from("/tmp/lsp_test.ub");
+        let mut analysis = analyze(&path, SOURCE, &vec![]);
+
+        assert!(analysis.error().is_none(), "{:?}", analysis.error());
+        (path, analysis.take_info().unwrap())
+    }
+
+    #[test]
+    fn test_hover_function_call() {
+        let (path, info) = analyze_source();
+
+        assert_eq!(
+            hover(&info, &path, 10, 12).unwrap(),
+            "length: fun (struct point* mut): u64"
+        );
+    }
+
+    #[test]
+    fn test_definition_of_field() {
+        let (path, info) = analyze_source();
+
+        let def = definition(&info, &path, 9, 6).unwrap();
+        assert_eq!((*def.line(), *def.col()), (0, 15));
+    }
+
+    #[test]
+    fn test_definition_of_parameter() {
+        let (path, info) = analyze_source();
+
+        let def = definition(&info, &path, 4, 12).unwrap();
+        assert_eq!((*def.line(), *def.col()), (3, 11));
+    }
+
+    #[test]
+    fn test_definition_of_struct() {
+        let (path, info) = analyze_source();
+
+        let def = definition(&info, &path, 8, 40).unwrap();
+        assert_eq!((*def.line(), *def.col()), (0, 0));
+    }
+
+    #[test]
+    fn test_complete_fields() {
+        let (path, info) = analyze_source();
+
+        let fields: Vec<&str> = complete_fields(&info, &path, "    p.", 10)
+            .iter()
+            .map(|f| f.name())
+            .collect();
+        assert_eq!(fields, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_utf16_columns() {
+        // 'ä' is a single code unit, '😀' is a surrogate pair
+        let line = "let s = \"ä😀\"; s";
+
+        assert_eq!(utf16_col(line, 9), 9);
+        assert_eq!(utf16_col(line, 10), 10);
+        assert_eq!(utf16_col(line, 14), 15);
+        assert_eq!(utf16_col(line, 16), 17);
+
+        assert_eq!(char_col(line, 10), 10);
+        assert_eq!(char_col(line, 11), 11);
+        assert_eq!(char_col(line, 12), 11);
+        assert_eq!(char_col(line, 15), 14);
+        assert_eq!(char_col(line, 17), 16);
+    }
+}
@@ -0,0 +1,17 @@
+use std::io;
+
+use crate::lsp::server::Server;
+
+pub mod analysis;
+pub mod server;
+pub mod transport;
+
+/// Runs the language server on stdin/stdout until the client exits.
+/// Returns the exit code of the server.
+pub fn run(include_paths: Vec<String>) -> io::Result<i32> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    let mut server = Server::new(stdout.lock(), include_paths);
+    server.run(&mut stdin.lock())
+}
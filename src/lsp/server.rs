@@ -0,0 +1,469 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::lsp::analysis;
+use crate::lsp::transport::{read_message, write_message};
+use crate::position::Position;
+use crate::semantic_info::{DeclarationKind, SemanticInfo};
+
+const ERROR_METHOD_NOT_FOUND: i64 = -32601;
+
+// see https://microsoft.github.io/language-server-protocol/specifications/specification-current
+const COMPLETION_ITEM_KIND_FIELD: i64 = 5;
+const DIAGNOSTIC_SEVERITY_ERROR: i64 = 1;
+const TEXT_DOCUMENT_SYNC_KIND_FULL: i64 = 1;
+
+struct Document {
+    path: PathBuf,
+    text: String,
+    info: Option<SemanticInfo>,
+    diagnostic_uris: HashSet<String>,
+}
+
+pub struct Server<W: Write> {
+    writer: W,
+    include_paths: Vec<String>,
+    documents: HashMap<String, Document>,
+    shutdown_requested: bool,
+    // columns are sent in UTF-16 code units, unless the client supports characters (UTF-32)
+    utf16_positions: bool,
+}
+
+pub fn uri_to_path(uri: &str) -> PathBuf {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+
+    let mut decoded: Vec<u8> = vec![];
+    let bytes = path.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 3 <= bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+            if let Ok(b) = u8::from_str_radix(hex, 16) {
+                decoded.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    PathBuf::from(String::from_utf8_lossy(&decoded).to_string())
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            ' ' => uri.push_str("%20"),
+            '%' => uri.push_str("%25"),
+            '#' => uri.push_str("%23"),
+            '?' => uri.push_str("%3F"),
+            _ => uri.push(c),
+        }
+    }
+    uri
+}
+
+fn line_text(text: &str, line: usize) -> &str {
+    text.lines().nth(line).unwrap_or_default()
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(writer: W, include_paths: Vec<String>) -> Self {
+        Self {
+            writer,
+            include_paths,
+            documents: HashMap::new(),
+            shutdown_requested: false,
+            utf16_positions: true,
+        }
+    }
+
+    /// Returns the text of the given document, which is read from disk if it isn't open (e.g. an
+    /// imported file).
+    fn document_text(&self, uri: &str) -> String {
+        match self.documents.get(uri) {
+            Some(document) => document.text.clone(),
+            None => std::fs::read_to_string(uri_to_path(uri)).unwrap_or_default(),
+        }
+    }
+
+    fn lsp_position(&self, text: &str, line: usize, col: usize) -> Value {
+        let col = if self.utf16_positions {
+            analysis::utf16_col(line_text(text, line), col)
+        } else {
+            col
+        };
+
+        json!({ "line": line, "character": col })
+    }
+
+    fn lsp_range(&self, text: &str, pos_start: &Position, pos_end: &Position) -> Value {
+        let (end_line, end_col) =
+            if (pos_end.line(), pos_end.col()) > (pos_start.line(), pos_start.col()) {
+                (*pos_end.line(), *pos_end.col())
+            } else {
+                (*pos_start.line(), pos_start.col() + 1)
+            };
+
+        json!({
+            "start": self.lsp_position(text, *pos_start.line(), *pos_start.col()),
+            "end": self.lsp_position(text, end_line, end_col),
+        })
+    }
+
+    /// Returns the document and the (zero based) line and column in characters of the position in
+    /// the given parameters.
+    fn text_document_position(&self, params: &Value) -> Option<(&Document, usize, usize)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let line = params["position"]["line"].as_u64()? as usize;
+        let col = params["position"]["character"].as_u64()? as usize;
+
+        let document = self.documents.get(uri)?;
+        let col = if self.utf16_positions {
+            analysis::char_col(line_text(&document.text, line), col)
+        } else {
+            col
+        };
+        Some((document, line, col))
+    }
+
+    /// Handles messages from the given reader until the client sends `exit`, or the input is
+    /// closed. Returns the exit code of the server.
+    pub fn run(&mut self, reader: &mut impl BufRead) -> io::Result<i32> {
+        while let Some(message) = read_message(reader)? {
+            if message["method"] == "exit" {
+                return Ok(if self.shutdown_requested { 0 } else { 1 });
+            }
+
+            self.handle_message(&message)?;
+        }
+
+        Ok(1)
+    }
+
+    fn send(&mut self, message: Value) -> io::Result<()> {
+        write_message(&mut self.writer, &message)
+    }
+
+    fn handle_message(&mut self, message: &Value) -> io::Result<()> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        let id = match message.get("id") {
+            Some(id) => id.clone(),
+            None => {
+                return self.handle_notification(method, params);
+            }
+        };
+
+        if method.is_empty() {
+            // a response to a request we never sent
+            return Ok(());
+        }
+
+        let result = match method {
+            "initialize" => Some(self.initialize(params)),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Some(Value::Null)
+            }
+            "textDocument/hover" => Some(self.hover(params)),
+            "textDocument/definition" => Some(self.definition(params)),
+            "textDocument/completion" => Some(self.completion(params)),
+            _ => None,
+        };
+
+        match result {
+            Some(result) => self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+            None => self.send(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {
+                    "code": ERROR_METHOD_NOT_FOUND,
+                    "message": format!("Method '{}' is not supported!", method),
+                },
+            })),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> io::Result<()> {
+        match method {
+            "textDocument/didOpen" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.update_document(uri, text.to_string())
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                // we only support full document synchronisation, so the last change contains the
+                // whole document
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+
+                match text {
+                    Some(text) => self.update_document(uri, text.to_string()),
+                    None => Ok(()),
+                }
+            }
+            "textDocument/didSave" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                match self.documents.get(uri).map(|d| d.text.clone()) {
+                    Some(text) => self.update_document(uri, text),
+                    None => Ok(()),
+                }
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                if let Some(document) = self.documents.remove(uri) {
+                    for diagnostic_uri in document.diagnostic_uris {
+                        self.publish_diagnostics(&diagnostic_uri, vec![])?;
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Value {
+        if let Some(paths) = params["initializationOptions"]["includePaths"].as_array() {
+            for path in paths.iter().filter_map(|p| p.as_str()) {
+                self.include_paths.push(path.to_string());
+            }
+        }
+
+        let encodings = params["capabilities"]["general"]["positionEncodings"].as_array();
+        self.utf16_positions = !encodings.is_some_and(|e| e.iter().any(|e| e == "utf-32"));
+
+        json!({
+            "capabilities": {
+                "positionEncoding": if self.utf16_positions { "utf-16" } else { "utf-32" },
+                "textDocumentSync": TEXT_DOCUMENT_SYNC_KIND_FULL,
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "completionProvider": { "triggerCharacters": ["."] },
+            },
+            "serverInfo": {
+                "name": "umber-lsp",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str, diagnostics: Vec<Value>) -> io::Result<()> {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }))
+    }
+
+    fn update_document(&mut self, uri: &str, text: String) -> io::Result<()> {
+        let path = uri_to_path(uri);
+        let mut analysis = analysis::analyze(&path, &text, &self.include_paths);
+
+        let mut diagnostics: HashMap<String, Vec<Value>> = HashMap::new();
+        if let Some(err) = analysis.error() {
+            let (pos_start, pos_end, message) = analysis::error_location(err, &path);
+
+            // errors without a file (or inside of imported files) are reported in their file, if
+            // known, and the document itself otherwise
+            let diagnostic_uri = if pos_start.file_name().as_os_str().is_empty() {
+                uri.to_string()
+            } else {
+                path_to_uri(pos_start.file_name())
+            };
+
+            let diagnostic_text = if diagnostic_uri == uri {
+                text.clone()
+            } else {
+                self.document_text(&diagnostic_uri)
+            };
+
+            diagnostics.entry(diagnostic_uri).or_default().push(json!({
+                "range": self.lsp_range(&diagnostic_text, &pos_start, &pos_end),
+                "severity": DIAGNOSTIC_SEVERITY_ERROR,
+                "source": "umber",
+                "message": message,
+            }));
+        }
+
+        let previous = self.documents.remove(uri);
+        let (previous_info, previous_uris) = match previous {
+            Some(document) => (document.info, document.diagnostic_uris),
+            None => (None, HashSet::new()),
+        };
+
+        // an incomplete document (e.g. while typing `p.`) usually doesn't parse, in which case we
+        // keep the information of the last analysis around
+        let info = analysis.take_info().or(previous_info);
+
+        let mut diagnostic_uris: HashSet<String> = diagnostics.keys().cloned().collect();
+        diagnostic_uris.insert(uri.to_string());
+
+        for stale_uri in previous_uris.difference(&diagnostic_uris) {
+            self.publish_diagnostics(stale_uri, vec![])?;
+        }
+        for diagnostic_uri in diagnostic_uris.iter() {
+            let list = diagnostics.remove(diagnostic_uri).unwrap_or_default();
+            self.publish_diagnostics(diagnostic_uri, list)?;
+        }
+
+        self.documents.insert(
+            uri.to_string(),
+            Document {
+                path,
+                text,
+                info,
+                diagnostic_uris,
+            },
+        );
+        Ok(())
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let hover = self
+            .text_document_position(params)
+            .and_then(|(document, line, col)| {
+                analysis::hover(document.info.as_ref()?, &document.path, line, col)
+            });
+
+        match hover {
+            Some(text) => json!({
+                "contents": { "kind": "markdown", "value": format!("```umber\n{}\n```", text) },
+            }),
+            None => Value::Null,
+        }
+    }
+
+    fn definition(&self, params: &Value) -> Value {
+        let definition = self
+            .text_document_position(params)
+            .and_then(|(document, line, col)| {
+                analysis::definition(document.info.as_ref()?, &document.path, line, col)
+            });
+
+        match definition {
+            Some(pos) => {
+                let uri = path_to_uri(pos.file_name());
+                let text = self.document_text(&uri);
+                json!({ "uri": uri, "range": self.lsp_range(&text, &pos, &pos) })
+            }
+            None => Value::Null,
+        }
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        let items = self
+            .text_document_position(params)
+            .and_then(|(document, line, col)| {
+                let line_text: String = line_text(&document.text, line).chars().take(col).collect();
+
+                let fields = analysis::complete_fields(
+                    document.info.as_ref()?,
+                    &document.path,
+                    &line_text,
+                    line,
+                );
+
+                Some(
+                    fields
+                        .iter()
+                        .filter(|f| f.kind() == DeclarationKind::Field)
+                        .map(|f| {
+                            json!({
+                                "label": f.name(),
+                                "kind": COMPLETION_ITEM_KIND_FIELD,
+                                "detail": f.value_type().as_ref().map(|t| t.to_string()),
+                            })
+                        })
+                        .collect::<Vec<Value>>(),
+                )
+            });
+
+        Value::Array(items.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_conversion() {
+        let path = PathBuf::from("/home/user/my project/main.ub");
+        let uri = path_to_uri(&path);
+
+        assert_eq!(uri, "file:///home/user/my%20project/main.ub");
+        assert_eq!(uri_to_path(&uri), path);
+
+        // escapes at the end of the path are decoded as well
+        assert_eq!(uri_to_path("file:///tmp/a%20"), PathBuf::from("/tmp/a "));
+        assert_eq!(uri_to_path("file:///tmp/a%2"), PathBuf::from("/tmp/a%2"));
+    }
+
+    fn request(server: &mut Server<Vec<u8>>, method: &str, params: Value) -> Value {
+        server.writer.clear();
+        server
+            .handle_message(
+                &json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }),
+            )
+            .unwrap();
+
+        let mut reader = server.writer.as_slice();
+        read_message(&mut reader).unwrap().unwrap()["result"].clone()
+    }
+
+    #[test]
+    fn test_utf16_positions() {
+        let uri = "file:///tmp/lsp_utf16_test.ub";
+        let text = "fun main(argc: u64, argv: char*): u64 {\n    let s: string = \"😀\"; let n: u64 = argc;\n    return n;\n};\n";
+
+        let mut server = Server::new(vec![], vec![]);
+        let result = request(&mut server, "initialize", json!({ "capabilities": {} }));
+        assert_eq!(result["capabilities"]["positionEncoding"], "utf-16");
+
+        server
+            .handle_notification(
+                "textDocument/didOpen",
+                &json!({ "textDocument": { "uri": uri, "text": text } }),
+            )
+            .unwrap();
+
+        // `argc` starts at character 38, which is code unit 39 after the surrogate pair
+        let position =
+            json!({ "textDocument": { "uri": uri }, "position": { "line": 1, "character": 39 } });
+        let result = request(&mut server, "textDocument/hover", position.clone());
+        assert_eq!(result["contents"]["value"], "```umber\nargc: u64\n```");
+
+        // the declaration of `n` starts at character 25
+        let position =
+            json!({ "textDocument": { "uri": uri }, "position": { "line": 2, "character": 11 } });
+        let result = request(&mut server, "textDocument/definition", position);
+        assert_eq!(
+            result["range"]["start"],
+            json!({ "line": 1, "character": 26 })
+        );
+
+        // clients supporting characters get them unchanged
+        let capabilities = json!({ "general": { "positionEncodings": ["utf-16", "utf-32"] } });
+        let result = request(
+            &mut server,
+            "initialize",
+            json!({ "capabilities": capabilities }),
+        );
+        assert_eq!(result["capabilities"]["positionEncoding"], "utf-32");
+
+        let position =
+            json!({ "textDocument": { "uri": uri }, "position": { "line": 1, "character": 38 } });
+        let result = request(&mut server, "textDocument/hover", position);
+        assert_eq!(result["contents"]["value"], "```umber\nargc: u64\n```");
+    }
+}
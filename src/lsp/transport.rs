@@ -0,0 +1,48 @@
+use std::io::{BufRead, Error, ErrorKind, Result, Write};
+
+use serde_json::Value;
+
+/// Reads a single JSON-RPC message (header + content) from the given reader.
+/// Returns `None` if the input was closed.
+pub fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let content_length = content_length
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing Content-Length header"))?;
+
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+pub fn write_message(writer: &mut impl Write, message: &Value) -> Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
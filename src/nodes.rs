@@ -7,31 +7,42 @@ pub mod accessor_node;
 pub mod address_of_node;
 pub mod array_node;
 pub mod asm_node;
+pub mod assert_node;
+pub mod assign_node;
 pub mod binop_node;
 pub mod break_node;
 pub mod call_node;
 pub mod cast_node;
 pub mod char_node;
+pub mod check_node;
 pub mod const_def_node;
 pub mod continue_node;
+pub mod copy_node;
+pub mod defer_node;
 pub mod dereference_node;
 pub mod extern_node;
 pub mod f64_to_u64_node;
 pub mod floating_binop_node;
 pub mod floating_point_node;
+pub mod for_in_node;
 pub mod for_node;
 pub mod functiondecl_node;
 pub mod functiondef_node;
 pub mod if_node;
 pub mod ignored_node;
 pub mod import_node;
+pub mod lenof_node;
 pub mod macro_def_node;
+pub mod memory_node;
 pub mod number_node;
 pub mod offset_node;
 pub mod pointer_assign_node;
+pub mod range_node;
 pub mod read_bytes_node;
+pub mod reference_node;
 pub mod return_node;
 pub mod sizeof_node;
+pub mod slice_node;
 pub mod stack_allocation_node;
 pub mod statements_node;
 pub mod static_decl_node;
@@ -40,6 +51,7 @@ pub mod string_node;
 pub mod struct_def_node;
 pub mod struct_init_node;
 pub mod syscall_node;
+pub mod test_def_node;
 pub mod u64_to_f64_node;
 pub mod unaryop_node;
 pub mod util;
@@ -55,11 +67,14 @@ pub enum NodeType {
     Array,
     Break,
     Continue,
+    Defer,
     If,
     Return,
     Number,
     While,
     For,
+    ForIn,
+    Range,
     String,
     Char,
     UnaryOp,
@@ -74,6 +89,7 @@ pub enum NodeType {
     Cast,
     ConstDef,
     SizeOf,
+    LenOf,
     StaticDef,
     StaticDecl,
     StructDef,
@@ -83,17 +99,25 @@ pub enum NodeType {
     MacroDef,
     Ignored,
     PointerAssign,
+    Assign,
     Offset,
     TypeCarrier,
     Accessor,
     Extern,
     AddressOf,
+    Reference,
     StructInit,
     StackAllocationNode,
+    Copy,
+    Check,
+    Slice,
+    Memory,
     FloatingPoint,
     FloatingBinOp,
     F64ToU64,
     U64ToF64,
+    TestDef,
+    Assert,
 }
 
 pub trait NodeToAny: 'static {
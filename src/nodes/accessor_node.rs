@@ -7,14 +7,21 @@ use std::fmt::{Display, Formatter};
 pub struct AccessorNode {
     node: Box<dyn Node>,
     accessor: String,
+    accessor_pos: Position,
     pos_end: Position,
 }
 
 impl AccessorNode {
-    pub fn new(node: Box<dyn Node>, accessor: String, pos_end: Position) -> Self {
+    pub fn new(
+        node: Box<dyn Node>,
+        accessor: String,
+        accessor_pos: Position,
+        pos_end: Position,
+    ) -> Self {
         Self {
             node,
             accessor,
+            accessor_pos,
             pos_end,
         }
     }
@@ -25,6 +32,9 @@ impl AccessorNode {
     pub fn accessor(&self) -> &str {
         &self.accessor
     }
+    pub fn accessor_pos(&self) -> &Position {
+        &self.accessor_pos
+    }
 }
 
 impl NodeToAny for AccessorNode {
@@ -0,0 +1,56 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+
+#[derive(Clone)]
+pub struct AssertNode {
+    condition_node: Box<dyn Node>,
+    pos_start: Position,
+    pos_end: Position,
+}
+
+impl AssertNode {
+    pub fn new(condition_node: Box<dyn Node>, pos_start: Position, pos_end: Position) -> Self {
+        AssertNode {
+            condition_node,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    pub fn condition_node(&self) -> &Box<dyn Node> {
+        &self.condition_node
+    }
+}
+
+impl Display for AssertNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "assert({})", self.condition_node)
+    }
+}
+
+impl NodeToAny for AssertNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Node for AssertNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        &self.pos_end
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Assert
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use crate::token::Token;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// Assigns to a field, an element or a dereferenced pointer. `op_token` is the operator of a
+/// compound assignment like `+=`. Assignments to variables are `VarAssignNode`s instead.
+#[derive(Clone)]
+pub struct AssignNode {
+    target: Box<dyn Node>,
+    op_token: Option<Token>,
+    value_node: Box<dyn Node>,
+}
+
+impl AssignNode {
+    pub fn new(target: Box<dyn Node>, op_token: Option<Token>, value_node: Box<dyn Node>) -> Self {
+        Self {
+            target,
+            op_token,
+            value_node,
+        }
+    }
+
+    pub fn target(&self) -> &Box<dyn Node> {
+        &self.target
+    }
+    pub fn op_token(&self) -> &Option<Token> {
+        &self.op_token
+    }
+    pub fn value_node(&self) -> &Box<dyn Node> {
+        &self.value_node
+    }
+}
+
+impl NodeToAny for AssignNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for AssignNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.op_token {
+            Some(op_token) => write!(f, "{} {}= {}", self.target, op_token, self.value_node),
+            None => write!(f, "{} = {}", self.target, self.value_node),
+        }
+    }
+}
+
+impl Node for AssignNode {
+    fn pos_start(&self) -> &Position {
+        self.target.pos_start()
+    }
+
+    fn pos_end(&self) -> &Position {
+        self.value_node.pos_end()
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Assign
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
@@ -6,19 +6,32 @@ use crate::position::Position;
 
 #[derive(Clone)]
 pub struct BreakNode {
+    label: Option<String>,
     pos_start: Position,
     pos_end: Position,
 }
 
 impl BreakNode {
-    pub fn new(pos_start: Position, pos_end: Position) -> Self {
-        BreakNode { pos_start, pos_end }
+    pub fn new(label: Option<String>, pos_start: Position, pos_end: Position) -> Self {
+        BreakNode {
+            label,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    /// The label of the loop to break, instead of the innermost one.
+    pub fn label(&self) -> &Option<String> {
+        &self.label
     }
 }
 
 impl Display for BreakNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "break")
+        match &self.label {
+            Some(label) => write!(f, "break '{}", label),
+            None => write!(f, "break"),
+        }
     }
 }
 
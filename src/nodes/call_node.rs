@@ -3,11 +3,14 @@ use std::fmt::{Display, Formatter};
 
 use crate::nodes::{Node, NodeToAny, NodeType};
 use crate::position::Position;
+use crate::values::value_type::slice_type::passed_types;
+use crate::values::value_type::{ValueType, ValueTypes};
 
 #[derive(Clone)]
 pub struct CallNode {
     func_to_call: String,
     arg_nodes: Vec<Box<dyn Node>>,
+    arg_types: Vec<Box<dyn ValueType>>,
     pos_start: Position,
 }
 
@@ -16,6 +19,22 @@ impl CallNode {
         CallNode {
             func_to_call,
             arg_nodes,
+            arg_types: vec![],
+            pos_start,
+        }
+    }
+
+    /// Creates a call with the types of its arguments, which are known after validation.
+    pub fn new_typed(
+        func_to_call: String,
+        arg_nodes: Vec<Box<dyn Node>>,
+        arg_types: Vec<Box<dyn ValueType>>,
+        pos_start: Position,
+    ) -> Self {
+        CallNode {
+            func_to_call,
+            arg_nodes,
+            arg_types,
             pos_start,
         }
     }
@@ -26,6 +45,25 @@ impl CallNode {
     pub fn arg_nodes(&self) -> &Vec<Box<dyn Node>> {
         &self.arg_nodes
     }
+    /// The types of the arguments, including the ones passed to the `...` of variadic functions.
+    /// Empty before validation.
+    pub fn arg_types(&self) -> &Vec<Box<dyn ValueType>> {
+        &self.arg_types
+    }
+
+    /// Whether the argument at the index is a slice, which is passed as two arguments: the pointer
+    /// to its first element and its length.
+    pub fn is_slice_arg(&self, index: usize) -> bool {
+        self.arg_types
+            .get(index)
+            .is_some_and(|arg_type| arg_type.value_type() == ValueTypes::Slice)
+    }
+
+    /// The types of the values passed to the function, with slices split into their pointer and
+    /// length.
+    pub fn passed_arg_types(&self) -> Vec<Box<dyn ValueType>> {
+        passed_types(&self.arg_types)
+    }
 }
 
 impl Display for CallNode {
@@ -0,0 +1,132 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use crate::values::value_size::ValueSize;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// The exit code of programs, that failed a runtime check.
+pub const CHECK_FAILED_EXIT_CODE: u64 = 101;
+
+/// A condition checked at runtime, if the program was compiled with `--checks`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Check {
+    /// The value is an index into an array with the given length
+    Bounds(u64),
+    /// The value is an index into a slice, whose length the length node evaluates to
+    SliceBounds,
+    /// The value is a bound of a sub-slice, which can be at most the value of the length node
+    SliceRange,
+    /// The value is a pointer, which is dereferenced
+    Null,
+    /// The node is a `+`, `-` or `*` `BinOpNode` on integers of the given size and signedness,
+    /// whose result has to fit into that type
+    Overflow { size: ValueSize, signed: bool },
+}
+
+impl Check {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Check::Bounds(_) | Check::SliceBounds => "Index out of bounds!",
+            Check::SliceRange => "Slice range out of bounds!",
+            Check::Null => "Null pointer dereference!",
+            Check::Overflow { .. } => "Arithmetic overflow!",
+        }
+    }
+}
+
+/// Evaluates to the value of `node`, after checking it. Failed checks print the position of the
+/// checked expression and exit with `CHECK_FAILED_EXIT_CODE`.
+#[derive(Clone)]
+pub struct CheckNode {
+    check: Check,
+    node: Box<dyn Node>,
+    /// The length the value is compared to, for the checks of slices
+    length: Option<Box<dyn Node>>,
+    pos_start: Position,
+    pos_end: Position,
+}
+
+impl CheckNode {
+    pub fn new(check: Check, node: Box<dyn Node>, pos_start: Position, pos_end: Position) -> Self {
+        Self {
+            check,
+            node,
+            length: None,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    /// Creates a check, which compares the value to the value of `length`. It is evaluated after
+    /// the value itself.
+    pub fn with_length(
+        check: Check,
+        node: Box<dyn Node>,
+        length: Box<dyn Node>,
+        pos_start: Position,
+        pos_end: Position,
+    ) -> Self {
+        Self {
+            check,
+            node,
+            length: Some(length),
+            pos_start,
+            pos_end,
+        }
+    }
+
+    pub fn check(&self) -> &Check {
+        &self.check
+    }
+    pub fn node(&self) -> &Box<dyn Node> {
+        &self.node
+    }
+    /// The length of `SliceBounds` and `SliceRange` checks.
+    pub fn length(&self) -> &Box<dyn Node> {
+        self.length.as_ref().unwrap()
+    }
+
+    /// The message printed, if the check fails.
+    pub fn failure_message(&self) -> String {
+        format!(
+            "{}:{}:{}: {}\n",
+            self.pos_start.file_name().display(),
+            self.pos_start.line() + 1,
+            self.pos_start.col() + 1,
+            self.check.message()
+        )
+    }
+}
+
+impl NodeToAny for CheckNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for CheckNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.length {
+            Some(length) => write!(f, "check({:?}, {}, {})", self.check, self.node, length),
+            None => write!(f, "check({:?}, {})", self.check, self.node),
+        }
+    }
+}
+
+impl Node for CheckNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        &self.pos_end
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Check
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
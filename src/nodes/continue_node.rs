@@ -6,19 +6,32 @@ use crate::position::Position;
 
 #[derive(Clone)]
 pub struct ContinueNode {
+    label: Option<String>,
     pos_start: Position,
     pos_end: Position,
 }
 
 impl ContinueNode {
-    pub fn new(pos_start: Position, pos_end: Position) -> Self {
-        ContinueNode { pos_start, pos_end }
+    pub fn new(label: Option<String>, pos_start: Position, pos_end: Position) -> Self {
+        ContinueNode {
+            label,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    /// The label of the loop to continue, instead of the innermost one.
+    pub fn label(&self) -> &Option<String> {
+        &self.label
     }
 }
 
 impl Display for ContinueNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "continue")
+        match &self.label {
+            Some(label) => write!(f, "continue '{}", label),
+            None => write!(f, "continue"),
+        }
     }
 }
 
@@ -0,0 +1,63 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// Copies `size_in_bytes` bytes from the address `source` evaluates to, to the address of
+/// `destination`. Evaluates to the destination address.
+#[derive(Clone)]
+pub struct CopyNode {
+    destination: Box<dyn Node>,
+    source: Box<dyn Node>,
+    size_in_bytes: u64,
+}
+
+impl CopyNode {
+    pub fn new(destination: Box<dyn Node>, source: Box<dyn Node>, size_in_bytes: u64) -> Self {
+        Self {
+            destination,
+            source,
+            size_in_bytes,
+        }
+    }
+
+    pub fn destination(&self) -> &Box<dyn Node> {
+        &self.destination
+    }
+    pub fn source(&self) -> &Box<dyn Node> {
+        &self.source
+    }
+    pub fn size_in_bytes(&self) -> u64 {
+        self.size_in_bytes
+    }
+}
+
+impl NodeToAny for CopyNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for CopyNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CopyNode")
+    }
+}
+
+impl Node for CopyNode {
+    fn pos_start(&self) -> &Position {
+        self.destination.pos_start()
+    }
+
+    fn pos_end(&self) -> &Position {
+        self.source.pos_end()
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Copy
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
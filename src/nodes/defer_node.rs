@@ -0,0 +1,52 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// A statement, which runs when the enclosing block is left. The validator copies it to every
+/// exit of the block, so it never reaches the backends.
+#[derive(Clone)]
+pub struct DeferNode {
+    node: Box<dyn Node>,
+    pos_start: Position,
+}
+
+impl DeferNode {
+    pub fn new(node: Box<dyn Node>, pos_start: Position) -> Self {
+        Self { node, pos_start }
+    }
+
+    pub fn node(&self) -> &Box<dyn Node> {
+        &self.node
+    }
+}
+
+impl NodeToAny for DeferNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for DeferNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "defer {}", self.node)
+    }
+}
+
+impl Node for DeferNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        self.node.pos_end()
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Defer
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
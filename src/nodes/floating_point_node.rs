@@ -28,46 +28,7 @@ impl FloatingPointNode {
 
     // get the double ieee 754 representation of the float
     pub fn ieee_754(&self) -> u64 {
-        let mut ieee_754: u64 = 0;
-        let mut exponent: u64 = 0;
-        let mut mantissa: u64 = 0;
-
-        let float = self.get_float();
-
-        if float == 0.0 {
-            return ieee_754;
-        }
-
-        // get the sign bit
-        if float < 0.0 {
-            ieee_754 |= 1 << 63;
-        }
-
-        // get the exponent
-        let mut float = float.abs();
-        while float >= 2.0 {
-            float /= 2.0;
-            exponent += 1;
-        }
-        while float < 1.0 {
-            float *= 2.0;
-            exponent -= 1;
-        }
-        exponent += 1023;
-        ieee_754 |= exponent << 52;
-
-        // get the mantissa
-        float -= 1.0;
-        for i in 0..52 {
-            float *= 2.0;
-            if float >= 1.0 {
-                mantissa |= 1 << (51 - i);
-                float -= 1.0;
-            }
-        }
-        ieee_754 |= mantissa;
-
-        ieee_754
+        self.get_float().to_bits()
     }
 
     pub fn size(&self) -> &Box<dyn ValueType> {
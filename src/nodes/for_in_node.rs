@@ -0,0 +1,80 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// A `for` loop over a range, an array or a slice. The validator lowers it to a `ForNode`, so it
+/// never reaches the backends.
+#[derive(Clone)]
+pub struct ForInNode {
+    var_name: String,
+    iterable: Box<dyn Node>,
+    body: Box<dyn Node>,
+    label: Option<String>,
+    pos_start: Position,
+}
+
+impl ForInNode {
+    pub fn new(
+        var_name: String,
+        iterable: Box<dyn Node>,
+        body: Box<dyn Node>,
+        label: Option<String>,
+        pos_start: Position,
+    ) -> Self {
+        Self {
+            var_name,
+            iterable,
+            body,
+            label,
+            pos_start,
+        }
+    }
+
+    pub fn var_name(&self) -> &str {
+        &self.var_name
+    }
+    pub fn iterable(&self) -> &Box<dyn Node> {
+        &self.iterable
+    }
+    pub fn body(&self) -> &Box<dyn Node> {
+        &self.body
+    }
+    pub fn label(&self) -> &Option<String> {
+        &self.label
+    }
+}
+
+impl NodeToAny for ForInNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for ForInNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "for {} in {} {{ {} }}",
+            self.var_name, self.iterable, self.body
+        )
+    }
+}
+
+impl Node for ForInNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        self.body.pos_end()
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::ForIn
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
@@ -9,6 +9,7 @@ pub struct ForNode {
     condition: Box<dyn Node>,
     next_expr: Box<dyn Node>,
     body: Box<dyn Node>,
+    label: Option<String>,
 }
 
 impl ForNode {
@@ -17,12 +18,14 @@ impl ForNode {
         condition: Box<dyn Node>,
         next_expr: Box<dyn Node>,
         body: Box<dyn Node>,
+        label: Option<String>,
     ) -> Self {
         Self {
             init_stmt,
             condition,
             next_expr,
             body,
+            label,
         }
     }
 
@@ -38,6 +41,10 @@ impl ForNode {
     pub fn body(&self) -> &Box<dyn Node> {
         &self.body
     }
+    /// The label `break` and `continue` in nested loops can refer to the loop with.
+    pub fn label(&self) -> &Option<String> {
+        &self.label
+    }
 }
 
 impl NodeToAny for ForNode {
@@ -10,6 +10,7 @@ pub struct FunctionDeclarationNode {
     var_name: String,
     args: Vec<(String, Box<dyn ValueType>)>,
     return_type: Box<dyn ValueType>,
+    is_variadic: bool,
     pos_start: Position,
     pos_end: Position,
 }
@@ -19,6 +20,7 @@ impl FunctionDeclarationNode {
         var_name: String,
         args: Vec<(String, Box<dyn ValueType>)>,
         return_type: Box<dyn ValueType>,
+        is_variadic: bool,
         pos_start: Position,
         pos_end: Position,
     ) -> Self {
@@ -26,6 +28,7 @@ impl FunctionDeclarationNode {
             var_name,
             args,
             return_type,
+            is_variadic,
             pos_start,
             pos_end,
         }
@@ -40,19 +43,24 @@ impl FunctionDeclarationNode {
     pub fn return_type(&self) -> &Box<dyn ValueType> {
         &self.return_type
     }
+    /// Whether the function takes additional arguments after `args`, declared with `...`
+    pub fn is_variadic(&self) -> bool {
+        self.is_variadic
+    }
 }
 
 impl Display for FunctionDeclarationNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "fun {}({}): {}",
+            "fun {}({}{}): {}",
             self.var_name,
             self.args
                 .iter()
                 .map(|(arg_name, arg_type)| format!("{}: {}", arg_name, arg_type))
                 .collect::<Vec<String>>()
                 .join(", "),
+            if self.is_variadic { ", ..." } else { "" },
             self.return_type
         )
     }
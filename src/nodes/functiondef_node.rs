@@ -9,9 +9,12 @@ use crate::values::value_type::ValueType;
 pub struct FunctionDefinitionNode {
     var_name: String,
     args: Vec<(String, Box<dyn ValueType>)>,
+    /// Where the names of the arguments are, if the function was parsed from source
+    arg_positions: Vec<Position>,
     return_type: Box<dyn ValueType>,
     body_node: Box<dyn Node>,
     generics: Vec<String>,
+    is_public: bool,
     pos_start: Position,
 }
 
@@ -22,24 +25,35 @@ impl FunctionDefinitionNode {
         return_type: Box<dyn ValueType>,
         body_node: Box<dyn Node>,
         generics: Vec<String>,
+        is_public: bool,
         pos_start: Position,
     ) -> Self {
         FunctionDefinitionNode {
             var_name,
             args,
+            arg_positions: vec![],
             return_type,
             body_node,
             generics,
+            is_public,
             pos_start,
         }
     }
 
+    pub fn with_arg_positions(mut self, arg_positions: Vec<Position>) -> Self {
+        self.arg_positions = arg_positions;
+        self
+    }
+
     pub fn var_name(&self) -> &str {
         &self.var_name
     }
     pub fn args(&self) -> &Vec<(String, Box<dyn ValueType>)> {
         &self.args
     }
+    pub fn arg_positions(&self) -> &Vec<Position> {
+        &self.arg_positions
+    }
     pub fn return_type(&self) -> &Box<dyn ValueType> {
         &self.return_type
     }
@@ -49,13 +63,18 @@ impl FunctionDefinitionNode {
     pub fn generics(&self) -> &Vec<String> {
         &self.generics
     }
+    /// Whether the function was marked with `pub` and is exported from shared libraries.
+    pub fn is_public(&self) -> bool {
+        self.is_public
+    }
 }
 
 impl Display for FunctionDefinitionNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "fun {}({}): {} {{ {} }}",
+            "{}fun {}({}): {} {{ {} }}",
+            if self.is_public { "pub " } else { "" },
             self.var_name,
             self.args
                 .iter()
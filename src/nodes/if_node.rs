@@ -3,6 +3,7 @@ use std::fmt::{Display, Formatter};
 
 use crate::nodes::if_node::case::IfCase;
 use crate::nodes::if_node::elsecase::ElseCase;
+use crate::nodes::statements_node::StatementsNode;
 use crate::nodes::{Node, NodeToAny, NodeType};
 use crate::position::Position;
 
@@ -30,6 +31,18 @@ impl IfNode {
     pub fn else_case(&self) -> &Option<ElseCase> {
         &self.else_case
     }
+
+    /// Whether the `if` is an expression. After validation, either all branches have a value or
+    /// none of them does, so it's enough to look at the `else` branch.
+    pub fn has_value(&self) -> bool {
+        self.else_case.as_ref().is_some_and(|else_case| {
+            else_case
+                .statements()
+                .as_any()
+                .downcast_ref::<StatementsNode>()
+                .is_some_and(|statements_node| statements_node.has_value())
+        })
+    }
 }
 
 impl Display for IfNode {
@@ -0,0 +1,55 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone)]
+pub struct LenOfNode {
+    node: Box<dyn Node>,
+    pos_start: Position,
+    pos_end: Position,
+}
+
+impl LenOfNode {
+    pub fn new(node: Box<dyn Node>, pos_start: Position, pos_end: Position) -> Self {
+        Self {
+            node,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    pub fn node(&self) -> &Box<dyn Node> {
+        &self.node
+    }
+}
+
+impl NodeToAny for LenOfNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for LenOfNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "lenof[{}]", self.node)
+    }
+}
+
+impl Node for LenOfNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        &self.pos_end
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::LenOf
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
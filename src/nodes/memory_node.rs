@@ -0,0 +1,107 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MemoryOperation {
+    /// Fills the destination with a byte
+    Set,
+    /// Copies the source to the destination, which must not overlap
+    Copy,
+    /// Copies the source to the destination, which may overlap
+    Move,
+}
+
+impl MemoryOperation {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            MemoryOperation::Set => "memset",
+            MemoryOperation::Copy => "memcpy",
+            MemoryOperation::Move => "memmove",
+        }
+    }
+}
+
+/// The `memset`, `memcpy` and `memmove` intrinsics, working on `count` bytes at the address
+/// `destination` evaluates to. `source` is the byte to fill the memory with for `memset` and the
+/// address to copy from otherwise.
+#[derive(Clone)]
+pub struct MemoryNode {
+    operation: MemoryOperation,
+    destination: Box<dyn Node>,
+    source: Box<dyn Node>,
+    count: Box<dyn Node>,
+    pos_start: Position,
+    pos_end: Position,
+}
+
+impl MemoryNode {
+    pub fn new(
+        operation: MemoryOperation,
+        destination: Box<dyn Node>,
+        source: Box<dyn Node>,
+        count: Box<dyn Node>,
+        pos_start: Position,
+        pos_end: Position,
+    ) -> Self {
+        Self {
+            operation,
+            destination,
+            source,
+            count,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    pub fn operation(&self) -> MemoryOperation {
+        self.operation
+    }
+    pub fn destination(&self) -> &Box<dyn Node> {
+        &self.destination
+    }
+    pub fn source(&self) -> &Box<dyn Node> {
+        &self.source
+    }
+    pub fn count(&self) -> &Box<dyn Node> {
+        &self.count
+    }
+}
+
+impl NodeToAny for MemoryNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for MemoryNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}, {}, {}]",
+            self.operation.keyword(),
+            self.destination,
+            self.source,
+            self.count
+        )
+    }
+}
+
+impl Node for MemoryNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        &self.pos_end
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Memory
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// The integers from `start` up to, but not including, `end`, which are iterated by a `for` loop.
+/// A reversed range starts at `end - 1` and counts down to `start`.
+#[derive(Clone)]
+pub struct RangeNode {
+    start: Box<dyn Node>,
+    end: Box<dyn Node>,
+    step: Option<Box<dyn Node>>,
+    is_reversed: bool,
+    pos_start: Position,
+    pos_end: Position,
+}
+
+impl RangeNode {
+    pub fn new(
+        start: Box<dyn Node>,
+        end: Box<dyn Node>,
+        step: Option<Box<dyn Node>>,
+        is_reversed: bool,
+        pos_start: Position,
+        pos_end: Position,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            step,
+            is_reversed,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    pub fn start(&self) -> &Box<dyn Node> {
+        &self.start
+    }
+    pub fn end(&self) -> &Box<dyn Node> {
+        &self.end
+    }
+    pub fn step(&self) -> &Option<Box<dyn Node>> {
+        &self.step
+    }
+    pub fn is_reversed(&self) -> bool {
+        self.is_reversed
+    }
+}
+
+impl NodeToAny for RangeNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for RangeNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}..{})", self.start, self.end)?;
+        if let Some(step) = &self.step {
+            write!(f, ".step({})", step)?;
+        }
+        if self.is_reversed {
+            write!(f, ".rev()")?;
+        }
+        Ok(())
+    }
+}
+
+impl Node for RangeNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        &self.pos_end
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Range
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,52 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// Takes the address of a field, an element or a dereferenced pointer. The addresses of
+/// variables are taken by `AddressOfNode`s instead.
+#[derive(Clone)]
+pub struct ReferenceNode {
+    node: Box<dyn Node>,
+    pos_start: Position,
+}
+
+impl ReferenceNode {
+    pub fn new(node: Box<dyn Node>, pos_start: Position) -> Self {
+        Self { node, pos_start }
+    }
+
+    pub fn node(&self) -> &Box<dyn Node> {
+        &self.node
+    }
+}
+
+impl NodeToAny for ReferenceNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for ReferenceNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "&({})", self.node)
+    }
+}
+
+impl Node for ReferenceNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        self.node.pos_end()
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Reference
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,98 @@
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// Creates a slice of the elements from `start` up to, but not including, `end` of another slice.
+/// Like array literals, the new slice is stored on the stack and the node evaluates to its address.
+#[derive(Clone)]
+pub struct SliceNode {
+    node: Box<dyn Node>,
+    start: Box<dyn Node>,
+    end: Box<dyn Node>,
+    element_size: u64,
+    pos_start: Position,
+    pos_end: Position,
+}
+
+impl SliceNode {
+    pub fn new(
+        node: Box<dyn Node>,
+        start: Box<dyn Node>,
+        end: Box<dyn Node>,
+        pos_start: Position,
+        pos_end: Position,
+    ) -> Self {
+        Self {
+            node,
+            start,
+            end,
+            element_size: 0,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    /// Creates the node with the size of the elements, which is known after validation.
+    pub fn new_typed(
+        node: Box<dyn Node>,
+        start: Box<dyn Node>,
+        end: Box<dyn Node>,
+        element_size: u64,
+        pos_start: Position,
+        pos_end: Position,
+    ) -> Self {
+        Self {
+            node,
+            start,
+            end,
+            element_size,
+            pos_start,
+            pos_end,
+        }
+    }
+
+    pub fn node(&self) -> &Box<dyn Node> {
+        &self.node
+    }
+    pub fn start(&self) -> &Box<dyn Node> {
+        &self.start
+    }
+    pub fn end(&self) -> &Box<dyn Node> {
+        &self.end
+    }
+    /// The size of the elements in bytes. 0 before validation.
+    pub fn element_size(&self) -> u64 {
+        self.element_size
+    }
+}
+
+impl NodeToAny for SliceNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for SliceNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}..{}]", self.node, self.start, self.end)
+    }
+}
+
+impl Node for SliceNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        &self.pos_end
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::Slice
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
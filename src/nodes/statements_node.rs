@@ -4,9 +4,12 @@ use std::fmt::{Display, Formatter};
 use crate::nodes::{Node, NodeToAny, NodeType};
 use crate::position::Position;
 
+/// A block of statements. If the last statement isn't terminated by a ';', it's the value of the
+/// block.
 #[derive(Clone)]
 pub struct StatementsNode {
     statement_nodes: Vec<Box<dyn Node>>,
+    has_value: bool,
     pos_start: Position,
     pos_end: Position,
 }
@@ -14,11 +17,13 @@ pub struct StatementsNode {
 impl StatementsNode {
     pub fn new(
         statement_nodes: Vec<Box<dyn Node>>,
+        has_value: bool,
         pos_start: Position,
         pos_end: Position,
     ) -> Self {
         StatementsNode {
             statement_nodes,
+            has_value,
             pos_start,
             pos_end,
         }
@@ -27,6 +32,9 @@ impl StatementsNode {
     pub fn statement_nodes(&self) -> &Vec<Box<dyn Node>> {
         &self.statement_nodes
     }
+    pub fn has_value(&self) -> bool {
+        self.has_value
+    }
 }
 
 impl Display for StatementsNode {
@@ -8,6 +8,7 @@ use std::fmt::{Display, Formatter};
 pub struct StructDefinitionNode {
     name: String,
     fields: Vec<(String, Box<dyn ValueType>)>,
+    field_positions: Vec<Position>,
     pos_start: Position,
     pos_end: Position,
 }
@@ -16,12 +17,14 @@ impl StructDefinitionNode {
     pub fn new(
         name: String,
         fields: Vec<(String, Box<dyn ValueType>)>,
+        field_positions: Vec<Position>,
         pos_start: Position,
         pos_end: Position,
     ) -> Self {
         Self {
             name,
             fields,
+            field_positions,
             pos_start,
             pos_end,
         }
@@ -33,6 +36,9 @@ impl StructDefinitionNode {
     pub fn fields(&self) -> &Vec<(String, Box<dyn ValueType>)> {
         &self.fields
     }
+    pub fn field_positions(&self) -> &Vec<Position> {
+        &self.field_positions
+    }
 }
 
 impl NodeToAny for StructDefinitionNode {
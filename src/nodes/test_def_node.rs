@@ -0,0 +1,55 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+use crate::nodes::functiondef_node::FunctionDefinitionNode;
+use crate::nodes::{Node, NodeToAny, NodeType};
+use crate::position::Position;
+
+#[derive(Clone)]
+pub struct TestDefinitionNode {
+    function_def_node: FunctionDefinitionNode,
+    pos_start: Position,
+}
+
+impl TestDefinitionNode {
+    pub fn new(function_def_node: FunctionDefinitionNode, pos_start: Position) -> Self {
+        TestDefinitionNode {
+            function_def_node,
+            pos_start,
+        }
+    }
+
+    pub fn function_def_node(&self) -> &FunctionDefinitionNode {
+        &self.function_def_node
+    }
+}
+
+impl Display for TestDefinitionNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "test {}", self.function_def_node)
+    }
+}
+
+impl NodeToAny for TestDefinitionNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Node for TestDefinitionNode {
+    fn pos_start(&self) -> &Position {
+        &self.pos_start
+    }
+
+    fn pos_end(&self) -> &Position {
+        self.function_def_node.pos_end()
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::TestDef
+    }
+
+    fn box_clone(&self) -> Box<dyn Node> {
+        Box::new(self.clone())
+    }
+}
@@ -9,6 +9,7 @@ use crate::values::value_type::ValueType;
 pub struct VarDeclarationNode {
     var_name: String,
     var_type: Box<dyn ValueType>,
+    /// An `IgnoredNode` for variables declared without a value
     value_node: Box<dyn Node>,
     is_mutable: bool,
     pos_start: Position,
@@ -52,12 +53,15 @@ impl Display for VarDeclarationNode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "let{} {}: {} = {}",
+            "let{} {}: {}",
             if self.is_mutable { " mut" } else { "" },
             self.var_name,
-            self.var_type,
-            self.value_node
-        )
+            self.var_type
+        )?;
+        if self.value_node.node_type() != NodeType::Ignored {
+            write!(f, " = {}", self.value_node)?;
+        }
+        Ok(())
     }
 }
 
@@ -8,13 +8,19 @@ use crate::position::Position;
 pub struct WhileNode {
     condition_node: Box<dyn Node>,
     body_node: Box<dyn Node>,
+    label: Option<String>,
 }
 
 impl WhileNode {
-    pub fn new(condition_node: Box<dyn Node>, body_node: Box<dyn Node>) -> Self {
+    pub fn new(
+        condition_node: Box<dyn Node>,
+        body_node: Box<dyn Node>,
+        label: Option<String>,
+    ) -> Self {
         WhileNode {
             condition_node,
             body_node,
+            label,
         }
     }
 
@@ -25,6 +31,11 @@ impl WhileNode {
     pub fn body_node(&self) -> &Box<dyn Node> {
         &self.body_node
     }
+
+    /// The label `break` and `continue` in nested loops can refer to the loop with.
+    pub fn label(&self) -> &Option<String> {
+        &self.label
+    }
 }
 
 impl Display for WhileNode {
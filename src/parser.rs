@@ -10,6 +10,8 @@ use crate::nodes::accessor_node::AccessorNode;
 use crate::nodes::address_of_node::AddressOfNode;
 use crate::nodes::array_node::ArrayNode;
 use crate::nodes::asm_node::AssemblyNode;
+use crate::nodes::assert_node::AssertNode;
+use crate::nodes::assign_node::AssignNode;
 use crate::nodes::binop_node::BinOpNode;
 use crate::nodes::break_node::BreakNode;
 use crate::nodes::call_node::CallNode;
@@ -17,9 +19,11 @@ use crate::nodes::cast_node::CastNode;
 use crate::nodes::char_node::CharNode;
 use crate::nodes::const_def_node::ConstDefinitionNode;
 use crate::nodes::continue_node::ContinueNode;
+use crate::nodes::defer_node::DeferNode;
 use crate::nodes::dereference_node::DereferenceNode;
 use crate::nodes::extern_node::ExternNode;
 use crate::nodes::floating_point_node::FloatingPointNode;
+use crate::nodes::for_in_node::ForInNode;
 use crate::nodes::for_node::ForNode;
 use crate::nodes::functiondecl_node::FunctionDeclarationNode;
 use crate::nodes::functiondef_node::FunctionDefinitionNode;
@@ -28,11 +32,16 @@ use crate::nodes::if_node::elsecase::ElseCase;
 use crate::nodes::if_node::IfNode;
 use crate::nodes::ignored_node::IgnoredNode;
 use crate::nodes::import_node::ImportNode;
+use crate::nodes::lenof_node::LenOfNode;
 use crate::nodes::macro_def_node::MacroDefNode;
+use crate::nodes::memory_node::{MemoryNode, MemoryOperation};
 use crate::nodes::number_node::NumberNode;
+use crate::nodes::range_node::RangeNode;
 use crate::nodes::read_bytes_node::ReadBytesNode;
+use crate::nodes::reference_node::ReferenceNode;
 use crate::nodes::return_node::ReturnNode;
 use crate::nodes::sizeof_node::SizeOfNode;
+use crate::nodes::slice_node::SliceNode;
 use crate::nodes::statements_node::StatementsNode;
 use crate::nodes::static_decl_node::StaticDeclarationNode;
 use crate::nodes::static_def_node::StaticDefinitionNode;
@@ -40,6 +49,7 @@ use crate::nodes::string_node::StringNode;
 use crate::nodes::struct_def_node::StructDefinitionNode;
 use crate::nodes::struct_init_node::StructInitNode;
 use crate::nodes::syscall_node::SyscallNode;
+use crate::nodes::test_def_node::TestDefinitionNode;
 use crate::nodes::unaryop_node::UnaryOpNode;
 use crate::nodes::util::type_carrier_node::TypeCarrierNode;
 use crate::nodes::var_node::access::VarAccessNode;
@@ -47,9 +57,11 @@ use crate::nodes::var_node::assign::VarAssignNode;
 use crate::nodes::var_node::declare::VarDeclarationNode;
 use crate::nodes::while_node::WhileNode;
 use crate::nodes::{Node, NodeType};
+use crate::position::Position;
 use crate::results::parse::ParseResult;
 use crate::token::{Token, TokenType, TOKEN_FLAGS_IS_ASSIGN};
 use crate::values::value_size::ValueSize;
+use crate::values::value_type::array_type::ArrayType;
 use crate::values::value_type::bool_type::BoolType;
 use crate::values::value_type::char_type::CharType;
 use crate::values::value_type::f64_type::F64Type;
@@ -59,6 +71,7 @@ use crate::values::value_type::i32_type::I32Type;
 use crate::values::value_type::i64_type::I64Type;
 use crate::values::value_type::i8_type::I8Type;
 use crate::values::value_type::pointer_type::PointerType;
+use crate::values::value_type::slice_type::SliceType;
 use crate::values::value_type::string_type::StringType;
 use crate::values::value_type::struct_type::StructType;
 use crate::values::value_type::u16_type::U16Type;
@@ -171,6 +184,40 @@ impl<'a> Parser<'a> {
         self.token_index -= amount;
     }
 
+    /// Checks, whether the statement at the current token can only be parsed as a top level
+    /// statement. A structure definition is told apart from the initialization of a structure by
+    /// its fields, e.g. `struct point { x: u64 }` vs. `struct point {}`.
+    fn is_top_level_statement(&self) -> bool {
+        let token = self.current_token();
+
+        if token.matches_keyword("struct") {
+            return self
+                .tokens
+                .get(self.token_index + 3)
+                .is_some_and(|t| t.token_type() != TokenType::Rcurly);
+        }
+
+        if self.at_contextual_keyword("test", |next| next.matches_keyword("fun"))
+            || self.at_contextual_keyword("pub", |next| next.matches_keyword("fun"))
+        {
+            return true;
+        }
+
+        ["fun", "const", "import", "macro", "static", "extern"]
+            .iter()
+            .any(|keyword| token.matches_keyword(keyword))
+    }
+
+    /// Checks, whether the current token is the contextual keyword `keyword`. It's only a keyword
+    /// if the next token fits, e.g. `test` before `fun`, and can be used as a name otherwise.
+    fn at_contextual_keyword(&self, keyword: &str, is_followed_by: fn(&Token) -> bool) -> bool {
+        self.current_token().matches(TokenType::Identifier, keyword)
+            && self
+                .tokens
+                .get(self.token_index + 1)
+                .is_some_and(is_followed_by)
+    }
+
     pub fn parse(&mut self) -> Result<Box<dyn Node>, Error> {
         let mut res = ParseResult::new();
 
@@ -190,12 +237,69 @@ impl<'a> Parser<'a> {
         return Ok(stmts.unwrap());
     }
 
+    /// Parses the tokens as a list of statements, like they are typed into the REPL. Every
+    /// statement may either be a top level statement (e.g. a function definition) or a statement of
+    /// a function body (e.g. a variable declaration or an expression). The last statement doesn't
+    /// need to be terminated by a ';'.
+    pub fn parse_statements(&mut self) -> Result<Vec<Box<dyn Node>>, Error> {
+        let mut statements: Vec<Box<dyn Node>> = vec![];
+
+        // skip the '{' the lexer wraps the tokens in
+        self.advance();
+
+        loop {
+            while self.current_token().token_type() == TokenType::Newline {
+                self.advance();
+            }
+
+            if self.current_token().token_type() == TokenType::Rcurly {
+                break;
+            }
+
+            let is_top_level = self.is_top_level_statement();
+            let res = self.statement(is_top_level);
+            if res.has_error() {
+                return Err(res.error().as_ref().unwrap().clone());
+            }
+            statements.push(res.node().as_ref().unwrap().clone());
+
+            if self.current_token().token_type() != TokenType::Newline
+                && self.current_token().token_type() != TokenType::Rcurly
+            {
+                return Err(error::invalid_syntax_error(
+                    self.current_token().pos_start().clone(),
+                    self.current_token().pos_end().clone(),
+                    "Expected token ';' of type 'Newline'!",
+                ));
+            }
+        }
+
+        self.advance();
+
+        if self.current_token().token_type() != TokenType::Eof {
+            return Err(error::invalid_syntax_error(
+                self.current_token().pos_start().clone(),
+                self.current_token().pos_end().clone(),
+                "Expected end of file!",
+            ));
+        }
+
+        Ok(statements)
+    }
+
     // region Helper functions
 
     fn parse_intrinsic_type(&mut self) -> ParseResult {
         let mut res = ParseResult::new();
 
-        expect_token!(self, res, TokenType::Keyword, "intrinsic type");
+        if self.current_token().token_type() == TokenType::Lsquare {
+            return self.parse_array_type();
+        }
+
+        // `str` isn't a keyword, so it can still be used as a name outside of types
+        if !self.current_token().matches(TokenType::Identifier, "str") {
+            expect_token!(self, res, TokenType::Keyword, "intrinsic type");
+        }
 
         let s = self
             .current_token()
@@ -215,6 +319,7 @@ impl<'a> Parser<'a> {
             "i8" => Box::new(I8Type::new()),
             "f64" => Box::new(F64Type::new()),
             "string" => Box::new(StringType::new()),
+            "str" => Box::new(SliceType::new(Box::new(CharType::new()))),
             "bool" => Box::new(BoolType::new()),
             "char" => Box::new(CharType::new()),
             "void" => Box::new(VoidType::new()),
@@ -248,6 +353,77 @@ impl<'a> Parser<'a> {
 
         advance!(self, res);
 
+        self.parse_pointer_type(base_type)
+    }
+
+    /// Parses an array type like `[u8; 16]`, followed by optional pointers, or a slice type like
+    /// `[]u8`. The pointers after a slice type belong to its element type.
+    fn parse_array_type(&mut self) -> ParseResult {
+        let mut res = ParseResult::new();
+
+        expect_token!(self, res, TokenType::Lsquare, "[");
+
+        advance!(self, res);
+
+        if self.current_token().token_type() == TokenType::Rsquare {
+            advance!(self, res);
+
+            let type_carrier = res.register_res(self.parse_intrinsic_type());
+            if res.has_error() {
+                return res;
+            }
+            let element_type = type_carrier
+                .unwrap()
+                .as_any()
+                .downcast_ref::<TypeCarrierNode>()
+                .unwrap()
+                .carried_type()
+                .clone();
+
+            res.success(Box::new(TypeCarrierNode::new(Box::new(SliceType::new(
+                element_type,
+            )))));
+            return res;
+        }
+
+        let type_carrier = res.register_res(self.parse_intrinsic_type());
+        if res.has_error() {
+            return res;
+        }
+        let element_type = type_carrier
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TypeCarrierNode>()
+            .unwrap()
+            .carried_type()
+            .clone();
+
+        expect_token!(self, res, TokenType::Newline, ";");
+
+        advance!(self, res);
+
+        expect_token!(self, res, TokenType::U64, "length");
+
+        let length = self
+            .current_token()
+            .token_value()
+            .as_ref()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+
+        advance!(self, res);
+
+        expect_token!(self, res, TokenType::Rsquare, "]");
+
+        advance!(self, res);
+
+        self.parse_pointer_type(Box::new(ArrayType::new(element_type, length)))
+    }
+
+    fn parse_pointer_type(&mut self, base_type: Box<dyn ValueType>) -> ParseResult {
+        let mut res = ParseResult::new();
+
         let mut final_type = base_type;
         while self.current_token().token_type() == TokenType::Mul {
             advance!(self, res);
@@ -311,13 +487,30 @@ impl<'a> Parser<'a> {
         res
     }
 
-    fn for_expr(&mut self) -> ParseResult {
+    fn for_expr(&mut self, label: Option<String>) -> ParseResult {
         let mut res = ParseResult::new();
 
+        let pos_start = self.current_token().pos_start().clone();
+
         expect_keyword!(self, res, "for");
 
         advance!(self, res);
 
+        if self.current_token().token_type() == TokenType::Identifier
+            && self
+                .tokens
+                .get(self.token_index + 1)
+                .is_some_and(|t| t.matches(TokenType::Identifier, "in"))
+        {
+            let for_in = res.register_res(self.for_in_expr(pos_start, label));
+            if res.has_error() {
+                return res;
+            }
+
+            res.success(for_in.unwrap());
+            return res;
+        }
+
         let init_expr = res.register_res(self.statement(false));
         if res.has_error() {
             return res;
@@ -351,11 +544,117 @@ impl<'a> Parser<'a> {
             condition.unwrap(),
             next_expr.unwrap(),
             statements.unwrap(),
+            label,
+        )));
+        res
+    }
+
+    /// Parses the rest of a `for <name> in <iterable> { ... }` loop, starting at the name of the
+    /// variable. The iterable is either a range `a..b`, which may be wrapped in parentheses to
+    /// call `.step(k)` and `.rev()` on it, or an expression evaluating to an array or a slice.
+    fn for_in_expr(&mut self, pos_start: Position, label: Option<String>) -> ParseResult {
+        let mut res = ParseResult::new();
+
+        expect_token_value!(self, res);
+        let var_name = self.current_token().token_value().as_ref().unwrap().clone();
+
+        advance!(self, res);
+        advance!(self, res);
+
+        let iterable_start = self.current_token().pos_start().clone();
+        let mut is_parenthesized = self.current_token().token_type() == TokenType::Lparen;
+        if is_parenthesized {
+            advance!(self, res);
+        }
+
+        let mut iterable = res.register_res(self.expression());
+        if res.has_error() {
+            return res;
+        }
+
+        // e.g. `(a as i32)..b`, where only the start of the range is parenthesized
+        if is_parenthesized && self.current_token().token_type() != TokenType::DotDot {
+            expect_token!(self, res, TokenType::Rparen, ")");
+            advance!(self, res);
+            is_parenthesized = false;
+        }
+
+        if self.current_token().token_type() == TokenType::DotDot {
+            advance!(self, res);
+
+            let end = res.register_res(self.expression());
+            if res.has_error() {
+                return res;
+            }
+
+            let mut pos_end = end.as_ref().unwrap().pos_end().clone();
+            let mut step = None;
+            let mut is_reversed = false;
+            if is_parenthesized {
+                expect_token!(self, res, TokenType::Rparen, ")");
+                pos_end = self.current_token().pos_end().clone();
+                advance!(self, res);
+
+                while self.current_token().token_type() == TokenType::Dot {
+                    advance!(self, res);
+
+                    expect_token!(self, res, TokenType::Identifier, "step or rev");
+                    expect_token_value!(self, res);
+                    let method = self.current_token().clone();
+                    let method_name = method.token_value().as_ref().unwrap().as_str();
+
+                    advance!(self, res);
+                    expect_token!(self, res, TokenType::Lparen, "(");
+                    advance!(self, res);
+
+                    if method_name == "step" && step.is_none() {
+                        step = res.register_res(self.expression());
+                        if res.has_error() {
+                            return res;
+                        }
+                    } else if method_name == "rev" && !is_reversed {
+                        is_reversed = true;
+                    } else {
+                        res.failure(error::invalid_syntax_error(
+                            method.pos_start().clone(),
+                            method.pos_end().clone(),
+                            "Expected a single call of 'step' or 'rev'!",
+                        ));
+                        return res;
+                    }
+
+                    expect_token!(self, res, TokenType::Rparen, ")");
+                    pos_end = self.current_token().pos_end().clone();
+                    advance!(self, res);
+                }
+            }
+
+            iterable = Some(Box::new(RangeNode::new(
+                iterable.unwrap(),
+                end.unwrap(),
+                step,
+                is_reversed,
+                iterable_start,
+                pos_end,
+            )));
+        }
+
+        let statements = res.register_res(self.statements(false));
+        if res.has_error() {
+            return res;
+        }
+
+        res.success(Box::new(ForInNode::new(
+            var_name,
+            iterable.unwrap(),
+            statements.unwrap(),
+            label,
+            pos_start,
         )));
         res
     }
 
-    fn while_expr(&mut self) -> ParseResult {
+    fn while_expr(&mut self, label: Option<String>) -> ParseResult {
         let mut res = ParseResult::new();
 
         expect_keyword!(self, res, "while");
@@ -372,7 +671,11 @@ impl<'a> Parser<'a> {
             return res;
         }
 
-        res.success(Box::new(WhileNode::new(condition.unwrap(), stmts.unwrap())));
+        res.success(Box::new(WhileNode::new(
+            condition.unwrap(),
+            stmts.unwrap(),
+            label,
+        )));
         res
     }
 
@@ -512,11 +815,23 @@ impl<'a> Parser<'a> {
         advance!(self, res);
 
         let mut args: Vec<(String, Box<dyn ValueType>)> = vec![];
+        let mut arg_positions: Vec<Position> = vec![];
+        let mut is_variadic = false;
+
+        if self.current_token().token_type() == TokenType::Ellipsis {
+            res.failure(error::invalid_syntax_error(
+                self.current_token().pos_start().clone(),
+                self.current_token().pos_end().clone(),
+                "Variadic functions need at least one argument before '...'!",
+            ));
+            return res;
+        }
 
         if self.current_token().token_type() == TokenType::Identifier {
             expect_token_value!(self, res);
 
             let arg_name = self.current_token().token_value().as_ref().unwrap().clone();
+            let arg_pos = self.current_token().pos_start().clone();
 
             for (key, _) in &args {
                 if &arg_name == key {
@@ -535,8 +850,6 @@ impl<'a> Parser<'a> {
 
             advance!(self, res);
 
-            expect_token!(self, res, TokenType::Keyword, "type");
-
             let type_carrier = res.register_res(self.parse_intrinsic_type());
             if res.has_error() {
                 return res;
@@ -550,13 +863,21 @@ impl<'a> Parser<'a> {
                 .clone();
 
             args.push((arg_name, arg_type));
+            arg_positions.push(arg_pos);
 
             while self.current_token().token_type() == TokenType::Comma {
                 advance!(self, res);
 
+                if self.current_token().token_type() == TokenType::Ellipsis {
+                    is_variadic = true;
+                    advance!(self, res);
+                    break;
+                }
+
                 expect_token_value!(self, res);
 
                 let arg_name = self.current_token().token_value().as_ref().unwrap().clone();
+                let arg_pos = self.current_token().pos_start().clone();
 
                 for (key, _) in &args {
                     if &arg_name == key {
@@ -575,8 +896,6 @@ impl<'a> Parser<'a> {
 
                 advance!(self, res);
 
-                expect_token!(self, res, TokenType::Keyword, "type");
-
                 let type_carrier = res.register_res(self.parse_intrinsic_type());
                 if res.has_error() {
                     return res;
@@ -590,6 +909,7 @@ impl<'a> Parser<'a> {
                     .clone();
 
                 args.push((arg_name, arg_type));
+                arg_positions.push(arg_pos);
             }
         }
 
@@ -601,8 +921,6 @@ impl<'a> Parser<'a> {
 
         advance!(self, res);
 
-        expect_token!(self, res, TokenType::Keyword, "type");
-
         let type_carrier = res.register_res(self.parse_intrinsic_type());
         if res.has_error() {
             return res;
@@ -620,25 +938,39 @@ impl<'a> Parser<'a> {
                 func_name,
                 args,
                 return_type,
+                is_variadic,
                 pos_start,
                 self.current_token().pos_end().clone(),
             )));
             return res;
         }
 
+        if is_variadic {
+            res.failure(error::invalid_syntax_error(
+                pos_start,
+                self.current_token().pos_end().clone(),
+                "Only extern functions can be variadic!",
+            ));
+            return res;
+        }
+
         let func_body = res.register_res(self.statements(false));
         if res.has_error() {
             return res;
         }
 
-        res.success(Box::new(FunctionDefinitionNode::new(
-            func_name,
-            args,
-            return_type,
-            func_body.unwrap(),
-            generics,
-            pos_start,
-        )));
+        res.success(Box::new(
+            FunctionDefinitionNode::new(
+                func_name,
+                args,
+                return_type,
+                func_body.unwrap(),
+                generics,
+                false,
+                pos_start,
+            )
+            .with_arg_positions(arg_positions),
+        ));
         res
     }
 
@@ -664,7 +996,9 @@ impl<'a> Parser<'a> {
             return res;
         }
 
-        while ops.contains(&self.current_token().token_type()) {
+        while ops.contains(&self.current_token().token_type())
+            && !self.current_token().has_flag(TOKEN_FLAGS_IS_ASSIGN)
+        {
             let op_token = self.current_token().clone();
 
             advance!(self, res);
@@ -698,32 +1032,35 @@ impl<'a> Parser<'a> {
             advance!(self, res);
         }
 
-        let statement = res.register_res(self.statement(is_top_level));
-        if res.has_error() {
-            return res;
-        }
-        statements.push(statement.unwrap());
-
-        expect_token!(self, res, TokenType::Newline, ";");
-        advance!(self, res);
-
-        while self.current_token().token_type() != TokenType::Eof
-            && self.current_token().token_type() != TokenType::Rcurly
-        {
+        let mut has_value = false;
+        loop {
             let statement = res.register_res(self.statement(is_top_level));
             if res.has_error() {
                 return res;
             }
             statements.push(statement.unwrap());
 
+            // the last statement of a block may leave out the ';' to become its value
+            if !is_top_level && self.current_token().token_type() == TokenType::Rcurly {
+                has_value = true;
+                break;
+            }
+
             expect_token!(self, res, TokenType::Newline, ";");
             advance!(self, res);
+
+            if self.current_token().token_type() == TokenType::Eof
+                || self.current_token().token_type() == TokenType::Rcurly
+            {
+                break;
+            }
         }
 
         advance!(self, res);
 
         res.success(Box::new(StatementsNode::new(
             statements,
+            has_value,
             pos_start,
             self.current_token().pos_end().clone(),
         )));
@@ -746,6 +1083,77 @@ impl<'a> Parser<'a> {
                 return res;
             }
 
+            if self.at_contextual_keyword("pub", |next| next.matches_keyword("fun")) {
+                advance!(self, res);
+
+                expect_keyword!(self, res, "fun");
+
+                let func_def = res.register_res(self.function_def());
+                if res.has_error() {
+                    return res;
+                }
+                let func_def = func_def.unwrap();
+
+                if func_def.node_type() != NodeType::FunctionDef {
+                    res.failure(error::invalid_syntax_error(
+                        pos_start,
+                        func_def.pos_end().clone(),
+                        "Public functions must be defined with a body!",
+                    ));
+                    return res;
+                }
+
+                let func_def = func_def
+                    .as_any()
+                    .downcast_ref::<FunctionDefinitionNode>()
+                    .unwrap();
+
+                res.success(Box::new(
+                    FunctionDefinitionNode::new(
+                        func_def.var_name().to_string(),
+                        func_def.args().clone(),
+                        func_def.return_type().clone(),
+                        func_def.body_node().clone(),
+                        func_def.generics().clone(),
+                        true,
+                        pos_start,
+                    )
+                    .with_arg_positions(func_def.arg_positions().clone()),
+                ));
+                return res;
+            }
+
+            if self.at_contextual_keyword("test", |next| next.matches_keyword("fun")) {
+                advance!(self, res);
+
+                expect_keyword!(self, res, "fun");
+
+                let func_def = res.register_res(self.function_def());
+                if res.has_error() {
+                    return res;
+                }
+                let func_def = func_def.unwrap();
+
+                if func_def.node_type() != NodeType::FunctionDef {
+                    res.failure(error::invalid_syntax_error(
+                        pos_start,
+                        func_def.pos_end().clone(),
+                        "Tests must be defined with a body!",
+                    ));
+                    return res;
+                }
+
+                res.success(Box::new(TestDefinitionNode::new(
+                    func_def
+                        .as_any()
+                        .downcast_ref::<FunctionDefinitionNode>()
+                        .unwrap()
+                        .clone(),
+                    pos_start,
+                )));
+                return res;
+            }
+
             if self.current_token().matches_keyword("const") {
                 let pos_start = self.current_token().pos_start().clone();
 
@@ -805,10 +1213,12 @@ impl<'a> Parser<'a> {
                 advance!(self, res);
 
                 let mut fields: Vec<(String, Box<dyn ValueType>)> = Vec::new();
+                let mut field_positions: Vec<Position> = Vec::new();
                 loop {
                     expect_token!(self, res, TokenType::Identifier, "name of field");
 
                     let field_name = self.current_token().token_value().as_ref().unwrap().clone();
+                    field_positions.push(self.current_token().pos_start().clone());
 
                     advance!(self, res);
 
@@ -845,6 +1255,7 @@ impl<'a> Parser<'a> {
                 res.success(Box::new(StructDefinitionNode::new(
                     name,
                     fields,
+                    field_positions,
                     pos_start,
                     self.current_token().pos_end().clone(),
                 )));
@@ -1075,6 +1486,28 @@ impl<'a> Parser<'a> {
                 return res;
             }
 
+            if self.at_contextual_keyword("assert", |next| next.token_type() == TokenType::Lparen) {
+                advance!(self, res);
+
+                expect_token!(self, res, TokenType::Lparen, "(");
+                advance!(self, res);
+
+                let condition = res.register_res(self.expression());
+                if res.has_error() {
+                    return res;
+                }
+
+                expect_token!(self, res, TokenType::Rparen, ")");
+                advance!(self, res);
+
+                res.success(Box::new(AssertNode::new(
+                    condition.unwrap(),
+                    pos_start,
+                    self.current_token().pos_end().clone(),
+                )));
+                return res;
+            }
+
             if self.current_token().matches_keyword("return") {
                 advance!(self, res);
 
@@ -1094,17 +1527,48 @@ impl<'a> Parser<'a> {
             if self.current_token().matches_keyword("continue") {
                 advance!(self, res);
 
+                let mut label = None;
+                if self.current_token().token_type() == TokenType::Label {
+                    label = self.current_token().token_value().clone();
+                    advance!(self, res);
+                }
+
                 res.success(Box::new(ContinueNode::new(
+                    label,
                     pos_start,
                     self.current_token().pos_end().clone(),
                 )));
                 return res;
             }
 
+            if self.at_contextual_keyword("defer", |next| {
+                matches!(
+                    next.token_type(),
+                    TokenType::Identifier | TokenType::Keyword | TokenType::Lcurly | TokenType::Mul
+                )
+            }) {
+                advance!(self, res);
+
+                let statement = res.register_res(self.statement(false));
+                if res.has_error() {
+                    return res;
+                }
+
+                res.success(Box::new(DeferNode::new(statement.unwrap(), pos_start)));
+                return res;
+            }
+
             if self.current_token().matches_keyword("break") {
                 advance!(self, res);
 
+                let mut label = None;
+                if self.current_token().token_type() == TokenType::Label {
+                    label = self.current_token().token_value().clone();
+                    advance!(self, res);
+                }
+
                 res.success(Box::new(BreakNode::new(
+                    label,
                     pos_start,
                     self.current_token().pos_end().clone(),
                 )));
@@ -1217,8 +1681,6 @@ impl<'a> Parser<'a> {
 
             advance!(self, res);
 
-            expect_token!(self, res, TokenType::Keyword, "type");
-
             let type_carrier = res.register_res(self.parse_intrinsic_type());
             if res.has_error() {
                 return res;
@@ -1231,21 +1693,24 @@ impl<'a> Parser<'a> {
                 .carried_type()
                 .clone();
 
-            expect_token!(self, res, TokenType::Eq, "=");
-
-            advance!(self, res);
+            // without a value, the variable has to be assigned before it's read
+            let value_node: Box<dyn Node> = if self.current_token().token_type() == TokenType::Eq {
+                advance!(self, res);
 
-            let expr = res.register_res(self.expression());
-            if res.has_error() {
-                return res;
-            }
+                let expr = res.register_res(self.expression());
+                if res.has_error() {
+                    return res;
+                }
+                expr.unwrap()
+            } else {
+                Box::new(IgnoredNode::new(
+                    self.current_token().pos_start().clone(),
+                    self.current_token().pos_start().clone(),
+                ))
+            };
 
             res.success(Box::new(VarDeclarationNode::new(
-                var_name,
-                var_type,
-                expr.unwrap(),
-                is_mutable,
-                pos_start,
+                var_name, var_type, value_node, is_mutable, pos_start,
             )));
             return res;
         }
@@ -1292,7 +1757,57 @@ impl<'a> Parser<'a> {
             return res;
         }
 
-        let node = res.register_res(self.bin_operation(
+        let memory_operation = [
+            MemoryOperation::Set,
+            MemoryOperation::Copy,
+            MemoryOperation::Move,
+        ]
+        .into_iter()
+        .find(|operation| {
+            self.at_contextual_keyword(operation.keyword(), |next| {
+                next.token_type() == TokenType::Lsquare
+            })
+        });
+        if let Some(operation) = memory_operation {
+            advance!(self, res);
+
+            expect_token!(self, res, TokenType::Lsquare, "[");
+
+            advance!(self, res);
+
+            let mut exprs: Vec<Box<dyn Node>> = vec![];
+            for i in 0..3 {
+                if i > 0 {
+                    expect_token!(self, res, TokenType::Comma, ",");
+
+                    advance!(self, res);
+                }
+
+                let new_expr = res.register_res(self.expression());
+                if res.has_error() {
+                    return res;
+                }
+
+                exprs.push(new_expr.unwrap());
+            }
+
+            expect_token!(self, res, TokenType::Rsquare, "]");
+
+            advance!(self, res);
+
+            let [destination, source, count]: [Box<dyn Node>; 3] = exprs.try_into().ok().unwrap();
+            res.success(Box::new(MemoryNode::new(
+                operation,
+                destination,
+                source,
+                count,
+                pos_start,
+                self.current_token().pos_end().clone(),
+            )));
+            return res;
+        }
+
+        let mut node = res.register_res(self.bin_operation(
             BinOpFunction::Comp,
             vec![TokenType::And, TokenType::Or],
             BinOpFunction::Comp,
@@ -1335,11 +1850,59 @@ impl<'a> Parser<'a> {
 
             advance!(self, res);
 
-            res.success(Box::new(ReadBytesNode::new(
+            node = Some(Box::new(ReadBytesNode::new(
                 node.unwrap(),
                 size,
                 self.current_token().pos_end().clone(),
             )));
+        }
+
+        let is_compound_assign = self.current_token().has_flag(TOKEN_FLAGS_IS_ASSIGN)
+            && (self.current_token().token_type() == TokenType::Plus
+                || self.current_token().token_type() == TokenType::Minus
+                || self.current_token().token_type() == TokenType::Mul
+                || self.current_token().token_type() == TokenType::Div
+                || self.current_token().token_type() == TokenType::Modulo
+                || self.current_token().token_type() == TokenType::BitAnd
+                || self.current_token().token_type() == TokenType::BitOr
+                || self.current_token().token_type() == TokenType::BitXor
+                || self.current_token().token_type() == TokenType::BitShl
+                || self.current_token().token_type() == TokenType::BitShr);
+        if is_compound_assign || self.current_token().token_type() == TokenType::Eq {
+            let op_token = if is_compound_assign {
+                Some(self.current_token().clone())
+            } else {
+                None
+            };
+
+            advance!(self, res);
+
+            let expr = res.register_res(self.expression());
+            if res.has_error() {
+                return res;
+            }
+
+            let target = node.unwrap();
+            if target.node_type() != NodeType::VarAccess {
+                res.success(Box::new(AssignNode::new(target, op_token, expr.unwrap())));
+                return res;
+            }
+
+            let var_name = target
+                .as_any()
+                .downcast_ref::<VarAccessNode>()
+                .unwrap()
+                .var_name()
+                .to_string();
+            let pos_start = target.pos_start().clone();
+            let value_node: Box<dyn Node> = match op_token {
+                Some(op_token) => Box::new(BinOpNode::new(target, op_token, expr.unwrap())),
+                None => expr.unwrap(),
+            };
+
+            res.success(Box::new(VarAssignNode::new(
+                var_name, value_node, pos_start,
+            )));
             return res;
         }
 
@@ -1437,11 +2000,7 @@ impl<'a> Parser<'a> {
             }
 
             if factor.as_ref().unwrap().node_type() != NodeType::VarAccess {
-                res.failure(error::invalid_syntax_error(
-                    pos_start,
-                    self.current_token().pos_end().clone(),
-                    "Expected variable!",
-                ));
+                res.success(Box::new(ReferenceNode::new(factor.unwrap(), pos_start)));
                 return res;
             }
 
@@ -1562,6 +2121,28 @@ impl<'a> Parser<'a> {
                     return res;
                 }
 
+                if self.current_token().token_type() == TokenType::DotDot {
+                    advance!(self, res);
+
+                    let end = res.register_res(self.expression());
+                    if res.has_error() {
+                        return res;
+                    }
+
+                    expect_token!(self, res, TokenType::Rsquare, "]");
+
+                    atom = Some(Box::new(SliceNode::new(
+                        atom.unwrap(),
+                        expr.unwrap(),
+                        end.unwrap(),
+                        s_pos_start,
+                        self.current_token().pos_end().clone(),
+                    )));
+
+                    advance!(self, res);
+                    continue;
+                }
+
                 expect_token!(self, res, TokenType::Rsquare, "]");
 
                 atom = Some(Box::new(BinOpNode::new(
@@ -1581,6 +2162,7 @@ impl<'a> Parser<'a> {
                 atom = Some(Box::new(AccessorNode::new(
                     atom.unwrap(),
                     accessor,
+                    self.current_token().pos_start().clone(),
                     self.current_token().pos_end().clone(),
                 )));
             }
@@ -1647,67 +2229,37 @@ impl<'a> Parser<'a> {
             advance!(self, res);
 
             node = Box::new(CharNode::new(actual_char, pos_start, pos_end));
-        } else if self.current_token().token_type() == TokenType::Identifier {
-            let var_name = self.current_token().token_value().as_ref().unwrap().clone();
-            let pos_start = self.current_token().pos_start().clone();
+        } else if self
+            .at_contextual_keyword("lenof", |next| next.token_type() == TokenType::Lsquare)
+        {
+            advance!(self, res);
+
+            expect_token!(self, res, TokenType::Lsquare, "[");
 
             advance!(self, res);
 
-            if self.macros.contains_key(&var_name) {
-                res.success(self.macros[&var_name].clone());
+            let expr = res.register_res(self.expression());
+            if res.has_error() {
                 return res;
             }
 
-            if self.current_token().has_flag(TOKEN_FLAGS_IS_ASSIGN)
-                && (self.current_token().token_type() == TokenType::Plus
-                    || self.current_token().token_type() == TokenType::Minus
-                    || self.current_token().token_type() == TokenType::Mul
-                    || self.current_token().token_type() == TokenType::Div
-                    || self.current_token().token_type() == TokenType::Modulo
-                    || self.current_token().token_type() == TokenType::BitAnd
-                    || self.current_token().token_type() == TokenType::BitOr
-                    || self.current_token().token_type() == TokenType::BitXor
-                    || self.current_token().token_type() == TokenType::BitShl
-                    || self.current_token().token_type() == TokenType::BitShr)
-            {
-                let op_token = self.current_token().clone();
-
-                advance!(self, res);
-
-                let assign_expr = res.register_res(self.expression());
-                if res.has_error() {
-                    return res;
-                }
+            expect_token!(self, res, TokenType::Rsquare, "]");
 
-                res.success(Box::new(VarAssignNode::new(
-                    var_name.clone(),
-                    Box::new(BinOpNode::new(
-                        Box::new(VarAccessNode::new(
-                            var_name,
-                            pos_start.clone(),
-                            self.current_token().pos_end().clone(),
-                        )),
-                        op_token,
-                        assign_expr.unwrap(),
-                    )),
-                    pos_start,
-                )));
-                return res;
-            }
+            advance!(self, res);
 
-            if self.current_token().token_type() == TokenType::Eq {
-                advance!(self, res);
+            node = Box::new(LenOfNode::new(
+                expr.unwrap(),
+                token.pos_start().clone(),
+                self.current_token().pos_end().clone(),
+            ));
+        } else if self.current_token().token_type() == TokenType::Identifier {
+            let var_name = self.current_token().token_value().as_ref().unwrap().clone();
+            let pos_start = self.current_token().pos_start().clone();
 
-                let expr = res.register_res(self.expression());
-                if res.has_error() {
-                    return res;
-                }
+            advance!(self, res);
 
-                res.success(Box::new(VarAssignNode::new(
-                    var_name,
-                    expr.unwrap(),
-                    pos_start,
-                )));
+            if self.macros.contains_key(&var_name) {
+                res.success(self.macros[&var_name].clone());
                 return res;
             }
 
@@ -1743,20 +2295,50 @@ impl<'a> Parser<'a> {
             }
 
             node = arr_expr.unwrap();
+        } else if token.token_type() == TokenType::Label {
+            advance!(self, res);
+            expect_token!(self, res, TokenType::Colon, ":");
+            advance!(self, res);
+
+            let label = token.token_value().clone();
+            let loop_expr = if self.current_token().matches_keyword("while") {
+                res.register_res(self.while_expr(label))
+            } else if self.current_token().matches_keyword("for") {
+                res.register_res(self.for_expr(label))
+            } else {
+                res.failure(error::invalid_syntax_error(
+                    self.current_token().pos_start().clone(),
+                    self.current_token().pos_end().clone(),
+                    "Expected a loop after the label!",
+                ));
+                return res;
+            };
+            if res.has_error() {
+                return res;
+            }
+
+            node = loop_expr.unwrap();
         } else if token.matches_keyword("while") {
-            let while_expr = res.register_res(self.while_expr());
+            let while_expr = res.register_res(self.while_expr(None));
             if res.has_error() {
                 return res;
             }
 
             node = while_expr.unwrap();
         } else if token.matches_keyword("for") {
-            let for_expr = res.register_res(self.for_expr());
+            let for_expr = res.register_res(self.for_expr(None));
             if res.has_error() {
                 return res;
             }
 
             node = for_expr.unwrap();
+        } else if token.token_type() == TokenType::Lcurly {
+            let block = res.register_res(self.statements(false));
+            if res.has_error() {
+                return res;
+            }
+
+            node = block.unwrap();
         } else if token.matches_keyword("if") {
             let if_expr = res.register_res(self.if_expr());
             if res.has_error() {
@@ -8,7 +8,6 @@ pub struct ValidationResult {
     error: Option<Error>,
     value_type: Option<Box<dyn ValueType>>,
     node: Option<Box<dyn Node>>,
-    return_type: Option<Box<dyn ValueType>>,
 }
 
 impl ValidationResult {
@@ -17,7 +16,6 @@ impl ValidationResult {
             error: None,
             value_type: None,
             node: None,
-            return_type: None,
         }
     }
 
@@ -30,8 +28,6 @@ impl ValidationResult {
             return (None, None);
         }
 
-        self.return_type = res.return_type.clone();
-
         if !res.has_value_type() || !res.has_node() {
             return (None, None);
         }
@@ -52,10 +48,6 @@ impl ValidationResult {
         self.node = Some(node);
     }
 
-    pub fn success_return(&mut self, return_type: Box<dyn ValueType>) {
-        self.return_type = Some(return_type);
-    }
-
     pub fn failure(&mut self, error: Error) {
         self.error = Some(error);
     }
@@ -69,16 +61,10 @@ impl ValidationResult {
     pub fn has_node(&self) -> bool {
         self.node.is_some()
     }
-    pub fn has_return_type(&self) -> bool {
-        self.return_type.is_some()
-    }
 
     pub fn value_type(&self) -> &Option<Box<dyn ValueType>> {
         &self.value_type
     }
-    pub fn return_type(&self) -> &Option<Box<dyn ValueType>> {
-        &self.return_type
-    }
     pub fn node(&self) -> &Option<Box<dyn Node>> {
         &self.node
     }
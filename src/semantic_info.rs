@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use crate::position::Position;
+use crate::values::value_type::ValueType;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeclarationKind {
+    Variable,
+    Function,
+    Static,
+    Struct,
+    Field,
+}
+
+#[derive(Clone)]
+pub struct Declaration {
+    name: String,
+    kind: DeclarationKind,
+    value_type: Option<Box<dyn ValueType>>,
+    pos: Position,
+}
+
+impl Declaration {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn kind(&self) -> DeclarationKind {
+        self.kind
+    }
+    pub fn value_type(&self) -> &Option<Box<dyn ValueType>> {
+        &self.value_type
+    }
+    pub fn pos(&self) -> &Position {
+        &self.pos
+    }
+}
+
+/// A use of a name in the source (e.g. a variable access, a call or a field access),
+/// together with the position it resolved to.
+#[derive(Clone)]
+pub struct Reference {
+    name: String,
+    pos: Position,
+    span: usize,
+    definition: Declaration,
+}
+
+impl Reference {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn pos(&self) -> &Position {
+        &self.pos
+    }
+    pub fn definition(&self) -> &Declaration {
+        &self.definition
+    }
+
+    /// Checks, whether the given (zero based) line and column lie on the referencing name.
+    pub fn contains(&self, line: usize, col: usize) -> bool {
+        *self.pos.line() == line && *self.pos.col() <= col && col < *self.pos.col() + self.span
+    }
+}
+
+/// Information collected by the `Validator` while validating, used by tooling like the language
+/// server. Collection is opt-in, see `Validator::new_with_info`.
+#[derive(Clone, Default)]
+pub struct SemanticInfo {
+    declarations: Vec<Declaration>,
+    references: Vec<Reference>,
+    expression_types: Vec<(Position, Box<dyn ValueType>)>,
+    struct_declarations: HashMap<String, (Declaration, Vec<Declaration>)>,
+}
+
+impl SemanticInfo {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn declare(
+        &mut self,
+        name: &str,
+        kind: DeclarationKind,
+        value_type: Option<Box<dyn ValueType>>,
+        pos: &Position,
+    ) {
+        self.declarations.push(Declaration {
+            name: name.to_string(),
+            kind,
+            value_type,
+            pos: pos.clone(),
+        });
+    }
+
+    pub fn declare_struct(
+        &mut self,
+        name: &str,
+        pos: &Position,
+        fields: Vec<(String, Box<dyn ValueType>, Position)>,
+    ) {
+        let declaration = Declaration {
+            name: name.to_string(),
+            kind: DeclarationKind::Struct,
+            value_type: None,
+            pos: pos.clone(),
+        };
+
+        let fields = fields
+            .into_iter()
+            .map(|(field_name, field_type, field_pos)| Declaration {
+                name: field_name,
+                kind: DeclarationKind::Field,
+                value_type: Some(field_type),
+                pos: field_pos,
+            })
+            .collect();
+
+        self.declarations.push(declaration.clone());
+        self.struct_declarations
+            .insert(name.to_string(), (declaration, fields));
+    }
+
+    pub fn reference(
+        &mut self,
+        name: &str,
+        pos: &Position,
+        kind: DeclarationKind,
+        value_type: Option<Box<dyn ValueType>>,
+        definition_pos: &Position,
+    ) {
+        self.references.push(Reference {
+            name: name.to_string(),
+            pos: pos.clone(),
+            span: name.chars().count(),
+            definition: Declaration {
+                name: name.to_string(),
+                kind,
+                value_type,
+                pos: definition_pos.clone(),
+            },
+        });
+    }
+
+    /// References a structure. As the position of a structure type usually points to the `struct`
+    /// keyword, the span covers the keyword as well.
+    pub fn reference_struct(&mut self, name: &str, pos: &Position) {
+        if let Some((declaration, _)) = self.struct_declarations.get(name) {
+            let definition = declaration.clone();
+            self.references.push(Reference {
+                name: name.to_string(),
+                pos: pos.clone(),
+                span: "struct ".len() + name.chars().count(),
+                definition,
+            });
+        }
+    }
+
+    pub fn reference_field(&mut self, struct_name: &str, field_name: &str, pos: &Position) {
+        let field = self
+            .struct_fields(struct_name)
+            .and_then(|fields| fields.iter().find(|f| f.name() == field_name))
+            .cloned();
+
+        if let Some(definition) = field {
+            self.references.push(Reference {
+                name: field_name.to_string(),
+                pos: pos.clone(),
+                span: field_name.chars().count(),
+                definition,
+            });
+        }
+    }
+
+    pub fn expression_type(&mut self, pos: &Position, value_type: Box<dyn ValueType>) {
+        self.expression_types.push((pos.clone(), value_type));
+    }
+
+    pub fn declarations(&self) -> &Vec<Declaration> {
+        &self.declarations
+    }
+    pub fn references(&self) -> &Vec<Reference> {
+        &self.references
+    }
+    pub fn expression_types(&self) -> &Vec<(Position, Box<dyn ValueType>)> {
+        &self.expression_types
+    }
+
+    pub fn struct_fields(&self, struct_name: &str) -> Option<&Vec<Declaration>> {
+        self.struct_declarations
+            .get(struct_name)
+            .map(|(_, fields)| fields)
+    }
+
+    /// Finds the reference at the given (zero based) line and column of the given file.
+    pub fn reference_at(
+        &self,
+        file: &std::path::Path,
+        line: usize,
+        col: usize,
+    ) -> Option<&Reference> {
+        self.references
+            .iter()
+            .find(|r| r.pos().file_name() == file && r.contains(line, col))
+    }
+}
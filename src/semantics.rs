@@ -1,23 +1,31 @@
-use std::collections::HashMap;
+use clap::ValueEnum;
+use std::collections::{HashMap, HashSet};
 use std::ops::IndexMut;
 
+use crate::constant;
 use crate::error;
 use crate::nodes::accessor_node::AccessorNode;
 use crate::nodes::address_of_node::AddressOfNode;
 use crate::nodes::array_node::ArrayNode;
 use crate::nodes::asm_node::AssemblyNode;
+use crate::nodes::assert_node::AssertNode;
+use crate::nodes::assign_node::AssignNode;
 use crate::nodes::binop_node::BinOpNode;
 use crate::nodes::break_node::BreakNode;
 use crate::nodes::call_node::CallNode;
 use crate::nodes::cast_node::CastNode;
 use crate::nodes::char_node::CharNode;
+use crate::nodes::check_node::{Check, CheckNode};
 use crate::nodes::const_def_node::ConstDefinitionNode;
 use crate::nodes::continue_node::ContinueNode;
+use crate::nodes::copy_node::CopyNode;
+use crate::nodes::defer_node::DeferNode;
 use crate::nodes::dereference_node::DereferenceNode;
 use crate::nodes::extern_node::ExternNode;
 use crate::nodes::f64_to_u64_node::F64ToU64Node;
 use crate::nodes::floating_binop_node::FloatingBinOpNode;
 use crate::nodes::floating_point_node::FloatingPointNode;
+use crate::nodes::for_in_node::ForInNode;
 use crate::nodes::for_node::ForNode;
 use crate::nodes::functiondecl_node::FunctionDeclarationNode;
 use crate::nodes::functiondef_node::FunctionDefinitionNode;
@@ -26,13 +34,18 @@ use crate::nodes::if_node::elsecase::ElseCase;
 use crate::nodes::if_node::IfNode;
 use crate::nodes::ignored_node::IgnoredNode;
 use crate::nodes::import_node::ImportNode;
+use crate::nodes::lenof_node::LenOfNode;
 use crate::nodes::macro_def_node::MacroDefNode;
+use crate::nodes::memory_node::{MemoryNode, MemoryOperation};
 use crate::nodes::number_node::NumberNode;
 use crate::nodes::offset_node::OffsetNode;
 use crate::nodes::pointer_assign_node::PointerAssignNode;
+use crate::nodes::range_node::RangeNode;
 use crate::nodes::read_bytes_node::ReadBytesNode;
+use crate::nodes::reference_node::ReferenceNode;
 use crate::nodes::return_node::ReturnNode;
 use crate::nodes::sizeof_node::SizeOfNode;
+use crate::nodes::slice_node::SliceNode;
 use crate::nodes::stack_allocation_node::StackAllocationNode;
 use crate::nodes::statements_node::StatementsNode;
 use crate::nodes::static_decl_node::StaticDeclarationNode;
@@ -41,6 +54,7 @@ use crate::nodes::string_node::StringNode;
 use crate::nodes::struct_def_node::StructDefinitionNode;
 use crate::nodes::struct_init_node::StructInitNode;
 use crate::nodes::syscall_node::SyscallNode;
+use crate::nodes::test_def_node::TestDefinitionNode;
 use crate::nodes::u64_to_f64_node::U64ToF64Node;
 use crate::nodes::unaryop_node::UnaryOpNode;
 use crate::nodes::var_node::access::VarAccessNode;
@@ -52,34 +66,102 @@ use crate::nodes::while_node::WhileNode;
 use crate::nodes::{Node, NodeType};
 use crate::position::Position;
 use crate::results::validation::ValidationResult;
+use crate::semantic_info::{DeclarationKind, SemanticInfo};
 use crate::symbol_table::Symbol;
 use crate::token::{Token, TokenType};
+use crate::utils::unescape;
+use crate::values::value_size::ValueSize;
+use crate::values::value_type::array_type::ArrayType;
 use crate::values::value_type::char_type::CharType;
 use crate::values::value_type::f64_type::F64Type;
 use crate::values::value_type::function_type::FunctionType;
 use crate::values::value_type::ignored_type::IgnoredType;
 use crate::values::value_type::pointer_type::PointerType;
+use crate::values::value_type::slice_type::{SliceType, SLICE_LENGTH_OFFSET};
 use crate::values::value_type::string_type::StringType;
 use crate::values::value_type::struct_type::StructType;
 use crate::values::value_type::u64_type::U64Type;
 use crate::values::value_type::void_type::VoidType;
 use crate::values::value_type::{ValueType, ValueTypes};
 
+/// A kind of check, that can be added to the program to catch bugs at runtime.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq)]
+pub enum RuntimeCheck {
+    /// Indices into arrays have to be less than their length
+    Bounds,
+    /// Dereferenced pointers can't be null
+    Null,
+    /// Integer '+', '-' and '*' can't overflow their type
+    Overflow,
+}
+
 #[derive(PartialEq, Debug)]
 enum ScopeType {
     Global,
     Function,
     Loop,
     Block,
+    Defer,
+}
+
+/// A loop enclosing the statement being validated
+struct LoopScope {
+    label: Option<String>,
+    /// The index of the scope of the loop body in `type_stack`
+    scope_index: usize,
+    /// Whether a `break` leaves the loop
+    has_break: bool,
+}
+
+/// What is known about all paths reaching the statement being validated
+#[derive(Clone, Default)]
+struct Flow {
+    /// Whether no path reaches the statement, because all of them returned or left a loop
+    diverged: bool,
+    /// The variables declared without a value, which aren't assigned on every path
+    unassigned: HashSet<String>,
+    /// The immutable variables declared without a value, which are assigned on some path
+    assigned: HashSet<String>,
+}
+
+impl Flow {
+    /// Combines what is known about two sets of paths, which meet at the same statement.
+    fn join(self, other: Flow) -> Flow {
+        if self.diverged {
+            return other;
+        }
+        if other.diverged {
+            return self;
+        }
+
+        Flow {
+            diverged: false,
+            unassigned: self.unassigned.union(&other.unassigned).cloned().collect(),
+            assigned: self.assigned.union(&other.assigned).cloned().collect(),
+        }
+    }
 }
 
 pub struct Validator {
     type_stack: Vec<HashMap<String, (Symbol, Position)>>,
     scope_stack: Vec<ScopeType>,
+    /// The statements deferred in each scope of `type_stack`, in the order of their declaration
+    deferred: Vec<Vec<Box<dyn Node>>>,
+    /// The enclosing loops, the innermost one last
+    loops: Vec<LoopScope>,
+    flow: Flow,
 
     current_function_return_type: Option<Box<dyn ValueType>>,
 
     structs: HashMap<String, Vec<(String, Box<dyn ValueType>)>>,
+
+    info: Option<SemanticInfo>,
+
+    tests: Vec<(String, Position)>,
+
+    checks: Vec<RuntimeCheck>,
+    /// The number of hidden variables declared for runtime checks, which keeps their names unique
+    check_vars: usize,
 }
 
 impl Validator {
@@ -87,11 +169,44 @@ impl Validator {
         Validator {
             type_stack: vec![HashMap::new()],
             scope_stack: vec![ScopeType::Global],
+            deferred: vec![vec![]],
+            loops: vec![],
+            flow: Flow::default(),
             current_function_return_type: None,
             structs: HashMap::new(),
+            info: None,
+            tests: vec![],
+            checks: vec![],
+            check_vars: 0,
         }
     }
 
+    /// Creates a validator, that additionally collects `SemanticInfo` (declarations, references and
+    /// expression types) while validating.
+    pub fn new_with_info() -> Self {
+        Validator {
+            info: Some(SemanticInfo::new()),
+            ..Validator::new()
+        }
+    }
+
+    /// Creates a validator, that adds the given checks to the validated program.
+    pub fn new_with_checks(checks: &[RuntimeCheck]) -> Self {
+        Validator {
+            checks: checks.to_vec(),
+            ..Validator::new()
+        }
+    }
+
+    pub fn info(&self) -> &Option<SemanticInfo> {
+        &self.info
+    }
+
+    /// The names and positions of all `test` functions, in the order they were defined.
+    pub fn tests(&self) -> &Vec<(String, Position)> {
+        &self.tests
+    }
+
     fn has_symbol(&self, name: &str) -> bool {
         for s in self.type_stack.iter().rev() {
             if s.contains_key(name) {
@@ -162,6 +277,7 @@ impl Validator {
     fn push_child_scope(&mut self, scope_type: ScopeType) {
         self.type_stack.push(HashMap::new());
         self.scope_stack.push(scope_type);
+        self.deferred.push(vec![]);
     }
 
     fn pop_child_scope(&mut self) {
@@ -171,6 +287,7 @@ impl Validator {
 
         self.type_stack.pop();
         self.scope_stack.pop();
+        self.deferred.pop();
     }
 
     fn is_in_scope_stack(&self, scope_type: ScopeType) -> bool {
@@ -182,6 +299,161 @@ impl Validator {
         false
     }
 
+    /// Returns copies of the statements deferred in the scopes from the innermost one down to the
+    /// scope at `scope_index`, in the order they run when these scopes are left.
+    fn deferred_since(&self, scope_index: usize) -> Vec<Box<dyn Node>> {
+        self.deferred[scope_index..]
+            .iter()
+            .rev()
+            .flat_map(|statements| statements.iter().rev().cloned())
+            .collect()
+    }
+
+    /// Runs the deferred statements before the node, which leaves their scopes.
+    fn run_deferred(deferred: Vec<Box<dyn Node>>, node: Box<dyn Node>) -> Box<dyn Node> {
+        if deferred.is_empty() {
+            return node;
+        }
+
+        let pos_start = node.pos_start().clone();
+        let pos_end = node.pos_end().clone();
+        let mut statements = deferred;
+        statements.push(node);
+        Box::new(StatementsNode::new(statements, false, pos_start, pos_end))
+    }
+
+    /// Stores the value in a hidden variable, so the deferred statements can run after it was
+    /// evaluated. Returns the statements to run and the node reading the value afterwards.
+    fn defer_value(
+        value_type: &Box<dyn ValueType>,
+        value_node: Box<dyn Node>,
+        deferred: Vec<Box<dyn Node>>,
+    ) -> (Vec<Box<dyn Node>>, Box<dyn Node>) {
+        let pos_start = value_node.pos_start().clone();
+        let pos_end = value_node.pos_end().clone();
+
+        let mut statements: Vec<Box<dyn Node>> = vec![Box::new(VarDeclarationNode::new(
+            String::from("defer.value"),
+            value_type.clone(),
+            value_node,
+            false,
+            pos_start.clone(),
+        ))];
+        statements.extend(deferred);
+        (
+            statements,
+            Box::new(VarTypedAccessNode::new(
+                String::from("defer.value"),
+                value_type.clone(),
+                pos_start,
+                pos_end,
+            )),
+        )
+    }
+
+    /// Returns the index in `loops` of the loop a `break` or `continue` with the label refers to.
+    /// `statement` is the name of the statement used in errors.
+    fn find_loop(
+        &self,
+        label: &Option<String>,
+        statement: &str,
+        pos_start: &Position,
+        pos_end: &Position,
+    ) -> Result<usize, error::Error> {
+        let target = match label {
+            Some(label) => self
+                .loops
+                .iter()
+                .rposition(|l| l.label.as_ref() == Some(label))
+                .ok_or_else(|| {
+                    error::semantic_error(
+                        pos_start.clone(),
+                        pos_end.clone(),
+                        format!("Label '{}' doesn't belong to an enclosing loop!", label).as_str(),
+                    )
+                })?,
+            None => self.loops.len().checked_sub(1).ok_or_else(|| {
+                error::semantic_error(
+                    pos_start.clone(),
+                    pos_end.clone(),
+                    format!("{} statement outside of loop!", statement).as_str(),
+                )
+            })?,
+        };
+
+        let innermost_defer = self
+            .scope_stack
+            .iter()
+            .rposition(|s| *s == ScopeType::Defer);
+        if innermost_defer.is_some_and(|defer| defer > self.loops[target].scope_index) {
+            return Err(error::semantic_error(
+                pos_start.clone(),
+                pos_end.clone(),
+                format!("{} statement can't leave a deferred statement!", statement).as_str(),
+            ));
+        }
+
+        Ok(target)
+    }
+
+    /// Declares the label of a loop, whose scope was just pushed.
+    fn push_loop(
+        &mut self,
+        label: &Option<String>,
+        pos_start: &Position,
+        pos_end: &Position,
+    ) -> Result<(), error::Error> {
+        if label.is_some() && self.loops.iter().any(|l| l.label == *label) {
+            return Err(error::semantic_error(
+                pos_start.clone(),
+                pos_end.clone(),
+                format!(
+                    "Label '{}' is already used by an enclosing loop!",
+                    label.as_ref().unwrap()
+                )
+                .as_str(),
+            ));
+        }
+
+        self.loops.push(LoopScope {
+            label: label.clone(),
+            scope_index: self.type_stack.len() - 1,
+            has_break: false,
+        });
+        Ok(())
+    }
+
+    /// Prepares validating code, which can run more than once or after the statements following
+    /// it, like loops and deferred statements. Immutable variables declared without a value might
+    /// already be assigned then. Returns the flow before the code, which has to be restored
+    /// afterwards.
+    fn enter_repeated(&mut self) -> Flow {
+        let flow = self.flow.clone();
+        self.flow.assigned.extend(flow.unassigned.iter().cloned());
+        flow
+    }
+
+    /// Continues after a loop with the paths, on which the condition is false. Assignments in the
+    /// body don't count, because it might not run at all. A loop, whose condition is always true,
+    /// can only be left by `break`.
+    fn leave_loop(
+        &mut self,
+        flow: Flow,
+        after_condition: Flow,
+        condition_node: &Box<dyn Node>,
+        loop_scope: &LoopScope,
+    ) {
+        let is_endless = constant::evaluate(condition_node)
+            .and_then(|condition| condition.bits())
+            .is_some_and(|condition| condition != 0);
+
+        self.flow = Flow {
+            diverged: after_condition.diverged || (is_endless && !loop_scope.has_break),
+            assigned: flow.assigned,
+            ..after_condition
+        };
+    }
+
     fn find_first_function(&self) -> Option<Box<dyn ValueType>> {
         if self.is_in_scope_stack(ScopeType::Function) {
             return self.current_function_return_type.clone();
@@ -189,10 +461,87 @@ impl Validator {
 
         None
     }
+
+    fn record_declaration(
+        &mut self,
+        name: &str,
+        kind: DeclarationKind,
+        value_type: &dyn ValueType,
+        pos: &Position,
+    ) {
+        // the hidden variables of `for` loops aren't visible to the user
+        if name.contains('.') {
+            return;
+        }
+
+        if let Some(info) = &mut self.info {
+            info.declare(name, kind, Some(value_type.box_clone()), pos);
+        }
+    }
+
+    fn record_reference(&mut self, name: &str, pos: &Position) {
+        if self.info.is_none() || name.contains('.') {
+            return;
+        }
+
+        let is_global = self.type_stack[0].contains_key(name)
+            && !self.type_stack[1..].iter().any(|s| s.contains_key(name));
+
+        if let Some((symbol, decl_pos)) = self.get_symbol(name) {
+            let kind = if symbol.value_type().value_type() == ValueTypes::Function {
+                DeclarationKind::Function
+            } else if is_global {
+                DeclarationKind::Static
+            } else {
+                DeclarationKind::Variable
+            };
+            let value_type = symbol.value_type().clone();
+            let decl_pos = decl_pos.clone();
+
+            self.info
+                .as_mut()
+                .unwrap()
+                .reference(name, pos, kind, Some(value_type), &decl_pos);
+        }
+    }
 }
 
 impl Validator {
     pub fn validate(&mut self, node: &Box<dyn Node>) -> ValidationResult {
+        let res = self.validate_node(node);
+
+        if let (Some(info), Some(value_type)) = (&mut self.info, res.value_type()) {
+            if value_type.value_type() != ValueTypes::Ignored {
+                info.expression_type(node.pos_start(), value_type.clone());
+            }
+        }
+
+        res
+    }
+
+    /// Validates a single statement in the global scope, like a line typed into the REPL. Unlike
+    /// `validate`, a statement that fails to validate leaves no declarations behind, so the
+    /// validator can still be used afterwards.
+    pub fn validate_global_statement(&mut self, node: &Box<dyn Node>) -> ValidationResult {
+        let globals = self.type_stack[0].clone();
+        let structs = self.structs.clone();
+        let test_count = self.tests.len();
+
+        let res = self.validate(node);
+
+        if res.has_error() {
+            self.type_stack.truncate(1);
+            self.scope_stack.truncate(1);
+            self.type_stack[0] = globals;
+            self.structs = structs;
+            self.tests.truncate(test_count);
+            self.current_function_return_type = None;
+        }
+
+        res
+    }
+
+    fn validate_node(&mut self, node: &Box<dyn Node>) -> ValidationResult {
         match node.node_type() {
             NodeType::Statements => self
                 .validate_statements_node(node.as_any().downcast_ref::<StatementsNode>().unwrap()),
@@ -222,6 +571,9 @@ impl Validator {
             ),
             NodeType::VarAssign => self
                 .validate_var_assign_node(node.as_any().downcast_ref::<VarAssignNode>().unwrap()),
+            NodeType::Assign => {
+                self.validate_assign_node(node.as_any().downcast_ref::<AssignNode>().unwrap())
+            }
             NodeType::VarAccess => self
                 .validate_var_access_node(node.as_any().downcast_ref::<VarAccessNode>().unwrap()),
             NodeType::FunctionDef => self.validate_function_def_node(
@@ -246,15 +598,24 @@ impl Validator {
             NodeType::Continue => {
                 self.validate_continue_node(node.as_any().downcast_ref::<ContinueNode>().unwrap())
             }
+            NodeType::Defer => {
+                self.validate_defer_node(node.as_any().downcast_ref::<DeferNode>().unwrap())
+            }
             NodeType::Syscall => {
                 self.validate_syscall_node(node.as_any().downcast_ref::<SyscallNode>().unwrap())
             }
+            NodeType::Memory => {
+                self.validate_memory_node(node.as_any().downcast_ref::<MemoryNode>().unwrap())
+            }
             NodeType::While => {
                 self.validate_while_node(node.as_any().downcast_ref::<WhileNode>().unwrap())
             }
             NodeType::For => {
                 self.validate_for_node(node.as_any().downcast_ref::<ForNode>().unwrap())
             }
+            NodeType::ForIn => {
+                self.validate_for_in_node(node.as_any().downcast_ref::<ForInNode>().unwrap())
+            }
             NodeType::If => self.validate_if_node(node.as_any().downcast_ref::<IfNode>().unwrap()),
             NodeType::Cast => {
                 self.validate_cast_node(node.as_any().downcast_ref::<CastNode>().unwrap())
@@ -265,6 +626,12 @@ impl Validator {
             NodeType::SizeOf => {
                 self.validate_sizeof_node(node.as_any().downcast_ref::<SizeOfNode>().unwrap())
             }
+            NodeType::LenOf => {
+                self.validate_lenof_node(node.as_any().downcast_ref::<LenOfNode>().unwrap())
+            }
+            NodeType::Slice => {
+                self.validate_slice_node(node.as_any().downcast_ref::<SliceNode>().unwrap())
+            }
             NodeType::StaticDef => self.validate_static_def_node(
                 node.as_any()
                     .downcast_ref::<StaticDefinitionNode>()
@@ -302,11 +669,20 @@ impl Validator {
             }
             NodeType::AddressOf => self
                 .validate_address_of_node(node.as_any().downcast_ref::<AddressOfNode>().unwrap()),
+            NodeType::Reference => {
+                self.validate_reference_node(node.as_any().downcast_ref::<ReferenceNode>().unwrap())
+            }
             NodeType::Assembly => {
                 self.validate_assembly_node(node.as_any().downcast_ref::<AssemblyNode>().unwrap())
             }
             NodeType::StructInit => self
                 .validate_struct_init_node(node.as_any().downcast_ref::<StructInitNode>().unwrap()),
+            NodeType::TestDef => self.validate_test_def_node(
+                node.as_any().downcast_ref::<TestDefinitionNode>().unwrap(),
+            ),
+            NodeType::Assert => {
+                self.validate_assert_node(node.as_any().downcast_ref::<AssertNode>().unwrap())
+            }
             _ => panic!("Unsupported node type: {:?}", node.node_type()),
         }
     }
@@ -315,26 +691,232 @@ impl Validator {
         t
     }
 
+    /// Wraps the node in a `CheckNode`, if checks of that kind are enabled.
+    fn check(
+        &self,
+        check: Check,
+        node: Box<dyn Node>,
+        pos_start: &Position,
+        pos_end: &Position,
+    ) -> Box<dyn Node> {
+        let kind = match check {
+            Check::Bounds(_) | Check::SliceBounds | Check::SliceRange => RuntimeCheck::Bounds,
+            Check::Null => RuntimeCheck::Null,
+            Check::Overflow { .. } => RuntimeCheck::Overflow,
+        };
+        if !self.checks.contains(&kind) {
+            return node;
+        }
+
+        Box::new(CheckNode::new(
+            check,
+            node,
+            pos_start.clone(),
+            pos_end.clone(),
+        ))
+    }
+
+    /// Stores the already validated value of a checked expression in a hidden variable, so it's
+    /// only evaluated once. Returns its declaration and the node reading it.
+    fn check_var(
+        &mut self,
+        name: &str,
+        value_type: Box<dyn ValueType>,
+        value_node: Box<dyn Node>,
+        pos_start: &Position,
+        pos_end: &Position,
+    ) -> (Box<dyn Node>, Box<dyn Node>) {
+        self.check_vars += 1;
+        let name = format!("{}.{}", name, self.check_vars);
+        (
+            Box::new(VarDeclarationNode::new(
+                name.clone(),
+                value_type.clone(),
+                value_node,
+                false,
+                pos_start.clone(),
+            )),
+            Box::new(VarTypedAccessNode::new(
+                name,
+                value_type,
+                pos_start.clone(),
+                pos_end.clone(),
+            )),
+        )
+    }
+
+    /// Whether a value of type `value_type` can be stored where a value of type `target` is
+    /// expected. Pointers to mutable memory can be used as pointers to immutable memory, but not
+    /// the other way around.
+    fn is_assignable(value_type: &Box<dyn ValueType>, target: &Box<dyn ValueType>) -> bool {
+        if !value_type.eq(target) {
+            return false;
+        }
+
+        match (
+            value_type.as_any().downcast_ref::<PointerType>(),
+            target.as_any().downcast_ref::<PointerType>(),
+        ) {
+            (Some(value_pointer), Some(target_pointer)) => {
+                *value_pointer.is_mutable() || !*target_pointer.is_mutable()
+            }
+            _ => true,
+        }
+    }
+
+    /// Arrays and slices are values, so storing or passing them copies their memory into a new
+    /// stack allocation. Fresh values, like literals, are used as they are. Values of other types
+    /// are returned unchanged.
+    fn copy_value(value_type: &Box<dyn ValueType>, node: Box<dyn Node>) -> Box<dyn Node> {
+        let is_fresh = match node.node_type() {
+            NodeType::Array | NodeType::StackAllocationNode | NodeType::Slice => true,
+            NodeType::Copy => {
+                node.as_any()
+                    .downcast_ref::<CopyNode>()
+                    .unwrap()
+                    .destination()
+                    .node_type()
+                    == NodeType::StackAllocationNode
+            }
+            _ => false,
+        };
+        if !matches!(
+            value_type.value_type(),
+            ValueTypes::Array | ValueTypes::Slice
+        ) || is_fresh
+        {
+            return node;
+        }
+
+        let allocation = Box::new(StackAllocationNode::new(
+            value_type.size_in_bytes(),
+            node.pos_start().clone(),
+            node.pos_end().clone(),
+        ));
+        Box::new(CopyNode::new(allocation, node, value_type.size_in_bytes()))
+    }
+
+    /// The value of variables declared without one. They aren't read before they are assigned, so
+    /// it only has to provide the memory of arrays and slices.
+    fn placeholder_value(value_type: &Box<dyn ValueType>, pos: &Position) -> Box<dyn Node> {
+        if Self::stack_value_kind(value_type).is_some() {
+            return Box::new(StackAllocationNode::new(
+                value_type.size_in_bytes(),
+                pos.clone(),
+                pos.clone(),
+            ));
+        }
+
+        Self::number(0, pos, pos)
+    }
+
+    /// Returns the name of the kind of values, which are stored on the stack and passed around as
+    /// the address of their memory.
+    fn stack_value_kind(value_type: &Box<dyn ValueType>) -> Option<&'static str> {
+        match value_type.value_type() {
+            ValueTypes::Array => Some("array"),
+            ValueTypes::Slice => Some("slice"),
+            _ => None,
+        }
+    }
+
+    /// Creates a slice with the pointer and length the nodes evaluate to. Like array literals, it is
+    /// stored on the stack.
+    fn slice(
+        pointer: Box<dyn Node>,
+        length: Box<dyn Node>,
+        pos_start: &Position,
+        pos_end: &Position,
+    ) -> Box<dyn Node> {
+        Box::new(ArrayNode::new(
+            2,
+            vec![pointer, length],
+            Box::new(U64Type::new()),
+            pos_start.clone(),
+            pos_end.clone(),
+        ))
+    }
+
+    fn number(value: u64, pos_start: &Position, pos_end: &Position) -> Box<dyn Node> {
+        Box::new(NumberNode::new(
+            Token::new_with_value(
+                TokenType::U64,
+                value.to_string(),
+                pos_start.clone(),
+                pos_end.clone(),
+            ),
+            Box::new(U64Type::new()),
+        ))
+    }
+
+    /// Reads the length of the slice the node evaluates to.
+    fn slice_length(node: Box<dyn Node>, pos_end: &Position) -> Box<dyn Node> {
+        Box::new(ReadBytesNode::new(
+            Box::new(BinOpNode::new(
+                node,
+                Token::new_without_value(TokenType::Plus, Position::empty(), Position::empty()),
+                Self::number(SLICE_LENGTH_OFFSET, &Position::empty(), &Position::empty()),
+            )),
+            ValueSize::Qword,
+            pos_end.clone(),
+        ))
+    }
+
     fn validate_statements_node(&mut self, node: &StatementsNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
         self.push_child_scope(ScopeType::Block);
 
         let mut stmts: Vec<Box<dyn Node>> = vec![];
-        for s in node.statement_nodes() {
-            let (_, stmt) = res.register_res(self.validate(s));
+        let mut value_type: Option<Box<dyn ValueType>> = None;
+        for (i, s) in node.statement_nodes().iter().enumerate() {
+            let (stmt_type, stmt) = res.register_res(self.validate(s));
             if res.has_error() {
                 return res;
             }
             stmts.push(stmt.unwrap());
+
+            // declarations and statements like loops don't produce a value
+            if node.has_value()
+                && i == node.statement_nodes().len() - 1
+                && s.node_type() != NodeType::VarDeclaration
+                && !matches!(
+                    stmt_type.as_ref().unwrap().value_type(),
+                    ValueTypes::Ignored | ValueTypes::Void
+                )
+            {
+                value_type = stmt_type;
+            }
+        }
+
+        // after `return`, `break` and `continue`, the deferred statements already ran
+        let deferred = self.deferred_since(self.deferred.len() - 1);
+        let ends_with_jump = node.statement_nodes().last().is_some_and(|s| {
+            matches!(
+                s.node_type(),
+                NodeType::Return | NodeType::Break | NodeType::Continue
+            )
+        });
+        if !deferred.is_empty() && !ends_with_jump {
+            match &value_type {
+                Some(value_type) => {
+                    let (statements, value_node) =
+                        Self::defer_value(value_type, stmts.pop().unwrap(), deferred);
+                    stmts.extend(statements);
+                    stmts.push(value_node);
+                }
+                None => stmts.extend(deferred),
+            }
         }
 
         self.pop_child_scope();
 
+        let has_value = value_type.is_some();
         res.success(
-            Box::new(IgnoredType::new()),
+            value_type.unwrap_or_else(|| Box::new(IgnoredType::new())),
             Box::new(StatementsNode::new(
                 stmts,
+                has_value,
                 node.pos_start().clone(),
                 node.pos_end().clone(),
             )),
@@ -373,8 +955,9 @@ impl Validator {
     fn validate_array_node(&mut self, node: &ArrayNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
+        let mut element_nodes: Vec<Box<dyn Node>> = vec![];
         for el in node.element_nodes() {
-            let (t, _) = res.register_res(self.validate(el));
+            let (t, element_node) = res.register_res(self.validate(el));
 
             if res.has_error() {
                 return res;
@@ -393,14 +976,50 @@ impl Validator {
                 ));
                 return res;
             }
+            element_nodes.push(element_node.unwrap());
+        }
+
+        let array_type = Box::new(ArrayType::new(
+            self.validate_type(node.element_type().clone()),
+            *node.size(),
+        ));
+
+        // the elements of arrays of arrays are stored inline, which literals can only zero
+        if node.element_type().value_type() == ValueTypes::Array {
+            if !element_nodes.is_empty() {
+                res.failure(error::semantic_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    format!(
+                        "Array literals with elements of type {} can only have a length!",
+                        node.element_type()
+                    )
+                    .as_str(),
+                ));
+                return res;
+            }
+
+            let size_in_bytes = array_type.size_in_bytes();
+            res.success(
+                array_type,
+                Box::new(StackAllocationNode::new(
+                    size_in_bytes,
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                )),
+            );
+            return res;
         }
 
         res.success(
-            Box::new(PointerType::new(
-                self.validate_type(node.element_type().clone()),
-                true,
+            array_type,
+            Box::new(ArrayNode::new(
+                *node.size(),
+                element_nodes,
+                node.element_type().clone(),
+                node.pos_start().clone(),
+                node.pos_end().clone(),
             )),
-            node.box_clone(),
         );
         res
     }
@@ -413,15 +1032,38 @@ impl Validator {
             return res;
         }
 
+        let before_right = self.flow.clone();
         let (right, right_node) = res.register_res(self.validate(node.right_node()));
         if res.has_error() {
             return res;
         }
 
-        let result_type = left
-            .as_ref()
-            .unwrap()
-            .is_valid_bin_op(node.op_token(), right.as_ref().unwrap());
+        // `&&` and `||` short-circuit, so the right side might not be evaluated
+        if matches!(node.op_token().token_type(), TokenType::And | TokenType::Or) {
+            self.flow = before_right.join(std::mem::take(&mut self.flow));
+        }
+
+        self.bin_op(
+            node,
+            left.unwrap(),
+            left_node.unwrap(),
+            right.unwrap(),
+            right_node.unwrap(),
+        )
+    }
+
+    /// Lowers the binary operation `node` between operands that were already validated.
+    fn bin_op(
+        &mut self,
+        node: &BinOpNode,
+        left: Box<dyn ValueType>,
+        left_node: Box<dyn Node>,
+        right: Box<dyn ValueType>,
+        right_node: Box<dyn Node>,
+    ) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let result_type = left.is_valid_bin_op(node.op_token(), &right);
         if result_type.is_none() {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
@@ -429,113 +1071,463 @@ impl Validator {
                 format!(
                     "Binary operation '{}' not allowed between value_type {} and {}!",
                     node.op_token(),
-                    left.as_ref().unwrap(),
-                    right.as_ref().unwrap()
+                    left,
+                    right
                 )
                 .as_str(),
             ));
             return res;
         }
 
-        if left.as_ref().unwrap().value_type() == ValueTypes::F64 {
+        if left.value_type() == ValueTypes::F64 {
             res.success(
                 result_type.unwrap(),
                 Box::new(FloatingBinOpNode::new(
-                    left_node.unwrap(),
+                    left_node,
                     node.op_token().clone(),
-                    right_node.unwrap(),
+                    right_node,
                 )),
             );
             return res;
         }
 
         if node.op_token().token_type() == TokenType::PointerAssign {
+            let pointee_type = left
+                .as_any()
+                .downcast_ref::<PointerType>()
+                .unwrap()
+                .pointee_type()
+                .box_clone();
+
+            let ptr_node = self.check(Check::Null, left_node, node.pos_start(), node.pos_end());
+
             res.success(
                 result_type.unwrap(),
-                Box::new(PointerAssignNode::new(
-                    left_node.unwrap(),
-                    self.validate_type(
-                        left.unwrap()
-                            .as_any()
-                            .downcast_ref::<PointerType>()
-                            .unwrap()
-                            .pointee_type()
-                            .box_clone(),
-                    ),
-                    right_node.unwrap(),
-                )),
+                self.store(pointee_type, ptr_node, right_node),
             );
             return res;
         }
         if node.op_token().token_type() == TokenType::Offset {
             res.success(
                 result_type.unwrap(),
-                Box::new(OffsetNode::new(
-                    left_node.unwrap(),
-                    right_node.unwrap(),
-                    self.validate_type(
-                        left.unwrap()
-                            .as_any()
-                            .downcast_ref::<PointerType>()
-                            .unwrap()
-                            .pointee_type()
-                            .box_clone(),
-                    ),
-                )),
+                self.offset(
+                    &left,
+                    left_node,
+                    right_node,
+                    node.pos_start(),
+                    node.pos_end(),
+                ),
             );
             return res;
         }
 
-        res.success(
-            self.validate_type(result_type.unwrap()),
-            Box::new(BinOpNode::new(
-                left_node.unwrap(),
-                node.op_token().clone(),
-                right_node.unwrap(),
-            )),
-        );
+        let result_type = result_type.unwrap();
+        let mut bin_op_node: Box<dyn Node> = Box::new(BinOpNode::new(
+            left_node,
+            node.op_token().clone(),
+            right_node,
+        ));
+
+        let signed = match result_type.value_type() {
+            ValueTypes::U64 | ValueTypes::U32 | ValueTypes::U16 | ValueTypes::U8 => Some(false),
+            ValueTypes::I64 | ValueTypes::I32 | ValueTypes::I16 | ValueTypes::I8 => Some(true),
+            _ => None,
+        };
+        if let Some(signed) = signed {
+            if matches!(
+                node.op_token().token_type(),
+                TokenType::Plus | TokenType::Minus | TokenType::Mul
+            ) {
+                bin_op_node = self.check(
+                    Check::Overflow {
+                        size: result_type.get_size(),
+                        signed,
+                    },
+                    bin_op_node,
+                    node.pos_start(),
+                    node.pos_end(),
+                );
+            }
+        }
+
+        res.success(self.validate_type(result_type), bin_op_node);
         res
     }
 
-    fn validate_unary_op_node(&mut self, node: &UnaryOpNode) -> ValidationResult {
-        let mut res = ValidationResult::new();
+    /// Computes the address of the element `index_node` of `left_node`, which is an array, a
+    /// slice or a pointer.
+    fn offset(
+        &mut self,
+        left: &Box<dyn ValueType>,
+        left_node: Box<dyn Node>,
+        index_node: Box<dyn Node>,
+        pos_start: &Position,
+        pos_end: &Position,
+    ) -> Box<dyn Node> {
+        // indexing arrays and slices works like indexing a pointer to their first element
+        let mut left_node = left_node;
+        let mut declaration = None;
+        let (pointee_type, offset_node) =
+            if let Some(array_type) = left.as_any().downcast_ref::<ArrayType>() {
+                (
+                    array_type.element_type().box_clone(),
+                    self.check(
+                        Check::Bounds(array_type.length() as u64),
+                        index_node,
+                        pos_start,
+                        pos_end,
+                    ),
+                )
+            } else if let Some(slice_type) = left.as_any().downcast_ref::<SliceType>() {
+                let mut index_node = index_node;
+                // the slice is read twice, for its pointer and its length
+                if self.checks.contains(&RuntimeCheck::Bounds) {
+                    let (slice_declaration, slice_node) =
+                        self.check_var("slice", left.clone(), left_node, pos_start, pos_end);
+                    declaration = Some(slice_declaration);
+                    left_node = slice_node.clone();
+                    index_node = Box::new(CheckNode::with_length(
+                        Check::SliceBounds,
+                        index_node,
+                        Self::slice_length(slice_node, pos_end),
+                        pos_start.clone(),
+                        pos_end.clone(),
+                    ));
+                }
+                left_node = Box::new(ReadBytesNode::new(
+                    left_node,
+                    ValueSize::Qword,
+                    pos_end.clone(),
+                ));
+                (slice_type.element_type().box_clone(), index_node)
+            } else {
+                (
+                    left.as_any()
+                        .downcast_ref::<PointerType>()
+                        .unwrap()
+                        .pointee_type()
+                        .box_clone(),
+                    index_node,
+                )
+            };
+
+        let offset_node: Box<dyn Node> = Box::new(OffsetNode::new(
+            left_node,
+            offset_node,
+            self.validate_type(pointee_type),
+        ));
+        match declaration {
+            Some(declaration) => Box::new(StatementsNode::new(
+                vec![declaration, offset_node],
+                true,
+                pos_start.clone(),
+                pos_end.clone(),
+            )),
+            None => offset_node,
+        }
+    }
 
-        let (right, right_node) = res.register_res(self.validate(node.node()));
-        if res.has_error() {
-            return res;
+    /// Reads the value of type `pointee_type` that `ptr_node` points to.
+    fn load(
+        &self,
+        pointee_type: &Box<dyn ValueType>,
+        ptr_node: Box<dyn Node>,
+        pos_end: &Position,
+    ) -> Box<dyn Node> {
+        // arrays and slices are the address of their memory, so there's nothing to load
+        if matches!(
+            pointee_type.value_type(),
+            ValueTypes::Array | ValueTypes::Slice
+        ) {
+            return ptr_node;
         }
 
-        let result_type = right.as_ref().unwrap().is_valid_unary_op(node.op_token());
-        if result_type.is_none() {
-            res.failure(error::semantic_error(
-                node.pos_start().clone(),
-                node.pos_end().clone(),
-                format!(
-                    "Unary operation '{}' not allowed on type {}!",
-                    node.op_token(),
-                    right.as_ref().unwrap()
-                )
-                .as_str(),
-            ));
-            return res;
+        Box::new(ReadBytesNode::new(
+            ptr_node,
+            pointee_type.get_size(),
+            pos_end.clone(),
+        ))
+    }
+
+    /// Writes `value_node` to the memory of type `pointee_type` that `ptr_node` points to.
+    fn store(
+        &self,
+        pointee_type: Box<dyn ValueType>,
+        ptr_node: Box<dyn Node>,
+        value_node: Box<dyn Node>,
+    ) -> Box<dyn Node> {
+        // arrays and slices are assigned by copying their memory
+        if matches!(
+            pointee_type.value_type(),
+            ValueTypes::Array | ValueTypes::Slice
+        ) {
+            let size_in_bytes = pointee_type.size_in_bytes();
+            return Box::new(CopyNode::new(ptr_node, value_node, size_in_bytes));
         }
 
-        res.success(
-            self.validate_type(result_type.unwrap()),
-            Box::new(UnaryOpNode::new(
-                node.op_token().clone(),
-                right_node.unwrap(),
-            )),
-        );
-        res
+        Box::new(PointerAssignNode::new(
+            ptr_node,
+            self.validate_type(pointee_type),
+            value_node,
+        ))
     }
 
-    fn validate_var_declaration_node(&mut self, node: &VarDeclarationNode) -> ValidationResult {
+    /// Validates `node` as a place in memory, which is a field, an element or a dereferenced
+    /// pointer. The result is a pointer to the place that is only mutable if the memory is.
+    fn validate_place(&mut self, node: &Box<dyn Node>) -> ValidationResult {
         let mut res = ValidationResult::new();
 
-        if self.has_symbol(node.var_name()) {
-            let (_, decl_pos) = self.get_symbol(node.var_name()).unwrap();
-            res.failure(error::semantic_error_with_parent(
+        match node.node_type() {
+            NodeType::Dereference => {
+                let dereference_node = node.as_any().downcast_ref::<DereferenceNode>().unwrap();
+
+                // `arr[i]` already is a pointer to the element, so `*arr[i]` is the same place
+                if let Some(bin_op_node) =
+                    dereference_node.node().as_any().downcast_ref::<BinOpNode>()
+                {
+                    if bin_op_node.op_token().token_type() == TokenType::Offset {
+                        return self.validate_element_place(bin_op_node);
+                    }
+                }
+
+                let (ptr_type, ptr_node) = res.register_res(self.validate(dereference_node.node()));
+                if res.has_error() {
+                    return res;
+                }
+
+                if ptr_type.as_ref().unwrap().value_type() != ValueTypes::Pointer {
+                    res.failure(error::semantic_error(
+                        node.pos_start().clone(),
+                        node.pos_end().clone(),
+                        "Can't dereference non-pointer type!",
+                    ));
+                    return res;
+                }
+
+                let ptr_node = self.check(
+                    Check::Null,
+                    ptr_node.unwrap(),
+                    node.pos_start(),
+                    node.pos_end(),
+                );
+                res.success(ptr_type.unwrap(), ptr_node);
+                res
+            }
+            NodeType::Accessor => {
+                let accessor_node = node.as_any().downcast_ref::<AccessorNode>().unwrap();
+
+                let (node_type, value_node) = res.register_res(self.validate(accessor_node.node()));
+                if res.has_error() {
+                    return res;
+                }
+
+                // the fields of slices are values and not places in memory
+                if node_type.as_ref().unwrap().value_type() == ValueTypes::Slice {
+                    res.failure(error::semantic_error(
+                        node.pos_start().clone(),
+                        node.pos_end().clone(),
+                        format!(
+                            "Field '{}' of slices can't be assigned or referenced!",
+                            accessor_node.accessor()
+                        )
+                        .as_str(),
+                    ));
+                    return res;
+                }
+
+                self.validate_field(accessor_node, node_type.unwrap(), value_node.unwrap())
+            }
+            NodeType::BinOp
+                if node
+                    .as_any()
+                    .downcast_ref::<BinOpNode>()
+                    .unwrap()
+                    .op_token()
+                    .token_type()
+                    == TokenType::Offset =>
+            {
+                self.validate_element_place(node.as_any().downcast_ref::<BinOpNode>().unwrap())
+            }
+            _ => {
+                res.failure(error::semantic_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    "Expected a field, an element or a dereferenced pointer!",
+                ));
+                res
+            }
+        }
+    }
+
+    /// Validates the element `arr[i]` as a place. Elements of arrays are mutable if the array is,
+    /// elements behind pointers if the pointer is and elements of slices never are.
+    fn validate_element_place(&mut self, node: &BinOpNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let (left, left_node, is_array_mutable) = match node.left_node().node_type() {
+            NodeType::Dereference => {
+                let (ptr_type, ptr_node) = res.register_res(self.validate_place(node.left_node()));
+                if res.has_error() {
+                    return res;
+                }
+
+                let ptr_type = ptr_type.unwrap();
+                let ptr_type = ptr_type.as_any().downcast_ref::<PointerType>().unwrap();
+                let pointee_type = ptr_type.pointee_type().box_clone();
+                let value_node = self.load(&pointee_type, ptr_node.unwrap(), node.pos_end());
+                (pointee_type, value_node, *ptr_type.is_mutable())
+            }
+            node_type => {
+                let (left, left_node) = res.register_res(self.validate(node.left_node()));
+                if res.has_error() {
+                    return res;
+                }
+
+                let is_array_mutable = node_type == NodeType::VarAccess
+                    && self.is_symbol_mut(
+                        node.left_node()
+                            .as_any()
+                            .downcast_ref::<VarAccessNode>()
+                            .unwrap()
+                            .var_name(),
+                    );
+                (left.unwrap(), left_node.unwrap(), is_array_mutable)
+            }
+        };
+
+        let (right, right_node) = res.register_res(self.validate(node.right_node()));
+        if res.has_error() {
+            return res;
+        }
+
+        let result_type = left.is_valid_bin_op(node.op_token(), right.as_ref().unwrap());
+        if result_type.is_none() {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Binary operation '{}' not allowed between value_type {} and {}!",
+                    node.op_token(),
+                    left,
+                    right.as_ref().unwrap()
+                )
+                .as_str(),
+            ));
+            return res;
+        }
+
+        let is_mutable = match left.value_type() {
+            ValueTypes::Pointer => *left
+                .as_any()
+                .downcast_ref::<PointerType>()
+                .unwrap()
+                .is_mutable(),
+            ValueTypes::Array => is_array_mutable,
+            _ => false,
+        };
+        let element_type = result_type
+            .unwrap()
+            .as_any()
+            .downcast_ref::<PointerType>()
+            .unwrap()
+            .pointee_type()
+            .box_clone();
+
+        res.success(
+            Box::new(PointerType::new(element_type, is_mutable)),
+            self.offset(
+                &left,
+                left_node,
+                right_node.unwrap(),
+                node.pos_start(),
+                node.pos_end(),
+            ),
+        );
+        res
+    }
+
+    /// Whether evaluating `node` twice has the same effect as evaluating it once, which compound
+    /// assignments rely on as they read and write their target.
+    fn is_pure(node: &Box<dyn Node>) -> bool {
+        match node.node_type() {
+            NodeType::Number
+            | NodeType::FloatingPoint
+            | NodeType::Char
+            | NodeType::VarAccess
+            | NodeType::SizeOf => true,
+            NodeType::BinOp => {
+                let bin_op_node = node.as_any().downcast_ref::<BinOpNode>().unwrap();
+                bin_op_node.op_token().token_type() != TokenType::PointerAssign
+                    && Self::is_pure(bin_op_node.left_node())
+                    && Self::is_pure(bin_op_node.right_node())
+            }
+            NodeType::UnaryOp => {
+                Self::is_pure(node.as_any().downcast_ref::<UnaryOpNode>().unwrap().node())
+            }
+            NodeType::Dereference => Self::is_pure(
+                node.as_any()
+                    .downcast_ref::<DereferenceNode>()
+                    .unwrap()
+                    .node(),
+            ),
+            NodeType::Accessor => {
+                Self::is_pure(node.as_any().downcast_ref::<AccessorNode>().unwrap().node())
+            }
+            NodeType::Cast => {
+                Self::is_pure(node.as_any().downcast_ref::<CastNode>().unwrap().node())
+            }
+            NodeType::ReadBytes => Self::is_pure(
+                node.as_any()
+                    .downcast_ref::<ReadBytesNode>()
+                    .unwrap()
+                    .node(),
+            ),
+            NodeType::LenOf => {
+                Self::is_pure(node.as_any().downcast_ref::<LenOfNode>().unwrap().node())
+            }
+            _ => false,
+        }
+    }
+
+    fn validate_unary_op_node(&mut self, node: &UnaryOpNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let (right, right_node) = res.register_res(self.validate(node.node()));
+        if res.has_error() {
+            return res;
+        }
+
+        let result_type = right.as_ref().unwrap().is_valid_unary_op(node.op_token());
+        if result_type.is_none() {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Unary operation '{}' not allowed on type {}!",
+                    node.op_token(),
+                    right.as_ref().unwrap()
+                )
+                .as_str(),
+            ));
+            return res;
+        }
+
+        res.success(
+            self.validate_type(result_type.unwrap()),
+            Box::new(UnaryOpNode::new(
+                node.op_token().clone(),
+                right_node.unwrap(),
+            )),
+        );
+        res
+    }
+
+    fn validate_var_declaration_node(&mut self, node: &VarDeclarationNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        if self.has_symbol(node.var_name()) {
+            let (_, decl_pos) = self.get_symbol(node.var_name()).unwrap();
+            res.failure(error::semantic_error_with_parent(
                 node.pos_start().clone(),
                 node.pos_end().clone(),
                 format!(
@@ -552,20 +1544,28 @@ impl Validator {
             return res;
         }
 
-        let (t, value_node) = res.register_res(self.validate(node.value_node()));
+        let is_initialized = node.value_node().node_type() != NodeType::Ignored;
+        let (t, value_node) = if is_initialized {
+            res.register_res(self.validate(node.value_node()))
+        } else {
+            (
+                Some(node.var_type().clone()),
+                Some(Self::placeholder_value(node.var_type(), node.pos_start())),
+            )
+        };
         if res.has_error() {
             return res;
         }
 
-        let symbol_type = t.unwrap();
+        let value_type = t.unwrap();
 
-        if !symbol_type.eq(node.var_type()) {
+        if !Self::is_assignable(&value_type, node.var_type()) {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
                 node.pos_end().clone(),
                 format!(
                     "Type '{}' can't be assigned to type '{}'!",
-                    &symbol_type,
+                    &value_type,
                     node.var_type()
                 )
                 .as_str(),
@@ -573,17 +1573,35 @@ impl Validator {
             return res;
         }
 
+        // the declared type decides, whether the memory behind a pointer is mutable
+        let symbol_type = node.var_type().clone();
         self.declare_symbol(
             node.var_name().to_string(),
             Symbol::new(symbol_type.clone(), node.is_mutable()),
             node.pos_start().clone(),
         );
+        self.record_declaration(
+            node.var_name(),
+            DeclarationKind::Variable,
+            symbol_type.as_ref(),
+            node.pos_start(),
+        );
+
+        // a variable declared earlier might have had the same name
+        self.flow.assigned.remove(node.var_name());
+        if is_initialized {
+            self.flow.unassigned.remove(node.var_name());
+        } else {
+            self.flow.unassigned.insert(node.var_name().to_string());
+        }
+
+        let value_node = Self::copy_value(&symbol_type, value_node.unwrap());
         res.success(
             symbol_type,
             Box::new(VarDeclarationNode::new(
                 node.var_name().to_string(),
                 node.var_type().box_clone(),
-                value_node.unwrap(),
+                value_node,
                 node.is_mutable(),
                 node.pos_start().clone(),
             )),
@@ -607,18 +1625,17 @@ impl Validator {
             return res;
         }
 
+        self.record_reference(node.var_name(), node.pos_start());
+
         let (assign_type, assign_node) = res.register_res(self.validate(node.value_node()));
         if res.has_error() {
             return res;
         }
 
-        if !self
-            .get_symbol(node.var_name())
-            .unwrap()
-            .0
-            .value_type()
-            .eq(assign_type.as_ref().unwrap())
-        {
+        if !Self::is_assignable(
+            assign_type.as_ref().unwrap(),
+            self.get_symbol(node.var_name()).unwrap().0.value_type(),
+        ) {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
                 node.pos_end().clone(),
@@ -632,15 +1649,54 @@ impl Validator {
             return res;
         }
 
-        if !self.is_symbol_mut(node.var_name()) {
+        // immutable variables declared without a value can be assigned once
+        let name = node.var_name();
+        let is_mutable = self.is_symbol_mut(name);
+        if !is_mutable && !self.flow.unassigned.contains(name) && !self.flow.assigned.contains(name)
+        {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!("Variable '{}' is not mutable!", name).as_str(),
+            ));
+            return res;
+        }
+        if !is_mutable && self.flow.assigned.contains(name) {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
                 node.pos_end().clone(),
-                format!("Variable '{}' is not mutable!", node.var_name()).as_str(),
+                format!("Variable '{}' might be assigned more than once!", name).as_str(),
             ));
             return res;
         }
 
+        self.flow.unassigned.remove(name);
+        if !is_mutable {
+            self.flow.assigned.insert(name.to_string());
+        }
+
+        // the variable keeps pointing to its own memory, which is overwritten
+        if matches!(
+            assign_type.as_ref().unwrap().value_type(),
+            ValueTypes::Array | ValueTypes::Slice
+        ) {
+            let size_in_bytes = assign_type.as_ref().unwrap().size_in_bytes();
+            res.success(
+                assign_type.as_ref().unwrap().clone(),
+                Box::new(CopyNode::new(
+                    Box::new(VarTypedAccessNode::new(
+                        node.var_name().to_string(),
+                        assign_type.unwrap(),
+                        node.pos_start().clone(),
+                        node.pos_start().clone(),
+                    )),
+                    assign_node.unwrap(),
+                    size_in_bytes,
+                )),
+            );
+            return res;
+        }
+
         res.success(
             assign_type.as_ref().unwrap().clone(),
             Box::new(VarTypedAssignNode::new(
@@ -670,6 +1726,19 @@ impl Validator {
             return res;
         }
 
+        if !self.flow.diverged && self.flow.unassigned.contains(node.var_name()) {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Variable '{}' might be read before it's assigned!",
+                    node.var_name()
+                )
+                .as_str(),
+            ));
+            return res;
+        }
+
         let base_type = self
             .get_symbol(node.var_name())
             .unwrap()
@@ -677,6 +1746,8 @@ impl Validator {
             .value_type()
             .clone();
 
+        self.record_reference(node.var_name(), node.pos_start());
+
         res.success(
             base_type.clone(),
             Box::new(VarTypedAccessNode::new(
@@ -693,6 +1764,22 @@ impl Validator {
     fn validate_function_def_node(&mut self, node: &FunctionDefinitionNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
+        // arrays and slices are stored on the stack of the function, so they can't outlive it
+        if let Some(kind) = Self::stack_value_kind(node.return_type()) {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Function '{}' can't return the {} type {}!",
+                    node.var_name(),
+                    kind,
+                    node.return_type()
+                )
+                .as_str(),
+            ));
+            return res;
+        }
+
         let symbol = self.get_symbol(node.var_name());
 
         if let Some((symbol, _)) = symbol {
@@ -777,6 +1864,7 @@ impl Validator {
 
             if function_type.arg_types().clone() != arg_types
                 || function_type.return_type() != node.return_type()
+                || function_type.is_variadic()
             {
                 res.failure(error::semantic_error(
                     node.pos_start().clone(),
@@ -797,6 +1885,7 @@ impl Validator {
                     Box::new(FunctionType::new(
                         arg_types.clone(),
                         node.return_type().clone(),
+                        false,
                     )),
                     false,
                 ),
@@ -809,6 +1898,7 @@ impl Validator {
                     Box::new(FunctionType::new(
                         arg_types.clone(),
                         node.return_type().clone(),
+                        false,
                     )),
                     false,
                 ),
@@ -816,22 +1906,33 @@ impl Validator {
             );
         }
 
+        self.record_declaration(
+            node.var_name(),
+            DeclarationKind::Function,
+            &FunctionType::new(arg_types, node.return_type().clone(), false),
+            node.pos_start(),
+        );
+
         self.push_child_scope(ScopeType::Function);
         let old_return_type = self.current_function_return_type.clone();
         self.current_function_return_type = Some(node.return_type().clone());
+        let old_flow = std::mem::take(&mut self.flow);
 
-        for (name, value_type) in node.args() {
+        for (i, (name, value_type)) in node.args().iter().enumerate() {
+            let pos = node.arg_positions().get(i).unwrap_or(node.pos_start());
             self.declare_symbol(
                 name.clone(),
                 Symbol::new(value_type.clone(), true),
-                node.pos_start().clone(),
+                pos.clone(),
             );
+            self.record_declaration(name, DeclarationKind::Variable, value_type.as_ref(), pos);
         }
 
         let (_, body_node) = res.register_res(self.validate(node.body_node()));
 
         self.pop_child_scope();
         self.current_function_return_type = old_return_type;
+        let body_flow = std::mem::replace(&mut self.flow, old_flow);
 
         if res.has_error() {
             res.failure(error::semantic_error_with_parent(
@@ -843,38 +1944,103 @@ impl Validator {
             return res;
         }
 
-        if !res.has_return_type() {
+        // void functions return at the end of their body
+        if !body_flow.diverged && node.return_type().value_type() != ValueTypes::Void {
             res.failure(error::semantic_error(
                 node.pos_end().clone(),
                 node.pos_end().clone(),
-                "No return statement given!",
+                format!(
+                    "Function '{}' doesn't return a value on every path!",
+                    node.var_name()
+                )
+                .as_str(),
             ));
             return res;
         }
 
-        if !res.return_type().as_ref().unwrap().eq(node.return_type()) {
+        res.success(
+            Box::new(IgnoredType::new()),
+            Box::new(FunctionDefinitionNode::new(
+                node.var_name().to_string(),
+                node.args().clone(),
+                node.return_type().box_clone(),
+                Self::discard_value(body_node.unwrap()),
+                node.generics().clone(),
+                node.is_public(),
+                node.pos_start().clone(),
+            )),
+        );
+        res
+    }
+
+    fn validate_test_def_node(&mut self, node: &TestDefinitionNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+        let func_def_node = node.function_def_node();
+
+        if !func_def_node.args().is_empty()
+            || !func_def_node.generics().is_empty()
+            || func_def_node.return_type().value_type() != ValueTypes::Void
+        {
             res.failure(error::semantic_error(
-                node.pos_end().clone(),
+                node.pos_start().clone(),
                 node.pos_end().clone(),
                 format!(
-                    "Function return type is '{}', returned was '{}'!",
-                    node.return_type(),
-                    res.return_type().as_ref().unwrap()
+                    "Test '{}' must not take any arguments and must return 'void'!",
+                    func_def_node.var_name()
                 )
                 .as_str(),
             ));
             return res;
         }
 
+        let (_, test_node) = res.register_res(self.validate_function_def_node(
+            &FunctionDefinitionNode::new(
+                func_def_node.var_name().to_string(),
+                vec![],
+                func_def_node.return_type().clone(),
+                func_def_node.body_node().clone(),
+                vec![],
+                false,
+                func_def_node.pos_start().clone(),
+            ),
+        ));
+        if res.has_error() {
+            return res;
+        }
+
+        self.tests.push((
+            func_def_node.var_name().to_string(),
+            node.pos_start().clone(),
+        ));
+
+        res.success(Box::new(IgnoredType::new()), test_node.unwrap());
+        res
+    }
+
+    fn validate_assert_node(&mut self, node: &AssertNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let (condition_type, condition_node) =
+            res.register_res(self.validate(node.condition_node()));
+        if res.has_error() {
+            return res;
+        }
+
+        if condition_type.as_ref().unwrap().value_type() != ValueTypes::Bool {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                "Condition must be of type bool!",
+            ));
+            return res;
+        }
+
         res.success(
             Box::new(IgnoredType::new()),
-            Box::new(FunctionDefinitionNode::new(
-                node.var_name().to_string(),
-                node.args().clone(),
-                node.return_type().box_clone(),
-                body_node.unwrap(),
-                node.generics().clone(),
+            Box::new(AssertNode::new(
+                condition_node.unwrap(),
                 node.pos_start().clone(),
+                node.pos_end().clone(),
             )),
         );
         res
@@ -883,6 +2049,22 @@ impl Validator {
     fn validate_function_decl_node(&mut self, node: &FunctionDeclarationNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
+        // arrays and slices are stored on the stack of the function, so they can't outlive it
+        if let Some(kind) = Self::stack_value_kind(node.return_type()) {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Function '{}' can't return the {} type {}!",
+                    node.var_name(),
+                    kind,
+                    node.return_type()
+                )
+                .as_str(),
+            ));
+            return res;
+        }
+
         if self.has_symbol(node.var_name()) {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
@@ -909,11 +2091,18 @@ impl Validator {
                 Box::new(FunctionType::new(
                     arg_types.clone(),
                     node.return_type().clone(),
+                    node.is_variadic(),
                 )),
                 true,
             ),
             node.pos_start().clone(),
         );
+        self.record_declaration(
+            node.var_name(),
+            DeclarationKind::Function,
+            &FunctionType::new(arg_types, node.return_type().clone(), node.is_variadic()),
+            node.pos_start(),
+        );
 
         res.success(Box::new(IgnoredType::new()), node.box_clone());
         res
@@ -947,6 +2136,8 @@ impl Validator {
             return res;
         }
 
+        self.record_reference(node.func_to_call(), node.pos_start());
+
         let symbol_type = self
             .get_symbol(node.func_to_call())
             .unwrap()
@@ -955,12 +2146,20 @@ impl Validator {
             .clone();
         let function_type = symbol_type.as_any().downcast_ref::<FunctionType>().unwrap();
 
-        if function_type.arg_types().len() != node.arg_nodes().len() {
+        if function_type.arg_types().len() != node.arg_nodes().len()
+            && !(function_type.is_variadic()
+                && node.arg_nodes().len() > function_type.arg_types().len())
+        {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
                 node.pos_end().clone(),
                 format!(
-                    "Function expected {} arguments. {} were passed!",
+                    "Function expected {}{} arguments. {} were passed!",
+                    if function_type.is_variadic() {
+                        "at least "
+                    } else {
+                        ""
+                    },
                     function_type.arg_types().len(),
                     node.arg_nodes().len()
                 )
@@ -970,6 +2169,7 @@ impl Validator {
         }
 
         let mut arg_nodes: Vec<Box<dyn Node>> = vec![];
+        let mut arg_types: Vec<Box<dyn ValueType>> = vec![];
 
         for (i, arg) in node.arg_nodes().iter().enumerate() {
             let (t, arg_node) = res.register_res(self.validate(arg));
@@ -977,7 +2177,36 @@ impl Validator {
                 return res;
             }
 
-            if !t.as_ref().unwrap().eq(&function_type.arg_types()[i]) {
+            // the arguments for '...' are passed with C's default argument promotions, which are
+            // applied by the code generators
+            if i >= function_type.arg_types().len() {
+                if matches!(
+                    t.as_ref().unwrap().value_type(),
+                    ValueTypes::Void
+                        | ValueTypes::Struct
+                        | ValueTypes::Array
+                        | ValueTypes::Slice
+                        | ValueTypes::Ignored
+                ) {
+                    res.failure(error::semantic_error(
+                        arg.pos_start().clone(),
+                        arg.pos_end().clone(),
+                        format!(
+                            "Values of type '{}' can't be passed as variadic argument at index {}!",
+                            t.as_ref().unwrap(),
+                            i
+                        )
+                        .as_str(),
+                    ));
+                    return res;
+                }
+
+                arg_types.push(t.unwrap());
+                arg_nodes.push(arg_node.unwrap());
+                continue;
+            }
+
+            if !Self::is_assignable(t.as_ref().unwrap(), &function_type.arg_types()[i]) {
                 res.failure(error::semantic_error(
                     arg.pos_start().clone(),
                     arg.pos_end().clone(),
@@ -992,14 +2221,21 @@ impl Validator {
                 return res;
             }
 
-            arg_nodes.push(arg_node.unwrap());
+            // slices are only a view of their elements, so they aren't copied
+            if t.as_ref().unwrap().value_type() == ValueTypes::Slice {
+                arg_nodes.push(arg_node.unwrap());
+            } else {
+                arg_nodes.push(Self::copy_value(t.as_ref().unwrap(), arg_node.unwrap()));
+            }
+            arg_types.push(t.unwrap());
         }
 
         res.success(
             function_type.return_type().clone(),
-            Box::new(CallNode::new(
+            Box::new(CallNode::new_typed(
                 node.func_to_call().to_string(),
                 arg_nodes,
+                arg_types,
                 node.pos_start().clone(),
             )),
         );
@@ -1019,24 +2255,43 @@ impl Validator {
             return res;
         }
 
-        if node.node_to_return().is_none() {
-            if function_return_type.as_ref().unwrap().value_type() != ValueTypes::Void {
-                res.failure(error::semantic_error(
-                    node.pos_start().clone(),
-                    node.pos_end().clone(),
-                    "Function return type is not void!",
+        if self.is_in_scope_stack(ScopeType::Defer) {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                "Return statement can't leave a deferred statement!",
+            ));
+            return res;
+        }
+
+        let function_scope = self
+            .scope_stack
+            .iter()
+            .rposition(|s| *s == ScopeType::Function)
+            .unwrap_or(0);
+        let deferred = self.deferred_since(function_scope);
+
+        if node.node_to_return().is_none() {
+            if function_return_type.as_ref().unwrap().value_type() != ValueTypes::Void {
+                res.failure(error::semantic_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    "Function return type is not void!",
                 ));
                 return res;
             }
 
-            res.success_return(Box::new(VoidType::new()));
+            self.flow.diverged = true;
             res.success(
                 Box::new(IgnoredType::new()),
-                Box::new(ReturnNode::new(
-                    None,
-                    node.pos_start().clone(),
-                    node.pos_end().clone(),
-                )),
+                Self::run_deferred(
+                    deferred,
+                    Box::new(ReturnNode::new(
+                        None,
+                        node.pos_start().clone(),
+                        node.pos_end().clone(),
+                    )),
+                ),
             );
             return res;
         }
@@ -1047,11 +2302,10 @@ impl Validator {
             return res;
         }
 
-        if !return_type
-            .as_ref()
-            .unwrap()
-            .eq(function_return_type.as_ref().unwrap())
-        {
+        if !Self::is_assignable(
+            return_type.as_ref().unwrap(),
+            function_return_type.as_ref().unwrap(),
+        ) {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
                 node.pos_end().clone(),
@@ -1065,14 +2319,24 @@ impl Validator {
             return res;
         }
 
-        res.success_return(return_type.unwrap());
+        let return_type = return_type.unwrap();
+        let mut return_node = return_node.unwrap();
+        let mut statements = vec![];
+        if !deferred.is_empty() {
+            (statements, return_node) = Self::defer_value(&return_type, return_node, deferred);
+        }
+
+        self.flow.diverged = true;
         res.success(
             Box::new(IgnoredType::new()),
-            Box::new(ReturnNode::new(
-                Some(return_node.unwrap()),
-                node.pos_start().clone(),
-                node.pos_end().clone(),
-            )),
+            Self::run_deferred(
+                statements,
+                Box::new(ReturnNode::new(
+                    Some(return_node),
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                )),
+            ),
         );
         res
     }
@@ -1080,32 +2344,87 @@ impl Validator {
     fn validate_break_node(&mut self, node: &BreakNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
-        if !self.is_in_scope_stack(ScopeType::Loop) {
+        let loop_index =
+            match self.find_loop(node.label(), "Break", node.pos_start(), node.pos_end()) {
+                Ok(loop_index) => loop_index,
+                Err(e) => {
+                    res.failure(e);
+                    return res;
+                }
+            };
+        self.loops[loop_index].has_break = true;
+        self.flow.diverged = true;
+
+        let loop_scope = self.loops[loop_index].scope_index;
+        res.success(
+            Box::new(IgnoredType::new()),
+            Self::run_deferred(self.deferred_since(loop_scope), node.box_clone()),
+        );
+        res
+    }
+
+    fn validate_continue_node(&mut self, node: &ContinueNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let loop_index =
+            match self.find_loop(node.label(), "Continue", node.pos_start(), node.pos_end()) {
+                Ok(loop_index) => loop_index,
+                Err(e) => {
+                    res.failure(e);
+                    return res;
+                }
+            };
+        self.flow.diverged = true;
+
+        let loop_scope = self.loops[loop_index].scope_index;
+        res.success(
+            Box::new(IgnoredType::new()),
+            Self::run_deferred(self.deferred_since(loop_scope), node.box_clone()),
+        );
+        res
+    }
+
+    fn validate_defer_node(&mut self, node: &DeferNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        if self.find_first_function().is_none() {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
                 node.pos_end().clone(),
-                "Break statement outside of loop!",
+                "Defer statement outside of function!",
             ));
             return res;
         }
 
-        res.success(Box::new(IgnoredType::new()), node.box_clone());
-        res
-    }
-
-    fn validate_continue_node(&mut self, node: &ContinueNode) -> ValidationResult {
-        let mut res = ValidationResult::new();
-
-        if !self.is_in_scope_stack(ScopeType::Loop) {
+        if node.node().node_type() == NodeType::VarDeclaration {
             res.failure(error::semantic_error(
                 node.pos_start().clone(),
                 node.pos_end().clone(),
-                "Continue statement outside of loop!",
+                "Variable declarations can't be deferred!",
             ));
             return res;
         }
 
-        res.success(Box::new(IgnoredType::new()), node.box_clone());
+        // the statement runs when the block is left, so it doesn't assign anything here
+        let flow = self.enter_repeated();
+        self.push_child_scope(ScopeType::Defer);
+        let (_, statement) = res.register_res(self.validate(node.node()));
+        self.pop_child_scope();
+        self.flow = flow;
+
+        if res.has_error() {
+            return res;
+        }
+
+        // the statement is copied to the exits of the block, see `validate_statements_node`
+        self.deferred.last_mut().unwrap().push(statement.unwrap());
+        res.success(
+            Box::new(IgnoredType::new()),
+            Box::new(IgnoredNode::new(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+            )),
+        );
         res
     }
 
@@ -1133,9 +2452,100 @@ impl Validator {
         res
     }
 
+    fn validate_memory_node(&mut self, node: &MemoryNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+        let operation = node.operation();
+
+        let (destination_type, destination_node) =
+            res.register_res(self.validate(node.destination()));
+        if res.has_error() {
+            return res;
+        }
+        let (source_type, source_node) = res.register_res(self.validate(node.source()));
+        if res.has_error() {
+            return res;
+        }
+        let (count_type, count_node) = res.register_res(self.validate(node.count()));
+        if res.has_error() {
+            return res;
+        }
+
+        let destination_type = destination_type.unwrap();
+        let source_type = source_type.unwrap();
+        let count_type = count_type.unwrap();
+
+        let error = if destination_type.value_type() != ValueTypes::Pointer {
+            Some(format!(
+                "Expected a pointer as destination of {}, got '{}'!",
+                operation.keyword(),
+                destination_type
+            ))
+        } else if operation == MemoryOperation::Set && source_type.value_type() != ValueTypes::U8 {
+            Some(format!(
+                "Expected type 'u8' as value of memset, got '{}'!",
+                source_type
+            ))
+        } else if operation != MemoryOperation::Set
+            && source_type.value_type() != ValueTypes::Pointer
+        {
+            Some(format!(
+                "Expected a pointer as source of {}, got '{}'!",
+                operation.keyword(),
+                source_type
+            ))
+        } else if count_type.value_type() != ValueTypes::U64 {
+            Some(format!(
+                "Expected type 'u64' as size of {}, got '{}'!",
+                operation.keyword(),
+                count_type
+            ))
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                error.as_str(),
+            ));
+            return res;
+        }
+
+        let destination_node = self.check(
+            Check::Null,
+            destination_node.unwrap(),
+            node.pos_start(),
+            node.pos_end(),
+        );
+        let source_node = if operation == MemoryOperation::Set {
+            source_node.unwrap()
+        } else {
+            self.check(
+                Check::Null,
+                source_node.unwrap(),
+                node.pos_start(),
+                node.pos_end(),
+            )
+        };
+
+        res.success(
+            Box::new(VoidType::new()),
+            Box::new(MemoryNode::new(
+                operation,
+                destination_node,
+                source_node,
+                count_node.unwrap(),
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+            )),
+        );
+        res
+    }
+
     fn validate_while_node(&mut self, node: &WhileNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
+        let flow = self.enter_repeated();
         let (condition_type, condition_node) =
             res.register_res(self.validate(node.condition_node()));
         if res.has_error() {
@@ -1152,16 +2562,30 @@ impl Validator {
         }
 
         self.push_child_scope(ScopeType::Loop);
+        if let Err(e) = self.push_loop(node.label(), node.pos_start(), node.pos_end()) {
+            self.pop_child_scope();
+            res.failure(e);
+            return res;
+        }
+        let after_condition = self.flow.clone();
         let (_, body_node) = res.register_res(self.validate(node.body_node()));
+        let loop_scope = self.loops.pop().unwrap();
         self.pop_child_scope();
 
         if res.has_error() {
             return res;
         }
 
+        let condition_node = condition_node.unwrap();
+        self.leave_loop(flow, after_condition, &condition_node, &loop_scope);
+
         res.success(
             Box::new(IgnoredType::new()),
-            Box::new(WhileNode::new(condition_node.unwrap(), body_node.unwrap())),
+            Box::new(WhileNode::new(
+                condition_node,
+                Self::discard_value(body_node.unwrap()),
+                node.label().clone(),
+            )),
         );
         res
     }
@@ -1177,6 +2601,7 @@ impl Validator {
             return res;
         }
 
+        let flow = self.enter_repeated();
         let (condition_type, condition_node) = res.register_res(self.validate(node.condition()));
         if res.has_error() {
             self.pop_child_scope();
@@ -1193,6 +2618,9 @@ impl Validator {
             return res;
         }
 
+        // the next expression is validated first, so it only relies on values assigned before
+        // the body
+        let after_condition = self.flow.clone();
         let (_, next_expr) = res.register_res(self.validate(node.next_expr()));
         if res.has_error() {
             self.pop_child_scope();
@@ -1200,9 +2628,16 @@ impl Validator {
         }
 
         self.push_child_scope(ScopeType::Loop);
+        if let Err(e) = self.push_loop(node.label(), node.pos_start(), node.pos_end()) {
+            self.pop_child_scope();
+            self.pop_child_scope();
+            res.failure(e);
+            return res;
+        }
 
         let (_, body) = res.register_res(self.validate(node.body()));
 
+        let loop_scope = self.loops.pop().unwrap();
         self.pop_child_scope();
         self.pop_child_scope();
 
@@ -1210,22 +2645,413 @@ impl Validator {
             return res;
         }
 
+        let condition_node = condition_node.unwrap();
+        self.leave_loop(flow, after_condition, &condition_node, &loop_scope);
+
         res.success(
             Box::new(IgnoredType::new()),
             Box::new(ForNode::new(
                 init_stmt.unwrap(),
-                condition_node.unwrap(),
+                condition_node,
                 next_expr.unwrap(),
-                body.unwrap(),
+                Self::discard_value(body.unwrap()),
+                node.label().clone(),
+            )),
+        );
+        res
+    }
+
+    /// Lowers a `for` loop over a range, an array or a slice to a `ForNode`. The values it needs
+    /// on every iteration are kept in hidden variables, whose names start with the name of the
+    /// loop variable followed by a '.'. They can't collide with other variables, because
+    /// identifiers can't contain dots and variables can't be shadowed.
+    fn validate_for_in_node(&mut self, node: &ForInNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        if let Some((_, decl_pos)) = self.get_symbol(node.var_name()) {
+            res.failure(error::semantic_error_with_parent(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Variable '{}' was already declared in this scope!",
+                    node.var_name()
+                )
+                .as_str(),
+                error::semantic_error(
+                    decl_pos.clone(),
+                    decl_pos.clone(),
+                    format!("Previous declaration of '{}'", node.var_name()).as_str(),
+                ),
+            ));
+            return res;
+        }
+
+        self.push_child_scope(ScopeType::Block);
+        let (_, statements) = if node.iterable().node_type() == NodeType::Range {
+            let range = node
+                .iterable()
+                .as_any()
+                .downcast_ref::<RangeNode>()
+                .unwrap();
+            res.register_res(self.range_loop(node, range))
+        } else {
+            res.register_res(self.collection_loop(node))
+        };
+        self.pop_child_scope();
+
+        if res.has_error() {
+            return res;
+        }
+
+        res.success(Box::new(IgnoredType::new()), statements.unwrap());
+        res
+    }
+
+    /// Validates the bounds and the step of the range and declares them as hidden variables.
+    /// Then the loop counts the number of remaining integers `rest` down to 0, so neither the loop
+    /// variable nor the counter can overflow:
+    /// ```text
+    /// for let mut i.rest: T = if i.start < i.end { i.end - i.start } else { 0 };
+    ///     i.rest > 0;
+    ///     i.rest = if i.rest > i.step { i.rest - i.step } else { 0 } {
+    ///     let i: T = i.end - i.rest; // reversed: i.start + i.rest - 1
+    ///     ...
+    /// }
+    /// ```
+    fn range_loop(&mut self, node: &ForInNode, range: &RangeNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let mut bounds: Vec<Box<dyn Node>> = vec![];
+        let mut range_type: Option<Box<dyn ValueType>> = None;
+        for (bound, suffix) in [
+            (Some(range.start()), "start"),
+            (Some(range.end()), "end"),
+            (range.step().as_ref(), "step"),
+        ] {
+            let Some(bound) = bound else {
+                continue;
+            };
+
+            let (t, bound_node) = res.register_res(self.validate(bound));
+            if res.has_error() {
+                return res;
+            }
+            let t = t.unwrap();
+
+            if let Some(range_type) = &range_type {
+                if !t.eq(range_type) {
+                    res.failure(error::semantic_error(
+                        bound.pos_start().clone(),
+                        bound.pos_end().clone(),
+                        format!(
+                            "Expected {} of range to be of type '{}', got '{}'!",
+                            suffix, range_type, t
+                        )
+                        .as_str(),
+                    ));
+                    return res;
+                }
+            } else if !matches!(
+                t.value_type(),
+                ValueTypes::U64
+                    | ValueTypes::U32
+                    | ValueTypes::U16
+                    | ValueTypes::U8
+                    | ValueTypes::I64
+                    | ValueTypes::I32
+                    | ValueTypes::I16
+                    | ValueTypes::I8
+            ) {
+                res.failure(error::semantic_error(
+                    range.pos_start().clone(),
+                    range.pos_end().clone(),
+                    format!("Ranges must be of an integer type, got '{}'!", t).as_str(),
+                ));
+                return res;
+            }
+
+            bounds.push(self.hidden_var(node, suffix, &t, bound_node.unwrap()));
+            range_type = Some(t);
+        }
+        let range_type = range_type.unwrap();
+
+        let pos_start = range.pos_start();
+        let pos_end = range.pos_end();
+        let var = |suffix: &str| -> Box<dyn Node> {
+            Box::new(VarAccessNode::new(
+                format!("{}.{}", node.var_name(), suffix),
+                pos_start.clone(),
+                pos_end.clone(),
+            ))
+        };
+        let bin_op = |left: Box<dyn Node>, op: TokenType, right: Box<dyn Node>| -> Box<dyn Node> {
+            Box::new(BinOpNode::new(
+                left,
+                Token::new_without_value(op, pos_start.clone(), pos_end.clone()),
+                right,
+            ))
+        };
+        let literal = |value: &str| -> Box<dyn Node> {
+            let number = Box::new(NumberNode::new(
+                Token::new_with_value(
+                    TokenType::U64,
+                    value.to_string(),
+                    pos_start.clone(),
+                    pos_end.clone(),
+                ),
+                Box::new(U64Type::new()),
+            ));
+            if range_type.value_type() == ValueTypes::U64 {
+                return number;
+            }
+            Box::new(CastNode::new(number, range_type.clone(), pos_end.clone()))
+        };
+        let if_else = |condition: Box<dyn Node>, value: Box<dyn Node>| -> Box<dyn Node> {
+            let branch = |value: Box<dyn Node>| -> Box<dyn Node> {
+                Box::new(StatementsNode::new(
+                    vec![value],
+                    true,
+                    pos_start.clone(),
+                    pos_end.clone(),
+                ))
+            };
+            Box::new(IfNode::new(
+                vec![IfCase::new(condition, branch(value))],
+                Some(ElseCase::new(branch(literal("0")))),
+            ))
+        };
+
+        let rest = format!("{}.rest", node.var_name());
+        let init_stmt = Box::new(VarDeclarationNode::new(
+            rest.clone(),
+            range_type.clone(),
+            if_else(
+                bin_op(var("start"), TokenType::Lt, var("end")),
+                bin_op(var("end"), TokenType::Minus, var("start")),
+            ),
+            true,
+            pos_start.clone(),
+        ));
+        let condition = bin_op(var("rest"), TokenType::Gt, literal("0"));
+        let next_value = match range.step() {
+            Some(_) => if_else(
+                bin_op(var("rest"), TokenType::Gt, var("step")),
+                bin_op(var("rest"), TokenType::Minus, var("step")),
+            ),
+            None => bin_op(var("rest"), TokenType::Minus, literal("1")),
+        };
+        let next_expr = Box::new(VarAssignNode::new(rest, next_value, pos_start.clone()));
+        let value = if range.is_reversed() {
+            bin_op(
+                bin_op(var("start"), TokenType::Plus, var("rest")),
+                TokenType::Minus,
+                literal("1"),
+            )
+        } else {
+            bin_op(var("end"), TokenType::Minus, var("rest"))
+        };
+
+        let for_node = ForNode::new(
+            init_stmt,
+            condition,
+            next_expr,
+            Self::loop_body(node, range_type.clone(), value),
+            node.label().clone(),
+        );
+        let (_, for_node) = res.register_res(self.validate(&(Box::new(for_node) as Box<dyn Node>)));
+        if res.has_error() {
+            return res;
+        }
+
+        bounds.push(for_node.unwrap());
+        res.success(
+            Box::new(IgnoredType::new()),
+            Box::new(StatementsNode::new(
+                bounds,
+                false,
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+            )),
+        );
+        res
+    }
+
+    /// Validates the array or slice and declares it as a hidden variable, which points to the
+    /// same memory. Then the loop copies its elements into the loop variable one after another:
+    /// ```text
+    /// let x.count: u64 = lenof x.items;
+    /// for let mut x.index: u64 = 0; x.index < x.count; x.index = x.index + 1 {
+    ///     let x: T = *x.items[x.index];
+    ///     ...
+    /// }
+    /// ```
+    fn collection_loop(&mut self, node: &ForInNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let iterable = node.iterable();
+        let (t, items_node) = res.register_res(self.validate(iterable));
+        if res.has_error() {
+            return res;
+        }
+        let t = t.unwrap();
+
+        let element_type = if let Some(array_type) = t.as_any().downcast_ref::<ArrayType>() {
+            array_type.element_type().clone()
+        } else if let Some(slice_type) = t.as_any().downcast_ref::<SliceType>() {
+            slice_type.element_type().clone()
+        } else {
+            res.failure(error::semantic_error(
+                iterable.pos_start().clone(),
+                iterable.pos_end().clone(),
+                format!("Expected a range, an array or a slice, got '{}'!", t).as_str(),
+            ));
+            return res;
+        };
+
+        let mut statements = vec![self.hidden_var(node, "items", &t, items_node.unwrap())];
+
+        let pos_start = iterable.pos_start();
+        let pos_end = iterable.pos_end();
+        let var = |suffix: &str| -> Box<dyn Node> {
+            Box::new(VarAccessNode::new(
+                format!("{}.{}", node.var_name(), suffix),
+                pos_start.clone(),
+                pos_end.clone(),
+            ))
+        };
+        let bin_op = |left: Box<dyn Node>, op: TokenType, right: Box<dyn Node>| -> Box<dyn Node> {
+            Box::new(BinOpNode::new(
+                left,
+                Token::new_without_value(op, pos_start.clone(), pos_end.clone()),
+                right,
+            ))
+        };
+        let literal = |value: &str| -> Box<dyn Node> {
+            Box::new(NumberNode::new(
+                Token::new_with_value(
+                    TokenType::U64,
+                    value.to_string(),
+                    pos_start.clone(),
+                    pos_end.clone(),
+                ),
+                Box::new(U64Type::new()),
+            ))
+        };
+
+        let index = format!("{}.index", node.var_name());
+        let count = Box::new(VarDeclarationNode::new(
+            format!("{}.count", node.var_name()),
+            Box::new(U64Type::new()),
+            Box::new(LenOfNode::new(
+                var("items"),
+                pos_start.clone(),
+                pos_end.clone(),
+            )),
+            false,
+            pos_start.clone(),
+        ));
+        let for_node = Box::new(ForNode::new(
+            Box::new(VarDeclarationNode::new(
+                index.clone(),
+                Box::new(U64Type::new()),
+                literal("0"),
+                true,
+                pos_start.clone(),
+            )),
+            bin_op(var("index"), TokenType::Lt, var("count")),
+            Box::new(VarAssignNode::new(
+                index,
+                bin_op(var("index"), TokenType::Plus, literal("1")),
+                pos_start.clone(),
+            )),
+            Self::loop_body(
+                node,
+                element_type,
+                Box::new(DereferenceNode::new(bin_op(
+                    var("items"),
+                    TokenType::Offset,
+                    var("index"),
+                ))),
+            ),
+            node.label().clone(),
+        ));
+
+        for statement in [count as Box<dyn Node>, for_node] {
+            let (_, statement) = res.register_res(self.validate(&statement));
+            if res.has_error() {
+                return res;
+            }
+            statements.push(statement.unwrap());
+        }
+
+        res.success(
+            Box::new(IgnoredType::new()),
+            Box::new(StatementsNode::new(
+                statements,
+                false,
+                node.pos_start().clone(),
+                node.pos_end().clone(),
             )),
         );
         res
     }
 
+    /// Declares the already validated value as an immutable, hidden variable of the loop.
+    fn hidden_var(
+        &mut self,
+        node: &ForInNode,
+        suffix: &str,
+        value_type: &Box<dyn ValueType>,
+        value_node: Box<dyn Node>,
+    ) -> Box<dyn Node> {
+        let name = format!("{}.{}", node.var_name(), suffix);
+        self.declare_symbol(
+            name.clone(),
+            Symbol::new(value_type.clone(), false),
+            node.pos_start().clone(),
+        );
+        Box::new(VarDeclarationNode::new(
+            name,
+            value_type.clone(),
+            value_node,
+            false,
+            node.pos_start().clone(),
+        ))
+    }
+
+    /// Prepends the declaration of the immutable loop variable to the body of the loop.
+    fn loop_body(
+        node: &ForInNode,
+        var_type: Box<dyn ValueType>,
+        value: Box<dyn Node>,
+    ) -> Box<dyn Node> {
+        Box::new(StatementsNode::new(
+            vec![
+                Box::new(VarDeclarationNode::new(
+                    node.var_name().to_string(),
+                    var_type,
+                    value,
+                    false,
+                    node.pos_start().clone(),
+                )),
+                node.body().clone(),
+            ],
+            false,
+            node.pos_start().clone(),
+            node.pos_end().clone(),
+        ))
+    }
+
     fn validate_if_node(&mut self, node: &IfNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
         let mut cases: Vec<IfCase> = vec![];
+        let mut branch_types: Vec<Box<dyn ValueType>> = vec![];
+        // the paths leaving the branches, which starts out as no path at all
+        let mut after_branches = Flow {
+            diverged: true,
+            ..Flow::default()
+        };
         for case in node.cases() {
             let (condition_type, condition_node) =
                 res.register_res(self.validate(case.condition()));
@@ -1242,21 +3068,26 @@ impl Validator {
                 return res;
             }
 
+            // the next condition is only evaluated, if this one was false
+            let after_condition = self.flow.clone();
             self.push_child_scope(ScopeType::Block);
-            let (_, statements) = res.register_res(self.validate(case.statements()));
+            let (statements_type, statements) = res.register_res(self.validate(case.statements()));
             self.pop_child_scope();
 
             if res.has_error() {
                 return res;
             }
 
+            after_branches =
+                after_branches.join(std::mem::replace(&mut self.flow, after_condition));
+            branch_types.push(statements_type.unwrap());
             cases.push(IfCase::new(condition_node.unwrap(), statements.unwrap()));
         }
 
         let mut else_case: Option<ElseCase> = None;
         if node.else_case().is_some() {
             self.push_child_scope(ScopeType::Block);
-            let (_, statements) =
+            let (statements_type, statements) =
                 res.register_res(self.validate(node.else_case().as_ref().unwrap().statements()));
             self.pop_child_scope();
 
@@ -1264,18 +3095,73 @@ impl Validator {
                 return res;
             }
 
+            branch_types.push(statements_type.unwrap());
             else_case = Some(ElseCase::new(statements.unwrap()));
         }
 
-        // TODO: check if all code paths return a value
+        // without an `else`, the paths on which every condition was false skip the branches
+        self.flow = after_branches.join(std::mem::take(&mut self.flow));
+
+        // only an `if` with an `else` where every branch has a value is an expression
+        let has_value = else_case.is_some()
+            && branch_types
+                .iter()
+                .all(|t| t.value_type() != ValueTypes::Ignored);
+        if !has_value {
+            let cases = cases
+                .into_iter()
+                .map(|case| {
+                    IfCase::new(
+                        case.condition().clone(),
+                        Self::discard_value(case.statements().clone()),
+                    )
+                })
+                .collect();
+            let else_case = else_case.map(|else_case| {
+                ElseCase::new(Self::discard_value(else_case.statements().clone()))
+            });
+
+            res.success(
+                Box::new(IgnoredType::new()),
+                Box::new(IfNode::new(cases, else_case)),
+            );
+            return res;
+        }
+
+        if let Some(branch_type) = branch_types.iter().find(|t| !branch_types[0].eq(t)) {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Branches of if have different types {} and {}!",
+                    branch_types[0], branch_type
+                )
+                .as_str(),
+            ));
+            return res;
+        }
 
         res.success(
-            Box::new(IgnoredType::new()),
+            branch_types.swap_remove(0),
             Box::new(IfNode::new(cases, else_case)),
         );
         res
     }
 
+    /// Drops the value of a block whose value isn't used, like the body of a loop, so the backends
+    /// only produce values that are consumed.
+    fn discard_value(node: Box<dyn Node>) -> Box<dyn Node> {
+        match node.as_any().downcast_ref::<StatementsNode>() {
+            Some(statements_node) if statements_node.has_value() => Box::new(StatementsNode::new(
+                statements_node.statement_nodes().clone(),
+                false,
+                statements_node.pos_start().clone(),
+                statements_node.pos_end().clone(),
+            )),
+            _ => node,
+        }
+    }
+
     fn validate_cast_node(&mut self, node: &CastNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
@@ -1316,6 +3202,45 @@ impl Validator {
             return res;
         }
 
+        // slices of literals and arrays have the length known at compile time
+        if node.cast_type().value_type() == ValueTypes::Slice {
+            let cast_node = cast_node.unwrap();
+            let length = match node_type
+                .as_ref()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<ArrayType>()
+            {
+                Some(array_type) => array_type.length() as u64,
+                None => match cast_node.as_any().downcast_ref::<StringNode>() {
+                    Some(string_node) => unescape(&string_node.get_string()).len() as u64,
+                    None => {
+                        res.failure(error::semantic_error(
+                            node.pos_start().clone(),
+                            node.pos_end().clone(),
+                            format!(
+                                "Only string literals can be converted to {}!",
+                                node.cast_type()
+                            )
+                            .as_str(),
+                        ));
+                        return res;
+                    }
+                },
+            };
+
+            res.success(
+                node.cast_type().clone(),
+                Self::slice(
+                    cast_node,
+                    Self::number(length, node.pos_start(), node.pos_end()),
+                    node.pos_start(),
+                    node.pos_end(),
+                ),
+            );
+            return res;
+        }
+
         res.success(
             node.cast_type().clone(),
             Box::new(CastNode::new(
@@ -1367,10 +3292,10 @@ impl Validator {
         res
     }
 
-    fn validate_sizeof_node(&self, node: &SizeOfNode) -> ValidationResult {
+    fn validate_sizeof_node(&mut self, node: &SizeOfNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
-        let mut size = node.value_type().get_size().get_size_in_bytes();
+        let mut size = node.value_type().size_in_bytes();
         if node.value_type().value_type() == ValueTypes::Struct {
             let struct_type = node
                 .value_type()
@@ -1388,7 +3313,11 @@ impl Validator {
 
             size = 0;
             for (_, field_type) in self.structs[struct_type.name()].iter() {
-                size += field_type.get_size().get_size_in_bytes();
+                size += field_type.size_in_bytes();
+            }
+
+            if let Some(info) = &mut self.info {
+                info.reference_struct(struct_type.name(), node.pos_start());
             }
         }
 
@@ -1407,6 +3336,198 @@ impl Validator {
         res
     }
 
+    fn validate_lenof_node(&mut self, node: &LenOfNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let (value_type, value_node) = res.register_res(self.validate(node.node()));
+        if res.has_error() {
+            return res;
+        }
+
+        // the length of slices is only known at runtime
+        if value_type.as_ref().unwrap().value_type() == ValueTypes::Slice {
+            res.success(
+                Box::new(U64Type::new()),
+                Self::slice_length(value_node.unwrap(), node.pos_end()),
+            );
+            return res;
+        }
+
+        let length = match value_type
+            .as_ref()
+            .unwrap()
+            .as_any()
+            .downcast_ref::<ArrayType>()
+        {
+            Some(array_type) => array_type.length(),
+            None => {
+                res.failure(error::semantic_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    format!(
+                        "Can't get the length of non-array type {}!",
+                        value_type.as_ref().unwrap()
+                    )
+                    .as_str(),
+                ));
+                return res;
+            }
+        };
+
+        res.success(
+            Box::new(U64Type::new()),
+            Box::new(NumberNode::new(
+                Token::new_with_value(
+                    TokenType::U64,
+                    length.to_string(),
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                ),
+                Box::new(U64Type::new()),
+            )),
+        );
+        res
+    }
+
+    fn validate_slice_node(&mut self, node: &SliceNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let (value_type, value_node) = res.register_res(self.validate(node.node()));
+        if res.has_error() {
+            return res;
+        }
+
+        let mut bound_nodes: Vec<Box<dyn Node>> = vec![];
+        for bound in [node.start(), node.end()] {
+            let (bound_type, bound_node) = res.register_res(self.validate(bound));
+            if res.has_error() {
+                return res;
+            }
+
+            if bound_type.as_ref().unwrap().value_type() != ValueTypes::U64 {
+                res.failure(error::semantic_error(
+                    bound.pos_start().clone(),
+                    bound.pos_end().clone(),
+                    format!(
+                        "Expected type 'u64' as bound of a slice, got '{}'!",
+                        bound_type.as_ref().unwrap()
+                    )
+                    .as_str(),
+                ));
+                return res;
+            }
+
+            bound_nodes.push(bound_node.unwrap());
+        }
+        let end_node = bound_nodes.pop().unwrap();
+        let start_node = bound_nodes.pop().unwrap();
+
+        // arrays and pointers are sliced through a slice of their elements, the length of which
+        // isn't needed for pointers
+        let value_type = value_type.unwrap();
+        let value_node = value_node.unwrap();
+        let (element_type, slice_node) =
+            if let Some(array_type) = value_type.as_any().downcast_ref::<ArrayType>() {
+                (
+                    array_type.element_type().clone(),
+                    Self::slice(
+                        value_node,
+                        Self::number(array_type.length() as u64, node.pos_start(), node.pos_end()),
+                        node.pos_start(),
+                        node.pos_end(),
+                    ),
+                )
+            } else if let Some(pointer_type) = value_type.as_any().downcast_ref::<PointerType>() {
+                (
+                    pointer_type.pointee_type().clone(),
+                    Self::slice(
+                        value_node,
+                        Self::number(0, node.pos_start(), node.pos_end()),
+                        node.pos_start(),
+                        node.pos_end(),
+                    ),
+                )
+            } else if let Some(slice_type) = value_type.as_any().downcast_ref::<SliceType>() {
+                (slice_type.element_type().clone(), value_node)
+            } else {
+                res.failure(error::semantic_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    format!("Can't slice values of type {}!", value_type).as_str(),
+                ));
+                return res;
+            };
+
+        let slice_type: Box<dyn ValueType> = Box::new(SliceType::new(element_type.clone()));
+        let element_size = element_type.size_in_bytes();
+        if !self.checks.contains(&RuntimeCheck::Bounds) {
+            res.success(
+                slice_type,
+                Box::new(SliceNode::new_typed(
+                    slice_node,
+                    start_node,
+                    end_node,
+                    element_size,
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                )),
+            );
+            return res;
+        }
+
+        // the bounds are stored, so they are evaluated once and in order, before checking
+        // `start <= end <= length`, where the length of pointers is unknown
+        let (pos_start, pos_end) = (node.pos_start(), node.pos_end());
+        let u64_type: Box<dyn ValueType> = Box::new(U64Type::new());
+        let (slice_declaration, slice_node) =
+            self.check_var("slice", slice_type.clone(), slice_node, pos_start, pos_end);
+        let (start_declaration, start_node) =
+            self.check_var("start", u64_type.clone(), start_node, pos_start, pos_end);
+        let end_node = if value_type.value_type() == ValueTypes::Pointer {
+            end_node
+        } else {
+            Box::new(CheckNode::with_length(
+                Check::SliceRange,
+                end_node,
+                Self::slice_length(slice_node.clone(), pos_end),
+                pos_start.clone(),
+                pos_end.clone(),
+            ))
+        };
+        let (end_declaration, end_node) =
+            self.check_var("end", u64_type, end_node, pos_start, pos_end);
+        let start_node = Box::new(CheckNode::with_length(
+            Check::SliceRange,
+            start_node,
+            end_node.clone(),
+            pos_start.clone(),
+            pos_end.clone(),
+        ));
+
+        res.success(
+            slice_type,
+            Box::new(StatementsNode::new(
+                vec![
+                    slice_declaration,
+                    start_declaration,
+                    end_declaration,
+                    Box::new(SliceNode::new_typed(
+                        slice_node,
+                        start_node,
+                        end_node,
+                        element_size,
+                        pos_start.clone(),
+                        pos_end.clone(),
+                    )),
+                ],
+                true,
+                pos_start.clone(),
+                pos_end.clone(),
+            )),
+        );
+        res
+    }
+
     fn validate_static_def_node(&mut self, node: &StaticDefinitionNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
@@ -1429,11 +3550,31 @@ impl Validator {
             return res;
         }
 
+        // statics are initialized in the data sections of the binary
+        if constant::evaluate(assign_node.as_ref().unwrap()).is_none() {
+            res.failure(error::semantic_error(
+                node.value().pos_start().clone(),
+                node.value().pos_end().clone(),
+                format!(
+                    "The value of static '{}' has to be known at compile time!",
+                    node.name()
+                )
+                .as_str(),
+            ));
+            return res;
+        }
+
         self.declare_symbol(
             node.name().to_string(),
             Symbol::new(assign_type.as_ref().unwrap().clone(), *node.is_mutable()),
             node.pos_start().clone(),
         );
+        self.record_declaration(
+            node.name(),
+            DeclarationKind::Static,
+            assign_type.as_ref().unwrap().as_ref(),
+            node.pos_start(),
+        );
 
         res.success(
             assign_type.unwrap(),
@@ -1456,6 +3597,12 @@ impl Validator {
             Symbol::new(node.value_type().box_clone(), *node.is_mutable()),
             node.pos_start().clone(),
         );
+        self.record_declaration(
+            node.name(),
+            DeclarationKind::Static,
+            node.value_type().as_ref(),
+            node.pos_start(),
+        );
 
         res.success(node.value_type().box_clone(), node.box_clone());
         res
@@ -1480,6 +3627,20 @@ impl Validator {
         self.structs
             .insert(node.name().to_string(), node.fields().clone());
 
+        if let Some(info) = &mut self.info {
+            info.declare_struct(
+                node.name(),
+                node.pos_start(),
+                node.fields()
+                    .iter()
+                    .zip(node.field_positions())
+                    .map(|((name, field_type), pos)| {
+                        (name.clone(), field_type.clone(), pos.clone())
+                    })
+                    .collect(),
+            );
+        }
+
         res.success(Box::new(IgnoredType::new()), node.box_clone());
         res
     }
@@ -1510,7 +3671,12 @@ impl Validator {
                 .pointee_type()
                 .clone(),
             Box::new(ReadBytesNode::new(
-                value_node.unwrap(),
+                self.check(
+                    Check::Null,
+                    value_node.unwrap(),
+                    node.pos_start(),
+                    node.pos_end(),
+                ),
                 *node.bytes(),
                 node.pos_end().clone(),
             )),
@@ -1535,30 +3701,23 @@ impl Validator {
             return res;
         }
 
-        let pointee_type_size = node_type
+        let pointee_type = node_type
             .as_ref()
             .unwrap()
             .as_any()
             .downcast_ref::<PointerType>()
             .unwrap()
             .pointee_type()
-            .get_size();
-
-        res.success(
-            node_type
-                .as_ref()
-                .unwrap()
-                .as_any()
-                .downcast_ref::<PointerType>()
-                .unwrap()
-                .pointee_type()
-                .clone(),
-            Box::new(ReadBytesNode::new(
-                value_node.unwrap(),
-                pointee_type_size,
-                node.pos_end().clone(),
-            )),
+            .clone();
+        let value_node = self.check(
+            Check::Null,
+            value_node.unwrap(),
+            node.pos_start(),
+            node.pos_end(),
         );
+
+        let value_node = self.load(&pointee_type, value_node, node.pos_end());
+        res.success(pointee_type, value_node);
         res
     }
 
@@ -1584,6 +3743,7 @@ impl Validator {
             Box::new(IgnoredType::new()),
             Box::new(ImportNode::new(Box::new(StatementsNode::new(
                 stmts,
+                false,
                 node.node().pos_start().clone(),
                 node.node().pos_end().clone(),
             )))),
@@ -1613,10 +3773,54 @@ impl Validator {
             return res;
         }
 
-        if node_type.as_ref().unwrap().value_type() != ValueTypes::Pointer
+        // unlike the fields of structures, the fields of slices are read as values
+        if let Some(slice_type) = node_type
+            .as_ref()
+            .unwrap()
+            .as_any()
+            .downcast_ref::<SliceType>()
+        {
+            match node.accessor() {
+                "ptr" => res.success(
+                    Box::new(PointerType::new(slice_type.element_type().clone(), false)),
+                    Box::new(ReadBytesNode::new(
+                        value_node.unwrap(),
+                        ValueSize::Qword,
+                        node.pos_end().clone(),
+                    )),
+                ),
+                "len" => res.success(
+                    Box::new(U64Type::new()),
+                    Self::slice_length(value_node.unwrap(), node.pos_end()),
+                ),
+                _ => res.failure(error::semantic_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    format!(
+                        "Field '{}' was not found in slice type {}!",
+                        node.accessor(),
+                        slice_type
+                    )
+                    .as_str(),
+                )),
+            }
+            return res;
+        }
+
+        self.validate_field(node, node_type.unwrap(), value_node.unwrap())
+    }
+
+    /// Validates the field access `node` on `value_node`, which has to be a pointer to a struct.
+    fn validate_field(
+        &mut self,
+        node: &AccessorNode,
+        node_type: Box<dyn ValueType>,
+        value_node: Box<dyn Node>,
+    ) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        if node_type.value_type() != ValueTypes::Pointer
             || node_type
-                .as_ref()
-                .unwrap()
                 .as_any()
                 .downcast_ref::<PointerType>()
                 .unwrap()
@@ -1632,12 +3836,7 @@ impl Validator {
             return res;
         }
 
-        let pointer_type = node_type
-            .as_ref()
-            .unwrap()
-            .as_any()
-            .downcast_ref::<PointerType>()
-            .unwrap();
+        let pointer_type = node_type.as_any().downcast_ref::<PointerType>().unwrap();
         let struct_type = pointer_type
             .pointee_type()
             .as_any()
@@ -1657,6 +3856,10 @@ impl Validator {
             return res;
         }
 
+        if let Some(info) = &mut self.info {
+            info.reference_field(struct_type.name(), node.accessor(), node.accessor_pos());
+        }
+
         let s_struct = &self.structs[struct_type.name()];
 
         let mut offset: usize = 0;
@@ -1669,7 +3872,7 @@ impl Validator {
                 break;
             }
 
-            offset += field_type.get_size().get_size_in_bytes() as usize;
+            offset += field_type.size_in_bytes() as usize;
         }
 
         if !found {
@@ -1692,7 +3895,7 @@ impl Validator {
                 *pointer_type.is_mutable(),
             )),
             Box::new(BinOpNode::new(
-                value_node.unwrap(),
+                self.check(Check::Null, value_node, node.pos_start(), node.pos_end()),
                 Token::new_without_value(TokenType::Plus, Position::empty(), Position::empty()),
                 Box::new(NumberNode::new(
                     Token::new_with_value(
@@ -1755,8 +3958,39 @@ impl Validator {
             return res;
         }
 
+        // the pointer could be used to read the variable
+        if !self.flow.diverged && self.flow.unassigned.contains(node.var_name()) {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Variable '{}' might be read before it's assigned!",
+                    node.var_name()
+                )
+                .as_str(),
+            ));
+            return res;
+        }
+
         let (s, _) = self.get_symbol(node.var_name()).unwrap();
 
+        // the value of array and slice variables already is the address of their memory
+        if matches!(
+            s.value_type().value_type(),
+            ValueTypes::Array | ValueTypes::Slice
+        ) {
+            res.success(
+                Box::new(PointerType::new(s.value_type().clone(), s.is_mutable())),
+                Box::new(VarTypedAccessNode::new(
+                    node.var_name().to_string(),
+                    s.value_type().clone(),
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                )),
+            );
+            return res;
+        }
+
         res.success(
             Box::new(PointerType::new(s.value_type().clone(), s.is_mutable())),
             node.box_clone(),
@@ -1764,6 +3998,88 @@ impl Validator {
         res
     }
 
+    fn validate_assign_node(&mut self, node: &AssignNode) -> ValidationResult {
+        let mut res = ValidationResult::new();
+
+        let (place_type, place_node) = res.register_res(self.validate_place(node.target()));
+        if res.has_error() {
+            return res;
+        }
+
+        let place_type = place_type.unwrap();
+        let pointer_type = place_type.as_any().downcast_ref::<PointerType>().unwrap();
+        let pointee_type = pointer_type.pointee_type().box_clone();
+        if !*pointer_type.is_mutable() {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!("Can't assign to immutable memory of type {}!", pointee_type).as_str(),
+            ));
+            return res;
+        }
+
+        let (mut assign_type, mut assign_node) = res.register_res(self.validate(node.value_node()));
+        if res.has_error() {
+            return res;
+        }
+
+        if let Some(op_token) = node.op_token() {
+            // the target is read and written, so it must not matter that it's evaluated twice
+            if !Self::is_pure(node.target()) {
+                res.failure(error::semantic_error(
+                    node.pos_start().clone(),
+                    node.pos_end().clone(),
+                    "Targets of compound assignments can't have side effects!",
+                ));
+                return res;
+            }
+
+            let read_node = self.load(
+                &pointee_type,
+                place_node.as_ref().unwrap().clone(),
+                node.target().pos_end(),
+            );
+            (assign_type, assign_node) = res.register_res(self.bin_op(
+                &BinOpNode::new(
+                    node.target().clone(),
+                    op_token.clone(),
+                    node.value_node().clone(),
+                ),
+                pointee_type.clone(),
+                read_node,
+                assign_type.unwrap(),
+                assign_node.unwrap(),
+            ));
+            if res.has_error() {
+                return res;
+            }
+        }
+
+        if !Self::is_assignable(assign_type.as_ref().unwrap(), &pointee_type) {
+            res.failure(error::semantic_error(
+                node.pos_start().clone(),
+                node.pos_end().clone(),
+                format!(
+                    "Target type {} does not match assign type {}!",
+                    pointee_type,
+                    assign_type.as_ref().unwrap()
+                )
+                .as_str(),
+            ));
+            return res;
+        }
+
+        res.success(
+            assign_type.unwrap(),
+            self.store(pointee_type, place_node.unwrap(), assign_node.unwrap()),
+        );
+        res
+    }
+
+    fn validate_reference_node(&mut self, node: &ReferenceNode) -> ValidationResult {
+        self.validate_place(node.node())
+    }
+
     fn validate_assembly_node(&self, node: &AssemblyNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
@@ -1771,7 +4087,7 @@ impl Validator {
         res
     }
 
-    fn validate_struct_init_node(&self, node: &StructInitNode) -> ValidationResult {
+    fn validate_struct_init_node(&mut self, node: &StructInitNode) -> ValidationResult {
         let mut res = ValidationResult::new();
 
         if !self.structs.contains_key(node.struct_name()) {
@@ -1783,10 +4099,14 @@ impl Validator {
             return res;
         }
 
+        if let Some(info) = &mut self.info {
+            info.reference_struct(node.struct_name(), node.pos_start());
+        }
+
         let struct_size = self.structs[node.struct_name()]
             .iter()
             .fold(0_u64, |acc, (_, field_type)| {
-                acc + field_type.get_size().get_size_in_bytes() as u64
+                acc + field_type.size_in_bytes()
             });
 
         res.success(
@@ -1841,4 +4161,666 @@ mod tests {
         assert_eq!(v.has_symbol("a"), true);
         assert_eq!(v.has_symbol("b"), false);
     }
+
+    /// Validates the statements inside of a function, returning the details of the innermost
+    /// error.
+    fn validation_error(declarations: &str, statements: &str) -> Option<String> {
+        let source = format!(
+            "{}\nfun main(): u64 {{\n{}\nreturn 0;\n}};",
+            declarations, statements
+        );
+        let tokens = crate::lexer::Lexer::new(std::path::PathBuf::new(), source)
+            .make_tokens()
+            .unwrap();
+        let mut macros = HashMap::new();
+        let mut already_included = vec![];
+        let ast_root =
+            crate::parser::Parser::new(tokens, &vec![], &mut macros, &mut already_included)
+                .parse()
+                .unwrap();
+
+        let mut error = Validator::new().validate(&ast_root).error().clone()?;
+        while let Some(parent) = error.parent().clone() {
+            error = *parent;
+        }
+        Some(error.details().to_string())
+    }
+
+    #[test]
+    pub fn semantics_variadic_calls() {
+        let declarations = "fun nothing(): void { return; };
+extern fun printf(fmt: char *, ...): i32;";
+
+        assert_eq!(
+            validation_error(declarations, "printf(\"a\" as char *);"),
+            None
+        );
+        assert_eq!(
+            validation_error(declarations, "printf(\"%d %f\" as char *, 1, 2.5);"),
+            None
+        );
+        assert_eq!(
+            validation_error(declarations, "printf();"),
+            Some(String::from(
+                "Function expected at least 1 arguments. 0 were passed!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "printf(\"%d\" as char *, nothing());"
+            ),
+            Some(String::from(
+                "Values of type 'void' can't be passed as variadic argument at index 1!"
+            ))
+        );
+    }
+
+    #[test]
+    pub fn semantics_arrays() {
+        let declarations = "struct packet { len: u64, data: [u8; 4] };
+fun first(values: [u8; 4]): u8 { return *values[0]; };
+extern fun printf(fmt: char *, ...): i32;";
+
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let a: [u8; 4] = [<u8>; 4];
+let mut b: [u8; 4] = a;
+b = [<u8> 1 as u8, 2 as u8, 3 as u8, 4 as u8];
+let p: struct packet *mut = struct packet {};
+p.data @= b;
+let c: [u8; 4] = *p.data;
+let x: u8 = first(c) + *c[lenof[c] - 1];
+let q: u8 *mut = c as u8 *mut;"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error(declarations, "let a: u8 *mut = [<u8>; 4];"),
+            Some(String::from(
+                "Type '[u8; 4]' can't be assigned to type 'u8* mut'!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "let a: [u8; 2] = [<u8>; 4];"),
+            Some(String::from(
+                "Type '[u8; 4]' can't be assigned to type '[u8; 2]'!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "let a: u64 = lenof[1];"),
+            Some(String::from("Can't get the length of non-array type u64!"))
+        );
+        assert_eq!(
+            validation_error(declarations, "printf(\"%p\" as char *, [<u8>; 4]);"),
+            Some(String::from(
+                "Values of type '[u8; 4]' can't be passed as variadic argument at index 1!"
+            ))
+        );
+        assert_eq!(
+            validation_error("fun f(): [u8; 4] { return [<u8>; 4]; };", ""),
+            Some(String::from(
+                "Function 'f' can't return the array type [u8; 4]!"
+            ))
+        );
+    }
+
+    #[test]
+    pub fn semantics_slices() {
+        let declarations = "struct text { name: str };
+fun count(s: str, values: []u16): u64 { return s.len + lenof[values]; };
+extern fun printf(fmt: char *, ...): i32;";
+
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let s: str = \"hello\" as str;
+let a: [u16; 3] = [<u16>; 3];
+let mut v: []u16 = a as []u16;
+v = v[1..lenof[v]];
+let p: char * = s.ptr;
+let t: struct text *mut = struct text {};
+t.name @= p[0..2];
+let n: u64 = count(s[1..3], v) + ((*s[0]) as u64);"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error(declarations, "let s: str = \"hello\";"),
+            Some(String::from(
+                "Type 'string' can't be assigned to type 'str'!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let x: string = \"a\";\nlet s: str = x as str;"
+            ),
+            Some(String::from(
+                "Only string literals can be converted to str!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let s: str = \"hello\" as str;\nlet t: str = s[1 as u8..2];"
+            ),
+            Some(String::from(
+                "Expected type 'u64' as bound of a slice, got 'u8'!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "let x: u64 = 1;\nlet s: str = x[0..1];"),
+            Some(String::from("Can't slice values of type u64!"))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let s: str = \"hello\" as str;\nlet n: u64 = s.size;"
+            ),
+            Some(String::from(
+                "Field 'size' was not found in slice type str!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "printf(\"%s\" as char *, \"hello\" as str);"),
+            Some(String::from(
+                "Values of type 'str' can't be passed as variadic argument at index 1!"
+            ))
+        );
+        assert_eq!(
+            validation_error("fun f(s: []u8): []u8 { return s; };", ""),
+            Some(String::from(
+                "Function 'f' can't return the slice type []u8!"
+            ))
+        );
+    }
+
+    #[test]
+    pub fn semantics_memory_intrinsics() {
+        let declarations = "struct pair { a: u64, b: u64 };";
+
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let buf: [u8; 64] = [<u8>; 64];
+let p: u8 *mut = buf as u8 *mut;
+let s: struct pair *mut = struct pair {};
+memset[p, 0xff as u8, 64];
+memcpy[s as u8 *mut, p, sizeof[struct pair]];
+memmove[p + 1, p, 63];"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error(declarations, "let x: u64 = 0;\nmemset[x, 0 as u8, 8];"),
+            Some(String::from(
+                "Expected a pointer as destination of memset, got 'u64'!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let p: u8 *mut = 0 as u8 *mut;\nmemset[p, 0, 8];"
+            ),
+            Some(String::from(
+                "Expected type 'u8' as value of memset, got 'u64'!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let p: u8 *mut = 0 as u8 *mut;\nmemcpy[p, 1, 8];"
+            ),
+            Some(String::from(
+                "Expected a pointer as source of memcpy, got 'u64'!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let p: u8 *mut = 0 as u8 *mut;\nmemmove[p, p, 8 as u32];"
+            ),
+            Some(String::from(
+                "Expected type 'u64' as size of memmove, got 'u32'!"
+            ))
+        );
+    }
+
+    #[test]
+    pub fn semantics_value_expressions() {
+        assert_eq!(
+            validation_error(
+                "",
+                "let a: u64 = 3;
+let x: u64 = if a > 2 { a } else if a > 1 { 2 } else { 1 };
+let y: u64 = { let t: u64 = a * 2; t + 1 };
+let mut n: u64 = 0;
+while n < 3 { n += 1 };
+if n == 3 { n = 0 };"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error("", "let x: u64 = if 1 == 1 { 1 } else { 'c' };"),
+            Some(String::from(
+                "Branches of if have different types u64 and char!"
+            ))
+        );
+        // without an `else`, an `if` has no value
+        assert_eq!(
+            validation_error("", "let x: u64 = if 1 == 1 { 1 };"),
+            Some(String::from(
+                "Type 'IgnoredType' can't be assigned to type 'u64'!"
+            ))
+        );
+    }
+
+    #[test]
+    pub fn semantics_for_in() {
+        assert_eq!(
+            validation_error(
+                "",
+                "let arr: [u8; 3] = [<u8> 1 as u8, 2 as u8, 3 as u8];
+let mut n: u64 = 0;
+for i in 0..3 { n += i };
+for i in (0..n).step(2) { n += 1 };
+for i in ((1 as i32)..(4 as i32)).rev() { n += i as u64 };
+for x in arr { n += x as u64 };
+for y in arr as []u8 { n += y as u64 };"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error("", "for i in 0..(3 as u8) { 1 };"),
+            Some(String::from(
+                "Expected end of range to be of type 'u64', got 'u8'!"
+            ))
+        );
+        assert_eq!(
+            validation_error("", "for c in 'a'..'z' { 1 };"),
+            Some(String::from(
+                "Ranges must be of an integer type, got 'char'!"
+            ))
+        );
+        assert_eq!(
+            validation_error("", "let n: u64 = 3;\nfor x in n { 1 };"),
+            Some(String::from(
+                "Expected a range, an array or a slice, got 'u64'!"
+            ))
+        );
+        assert_eq!(
+            validation_error("", "for i in 0..3 { i = 2 };"),
+            Some(String::from("Variable 'i' is not mutable!"))
+        );
+        assert_eq!(
+            validation_error("", "for i in 0..3 { for i in 0..2 { 1 } };"),
+            Some(String::from("Previous declaration of 'i'"))
+        );
+    }
+
+    #[test]
+    pub fn semantics_labels_and_defer() {
+        assert_eq!(
+            validation_error(
+                "",
+                "let mut n: u64 = 0;
+'outer: for i in 0..3 {
+    defer n += 1;
+    'inner: while n < 10 {
+        if n == 5 { break 'outer };
+        if n == 7 { continue 'inner };
+        continue 'outer;
+    };
+};
+{
+    defer n = 0;
+    n += 2;
+};"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error("", "while 1 == 1 { break 'outer };"),
+            Some(String::from(
+                "Label 'outer' doesn't belong to an enclosing loop!"
+            ))
+        );
+        assert_eq!(
+            validation_error("", "'a: while 1 == 1 { 'a: while 1 == 1 { break } };"),
+            Some(String::from(
+                "Label 'a' is already used by an enclosing loop!"
+            ))
+        );
+        assert_eq!(
+            validation_error("", "while 1 == 1 { defer break };"),
+            Some(String::from(
+                "Break statement can't leave a deferred statement!"
+            ))
+        );
+        assert_eq!(
+            validation_error("", "defer return 0;"),
+            Some(String::from(
+                "Return statement can't leave a deferred statement!"
+            ))
+        );
+        assert_eq!(
+            validation_error("", "defer let n: u64 = 0;"),
+            Some(String::from("Variable declarations can't be deferred!"))
+        );
+    }
+
+    #[test]
+    pub fn semantics_return_paths() {
+        assert_eq!(
+            validation_error(
+                "fun sign(n: i64): i64 {
+    if n < (0 as i64) { return (0 as i64) - (1 as i64); } else if n == (0 as i64) { return n; } else { return 1 as i64; };
+};
+fun first(n: u64): u64 {
+    let mut i: u64 = 0;
+    while 1 == 1 {
+        if i * i >= n { return i; };
+        i += 1;
+    };
+};
+fun log(n: u64): void {
+    if n == 0 { return; };
+};",
+                ""
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error("fun f(n: u64): u64 { if n == 0 { return 1; }; };", ""),
+            Some(String::from(
+                "Function 'f' doesn't return a value on every path!"
+            ))
+        );
+        assert_eq!(
+            validation_error("fun f(n: u64): u64 { while n > 0 { return n; }; };", ""),
+            Some(String::from(
+                "Function 'f' doesn't return a value on every path!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "fun f(n: u64): u64 { while 1 == 1 { if n == 0 { break; }; return n; }; };",
+                ""
+            ),
+            Some(String::from(
+                "Function 'f' doesn't return a value on every path!"
+            ))
+        );
+    }
+
+    #[test]
+    pub fn semantics_definite_assignment() {
+        assert_eq!(
+            validation_error(
+                "",
+                "let n: u64 = 3;
+let x: u64;
+if n > 2 { x = 1 } else if n > 1 { x = 2 } else { return 0 };
+let mut y: u64;
+while n > 0 { y = 1; break };
+y = x;
+let arr: [u64; 2];
+arr = [<u64> x, y];"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let x: u64;
+let y: u64 = x + 1;"
+            ),
+            Some(String::from(
+                "Variable 'x' might be read before it's assigned!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let x: u64;
+if 1 == 1 { x = 1 };
+let y: u64 = x;"
+            ),
+            Some(String::from(
+                "Variable 'x' might be read before it's assigned!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let mut x: u64;
+while 1 == 2 { x = 1 };
+let y: u64 = x;"
+            ),
+            Some(String::from(
+                "Variable 'x' might be read before it's assigned!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let mut x: u64;
+let c: bool = 1 == 2 && { x = 1; 1 == 1 };
+let y: u64 = x;"
+            ),
+            Some(String::from(
+                "Variable 'x' might be read before it's assigned!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let mut x: u64;
+defer x = 1;
+let y: u64 = x;"
+            ),
+            Some(String::from(
+                "Variable 'x' might be read before it's assigned!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let x: u64;
+let p: u64 * = &x;
+let y: u64 = *p;"
+            ),
+            Some(String::from(
+                "Variable 'x' might be read before it's assigned!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let x: u64;
+x = 1;
+x = 2;"
+            ),
+            Some(String::from(
+                "Variable 'x' might be assigned more than once!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let x: u64;
+while 1 == 2 { x = 1 };"
+            ),
+            Some(String::from(
+                "Variable 'x' might be assigned more than once!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "",
+                "let x: u64 = 1;
+x = 2;"
+            ),
+            Some(String::from("Variable 'x' is not mutable!"))
+        );
+    }
+
+    #[test]
+    pub fn semantics_lvalues() {
+        let declarations = "struct pair { a: u64, b: u64 };";
+
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let mut arr: [u64; 4] = [<u64>; 4];
+let p: struct pair *mut = struct pair {};
+let q: u64 *mut = &p.b;
+arr[1] = 2;
+*arr[2] += *arr[1];
+p.a = 3;
+*p.b <<= 2;
+*q = lenof[arr];
+let e: u64 *mut = &arr[3];
+let f: u64 *mut = &*q;"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error(declarations, "let arr: [u64; 4] = [<u64>; 4];\narr[1] = 2;"),
+            Some(String::from(
+                "Can't assign to immutable memory of type u64!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let p: struct pair *mut = struct pair {};
+let r: struct pair * = p as struct pair *;
+r.a += 1;"
+            ),
+            Some(String::from(
+                "Can't assign to immutable memory of type u64!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "let s: str = \"abc\" as str;\n*s[0] = 'x';"),
+            Some(String::from(
+                "Can't assign to immutable memory of type char!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "let x: u64 = 0;\n&(x + 1);"),
+            Some(String::from(
+                "Expected a field, an element or a dereferenced pointer!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "let s: str = \"abc\" as str;\n&s.len;"),
+            Some(String::from(
+                "Field 'len' of slices can't be assigned or referenced!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let p: struct pair *mut = struct pair {};\np.a = 'c';"
+            ),
+            Some(String::from(
+                "Target type u64 does not match assign type char!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                "fun f(): u64 { return 0; };",
+                "let mut arr: [u64; 4] = [<u64>; 4];\narr[f()] += 1;"
+            ),
+            Some(String::from(
+                "Targets of compound assignments can't have side effects!"
+            ))
+        );
+    }
+
+    #[test]
+    pub fn semantics_pointer_mutability() {
+        let declarations = "fun set(p: u64 *mut): void { *p = 9; };";
+
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let mut x: u64 = 1;
+let p: u64 * = &x;
+let q: u64 *mut = &x;
+*q = 2;
+set(q);
+let r: u64 * = q;"
+            ),
+            None
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let mut x: u64 = 1;\nlet p: u64 * = &x;\n*p = 3;"
+            ),
+            Some(String::from(
+                "Can't assign to immutable memory of type u64!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "let x: u64 = 1;\nlet p: u64 *mut = &x;"),
+            Some(String::from(
+                "Type 'u64*' can't be assigned to type 'u64* mut'!"
+            ))
+        );
+        assert_eq!(
+            validation_error(
+                declarations,
+                "let mut y: u64 = 0;
+let x: u64 = 1;
+let mut p: u64 *mut = &y;
+p = &x;"
+            ),
+            Some(String::from(
+                "Variable type u64* mut does not match assign type u64*!"
+            ))
+        );
+        assert_eq!(
+            validation_error(declarations, "let x: u64 = 1;\nset(&x);"),
+            Some(String::from(
+                "Expected type 'u64* mut' as argument at index 0, got 'u64*'!"
+            ))
+        );
+        assert_eq!(
+            validation_error("fun get(p: u64 *): u64 *mut { return p; };", ""),
+            Some(String::from("Expected return type 'u64* mut', got 'u64*'!"))
+        );
+    }
+
+    #[test]
+    pub fn semantics_static_initializers() {
+        assert_eq!(
+            validation_error("static x: u64 = (1 << 3) + 2 * 3;", ""),
+            None
+        );
+        assert_eq!(
+            validation_error("static x: [u16; 2] = [<u16> 1 as u16, 2 as u16];", ""),
+            None
+        );
+        assert_eq!(
+            validation_error(
+                "fun one(): u64 { return 1; };\nstatic x: u64 = one() + 1;",
+                ""
+            ),
+            Some(String::from(
+                "The value of static 'x' has to be known at compile time!"
+            ))
+        );
+        assert_eq!(
+            validation_error("static x: u64 = 1;\nstatic y: u64 = x;", ""),
+            Some(String::from(
+                "The value of static 'y' has to be known at compile time!"
+            ))
+        );
+    }
 }
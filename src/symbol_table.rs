@@ -1,5 +1,6 @@
 use crate::values::value_type::ValueType;
 
+#[derive(Clone)]
 pub struct Symbol {
     value_type: Box<dyn ValueType>,
     is_mutable: bool,
@@ -3,6 +3,8 @@ use clap::ValueEnum;
 pub enum CallingConvention {
     Unix,
     Win,
+    /// The procedure call standard for the Arm 64-bit architecture, arguments are passed in x0-x7
+    Aapcs64,
 }
 
 #[derive(ValueEnum, Copy, Clone, Debug)]
@@ -11,6 +13,7 @@ pub enum TargetObjectType {
     X86_64,
     Win64,
     Macho64,
+    Aarch64Linux,
 }
 
 impl TargetObjectType {
@@ -19,6 +22,7 @@ impl TargetObjectType {
             Self::X86_64 => "elf64",
             Self::Win64 => "win64",
             Self::Macho64 => "macho64",
+            Self::Aarch64Linux => "elf64",
         }
     }
 
@@ -27,6 +31,7 @@ impl TargetObjectType {
             Self::X86_64 => "o",
             Self::Win64 => "obj",
             Self::Macho64 => "o",
+            Self::Aarch64Linux => "o",
         }
     }
 
@@ -35,6 +40,7 @@ impl TargetObjectType {
             Self::X86_64 => CallingConvention::Unix,
             Self::Win64 => CallingConvention::Win,
             Self::Macho64 => CallingConvention::Unix,
+            Self::Aarch64Linux => CallingConvention::Aapcs64,
         }
     }
 
@@ -43,12 +49,17 @@ impl TargetObjectType {
             Self::X86_64 => false,
             Self::Win64 => false,
             Self::Macho64 => true,
+            Self::Aarch64Linux => true,
         }
     }
 }
 
 pub enum SyscallTable {
     Exit,
+    Read,
+    Write,
+    Fork,
+    Wait4,
 }
 
 impl SyscallTable {
@@ -58,6 +69,32 @@ impl SyscallTable {
                 TargetObjectType::X86_64 => 60,
                 TargetObjectType::Win64 => 1,
                 TargetObjectType::Macho64 => 0x2000001,
+                TargetObjectType::Aarch64Linux => 93,
+            },
+            Self::Read => match arch {
+                TargetObjectType::X86_64 => 0,
+                TargetObjectType::Win64 => unreachable!("Windows has no stable syscall numbers"),
+                TargetObjectType::Macho64 => 0x2000003,
+                TargetObjectType::Aarch64Linux => 63,
+            },
+            Self::Write => match arch {
+                TargetObjectType::X86_64 => 1,
+                TargetObjectType::Win64 => unreachable!("Windows has no stable syscall numbers"),
+                TargetObjectType::Macho64 => 0x2000004,
+                TargetObjectType::Aarch64Linux => 64,
+            },
+            Self::Fork => match arch {
+                TargetObjectType::X86_64 => 57,
+                TargetObjectType::Win64 => unreachable!("Windows has no stable syscall numbers"),
+                TargetObjectType::Macho64 => 0x2000002,
+                // there is no fork on aarch64, `clone` with only SIGCHLD as flags does the same
+                TargetObjectType::Aarch64Linux => 220,
+            },
+            Self::Wait4 => match arch {
+                TargetObjectType::X86_64 => 61,
+                TargetObjectType::Win64 => unreachable!("Windows has no stable syscall numbers"),
+                TargetObjectType::Macho64 => 0x2000007,
+                TargetObjectType::Aarch64Linux => 260,
             },
         }
     }
@@ -135,6 +135,7 @@ pub enum TokenType {
     String,
     Char,
     Identifier,
+    Label,
     Keyword,
     Plus,
     Minus,
@@ -171,11 +172,16 @@ pub enum TokenType {
     Newline,
     Dereference,
     Dot,
+    DotDot,
+    Ellipsis,
     Offset,
     ReadBytes,
     PointerAssign,
     Bof,
     Eof,
+    // only produced by Lexer::make_lossless_tokens
+    Whitespace,
+    Comment,
 }
 
 pub static KEYWORDS: [&'static str; 34] = [
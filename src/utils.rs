@@ -16,3 +16,69 @@ pub fn escape_char(c: &char) -> Option<char> {
         _ => None,
     }
 }
+
+/// Resolves the escape sequences of a string literal, like NASM does for the backquoted strings the
+/// compiler emits them as.
+pub fn unescape(string: &str) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![];
+    let mut chars = string.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0_u8; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some(escaped) => escaped,
+            None => {
+                bytes.push(b'\\');
+                break;
+            }
+        };
+
+        match escaped {
+            'n' => bytes.push(b'\n'),
+            't' => bytes.push(b'\t'),
+            'r' => bytes.push(b'\r'),
+            'a' => bytes.push(0x07),
+            'b' => bytes.push(0x08),
+            'e' => bytes.push(0x1b),
+            'f' => bytes.push(0x0c),
+            'v' => bytes.push(0x0b),
+            'x' => {
+                let mut value = 0_u8;
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(16)) {
+                        Some(digit) => {
+                            value = value * 16 + digit as u8;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                bytes.push(value);
+            }
+            '0'..='7' => {
+                let mut value = escaped.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            other => {
+                let mut buf = [0_u8; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+
+    bytes
+}
@@ -4,6 +4,7 @@ use std::fmt::Display;
 use crate::token::Token;
 use crate::values::value_size::ValueSize;
 
+pub mod array_type;
 pub mod bool_type;
 pub mod char_type;
 pub mod f64_type;
@@ -15,6 +16,7 @@ pub mod i64_type;
 pub mod i8_type;
 pub mod ignored_type;
 pub mod pointer_type;
+pub mod slice_type;
 pub mod string_type;
 pub mod struct_type;
 pub mod u16_type;
@@ -40,6 +42,8 @@ pub enum ValueTypes {
     Void,
     Pointer,
     Struct,
+    Array,
+    Slice,
     Generic,
     Ignored,
     F64,
@@ -60,6 +64,11 @@ pub trait ValueType: ValueTypeAsAny + Display {
     fn box_clone(&self) -> Box<dyn ValueType>;
 
     fn get_size(&self) -> ValueSize;
+
+    /// The number of bytes values of this type take up in memory, e.g. as a struct field.
+    fn size_in_bytes(&self) -> u64 {
+        self.get_size().get_size_in_bytes() as u64
+    }
 }
 
 impl Clone for Box<dyn ValueType> {
@@ -70,6 +79,11 @@ impl Clone for Box<dyn ValueType> {
 
 impl PartialEq for dyn ValueType {
     fn eq(&self, other: &Self) -> bool {
+        // arrays and slices are copied, so their element types (and lengths) have to match as well
+        if matches!(self.value_type(), ValueTypes::Array | ValueTypes::Slice) {
+            return ValueType::eq(self, &other.box_clone());
+        }
+
         self.value_type() == other.value_type()
     }
 }
@@ -0,0 +1,98 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+use crate::token::{Token, TokenType};
+use crate::values::value_size::ValueSize;
+use crate::values::value_type::pointer_type::PointerType;
+use crate::values::value_type::slice_type::SliceType;
+use crate::values::value_type::{ValueType, ValueTypeAsAny, ValueTypes};
+
+/// An array with a fixed number of elements, which are stored inline, e.g. in structs. Values of
+/// this type are the address of the first element.
+#[derive(Clone)]
+pub struct ArrayType {
+    element_type: Box<dyn ValueType>,
+    length: usize,
+}
+
+impl ArrayType {
+    pub fn new(element_type: Box<dyn ValueType>, length: usize) -> Self {
+        Self {
+            element_type,
+            length,
+        }
+    }
+
+    pub fn element_type(&self) -> &Box<dyn ValueType> {
+        &self.element_type
+    }
+    pub fn length(&self) -> usize {
+        self.length
+    }
+}
+
+impl ValueTypeAsAny for ArrayType {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for ArrayType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}; {}]", self.element_type, self.length)
+    }
+}
+
+impl ValueType for ArrayType {
+    fn value_type(&self) -> ValueTypes {
+        ValueTypes::Array
+    }
+
+    fn eq(&self, other: &Box<dyn ValueType>) -> bool {
+        match other.as_any().downcast_ref::<Self>() {
+            Some(other) => {
+                self.length == other.length
+                    && ValueType::eq(self.element_type.as_ref(), &other.element_type)
+            }
+            None => false,
+        }
+    }
+
+    fn is_valid_bin_op(&self, op: &Token, t: &Box<dyn ValueType>) -> Option<Box<dyn ValueType>> {
+        if op.token_type() == TokenType::Offset && t.value_type() == ValueTypes::U64 {
+            return Some(Box::new(PointerType::new(self.element_type.clone(), false)));
+        }
+
+        None
+    }
+
+    fn is_valid_unary_op(&self, _: &Token) -> Option<Box<dyn ValueType>> {
+        None
+    }
+
+    /// Arrays only decay to pointers to their elements or slices of them explicitly.
+    fn is_valid_cast(&self, t: &Box<dyn ValueType>) -> bool {
+        if let Some(slice_type) = t.as_any().downcast_ref::<SliceType>() {
+            return ValueType::eq(slice_type.element_type().as_ref(), &self.element_type);
+        }
+
+        match t.as_any().downcast_ref::<PointerType>() {
+            Some(pointer_type) => {
+                ValueType::eq(pointer_type.pointee_type().as_ref(), &self.element_type)
+            }
+            None => t.value_type() == ValueTypes::U64,
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn ValueType> {
+        Box::new(self.clone())
+    }
+
+    fn get_size(&self) -> ValueSize {
+        ValueSize::Qword
+    }
+
+    fn size_in_bytes(&self) -> u64 {
+        self.length as u64 * self.element_type.size_in_bytes()
+    }
+}
@@ -11,13 +11,19 @@ use crate::values::value_type::{ValueType, ValueTypeAsAny, ValueTypes};
 pub struct FunctionType {
     arg_types: Vec<Box<dyn ValueType>>,
     return_type: Box<dyn ValueType>,
+    is_variadic: bool,
 }
 
 impl FunctionType {
-    pub fn new(arg_types: Vec<Box<dyn ValueType>>, return_type: Box<dyn ValueType>) -> Self {
+    pub fn new(
+        arg_types: Vec<Box<dyn ValueType>>,
+        return_type: Box<dyn ValueType>,
+        is_variadic: bool,
+    ) -> Self {
         FunctionType {
             arg_types,
             return_type,
+            is_variadic,
         }
     }
 
@@ -28,6 +34,10 @@ impl FunctionType {
     pub fn return_type(&self) -> &Box<dyn ValueType> {
         &self.return_type
     }
+
+    pub fn is_variadic(&self) -> bool {
+        self.is_variadic
+    }
 }
 
 impl ValueTypeAsAny for FunctionType {
@@ -40,12 +50,13 @@ impl Display for FunctionType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "fun ({}): {}",
+            "fun ({}{}): {}",
             self.arg_types
                 .iter()
                 .map(|a| format!("{}", a))
                 .collect::<Vec<String>>()
                 .join(", "),
+            if self.is_variadic { ", ..." } else { "" },
             self.return_type
         )
     }
@@ -0,0 +1,107 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+use crate::token::{Token, TokenType};
+use crate::values::value_size::ValueSize;
+use crate::values::value_type::pointer_type::PointerType;
+use crate::values::value_type::u64_type::U64Type;
+use crate::values::value_type::{ValueType, ValueTypeAsAny, ValueTypes};
+
+/// The offset of the length in the memory of a slice, which starts with the pointer to the first
+/// element.
+pub const SLICE_LENGTH_OFFSET: u64 = 8;
+
+/// Returns the types of the values passed to a function with the given argument types, with
+/// slices split into their pointer and length.
+pub fn passed_types(arg_types: &[Box<dyn ValueType>]) -> Vec<Box<dyn ValueType>> {
+    let mut passed_types: Vec<Box<dyn ValueType>> = vec![];
+    for arg_type in arg_types {
+        match arg_type.as_any().downcast_ref::<SliceType>() {
+            Some(slice_type) => {
+                passed_types.push(Box::new(PointerType::new(
+                    slice_type.element_type().clone(),
+                    false,
+                )));
+                passed_types.push(Box::new(U64Type::new()));
+            }
+            None => passed_types.push(arg_type.clone()),
+        }
+    }
+    passed_types
+}
+
+/// A view of a number of elements, which are stored somewhere else. Slices are stored as a pointer
+/// to their first element followed by their length, values of this type are the address of that
+/// pair. They are passed to functions as two arguments, the pointer and the length. `str` is a
+/// slice of chars.
+#[derive(Clone)]
+pub struct SliceType {
+    element_type: Box<dyn ValueType>,
+}
+
+impl SliceType {
+    pub fn new(element_type: Box<dyn ValueType>) -> Self {
+        Self { element_type }
+    }
+
+    pub fn element_type(&self) -> &Box<dyn ValueType> {
+        &self.element_type
+    }
+}
+
+impl ValueTypeAsAny for SliceType {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Display for SliceType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.element_type.value_type() == ValueTypes::Char {
+            return write!(f, "str");
+        }
+
+        write!(f, "[]{}", self.element_type)
+    }
+}
+
+impl ValueType for SliceType {
+    fn value_type(&self) -> ValueTypes {
+        ValueTypes::Slice
+    }
+
+    fn eq(&self, other: &Box<dyn ValueType>) -> bool {
+        match other.as_any().downcast_ref::<Self>() {
+            Some(other) => ValueType::eq(self.element_type.as_ref(), &other.element_type),
+            None => false,
+        }
+    }
+
+    fn is_valid_bin_op(&self, op: &Token, t: &Box<dyn ValueType>) -> Option<Box<dyn ValueType>> {
+        if op.token_type() == TokenType::Offset && t.value_type() == ValueTypes::U64 {
+            return Some(Box::new(PointerType::new(self.element_type.clone(), false)));
+        }
+
+        None
+    }
+
+    fn is_valid_unary_op(&self, _: &Token) -> Option<Box<dyn ValueType>> {
+        None
+    }
+
+    fn is_valid_cast(&self, _: &Box<dyn ValueType>) -> bool {
+        false
+    }
+
+    fn box_clone(&self) -> Box<dyn ValueType> {
+        Box::new(self.clone())
+    }
+
+    fn get_size(&self) -> ValueSize {
+        ValueSize::Qword
+    }
+
+    fn size_in_bytes(&self) -> u64 {
+        SLICE_LENGTH_OFFSET + 8
+    }
+}
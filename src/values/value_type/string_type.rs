@@ -4,6 +4,7 @@ use std::fmt::{Display, Formatter};
 use crate::token::Token;
 use crate::values::value_size::ValueSize;
 use crate::values::value_type::pointer_type::PointerType;
+use crate::values::value_type::slice_type::SliceType;
 use crate::values::value_type::{ValueType, ValueTypeAsAny, ValueTypes};
 
 #[derive(Clone)]
@@ -45,6 +46,11 @@ impl ValueType for StringType {
     }
 
     fn is_valid_cast(&self, t: &Box<dyn ValueType>) -> bool {
+        // only literals, whose length is known, can be converted to `str`
+        if let Some(slice_type) = t.as_any().downcast_ref::<SliceType>() {
+            return slice_type.element_type().value_type() == ValueTypes::Char;
+        }
+
         if t.value_type() == ValueTypes::Pointer {
             let p = t.as_any().downcast_ref::<PointerType>().unwrap();
             if p.pointee_type().value_type() == ValueTypes::Char && !*p.is_mutable() {
@@ -1,26 +1,37 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
 use std::time::Instant;
 
-use umber_lang;
 use umber_lang::error;
 use umber_lang::error::Error;
+use umber_lang::nodes::Node;
 use umber_lang::position::Position;
+use umber_lang::semantics::Validator;
+use umber_lang::syscall::TargetObjectType;
 
-#[test]
-pub fn test_file() -> Result<(), Error> {
-    let now = Instant::now();
+fn test_file_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("test.ub")
+}
 
-    let file = Path::new("E:\\Coding\\Assembly Projects\\umber-output\\test.ub");
+fn is_installed(tool: &str) -> bool {
+    Command::new(tool).arg("--version").output().is_ok()
+}
 
-    let asm_path = file.parent().unwrap().join(format!(
-        "{}.asm",
-        file.file_stem().unwrap().to_str().unwrap()
-    ));
+fn umber_run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_umber"))
+        .arg("run")
+        .args(args)
+        .output()
+        .expect("Failed to run umber")
+}
 
+fn validate_file(file: &Path) -> Result<(Validator, Box<dyn Node>), Error> {
     println!("Opening file: {}", file.to_str().unwrap());
-    let file_contents = fs::read_to_string(&file).expect("Failed to read file");
+    let file_contents = fs::read_to_string(file).expect("Failed to read file");
 
     println!("Lexing file...");
     let mut lexer = umber_lang::lexer::Lexer::new(file.to_path_buf(), file_contents);
@@ -28,7 +39,7 @@ pub fn test_file() -> Result<(), Error> {
 
     println!("Parsing file...");
 
-    let included_paths = vec!["E:\\Coding\\Umber\\include\\".to_string()];
+    let included_paths = vec![];
     let mut macros = HashMap::new();
     let mut already_included: Vec<PathBuf> = vec![];
 
@@ -39,21 +50,35 @@ pub fn test_file() -> Result<(), Error> {
         &mut already_included,
     );
 
-    let mut ast_root = &parser.parse()?;
+    let ast_root = parser.parse()?;
 
     println!("Validating file...");
-    let mut validator = umber_lang::semantics::Validator::new();
-    let validation_res = validator.validate(ast_root);
+    let mut validator = Validator::new();
+    let validation_res = validator.validate(&ast_root);
 
     if let Some(error) = validation_res.error() {
         return Err(error.clone());
     }
 
-    ast_root = validation_res.node().as_ref().unwrap();
+    let ast_root = validation_res.node().as_ref().unwrap().clone();
+    Ok((validator, ast_root))
+}
+
+#[test]
+pub fn test_file() -> Result<(), Error> {
+    let now = Instant::now();
+
+    let file = test_file_path();
+    let asm_path = std::env::temp_dir().join(format!(
+        "{}.asm",
+        file.file_stem().unwrap().to_str().unwrap()
+    ));
+
+    let (_, ast_root) = validate_file(&file)?;
 
     println!("Compiling file...");
-    let mut compiler = umber_lang::compiler::Compiler::new();
-    let asm = compiler.compile_to_str(ast_root, false);
+    let mut compiler = umber_lang::compiler::Compiler::new(TargetObjectType::X86_64);
+    let asm = compiler.compile_to_str(&ast_root, false, TargetObjectType::X86_64);
 
     if let Err(fmt_error) = asm {
         return Err(error::io_error(
@@ -72,3 +97,76 @@ pub fn test_file() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+pub fn test_test_harness() -> Result<(), Error> {
+    let (validator, ast_root) = validate_file(&test_file_path())?;
+
+    let tests: Vec<&str> = validator
+        .tests()
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(tests, vec!["adds_numbers", "initializes_structs"]);
+
+    let mut compiler = umber_lang::compiler::Compiler::new(TargetObjectType::X86_64);
+    let asm = compiler
+        .compile_tests_to_str(&ast_root, validator.tests(), TargetObjectType::X86_64)
+        .unwrap();
+
+    assert!(asm.contains("call    adds_numbers"));
+    assert!(asm.contains("call    initializes_structs"));
+    assert!(!asm.contains("call    main"));
+    assert!(asm.contains("test.ub:11:5: Assertion failed!\\n"));
+
+    Ok(())
+}
+
+#[test]
+pub fn test_run_forwards_arguments_and_exit_code() {
+    let file = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("args.ub");
+    let file = file.to_str().unwrap();
+
+    // both builds share the build directory of the file, so they can't run in parallel, and they
+    // are skipped if the assembler or the C compiler isn't installed
+    for (tool, build_arg) in [("as", "--asm-syntax=gas"), ("cc", "--backend=c")] {
+        if !is_installed(tool) {
+            continue;
+        }
+
+        // the program returns argc * 10 + its last argument
+        for (program_args, code) in [(vec!["1", "7"], 37), (vec!["5"], 25)] {
+            let mut args = vec![file, build_arg, "--"];
+            args.extend(&program_args);
+
+            let output = umber_run(&args);
+            assert_eq!(
+                output.status.code(),
+                Some(code),
+                "{}",
+                String::from_utf8_lossy(&output.stdout)
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_run_reports_compile_errors() {
+    let dir = std::env::temp_dir().join(format!("umber_run_test_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("invalid.ub");
+    fs::write(
+        &file,
+        "fun main(argc: u64, argv: char *): u64 {\n    return x;\n};\n",
+    )
+    .unwrap();
+
+    let output = umber_run(&[file.to_str().unwrap()]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    // the program isn't built, so it doesn't need an assembler
+    assert_eq!(output.status.code(), Some(255));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("SemanticError"));
+}